@@ -0,0 +1,53 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn rescheduled(
+    user: &User,
+    event: &Event,
+    event_reschedule: &EventReschedule,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: {} has been rescheduled", event.name);
+    let template_id = config.email_templates.event_rescheduled.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(event, &config.front_end_url, &mut extra_data, conn)?;
+    extra_data.insert(
+        "previous_event_start".to_string(),
+        json!(event_reschedule.previous_event_start.map(|e| e.timestamp())),
+    );
+    extra_data.insert(
+        "refund_window_ends_at".to_string(),
+        json!(event_reschedule.refund_window_ends_at.timestamp()),
+    );
+    extra_data.insert(
+        "refund_url".to_string(),
+        json!(format!("{}/events/{}/refund", config.front_end_url, event.slug(conn)?)),
+    );
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["event_rescheduled"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn comp_issued(comp: &Hold, comp_link: &str, config: &Config, conn: &PgConnection) -> Result<(), BigNeonError> {
+    let email = match comp.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+    let event = comp.event(conn)?;
+    let ticket_type = TicketType::find(comp.ticket_type_id, conn)?;
+    let (quantity, _) = comp.quantity(conn)?;
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: You're on the guest list for {}", event.name);
+    let template_id = config.email_templates.comp_issued.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(&event, &config.front_end_url, &mut extra_data, conn)?;
+    extra_data.insert("guest_name".to_string(), json!(comp.name));
+    extra_data.insert("comp_url".to_string(), json!(comp_link));
+    extra_data.insert("redemption_code".to_string(), json!(comp.redemption_code));
+    extra_data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+    extra_data.insert("quantity".to_string(), json!(quantity));
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["comps"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
+
+pub fn released(hold: &Hold, released_quantity: u32, config: &Config, conn: &PgConnection) -> Result<(), BigNeonError> {
+    let email = match hold.creator(conn)?.and_then(|user| user.email) {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let event = hold.event(conn)?;
+    let ticket_type = TicketType::find(hold.ticket_type_id, conn)?;
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: Hold {} has ended", hold.name);
+    let template_id = config.email_templates.hold_released.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(&event, &config.front_end_url, &mut extra_data, conn)?;
+    extra_data.insert("hold_name".to_string(), json!(hold.name));
+    extra_data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+    extra_data.insert("released_quantity".to_string(), json!(released_quantity));
+    extra_data.insert("end_at".to_string(), json!(hold.end_at.map(|e| e.timestamp())));
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["holds"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
@@ -3,11 +3,16 @@ use diesel::PgConnection;
 use errors::BigNeonError;
 use url::form_urlencoded::byte_serialize;
 
+pub mod events;
+pub mod holds;
 pub mod orders;
+pub mod presale_lotteries;
 pub mod organization_invites;
+pub mod payment_plans;
 pub mod reports;
 pub mod tickets;
 pub mod user;
+pub mod waitlist;
 
 pub fn insert_event_template_data(
     template_data: &mut TemplateData,
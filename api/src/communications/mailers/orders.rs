@@ -18,20 +18,25 @@ pub fn confirmation_email(
     let template_id = config.sendgrid_template_bn_purchase_completed.clone();
     let mut template_data = TemplateData::new();
     template_data.insert(String::from("name"), user_first_name.clone());
+    template_data.insert(String::from("currency"), display_order.currency.clone());
+    let symbol = currency_symbol(&display_order.currency);
     //Construct an itemised breakdown using a HTML table
     let mut item_breakdown = r#"<table style="width:100%"><tbody>"#.to_string();
     item_breakdown.push_str("<tr><th>Units</th><th>Description</th><th>Unit Price</th><th>Total</th></tr>");
     let mut total_fees = 0;
     let mut total_initial_fees = 0;
     let mut total_refunded_fees = 0;
+    let mut total_tax = 0;
+    let mut total_included_tax = 0;
 
     for oi in &display_order.items {
         match oi.item_type {
-            OrderItemTypes::Tickets => {
+            OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets | OrderItemTypes::Products => {
                 item_breakdown.push_str(&generate_item_row(
                     &oi.description,
                     oi.quantity,
                     oi.unit_price_in_cents,
+                    &symbol,
                     false,
                 ));
                 let mut discount_per_ticket = 0;
@@ -42,6 +47,7 @@ pub fn confirmation_email(
                         "Discount",
                         discount_item.quantity,
                         discount_item.unit_price_in_cents,
+                        &symbol,
                         false,
                     ));
                 }
@@ -51,12 +57,22 @@ pub fn confirmation_email(
                         "Refunded",
                         oi.refunded_quantity,
                         oi.unit_price_in_cents + discount_per_ticket,
+                        &symbol,
                         true,
                     ));
                 }
             }
             // Do nothing, included above with ticket for display
             OrderItemTypes::Discount => (),
+            // Inclusive tax is already part of the item price so it is only listed in the totals
+            OrderItemTypes::Tax => {
+                let tax = (oi.quantity - oi.refunded_quantity) * oi.tax_in_cents;
+                if oi.unit_price_in_cents == 0 {
+                    total_included_tax += tax;
+                } else {
+                    total_tax += tax;
+                }
+            }
             _ => {
                 //Accumulate fees
                 total_initial_fees += oi.quantity * oi.unit_price_in_cents;
@@ -76,10 +92,23 @@ pub fn confirmation_email(
     }
     if total_refunded_fees > 0 {
         total_breakdown.push_str(&format!(
-            r#"<tr style="color: red"><th>Refunded</th><td>(${})</td></tr>"#,
+            r#"<tr style="color: red"><th>Refunded</th><td>({}{})</td></tr>"#,
+            symbol,
             format!("{:.*}", 2, total_refunded_fees as f64 / 100.0)
         ));
     }
+    if total_tax > 0 {
+        total_breakdown.push_str(&format!(
+            "<tr><th>Tax</th><td>{}</td></tr>",
+            format!("{:.*}", 2, total_tax as f64 / 100.0)
+        ));
+    }
+    if total_included_tax > 0 {
+        total_breakdown.push_str(&format!(
+            "<tr><th>Tax Included</th><td>{}</td></tr>",
+            format!("{:.*}", 2, total_included_tax as f64 / 100.0)
+        ));
+    }
     total_breakdown.push_str(&format!(
         "<tr><th>Order Total</th><td>{}</td></tr>",
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0)
@@ -106,6 +135,10 @@ pub fn confirmation_email(
         format!("{:.*}", 2, total_refunded_fees as f64 / 100.0),
     );
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert(
+        "total_tax".to_string(),
+        format!("{:.*}", 2, (total_tax + total_included_tax) as f64 / 100.0),
+    );
     template_data.insert(
         "total_price".to_string(),
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0),
@@ -128,7 +161,18 @@ pub fn confirmation_email(
     ))
 }
 
-fn generate_item_row(description: &str, quantity: i64, unit_price_in_cents: i64, refund: bool) -> String {
+/// Prefix for amounts in emails, currencies without a well known symbol use their code.
+fn currency_symbol(currency: &str) -> String {
+    match currency {
+        "USD" => "$".to_string(),
+        "CAD" => "CA$".to_string(),
+        "GBP" => "£".to_string(),
+        "EUR" => "€".to_string(),
+        _ => format!("{} ", currency),
+    }
+}
+
+fn generate_item_row(description: &str, quantity: i64, unit_price_in_cents: i64, symbol: &str, refund: bool) -> String {
     let mut item_row = "".to_string();
 
     if refund {
@@ -142,14 +186,19 @@ fn generate_item_row(description: &str, quantity: i64, unit_price_in_cents: i64,
     item_row.push_str(&description);
     item_row.push_str(r#"</td><td align="right">"#);
 
-    let mut unit_price_display = format!("${:.*}", 2, unit_price_in_cents.abs() as f64 / 100.0);
+    let mut unit_price_display = format!("{}{:.*}", symbol, 2, unit_price_in_cents.abs() as f64 / 100.0);
     if unit_price_in_cents < 0 || refund {
         unit_price_display = format!("({})", unit_price_display);
     }
     item_row.push_str(&unit_price_display);
     item_row.push_str(r#"</td><td align="right">"#);
 
-    let mut total_price_display = format!("${:.*}", 2, (quantity * unit_price_in_cents.abs()) as f64 / 100.0);
+    let mut total_price_display = format!(
+        "{}{:.*}",
+        symbol,
+        2,
+        (quantity * unit_price_in_cents.abs()) as f64 / 100.0
+    );
     if unit_price_in_cents < 0 || refund {
         total_price_display = format!("({})", total_price_display);
     }
@@ -172,6 +221,9 @@ pub fn refund_email(
     let template_id = config.sendgrid_template_bn_refund.clone();
     let mut template_data = TemplateData::new();
     template_data.insert(String::from("name"), user_first_name.clone());
+    let currency = Order::find(refund.order_id, conn)?.currency;
+    let symbol = currency_symbol(&currency);
+    template_data.insert(String::from("currency"), currency);
     //Construct an itemised breakdown using a HTML table
     let mut item_breakdown = r#"<table style="width:100%"><tbody>"#.to_string();
     item_breakdown.push_str("<tr><th>Units Refunded</th><th>Description</th><th>Total</th></tr>");
@@ -197,7 +249,8 @@ pub fn refund_email(
         item_breakdown.push_str(&item.quantity.to_string());
         item_breakdown.push_str("</th><th>");
         item_breakdown.push_str(&oi.description(conn)?);
-        item_breakdown.push_str(r#"</th><th align="right">$"#);
+        item_breakdown.push_str(r#"</th><th align="right">"#);
+        item_breakdown.push_str(&symbol);
         item_breakdown.push_str(&format!("{:.*}", 2, item.amount as f64 / 100.0));
 
         item_breakdown.push_str("</th></tr>");
@@ -221,7 +274,14 @@ pub fn refund_email(
         .map(|i| i.0.amount)
         .sum::<i64>();
 
+    let total_tax = items
+        .iter()
+        .filter(|i| i.1.item_type == OrderItemTypes::Tax)
+        .map(|i| i.0.quantity * i.1.tax_in_cents)
+        .sum::<i64>();
+
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert("total_tax".to_string(), format!("{:.*}", 2, total_tax as f64 / 100.0));
     template_data.insert("total_price".to_string(), format!("{:.*}", 2, amount));
     template_data.insert("item_breakdown".to_string(), item_breakdown);
     template_data.insert("tickets_link".to_string(), format!("{}/orders", config.front_end_url));
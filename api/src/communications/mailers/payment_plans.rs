@@ -0,0 +1,106 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn installment_failed(
+    user: &User,
+    payment_plan: &PaymentPlan,
+    installment: &PaymentPlanInstallment,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "BigNeon: Your payment plan installment could not be charged".to_string();
+    let template_id = config.email_templates.payment_plan_installment_failed.to_string();
+    let mut extra_data = payment_plan_data(payment_plan, config, conn)?;
+    extra_data.insert("installment_number".to_string(), json!(installment.installment_number));
+    extra_data.insert("amount_in_cents".to_string(), json!(installment.amount_in_cents));
+    extra_data.insert("failed_attempts".to_string(), json!(installment.failed_attempts));
+    extra_data.insert(
+        "grace_period_ends_at".to_string(),
+        json!(installment.grace_period_ends_at.map(|g| g.timestamp())),
+    );
+    extra_data.insert(
+        "payment_methods_url".to_string(),
+        json!(format!("{}/my-account/payment-methods", config.front_end_url)),
+    );
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["payment_plan_installment_failed"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
+
+pub fn cancelled(
+    user: &User,
+    payment_plan: &PaymentPlan,
+    refund_due: i64,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "BigNeon: Your payment plan has been cancelled".to_string();
+    let template_id = config.email_templates.payment_plan_cancelled.to_string();
+    let mut extra_data = payment_plan_data(payment_plan, config, conn)?;
+    extra_data.insert("refund_in_cents".to_string(), json!(refund_due));
+    extra_data.insert("refund_policy".to_string(), json!(payment_plan.refund_policy));
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["payment_plan_cancelled"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
+
+fn payment_plan_data(
+    payment_plan: &PaymentPlan,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<HashMap<String, serde_json::Value>, BigNeonError> {
+    let order = payment_plan.order(conn)?;
+    let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+    if let Some(event) = order.events(conn)?.first() {
+        Event::event_payload_data(event, &config.front_end_url, &mut data, conn)?;
+    }
+    data.insert("order_id".to_string(), json!(order.id));
+    data.insert("order_number".to_string(), json!(order.order_number()));
+    data.insert("currency".to_string(), json!(order.currency));
+    data.insert("total_in_cents".to_string(), json!(payment_plan.total_in_cents));
+    data.insert("amount_paid_in_cents".to_string(), json!(order.total_paid(conn)?));
+
+    Ok(data)
+}
@@ -0,0 +1,94 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn won(
+    user: &User,
+    event: &Event,
+    presale_lottery: &PresaleLottery,
+    code: &Code,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: You have been selected for the {} presale", event.name);
+    let template_id = config.email_templates.presale_lottery_won.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(event, &config.front_end_url, &mut extra_data, conn)?;
+    extra_data.insert("presale_name".to_string(), json!(presale_lottery.name));
+    extra_data.insert("redemption_code".to_string(), json!(code.redemption_code));
+    extra_data.insert("code_expires_at".to_string(), json!(code.end_date.timestamp()));
+    extra_data.insert(
+        "tickets_per_winner".to_string(),
+        json!(presale_lottery.tickets_per_winner),
+    );
+    extra_data.insert(
+        "presale_url".to_string(),
+        json!(format!(
+            "{}/tickets/{}?code={}",
+            config.front_end_url,
+            event.slug(conn)?,
+            code.redemption_code
+        )),
+    );
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["presale_lottery_won"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
+
+pub fn lost(
+    user: &User,
+    event: &Event,
+    presale_lottery: &PresaleLottery,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("BigNeon: {} presale results", event.name);
+    let template_id = config.email_templates.presale_lottery_lost.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(event, &config.front_end_url, &mut extra_data, conn)?;
+    extra_data.insert("presale_name".to_string(), json!(presale_lottery.name));
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["presale_lottery_lost"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::PgConnection;
+use errors::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub fn offer(entry: &WaitlistEntry, config: &Config, conn: &PgConnection) -> Result<(), BigNeonError> {
+    let hold = match entry.hold(conn)? {
+        Some(hold) => hold,
+        None => return Err(ApplicationError::new("Waitlist entry has not been offered tickets".to_string()).into()),
+    };
+    let ticket_type = entry.ticket_type(conn)?;
+    let event = ticket_type.event(conn)?;
+    let user = User::find(entry.user_id, conn)?;
+    let email = match user.email {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "BigNeon: Tickets are available for you".to_string();
+    let template_id = config.email_templates.waitlist_offer.to_string();
+    let mut extra_data: HashMap<String, serde_json::Value> = HashMap::new();
+    Event::event_payload_data(&event, &config.front_end_url, &mut extra_data, conn)?;
+    let redemption_code = hold.redemption_code.unwrap_or("".to_string());
+    extra_data.insert(
+        "offer_url".to_string(),
+        json!(format!(
+            "{}/tickets/{}?code={}",
+            config.front_end_url,
+            event.slug(conn)?,
+            redemption_code
+        )),
+    );
+    extra_data.insert("redemption_code".to_string(), json!(redemption_code));
+    extra_data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+    extra_data.insert("quantity".to_string(), json!(entry.quantity));
+    extra_data.insert(
+        "offer_expires_at".to_string(),
+        json!(entry.offer_expires_at.map(|e| e.timestamp())),
+    );
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        None,
+        Some(vec!["waitlist"]),
+        Some(extra_data),
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
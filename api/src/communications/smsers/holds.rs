@@ -0,0 +1,33 @@
+use bigneon_db::models::*;
+use config::Config;
+use diesel::pg::PgConnection;
+use errors::*;
+
+pub fn comp_issued(
+    config: &Config,
+    phone: String,
+    event: &Event,
+    comp_link: &str,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let body = format!(
+        "You're on the guest list for {}! Claim your tickets at {}",
+        event.name, comp_link
+    );
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+        Some(vec!["comps"]),
+        None,
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
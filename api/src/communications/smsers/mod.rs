@@ -1,2 +1,3 @@
 pub mod box_office;
+pub mod holds;
 pub mod tickets;
@@ -34,6 +34,9 @@ pub struct Config {
     pub block_external_comms: bool,
     pub primary_currency: String,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: Option<String>,
+    pub stripe_base_url: String,
+    pub stripe_payment_intents: bool,
     pub token_secret: String,
     pub token_issuer: String,
     pub tari_client: Box<dyn TariClient + Send + Sync>,
@@ -84,10 +87,18 @@ pub struct CubeJs {
 
 #[derive(Clone)]
 pub struct EmailTemplates {
+    pub comp_issued: EmailTemplate,
     pub custom_broadcast: EmailTemplate,
+    pub event_rescheduled: EmailTemplate,
+    pub hold_released: EmailTemplate,
     pub org_invite: EmailTemplate,
     pub password_reset: EmailTemplate,
+    pub payment_plan_cancelled: EmailTemplate,
+    pub payment_plan_installment_failed: EmailTemplate,
+    pub presale_lottery_lost: EmailTemplate,
+    pub presale_lottery_won: EmailTemplate,
     pub ticket_count_report: EmailTemplate,
+    pub waitlist_offer: EmailTemplate,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -140,10 +151,18 @@ const API_PORT: &str = "API_PORT";
 const DATABASE_URL: &str = "DATABASE_URL";
 const READONLY_DATABASE_URL: &str = "READONLY_DATABASE_URL";
 const DOMAIN: &str = "DOMAIN";
+const EMAIL_TEMPLATES_COMP_ISSUED: &str = "EMAIL_TEMPLATES_COMP_ISSUED";
 const EMAIL_TEMPLATES_CUSTOM_BROADCAST: &str = "EMAIL_TEMPLATES_CUSTOM_BROADCAST";
+const EMAIL_TEMPLATES_EVENT_RESCHEDULED: &str = "EMAIL_TEMPLATES_EVENT_RESCHEDULED";
+const EMAIL_TEMPLATES_HOLD_RELEASED: &str = "EMAIL_TEMPLATES_HOLD_RELEASED";
 const EMAIL_TEMPLATES_ORG_INVITE: &str = "EMAIL_TEMPLATES_ORG_INVITE";
 const EMAIL_TEMPLATES_PASSWORD_RESET: &str = "EMAIL_TEMPLATES_PASSWORD_RESET";
+const EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED: &str = "EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED";
+const EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED: &str = "EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED";
+const EMAIL_TEMPLATES_PRESALE_LOTTERY_LOST: &str = "EMAIL_TEMPLATES_PRESALE_LOTTERY_LOST";
+const EMAIL_TEMPLATES_PRESALE_LOTTERY_WON: &str = "EMAIL_TEMPLATES_PRESALE_LOTTERY_WON";
 const EMAIL_TEMPLATES_TICKET_COUNT_REPORT: &str = "EMAIL_TEMPLATES_TICKET_COUNT_REPORT";
+const EMAIL_TEMPLATES_WAITLIST_OFFER: &str = "EMAIL_TEMPLATES_WAITLIST_OFFER";
 const ENVIRONMENT: &str = "ENVIRONMENT";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
@@ -154,6 +173,10 @@ const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
+const STRIPE_BASE_URL: &str = "STRIPE_BASE_URL";
+// Takes card payments with payment intents, which support 3D Secure
+const STRIPE_PAYMENT_INTENTS: &str = "STRIPE_PAYMENT_INTENTS";
 const STATIC_FILE_PATH: &str = "STATIC_FILE_PATH";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
@@ -248,6 +271,12 @@ impl Config {
 
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key = env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
+        let stripe_webhook_secret = env::var(&STRIPE_WEBHOOK_SECRET).ok();
+        let stripe_base_url = env::var(&STRIPE_BASE_URL).unwrap_or_else(|_| "https://api.stripe.com/v1/".to_string());
+        let stripe_payment_intents = env::var(&STRIPE_PAYMENT_INTENTS)
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect(&format!("{} is not a valid boolean value", STRIPE_PAYMENT_INTENTS));
         let token_secret = get_env_var(TOKEN_SECRET);
 
         let token_issuer = get_env_var(TOKEN_ISSUER);
@@ -292,10 +321,20 @@ impl Config {
         let communication_default_source_phone = get_env_var(COMMUNICATION_DEFAULT_SOURCE_PHONE);
 
         let email_templates = EmailTemplates {
+            comp_issued: get_env_var(EMAIL_TEMPLATES_COMP_ISSUED).parse().unwrap(),
             custom_broadcast: get_env_var(EMAIL_TEMPLATES_CUSTOM_BROADCAST).parse().unwrap(),
+            event_rescheduled: get_env_var(EMAIL_TEMPLATES_EVENT_RESCHEDULED).parse().unwrap(),
+            hold_released: get_env_var(EMAIL_TEMPLATES_HOLD_RELEASED).parse().unwrap(),
             org_invite: get_env_var(EMAIL_TEMPLATES_ORG_INVITE).parse().unwrap(),
             password_reset: get_env_var(EMAIL_TEMPLATES_PASSWORD_RESET).parse().unwrap(),
+            payment_plan_cancelled: get_env_var(EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED).parse().unwrap(),
+            payment_plan_installment_failed: get_env_var(EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED)
+                .parse()
+                .unwrap(),
+            presale_lottery_lost: get_env_var(EMAIL_TEMPLATES_PRESALE_LOTTERY_LOST).parse().unwrap(),
+            presale_lottery_won: get_env_var(EMAIL_TEMPLATES_PRESALE_LOTTERY_WON).parse().unwrap(),
             ticket_count_report: get_env_var(EMAIL_TEMPLATES_TICKET_COUNT_REPORT).parse().unwrap(),
+            waitlist_offer: get_env_var(EMAIL_TEMPLATES_WAITLIST_OFFER).parse().unwrap(),
         };
 
         let customer_io_base_url = get_env_var(CUSTOMER_IO_BASE_URL);
@@ -401,6 +440,9 @@ impl Config {
             block_external_comms,
             primary_currency,
             stripe_secret_key,
+            stripe_webhook_secret,
+            stripe_base_url,
+            stripe_payment_intents,
             token_secret,
             token_issuer,
             front_end_url,
@@ -0,0 +1,119 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateCapacityPoolRequest {
+    pub name: String,
+    pub capacity: i64,
+    pub stage_id: Option<Uuid>,
+    #[serde(default)]
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdateCapacityPoolTicketTypesRequest {
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::DashboardRead,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let mut capacity_pools = Vec::new();
+    for capacity_pool in CapacityPool::find_for_event(event.id, connection)? {
+        capacity_pools.push(capacity_pool.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(&capacity_pools))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateCapacityPoolRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let event = Event::find(parameters.id, connection)?;
+    requires_event_write(&user, &event, connection)?;
+
+    let capacity_pool =
+        CapacityPool::create(event.id, json.stage_id, json.name, json.capacity).commit(Some(user.id()), connection)?;
+    if !json.ticket_type_ids.is_empty() {
+        capacity_pool.set_ticket_types(&json.ticket_type_ids, Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(capacity_pool.for_display(connection)?))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CapacityPoolEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let capacity_pool = CapacityPool::find(parameters.id, connection)?;
+    requires_event_write(&user, &capacity_pool.event(connection)?, connection)?;
+
+    let capacity_pool = capacity_pool.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(capacity_pool.for_display(connection)?))
+}
+
+pub fn update_ticket_types(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdateCapacityPoolTicketTypesRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let capacity_pool = CapacityPool::find(parameters.id, connection)?;
+    requires_event_write(&user, &capacity_pool.event(connection)?, connection)?;
+
+    capacity_pool.set_ticket_types(&json.ticket_type_ids, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(capacity_pool.for_display(connection)?))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let capacity_pool = CapacityPool::find(parameters.id, connection)?;
+    requires_event_write(&user, &capacity_pool.event(connection)?, connection)?;
+
+    capacity_pool.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
+
+fn requires_event_write(user: &AuthUser, event: &Event, connection: &PgConnection) -> Result<(), BigNeonError> {
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        event,
+        connection,
+    )?;
+    Ok(())
+}
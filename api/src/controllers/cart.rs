@@ -16,6 +16,9 @@ use log::Level::Debug;
 use log::Level::Info;
 use models::*;
 use payments::AuthThenCompletePaymentBehavior;
+use payments::ConfirmThenCompletePaymentBehavior;
+use payments::PaymentIntentResult;
+use payments::PaymentIntentStatus;
 use payments::PaymentProcessor;
 use payments::PaymentProcessorBehavior;
 use payments::RedirectToPaymentPageBehavior;
@@ -86,6 +89,136 @@ pub fn update_cart(
     Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct UpdateCartSeatsRequest {
+    pub ticket_type_id: Uuid,
+    pub seat_ids: Vec<Uuid>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub redemption_code: Option<String>,
+    pub box_office_pricing: Option<bool>,
+}
+
+/// Picks specific seats for a reserved seating ticket type. Adding tickets through `update_cart`
+/// instead assigns the best available seats.
+pub fn update_seats(
+    (connection, json, user): (Connection, Json<UpdateCartSeatsRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update Cart Seats", {"cart": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let box_office_pricing = json.box_office_pricing.unwrap_or(false);
+    if box_office_pricing {
+        for organization in Organization::find_by_ticket_type_ids(vec![json.ticket_type_id], connection)? {
+            user.requires_scope_for_organization(Scopes::BoxOfficeTicketRead, &organization, connection)?
+        }
+    }
+
+    if !Dbticket_types::is_event_available_for_sale(&json.ticket_type_id, connection)? {
+        return Ok(HttpResponse::BadRequest().json(json!({"error": "Event has not been published.".to_string()})));
+    }
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_seats(
+        user.id(),
+        json.ticket_type_id,
+        &json.seat_ids,
+        json.redemption_code.clone(),
+        box_office_pricing,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddResaleListingRequest {
+    pub resale_listing_id: Uuid,
+}
+
+pub fn add_resale_listing(
+    (connection, json, user): (Connection, Json<AddResaleListingRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.add_resale_listing(user.id(), json.resale_listing_id, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+pub fn remove_resale_listing(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let mut cart = match Order::find_cart_for_user(user.id(), connection)? {
+        Some(o) => o,
+        None => return application::unprocessable("No cart exists for user"),
+    };
+    cart.remove_resale_listing(path.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateCartEntrySlotRequest {
+    pub entry_slot_id: Uuid,
+}
+
+/// Moves the cart's tickets for a timed entry ticket type into another entry slot. Adding tickets
+/// through `update_cart` instead assigns the earliest slot with enough capacity.
+pub fn update_entry_slot(
+    (connection, json, user): (Connection, Json<UpdateCartEntrySlotRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update Cart Entry Slot", {"cart": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_entry_slot(json.entry_slot_id, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateCartPackagesRequest {
+    pub items: Vec<UpdatePackageOrderItem>,
+}
+
+/// Sets the number of each package in the cart, reserving a ticket for every event the package
+/// includes.
+pub fn update_packages(
+    (connection, json, user): (Connection, Json<UpdateCartPackagesRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update Cart Packages", {"cart": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_package_quantities(user.id(), &json.items, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateCartProductsRequest {
+    pub items: Vec<UpdateProductOrderItem>,
+}
+
+/// Sets the quantities of add-on products in the cart. Products that are not tied to an event need
+/// the `event_id` of the event they will be picked up at.
+pub fn update_products(
+    (connection, json, user): (Connection, Json<UpdateCartProductsRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update Cart Products", {"cart": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_product_quantities(user.id(), &json.items, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
 pub fn duplicate(
     (connection, path, user): (Connection, Path<PathParameters>, User),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -165,10 +298,31 @@ pub fn show((connection, user): (Connection, User)) -> Result<HttpResponse, BigN
     Ok(HttpResponse::Ok().json(order.for_display(None, user.id(), connection)?))
 }
 
+/// Questions the attendees need to answer before the cart can be checked out.
+pub fn questions((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let questions = match Order::find_cart_for_user(user.id(), connection)? {
+        Some(order) => EventAnswer::questions_for_order(&order, connection)?,
+        None => Vec::new(),
+    };
+    Ok(HttpResponse::Ok().json(&questions))
+}
+
 #[derive(Deserialize)]
 pub struct CheckoutCartRequest {
     pub method: PaymentRequest,
     pub tracking_data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub answers: Vec<EventAnswerRequest>,
+    /// Store credit to put towards the order, the rest is charged to the card.
+    pub store_credit_in_cents: Option<i64>,
+    /// Splits the order into this many installments, the first of which is charged now as the deposit.
+    /// Requires a saved payment method so the remaining installments can be charged when they fall due.
+    pub installment_count: Option<u32>,
+    /// Pays only this amount with the payment method, leaving the rest of the order to be paid with
+    /// further checkouts. The order stays pending payment, and remains the user's cart, until its total
+    /// is covered. Leaving it out pays everything still due.
+    pub amount_in_cents: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -202,6 +356,8 @@ pub enum PaymentRequest {
     },
     // Only for 0 amount carts
     Free,
+    // Pays the whole cart from the user's store credit
+    StoreCredit,
 }
 
 pub fn clear_invalid_items((connection, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
@@ -244,6 +400,10 @@ pub fn checkout(
     }
 
     order.set_tracking_data(req.tracking_data.clone(), Some(user.id()), connection.get())?;
+    // Answers are given with the first payment of a split payment
+    if order.status == OrderStatus::Draft {
+        EventAnswer::save_for_order(&order, &req.answers, Some(user.id()), connection.get())?;
+    }
 
     let order_items = order.items(connection.get())?;
 
@@ -271,10 +431,43 @@ pub fn checkout(
         }
     }
 
+    if req.installment_count.is_some() && req.store_credit_in_cents.is_some() {
+        return application::unprocessable("Store credit cannot be combined with a payment plan");
+    }
+
+    let mut store_credit_payment = None;
+    if let Some(store_credit_in_cents) = req.store_credit_in_cents {
+        match &req.method {
+            PaymentRequest::Card { .. } | PaymentRequest::PaymentMethod { .. } | PaymentRequest::Provider { .. } => {
+                info!("CART: Applying store credit");
+                store_credit_payment =
+                    Some(order.add_store_credit_payment(user.id(), store_credit_in_cents, connection.get())?);
+            }
+            _ => {
+                return application::unprocessable("Store credit can only be combined with a card payment");
+            }
+        }
+    }
+
+    if let Some(amount_in_cents) = req.amount_in_cents {
+        if req.installment_count.is_some() {
+            return application::unprocessable("Split payments cannot be combined with a payment plan");
+        }
+        if amount_in_cents <= 0 || amount_in_cents > order.amount_due_now(connection.get())? {
+            return application::unprocessable("Payment amount must be more than zero and no more than the amount due");
+        }
+    }
+
+    // Orders are charged in the currency of the organization selling the items
+    let currency = order.currency.clone();
     let payment_response = match &req.method {
+        PaymentRequest::StoreCredit => {
+            info!("CART: Received store credit payment");
+            checkout_store_credit(&connection, order, req.amount_in_cents, &user, &request_info)
+        }
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
-            checkout_free(&connection, order, &user, &request_info)?
+            checkout_free(&connection, order, &user, &request_info)
         }
         PaymentRequest::External {
             reference,
@@ -296,9 +489,10 @@ pub fn checkout(
                 email.clone(),
                 phone.clone(),
                 note.clone(),
+                req.amount_in_cents,
                 &user,
                 &request_info,
-            )?
+            )
         }
         PaymentRequest::PaymentMethod { provider } => {
             info!("CART: Received provider payment");
@@ -319,30 +513,34 @@ pub fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
+                &currency,
                 provider.clone(),
                 true,
                 false,
                 false,
+                req.installment_count,
+                req.amount_in_cents,
                 &state.service_locator,
                 &state.config,
                 &request_info,
-            )?
+            )
         }
         PaymentRequest::Provider { provider } => checkout_payment_processor(
             &connection,
             &mut order,
             None,
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
             false,
             false,
             false,
+            req.installment_count,
+            req.amount_in_cents,
             &state.service_locator,
             &state.config,
             &request_info,
-        )?,
+        ),
         PaymentRequest::Card {
             token,
             provider,
@@ -353,17 +551,47 @@ pub fn checkout(
             &mut order,
             Some(&token),
             &user,
-            &state.config.primary_currency,
+            &currency,
             *provider,
             false,
             *save_payment_method,
             *set_default,
+            req.installment_count,
+            req.amount_in_cents,
             &state.service_locator,
             &state.config,
             &request_info,
-        )?,
+        ),
     };
-    Ok(payment_response)
+
+    match payment_response {
+        Err(e) => {
+            if let Some(store_credit_payment) = store_credit_payment {
+                reverse_store_credit_payment(&connection, &store_credit_payment, &user)?;
+            }
+            Err(e)
+        }
+        payment_response => payment_response,
+    }
+}
+
+/// Card payments commit the store credit applied with them before the card is charged, so when
+/// the charge then fails the credit is returned to the purchaser rather than left spent on an
+/// order that was not paid for.
+fn reverse_store_credit_payment(conn: &Connection, payment: &Payment, user: &User) -> Result<(), BigNeonError> {
+    conn.rollback_transaction()?;
+    conn.begin_transaction()?;
+
+    // Failures before the card was authorized roll the store credit back with them
+    if let Some(payment) = Payment::find(payment.id, conn.get()).optional()? {
+        if payment.status == PaymentStatus::Completed {
+            info!("CART: Reversing store credit payment");
+            payment.reverse_store_credit(Some(user.id()), conn.get())?;
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+        }
+    }
+    Ok(())
 }
 
 fn checkout_free(
@@ -389,6 +617,29 @@ fn checkout_free(
     Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), conn)?)))
 }
 
+fn checkout_store_credit(
+    conn: &Connection,
+    order: Order,
+    amount_in_cents: Option<i64>,
+    user: &User,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    if order.status != OrderStatus::Draft && order.status != OrderStatus::PendingPayment {
+        return application::unprocessable("Could not complete this cart because it is not in the correct status");
+    }
+    let amount_due = order.calculate_total(conn)? - order.total_paid(conn)?;
+    if amount_due <= 0 {
+        return application::unprocessable("Could not complete this cart; only paid orders require store credit");
+    }
+    let mut order = order;
+    order.add_store_credit_payment(user.id(), amount_in_cents.unwrap_or(amount_due), conn)?;
+
+    let mut order = Order::find(order.id, conn)?;
+    order.set_browser_data(request_info.user_agent.clone(), true, conn)?;
+    Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), conn)?)))
+}
+
 // TODO: This should actually probably move to an `orders` controller, since the
 // user will not be calling this.
 fn checkout_external(
@@ -401,6 +652,7 @@ fn checkout_external(
     email: Option<String>,
     phone: Option<String>,
     note: Option<String>,
+    amount_in_cents: Option<i64>,
     user: &User,
     request_info: &RequestInfo,
 ) -> Result<HttpResponse, BigNeonError> {
@@ -420,37 +672,44 @@ fn checkout_external(
         }
     }
 
-    if order.status != OrderStatus::Draft {
+    if order.status != OrderStatus::Draft && order.status != OrderStatus::PendingPayment {
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
     }
 
-    let mut guest: Option<DbUser> = None;
+    // The guest is set by the first payment when the order is split across several payments
+    if order.status == OrderStatus::Draft {
+        let mut guest: Option<DbUser> = None;
 
-    if let Some(ref e) = email {
-        // Guest can be a deleted user
-        guest = DbUser::find_by_email(e, true, conn).optional()?;
-    };
-    if guest.is_none() {
-        if let Some(ref p) = phone {
+        if let Some(ref e) = email {
             // Guest can be a deleted user
-            guest = DbUser::find_by_phone(p, true, conn).optional()?;
+            guest = DbUser::find_by_email(e, true, conn).optional()?;
+        };
+        if guest.is_none() {
+            if let Some(ref p) = phone {
+                // Guest can be a deleted user
+                guest = DbUser::find_by_phone(p, true, conn).optional()?;
+            }
         }
+        let guest = match guest {
+            Some(g) => g,
+            None => DbUser::create_stub(first_name, last_name, email, phone, Some(user.id()), conn)?,
+        };
+        order.set_behalf_of_user(guest, user.id(), conn)?;
     }
-    let guest = match guest {
-        Some(g) => g,
-        None => DbUser::create_stub(first_name, last_name, email, phone, Some(user.id()), conn)?,
-    };
 
     if let Some(note) = note {
         order.create_note(note, user.id(), conn)?;
     }
-    order.set_behalf_of_user(guest, user.id(), conn)?;
     let total = order.calculate_total(conn)?;
 
     if total == 0 {
         order.add_free_payment(true, user.id(), conn)?;
     } else {
-        order.add_external_payment(reference, external_payment_type, user.id(), total, conn)?;
+        let amount = match amount_in_cents {
+            Some(amount_in_cents) => amount_in_cents,
+            None => order.amount_due_now(conn)?,
+        };
+        order.add_external_payment(reference, external_payment_type, user.id(), amount, conn)?;
     }
     order.set_browser_data(request_info.user_agent.clone(), true, conn)?;
 
@@ -468,6 +727,8 @@ fn checkout_payment_processor(
     use_stored_payment: bool,
     save_payment_method: bool,
     set_default: bool,
+    installment_count: Option<u32>,
+    amount_in_cents: Option<i64>,
     service_locator: &ServiceLocator,
     config: &Config,
     request_info: &RequestInfo,
@@ -477,9 +738,9 @@ fn checkout_payment_processor(
 
     if order.user_id != auth_user.id() {
         return application::forbidden("This cart does not belong to you");
-    } else if order.status != OrderStatus::Draft {
+    } else if order.status != OrderStatus::Draft && order.status != OrderStatus::PendingPayment {
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
-    } else if order.calculate_total(connection)? == 0 {
+    } else if order.calculate_total(connection)? - order.total_paid(connection)? <= 0 {
         return application::unprocessable("Could not complete this cart; only paid orders require payment processing");
     }
 
@@ -495,74 +756,60 @@ fn checkout_payment_processor(
     let client = service_locator.create_payment_processor(provider, &event.organization(connection)?)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
+            if order.total_paid(connection)? > 0 || amount_in_cents.is_some() {
+                return application::unprocessable(
+                    "Payments through this provider cannot be combined with other payments",
+                );
+            }
+            if installment_count.is_some() {
+                return application::unprocessable("Payment plans are not supported for this payment processor");
+            }
             return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config);
         }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
-            let token = if use_stored_payment {
-                info!("CART: Using stored payment");
-                match auth_user.user.payment_method(provider, connection).optional()? {
-                    Some(payment_method) => payment_method.provider,
-                    None => {
-                        return application::unprocessable(
-                            "Could not complete this cart because stored provider does not exist",
-                        );
-                    }
-                }
-            } else {
-                info!("CART: Not using stored payment");
-                let token = match token {
-                    Some(t) => t,
-                    None => {
-                        return application::unprocessable("Could not complete this cart because no token provided");
-                    }
-                };
-
-                if save_payment_method {
-                    info!("CART: User has requested to save the payment method");
-                    match auth_user.user.payment_method(provider, connection).optional()? {
-                        Some(payment_method) => {
-                            let behavior = match client.behavior() {
-                                    PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                                    _ => return application::unprocessable(
-                                        "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                                    )
-                                };
-                            let client_response =
-                                behavior.update_repeat_token(&payment_method.provider, token, "Big Neon something")?;
-                            let payment_method_parameters = PaymentMethodEditableAttributes {
-                                provider_data: Some(client_response.to_json()?),
-                            };
-                            payment_method.update(&payment_method_parameters, auth_user.id(), connection)?;
-
-                            payment_method.provider
-                        }
-                        None => {
-                            let behavior = match client.behavior() {
-                                    PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                                    _ => return application::unprocessable(
-                                        "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                                    )
-                                };
-                            let repeat_token = behavior.create_token_for_repeat_charges(token, "Big Neon")?;
-                            let _payment_method = PaymentMethod::create(
-                                auth_user.id(),
-                                provider,
-                                set_default,
-                                repeat_token.token.clone(),
-                                repeat_token.to_json()?,
-                            )
-                            .commit(auth_user.id(), connection)?;
-                            repeat_token.token
-                        }
-                    }
-                } else {
-                    token.to_string()
-                }
-            };
+            let token = payment_token(
+                &*client,
+                order,
+                token,
+                auth_user,
+                provider,
+                use_stored_payment,
+                save_payment_method,
+                set_default,
+                installment_count,
+                connection,
+            )?;
 
             return auth_then_complete(
                 &*behavior,
                 token,
+                amount_in_cents,
+                currency,
+                order,
+                auth_user,
+                conn,
+                &*client,
+                request_info,
+            );
+        }
+        PaymentProcessorBehavior::ConfirmThenComplete(behavior) => {
+            let token = payment_token(
+                &*client,
+                order,
+                token,
+                auth_user,
+                provider,
+                use_stored_payment,
+                save_payment_method,
+                set_default,
+                installment_count,
+                connection,
+            )?;
+
+            return confirm_then_complete(
+                &*behavior,
+                token,
+                amount_in_cents,
                 currency,
                 order,
                 auth_user,
@@ -574,9 +821,107 @@ fn checkout_payment_processor(
     };
 }
 
+/// Token of the card to charge, which is saved for future payments if requested. Also sets up the
+/// payment plan, which is charged to the saved card.
+fn payment_token(
+    client: &dyn PaymentProcessor,
+    order: &Order,
+    token: Option<&str>,
+    auth_user: &User,
+    provider: PaymentProviders,
+    use_stored_payment: bool,
+    save_payment_method: bool,
+    set_default: bool,
+    installment_count: Option<u32>,
+    connection: &PgConnection,
+) -> Result<String, BigNeonError> {
+    if installment_count.is_some() && !use_stored_payment && !save_payment_method {
+        return application::unprocessable(
+            "Payment plans require a saved payment method to charge the remaining installments",
+        );
+    }
+    let token = if use_stored_payment {
+        info!("CART: Using stored payment");
+        match auth_user.user.payment_method(provider, connection).optional()? {
+            Some(payment_method) => payment_method.provider,
+            None => {
+                return application::unprocessable(
+                    "Could not complete this cart because stored provider does not exist",
+                );
+            }
+        }
+    } else {
+        info!("CART: Not using stored payment");
+        let token = match token {
+            Some(t) => t,
+            None => {
+                return application::unprocessable("Could not complete this cart because no token provided");
+            }
+        };
+
+        if save_payment_method {
+            info!("CART: User has requested to save the payment method");
+            match auth_user.user.payment_method(provider, connection).optional()? {
+                Some(payment_method) => {
+                    let client_response = match client.behavior() {
+                        PaymentProcessorBehavior::AuthThenComplete(b) => {
+                            b.update_repeat_token(&payment_method.provider, token, "Big Neon something")?
+                        }
+                        PaymentProcessorBehavior::ConfirmThenComplete(b) => {
+                            b.update_repeat_token(&payment_method.provider, token, "Big Neon something")?
+                        }
+                        PaymentProcessorBehavior::RedirectToPaymentPage(_) => return application::unprocessable(
+                            "Could not complete this cart using saved payment methods is not supported for this payment processor",
+                        ),
+                    };
+                    let payment_method_parameters = PaymentMethodEditableAttributes {
+                        provider_data: Some(client_response.to_json()?),
+                    };
+                    payment_method.update(&payment_method_parameters, auth_user.id(), connection)?;
+
+                    payment_method.provider
+                }
+                None => {
+                    let repeat_token = match client.behavior() {
+                        PaymentProcessorBehavior::AuthThenComplete(b) => {
+                            b.create_token_for_repeat_charges(token, "Big Neon")?
+                        }
+                        PaymentProcessorBehavior::ConfirmThenComplete(b) => {
+                            b.create_token_for_repeat_charges(token, "Big Neon")?
+                        }
+                        PaymentProcessorBehavior::RedirectToPaymentPage(_) => return application::unprocessable(
+                            "Could not complete this cart using saved payment methods is not supported for this payment processor",
+                        ),
+                    };
+                    let _payment_method = PaymentMethod::create(
+                        auth_user.id(),
+                        provider,
+                        set_default,
+                        repeat_token.token.clone(),
+                        repeat_token.to_json()?,
+                    )
+                    .commit(auth_user.id(), connection)?;
+                    repeat_token.token
+                }
+            }
+        } else {
+            token.to_string()
+        }
+    };
+
+    if let Some(installment_count) = installment_count {
+        info!("CART: Creating payment plan");
+        let payment_method = auth_user.user.payment_method(provider, connection)?;
+        PaymentPlan::create_for_order(order, payment_method.id, installment_count as i32, connection)?;
+    }
+
+    Ok(token)
+}
+
 fn auth_then_complete(
     client: &dyn AuthThenCompletePaymentBehavior,
     token: String,
+    amount_in_cents: Option<i64>,
     currency: &str,
     order: &mut Order,
     auth_user: &User,
@@ -586,7 +931,12 @@ fn auth_then_complete(
 ) -> Result<HttpResponse, BigNeonError> {
     let connection = conn.get();
     info!("CART: Auth'ing to payment provider");
-    let amount = order.calculate_total(connection)?;
+    // Store credit applied to the order is not charged to the card, and orders on a payment plan
+    // are only charged their deposit
+    let amount = match amount_in_cents {
+        Some(amount_in_cents) => amount_in_cents,
+        None => order.amount_due_now(connection)?,
+    };
     let auth_result = client.auth(
         &token,
         amount,
@@ -632,6 +982,241 @@ fn auth_then_complete(
     }
 }
 
+fn confirm_then_complete(
+    client: &dyn ConfirmThenCompletePaymentBehavior,
+    token: String,
+    amount_in_cents: Option<i64>,
+    currency: &str,
+    order: &mut Order,
+    auth_user: &User,
+    conn: &Connection,
+    payment_processor: &dyn PaymentProcessor,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    info!("CART: Auth'ing to payment provider");
+    // Store credit applied to the order is not charged to the card, and orders on a payment plan
+    // are only charged their deposit
+    let amount = match amount_in_cents {
+        Some(amount_in_cents) => amount_in_cents,
+        None => order.amount_due_now(connection)?,
+    };
+    let auth_result = client.auth(
+        &token,
+        amount,
+        currency,
+        "Big Neon Tickets",
+        false,
+        order.purchase_metadata(connection)?,
+    )?;
+    let status = match auth_result.status {
+        PaymentIntentStatus::RequiresAction => PaymentStatus::Requested,
+        PaymentIntentStatus::Authorized | PaymentIntentStatus::Completed => PaymentStatus::Authorized,
+        PaymentIntentStatus::Failed => {
+            return application::unprocessable("Could not complete this cart because the payment was declined");
+        }
+    };
+
+    info!("CART: Saving payment to order");
+    let payment = match order.add_credit_card_payment(
+        auth_user.id(),
+        amount,
+        client.payment_provider(),
+        auth_result.id.clone(),
+        status,
+        auth_result.to_json()?,
+        connection,
+    ) {
+        Ok(p) => p,
+        Err(e) => {
+            release_payment_intent(
+                client,
+                payment_processor,
+                &auth_result.id,
+                auth_result.status == PaymentIntentStatus::Completed,
+            )?;
+            return Err(e.into());
+        }
+    };
+
+    complete_payment_intent(
+        client,
+        payment,
+        auth_result,
+        auth_user,
+        conn,
+        payment_processor,
+        request_info,
+    )
+}
+
+#[derive(Serialize)]
+pub struct PaymentActionRequiredResponse {
+    pub order: DisplayOrder,
+    pub payment_id: Uuid,
+    /// Used by the frontend to let the customer complete the `next_action`, after which the
+    /// payment is confirmed with `POST /cart/payments/{payment_id}/confirm`
+    pub client_secret: Option<String>,
+    pub next_action: Option<Value>,
+}
+
+fn complete_payment_intent(
+    client: &dyn ConfirmThenCompletePaymentBehavior,
+    payment: Payment,
+    auth_result: PaymentIntentResult,
+    auth_user: &User,
+    conn: &Connection,
+    payment_processor: &dyn PaymentProcessor,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let charge_result = match auth_result.status {
+        PaymentIntentStatus::RequiresAction => {
+            info!("CART: Payment requires action from the customer");
+            let order = Order::find(payment.order_id, connection)?;
+            return Ok(HttpResponse::Accepted().json(PaymentActionRequiredResponse {
+                order: order.for_display(None, auth_user.id(), connection)?,
+                payment_id: payment.id,
+                client_secret: auth_result.client_secret,
+                next_action: auth_result.next_action,
+            }));
+        }
+        PaymentIntentStatus::Failed => {
+            payment.mark_cancelled(auth_result.to_json()?, Some(auth_user.id()), connection)?;
+            // Keep the cancellation when the error rolls back the request's transaction
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+            return application::unprocessable("Could not complete this cart because the payment was declined");
+        }
+        // Already captured by the payment provider
+        PaymentIntentStatus::Completed => auth_result.to_json()?,
+        PaymentIntentStatus::Authorized => {
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+
+            info!("CART: Completing auth with payment provider");
+            let charge_result = match client.complete_authed_charge(&auth_result.id) {
+                Ok(charge_result) => charge_result,
+                Err(e) => {
+                    // Release the hold on the card rather than leaving the authorization to lapse
+                    let cancel_result = client.cancel(&auth_result.id)?;
+                    payment.mark_cancelled(cancel_result.to_json()?, Some(auth_user.id()), connection)?;
+                    conn.commit_transaction()?;
+                    conn.begin_transaction()?;
+                    return Err(e.into());
+                }
+            };
+            info!("charge_result:{:?}", charge_result);
+            charge_result.to_json()?
+        }
+    };
+
+    info!("CART: Completing payment on order");
+    match payment.mark_complete(charge_result, Some(auth_user.id()), connection) {
+        Ok(_) => {
+            let mut order = Order::find(payment.order_id, connection)?;
+            order.set_browser_data(request_info.user_agent.clone(), true, connection)?;
+            Ok(HttpResponse::Ok().json(json!(order.for_display(None, auth_user.id(), connection)?)))
+        }
+        Err(e) => {
+            // The payment has been captured by this point
+            release_payment_intent(client, payment_processor, &auth_result.id, true)?;
+            Err(e.into())
+        }
+    }
+}
+
+/// Returns the customer's money when a payment intent can't be recorded against the order. An
+/// uncaptured intent is cancelled to release the hold on the card; refunding it would leave the
+/// authorization in place.
+fn release_payment_intent(
+    client: &dyn ConfirmThenCompletePaymentBehavior,
+    payment_processor: &dyn PaymentProcessor,
+    payment_intent_id: &str,
+    captured: bool,
+) -> Result<(), BigNeonError> {
+    if captured {
+        payment_processor.refund(payment_intent_id)?;
+    } else {
+        client.cancel(payment_intent_id)?;
+    }
+    Ok(())
+}
+
+/// Confirms a card payment once the customer has completed the action returned by checkout, e.g.
+/// authenticating the payment with 3D Secure. The order is completed when the payment covers it.
+pub fn confirm_payment(
+    (connection, path, user, state, request_info): (
+        Connection,
+        Path<PathParameters>,
+        User,
+        State<AppState>,
+        RequestInfo,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let payment = Payment::find(path.id, conn)?;
+    let mut order = Order::find(payment.order_id, conn)?;
+    if order.user_id != user.id() {
+        return application::forbidden("This cart does not belong to you");
+    }
+    let external_reference = match payment.external_reference.clone() {
+        Some(external_reference) => external_reference,
+        None => {
+            return application::unprocessable(
+                "Could not confirm this payment because it is not awaiting confirmation",
+            );
+        }
+    };
+    if payment.status != PaymentStatus::Requested || payment.payment_method != PaymentMethods::CreditCard {
+        return application::unprocessable("Could not confirm this payment because it is not awaiting confirmation");
+    }
+    order.lock_version(conn)?;
+
+    let organization = match order.organizations(conn)?.pop() {
+        Some(organization) => organization,
+        None => return application::internal_server_error("Could not find the organization for this cart"),
+    };
+    let client = state
+        .service_locator
+        .create_payment_processor(payment.provider, &organization)?;
+    let behavior = match client.behavior() {
+        PaymentProcessorBehavior::ConfirmThenComplete(behavior) => behavior,
+        _ => {
+            return application::unprocessable(
+                "Could not confirm this payment because it is not supported for this payment processor",
+            );
+        }
+    };
+
+    // The cart may have expired or changed while the customer was authenticating the payment
+    if order.is_expired() && order.try_refresh_expired_cart(Some(user.id()), conn).is_err() {
+        let cancel_result = behavior.cancel(&external_reference)?;
+        payment.mark_cancelled(cancel_result.to_json()?, Some(user.id()), conn)?;
+        return application::unprocessable("Could not confirm this payment because the cart has expired");
+    }
+    if (order.status != OrderStatus::Draft && order.status != OrderStatus::PendingPayment)
+        || payment.amount > order.amount_due_now(conn)?
+        || !order.items_valid_for_purchase(conn)?
+    {
+        let cancel_result = behavior.cancel(&external_reference)?;
+        payment.mark_cancelled(cancel_result.to_json()?, Some(user.id()), conn)?;
+        return application::unprocessable("Could not confirm this payment because the cart has changed");
+    }
+
+    info!("CART: Confirming payment with payment provider");
+    let auth_result = behavior.confirm(&external_reference)?;
+    complete_payment_intent(
+        &*behavior,
+        payment,
+        auth_result,
+        &user,
+        &connection,
+        &*client,
+        &request_info,
+    )
+}
+
 fn redirect_to_payment_page(
     client: &dyn RedirectToPaymentPageBehavior,
     user: &DbUser,
@@ -652,6 +1237,7 @@ fn redirect_to_payment_page(
     let nonce = random_alpha_string(12);
     let response = client.create_payment_request(
         amount as f64 / 100_f64,
+        &order.currency,
         email,
         order.id,
         ipn,
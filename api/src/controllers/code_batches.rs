@@ -0,0 +1,113 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User;
+use bigneon_db::dev::times;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateCodeBatchRequest {
+    pub name: String,
+    pub quantity: u32,
+    pub prefix: Option<String>,
+    pub code_type: CodeTypes,
+    pub discount_in_cents: Option<u32>,
+    pub discount_as_percentage: Option<u32>,
+    pub start_date: Option<NaiveDateTime>,
+    pub end_date: Option<NaiveDateTime>,
+    pub max_tickets_per_user: Option<u32>,
+    pub ticket_type_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub discount_rules: CodeDiscountRules,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayCodeBatch {
+    #[serde(flatten)]
+    pub code_batch: CodeBatch,
+    pub summary: CodeBatchSummary,
+}
+
+pub fn index((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeRead, &event.organization(conn)?, &event, conn)?;
+
+    Ok(HttpResponse::Ok().json(CodeBatch::find_for_event(event.id, conn)?))
+}
+
+pub fn create(
+    (conn, req, path, user): (Connection, Json<CreateCodeBatchRequest>, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let req = req.into_inner();
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeWrite, &event.organization(conn)?, &event, conn)?;
+
+    let code = Code::create(
+        req.name.clone(),
+        event.id,
+        req.code_type,
+        String::new(),
+        1,
+        req.discount_in_cents,
+        req.discount_as_percentage,
+        req.start_date.unwrap_or(times::zero()),
+        req.end_date.unwrap_or(times::infinity()),
+        req.max_tickets_per_user,
+    )
+    .with_discount_rules(req.discount_rules);
+
+    let code_batch = CodeBatch::create(event.id, req.name, req.quantity as i32, req.prefix, user.id()).commit(
+        code,
+        &req.ticket_type_ids,
+        conn,
+    )?;
+    application::created(json!(DisplayCodeBatch {
+        summary: code_batch.summary(conn)?,
+        code_batch,
+    }))
+}
+
+pub fn show((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let code_batch = CodeBatch::find(path.id, conn)?;
+    let event = code_batch.event(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeRead, &event.organization(conn)?, &event, conn)?;
+
+    Ok(HttpResponse::Ok().json(DisplayCodeBatch {
+        summary: code_batch.summary(conn)?,
+        code_batch,
+    }))
+}
+
+/// Downloads the batch's redemption codes as CSV.
+pub fn export((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let code_batch = CodeBatch::find(path.id, conn)?;
+    let event = code_batch.event(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeRead, &event.organization(conn)?, &event, conn)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv")
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"code_batch_{}.csv\"", code_batch.id),
+        )
+        .body(code_batch.to_csv(conn)?))
+}
+
+/// Revokes all of the batch's codes.
+pub fn destroy((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let code_batch = CodeBatch::find(path.id, conn)?;
+    let event = code_batch.event(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeWrite, &event.organization(conn)?, &event, conn)?;
+
+    Ok(HttpResponse::Ok().json(code_batch.revoke(Some(user.id()), conn)?))
+}
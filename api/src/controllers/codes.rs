@@ -24,6 +24,8 @@ pub struct CreateCodeRequest {
     pub end_date: Option<NaiveDateTime>,
     pub max_tickets_per_user: Option<u32>,
     pub ticket_type_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub discount_rules: CodeDiscountRules,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -42,6 +44,16 @@ pub struct UpdateCodeRequest {
     #[serde(default, deserialize_with = "double_option::deserialize")]
     pub max_tickets_per_user: Option<Option<u32>>,
     pub ticket_type_ids: Option<Vec<Uuid>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub min_quantity: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub buy_quantity: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub get_quantity: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub order_discount_in_cents: Option<Option<u32>>,
+    pub waives_fees: Option<bool>,
+    pub hold_stacking: Option<CodeHoldStackingRules>,
 }
 
 impl From<UpdateCodeRequest> for UpdateCodeAttributes {
@@ -77,6 +89,12 @@ impl From<UpdateCodeRequest> for UpdateCodeAttributes {
             start_date,
             end_date,
             max_tickets_per_user: attributes.max_tickets_per_user.map(|m| m.map(|m2| m2 as i64)),
+            min_quantity: attributes.min_quantity.map(|q| q.map(|q2| q2 as i64)),
+            buy_quantity: attributes.buy_quantity.map(|q| q.map(|q2| q2 as i64)),
+            get_quantity: attributes.get_quantity.map(|q| q.map(|q2| q2 as i64)),
+            order_discount_in_cents: attributes.order_discount_in_cents.map(|d| d.map(|d2| d2 as i64)),
+            waives_fees: attributes.waives_fees,
+            hold_stacking: attributes.hold_stacking,
         }
     }
 }
@@ -141,6 +159,7 @@ pub fn create(
         req.end_date.unwrap_or(times::infinity()),
         req.max_tickets_per_user,
     )
+    .with_discount_rules(req.discount_rules.clone())
     .commit(Some(user.id()), conn)?;
 
     code.update_ticket_types(req.ticket_type_ids.clone(), conn)?;
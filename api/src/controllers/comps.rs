@@ -1,12 +1,14 @@
-use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
+use communications::{mailers, smsers};
 use controllers::holds::UpdateHoldRequest;
 use db::Connection;
 use errors::BigNeonError;
 use extractors::*;
 use models::{PathParameters, WebPayload, WebResult};
+use server::AppState;
 
 pub fn index(
     (conn, path, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
@@ -82,6 +84,65 @@ pub fn create(
     Ok(WebResult::new(StatusCode::CREATED, comp.into_display(conn)?))
 }
 
+#[derive(Default, Deserialize, Serialize)]
+pub struct ImportCompsRequest {
+    pub csv: String,
+    pub end_at: Option<NaiveDateTime>,
+    pub max_per_user: Option<u32>,
+    #[serde(default)]
+    pub send_comp_links: bool,
+}
+
+pub fn import(
+    (conn, import_request, path, user, state): (
+        Connection,
+        Json<ImportCompsRequest>,
+        Path<PathParameters>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let hold = Hold::find(path.id, conn)?;
+    user.requires_scope_for_organization(Scopes::CompWrite, &hold.organization(conn)?, conn)?;
+    let import_request = import_request.into_inner();
+    let result = CompImport::from_csv(
+        hold.id,
+        &import_request.csv,
+        import_request.end_at,
+        import_request.max_per_user,
+    )?
+    .commit(Some(user.id()), conn)?;
+
+    if !result.errors.is_empty() {
+        return Ok(HttpResponse::UnprocessableEntity().json(json!({ "errors": result.errors })));
+    }
+
+    if import_request.send_comp_links {
+        let event = hold.event(conn)?;
+        let event_slug = event.slug(conn)?;
+        for comp in &result.comps {
+            let comp_link = format!(
+                "{}/{}/tickets?code={}",
+                &state.config.front_end_url,
+                event_slug,
+                comp.redemption_code.clone().unwrap_or("".to_string())
+            );
+            if comp.email.is_some() {
+                mailers::holds::comp_issued(comp, &comp_link, &state.config, conn)?;
+            } else if let Some(ref phone) = comp.phone {
+                smsers::holds::comp_issued(&state.config, phone.clone(), &event, &comp_link, conn)?;
+            }
+        }
+    }
+
+    let mut comps = Vec::new();
+    for comp in result.comps {
+        comps.push(comp.into_display(conn)?);
+    }
+    Ok(HttpResponse::Created().json(comps))
+}
+
 pub fn update(
     (conn, req, path, user): (Connection, Json<UpdateHoldRequest>, Path<PathParameters>, User),
 ) -> Result<HttpResponse, BigNeonError> {
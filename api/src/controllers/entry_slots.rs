@@ -0,0 +1,56 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+pub fn index((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    let entry_slots = EntrySlot::find_for_ticket_type_with_availability(ticket_type.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&entry_slots))
+}
+
+/// Creates the ticket type's entry slots from a daily schedule. Slots already scheduled for the
+/// same entry start are kept as is.
+pub fn create(
+    (connection, parameters, schedule, user): (Connection, Path<PathParameters>, Json<EntrySlotSchedule>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    requires_event_write(&user, &ticket_type, connection)?;
+
+    let entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(&entry_slots))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let entry_slot = EntrySlot::find(parameters.id, connection)?;
+    requires_event_write(&user, &entry_slot.ticket_type(connection)?, connection)?;
+
+    entry_slot.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
+
+fn requires_event_write(
+    user: &AuthUser,
+    ticket_type: &TicketType,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let event = ticket_type.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+    Ok(())
+}
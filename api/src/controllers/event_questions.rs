@@ -0,0 +1,100 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateEventQuestionRequest {
+    pub question: String,
+    pub question_type: EventQuestionTypes,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub per_ticket: bool,
+    #[serde(default)]
+    pub rank: i32,
+    pub answers_editable_until: Option<NaiveDateTime>,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    requires_event_write(&user, &event, connection)?;
+
+    Ok(HttpResponse::Ok().json(&EventQuestion::find_for_event(event.id, connection)?))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateEventQuestionRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let event = Event::find(parameters.id, connection)?;
+    requires_event_write(&user, &event, connection)?;
+
+    let event_question = EventQuestion::create(
+        event.id,
+        json.question,
+        json.question_type,
+        json.choices,
+        json.required,
+        json.per_ticket,
+        json.rank,
+        json.answers_editable_until,
+    )
+    .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&event_question))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventQuestionEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_question = EventQuestion::find(parameters.id, connection)?;
+    requires_event_write(&user, &event_question.event(connection)?, connection)?;
+
+    let event_question = event_question.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&event_question))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_question = EventQuestion::find(parameters.id, connection)?;
+    requires_event_write(&user, &event_question.event(connection)?, connection)?;
+
+    event_question.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
+
+fn requires_event_write(user: &AuthUser, event: &Event, connection: &PgConnection) -> Result<(), BigNeonError> {
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        event,
+        connection,
+    )?;
+    Ok(())
+}
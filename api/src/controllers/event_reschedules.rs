@@ -0,0 +1,117 @@
+use actix_web::{HttpResponse, Path, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use controllers::orders::{self, RefundResponse};
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use server::AppState;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventRescheduleRequest {
+    pub event_start: NaiveDateTime,
+    pub event_end: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub refund_window_ends_at: NaiveDateTime,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(EventReschedule::find_for_event(event.id, connection)?))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewEventRescheduleRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let event_reschedule = EventReschedule::create(
+        event.id,
+        json.event_start,
+        json.event_end,
+        json.door_time,
+        json.refund_window_ends_at,
+        user.id(),
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&event_reschedule))
+}
+
+/// Lets a ticket holder refund their tickets while the event's refund window is open.
+pub fn refund(
+    (connection, parameters, user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection.get();
+    let event = Event::find(parameters.id, conn)?;
+    let event_reschedule = match EventReschedule::find_open_for_event(event.id, conn)? {
+        Some(event_reschedule) => event_reschedule,
+        None => return application::unprocessable("Event is not open for refunds"),
+    };
+
+    let organization_wallet = Wallet::find_default_for_organization(event.organization_id, conn)?;
+    let mut amount_refunded = 0;
+    let mut refund_breakdown: HashMap<PaymentMethods, i64> = HashMap::new();
+    for (order, refund, refund_due, tickets) in event_reschedule.refund_tickets_for_user(user.id(), conn)? {
+        // Transfer tickets back to the organization wallet, returning them if the refund fails
+        let mut transferred_tickets = Vec::new();
+        let mut result = Ok((0, HashMap::new()));
+        for ticket in &tickets {
+            let user_wallet = Wallet::find(ticket.wallet_id, conn)?;
+            if let Err(e) = orders::transfer_ticket(ticket, &user_wallet, &organization_wallet, &state.config, conn) {
+                result = Err(e);
+                break;
+            }
+            transferred_tickets.push((ticket, user_wallet));
+        }
+        if result.is_ok() {
+            result = orders::refund_payments(&order, &refund, refund_due, 0, false, false, user.id(), &state.service_locator, conn);
+        }
+        let (amount, breakdown) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                for (ticket, user_wallet) in transferred_tickets {
+                    orders::transfer_ticket(ticket, &organization_wallet, &user_wallet, &state.config, conn)?;
+                }
+                return Err(e);
+            }
+        };
+
+        // Commit each order's refund as its payments have been refunded
+        if state.config.environment != Environment::Test {
+            connection.commit_transaction()?;
+            connection.begin_transaction()?;
+        }
+
+        amount_refunded += amount;
+        for (payment_method, amount) in breakdown {
+            *refund_breakdown.entry(payment_method).or_insert(0) += amount;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(RefundResponse {
+        amount_refunded,
+        refund_breakdown,
+    }))
+}
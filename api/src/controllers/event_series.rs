@@ -0,0 +1,203 @@
+use actix_web::{HttpResponse, Path, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use controllers::ticket_types;
+use db::Connection;
+use diesel::PgConnection;
+use domain_events::executors::UpdateGenresPayload;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use server::AppState;
+use uuid::Uuid;
+
+/// Occurrences are generated this far ahead when no horizon is given for open ended rules.
+pub const DEFAULT_OCCURRENCE_HORIZON_IN_DAYS: i64 = 365;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventSeriesRequest {
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_rule: String,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct GenerateOccurrencesRequest {
+    pub until: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DisplayEventSeries {
+    #[serde(flatten)]
+    pub event_series: EventSeries,
+    pub occurrences: Vec<Event>,
+}
+
+pub fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &organization, connection)?;
+
+    Ok(HttpResponse::Ok().json(EventSeries::find_for_organization(organization.id, connection)?))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    let organization = event_series.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &organization, connection)?;
+
+    let occurrences = event_series.occurrences(connection)?;
+    Ok(HttpResponse::Ok().json(DisplayEventSeries {
+        event_series,
+        occurrences,
+    }))
+}
+
+pub fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewEventSeriesRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventClone, &organization, connection)?;
+
+    let json = json.into_inner();
+    let event_series = EventSeries::create(organization.id, json.template_event_id, json.name, json.recurrence_rule)
+        .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&event_series))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventSeriesEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    let organization = event_series.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let event_series = event_series.update(json.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&event_series))
+}
+
+/// Creates any occurrences of the series that do not exist yet. Existing occurrences are kept
+/// even when they no longer match the recurrence rule so sold tickets are never orphaned.
+pub fn generate_occurrences(
+    (connection, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<GenerateOccurrencesRequest>,
+        AuthUser,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    let organization = event_series.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::EventClone, &organization, connection)?;
+
+    let horizon = json
+        .until
+        .unwrap_or(Utc::now().naive_utc() + Duration::days(DEFAULT_OCCURRENCE_HORIZON_IN_DAYS));
+    let occurrences = event_series.generate_occurrences(horizon, Some(user.id()), connection)?;
+    for occurrence in occurrences.iter() {
+        let ticket_types = occurrence.ticket_types(false, None, connection)?;
+        ticket_types::create_ticket_type_blockchain_assets(occurrence, &ticket_types, &state, connection)?;
+        create_update_genres_action(occurrence, user.id(), connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&occurrences))
+}
+
+pub fn sync(
+    (connection, path, user, state): (Connection, Path<PathParameters>, AuthUser, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    let organization = event_series.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let occurrences = event_series.sync_occurrences(Some(user.id()), connection)?;
+    sync_ticket_types(&event_series, user.id(), &state, connection)?;
+    for occurrence in occurrences.iter() {
+        create_update_genres_action(occurrence, user.id(), connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(&occurrences))
+}
+
+/// Pushes changes made to a series template outside of `Event::update` (e.g. artists) out to the
+/// upcoming occurrences.
+pub(crate) fn sync_occurrences_for_template(
+    event: &Event,
+    user_id: Uuid,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    if event.event_series_id.is_none() {
+        return Ok(());
+    }
+
+    if let Some(event_series) = EventSeries::find_by_template_event_id(event.id, connection)? {
+        for occurrence in event_series.sync_occurrences(Some(user_id), connection)? {
+            create_update_genres_action(&occurrence, user_id, connection)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes ticket types added to a series template out to the upcoming occurrences.
+pub(crate) fn sync_ticket_types_for_template(
+    event: &Event,
+    user_id: Uuid,
+    state: &State<AppState>,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    if event.event_series_id.is_none() {
+        return Ok(());
+    }
+
+    if let Some(event_series) = EventSeries::find_by_template_event_id(event.id, connection)? {
+        sync_ticket_types(&event_series, user_id, state, connection)?;
+    }
+
+    Ok(())
+}
+
+fn sync_ticket_types(
+    event_series: &EventSeries,
+    user_id: Uuid,
+    state: &State<AppState>,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    for (occurrence, ticket_types) in event_series.sync_ticket_types(Some(user_id), connection)? {
+        ticket_types::create_ticket_type_blockchain_assets(&occurrence, &ticket_types, state, connection)?;
+    }
+
+    Ok(())
+}
+
+fn create_update_genres_action(event: &Event, user_id: Uuid, connection: &PgConnection) -> Result<(), BigNeonError> {
+    DomainAction::create(
+        None,
+        DomainActionTypes::UpdateGenres,
+        None,
+        json!(UpdateGenresPayload { user_id }),
+        Some(Tables::Events),
+        Some(event.id),
+    )
+    .commit(connection)?;
+
+    Ok(())
+}
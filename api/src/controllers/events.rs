@@ -4,6 +4,7 @@ use bigneon_db::dev::times;
 use bigneon_db::prelude::*;
 use chrono::prelude::*;
 use chrono::Duration;
+use controllers::event_series;
 use controllers::organizations::DisplayOrganizationUser;
 use controllers::ticket_types;
 use db::Connection;
@@ -114,6 +115,7 @@ pub struct EventExportData {
     pub localized_times: EventLocalizedTimeStrings,
     pub event_type: EventTypes,
     pub slug: String,
+    pub attendee_questions: Vec<EventQuestion>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -164,6 +166,7 @@ impl From<EventSummaryResult> for EventExportData {
             localized_times: e.localized_times.clone(),
             event_type: e.event_type.clone(),
             slug: e.slug.clone(),
+            attendee_questions: Vec::new(),
         }
     }
 }
@@ -205,7 +208,10 @@ pub fn export_event_data(
         conn,
     )?;
 
-    let export_data: Vec<EventExportData> = events.data.into_iter().map(|e| e.into()).collect();
+    let mut export_data: Vec<EventExportData> = events.data.into_iter().map(|e| e.into()).collect();
+    for event in &mut export_data {
+        event.attendee_questions = EventQuestion::find_for_event(event.id, conn)?;
+    }
     Ok(WebPayload::new(
         StatusCode::OK,
         Payload::from_data(export_data, paging.page(), paging.limit(), None),
@@ -507,6 +513,7 @@ pub fn show(
         event_end: event.event_end,
         cancelled_at: event.cancelled_at,
         fee_in_cents,
+        currency: organization.currency.clone(),
         status,
         publish_date: event.publish_date,
         promo_image_url: optimize_cloudinary(&event.promo_image_url),
@@ -647,7 +654,7 @@ pub fn redeem_ticket(
     )?;
 
     match result {
-        RedeemResults::TicketRedeemSuccess => {
+        RedeemResults::TicketRedeemSuccess | RedeemResults::TicketRedeemedOutsideEntryWindow => {
             //Redeem ticket on chain
             let asset = Asset::find(ticket.asset_id, connection)?;
             match asset.blockchain_asset_id {
@@ -661,11 +668,19 @@ pub fn redeem_ticket(
                     //Fetch the redeemable again to include the redeemed_by and redeemed_at fields
                     let redeemable = TicketInstance::show_redeemable_ticket(parameters.ticket_instance_id, connection)?;
 
+                    if result == RedeemResults::TicketRedeemedOutsideEntryWindow {
+                        let mut body = json!(redeemable);
+                        body["warning"] = json!("Ticket was redeemed outside of its entry window.");
+                        return Ok(HttpResponse::Ok().json(body));
+                    }
+
                     Ok(HttpResponse::Ok().json(redeemable))
                 }
                 None => Ok(HttpResponse::BadRequest().json(json!({ "error": "Could not complete this checkout because the asset has not been assigned on the blockchain.".to_string()}))),
             }
         }
+        RedeemResults::TicketPaymentPlanUnpaid => Ok(HttpResponse::BadRequest()
+            .json(json!({"error": "Ticket's payment plan has not been paid in full.".to_string()}))),
         RedeemResults::TicketTransferInProcess => {
             Ok(HttpResponse::BadRequest()
                 .json(json!({"error": "Ticket has pending transfer in progress.".to_string()})))
@@ -675,6 +690,11 @@ pub fn redeem_ticket(
         "redeemed_by": redeemable.redeemed_by,
         "redeemed_at": redeemable.redeemed_at
         }))),
+        RedeemResults::TicketOutsideEntryWindow => Ok(HttpResponse::BadRequest().json(json!({
+        "error": "Ticket is outside of its entry window.".to_string(),
+        "entry_start": redeemable.entry_start,
+        "entry_end": redeemable.entry_end
+        }))),
         RedeemResults::TicketInvalid => {
             Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is invalid.".to_string()})))
         }
@@ -742,6 +762,7 @@ pub struct DashboardResult {
     pub event: EventSummaryResult,
     pub day_stats: Vec<DayStats>,
     pub cube_js_token: String,
+    pub capacity_pools: Vec<DisplayCapacityPool>,
 }
 
 pub fn dashboard(
@@ -769,11 +790,17 @@ pub fn dashboard(
 
     let day_stats = event.get_sales_by_date_range(start_utc, end_utc, conn)?;
 
+    let mut capacity_pools = Vec::new();
+    for capacity_pool in CapacityPool::find_for_event(event.id, conn)? {
+        capacity_pools.push(capacity_pool.for_display(conn)?);
+    }
+
     let cube_js_token = create_cube_js_token(event.id, &state.config.cube_js.secret)?;
     Ok(HttpResponse::Ok().json(DashboardResult {
         event: summary,
         day_stats,
         cube_js_token,
+        capacity_pools,
     }))
 }
 
@@ -943,6 +970,7 @@ pub fn add_artist(
         event_artist.stage_id,
     )
     .commit(Some(user.id()), connection)?;
+    event_series::sync_occurrences_for_template(&event, user.id(), connection)?;
 
     // Trigger update for event and associated users in background
     let action = DomainAction::create(
@@ -996,6 +1024,7 @@ pub fn update_artists(
         );
         rank += 1;
     }
+    event_series::sync_occurrences_for_template(&event, user.id(), connection)?;
 
     let action = DomainAction::create(
         None,
@@ -1074,6 +1103,7 @@ pub fn guest_list(
         #[serde(flatten)]
         pending_transfer: PendingTransfer,
         refund_supported: bool,
+        attendee_answers: Vec<DisplayEventAnswer>,
     }
 
     let mut tickets_refund: Vec<TicketRefundable> = Vec::new();
@@ -1093,6 +1123,7 @@ pub fn guest_list(
                 .clone()
                 .unwrap_or(PendingTransfer { ..Default::default() }),
             refund_supported: refundable,
+            attendee_answers: t.attendee_answers,
         });
     }
 
@@ -1197,6 +1228,13 @@ pub fn holds(
     Ok(HttpResponse::Ok().json(Payload::from_data(list, query.page(), query.limit(), None)))
 }
 
+pub fn seats((conn, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    let event = Event::find(path.id, conn)?;
+
+    Ok(HttpResponse::Ok().json(Seat::find_for_event(event.id, conn)?))
+}
+
 pub fn users(
     (connection, path_parameters, query_parameters, user): (
         Connection,
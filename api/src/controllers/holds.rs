@@ -24,6 +24,8 @@ pub struct CreateHoldRequest {
     pub ticket_type_id: Uuid,
     pub end_at: Option<NaiveDateTime>,
     pub max_per_user: Option<u32>,
+    #[serde(default)]
+    pub expiry_behavior: Option<HoldExpiryBehaviors>,
 }
 
 #[derive(Default, Deserialize, Serialize)]
@@ -43,6 +45,7 @@ pub struct UpdateHoldRequest {
     pub end_at: Option<Option<NaiveDateTime>>,
     #[serde(default, deserialize_with = "double_option::deserialize")]
     pub max_per_user: Option<Option<i64>>,
+    pub expiry_behavior: Option<HoldExpiryBehaviors>,
 }
 
 impl From<UpdateHoldRequest> for UpdateHoldAttributes {
@@ -56,6 +59,7 @@ impl From<UpdateHoldRequest> for UpdateHoldAttributes {
             end_at: attributes.end_at,
             max_per_user: attributes.max_per_user,
             redemption_code: attributes.redemption_code,
+            expiry_behavior: attributes.expiry_behavior,
         }
     }
 }
@@ -69,7 +73,7 @@ pub fn create(
     let event = Event::find(path.id, conn)?;
     user.requires_scope_for_organization_event(Scopes::HoldWrite, &event.organization(conn)?, &event, conn)?;
 
-    let hold = Hold::create_hold(
+    let mut new_hold = Hold::create_hold(
         req.name.clone(),
         path.id,
         req.redemption_code.clone(),
@@ -78,8 +82,11 @@ pub fn create(
         req.max_per_user,
         req.hold_type,
         req.ticket_type_id,
-    )
-    .commit(Some(user.id()), conn)?;
+    );
+    if let Some(expiry_behavior) = req.expiry_behavior {
+        new_hold.expiry_behavior = expiry_behavior;
+    }
+    let hold = new_hold.commit(Some(user.id()), conn)?;
 
     hold.set_quantity(Some(user.id()), req.quantity, conn)?;
 
@@ -94,6 +101,7 @@ pub fn create(
         pub max_per_user: Option<i64>,
         pub hold_type: HoldTypes,
         pub ticket_type_id: Uuid,
+        pub expiry_behavior: HoldExpiryBehaviors,
         pub available: u32,
         pub quantity: u32,
     }
@@ -110,6 +118,7 @@ pub fn create(
         max_per_user: hold.max_per_user,
         hold_type: hold.hold_type,
         ticket_type_id: hold.ticket_type_id,
+        expiry_behavior: hold.expiry_behavior,
         available,
         quantity,
     };
@@ -149,6 +158,7 @@ pub fn show((conn, path, user): (Connection, Path<PathParameters>, User)) -> Res
         pub max_per_user: Option<i64>,
         pub hold_type: HoldTypes,
         pub ticket_type_id: Uuid,
+        pub expiry_behavior: HoldExpiryBehaviors,
         pub available: u32,
         pub quantity: u32,
     }
@@ -165,6 +175,7 @@ pub fn show((conn, path, user): (Connection, Path<PathParameters>, User)) -> Res
         max_per_user: hold.max_per_user,
         hold_type: hold.hold_type,
         ticket_type_id: hold.ticket_type_id,
+        expiry_behavior: hold.expiry_behavior,
         available,
         quantity,
     };
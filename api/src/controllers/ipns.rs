@@ -1,11 +1,15 @@
-use actix_web::HttpResponse;
+use actix_web::{HttpRequest, HttpResponse};
 use bigneon_db::prelude::*;
 use bigneon_db::utils::dates::IntoDateBuilder;
 use db::Connection;
 use errors::BigNeonError;
 use extractors::Json;
 use globee::GlobeeIpnRequest;
-use log::Level::Debug;
+use helpers::application;
+use log::Level::{Debug, Warn};
+use serde_json;
+use server::AppState;
+use stripe::WebhookEvent;
 use uuid::Uuid;
 
 pub fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<HttpResponse, BigNeonError> {
@@ -33,3 +37,51 @@ pub fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<Http
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub fn stripe(
+    (request, body, conn): (HttpRequest<AppState>, String, Connection),
+) -> Result<HttpResponse, BigNeonError> {
+    let secret = match request.state().config.stripe_webhook_secret {
+        Some(ref secret) => secret.clone(),
+        None => return application::not_found(),
+    };
+    let signature = match request.headers().get("Stripe-Signature").and_then(|h| h.to_str().ok()) {
+        Some(signature) => signature.to_string(),
+        None => return application::bad_request("Stripe-Signature header is required"),
+    };
+    // The signature is computed over the raw body, so it is verified before the body is parsed
+    let event = match WebhookEvent::construct(&body, &signature, &secret) {
+        Ok(event) => event,
+        Err(e) => {
+            jlog!(Warn, "Stripe webhook rejected", { "error": e.to_string() });
+            return application::bad_request("Invalid Stripe-Signature header");
+        }
+    };
+    jlog!(Debug, "Stripe webhook received", { "id": &event.id, "type": &event.event_type });
+
+    // Stripe redelivers events until they are acknowledged, so events that have already been
+    // recorded are acknowledged without being processed again
+    let connection = conn.get();
+    let provider_event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        event.id.clone(),
+        event.event_type.clone(),
+        serde_json::from_str(&body)?,
+    )
+    .commit(connection)?;
+    if let Some(provider_event) = provider_event {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ProcessPaymentProviderEvent,
+            None,
+            json!({}),
+            Some(Tables::PaymentProviderEvents),
+            Some(provider_event.id),
+        );
+        action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+        action.max_attempt_count = 5;
+        action.commit(connection)?;
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -3,10 +3,16 @@ pub mod analytics;
 pub mod artists;
 pub mod auth;
 pub mod broadcasts;
+pub mod capacity_pools;
 pub mod cart;
+pub mod code_batches;
 pub mod codes;
 pub mod comps;
+pub mod entry_slots;
+pub mod event_questions;
 pub mod event_report_subscribers;
+pub mod event_reschedules;
+pub mod event_series;
 pub mod events;
 pub mod external;
 pub mod genres;
@@ -16,21 +22,31 @@ pub mod notes;
 pub mod orders;
 pub mod organization_invites;
 pub mod organizations;
+pub mod packages;
 pub mod password_resets;
 pub mod payment_methods;
 pub mod payments;
+pub mod presale_lotteries;
+pub mod pricing_rules;
+pub mod products;
 pub mod redemption_codes;
 pub mod regions;
 pub mod reports;
+pub mod resale_listings;
+pub mod seating_sections;
 pub mod settlement_adjustments;
 pub mod settlements;
 pub mod sitemap_gen;
 pub mod slugs;
 pub mod stages;
 pub mod status;
+pub mod store_credits;
+pub mod tax_rules;
 pub mod ticket_types;
+pub mod ticket_exchanges;
 pub mod tickets;
 pub mod transfers;
 pub mod user_invites;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
@@ -4,6 +4,7 @@ use bigneon_db::models::User as DbUser;
 use bigneon_db::models::*;
 use communications::mailers;
 use communications::smsers;
+use config::Config;
 use db::Connection;
 use diesel::pg::PgConnection;
 use diesel::Connection as DieselConnection;
@@ -17,6 +18,7 @@ use server::AppState;
 use std::cmp;
 use std::collections::HashMap;
 use utils::serializers::default_as_false;
+use utils::ServiceLocator;
 use uuid::Uuid;
 
 pub fn index(
@@ -72,6 +74,69 @@ pub fn show((conn, path, auth_user): (Connection, Path<PathParameters>, User)) -
     )?)))
 }
 
+pub fn answers(
+    (conn, path, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let order = Order::find(path.id, connection)?;
+    if order.on_behalf_of_user_id.unwrap_or(order.user_id) == auth_user.id() {
+        auth_user.requires_scope(Scopes::OrderReadOwn)?;
+    } else {
+        let mut has_access = false;
+        for organization in order.organizations(connection)? {
+            if auth_user.has_scope_for_organization(Scopes::OrderRead, &organization, connection)? {
+                has_access = true;
+            }
+        }
+        if !has_access {
+            return application::forbidden("You do not have access to this order");
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(&EventAnswer::questions_for_order(&order, connection)?))
+}
+
+/// Shows the installment schedule and progress of the payment plan the order was bought with.
+pub fn payment_plan(
+    (conn, path, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let order = Order::find(path.id, connection)?;
+    if order.user_id == auth_user.id() {
+        auth_user.requires_scope(Scopes::OrderReadOwn)?;
+    } else {
+        let mut has_access = false;
+        for organization in order.organizations(connection)? {
+            if auth_user.has_scope_for_organization(Scopes::OrderRead, &organization, connection)? {
+                has_access = true;
+            }
+        }
+        if !has_access {
+            return application::forbidden("You do not have access to this order");
+        }
+    }
+
+    match PaymentPlan::find_for_order(order.id, connection)? {
+        Some(payment_plan) => Ok(HttpResponse::Ok().json(&payment_plan.for_display(connection)?)),
+        None => application::not_found(),
+    }
+}
+
+/// Lets the purchaser change their attendee answers until the cutoff for each question.
+pub fn update_answers(
+    (conn, path, json, auth_user): (Connection, Path<PathParameters>, Json<Vec<EventAnswerRequest>>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let order = Order::find(path.id, connection)?;
+    if order.on_behalf_of_user_id.unwrap_or(order.user_id) != auth_user.id() || order.status != OrderStatus::Paid {
+        return application::forbidden("You do not have access to this order");
+    }
+    auth_user.requires_scope(Scopes::OrderReadOwn)?;
+
+    let questions = EventAnswer::save_for_order(&order, &json.into_inner(), Some(auth_user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&questions))
+}
+
 pub fn resend_confirmation(
     (conn, path, auth_user, state): (Connection, Path<PathParameters>, User, State<AppState>),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -134,6 +199,9 @@ pub struct RefundAttributes {
     pub reason: Option<String>,
     #[serde(default = "default_as_false")]
     pub manual_override: bool,
+    /// Issues the refund as store credit instead of returning it to the original payment method.
+    #[serde(default = "default_as_false")]
+    pub to_store_credit: bool,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -157,6 +225,7 @@ pub fn refund(
     let reason = refund_attributes.reason;
     let items = refund_attributes.items;
     let manual_override = refund_attributes.manual_override;
+    let to_store_credit = refund_attributes.to_store_credit;
     let mut order = Order::find(path.id, connection)?;
 
     if order.status != OrderStatus::Paid {
@@ -200,12 +269,8 @@ pub fn refund(
     }
     let mut modified_tokens: HashMap<Uuid, Vec<u64>> = HashMap::new();
 
-    let mut refund_breakdown: HashMap<PaymentMethods, i64> = HashMap::new();
-    let mut payment_remaining_balance_map: HashMap<Option<String>, i64> = HashMap::new();
-    let mut amount_refunded = 0;
-
     // Begin transaction, if it fails at this point all transferred tickets are returned to wallets
-    match connection.transaction::<_, BigNeonError, _>(|| {
+    let (amount_refunded, refund_breakdown) = match connection.transaction::<_, BigNeonError, _>(|| {
         for (asset_id, token_ids) in &tokens_per_asset {
             let organization_id = Organization::find_by_asset_id(*asset_id, connection)?.id;
             let organization_wallet = Wallet::find_default_for_organization(organization_id, connection)?;
@@ -254,74 +319,17 @@ pub fn refund(
         }
 
         // Perform refunds
-
-        // Negative payments / refunds cancel out remaining payment balance
-        for payment in order.payments(connection)? {
-            // Ignore payments that were only authorized
-            if payment.status == PaymentStatus::Authorized {
-                continue;
-            }
-
-            *payment_remaining_balance_map
-                .entry(payment.external_reference)
-                .or_insert(0) += payment.amount;
-        }
-
-        for payment in order.payments(connection)? {
-            if payment.status != PaymentStatus::Completed {
-                continue;
-            }
-
-            let remaining_balance = payment_remaining_balance_map
-                .get(&payment.external_reference)
-                .map(|n| *n)
-                .unwrap_or(0);
-            if remaining_balance == 0 {
-                continue;
-            }
-
-            let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance);
-            let mut refund_data = None;
-            if !manual_override && payment.payment_method == PaymentMethods::CreditCard {
-                let mut organizations = order.organizations(connection)?;
-                if organizations.len() != 1 {
-                    return Err(application::internal_server_error::<HttpResponse>(
-                        "Cannot process refunds for orders that contain more than one event",
-                    )
-                    .unwrap_err());
-                }
-                let organization = organizations.remove(0);
-                let client = &state
-                    .service_locator
-                    .create_payment_processor(payment.provider, &organization)?;
-
-                refund_data = match payment.external_reference {
-                    Some(ref external_reference) => {
-                        Some(client.partial_refund(external_reference, amount_to_refund)?.to_json()?)
-                    }
-                    None => {
-                        return Err(application::internal_server_error::<HttpResponse>(&format!(
-                            "Unable to refund amount owed payment {} lacks external reference",
-                            payment.id
-                        ))
-                        .unwrap_err());
-                    }
-                };
-            }
-            payment.log_refund(user.id(), &refund, amount_to_refund, refund_data, connection)?;
-            *refund_breakdown.entry(payment.payment_method).or_insert(0) += amount_to_refund;
-            amount_refunded += amount_to_refund;
-        }
-
-        if amount_refunded < refund_due {
-            return Err(application::internal_server_error::<HttpResponse>(&format!(
-                "Unable to refund amount owed {} refunded, {} due",
-                amount_refunded, refund_due
-            ))
-            .unwrap_err());
-        }
-
-        Ok(())
+        refund_payments(
+            &order,
+            &refund,
+            refund_due,
+            0,
+            manual_override,
+            to_store_credit,
+            user.id(),
+            &state.service_locator,
+            connection,
+        )
     }) {
         Err(error) => {
             for (asset_id, token_ids) in &modified_tokens {
@@ -358,8 +366,8 @@ pub fn refund(
             // Return error
             return Err(error);
         }
-        _ => (),
-    }
+        Ok(result) => result,
+    };
 
     // Commit changes as payment completed
     if state.config.environment != Environment::Test {
@@ -382,6 +390,116 @@ pub fn refund(
     })))
 }
 
+/// Refunds `refund_due` against the order's completed payments, logging a refund on each payment used.
+/// Only `refund_due - retained_in_cents` is returned through the payment processors, the retained
+/// amount is kept as credit (e.g. towards the new order of a ticket exchange) and logged as an
+/// Exchange refund. Payments made with
+/// store credit, and all payments when `to_store_credit` is set, are returned as store credit and
+/// logged as StoreCredit refunds.
+/// Orders split across several payments are refunded from the most recent payment first.
+pub(crate) fn refund_payments(
+    order: &Order,
+    refund: &Refund,
+    refund_due: i64,
+    retained_in_cents: i64,
+    manual_override: bool,
+    to_store_credit: bool,
+    user_id: Uuid,
+    service_locator: &ServiceLocator,
+    connection: &PgConnection,
+) -> Result<(i64, HashMap<PaymentMethods, i64>), BigNeonError> {
+    let mut refund_breakdown: HashMap<PaymentMethods, i64> = HashMap::new();
+    let mut amount_refunded = 0;
+    let provider_refund_due = refund_due - retained_in_cents;
+    let mut provider_amount_refunded = 0;
+
+    for (payment, remaining_balance) in order.refundable_payments(connection)? {
+        if amount_refunded >= refund_due {
+            break;
+        }
+
+        let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance);
+        let provider_amount = cmp::min(amount_to_refund, provider_refund_due - provider_amount_refunded);
+        let mut refund_data = None;
+        let mut refund_method = payment.payment_method;
+        let mut refund_provider = payment.provider;
+        if (to_store_credit || payment.payment_method == PaymentMethods::StoreCredit) && provider_amount > 0 {
+            let store_credit = StoreCreditTransaction::credit_refund(
+                order.on_behalf_of_user_id.unwrap_or(order.user_id),
+                refund,
+                &payment,
+                provider_amount,
+                user_id,
+                connection,
+            )?;
+            refund_data = Some(json!({ "store_credit_transaction_id": store_credit.id }));
+            refund_method = PaymentMethods::StoreCredit;
+            refund_provider = PaymentProviders::StoreCredit;
+        } else if !manual_override && payment.payment_method == PaymentMethods::CreditCard && provider_amount > 0 {
+            let mut organizations = order.organizations(connection)?;
+            if organizations.len() != 1 {
+                return Err(application::internal_server_error::<HttpResponse>(
+                    "Cannot process refunds for orders that contain more than one event",
+                )
+                .unwrap_err());
+            }
+            let organization = organizations.remove(0);
+            let client = &service_locator.create_payment_processor(payment.provider, &organization)?;
+
+            refund_data = match payment.external_reference {
+                Some(ref external_reference) => {
+                    Some(client.partial_refund(external_reference, provider_amount)?.to_json()?)
+                }
+                None => {
+                    return Err(application::internal_server_error::<HttpResponse>(&format!(
+                        "Unable to refund amount owed payment {} lacks external reference",
+                        payment.id
+                    ))
+                    .unwrap_err());
+                }
+            };
+        }
+        if provider_amount > 0 {
+            payment.log_refund_as(
+                user_id,
+                refund,
+                provider_amount,
+                refund_method,
+                refund_provider,
+                refund_data,
+                connection,
+            )?;
+            *refund_breakdown.entry(refund_method).or_insert(0) += provider_amount;
+        }
+        // The retained amount pays towards the exchange's new order rather than going back to the payment
+        let retained_amount = amount_to_refund - cmp::max(provider_amount, 0);
+        if retained_amount > 0 {
+            payment.log_refund_as(
+                user_id,
+                refund,
+                retained_amount,
+                PaymentMethods::Exchange,
+                PaymentProviders::Exchange,
+                None,
+                connection,
+            )?;
+            *refund_breakdown.entry(PaymentMethods::Exchange).or_insert(0) += retained_amount;
+        }
+        amount_refunded += amount_to_refund;
+        provider_amount_refunded += cmp::max(provider_amount, 0);
+    }
+
+    if amount_refunded < refund_due {
+        return Err(application::internal_server_error::<HttpResponse>(&format!(
+            "Unable to refund amount owed {} refunded, {} due",
+            amount_refunded, refund_due
+        ))
+        .unwrap_err());
+    }
+
+    Ok((amount_refunded, refund_breakdown))
+}
+
 fn is_authorized_to_refund(
     user: &User,
     connection: &PgConnection,
@@ -479,3 +597,33 @@ pub fn send_box_office_instructions(
     smsers::box_office::checkin_instructions(&state.config, phone, path.id, conn)?;
     Ok(HttpResponse::Ok().json({}))
 }
+
+/// Moves a ticket's token between wallets on the blockchain and records the new wallet.
+pub(crate) fn transfer_ticket(
+    ticket: &TicketInstance,
+    from_wallet: &Wallet,
+    to_wallet: &Wallet,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let asset = Asset::find(ticket.asset_id, conn)?;
+    let blockchain_asset_id = match asset.blockchain_asset_id {
+        Some(blockchain_asset_id) => blockchain_asset_id,
+        None => {
+            return Err(application::internal_server_error::<HttpResponse>(
+                "Could not transfer ticket because the asset is not assigned on the blockchain",
+            )
+            .unwrap_err());
+        }
+    };
+    config.tari_client.transfer_tokens(
+        &from_wallet.secret_key,
+        &from_wallet.public_key,
+        &blockchain_asset_id,
+        vec![ticket.token_id as u64],
+        to_wallet.public_key.clone(),
+    )?;
+    ticket.set_wallet(to_wallet, conn)?;
+
+    Ok(())
+}
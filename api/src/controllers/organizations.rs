@@ -24,6 +24,8 @@ pub struct FeeScheduleWithRanges {
     pub name: String,
     pub version: i16,
     pub created_at: NaiveDateTime,
+    pub resale_company_fee_percent: f32,
+    pub resale_client_fee_percent: f32,
     pub ranges: Vec<FeeScheduleRange>,
 }
 
@@ -58,12 +60,18 @@ pub struct NewOrganizationRequest {
     pub cc_fee_percent: Option<f32>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct NewFeeScheduleRequest {
     pub name: String,
     pub ranges: Vec<NewFeeScheduleRange>,
+    #[serde(default)]
+    pub resale_company_fee_percent: f32,
+    #[serde(default)]
+    pub resale_client_fee_percent: f32,
 }
 
 pub fn index(
@@ -151,6 +159,12 @@ pub fn create(
             None => state.config.max_instances_per_ticket_type,
         }),
         settlement_type: new_organization.settlement_type,
+        currency: Some(
+            new_organization
+                .currency
+                .clone()
+                .unwrap_or_else(|| state.config.primary_currency.clone()),
+        ),
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -182,7 +196,7 @@ pub fn update(
 
     if organization_update.settlement_type.is_some() {
         user.requires_scope_for_organization(Scopes::OrgModifySettlementType, &organization, conn)?;
-    } else if organization_update.max_instances_per_ticket_type.is_some() {
+    } else if organization_update.max_instances_per_ticket_type.is_some() || organization_update.currency.is_some() {
         user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, conn)?;
     } else {
         user.requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)?;
@@ -342,6 +356,8 @@ pub fn show_fee_schedule(
         name: fee_schedule.name,
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
+        resale_company_fee_percent: fee_schedule.resale_company_fee_percent,
+        resale_client_fee_percent: fee_schedule.resale_client_fee_percent,
         ranges: fee_schedule_ranges,
     }))
 }
@@ -352,10 +368,13 @@ pub fn add_fee_schedule(
     user.requires_scope(Scopes::OrgAdmin)?;
     let connection = connection.get();
 
+    let json = json.into_inner();
     let new_fee_schedule = NewFeeSchedule {
         organization_id: parameters.id,
-        name: json.name.clone(),
-        ranges: json.into_inner().ranges,
+        name: json.name,
+        ranges: json.ranges,
+        resale_company_fee_percent: json.resale_company_fee_percent,
+        resale_client_fee_percent: json.resale_client_fee_percent,
     };
     let fee_schedule = new_fee_schedule.commit(Some(user.id()), connection)?;
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
@@ -367,6 +386,8 @@ pub fn add_fee_schedule(
         name: fee_schedule.name,
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
+        resale_company_fee_percent: fee_schedule.resale_company_fee_percent,
+        resale_client_fee_percent: fee_schedule.resale_client_fee_percent,
         ranges: fee_schedule_ranges,
     }))
 }
@@ -0,0 +1,124 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreatePackageRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct AddPackageTicketTypeRequest {
+    pub ticket_type_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UpdatePackageTicketTypeRequest {
+    pub price_in_cents: i64,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, OptionalUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    // Inactive packages are only listed for the organization's event managers
+    let active_only = match user.into_inner() {
+        Some(user) => !user.has_scope_for_organization(Scopes::EventWrite, &organization, connection)?,
+        None => true,
+    };
+
+    let mut packages = Vec::new();
+    for package in Package::find_for_organization(organization.id, active_only, connection)? {
+        packages.push(package.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(&packages))
+}
+
+pub fn show((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let package = Package::find(parameters.id, connection)?;
+    Ok(HttpResponse::Ok().json(package.for_display(connection)?))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<CreatePackageRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let package = Package::create(organization.id, json.name, json.description).commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(package.for_display(connection)?))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<PackageEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let package = Package::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &package.organization(connection)?, connection)?;
+
+    let package = package.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(package.for_display(connection)?))
+}
+
+pub fn add_ticket_type(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AddPackageTicketTypeRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let package = Package::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &package.organization(connection)?, connection)?;
+
+    package.add_ticket_type(json.ticket_type_id, json.price_in_cents, Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(package.for_display(connection)?))
+}
+
+pub fn update_ticket_type(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdatePackageTicketTypeRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let package_ticket_type = PackageTicketType::find(parameters.id, connection)?;
+    let package = package_ticket_type.package(connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &package.organization(connection)?, connection)?;
+
+    package_ticket_type.update_price(json.price_in_cents, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(package.for_display(connection)?))
+}
+
+pub fn destroy_ticket_type(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let package_ticket_type = PackageTicketType::find(parameters.id, connection)?;
+    let package = package_ticket_type.package(connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &package.organization(connection)?, connection)?;
+
+    package_ticket_type.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
@@ -0,0 +1,104 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreatePresaleLotteryRequest {
+    pub name: String,
+    pub ticket_type_ids: Vec<Uuid>,
+    pub registration_start: NaiveDateTime,
+    pub registration_end: NaiveDateTime,
+    pub draw_at: NaiveDateTime,
+    pub code_expires_at: NaiveDateTime,
+    pub winner_count: i32,
+    pub tickets_per_winner: i32,
+    #[serde(default)]
+    pub interaction_weight: i32,
+    pub max_weight: Option<i32>,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    requires_event_write(&user, &event, connection)?;
+
+    Ok(HttpResponse::Ok().json(&PresaleLottery::find_for_event(event.id, connection)?))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreatePresaleLotteryRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let event = Event::find(parameters.id, connection)?;
+    requires_event_write(&user, &event, connection)?;
+
+    let presale_lottery = PresaleLottery::create(
+        event.id,
+        json.name,
+        json.ticket_type_ids,
+        json.registration_start,
+        json.registration_end,
+        json.draw_at,
+        json.code_expires_at,
+        json.winner_count,
+        json.tickets_per_winner,
+        json.interaction_weight,
+        json.max_weight.unwrap_or(1),
+        user.id(),
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&presale_lottery))
+}
+
+pub fn show((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let presale_lottery = PresaleLottery::find(parameters.id, connection)?;
+    Ok(HttpResponse::Ok().json(&presale_lottery))
+}
+
+pub fn register(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let presale_lottery = PresaleLottery::find(parameters.id, connection)?;
+    let entry = presale_lottery.register(user.id(), connection)?;
+
+    Ok(HttpResponse::Created().json(&entry))
+}
+
+/// Draws the lottery ahead of its scheduled draw, registration must already be closed.
+pub fn draw(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let presale_lottery = PresaleLottery::find(parameters.id, connection)?;
+    requires_event_write(&user, &presale_lottery.event(connection)?, connection)?;
+
+    Ok(HttpResponse::Ok().json(&presale_lottery.draw(connection)?))
+}
+
+fn requires_event_write(user: &AuthUser, event: &Event, connection: &PgConnection) -> Result<(), BigNeonError> {
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        event,
+        connection,
+    )?;
+    Ok(())
+}
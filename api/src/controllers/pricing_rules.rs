@@ -0,0 +1,107 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreatePricingRuleRequest {
+    pub name: String,
+    pub rule_type: PricingRuleTypes,
+    pub threshold: i64,
+    pub adjustment_in_cents: i64,
+    pub floor_price_in_cents: i64,
+    pub ceiling_price_in_cents: i64,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    let event = ticket_type.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::DashboardRead,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(&PricingRule::find_for_ticket_type(ticket_type.id, connection)?))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreatePricingRuleRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    requires_event_write(&user, &ticket_type.event(connection)?, connection)?;
+
+    let pricing_rule = PricingRule::create(
+        ticket_type.id,
+        json.name,
+        json.rule_type,
+        json.threshold,
+        json.adjustment_in_cents,
+        json.floor_price_in_cents,
+        json.ceiling_price_in_cents,
+    )
+    .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&pricing_rule))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<PricingRuleEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let pricing_rule = PricingRule::find(parameters.id, connection)?;
+    requires_event_write(
+        &user,
+        &pricing_rule.ticket_type(connection)?.event(connection)?,
+        connection,
+    )?;
+
+    let pricing_rule = pricing_rule.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&pricing_rule))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let pricing_rule = PricingRule::find(parameters.id, connection)?;
+    requires_event_write(
+        &user,
+        &pricing_rule.ticket_type(connection)?.event(connection)?,
+        connection,
+    )?;
+
+    pricing_rule.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
+
+fn requires_event_write(user: &AuthUser, event: &Event, connection: &PgConnection) -> Result<(), BigNeonError> {
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        event,
+        connection,
+    )?;
+    Ok(())
+}
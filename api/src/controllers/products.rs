@@ -0,0 +1,200 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateProductRequest {
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    #[serde(default)]
+    pub gift_card: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateProductVariantRequest {
+    pub name: String,
+    pub sku: String,
+    pub price_in_cents: Option<i64>,
+    pub stock: i32,
+    pub rank: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RedeemProductRequest {
+    pub redeem_key: String,
+}
+
+pub fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let mut products = Vec::new();
+    for product in Product::find_for_organization(organization.id, connection)? {
+        products.push(product.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(&products))
+}
+
+/// Products on sale for the event, including the organization's products that can be picked up at
+/// any of its events.
+pub fn index_for_event(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+
+    let mut products = Vec::new();
+    for product in Product::find_for_event(&event, connection)? {
+        products.push(product.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(&products))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<CreateProductRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let organization = Organization::find(parameters.id, connection)?;
+    match json.event_id {
+        Some(event_id) => {
+            let event = Event::find(event_id, connection)?;
+            user.requires_scope_for_organization_event(Scopes::EventWrite, &organization, &event, connection)?;
+        }
+        None => user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?,
+    }
+
+    let mut product = Product::create(
+        organization.id,
+        json.event_id,
+        json.name,
+        json.description,
+        json.price_in_cents,
+    );
+    if json.gift_card {
+        product = product.as_gift_card();
+    }
+    let product = product.commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(product.for_display(connection)?))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<ProductEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let product = Product::find(parameters.id, connection)?;
+    requires_product_write(&user, &product, connection)?;
+
+    let product = product.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(product.for_display(connection)?))
+}
+
+pub fn create_variant(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateProductVariantRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let json = json.into_inner();
+    let product = Product::find(parameters.id, connection)?;
+    requires_product_write(&user, &product, connection)?;
+
+    let variant = ProductVariant::create(
+        product.id,
+        json.name,
+        json.sku,
+        json.price_in_cents,
+        json.stock,
+        json.rank.unwrap_or(0),
+    )
+    .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(variant.for_display(&product, connection)?))
+}
+
+pub fn update_variant(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<ProductVariantEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let variant = ProductVariant::find(parameters.id, connection)?;
+    let product = variant.product(connection)?;
+    requires_product_write(&user, &product, connection)?;
+
+    let variant = variant.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(variant.for_display(&product, connection)?))
+}
+
+/// Products the current user has bought, along with the keys used to pick them up.
+pub fn index_instances((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let product_instances = ProductInstance::find_for_user_for_display(user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&product_instances))
+}
+
+pub fn redeem(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<RedeemProductRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let product_instance = ProductInstance::find(parameters.id, connection)?;
+    let event_id = product_instance.order_item(connection)?.event_id;
+    let product = product_instance.product_variant(connection)?.product(connection)?;
+    let organization = product.organization(connection)?;
+    match event_id {
+        Some(event_id) => {
+            let event = Event::find(event_id, connection)?;
+            user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+        }
+        None => user.requires_scope_for_organization(Scopes::RedeemTicket, &organization, connection)?,
+    }
+
+    match product_instance.redeem(&json.redeem_key, user.id(), connection)? {
+        ProductRedeemResults::ProductRedeemSuccess => {
+            Ok(HttpResponse::Ok().json(ProductInstance::find(product_instance.id, connection)?))
+        }
+        ProductRedeemResults::ProductAlreadyRedeemed => Ok(HttpResponse::Conflict().json(json!({
+            "error": "Product has already been picked up.".to_string(),
+            "redeemed_at": product_instance.redeemed_at
+        }))),
+        ProductRedeemResults::ProductInvalid => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Product is invalid.".to_string()})))
+        }
+    }
+}
+
+fn requires_product_write(user: &AuthUser, product: &Product, connection: &PgConnection) -> Result<(), BigNeonError> {
+    let organization = product.organization(connection)?;
+    match product.event(connection)? {
+        Some(event) => {
+            user.requires_scope_for_organization_event(Scopes::EventWrite, &organization, &event, connection)?
+        }
+        None => user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?,
+    }
+    Ok(())
+}
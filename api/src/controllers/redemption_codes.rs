@@ -34,6 +34,11 @@ pub enum RedemptionCodeResponse {
         start_date: NaiveDateTime,
         end_date: NaiveDateTime,
         max_per_user: Option<i64>,
+        min_quantity: Option<i64>,
+        buy_quantity: Option<i64>,
+        get_quantity: Option<i64>,
+        order_discount_in_cents: Option<i64>,
+        waives_fees: bool,
     },
 }
 
@@ -93,6 +98,11 @@ pub fn show(
                 start_date: code_available.code.start_date,
                 end_date: code_available.code.end_date,
                 max_per_user: code_available.code.max_tickets_per_user,
+                min_quantity: code_available.code.min_quantity,
+                buy_quantity: code_available.code.buy_quantity,
+                get_quantity: code_available.code.get_quantity,
+                order_discount_in_cents: code_available.code.order_discount_in_cents,
+                waives_fees: code_available.code.waives_fees,
             }
         } else {
             return application::not_found();
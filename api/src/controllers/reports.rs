@@ -64,6 +64,8 @@ pub fn get_report(
         "reconciliation_summary" => reconciliation_summary_report((connection, query, path, user)),
         "reconciliation_details" => reconciliation_detail_report((connection, query, path, user)),
         "promo_code" => promo_code_report((connection, query, path, user)),
+        "store_credit" => store_credit_report((connection, query, path, user)),
+        "tax" => tax_report((connection, query, path, user)),
         _ => application::not_found(),
     }
 }
@@ -244,3 +246,29 @@ pub fn reconciliation_detail_report(
     let result = Report::reconciliation_detail_report(path.id, query.start_utc, query.end_utc, connection)?;
     Ok(HttpResponse::Ok().json(result))
 }
+
+/// Store credit issued and spent through the organization's events and gift cards.
+pub fn store_credit_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let result = Report::store_credit_report(Some(path.id), query.start_utc, query.end_utc, connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Sales tax and VAT charged on the organization's events per tax rule.
+pub fn tax_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let result = Report::tax_report(path.id, query.event_id, query.start_utc, query.end_utc, connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
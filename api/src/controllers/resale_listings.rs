@@ -0,0 +1,62 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewResaleListingRequest {
+    pub price_in_cents: i64,
+}
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    Ok(HttpResponse::Ok().json(ResaleListing::find_for_seller(user.id(), connection)?))
+}
+
+/// Listings available to purchase for the event, cheapest first.
+pub fn index_for_event(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let mut listings = Vec::new();
+    for listing in ResaleListing::find_available_for_event(event.id, connection)? {
+        listings.push(listing.for_display(connection)?);
+    }
+
+    Ok(HttpResponse::Ok().json(listings))
+}
+
+pub fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewResaleListingRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket = TicketInstance::find(parameters.id, connection)?;
+    let ticket_type = ticket.ticket_type(connection)?;
+    let listing =
+        ResaleListing::create(ticket.id, ticket_type.event_id, user.id(), json.price_in_cents).commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&listing))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let listing = ResaleListing::find(parameters.id, connection)?;
+    if listing.seller_user_id != user.id() {
+        return application::forbidden("This resale listing does not belong to you");
+    }
+    listing.cancel(Some(user.id()), connection)?;
+
+    application::no_content()
+}
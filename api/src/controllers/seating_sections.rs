@@ -0,0 +1,187 @@
+use actix_web::{HttpResponse, Path, Query};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::pg::PgConnection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+
+pub fn index(
+    (connection, path_parameters, query_parameters): (Connection, Path<PathParameters>, Query<PagingParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let seating_sections = SeatingSection::find_by_stage_id(path_parameters.id, connection.get())?;
+
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        seating_sections,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub fn show((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let seating_section = SeatingSection::find(parameters.id, connection.get())?;
+
+    Ok(HttpResponse::Ok().json(&seating_section))
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeatingSection {
+    pub name: String,
+    #[serde(default)]
+    pub rank: i32,
+}
+
+pub fn create(
+    (connection, parameters, create_seating_section, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateSeatingSection>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+    requires_venue_write(&user, &stage, connection)?;
+
+    let seating_section = SeatingSection::create(
+        stage.id,
+        create_seating_section.name.clone(),
+        create_seating_section.rank,
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&seating_section))
+}
+
+pub fn update(
+    (connection, parameters, seating_section_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<SeatingSectionEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seating_section = SeatingSection::find(parameters.id, connection)?;
+    requires_venue_write(&user, &seating_section.stage(connection)?, connection)?;
+
+    let seating_section = seating_section.update(seating_section_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(seating_section))
+}
+
+pub fn delete(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seating_section = SeatingSection::find(parameters.id, connection)?;
+    requires_venue_write(&user, &seating_section.stage(connection)?, connection)?;
+
+    seating_section.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub fn seats((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seating_section = SeatingSection::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(seating_section.seats(connection)?))
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeat {
+    pub seat_number: String,
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeatRow {
+    pub row_name: String,
+    pub seats: Vec<CreateSeat>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeatsRequest {
+    pub rows: Vec<CreateSeatRow>,
+}
+
+/// Adds rows of seats to the section. Rows and seats are ranked in the order they are supplied,
+/// after any rows already in the section.
+pub fn create_seats(
+    (connection, parameters, create_seats_request, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateSeatsRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seating_section = SeatingSection::find(parameters.id, connection)?;
+    requires_venue_write(&user, &seating_section.stage(connection)?, connection)?;
+
+    let first_row_rank = seating_section
+        .seats(connection)?
+        .iter()
+        .map(|s| s.row_rank + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut new_seats = Vec::new();
+    for (row_index, row) in create_seats_request.into_inner().rows.into_iter().enumerate() {
+        for (seat_index, seat) in row.seats.into_iter().enumerate() {
+            new_seats.push(Seat::create(
+                seating_section.id,
+                row.row_name.clone(),
+                seat.seat_number,
+                first_row_rank + row_index as i32,
+                seat_index as i32,
+                seat.accessible,
+            ));
+        }
+    }
+    let seats = Seat::create_multiple(new_seats, connection)?;
+
+    Ok(HttpResponse::Created().json(&seats))
+}
+
+pub fn update_seat(
+    (connection, parameters, seat_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<SeatEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seat = Seat::find(parameters.id, connection)?;
+    let seating_section = SeatingSection::find(seat.seating_section_id, connection)?;
+    requires_venue_write(&user, &seating_section.stage(connection)?, connection)?;
+
+    let seat = seat.update(seat_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(seat))
+}
+
+pub fn delete_seat(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let seat = Seat::find(parameters.id, connection)?;
+    let seating_section = SeatingSection::find(seat.seating_section_id, connection)?;
+    requires_venue_write(&user, &seating_section.stage(connection)?, connection)?;
+
+    seat.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn requires_venue_write(user: &AuthUser, stage: &Stage, connection: &PgConnection) -> Result<(), BigNeonError> {
+    let venue = Venue::find(stage.venue_id, connection)?;
+    if !venue.is_private || venue.organization_id.is_none() {
+        user.requires_scope(Scopes::VenueWrite)?;
+    } else {
+        let organization = venue.organization(connection)?.unwrap();
+        user.requires_scope_for_organization(Scopes::VenueWrite, &organization, connection)?;
+    }
+    Ok(())
+}
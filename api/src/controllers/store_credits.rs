@@ -0,0 +1,27 @@
+use actix_web::HttpResponse;
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+
+#[derive(Deserialize, Serialize)]
+pub struct RedeemGiftCardRequest {
+    pub redeem_key: String,
+}
+
+/// The current user's store credit balance and ledger.
+pub fn show((conn, user): (Connection, User)) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    Ok(HttpResponse::Ok().json(StoreCreditTransaction::for_display(user.id(), conn)?))
+}
+
+/// Adds the value of a gift card to the current user's store credit.
+pub fn redeem_gift_card(
+    (conn, json, user): (Connection, Json<RedeemGiftCardRequest>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = conn.get();
+    ProductInstance::redeem_gift_card(&json.redeem_key, user.id(), conn)?;
+    application::created(json!(StoreCreditTransaction::for_display(user.id(), conn)?))
+}
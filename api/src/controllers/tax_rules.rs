@@ -0,0 +1,87 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateTaxRuleRequest {
+    pub name: String,
+    pub rate_percent: f64,
+    #[serde(default)]
+    pub inclusive: bool,
+    #[serde(default)]
+    pub taxes_fees: bool,
+}
+
+pub fn index_for_region(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let region = Region::find(parameters.id, connection)?;
+    Ok(HttpResponse::Ok().json(&TaxRule::find_for_region(region.id, connection)?))
+}
+
+pub fn index_for_venue(
+    (connection, parameters): (Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let venue = Venue::find(parameters.id, connection)?;
+    Ok(HttpResponse::Ok().json(&TaxRule::find_for_venue(venue.id, connection)?))
+}
+
+pub fn create_for_region(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<CreateTaxRuleRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::RegionWrite)?;
+    let connection = connection.get();
+    let json = json.into_inner();
+    let region = Region::find(parameters.id, connection)?;
+
+    let tax_rule = TaxRule::create_for_region(region.id, json.name, json.rate_percent, json.inclusive, json.taxes_fees)
+        .commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub fn create_for_venue(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<CreateTaxRuleRequest>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::RegionWrite)?;
+    let connection = connection.get();
+    let json = json.into_inner();
+    let venue = Venue::find(parameters.id, connection)?;
+
+    let tax_rule = TaxRule::create_for_venue(venue.id, json.name, json.rate_percent, json.inclusive, json.taxes_fees)
+        .commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub fn update(
+    (connection, parameters, attributes, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<TaxRuleEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::RegionWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+
+    let tax_rule = tax_rule.update(attributes.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&tax_rule))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::RegionWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+
+    tax_rule.destroy(Some(user.id()), connection)?;
+    application::no_content()
+}
@@ -0,0 +1,168 @@
+use actix_web::{HttpResponse, Path, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use controllers::orders;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewTicketExchangeRequest {
+    pub ticket_type_id: Uuid,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub token: Option<String>,
+    pub provider: Option<PaymentProviders>,
+}
+
+#[derive(Serialize)]
+pub struct TicketExchangeResponse {
+    pub ticket_exchange: TicketExchange,
+    pub price_difference_in_cents: i64,
+    pub order: DisplayOrder,
+}
+
+/// Exchanges a ticket for a ticket of another ticket type. The ticket holder pays any price difference
+/// with their card, any amount left over after the new ticket is paid for is returned to the original
+/// payment. Organization users can exchange tickets on behalf of the holder as long as no payment is due.
+pub fn create(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewTicketExchangeRequest>,
+        User,
+        State<AppState>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let request = json.into_inner();
+    let conn = connection.get();
+    let ticket = TicketInstance::find(parameters.id, conn)?;
+    let event = ticket.ticket_type(conn)?.event(conn)?;
+    let organization = event.organization(conn)?;
+    let is_owner = ticket.owner(conn).optional()?.map(|owner| owner.id) == Some(user.id());
+    if !is_owner {
+        user.requires_scope_for_organization_event(Scopes::OrderRefund, &organization, &event, conn)?;
+    }
+
+    let user_wallet = Wallet::find(ticket.wallet_id, conn)?;
+    let ticket_exchange = TicketExchange::exchange(ticket.id, request.ticket_type_id, user.id(), conn)?;
+    let price_difference_in_cents = ticket_exchange.price_difference_in_cents();
+    let mut new_order = ticket_exchange.new_order(conn)?;
+
+    // Authorize the difference before any tokens or money are moved
+    let mut pending_charge = None;
+    if price_difference_in_cents > 0 {
+        if !is_owner {
+            return application::unprocessable("Only the ticket holder can pay the difference for this exchange");
+        }
+        let provider = match request.provider {
+            Some(provider) => provider,
+            None => match user.user.default_payment_method(conn).optional()? {
+                Some(payment_method) => payment_method.name,
+                None => {
+                    return application::unprocessable(
+                        "Could not complete this exchange because user has no default payment method",
+                    );
+                }
+            },
+        };
+        let client = state
+            .service_locator
+            .create_payment_processor(provider, &organization)?;
+        // Exchanges have no step for the customer to authenticate the payment
+        let behavior = match client.behavior().without_customer_action() {
+            Some(behavior) => behavior,
+            None => {
+                return application::unprocessable(
+                    "Could not complete this exchange because the payment provider is not supported",
+                );
+            }
+        };
+        let token = match request.token {
+            Some(token) => token,
+            None => match user.user.payment_method(provider, conn).optional()? {
+                Some(payment_method) => payment_method.provider,
+                None => {
+                    return application::unprocessable("Could not complete this exchange because no token provided");
+                }
+            },
+        };
+
+        let auth_result = behavior.auth(
+            &token,
+            price_difference_in_cents,
+            &new_order.currency,
+            "Big Neon Tickets",
+            new_order.purchase_metadata(conn)?,
+        )?;
+        let payment = match new_order.add_credit_card_payment(
+            user.id(),
+            price_difference_in_cents,
+            behavior.payment_provider(),
+            auth_result.id.clone(),
+            PaymentStatus::Authorized,
+            auth_result.to_json()?,
+            conn,
+        ) {
+            Ok(payment) => payment,
+            Err(e) => {
+                client.refund(&auth_result.id)?;
+                return Err(e.into());
+            }
+        };
+        pending_charge = Some((client, behavior, auth_result.id, payment));
+    }
+
+    // Return the exchanged ticket to the organization wallet
+    let organization_wallet = Wallet::find_default_for_organization(organization.id, conn)?;
+    if let Err(e) = orders::transfer_ticket(&ticket, &user_wallet, &organization_wallet, &state.config, conn) {
+        if let Some((client, _, auth_id, _)) = pending_charge {
+            client.refund(&auth_id)?;
+        }
+        return Err(e);
+    }
+
+    let result = orders::refund_payments(
+        &ticket_exchange.original_order(conn)?,
+        &ticket_exchange.refund(conn)?,
+        ticket_exchange.refunded_amount_in_cents,
+        ticket_exchange.credit_applied_in_cents(),
+        false,
+        false,
+        user.id(),
+        &state.service_locator,
+        conn,
+    )
+    .and_then(|_| match pending_charge {
+        Some((ref client, ref behavior, ref auth_id, ref payment)) => {
+            let charge_result = match behavior.complete_authed_charge(auth_id) {
+                Ok(charge_result) => charge_result,
+                Err(e) => {
+                    client.refund(auth_id)?;
+                    return Err(e.into());
+                }
+            };
+            if let Err(e) = payment.mark_complete(charge_result.to_json()?, Some(user.id()), conn) {
+                client.refund(auth_id)?;
+                return Err(e.into());
+            }
+            Ok(())
+        }
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        orders::transfer_ticket(&ticket, &organization_wallet, &user_wallet, &state.config, conn)?;
+        return Err(e);
+    }
+
+    let new_order = Order::find(new_order.id, conn)?;
+    Ok(HttpResponse::Created().json(TicketExchangeResponse {
+        order: new_order.for_display(None, user.id(), conn)?,
+        ticket_exchange,
+        price_difference_in_cents,
+    }))
+}
@@ -3,6 +3,7 @@ use auth::user::User;
 use bigneon_db::dev::times;
 use bigneon_db::models::*;
 use chrono::prelude::*;
+use controllers::event_series;
 use db::Connection;
 use diesel::PgConnection;
 use errors::*;
@@ -23,6 +24,8 @@ pub struct CreateTicketPricingRequest {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub is_box_office_only: Option<bool>,
+    #[serde(default)]
+    pub quantity_limit: Option<i64>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -56,6 +59,10 @@ pub struct CreateTicketTypeRequest {
     pub box_office_sales_enabled: bool,
     #[serde(default = "default_as_true")]
     pub app_sales_enabled: bool,
+    #[serde(default)]
+    pub seating_section_id: Option<Uuid>,
+    #[serde(default)]
+    pub entry_window_policy: Option<EntryWindowPolicy>,
 }
 
 impl Default for CreateTicketTypeRequest {
@@ -78,6 +85,8 @@ impl Default for CreateTicketTypeRequest {
             web_sales_enabled: true,
             box_office_sales_enabled: true,
             app_sales_enabled: true,
+            seating_section_id: None,
+            entry_window_policy: None,
         }
     }
 }
@@ -90,6 +99,8 @@ pub struct UpdateTicketPricingRequest {
     pub end_date: Option<NaiveDateTime>,
     pub price_in_cents: Option<i64>,
     pub is_box_office_only: Option<bool>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub quantity_limit: Option<Option<i64>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -119,6 +130,10 @@ pub struct UpdateTicketTypeRequest {
     #[serde(default)]
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub seating_section_id: Option<Option<Uuid>>,
+    #[serde(default)]
+    pub entry_window_policy: Option<EntryWindowPolicy>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -272,6 +287,9 @@ pub fn update(
             jlog!(Debug, "Update ticket type: Capacity increased", {"ticket_type_id": path.ticket_type_id, "new_capacity": requested_capacity, "old_capacity": valid_ticket_count});
             let starting_tari_id = ticket_type.ticket_count(connection)?;
             let additional_ticket_count = requested_capacity - valid_ticket_count;
+            if !ticket_type.has_stage_capacity_for(additional_ticket_count as i64, connection)? {
+                return application::unprocessable("Requested capacity larger than the stage capacity");
+            }
             let asset = Asset::find_by_ticket_type(ticket_type.id, connection)?;
             let org_wallet = Wallet::find_default_for_organization(event.organization_id, connection)?;
             //Issue more tickets locally
@@ -325,6 +343,8 @@ pub fn update(
         additional_fee_in_cents: data.additional_fee_in_cents,
         app_sales_enabled: data.app_sales_enabled,
         rank: data.rank,
+        seating_section_id: data.seating_section_id,
+        entry_window_policy: data.entry_window_policy,
     };
     let updated_ticket_type = ticket_type.update(update_parameters, Some(user.id()), connection)?;
 
@@ -356,6 +376,7 @@ pub fn update(
                     start_date: current_ticket_pricing.start_date,
                     end_date: current_ticket_pricing.end_date,
                     is_box_office_only: current_ticket_pricing.is_box_office_only,
+                    quantity_limit: current_ticket_pricing.quantity_limit,
                 };
                 let found_index = ticket_pricing
                     .iter()
@@ -377,7 +398,7 @@ pub fn update(
             ) {
                 //Only create a new pricing entry if all of its required data was provided
                 //Add new ticket pricing
-                let _pricing_result = updated_ticket_type.add_ticket_pricing(
+                let ticket_pricing = updated_ticket_type.add_ticket_pricing(
                     name,
                     start_date,
                     end_date,
@@ -387,6 +408,9 @@ pub fn update(
                     Some(user.id()),
                     connection,
                 )?;
+                if let Some(Some(quantity_limit)) = current_ticket_pricing.quantity_limit {
+                    set_quantity_limit(&ticket_pricing, quantity_limit, user.id(), connection)?;
+                }
             } else {
                 //TODO send error when all data was not specified
             }
@@ -403,6 +427,22 @@ pub fn update(
     Ok(HttpResponse::Ok().json(result))
 }
 
+fn set_quantity_limit(
+    ticket_pricing: &TicketPricing,
+    quantity_limit: i64,
+    user_id: Uuid,
+    connection: &PgConnection,
+) -> Result<TicketPricing, BigNeonError> {
+    Ok(ticket_pricing.update(
+        TicketPricingEditableAttributes {
+            quantity_limit: Some(Some(quantity_limit)),
+            ..Default::default()
+        },
+        Some(user_id),
+        connection,
+    )?)
+}
+
 fn nullify_tickets(
     state: State<AppState>,
     organization: Organization,
@@ -523,9 +563,23 @@ fn create_ticket_types(
             Some(user.id()),
             connection,
         )?;
+        let ticket_type =
+            if ticket_type_data.seating_section_id.is_some() || ticket_type_data.entry_window_policy.is_some() {
+                ticket_type.update(
+                    TicketTypeEditableAttributes {
+                        seating_section_id: ticket_type_data.seating_section_id.map(Some),
+                        entry_window_policy: ticket_type_data.entry_window_policy,
+                        ..Default::default()
+                    },
+                    Some(user.id()),
+                    connection,
+                )?
+            } else {
+                ticket_type
+            };
         //Add each ticket pricing entry for newly created ticket type
         for current_pricing_entry in &ticket_type_data.ticket_pricing {
-            let _pricing_result = ticket_type.add_ticket_pricing(
+            let ticket_pricing = ticket_type.add_ticket_pricing(
                 current_pricing_entry.name.clone(),
                 current_pricing_entry.start_date,
                 current_pricing_entry.end_date,
@@ -535,6 +589,9 @@ fn create_ticket_types(
                 Some(user.id()),
                 connection,
             )?;
+            if let Some(quantity_limit) = current_pricing_entry.quantity_limit {
+                set_quantity_limit(&ticket_pricing, quantity_limit, user.id(), connection)?;
+            }
         }
 
         ticket_type.validate_ticket_pricing(connection)?;
@@ -542,6 +599,7 @@ fn create_ticket_types(
     }
 
     create_ticket_type_blockchain_assets(event, &results, state, connection)?;
+    event_series::sync_ticket_types_for_template(event, user.id(), state, connection)?;
 
     Ok(results.iter().map(|r| DisplayCreatedTicket { id: r.id }).collect())
 }
@@ -0,0 +1,58 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::PathParameters;
+
+#[derive(Deserialize)]
+pub struct JoinWaitlistRequest {
+    pub quantity: Option<u32>,
+}
+
+pub fn show(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    match WaitlistEntry::find_active_for_user(ticket_type.id, user.id(), connection)? {
+        Some(waitlist_entry) => Ok(HttpResponse::Ok().json(&waitlist_entry)),
+        None => application::not_found(),
+    }
+}
+
+pub fn create(
+    (connection, parameters, join_waitlist_request, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<JoinWaitlistRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    let waitlist_entry = WaitlistEntry::create(
+        ticket_type.id,
+        user.id(),
+        join_waitlist_request.quantity.unwrap_or(1) as i32,
+    )
+    .commit(connection)?;
+
+    Ok(HttpResponse::Created().json(&waitlist_entry))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(parameters.id, connection)?;
+    let waitlist_entry = match WaitlistEntry::find_active_for_user(ticket_type.id, user.id(), connection)? {
+        Some(waitlist_entry) => waitlist_entry,
+        None => return application::not_found(),
+    };
+    waitlist_entry.cancel(Some(user.id()), connection)?;
+
+    application::no_content()
+}
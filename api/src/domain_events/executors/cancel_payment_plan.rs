@@ -0,0 +1,86 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use controllers::orders;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use utils::ServiceLocator;
+
+pub struct CancelPaymentPlanExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for CancelPaymentPlanExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Cancel payment plan action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl CancelPaymentPlanExecutor {
+    pub fn new(config: Config) -> CancelPaymentPlanExecutor {
+        CancelPaymentPlanExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No payment plan id supplied in the action".to_string(),
+        ))?;
+        let payment_plan = PaymentPlan::find(id, conn)?;
+        // The failed installment may have been paid during the grace period
+        if !payment_plan.is_overdue(conn)? {
+            return Ok(());
+        }
+
+        let (order, refund, refund_due, tickets) = payment_plan.cancel(conn)?;
+
+        // Return the tickets to the organization wallet, giving them back if the refund fails
+        let mut transferred_tickets = Vec::new();
+        let mut result = Ok(());
+        for ticket in &tickets {
+            let organization_wallet = Wallet::find_default_for_organization(ticket.organization(conn)?.id, conn)?;
+            let user_wallet = Wallet::find(ticket.wallet_id, conn)?;
+            if let Err(e) = orders::transfer_ticket(ticket, &user_wallet, &organization_wallet, &self.config, conn) {
+                result = Err(e);
+                break;
+            }
+            transferred_tickets.push((ticket, user_wallet, organization_wallet));
+        }
+        if result.is_ok() && refund_due > 0 {
+            let service_locator = ServiceLocator::new(&self.config)?;
+            result = orders::refund_payments(
+                &order,
+                &refund,
+                refund_due,
+                0,
+                false,
+                false,
+                payment_plan.user_id,
+                &service_locator,
+                conn,
+            )
+            .map(|_| ());
+        }
+        if let Err(e) = result {
+            for (ticket, user_wallet, organization_wallet) in transferred_tickets {
+                orders::transfer_ticket(ticket, &organization_wallet, &user_wallet, &self.config, conn)?;
+            }
+            return Err(e);
+        }
+
+        let user = User::find(payment_plan.user_id, conn)?;
+        mailers::payment_plans::cancelled(&user, &payment_plan, refund_due, &self.config, conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use bigneon_db::prelude::*;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::{Error, Info};
+use utils::ServiceLocator;
+
+pub struct ChargePaymentPlanInstallmentExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ChargePaymentPlanInstallmentExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Charge payment plan installment action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ChargePaymentPlanInstallmentExecutor {
+    pub fn new(config: Config) -> ChargePaymentPlanInstallmentExecutor {
+        ChargePaymentPlanInstallmentExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No payment plan installment id supplied in the action".to_string(),
+        ))?;
+        let installment = PaymentPlanInstallment::find(id, conn)?;
+        let payment_plan = installment.payment_plan(conn)?;
+        // A retry may already have paid the installment or the plan may have been cancelled
+        if installment.status == PaymentPlanInstallmentStatus::Paid || payment_plan.status != PaymentPlanStatus::Active
+        {
+            return Ok(());
+        }
+
+        let order = payment_plan.order(conn)?;
+        let payment_method = PaymentMethod::find(payment_plan.payment_method_id, conn)?;
+        let organization = match order.organizations(conn)?.pop() {
+            Some(organization) => organization,
+            None => {
+                return Err(ApplicationError::new("Payment plan order has no organization".to_string()).into());
+            }
+        };
+        let client = ServiceLocator::new(&self.config)?.create_payment_processor(payment_method.name, &organization)?;
+        let behavior = match client.behavior().without_customer_action() {
+            Some(behavior) => behavior,
+            None => {
+                installment.mark_failed("Payment provider does not support scheduled charges", conn)?;
+                return Ok(());
+            }
+        };
+
+        let auth_result = match behavior.auth(
+            &payment_method.provider,
+            installment.amount_in_cents,
+            &order.currency,
+            "Big Neon Tickets",
+            order.purchase_metadata(conn)?,
+        ) {
+            Ok(auth_result) => auth_result,
+            Err(e) => {
+                jlog!(Info, "Payment plan installment declined", {"installment_id": installment.id, "error": e.to_string()});
+                installment.mark_failed(&e.to_string(), conn)?;
+                return Ok(());
+            }
+        };
+        let charge_result = match behavior.complete_authed_charge(&auth_result.id) {
+            Ok(charge_result) => charge_result,
+            Err(e) => {
+                client.refund(&auth_result.id)?;
+                installment.mark_failed(&e.to_string(), conn)?;
+                return Ok(());
+            }
+        };
+
+        if let Err(e) = installment.mark_paid(
+            behavior.payment_provider(),
+            auth_result.id.clone(),
+            charge_result.to_json()?,
+            conn,
+        ) {
+            client.refund(&auth_result.id)?;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
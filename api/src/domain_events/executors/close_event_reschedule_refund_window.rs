@@ -0,0 +1,38 @@
+use bigneon_db::prelude::*;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct CloseEventRescheduleRefundWindowExecutor {}
+
+impl DomainActionExecutor for CloseEventRescheduleRefundWindowExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Close event reschedule refund window action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl CloseEventRescheduleRefundWindowExecutor {
+    pub fn new() -> CloseEventRescheduleRefundWindowExecutor {
+        CloseEventRescheduleRefundWindowExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No event reschedule id supplied in the action".to_string(),
+        ))?;
+
+        EventReschedule::find(id, conn)?.close_refund_window(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use bigneon_db::prelude::*;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct DrawPresaleLotteryExecutor {}
+
+impl DomainActionExecutor for DrawPresaleLotteryExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Draw presale lottery action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl DrawPresaleLotteryExecutor {
+    pub fn new() -> DrawPresaleLotteryExecutor {
+        DrawPresaleLotteryExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No presale lottery id supplied in the action".to_string(),
+        ))?;
+        let presale_lottery = PresaleLottery::find(id, conn)?;
+
+        // The lottery may have been drawn manually before the scheduled draw
+        if presale_lottery.status == PresaleLotteryStatus::Open {
+            presale_lottery.draw(conn)?;
+        }
+
+        Ok(())
+    }
+}
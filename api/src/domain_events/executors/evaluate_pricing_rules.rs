@@ -0,0 +1,36 @@
+use bigneon_db::prelude::*;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct EvaluatePricingRulesExecutor {}
+
+impl DomainActionExecutor for EvaluatePricingRulesExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Evaluating pricing rules action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl EvaluatePricingRulesExecutor {
+    pub fn new() -> EvaluatePricingRulesExecutor {
+        EvaluatePricingRulesExecutor {}
+    }
+
+    pub fn perform_job(&self, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        PricingRule::evaluate_all(conn)?;
+
+        PricingRule::create_next_evaluate_pricing_rules_domain_action(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use bigneon_db::prelude::*;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ExpireWaitlistOfferExecutor {}
+
+impl DomainActionExecutor for ExpireWaitlistOfferExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Expire waitlist offer action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ExpireWaitlistOfferExecutor {
+    pub fn new() -> ExpireWaitlistOfferExecutor {
+        ExpireWaitlistOfferExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No waitlist entry id supplied in the action".to_string(),
+        ))?;
+
+        WaitlistEntry::find(id, conn)?.expire_offer(conn)?;
+
+        Ok(())
+    }
+}
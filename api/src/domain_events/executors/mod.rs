@@ -1,23 +1,49 @@
 pub use self::broadcast_push_notification::*;
+pub use self::cancel_payment_plan::*;
+pub use self::charge_payment_plan_installment::*;
+pub use self::close_event_reschedule_refund_window::*;
+pub use self::draw_presale_lottery::*;
+pub use self::evaluate_pricing_rules::*;
+pub use self::expire_waitlist_offer::*;
+pub use self::process_event_reschedule::*;
 pub use self::process_payment_ipn::*;
+pub use self::process_payment_provider_event::*;
 pub use self::process_settlement_report::*;
 pub use self::process_transfer_drip_event::*;
+pub use self::process_waitlist::*;
+pub use self::refund_expired_order_payments::*;
 pub use self::regenerate_drip_actions::*;
+pub use self::release_expired_holds::*;
 pub use self::retarget_abandoned_orders::*;
 pub use self::send_automatic_report_emails::*;
 pub use self::send_communication::*;
 pub use self::send_order_complete::*;
+pub use self::send_payment_plan_dunning_communication::*;
+pub use self::send_presale_lottery_results::*;
 pub use self::submit_sitemap_to_search_engines::*;
 pub use self::update_genres::*;
 
 mod broadcast_push_notification;
+mod cancel_payment_plan;
+mod charge_payment_plan_installment;
+mod close_event_reschedule_refund_window;
+mod draw_presale_lottery;
+mod evaluate_pricing_rules;
+mod expire_waitlist_offer;
+mod process_event_reschedule;
 mod process_payment_ipn;
+mod process_payment_provider_event;
 mod process_settlement_report;
 mod process_transfer_drip_event;
+mod process_waitlist;
+mod refund_expired_order_payments;
 mod regenerate_drip_actions;
+mod release_expired_holds;
 mod retarget_abandoned_orders;
 mod send_automatic_report_emails;
 mod send_communication;
 mod send_order_complete;
+mod send_payment_plan_dunning_communication;
+mod send_presale_lottery_results;
 mod submit_sitemap_to_search_engines;
 mod update_genres;
@@ -0,0 +1,51 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use std::collections::HashSet;
+
+pub struct ProcessEventRescheduleExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessEventRescheduleExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process event reschedule action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessEventRescheduleExecutor {
+    pub fn new(config: Config) -> ProcessEventRescheduleExecutor {
+        ProcessEventRescheduleExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No event reschedule id supplied in the action".to_string(),
+        ))?;
+        let event_reschedule = EventReschedule::find(id, conn)?;
+        let event = event_reschedule.event(conn)?;
+
+        // Holders are listed once per order so only notify each of them once
+        let mut notified_user_ids = HashSet::new();
+        for (user, _, _) in Event::find_all_ticket_holders(event.id, conn, TicketHoldersCountType::WithEmailAddress)? {
+            if notified_user_ids.insert(user.id) {
+                mailers::events::rescheduled(&user, &event, &event_reschedule, &self.config, conn)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use bigneon_db::prelude::*;
+use config::Config;
+use controllers::orders;
+use db::Connection;
+use diesel::PgConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::{Debug, Error, Warn};
+use serde_json;
+use stripe::WebhookEvent;
+
+pub struct ProcessPaymentProviderEventExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessPaymentProviderEventExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process payment provider event action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessPaymentProviderEventExecutor {
+    pub fn new(config: Config) -> ProcessPaymentProviderEventExecutor {
+        ProcessPaymentProviderEventExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No payment provider event id supplied in the action".to_string(),
+        ))?;
+        let provider_event = PaymentProviderEvent::find(id, conn)?;
+        if provider_event.processed_at.is_some() {
+            return Ok(());
+        }
+
+        match provider_event.provider {
+            PaymentProviders::Stripe => self.process_stripe_event(&provider_event, conn)?,
+            _ => {
+                return Err(ApplicationError::new(format!(
+                    "Events from {} are not supported",
+                    provider_event.provider
+                ))
+                .into());
+            }
+        }
+
+        provider_event.mark_processed(conn)?;
+        Ok(())
+    }
+
+    fn process_stripe_event(
+        &self,
+        provider_event: &PaymentProviderEvent,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        let event: WebhookEvent = serde_json::from_value(provider_event.payload.clone())?;
+        match event.event_type.as_str() {
+            "charge.dispute.created" => {
+                let dispute = event.dispute()?;
+                if let Some(payment) =
+                    self.find_stripe_payment(&event, &dispute.charge, &dispute.payment_intent, conn)?
+                {
+                    // Dispute events may be delivered out of order
+                    if payment.status != PaymentStatus::Completed || payment.dispute_status.is_some() {
+                        jlog!(Warn, "Dispute received for payment that is not completed or is already disputed", {"event_id": &event.id, "payment_id": payment.id, "status": payment.status, "dispute_status": payment.dispute_status});
+                        return Ok(());
+                    }
+                    let adjustment = payment.mark_disputed(dispute.amount, json!(dispute), conn)?;
+                    self.log_unapplied_adjustment(&event, &payment, adjustment);
+                }
+            }
+            "charge.dispute.closed" => {
+                let dispute = event.dispute()?;
+                if let Some(payment) =
+                    self.find_stripe_payment(&event, &dispute.charge, &dispute.payment_intent, conn)?
+                {
+                    if payment.status != PaymentStatus::Completed
+                        || (payment.dispute_status.is_some()
+                            && payment.dispute_status != Some(PaymentDisputeStatus::Open))
+                    {
+                        jlog!(Warn, "Dispute closed for payment that is not disputed", {"event_id": &event.id, "payment_id": payment.id, "status": payment.status, "dispute_status": payment.dispute_status});
+                        return Ok(());
+                    }
+                    let adjustment = payment.close_dispute(dispute.is_won(), dispute.amount, json!(dispute), conn)?;
+                    // Won disputes of payments never marked as disputed need no adjustment
+                    if payment.dispute_status == Some(PaymentDisputeStatus::Open) || !dispute.is_won() {
+                        self.log_unapplied_adjustment(&event, &payment, adjustment);
+                    }
+                }
+            }
+            "charge.refunded" => {
+                let charge = event.charge()?;
+                if let Some(payment) = self.find_stripe_payment(&event, &charge.id, &charge.payment_intent, conn)? {
+                    if let Some((_, tickets)) =
+                        payment.reconcile_provider_refund(charge.amount_refunded, event.data.object.clone(), conn)?
+                    {
+                        // Return the refunded tickets to the organization wallet
+                        for ticket in tickets {
+                            let organization_wallet =
+                                Wallet::find_default_for_organization(ticket.organization(conn)?.id, conn)?;
+                            let user_wallet = Wallet::find(ticket.wallet_id, conn)?;
+                            orders::transfer_ticket(&ticket, &user_wallet, &organization_wallet, &self.config, conn)?;
+                        }
+                    }
+                }
+            }
+            _ => {
+                jlog!(Debug, "Ignoring Stripe event", {"event_id": &event.id, "type": &event.event_type});
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_stripe_payment(
+        &self,
+        event: &WebhookEvent,
+        charge_id: &str,
+        payment_intent_id: &Option<String>,
+        conn: &PgConnection,
+    ) -> Result<Option<Payment>, BigNeonError> {
+        let mut payment = Payment::find_by_provider_reference(PaymentProviders::Stripe, charge_id, conn)?;
+        // Payments taken with a payment intent are recorded under the intent instead of its charge
+        if payment.is_none() {
+            if let Some(payment_intent_id) = payment_intent_id {
+                payment = Payment::find_by_provider_reference(PaymentProviders::Stripe, payment_intent_id, conn)?;
+            }
+        }
+        if payment.is_none() {
+            jlog!(Warn, "No payment found for Stripe charge", {"event_id": &event.id, "charge_id": charge_id});
+        }
+        Ok(payment)
+    }
+
+    fn log_unapplied_adjustment(
+        &self,
+        event: &WebhookEvent,
+        payment: &Payment,
+        adjustment: Option<SettlementAdjustment>,
+    ) {
+        if adjustment.is_none() {
+            jlog!(Warn, "No open settlement to apply dispute adjustment to, it must be applied manually", {"event_id": &event.id, "payment_id": payment.id, "order_id": payment.order_id});
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ProcessWaitlistExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessWaitlistExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process waitlist action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessWaitlistExecutor {
+    pub fn new(config: Config) -> ProcessWaitlistExecutor {
+        ProcessWaitlistExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No ticket type id supplied in the action".to_string(),
+        ))?;
+
+        for entry in WaitlistEntry::process_waitlist(id, conn)? {
+            mailers::waitlist::offer(&entry, &self.config, conn)?;
+        }
+
+        Ok(())
+    }
+}
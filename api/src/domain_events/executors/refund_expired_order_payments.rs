@@ -0,0 +1,80 @@
+use bigneon_db::prelude::*;
+use config::Config;
+use controllers::orders;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use utils::ServiceLocator;
+
+pub struct RefundExpiredOrderPaymentsExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for RefundExpiredOrderPaymentsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Refund expired order payments action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl RefundExpiredOrderPaymentsExecutor {
+    pub fn new(config: Config) -> RefundExpiredOrderPaymentsExecutor {
+        RefundExpiredOrderPaymentsExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No order id supplied in the action".to_string()))?;
+        let mut order = Order::find(id, conn)?;
+        // The rest of the order was paid or it has already been cancelled
+        if order.status != OrderStatus::PendingPayment {
+            return Ok(());
+        }
+        // The cart was refreshed so wait for its new expiry
+        if !order.is_expired() {
+            order.schedule_expired_payments_refund(conn)?;
+            return Ok(());
+        }
+
+        let refund_due: i64 = order
+            .refundable_payments(conn)?
+            .iter()
+            .map(|(_, balance)| balance)
+            .sum();
+        if refund_due > 0 {
+            let refund = Refund::create(
+                order.id,
+                order.user_id,
+                Some("Order expired before it was paid in full".to_string()),
+                false,
+            )
+            .commit(conn)?;
+            let service_locator = ServiceLocator::new(&self.config)?;
+            orders::refund_payments(
+                &order,
+                &refund,
+                refund_due,
+                0,
+                false,
+                false,
+                order.user_id,
+                &service_locator,
+                conn,
+            )?;
+        }
+        order.cancel_expired(conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ReleaseExpiredHoldsExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ReleaseExpiredHoldsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Release expired holds action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ReleaseExpiredHoldsExecutor {
+    pub fn new(config: Config) -> ReleaseExpiredHoldsExecutor {
+        ReleaseExpiredHoldsExecutor { config }
+    }
+
+    pub fn perform_job(&self, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        for (hold, released_quantity) in Hold::release_expired_holds(conn)? {
+            if released_quantity > 0 {
+                mailers::holds::released(&hold, released_quantity, &self.config, conn)?;
+            }
+        }
+
+        Hold::create_next_release_expired_holds_domain_action(conn)?;
+
+        Ok(())
+    }
+}
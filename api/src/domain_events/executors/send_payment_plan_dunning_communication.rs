@@ -0,0 +1,51 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendPaymentPlanDunningCommunicationExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendPaymentPlanDunningCommunicationExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send payment plan dunning communication action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendPaymentPlanDunningCommunicationExecutor {
+    pub fn new(config: Config) -> SendPaymentPlanDunningCommunicationExecutor {
+        SendPaymentPlanDunningCommunicationExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No payment plan installment id supplied in the action".to_string(),
+        ))?;
+        let installment = PaymentPlanInstallment::find(id, conn)?;
+        let payment_plan = installment.payment_plan(conn)?;
+        // Nothing to chase if the installment was paid before the email went out
+        if installment.status != PaymentPlanInstallmentStatus::Failed
+            || payment_plan.status != PaymentPlanStatus::Active
+        {
+            return Ok(());
+        }
+
+        let user = User::find(payment_plan.user_id, conn)?;
+        mailers::payment_plans::installment_failed(&user, &payment_plan, &installment, &self.config, conn)?;
+
+        Ok(())
+    }
+}
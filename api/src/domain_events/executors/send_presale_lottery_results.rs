@@ -0,0 +1,58 @@
+use bigneon_db::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendPresaleLotteryResultsExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendPresaleLotteryResultsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send presale lottery results action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendPresaleLotteryResultsExecutor {
+    pub fn new(config: Config) -> SendPresaleLotteryResultsExecutor {
+        SendPresaleLotteryResultsExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let id = action.main_table_id.clone().ok_or(ApplicationError::new(
+            "No presale lottery id supplied in the action".to_string(),
+        ))?;
+        let presale_lottery = PresaleLottery::find(id, conn)?;
+        let event = presale_lottery.event(conn)?;
+
+        for entry in presale_lottery.entries(conn)? {
+            let user = entry.user(conn)?;
+            match entry.status {
+                PresaleLotteryEntryStatus::Won => {
+                    let code = entry.code(conn)?.ok_or(ApplicationError::new(
+                        "Presale lottery winner has not been issued a code".to_string(),
+                    ))?;
+                    mailers::presale_lotteries::won(&user, &event, &presale_lottery, &code, &self.config, conn)?;
+                }
+                PresaleLotteryEntryStatus::Lost => {
+                    mailers::presale_lotteries::lost(&user, &event, &presale_lottery, &self.config, conn)?;
+                }
+                PresaleLotteryEntryStatus::Registered => (),
+            }
+        }
+
+        Ok(())
+    }
+}
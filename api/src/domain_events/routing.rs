@@ -48,15 +48,30 @@ impl DomainActionRouter {
             match action_type {
                 Communication => Box::new(SendCommunicationExecutor::new(conf)),
                 BroadcastPushNotification => Box::new(BroadcastPushNotificationExecutor::new(&conf)),
+                CancelPaymentPlan => Box::new(CancelPaymentPlanExecutor::new(conf)),
+                ChargePaymentPlanInstallment => Box::new(ChargePaymentPlanInstallmentExecutor::new(conf)),
+                CloseEventRescheduleRefundWindow => Box::new(CloseEventRescheduleRefundWindowExecutor::new()),
+                DrawPresaleLottery => Box::new(DrawPresaleLotteryExecutor::new()),
+                EvaluatePricingRules => Box::new(EvaluatePricingRulesExecutor::new()),
+                ExpireWaitlistOffer => Box::new(ExpireWaitlistOfferExecutor::new()),
 
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
+                ProcessEventReschedule => Box::new(ProcessEventRescheduleExecutor::new(conf)),
+                ProcessPaymentProviderEvent => Box::new(ProcessPaymentProviderEventExecutor::new(conf)),
+                RefundExpiredOrderPayments => Box::new(RefundExpiredOrderPaymentsExecutor::new(conf)),
                 RegenerateDripActions => Box::new(RegenerateDripActionsExecutor::new(conf)),
                 SendPurchaseCompletedCommunication => Box::new(SendOrderCompleteExecutor::new(conf)),
                 UpdateGenres => Box::new(UpdateGenresExecutor::new()),
                 ProcessSettlementReport => Box::new(ProcessSettlementReportExecutor::new(conf)),
                 ProcessTransferDrip => Box::new(ProcessTransferDripEventExecutor::new(conf)),
+                ProcessWaitlist => Box::new(ProcessWaitlistExecutor::new(conf)),
+                ReleaseExpiredHolds => Box::new(ReleaseExpiredHoldsExecutor::new(conf)),
                 RetargetAbandonedOrders => Box::new(RetargetAbandonedOrdersExecutor::new()),
                 SendAutomaticReportEmails => Box::new(SendAutomaticReportEmailsExecutor::new(conf)),
+                SendPaymentPlanDunningCommunication => {
+                    Box::new(SendPaymentPlanDunningCommunicationExecutor::new(conf))
+                }
+                SendPresaleLotteryResults => Box::new(SendPresaleLotteryResultsExecutor::new(conf)),
                 SubmitSitemapToSearchEngines => Box::new(SubmitSitemapToSearchEnginesExecutor::new(
                     conf.api_base_url.clone(),
                     conf.block_external_comms,
@@ -72,18 +87,54 @@ impl DomainActionRouter {
         self.add_executor(BroadcastPushNotification, find_executor(BroadcastPushNotification))
             .expect("Configuration error");
 
+        self.add_executor(CancelPaymentPlan, find_executor(CancelPaymentPlan))
+            .expect("Configuration error");
+
+        self.add_executor(ChargePaymentPlanInstallment, find_executor(ChargePaymentPlanInstallment))
+            .expect("Configuration error");
+
+        self.add_executor(
+            CloseEventRescheduleRefundWindow,
+            find_executor(CloseEventRescheduleRefundWindow),
+        )
+        .expect("Configuration error");
+
+        self.add_executor(DrawPresaleLottery, find_executor(DrawPresaleLottery))
+            .expect("Configuration error");
+
+        self.add_executor(EvaluatePricingRules, find_executor(EvaluatePricingRules))
+            .expect("Configuration error");
+
+        self.add_executor(ExpireWaitlistOffer, find_executor(ExpireWaitlistOffer))
+            .expect("Configuration error");
+
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
+        self.add_executor(ProcessEventReschedule, find_executor(ProcessEventReschedule))
+            .expect("Configuration error");
+
+        self.add_executor(ProcessPaymentProviderEvent, find_executor(ProcessPaymentProviderEvent))
+            .expect("Configuration error");
+
         self.add_executor(ProcessSettlementReport, find_executor(ProcessSettlementReport))
             .expect("Configuration error");
 
         self.add_executor(ProcessTransferDrip, find_executor(ProcessTransferDrip))
             .expect("Configuration error");
 
+        self.add_executor(ProcessWaitlist, find_executor(ProcessWaitlist))
+            .expect("Configuration error");
+
+        self.add_executor(RefundExpiredOrderPayments, find_executor(RefundExpiredOrderPayments))
+            .expect("Configuration error");
+
         self.add_executor(RegenerateDripActions, find_executor(RegenerateDripActions))
             .expect("Configuration error");
 
+        self.add_executor(ReleaseExpiredHolds, find_executor(ReleaseExpiredHolds))
+            .expect("Configuration error");
+
         self.add_executor(RetargetAbandonedOrders, find_executor(RetargetAbandonedOrders))
             .expect("Configuration error");
 
@@ -93,6 +144,15 @@ impl DomainActionRouter {
         self.add_executor(SendAutomaticReportEmails, find_executor(SendAutomaticReportEmails))
             .expect("Configuration error");
 
+        self.add_executor(
+            SendPaymentPlanDunningCommunication,
+            find_executor(SendPaymentPlanDunningCommunication),
+        )
+        .expect("Configuration error");
+
+        self.add_executor(SendPresaleLotteryResults, find_executor(SendPresaleLotteryResults))
+            .expect("Configuration error");
+
         self.add_executor(
             SendPurchaseCompletedCommunication,
             find_executor(SendPurchaseCompletedCommunication),
@@ -28,6 +28,9 @@ pub struct AdminDisplayTicketType {
     pub app_sales_enabled: bool,
     pub web_sales_enabled: bool,
     pub box_office_sales_enabled: bool,
+    pub seating_section_id: Option<Uuid>,
+    pub entry_window_policy: EntryWindowPolicy,
+    pub capacity_pool_id: Option<Uuid>,
 }
 
 impl AdminDisplayTicketType {
@@ -80,6 +83,9 @@ impl AdminDisplayTicketType {
             app_sales_enabled: ticket_type.app_sales_enabled,
             web_sales_enabled: ticket_type.web_sales_enabled,
             box_office_sales_enabled: ticket_type.box_office_sales_enabled,
+            seating_section_id: ticket_type.seating_section_id,
+            entry_window_policy: ticket_type.entry_window_policy,
+            capacity_pool_id: ticket_type.capacity_pool_id,
         };
         Ok(result)
     }
@@ -15,6 +15,8 @@ pub struct DisplayTicketPricing {
     pub fee_in_cents: i64,
     pub discount_in_cents: i64,
     pub associated_with_active_orders: bool,
+    pub quantity_limit: Option<i64>,
+    pub remaining_quantity: Option<i64>,
 }
 
 impl DisplayTicketPricing {
@@ -78,6 +80,7 @@ impl DisplayTicketPricing {
         }
 
         let associated_with_active_orders = ticket_pricing.associated_with_active_orders(conn)?;
+        let remaining_quantity = ticket_pricing.remaining_quantity(conn)?;
         Ok(DisplayTicketPricing {
             id: ticket_pricing.id,
             name: ticket_pricing.name.clone(),
@@ -88,6 +91,8 @@ impl DisplayTicketPricing {
             fee_in_cents,
             discount_in_cents,
             associated_with_active_orders,
+            quantity_limit: ticket_pricing.quantity_limit,
+            remaining_quantity,
         })
     }
 }
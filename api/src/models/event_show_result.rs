@@ -20,6 +20,7 @@ pub struct EventShowResult {
     pub event_end: Option<NaiveDateTime>,
     pub cancelled_at: Option<NaiveDateTime>,
     pub fee_in_cents: i64,
+    pub currency: String,
     pub status: EventStatus,
     pub publish_date: Option<NaiveDateTime>,
     pub promo_image_url: Option<String>,
@@ -19,6 +19,7 @@ pub struct UserDisplayTicketType {
     pub redemption_code: Option<String>,
     pub event_id: Uuid,
     pub rank: i32,
+    pub seating_section_id: Option<Uuid>,
 }
 
 impl UserDisplayTicketType {
@@ -60,6 +61,7 @@ impl UserDisplayTicketType {
             increment: ticket_type.increment,
             limit_per_person: ticket_type.limit_per_person as u32,
             rank: ticket_type.rank,
+            seating_section_id: ticket_type.seating_section_id,
         };
 
         if let Some(ref redemption_code) = redemption_code {
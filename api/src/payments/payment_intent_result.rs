@@ -0,0 +1,23 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaymentIntentStatus {
+    /// The customer has to authenticate the payment, e.g. with 3D Secure, before it can be confirmed
+    RequiresAction,
+    Authorized,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug)]
+pub struct PaymentIntentResult {
+    pub id: String,
+    pub status: PaymentIntentStatus,
+    pub client_secret: Option<String>,
+    pub next_action: Option<serde_json::Value>,
+    pub raw: String,
+}
+
+impl PaymentIntentResult {
+    pub fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::from_str(&self.raw)
+    }
+}
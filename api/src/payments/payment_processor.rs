@@ -5,9 +5,24 @@ use uuid::Uuid;
 
 pub enum PaymentProcessorBehavior {
     AuthThenComplete(Box<dyn AuthThenCompletePaymentBehavior>),
+    ConfirmThenComplete(Box<dyn ConfirmThenCompletePaymentBehavior>),
     RedirectToPaymentPage(Box<dyn RedirectToPaymentPageBehavior>),
 }
 
+impl PaymentProcessorBehavior {
+    /// Behavior for charging a card when the customer cannot take any further action, e.g. for
+    /// scheduled charges. Returns `None` if the processor can't charge without the customer.
+    pub fn without_customer_action(self) -> Option<Box<dyn AuthThenCompletePaymentBehavior>> {
+        match self {
+            PaymentProcessorBehavior::AuthThenComplete(behavior) => Some(behavior),
+            PaymentProcessorBehavior::ConfirmThenComplete(behavior) => {
+                Some(Box::new(OffSessionPaymentBehavior { behavior }))
+            }
+            PaymentProcessorBehavior::RedirectToPaymentPage(_) => None,
+        }
+    }
+}
+
 pub trait AuthThenCompletePaymentBehavior {
     fn payment_provider(&self) -> PaymentProviders;
 
@@ -36,11 +51,110 @@ pub trait AuthThenCompletePaymentBehavior {
     fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError>;
 }
 
+/// Payments the card issuer may require the customer to authenticate (e.g. with 3D Secure) before
+/// they are authorized.
+pub trait ConfirmThenCompletePaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders;
+
+    fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError>;
+
+    fn update_repeat_token(
+        &self,
+        repeat_token: &str,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError>;
+
+    /// Authorizes the amount unless the customer has to authenticate the payment first, in which
+    /// case the result holds the action the frontend must take before calling `confirm`. Payments
+    /// made `off_session` are declined rather than requiring action.
+    fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        off_session: bool,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntentResult, PaymentProcessorError>;
+
+    fn confirm(&self, auth_token: &str) -> Result<PaymentIntentResult, PaymentProcessorError>;
+
+    /// Abandons a payment that has not been authorized yet
+    fn cancel(&self, auth_token: &str) -> Result<PaymentIntentResult, PaymentProcessorError>;
+
+    fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError>;
+}
+
+struct OffSessionPaymentBehavior {
+    behavior: Box<dyn ConfirmThenCompletePaymentBehavior>,
+}
+
+impl AuthThenCompletePaymentBehavior for OffSessionPaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        self.behavior.payment_provider()
+    }
+
+    fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        self.behavior.create_token_for_repeat_charges(token, description)
+    }
+
+    fn update_repeat_token(
+        &self,
+        repeat_token: &str,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        self.behavior.update_repeat_token(repeat_token, token, description)
+    }
+
+    fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let result = self
+            .behavior
+            .auth(token, amount, currency, description, true, metadata)?;
+        if result.status == PaymentIntentStatus::Authorized {
+            return Ok(ChargeAuthResult {
+                id: result.id,
+                raw: result.raw,
+            });
+        }
+
+        if result.status == PaymentIntentStatus::RequiresAction {
+            self.behavior.cancel(&result.id)?;
+        }
+        Err(PaymentProcessorError {
+            description: format!("Payment {} could not be authorized without the customer", result.id),
+            cause: None,
+            validation_response: Some("Card requires authentication".to_string()),
+        })
+    }
+
+    fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        self.behavior.complete_authed_charge(auth_token)
+    }
+}
+
 pub trait RedirectToPaymentPageBehavior {
     fn payment_provider(&self) -> PaymentProviders;
     fn create_payment_request(
         &self,
         total: f64,
+        currency: &str,
         email: String,
         order_id: Uuid,
         ipn_url: Option<String>,
@@ -1,5 +1,7 @@
 use bigneon_db::models::PaymentProviders;
 use payments::*;
+use stripe::PaymentIntent;
+use stripe::PaymentIntentStatus as StripePaymentIntentStatus;
 use stripe::StripeClient;
 use stripe::StripeError;
 
@@ -19,6 +21,8 @@ impl From<StripeError> for PaymentProcessorError {
                 "invalid_expiry_year" => Some("Invalid card expiry year"),
                 "invalid_number" => Some("Invalid card number"),
                 "balance_insufficient" => Some("Balance insufficient"),
+                "authentication_required" => Some("Card requires authentication"),
+                "payment_intent_authentication_failure" => Some("Card authentication failed"),
                 _ => None,
             },
             None => None,
@@ -33,14 +37,41 @@ impl From<StripeError> for PaymentProcessorError {
     }
 }
 
+impl From<PaymentIntent> for PaymentIntentResult {
+    fn from(p: PaymentIntent) -> PaymentIntentResult {
+        let status = match p.status {
+            StripePaymentIntentStatus::RequiresAction | StripePaymentIntentStatus::RequiresConfirmation => {
+                PaymentIntentStatus::RequiresAction
+            }
+            StripePaymentIntentStatus::RequiresCapture => PaymentIntentStatus::Authorized,
+            StripePaymentIntentStatus::Succeeded => PaymentIntentStatus::Completed,
+            // Processing is only used for payment methods other than cards
+            StripePaymentIntentStatus::RequiresPaymentMethod
+            | StripePaymentIntentStatus::Processing
+            | StripePaymentIntentStatus::Canceled => PaymentIntentStatus::Failed,
+        };
+        PaymentIntentResult {
+            id: p.id,
+            status,
+            client_secret: p.client_secret,
+            next_action: p.next_action,
+            raw: p.raw_data,
+        }
+    }
+}
+
 pub struct StripePaymentProcessor {
     client: StripeClient,
+    payment_intents: bool,
 }
 
 impl StripePaymentProcessor {
-    pub fn new(stripe_secret_key: String) -> StripePaymentProcessor {
+    /// Card payments are taken with payment intents when `payment_intents` is set, otherwise with
+    /// the charges API which does not support 3D Secure.
+    pub fn new(stripe_secret_key: String, base_url: String, payment_intents: bool) -> StripePaymentProcessor {
         StripePaymentProcessor {
-            client: StripeClient::new(stripe_secret_key),
+            client: StripeClient::new(stripe_secret_key, base_url),
+            payment_intents,
         }
     }
 }
@@ -49,11 +80,21 @@ pub struct StripePaymentBehavior {
     client: StripeClient,
 }
 
+pub struct StripePaymentIntentBehavior {
+    client: StripeClient,
+}
+
 impl PaymentProcessor for StripePaymentProcessor {
     fn behavior(&self) -> PaymentProcessorBehavior {
-        PaymentProcessorBehavior::AuthThenComplete(Box::new(StripePaymentBehavior {
-            client: self.client.clone(),
-        }))
+        if self.payment_intents {
+            PaymentProcessorBehavior::ConfirmThenComplete(Box::new(StripePaymentIntentBehavior {
+                client: self.client.clone(),
+            }))
+        } else {
+            PaymentProcessorBehavior::AuthThenComplete(Box::new(StripePaymentBehavior {
+                client: self.client.clone(),
+            }))
+        }
     }
 
     fn update_metadata(
@@ -129,9 +170,10 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        // Stripe expects lower case currency codes
         Ok(self
             .client
-            .auth(token, amount, currency, description, metadata)
+            .auth(token, amount, &currency.to_lowercase(), description, metadata)
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
@@ -145,3 +187,82 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         })?)
     }
 }
+
+impl ConfirmThenCompletePaymentBehavior for StripePaymentIntentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Stripe
+    }
+
+    fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        Ok(self
+            .client
+            .create_customer(description, token, Vec::<(String, String)>::new())
+            .map(|r| RepeatChargeToken {
+                token: r.id,
+                raw: r.raw_data,
+            })?)
+    }
+
+    fn update_repeat_token(
+        &self,
+        repeat_token: &str,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        Ok(self
+            .client
+            .update_customer(repeat_token, description, token, Vec::<(String, String)>::new())
+            .map(|r| RepeatChargeToken {
+                token: r.id,
+                raw: r.raw_data,
+            })?)
+    }
+
+    fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        off_session: bool,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntentResult, PaymentProcessorError> {
+        // Stripe expects lower case currency codes
+        Ok(self
+            .client
+            .create_payment_intent(
+                token,
+                amount,
+                &currency.to_lowercase(),
+                description,
+                off_session,
+                metadata,
+            )
+            .map(PaymentIntentResult::from)?)
+    }
+
+    fn confirm(&self, auth_token: &str) -> Result<PaymentIntentResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .confirm_payment_intent(auth_token)
+            .map(PaymentIntentResult::from)?)
+    }
+
+    fn cancel(&self, auth_token: &str) -> Result<PaymentIntentResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .cancel_payment_intent(auth_token)
+            .map(PaymentIntentResult::from)?)
+    }
+
+    fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        Ok(self.client.capture_payment_intent(auth_token).map(|r| ChargeResult {
+            id: r.id,
+            raw: r.raw_data,
+        })?)
+    }
+}
@@ -44,6 +44,13 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/broadcasts/{id}/tracking_count", |r| {
         r.method(Method::POST).with(broadcasts::tracking_count);
     })
+    .resource("/capacity_pools/{id}", |r| {
+        r.method(Method::PUT).with(capacity_pools::update);
+        r.method(Method::DELETE).with(capacity_pools::destroy);
+    })
+    .resource("/capacity_pools/{id}/ticket_types", |r| {
+        r.method(Method::PUT).with(capacity_pools::update_ticket_types);
+    })
     .resource("/cart", |r| {
         r.method(Method::DELETE).with(cart::destroy);
         r.method(Method::POST).with(cart::update_cart);
@@ -59,6 +66,37 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/cart/checkout", |r| {
         r.method(Method::POST).with(cart::checkout);
     })
+    .resource("/cart/packages", |r| {
+        r.method(Method::PUT).with(cart::update_packages);
+    })
+    .resource("/cart/payments/{id}/confirm", |r| {
+        r.method(Method::POST).with(cart::confirm_payment);
+    })
+    .resource("/cart/products", |r| {
+        r.method(Method::PUT).with(cart::update_products);
+    })
+    .resource("/cart/questions", |r| {
+        r.method(Method::GET).with(cart::questions);
+    })
+    .resource("/cart/resale_listings", |r| {
+        r.method(Method::POST).with(cart::add_resale_listing);
+    })
+    .resource("/cart/resale_listings/{id}", |r| {
+        r.method(Method::DELETE).with(cart::remove_resale_listing);
+    })
+    .resource("/cart/entry_slots", |r| {
+        r.method(Method::PUT).with(cart::update_entry_slot);
+    })
+    .resource("/cart/seats", |r| {
+        r.method(Method::PUT).with(cart::update_seats);
+    })
+    .resource("/code_batches/{id}/export", |r| {
+        r.method(Method::GET).with(code_batches::export);
+    })
+    .resource("/code_batches/{id}", |r| {
+        r.method(Method::GET).with(code_batches::show);
+        r.method(Method::DELETE).with(code_batches::destroy);
+    })
     .resource("/codes/{id}/link", |r| {
         r.method(Method::GET).with(codes::link);
     })
@@ -72,9 +110,26 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PATCH).with(comps::update);
         r.method(Method::DELETE).with(comps::destroy);
     })
+    .resource("/entry_slots/{id}", |r| {
+        r.method(Method::DELETE).with(entry_slots::destroy);
+    })
     .resource("/event_report_subscribers/{id}", |r| {
         r.method(Method::DELETE).with(event_report_subscribers::destroy);
     })
+    .resource("/event_questions/{id}", |r| {
+        r.method(Method::PUT).with(event_questions::update);
+        r.method(Method::DELETE).with(event_questions::destroy);
+    })
+    .resource("/event_series/{id}", |r| {
+        r.method(Method::GET).with(event_series::show);
+        r.method(Method::PUT).with(event_series::update);
+    })
+    .resource("/event_series/{id}/occurrences", |r| {
+        r.method(Method::POST).with(event_series::generate_occurrences);
+    })
+    .resource("/event_series/{id}/sync", |r| {
+        r.method(Method::POST).with(event_series::sync);
+    })
     .resource("/events", |r| {
         r.method(Method::GET).with(events::index);
         r.method(Method::POST).with(events::create);
@@ -97,9 +152,17 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/events/{id}/ticket_holder_count", |r| {
         r.method(Method::GET).with(events::ticket_holder_count);
     })
+    .resource("/events/{id}/capacity_pools", |r| {
+        r.method(Method::GET).with(capacity_pools::index);
+        r.method(Method::POST).with(capacity_pools::create);
+    })
     .resource("/events/{id}/clone", |r| {
         r.method(Method::POST).with(events::clone);
     })
+    .resource("/events/{id}/code_batches", |r| {
+        r.method(Method::GET).with(code_batches::index);
+        r.method(Method::POST).with(code_batches::create);
+    })
     .resource("/events/{id}/codes", |r| {
         r.method(Method::GET).with(events::codes);
         r.method(Method::POST).with(codes::create);
@@ -119,6 +182,13 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(events::add_interest);
         r.method(Method::DELETE).with(events::remove_interest);
     })
+    .resource("/events/{id}/products", |r| {
+        r.method(Method::GET).with(products::index_for_event);
+    })
+    .resource("/events/{id}/presale_lotteries", |r| {
+        r.method(Method::GET).with(presale_lotteries::index);
+        r.method(Method::POST).with(presale_lotteries::create);
+    })
     .resource("/events/{id}/publish", |r| {
         r.method(Method::POST).with(events::publish);
     })
@@ -130,6 +200,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/events/{id}/links", |r| {
         r.method(Method::POST).with(events::create_link);
     })
+    .resource("/events/{id}/questions", |r| {
+        r.method(Method::GET).with(event_questions::index);
+        r.method(Method::POST).with(event_questions::create);
+    })
     .resource("/events/{id}/redeem/{ticket_instance_id}", |r| {
         r.method(Method::POST).with(events::redeem_ticket);
     })
@@ -137,6 +211,19 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(event_report_subscribers::index);
         r.method(Method::POST).with(event_report_subscribers::create);
     })
+    .resource("/events/{id}/reschedules", |r| {
+        r.method(Method::GET).with(event_reschedules::index);
+        r.method(Method::POST).with(event_reschedules::create);
+    })
+    .resource("/events/{id}/reschedules/refund", |r| {
+        r.method(Method::POST).with(event_reschedules::refund);
+    })
+    .resource("/events/{id}/resale_listings", |r| {
+        r.method(Method::GET).with(resale_listings::index_for_event);
+    })
+    .resource("/events/{id}/seats", |r| {
+        r.method(Method::GET).with(events::seats);
+    })
     .resource("/events/{id}/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     })
@@ -193,10 +280,16 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/ipns/globee", |r| {
         r.method(Method::POST).with(ipns::globee);
     })
+    .resource("/ipns/stripe", |r| {
+        r.method(Method::POST).with(ipns::stripe);
+    })
     .resource("/holds/{id}/comps", |r| {
         r.method(Method::GET).with(comps::index);
         r.method(Method::POST).with(comps::create);
     })
+    .resource("/holds/{id}/comps/import", |r| {
+        r.method(Method::POST).with(comps::import);
+    })
     .resource("/holds/{id}/split", |r| {
         r.method(Method::POST).with(holds::split);
     })
@@ -224,9 +317,16 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/orders/{id}/activity", |r| {
         r.method(Method::GET).with(orders::activity);
     })
+    .resource("/orders/{id}/answers", |r| {
+        r.method(Method::GET).with(orders::answers);
+        r.method(Method::PUT).with(orders::update_answers);
+    })
     .resource("/orders/{id}/details", |r| {
         r.method(Method::GET).with(orders::details);
     })
+    .resource("/orders/{id}/payment_plan", |r| {
+        r.method(Method::GET).with(orders::payment_plan);
+    })
     .resource("/orders/{id}/refund", |r| {
         r.method(Method::PATCH).with(orders::refund);
     })
@@ -249,6 +349,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(artists::show_from_organizations);
         r.method(Method::POST).with(organizations::add_artist);
     })
+    .resource("/organizations/{id}/event_series", |r| {
+        r.method(Method::GET).with(event_series::index);
+        r.method(Method::POST).with(event_series::create);
+    })
     .resource("/organizations/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_organizations);
     })
@@ -274,6 +378,14 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/organizations/{id}/invites/{invite_id}", |r| {
         r.method(Method::DELETE).with(organization_invites::destroy);
     })
+    .resource("/organizations/{id}/packages", |r| {
+        r.method(Method::GET).with(packages::index);
+        r.method(Method::POST).with(packages::create);
+    })
+    .resource("/organizations/{id}/products", |r| {
+        r.method(Method::GET).with(products::index);
+        r.method(Method::POST).with(products::create);
+    })
     .resource("/organizations/{id}/settlements", |r| {
         r.method(Method::GET).with(settlements::index);
         r.method(Method::POST).with(settlements::create);
@@ -302,6 +414,17 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(organizations::index);
         r.method(Method::POST).with(organizations::create);
     })
+    .resource("/package_ticket_types/{id}", |r| {
+        r.method(Method::PUT).with(packages::update_ticket_type);
+        r.method(Method::DELETE).with(packages::destroy_ticket_type);
+    })
+    .resource("/packages/{id}", |r| {
+        r.method(Method::GET).with(packages::show);
+        r.method(Method::PUT).with(packages::update);
+    })
+    .resource("/packages/{id}/ticket_types", |r| {
+        r.method(Method::POST).with(packages::add_ticket_type);
+    })
     .resource("/password_reset", |r| {
         r.method(Method::POST).with(password_resets::create);
         r.method(Method::PUT).with(password_resets::update);
@@ -312,9 +435,41 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/payment_methods", |r| {
         r.method(Method::GET).with(payment_methods::index);
     })
+    .resource("/presale_lotteries/{id}", |r| {
+        r.method(Method::GET).with(presale_lotteries::show);
+    })
+    .resource("/presale_lotteries/{id}/draw", |r| {
+        r.method(Method::POST).with(presale_lotteries::draw);
+    })
+    .resource("/presale_lotteries/{id}/register", |r| {
+        r.method(Method::POST).with(presale_lotteries::register);
+    })
+    .resource("/pricing_rules/{id}", |r| {
+        r.method(Method::PUT).with(pricing_rules::update);
+        r.method(Method::DELETE).with(pricing_rules::destroy);
+    })
+    .resource("/product_instances", |r| {
+        r.method(Method::GET).with(products::index_instances);
+    })
+    .resource("/product_instances/{id}/redeem", |r| {
+        r.method(Method::POST).with(products::redeem);
+    })
+    .resource("/product_variants/{id}", |r| {
+        r.method(Method::PUT).with(products::update_variant);
+    })
+    .resource("/products/{id}", |r| {
+        r.method(Method::PUT).with(products::update);
+    })
+    .resource("/products/{id}/variants", |r| {
+        r.method(Method::POST).with(products::create_variant);
+    })
     .resource("/redemption_codes/{code}", |r| {
         r.method(Method::GET).with(redemption_codes::show)
     })
+    .resource("/regions/{id}/tax_rules", |r| {
+        r.method(Method::GET).with(tax_rules::index_for_region);
+        r.method(Method::POST).with(tax_rules::create_for_region);
+    })
     .resource("/regions/{id}", |r| {
         r.method(Method::GET).with(regions::show);
         r.method(Method::PUT).with(regions::update);
@@ -326,6 +481,25 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/reports/{id}", |r| {
         r.method(Method::GET).with(reports::get_report);
     })
+    .resource("/resale_listings/{id}", |r| {
+        r.method(Method::DELETE).with(resale_listings::destroy);
+    })
+    .resource("/resale_listings", |r| {
+        r.method(Method::GET).with(resale_listings::index);
+    })
+    .resource("/seating_sections/{id}", |r| {
+        r.method(Method::GET).with(seating_sections::show);
+        r.method(Method::PUT).with(seating_sections::update);
+        r.method(Method::DELETE).with(seating_sections::delete);
+    })
+    .resource("/seating_sections/{id}/seats", |r| {
+        r.method(Method::GET).with(seating_sections::seats);
+        r.method(Method::POST).with(seating_sections::create_seats);
+    })
+    .resource("/seats/{id}", |r| {
+        r.method(Method::PUT).with(seating_sections::update_seat);
+        r.method(Method::DELETE).with(seating_sections::delete_seat);
+    })
     .resource("/slugs", |r| {
         r.method(Method::GET).with(slugs::index);
     })
@@ -339,6 +513,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PUT).with(stages::update);
         r.method(Method::DELETE).with(stages::delete);
     })
+    .resource("/stages/{id}/seating_sections", |r| {
+        r.method(Method::GET).with(seating_sections::index);
+        r.method(Method::POST).with(seating_sections::create);
+    })
     .resource("/settlement_adjustments/{id}", |r| {
         r.method(Method::DELETE).with(settlement_adjustments::destroy);
     })
@@ -350,6 +528,29 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(settlements::show);
         r.method(Method::DELETE).with(settlements::destroy);
     })
+    .resource("/store_credits", |r| {
+        r.method(Method::GET).with(store_credits::show);
+    })
+    .resource("/store_credits/gift_cards", |r| {
+        r.method(Method::POST).with(store_credits::redeem_gift_card);
+    })
+    .resource("/tax_rules/{id}", |r| {
+        r.method(Method::PUT).with(tax_rules::update);
+        r.method(Method::DELETE).with(tax_rules::destroy);
+    })
+    .resource("/ticket_types/{id}/entry_slots", |r| {
+        r.method(Method::GET).with(entry_slots::index);
+        r.method(Method::POST).with(entry_slots::create);
+    })
+    .resource("/ticket_types/{id}/pricing_rules", |r| {
+        r.method(Method::GET).with(pricing_rules::index);
+        r.method(Method::POST).with(pricing_rules::create);
+    })
+    .resource("/ticket_types/{id}/waitlist", |r| {
+        r.method(Method::GET).with(waitlist_entries::show);
+        r.method(Method::POST).with(waitlist_entries::create);
+        r.method(Method::DELETE).with(waitlist_entries::destroy);
+    })
     .resource("/tickets/transfer", |r| {
         r.method(Method::POST).with(tickets::transfer_authorization);
     })
@@ -366,6 +567,12 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     })
+    .resource("/tickets/{id}/exchanges", |r| {
+        r.method(Method::POST).with(ticket_exchanges::create);
+    })
+    .resource("/tickets/{id}/resale_listings", |r| {
+        r.method(Method::POST).with(resale_listings::create);
+    })
     .resource("/tickets/{id}/redeem", |r| {
         r.method(Method::GET).with(tickets::show_redeemable_ticket);
     })
@@ -417,6 +624,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(stages::create);
         r.method(Method::GET).with(stages::index);
     })
+    .resource("/venues/{id}/tax_rules", |r| {
+        r.method(Method::GET).with(tax_rules::index_for_venue);
+        r.method(Method::POST).with(tax_rules::create_for_venue);
+    })
     .resource("/venues/{id}/toggle_privacy", |r| {
         r.method(Method::PUT).with(venues::toggle_privacy);
     })
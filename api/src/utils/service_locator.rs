@@ -11,6 +11,8 @@ use utils::deep_linker::DeepLinker;
 
 pub struct ServiceLocator {
     stripe_secret_key: String,
+    stripe_base_url: String,
+    stripe_payment_intents: bool,
     globee_api_key: String,
     globee_base_url: String,
     branch_io_base_url: String,
@@ -30,6 +32,8 @@ impl ServiceLocator {
         };
         Ok(ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
+            stripe_base_url: config.stripe_base_url.clone(),
+            stripe_payment_intents: config.stripe_payment_intents,
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
             branch_io_base_url: config.branch_io_base_url.clone(),
@@ -49,7 +53,11 @@ impl ServiceLocator {
         organization: &Organization,
     ) -> Result<Box<dyn PaymentProcessor>, BigNeonError> {
         match provider {
-            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(self.stripe_secret_key.clone()))),
+            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(
+                self.stripe_secret_key.clone(),
+                self.stripe_base_url.clone(),
+                self.stripe_payment_intents,
+            ))),
             PaymentProviders::Globee => {
                 let mut org = organization.clone();
                 org.decrypt(&self.api_keys_encryption_key)?;
@@ -61,7 +69,10 @@ impl ServiceLocator {
                 )))
             }
             // External is not valid for service locator
-            PaymentProviders::Free | PaymentProviders::External => {
+            PaymentProviders::Free
+            | PaymentProviders::External
+            | PaymentProviders::Exchange
+            | PaymentProviders::StoreCredit => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
             }
         }
@@ -0,0 +1,84 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::capacity_pools::{self, CreateCapacityPoolRequest, UpdateCapacityPoolTicketTypesRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_type_count(2)
+        .with_tickets()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreateCapacityPoolRequest {
+        name: "Venue".to_string(),
+        capacity: 150,
+        stage_id: None,
+        ticket_type_ids: ticket_types.iter().map(|t| t.id).collect(),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = capacity_pools::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let capacity_pool: DisplayCapacityPool = serde_json::from_str(&body).unwrap();
+    assert_eq!(capacity_pool.capacity_pool.event_id, event.id);
+    assert_eq!(capacity_pool.capacity_pool.capacity, 150);
+    assert_eq!(capacity_pool.available, 150);
+    assert_eq!(capacity_pool.ticket_type_ids.len(), 2);
+}
+
+pub fn update_ticket_types(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let capacity_pool = CapacityPool::create(event.id, None, "Venue".to_string(), 50)
+        .commit(None, connection)
+        .unwrap();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(UpdateCapacityPoolTicketTypesRequest {
+        ticket_type_ids: vec![ticket_type.id],
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = capacity_pool.id;
+    let response: HttpResponse =
+        capacity_pools::update_ticket_types((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let capacity_pool: DisplayCapacityPool = serde_json::from_str(&body).unwrap();
+    assert_eq!(capacity_pool.ticket_type_ids, vec![ticket_type.id]);
+    // The ticket type can only sell what remains in the pool
+    assert_eq!(capacity_pool.available, 50);
+    let ticket_type = TicketType::find(ticket_type.id, connection).unwrap();
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 50);
+}
@@ -0,0 +1,55 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::code_batches::{self, CreateCodeBatchRequest, DisplayCodeBatch};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreateCodeBatchRequest {
+        name: "Partner campaign".to_string(),
+        quantity: 25,
+        prefix: Some("PARTNER".to_string()),
+        code_type: CodeTypes::Discount,
+        discount_in_cents: Some(100),
+        discount_as_percentage: None,
+        start_date: None,
+        end_date: None,
+        max_tickets_per_user: None,
+        ticket_type_ids: vec![ticket_type.id],
+        discount_rules: CodeDiscountRules::default(),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = code_batches::create((database.connection.clone().into(), json, path, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_code_batch: DisplayCodeBatch = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_code_batch.code_batch.event_id, event.id);
+    assert_eq!(display_code_batch.summary.code_count, 25);
+    assert_eq!(display_code_batch.summary.redeemed_code_count, 0);
+    assert_eq!(
+        CodeBatch::find_for_event(event.id, connection).unwrap(),
+        vec![display_code_batch.code_batch]
+    );
+}
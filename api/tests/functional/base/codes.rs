@@ -85,6 +85,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         end_date: Some(end_date),
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        discount_rules: CodeDiscountRules::default(),
     });
 
     let test_request = TestRequest::create();
@@ -1,5 +1,5 @@
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
-use bigneon_api::controllers::comps::{self, NewCompRequest};
+use bigneon_api::controllers::comps::{self, ImportCompsRequest, NewCompRequest};
 use bigneon_api::controllers::holds::UpdateHoldRequest;
 use bigneon_api::extractors::*;
 use bigneon_api::models::PathParameters;
@@ -136,6 +136,52 @@ pub fn create(role: Roles, should_test_succeed: bool) {
     }
 }
 
+pub fn import(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let hold = database.create_hold().with_hold_type(HoldTypes::Comp).finish();
+    let event = Event::find(hold.event_id, connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let json = Json(ImportCompsRequest {
+        csv: "name,email,phone,quantity\nGuest One,guest1@address.com,,2\nGuest Two,,5551234567,3".to_string(),
+        end_at: None,
+        max_per_user: None,
+        send_comp_links: true,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = hold.id;
+
+    let response: HttpResponse = comps::import((
+        database.connection.clone().into(),
+        json,
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_succeed {
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let comps: Vec<DisplayHold> = serde_json::from_str(&body).unwrap();
+        assert_eq!(comps.len(), 2);
+        assert_eq!(comps[0].name, "Guest One");
+        assert_eq!(comps[0].email, Some("guest1@address.com".to_string()));
+        assert_eq!(comps[0].quantity, 2);
+        assert_eq!(comps[1].name, "Guest Two");
+        assert_eq!(comps[1].phone, Some("5551234567".to_string()));
+        assert_eq!(comps[1].quantity, 3);
+        assert_eq!(hold.quantity(connection).unwrap(), (5, 5));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
 pub fn destroy(role: Roles, should_succeed: bool) {
     let database = TestDatabase::new();
     let connection = database.connection.get();
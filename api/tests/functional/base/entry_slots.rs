@@ -0,0 +1,82 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::entry_slots;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{EntrySlot, EntrySlotSchedule, Roles};
+use chrono::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(EntrySlotSchedule {
+        start_date: NaiveDate::from_ymd(2050, 7, 8),
+        end_date: NaiveDate::from_ymd(2050, 7, 8),
+        first_entry: NaiveTime::from_hms(10, 0, 0),
+        last_entry: NaiveTime::from_hms(11, 0, 0),
+        interval_minutes: 30,
+        entry_window_minutes: None,
+        capacity: 20,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket_type.id;
+    let response: HttpResponse = entry_slots::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let entry_slots: Vec<EntrySlot> = serde_json::from_str(&body).unwrap();
+    assert_eq!(entry_slots.len(), 3);
+    assert_eq!(entry_slots[0].ticket_type_id, ticket_type.id);
+    assert_eq!(entry_slots[0].capacity, 20);
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let entry_start = NaiveDate::from_ymd(2050, 7, 8).and_hms(10, 0, 0);
+    let entry_slot = EntrySlot::create(
+        ticket_type.id,
+        entry_start,
+        NaiveDate::from_ymd(2050, 7, 8).and_hms(11, 0, 0),
+        20,
+    )
+    .commit(connection)
+    .unwrap();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = entry_slot.id;
+    let response: HttpResponse = entry_slots::destroy((database.connection.clone().into(), path, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(EntrySlot::find(entry_slot.id, connection).is_err());
+}
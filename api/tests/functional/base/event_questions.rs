@@ -0,0 +1,45 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_questions::{self, CreateEventQuestionRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreateEventQuestionRequest {
+        question: "T-shirt size".to_string(),
+        question_type: EventQuestionTypes::Choice,
+        choices: vec!["S".to_string(), "M".to_string(), "L".to_string()],
+        required: true,
+        per_ticket: true,
+        rank: 0,
+        answers_editable_until: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = event_questions::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let event_question: EventQuestion = serde_json::from_str(&body).unwrap();
+    assert_eq!(event_question.event_id, event.id);
+    assert_eq!(event_question.question_type, EventQuestionTypes::Choice);
+    assert_eq!(
+        EventQuestion::find_for_event(event.id, connection).unwrap(),
+        vec![event_question]
+    );
+}
@@ -0,0 +1,77 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_reschedules::{self, NewEventRescheduleRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let event_reschedule = EventReschedule::create(
+        event.id,
+        dates::now().add_days(30).finish(),
+        dates::now().add_days(31).finish(),
+        None,
+        dates::now().add_days(7).finish(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = event_reschedules::index((database.connection.clone(), path, auth_user)).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let event_reschedules: Vec<EventReschedule> = serde_json::from_str(&body).unwrap();
+        assert_eq!(event_reschedules, vec![event_reschedule]);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let event_start = dates::now().add_days(30).finish();
+    let json = Json(NewEventRescheduleRequest {
+        event_start,
+        event_end: dates::now().add_days(31).finish(),
+        door_time: None,
+        refund_window_ends_at: dates::now().add_days(7).finish(),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = event_reschedules::create((database.connection.clone(), path, json, auth_user)).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let event_reschedule: EventReschedule = serde_json::from_str(&body).unwrap();
+        assert_eq!(event_reschedule.event_id, event.id);
+        assert_eq!(event_reschedule.previous_event_start, event.event_start);
+        let event = Event::find(event.id, connection).unwrap();
+        assert_eq!(event.event_start, Some(event_reschedule.event_start));
+        assert_eq!(event.override_status, Some(EventOverrideStatus::Rescheduled));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
@@ -0,0 +1,96 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_series;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::prelude::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(7).finish())
+        .with_event_end(dates::now().add_days(7).add_hours(3).finish())
+        .with_ticket_pricing()
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let json = Json(event_series::NewEventSeriesRequest {
+        template_event_id: event.id,
+        name: "Weekly residency".to_string(),
+        recurrence_rule: "FREQ=WEEKLY;COUNT=4".to_string(),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        event_series::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let event_series: EventSeries = serde_json::from_str(&body).unwrap();
+    assert_eq!(event_series.template_event_id, event.id);
+    assert_eq!(event_series.recurrence_rule, "FREQ=WEEKLY;COUNT=4");
+    let event = Event::find(event.id, database.connection.get()).unwrap();
+    assert_eq!(event.event_series_id, Some(event_series.id));
+}
+
+pub fn generate_occurrences(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(7).finish())
+        .with_event_end(dates::now().add_days(7).add_hours(3).finish())
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=4".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event_series.id;
+    let json = Json(event_series::GenerateOccurrencesRequest::default());
+    let response: HttpResponse = event_series::generate_occurrences((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let occurrences: Vec<Event> = serde_json::from_str(&body).unwrap();
+    assert_eq!(occurrences.len(), 3);
+    for occurrence in occurrences {
+        assert_eq!(occurrence.event_series_id, Some(event_series.id));
+        assert_eq!(occurrence.ticket_types(true, None, connection).unwrap().len(), 1);
+    }
+    assert_eq!(event_series.occurrences(connection).unwrap().len(), 4);
+}
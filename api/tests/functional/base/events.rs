@@ -864,6 +864,7 @@ pub fn expected_show_json(
         event_end: Option<NaiveDateTime>,
         cancelled_at: Option<NaiveDateTime>,
         fee_in_cents: Option<i64>,
+        currency: String,
         status: EventStatus,
         publish_date: Option<NaiveDateTime>,
         promo_image_url: Option<String>,
@@ -965,6 +966,7 @@ pub fn expected_show_json(
         event_end: event.event_end,
         cancelled_at: event.cancelled_at,
         fee_in_cents: Some(fee_in_cents),
+        currency: organization.currency.clone(),
         status: status.unwrap_or(event.status),
         publish_date: event.publish_date,
         promo_image_url: event.promo_image_url.clone(),
@@ -34,6 +34,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         end_at: None,
         max_per_user: None,
         quantity: 2,
+        expiry_behavior: None,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
     });
 
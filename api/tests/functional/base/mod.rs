@@ -1,20 +1,32 @@
 pub mod artists;
+pub mod capacity_pools;
 pub mod cart;
+pub mod code_batches;
 pub mod codes;
 pub mod comps;
+pub mod entry_slots;
+pub mod event_questions;
 pub mod event_report_subscribers;
+pub mod event_reschedules;
+pub mod event_series;
 pub mod events;
 pub mod holds;
 pub mod notes;
 pub mod orders;
 pub mod organization_invites;
 pub mod organizations;
+pub mod packages;
+pub mod presale_lotteries;
+pub mod pricing_rules;
+pub mod products;
 pub mod regions;
 pub mod reports;
+pub mod seating_sections;
 pub mod settlement_adjustments;
 pub mod settlements;
 pub mod stages;
 pub mod ticket_types;
+pub mod ticket_exchanges;
 pub mod tickets;
 pub mod transfers;
 pub mod users;
@@ -424,6 +424,7 @@ pub fn refund(role: Roles, manual_override: bool, should_succeed: bool) {
         items: refund_items,
         reason: None,
         manual_override,
+        to_store_credit: false,
     });
 
     let test_request = TestRequest::create();
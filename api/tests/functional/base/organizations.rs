@@ -274,6 +274,9 @@ pub fn update_restricted_field(restricted_field: &str, role: Roles, should_succe
         "max_instances_per_ticket_type" => {
             attributes.max_instances_per_ticket_type = Some(11000);
         }
+        "currency" => {
+            attributes.currency = Some("cad".to_string());
+        }
         _ => panic!("Unexpected restricted field"),
     }
 
@@ -303,6 +306,9 @@ pub fn update_restricted_field(restricted_field: &str, role: Roles, should_succe
         "max_instances_per_ticket_type" => {
             assert_eq!(updated_organization.max_instances_per_ticket_type, 11000);
         }
+        "currency" => {
+            assert_eq!(updated_organization.currency, "CAD");
+        }
         _ => panic!("Unexpected restricted field"),
     }
 }
@@ -508,6 +514,8 @@ pub fn show_fee_schedule(role: Roles, should_succeed: bool) {
         name: String,
         version: i64,
         created_at: NaiveDateTime,
+        resale_company_fee_percent: f32,
+        resale_client_fee_percent: f32,
         ranges: Vec<FeeScheduleRange>,
     }
 
@@ -516,6 +524,8 @@ pub fn show_fee_schedule(role: Roles, should_succeed: bool) {
         name: fee_schedule.name,
         version: 0,
         created_at: fee_schedule.created_at,
+        resale_company_fee_percent: fee_schedule.resale_company_fee_percent,
+        resale_client_fee_percent: fee_schedule.resale_client_fee_percent,
         ranges: fee_schedule_ranges,
     };
 
@@ -555,6 +565,8 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
                 client_fee_in_cents: 60,
             },
         ],
+        resale_company_fee_percent: 2.5,
+        resale_client_fee_percent: 5.0,
     });
     let test_request = TestRequest::create();
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
@@ -571,4 +583,6 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
     let body = support::unwrap_body_to_string(&response).unwrap();
     let result: FeeScheduleWithRanges = serde_json::from_str(&body).unwrap();
     assert_eq!(result.name, "Fees".to_string());
+    assert_eq!(result.resale_company_fee_percent, 2.5);
+    assert_eq!(result.resale_client_fee_percent, 5.0);
 }
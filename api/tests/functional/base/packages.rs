@@ -0,0 +1,72 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::packages::{self, AddPackageTicketTypeRequest, CreatePackageRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreatePackageRequest {
+        name: "Season pass".to_string(),
+        description: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = packages::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let package: DisplayPackage = serde_json::from_str(&body).unwrap();
+    assert_eq!(package.package.organization_id, organization.id);
+    assert_eq!(package.package.name, "Season pass");
+    assert_eq!(package.price_in_cents, 0);
+}
+
+pub fn add_ticket_type(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let package = Package::create(organization.id, "Season pass".to_string(), None)
+        .commit(None, connection)
+        .unwrap();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(AddPackageTicketTypeRequest {
+        ticket_type_id: ticket_type.id,
+        price_in_cents: 2500,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = package.id;
+    let response: HttpResponse =
+        packages::add_ticket_type((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let package: DisplayPackage = serde_json::from_str(&body).unwrap();
+    assert_eq!(package.price_in_cents, 2500);
+    assert_eq!(package.ticket_types.len(), 1);
+    assert_eq!(package.ticket_types[0].event_id, event.id);
+}
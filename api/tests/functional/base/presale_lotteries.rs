@@ -0,0 +1,55 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::presale_lotteries::{self, CreatePresaleLotteryRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreatePresaleLotteryRequest {
+        name: "Fan presale".to_string(),
+        ticket_type_ids: vec![ticket_type.id],
+        registration_start: dates::now().finish(),
+        registration_end: dates::now().add_days(1).finish(),
+        draw_at: dates::now().add_days(1).finish(),
+        code_expires_at: dates::now().add_days(2).finish(),
+        winner_count: 100,
+        tickets_per_winner: 2,
+        interaction_weight: 1,
+        max_weight: Some(3),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        presale_lotteries::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let presale_lottery: PresaleLottery = serde_json::from_str(&body).unwrap();
+    assert_eq!(presale_lottery.event_id, event.id);
+    assert_eq!(presale_lottery.max_weight, 3);
+    assert_eq!(
+        PresaleLottery::find_for_event(event.id, connection).unwrap(),
+        vec![presale_lottery]
+    );
+}
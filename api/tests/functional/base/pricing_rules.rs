@@ -0,0 +1,54 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::pricing_rules::{self, CreatePricingRuleRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreatePricingRuleRequest {
+        name: "Last days".to_string(),
+        rule_type: PricingRuleTypes::DaysUntilEvent,
+        threshold: 7,
+        adjustment_in_cents: 50,
+        floor_price_in_cents: 0,
+        ceiling_price_in_cents: 500,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket_type.id;
+    let response: HttpResponse = pricing_rules::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let pricing_rule: PricingRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(pricing_rule.ticket_type_id, ticket_type.id);
+    assert_eq!(pricing_rule.rule_type, PricingRuleTypes::DaysUntilEvent);
+    // The event starts within the threshold so the rule applies straight away
+    assert_eq!(
+        ticket_type
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        200
+    );
+}
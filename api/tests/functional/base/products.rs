@@ -0,0 +1,94 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::products::{self, CreateProductRequest, RedeemProductRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(CreateProductRequest {
+        event_id: Some(event.id),
+        name: "Parking".to_string(),
+        description: None,
+        price_in_cents: 1500,
+        gift_card: false,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = products::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let product: DisplayProduct = serde_json::from_str(&body).unwrap();
+    assert_eq!(product.product.organization_id, organization.id);
+    assert_eq!(product.product.event_id, Some(event.id));
+    assert_eq!(product.product.price_in_cents, 1500);
+    assert!(product.variants.is_empty());
+}
+
+pub fn redeem(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let product = Product::create(organization.id, Some(event.id), "Parking".to_string(), None, 1500)
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Lot A".to_string(), "PARK-A".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+    let customer = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&customer, connection).unwrap();
+    cart.update_product_quantities(
+        customer.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: None,
+            quantity: 1,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        customer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let product_instance = ProductInstance::find_for_user_for_display(customer.id, connection)
+        .unwrap()
+        .remove(0);
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let json = Json(RedeemProductRequest {
+        redeem_key: product_instance.redeem_key.clone(),
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = product_instance.id;
+    let response: HttpResponse = products::redeem((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let product_instance = ProductInstance::find(product_instance.id, connection).unwrap();
+    assert_eq!(product_instance.status, ProductInstanceStatus::Redeemed);
+}
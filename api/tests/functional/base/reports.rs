@@ -403,6 +403,8 @@ fn build_transaction_report_row(
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
+        store_credit_in_cents: 0,
+        store_credit_refunded_in_cents: 0,
         transaction_date: order.paid_at.clone().unwrap(),
         redemption_code: None,
         order_id: order.id,
@@ -423,5 +425,7 @@ fn build_transaction_report_row(
         content: None,
         platform: None,
         check_in_source: None,
+        attendee_answers: None,
+        currency: order.currency.clone(),
     }
 }
@@ -0,0 +1,78 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::seating_sections;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{Roles, Seat, SeatingSection};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let name = "Orchestra";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(seating_sections::CreateSeatingSection {
+        name: name.to_string(),
+        rank: 0,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = stage.id;
+    let response: HttpResponse = seating_sections::create((database.connection.into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seating_section: SeatingSection = serde_json::from_str(&body).unwrap();
+    assert_eq!(seating_section.name, name);
+    assert_eq!(seating_section.stage_id, stage.id);
+}
+
+pub fn create_seats(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let seating_section = database.create_seating_section().with_seats(1, 2).finish();
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(seating_sections::CreateSeatsRequest {
+        rows: vec![seating_sections::CreateSeatRow {
+            row_name: "B".to_string(),
+            seats: vec![
+                seating_sections::CreateSeat {
+                    seat_number: "1".to_string(),
+                    accessible: true,
+                },
+                seating_sections::CreateSeat {
+                    seat_number: "2".to_string(),
+                    accessible: false,
+                },
+            ],
+        }],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = seating_section.id;
+    let response: HttpResponse =
+        seating_sections::create_seats((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seats: Vec<Seat> = serde_json::from_str(&body).unwrap();
+    assert_eq!(seats.len(), 2);
+    assert_eq!(seats[0].row_rank, 1);
+    assert_eq!(seats[1].seat_rank, 1);
+    assert!(seats[0].accessible);
+    assert_eq!(seating_section.seats(database.connection.get()).unwrap().len(), 4);
+}
@@ -0,0 +1,104 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::ticket_exchanges::{self, NewTicketExchangeRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use serde_json;
+use serde_json::Value;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet_id = event.issuer_wallet(connection).unwrap().id;
+    let new_ticket_type = event
+        .add_ticket_type(
+            "Balcony".to_string(),
+            None,
+            10,
+            Some(dates::now().add_hours(-1).finish()),
+            None,
+            TicketTypeEndDateType::EventEnd,
+            Some(wallet_id),
+            None,
+            0,
+            100,
+            TicketTypeVisibility::Always,
+            None,
+            0,
+            true,
+            true,
+            true,
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let ticket = &cart.tickets(Some(ticket_type.id), connection).unwrap()[0];
+
+    let org_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&org_user, role, Some(&organization), &database);
+    let json = Json(NewTicketExchangeRequest {
+        ticket_type_id: new_ticket_type.id,
+        token: None,
+        provider: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse = ticket_exchanges::create((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    if should_succeed {
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let result: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(result["price_difference_in_cents"], json!(100 - total));
+        let ticket_exchange = &TicketExchange::find_for_order(cart.id, connection).unwrap()[0];
+        assert_eq!(ticket_exchange.ticket_instance_id, ticket.id);
+        assert_eq!(ticket_exchange.new_ticket_type_id, new_ticket_type.id);
+        assert_eq!(result["order"]["id"], json!(ticket_exchange.new_order_id));
+        assert_eq!(ticket_exchange.new_order(connection).unwrap().status, OrderStatus::Paid);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
@@ -32,6 +32,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -39,6 +40,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         start_date: middle_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -91,6 +93,7 @@ pub fn create_multiple(role: Roles, should_test_succeed: bool) {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -98,6 +101,7 @@ pub fn create_multiple(role: Roles, should_test_succeed: bool) {
         start_date: middle_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_types.push(CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -121,6 +125,7 @@ pub fn create_multiple(role: Roles, should_test_succeed: bool) {
         start_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_types.push(CreateTicketTypeRequest {
         name: "GA".into(),
@@ -225,6 +230,7 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     request_ticket_pricing.push(UpdateTicketPricingRequest {
         id: None,
@@ -233,6 +239,7 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         end_date: middle_date,
         price_in_cents: Some(15000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -282,6 +289,7 @@ pub fn update(role: Roles, should_test_succeed: bool) {
             end_date: Some(current_ticket_pricing.end_date),
             price_in_cents: Some(current_ticket_pricing.price_in_cents),
             is_box_office_only: Some(false),
+            quantity_limit: None,
         });
     }
     let updated_data = UpdateTicketTypeRequest {
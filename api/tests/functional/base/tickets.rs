@@ -53,6 +53,11 @@ pub fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             transfer_key: None,
             transfer_address: None,
             check_in_source: None,
+            section_name: None,
+            row_name: None,
+            seat_number: None,
+            entry_start: None,
+            entry_end: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -118,6 +123,11 @@ pub fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
             transfer_key: None,
             transfer_address: None,
             check_in_source: None,
+            section_name: None,
+            row_name: None,
+            seat_number: None,
+            entry_start: None,
+            entry_end: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::capacity_pools::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::capacity_pools::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::capacity_pools::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::capacity_pools::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::capacity_pools::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::capacity_pools::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::capacity_pools::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::capacity_pools::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::capacity_pools::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod update_ticket_types_tests {
+    use super::*;
+    #[test]
+    fn update_ticket_types_org_member() {
+        base::capacity_pools::update_ticket_types(Roles::OrgMember, true);
+    }
+    #[test]
+    fn update_ticket_types_admin() {
+        base::capacity_pools::update_ticket_types(Roles::Admin, true);
+    }
+    #[test]
+    fn update_ticket_types_user() {
+        base::capacity_pools::update_ticket_types(Roles::User, false);
+    }
+    #[test]
+    fn update_ticket_types_org_owner() {
+        base::capacity_pools::update_ticket_types(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn update_ticket_types_door_person() {
+        base::capacity_pools::update_ticket_types(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn update_ticket_types_promoter() {
+        base::capacity_pools::update_ticket_types(Roles::Promoter, true);
+    }
+    #[test]
+    fn update_ticket_types_promoter_read_only() {
+        base::capacity_pools::update_ticket_types(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn update_ticket_types_org_admin() {
+        base::capacity_pools::update_ticket_types(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn update_ticket_types_box_office() {
+        base::capacity_pools::update_ticket_types(Roles::OrgBoxOffice, false);
+    }
+}
@@ -20,6 +20,7 @@ use globee::GlobeeIpnRequest;
 use globee::PaymentDetails;
 use serde_json;
 use support::database::TestDatabase;
+use support::stripe_mock::mock_stripe;
 use support::test_request::TestRequest;
 use support::{self, *};
 use uuid::Uuid;
@@ -894,6 +895,10 @@ fn checkout_external() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Voucher,
@@ -931,6 +936,84 @@ fn checkout_external() {
     assert_eq!("Example note".to_string(), note.note);
 }
 
+#[test]
+fn checkout_split_payment() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+    let external_checkout = |amount_in_cents: Option<i64>, external_payment_type: ExternalPaymentType| {
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            answers: Vec::new(),
+            store_credit_in_cents: None,
+            installment_count: None,
+            amount_in_cents,
+            method: PaymentRequest::External {
+                reference: None,
+                external_payment_type,
+                first_name: "First".to_string(),
+                last_name: "Last".to_string(),
+                email: Some("split@test.com".to_string()),
+                phone: None,
+                note: None,
+            },
+        })
+    };
+
+    // Can't pay more than is due
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        external_checkout(Some(total + 1), ExternalPaymentType::Cash),
+        auth_user.clone(),
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Part of the order is paid in cash, the order waits for the rest
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        external_checkout(Some(500), ExternalPaymentType::Cash),
+        auth_user.clone(),
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_order: DisplayOrder = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_order.status, OrderStatus::PendingPayment);
+    assert_eq!(display_order.amount_paid_in_cents, 500);
+    assert_eq!(
+        Order::find_cart_for_user(user.id, connection).unwrap().map(|o| o.id),
+        Some(order.id)
+    );
+
+    // The rest is paid by another method
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        external_checkout(None, ExternalPaymentType::CreditCard),
+        auth_user,
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let mut payment_amounts: Vec<i64> = order.payments(connection).unwrap().iter().map(|p| p.amount).collect();
+    payment_amounts.sort();
+    let mut expected_amounts = vec![500, total - 500];
+    expected_amounts.sort();
+    assert_eq!(payment_amounts, expected_amounts);
+    assert!(Order::find_cart_for_user(user.id, connection).unwrap().is_none());
+}
+
 #[test]
 fn checkout_external_with_free_cart() {
     let database = TestDatabase::new();
@@ -948,6 +1031,10 @@ fn checkout_external_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -1001,6 +1088,10 @@ fn checkout_paid_fails_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::Card {
             token: "abc".into(),
             provider: PaymentProviders::Stripe,
@@ -1036,6 +1127,245 @@ fn checkout_paid_fails_with_free_cart() {
     assert_eq!(order.status, OrderStatus::Draft);
 }
 
+const PAYMENT_INTENT_REQUIRES_ACTION: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "usd",
+    "status": "requires_action",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": {"type": "use_stripe_sdk", "use_stripe_sdk": {"type": "three_d_secure_redirect"}}
+}"#;
+const PAYMENT_INTENT_REQUIRES_CAPTURE: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "usd",
+    "status": "requires_capture",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": null
+}"#;
+const PAYMENT_INTENT_CANCELED: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "usd",
+    "status": "canceled",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": null
+}"#;
+const PAYMENT_INTENT_SUCCEEDED: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "usd",
+    "status": "succeeded",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": null
+}"#;
+
+fn card_checkout() -> Json<cart::CheckoutCartRequest> {
+    Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
+        method: PaymentRequest::Card {
+            token: "tok_visa".into(),
+            provider: PaymentProviders::Stripe,
+            save_payment_method: false,
+            set_default: false,
+        },
+    })
+}
+
+#[test]
+fn checkout_payment_intent() {
+    let database = TestDatabase::new();
+    // Authorized payments are committed before they are captured
+    database.connection.begin_transaction().unwrap();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let (stripe_base_url, stripe) = mock_stripe(vec![
+        (200, PAYMENT_INTENT_REQUIRES_CAPTURE),
+        (200, PAYMENT_INTENT_SUCCEEDED),
+    ]);
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        card_checkout(),
+        auth_user,
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let requests = stripe.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].starts_with("POST /v1/payment_intents "));
+    assert!(requests[0].contains(&format!("amount={}", total)));
+    assert!(requests[0].contains("currency=usd"));
+    assert!(requests[0].contains("capture_method=manual"));
+    assert_eq!(requests[1], "POST /v1/payment_intents/pi_1GAbc/capture ");
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].status, PaymentStatus::Completed);
+    assert_eq!(payments[0].external_reference, Some("pi_1GAbc".to_string()));
+}
+
+#[test]
+fn checkout_payment_intent_capture_failure() {
+    let database = TestDatabase::new();
+    // Authorized payments are committed before they are captured
+    database.connection.begin_transaction().unwrap();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let (stripe_base_url, stripe) = mock_stripe(vec![
+        (200, PAYMENT_INTENT_REQUIRES_CAPTURE),
+        (
+            402,
+            r#"{"error": {"code": "card_declined", "message": "Your card was declined.", "type": "card_error"}}"#,
+        ),
+        (200, PAYMENT_INTENT_CANCELED),
+    ]);
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let result = cart::checkout((
+        database.connection.clone().into(),
+        card_checkout(),
+        auth_user,
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ));
+    assert!(result.is_err());
+    // The error rolls back the request's transaction
+    database.connection.rollback_transaction().unwrap();
+
+    let requests = stripe.join().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2], "POST /v1/payment_intents/pi_1GAbc/cancel ");
+
+    // The hold on the card was released so the payment stays cancelled
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].status, PaymentStatus::Cancelled);
+}
+
+#[test]
+fn checkout_payment_intent_requiring_action() {
+    let database = TestDatabase::new();
+    // Authorized payments are committed before they are captured
+    database.connection.begin_transaction().unwrap();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let (stripe_base_url, stripe) = mock_stripe(vec![(200, PAYMENT_INTENT_REQUIRES_ACTION)]);
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        card_checkout(),
+        auth_user.clone(),
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body: serde_json::Value = serde_json::from_str(unwrap_body_to_string(&response).unwrap()).unwrap();
+    assert_eq!(body["client_secret"], json!("pi_1GAbc_secret_xyz"));
+    assert_eq!(body["next_action"]["type"], json!("use_stripe_sdk"));
+    assert_eq!(stripe.join().unwrap().len(), 1);
+
+    // Payment is waiting for the customer to authenticate it, the order stays in the cart
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    let payment = order.payments(connection).unwrap().remove(0);
+    assert_eq!(body["payment_id"], json!(payment.id));
+    assert_eq!(payment.status, PaymentStatus::Requested);
+    assert_eq!(
+        Order::find_cart_for_user(user.id, connection).unwrap().map(|o| o.id),
+        Some(order.id)
+    );
+
+    // Other users can't confirm the payment
+    let (stripe_base_url, _) = mock_stripe(Vec::new());
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = payment.id;
+    let other_user = database.create_user().finish();
+    let other_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let response: HttpResponse = cart::confirm_payment((
+        database.connection.clone().into(),
+        path,
+        other_user,
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let (stripe_base_url, stripe) = mock_stripe(vec![
+        (200, PAYMENT_INTENT_REQUIRES_CAPTURE),
+        (200, PAYMENT_INTENT_SUCCEEDED),
+    ]);
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = payment.id;
+    let response = cart::confirm_payment((
+        database.connection.clone().into(),
+        path,
+        auth_user.clone(),
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        stripe.join().unwrap(),
+        vec![
+            "POST /v1/payment_intents/pi_1GAbc/confirm ".to_string(),
+            "POST /v1/payment_intents/pi_1GAbc/capture ".to_string(),
+        ]
+    );
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+
+    // Payments can only be confirmed once
+    let (stripe_base_url, _) = mock_stripe(Vec::new());
+    let request = TestRequest::create_with_stripe(stripe_base_url);
+    let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
+    path.id = payment.id;
+    let response: HttpResponse = cart::confirm_payment((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+        request.extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 #[test]
 fn checkout_free() {
     let database = TestDatabase::new();
@@ -1054,6 +1384,10 @@ fn checkout_free() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::Free,
     });
 
@@ -1081,6 +1415,86 @@ fn checkout_free() {
     assert_eq!(payment.provider, PaymentProviders::Free);
 }
 
+#[test]
+fn checkout_with_attendee_questions() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let question = EventQuestion::create(
+        event.id,
+        "Name on badge".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        true,
+        true,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let user = database.create_user().finish();
+    let order = database
+        .create_cart()
+        .with_free_items()
+        .for_user(&user)
+        .for_event(&event)
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Required question is not answered
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            answers: Vec::new(),
+            store_credit_in_cents: None,
+            installment_count: None,
+            amount_in_cents: None,
+            method: PaymentRequest::Free,
+        }),
+        auth_user.clone(),
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let attendee_questions = EventAnswer::questions_for_order(&order, connection).unwrap();
+    assert!(!attendee_questions.is_empty());
+    let answers: Vec<EventAnswerRequest> = attendee_questions
+        .iter()
+        .map(|q| EventAnswerRequest {
+            event_question_id: question.id,
+            ticket_instance_id: q.ticket_instance_id,
+            answer: "Jane".to_string(),
+        })
+        .collect();
+    let response = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            answers,
+            store_credit_in_cents: None,
+            installment_count: None,
+            amount_in_cents: None,
+            method: PaymentRequest::Free,
+        }),
+        auth_user,
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(
+        EventAnswer::find_for_order(order.id, connection).unwrap().len(),
+        attendee_questions.len()
+    );
+}
+
 #[test]
 fn checkout_free_for_paid_items() {
     let database = TestDatabase::new();
@@ -1094,6 +1508,10 @@ fn checkout_free_for_paid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::Free,
     });
 
@@ -1202,6 +1620,10 @@ fn checkout_fails_for_invalid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -1252,6 +1674,10 @@ fn checkout_provider_globee() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        answers: Vec::new(),
+        store_credit_in_cents: None,
+        installment_count: None,
+        amount_in_cents: None,
         method: PaymentRequest::Provider {
             provider: PaymentProviders::Globee,
         },
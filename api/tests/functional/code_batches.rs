@@ -0,0 +1,43 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::code_batches::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::code_batches::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::code_batches::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::code_batches::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::code_batches::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::code_batches::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::code_batches::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::code_batches::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::code_batches::create(Roles::OrgBoxOffice, false);
+    }
+}
@@ -198,6 +198,7 @@ fn create_with_validation_errors() {
         end_date: Some(end_date),
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        discount_rules: CodeDiscountRules::default(),
     });
 
     let test_request = TestRequest::create();
@@ -228,6 +229,7 @@ fn create_with_validation_errors() {
         end_date: Some(end_date),
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        discount_rules: CodeDiscountRules::default(),
     });
 
     let test_request = TestRequest::create();
@@ -254,6 +256,7 @@ fn create_with_validation_errors() {
         end_date: Some(end_date),
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        discount_rules: CodeDiscountRules::default(),
     });
 
     let test_request = TestRequest::create();
@@ -293,6 +296,7 @@ fn create_fails_adding_ticket_type_id_from_other_event() {
         end_date: Some(end_date),
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        discount_rules: CodeDiscountRules::default(),
     });
 
     let test_request = TestRequest::create();
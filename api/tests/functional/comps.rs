@@ -1,11 +1,12 @@
 use actix_web::error::ResponseError;
 use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
-use bigneon_api::controllers::comps::{self, NewCompRequest};
+use bigneon_api::controllers::comps::{self, ImportCompsRequest, NewCompRequest};
 use bigneon_api::controllers::holds::UpdateHoldRequest;
 use bigneon_api::extractors::*;
 use bigneon_api::models::PathParameters;
 use bigneon_db::models::*;
 use functional::base;
+use serde_json;
 use support;
 use support::database::TestDatabase;
 use support::test_request::TestRequest;
@@ -133,6 +134,47 @@ mod create_tests {
     }
 }
 
+#[cfg(test)]
+mod import_tests {
+    use super::*;
+    #[test]
+    fn import_org_member() {
+        base::comps::import(Roles::OrgMember, true);
+    }
+    #[test]
+    fn import_admin() {
+        base::comps::import(Roles::Admin, true);
+    }
+    #[test]
+    fn import_user() {
+        base::comps::import(Roles::User, false);
+    }
+    #[test]
+    fn import_org_owner() {
+        base::comps::import(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn import_door_person() {
+        base::comps::import(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn import_promoter() {
+        base::comps::import(Roles::Promoter, true);
+    }
+    #[test]
+    fn import_promoter_read_only() {
+        base::comps::import(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn import_org_admin() {
+        base::comps::import(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn import_box_office() {
+        base::comps::import(Roles::OrgBoxOffice, false);
+    }
+}
+
 #[cfg(test)]
 mod update_tests {
     use super::*;
@@ -253,6 +295,50 @@ fn create_with_validation_errors() {
     assert_eq!(&email[0].message.clone().unwrap().into_owned(), "Email is invalid");
 }
 
+#[test]
+fn import_with_row_errors() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let hold = database.create_hold().with_hold_type(HoldTypes::Comp).finish();
+    let event = Event::find(hold.event_id, connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let json = Json(ImportCompsRequest {
+        csv: "Guest One,invalid,,2\nGuest Two,,,many\nGuest Three,,,9".to_string(),
+        end_at: None,
+        max_per_user: None,
+        send_comp_links: false,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = hold.id;
+
+    let response: HttpResponse = comps::import((
+        database.connection.clone().into(),
+        json,
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        body["errors"],
+        json!([
+            {"row": 1, "errors": ["Email is invalid"]},
+            {"row": 2, "errors": ["Quantity 'many' is not a valid number"]},
+            {"row": 3, "errors": ["Quantity exceeds the 10 tickets remaining in the hold"]}
+        ])
+    );
+    // Nothing is created when any row is invalid
+    assert_eq!(hold.quantity(connection).unwrap(), (10, 10));
+}
+
 #[test]
 fn update_with_validation_errors() {
     let database = TestDatabase::new();
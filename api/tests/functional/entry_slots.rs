@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::entry_slots::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::entry_slots::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::entry_slots::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::entry_slots::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::entry_slots::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::entry_slots::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::entry_slots::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::entry_slots::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::entry_slots::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::entry_slots::destroy(Roles::OrgMember, true);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::entry_slots::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::entry_slots::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::entry_slots::destroy(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::entry_slots::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_promoter() {
+        base::entry_slots::destroy(Roles::Promoter, true);
+    }
+    #[test]
+    fn destroy_promoter_read_only() {
+        base::entry_slots::destroy(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::entry_slots::destroy(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::entry_slots::destroy(Roles::OrgBoxOffice, false);
+    }
+}
@@ -0,0 +1,43 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_questions::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_questions::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_questions::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_questions::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_questions::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::event_questions::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::event_questions::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_questions::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_questions::create(Roles::OrgBoxOffice, false);
+    }
+}
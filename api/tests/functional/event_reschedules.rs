@@ -0,0 +1,176 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_reschedules;
+use bigneon_api::controllers::orders::RefundResponse;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::event_reschedules::index(Roles::OrgMember, true);
+    }
+    #[test]
+    fn index_admin() {
+        base::event_reschedules::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::event_reschedules::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::event_reschedules::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::event_reschedules::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_promoter() {
+        base::event_reschedules::index(Roles::Promoter, true);
+    }
+    #[test]
+    fn index_promoter_read_only() {
+        base::event_reschedules::index(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::event_reschedules::index(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn index_box_office() {
+        base::event_reschedules::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_reschedules::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_reschedules::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_reschedules::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_reschedules::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_reschedules::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::event_reschedules::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::event_reschedules::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_reschedules::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_reschedules::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn refund() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Event has not been rescheduled
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = event_reschedules::refund((
+        database.connection.clone(),
+        path,
+        auth_user.clone(),
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    EventReschedule::create(
+        event.id,
+        dates::now().add_days(30).finish(),
+        dates::now().add_days(31).finish(),
+        None,
+        dates::now().add_days(7).finish(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let response: HttpResponse = event_reschedules::refund((
+        database.connection.clone(),
+        path,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_response: RefundResponse = serde_json::from_str(&body).unwrap();
+    let ticket_total: i64 = cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets || i.item_type == OrderItemTypes::PerUnitFees)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(refund_response.amount_refunded, ticket_total);
+    assert_eq!(
+        refund_response.refund_breakdown.get(&PaymentMethods::External),
+        Some(&ticket_total)
+    );
+    assert!(TicketInstance::find_for_user(user.id, connection).unwrap().is_empty());
+}
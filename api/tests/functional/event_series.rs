@@ -0,0 +1,92 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_series::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_series::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_super() {
+        base::event_series::create(Roles::Super, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_series::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_series::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_series::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::event_series::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::event_series::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_series::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_series::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod generate_occurrences_tests {
+    use super::*;
+    #[test]
+    fn generate_occurrences_org_member() {
+        base::event_series::generate_occurrences(Roles::OrgMember, true);
+    }
+    #[test]
+    fn generate_occurrences_admin() {
+        base::event_series::generate_occurrences(Roles::Admin, true);
+    }
+    #[test]
+    fn generate_occurrences_super() {
+        base::event_series::generate_occurrences(Roles::Super, true);
+    }
+    #[test]
+    fn generate_occurrences_user() {
+        base::event_series::generate_occurrences(Roles::User, false);
+    }
+    #[test]
+    fn generate_occurrences_org_owner() {
+        base::event_series::generate_occurrences(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn generate_occurrences_door_person() {
+        base::event_series::generate_occurrences(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn generate_occurrences_promoter() {
+        base::event_series::generate_occurrences(Roles::Promoter, false);
+    }
+    #[test]
+    fn generate_occurrences_promoter_read_only() {
+        base::event_series::generate_occurrences(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn generate_occurrences_org_admin() {
+        base::event_series::generate_occurrences(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn generate_occurrences_box_office() {
+        base::event_series::generate_occurrences(Roles::OrgBoxOffice, false);
+    }
+}
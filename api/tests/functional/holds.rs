@@ -197,6 +197,7 @@ fn create_with_validation_errors() {
         end_at: None,
         max_per_user: None,
         quantity: 2,
+        expiry_behavior: None,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
     });
 
@@ -275,6 +276,7 @@ pub fn read_hold() {
         end_at: None,
         max_per_user: None,
         quantity: 2,
+        expiry_behavior: None,
         ticket_type_id: event.ticket_types(true, None, database.connection.get()).unwrap()[0].id,
     });
 
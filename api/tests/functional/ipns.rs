@@ -0,0 +1,189 @@
+use actix_web::error::ResponseError;
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::ipns;
+use bigneon_api::domain_events::executors::ProcessPaymentProviderEventExecutor;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use serde_json;
+use stripe;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn post_stripe_event(database: &TestDatabase, body: &str, signature: Option<String>) -> HttpResponse {
+    let headers = match signature {
+        Some(signature) => vec![("Stripe-Signature", signature)],
+        None => Vec::new(),
+    };
+    let test_request = TestRequest::create_with_headers("/ipns/stripe", Vec::new(), headers);
+    match ipns::stripe((
+        test_request.request,
+        body.to_string(),
+        database.connection.clone().into(),
+    )) {
+        Ok(response) => response,
+        Err(e) => e.error_response(),
+    }
+}
+
+fn signed_stripe_event(database: &TestDatabase, event: serde_json::Value) -> HttpResponse {
+    let body = event.to_string();
+    let signature = stripe::signature_header(&body, "whsec_test_secret", Utc::now().timestamp());
+    post_stripe_event(database, &body, Some(signature))
+}
+
+fn process_stripe_event(database: &TestDatabase, event_id: &str) -> PaymentProviderEvent {
+    let connection = database.connection.get();
+    let provider_event = PaymentProviderEvent::find_by_external_id(PaymentProviders::Stripe, event_id, connection)
+        .unwrap()
+        .unwrap();
+    let domain_action = DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection)
+        .unwrap()
+        .into_iter()
+        .find(|a| a.main_table_id == Some(provider_event.id))
+        .unwrap();
+    let request = TestRequest::create();
+    ProcessPaymentProviderEventExecutor::new(request.config.clone())
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+    PaymentProviderEvent::find(provider_event.id, connection).unwrap()
+}
+
+#[test]
+fn stripe_rejects_unsigned_events() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let body = json!({ "id": "evt_1", "type": "charge.refunded", "created": 1, "data": { "object": {} } }).to_string();
+
+    let response = post_stripe_event(&database, &body, None);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let signature = stripe::signature_header(&body, "whsec_other_secret", Utc::now().timestamp());
+    let response = post_stripe_event(&database, &body, Some(signature));
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    assert!(
+        PaymentProviderEvent::find_by_external_id(PaymentProviders::Stripe, "evt_1", connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn stripe_records_events_once() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = json!({ "id": "evt_1", "type": "customer.created", "created": 1, "data": { "object": {} } });
+
+    let response = signed_stripe_event(&database, event.clone());
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = signed_stripe_event(&database, event.clone());
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let provider_event = PaymentProviderEvent::find_by_external_id(PaymentProviders::Stripe, "evt_1", connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(provider_event.event_type, "customer.created");
+    assert_eq!(provider_event.payload, event);
+    let domain_actions =
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(domain_actions[0].main_table_id, Some(provider_event.id));
+
+    // Unhandled event types are only recorded
+    let provider_event = process_stripe_event(&database, "evt_1");
+    assert!(provider_event.processed_at.is_some());
+}
+
+#[test]
+fn stripe_dispute() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let order = database.create_order().for_event(&event).is_paid().finish();
+    let settlement = database.create_settlement().with_organization(&organization).finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let dispute = json!({
+        "id": "dp_1",
+        "charge": "blah",
+        "amount": payment.amount,
+        "currency": "usd",
+        "reason": "fraudulent",
+        "status": "needs_response"
+    });
+
+    signed_stripe_event(
+        &database,
+        json!({ "id": "evt_1", "type": "charge.dispute.created", "created": 1, "data": { "object": dispute } }),
+    );
+    let provider_event = process_stripe_event(&database, "evt_1");
+    assert!(provider_event.processed_at.is_some());
+    assert_eq!(
+        Payment::find(payment.id, connection).unwrap().dispute_status,
+        Some(PaymentDisputeStatus::Open)
+    );
+    let adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].amount_in_cents, payment.amount);
+
+    let mut dispute = dispute;
+    dispute["status"] = json!("lost");
+    signed_stripe_event(
+        &database,
+        json!({ "id": "evt_2", "type": "charge.dispute.closed", "created": 2, "data": { "object": dispute } }),
+    );
+    process_stripe_event(&database, "evt_2");
+    assert_eq!(
+        Payment::find(payment.id, connection).unwrap().dispute_status,
+        Some(PaymentDisputeStatus::Lost)
+    );
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 1);
+}
+
+#[test]
+fn stripe_dashboard_refund() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let order = database.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let charge = json!({
+        "id": "blah",
+        "amount": payment.amount,
+        "amount_refunded": payment.amount,
+        "currency": "usd",
+        "refunded": true
+    });
+
+    signed_stripe_event(
+        &database,
+        json!({ "id": "evt_1", "type": "charge.refunded", "created": 1, "data": { "object": charge } }),
+    );
+    process_stripe_event(&database, "evt_1");
+
+    let refunds: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refunded_payment_id == Some(payment.id))
+        .collect();
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].amount, -payment.amount);
+    let refund = Refund::find(refunds[0].refund_id.unwrap(), connection).unwrap();
+    assert!(refund.manual_override);
+    assert!(!refund.items(connection).unwrap().is_empty());
+    for item in order.items(connection).unwrap() {
+        for ticket in TicketInstance::find_for_order_item(item.id, connection).unwrap() {
+            assert_eq!(ticket.status, TicketInstanceStatus::Available);
+        }
+    }
+}
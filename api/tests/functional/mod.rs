@@ -2,30 +2,47 @@ mod artists;
 mod auth;
 mod base;
 mod broadcast;
+mod capacity_pools;
 mod cart;
+mod code_batches;
 mod codes;
 mod comps;
+mod entry_slots;
+mod event_questions;
 mod event_report_subscribers;
+mod event_reschedules;
+mod event_series;
 mod events;
 mod genres;
 mod holds;
+mod ipns;
 mod notes;
 mod orders;
 mod organization_invites;
 mod organizations;
+mod packages;
 mod password_resets;
 mod payment_methods;
+mod presale_lotteries;
+mod pricing_rules;
+mod products;
 mod redemption_codes;
 mod regions;
 mod reports;
+mod resale_listings;
+mod seating_sections;
 mod settlement_adjustments;
 mod settlements;
 mod sitemap;
 mod slugs;
 mod stages;
+mod store_credits;
+mod tax_rules;
 mod ticket_types;
+mod ticket_exchanges;
 mod tickets;
 mod transfers;
 mod user_invites;
 mod users;
 mod venues;
+mod waitlist_entries;
@@ -12,6 +12,7 @@ use bigneon_api::extractors::Json;
 use bigneon_api::models::PathParameters;
 use bigneon_db::models::*;
 use bigneon_db::schema;
+use bigneon_db::utils::dates;
 use functional::base;
 use support;
 use support::database::TestDatabase;
@@ -552,6 +553,7 @@ pub fn refund_for_non_refundable_tickets() {
         items: refund_items,
         reason: None,
         manual_override: false,
+        to_store_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -640,6 +642,7 @@ pub fn refund_hold_ticket() {
         items: refund_items,
         reason: Some("Purchased by mistake".to_string()),
         manual_override: false,
+        to_store_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -691,3 +694,150 @@ pub fn refund_hold_ticket() {
     assert_eq!(ticket.status, TicketInstanceStatus::Reserved);
     assert_ne!(Some(order_item.id), ticket.order_item_id);
 }
+
+#[test]
+pub fn payment_plan() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                payment_plan_min_total_in_cents: Some(Some(100)),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(180).finish())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let payment_method = database.create_payment_method().with_user(&user).finish();
+    let order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = order.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Order has no payment plan
+    let response: HttpResponse = orders::payment_plan((database.connection.clone(), path, auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let payment_plan = PaymentPlan::create_for_order(&order, payment_method.id, 2, connection).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = order.id;
+    let response: HttpResponse = orders::payment_plan((database.connection.clone(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_payment_plan: DisplayPaymentPlan = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_payment_plan.payment_plan.id, payment_plan.id);
+    assert_eq!(found_payment_plan.amount_paid_in_cents, 0);
+    assert_eq!(found_payment_plan.installments.len(), 2);
+
+    // Other users can't see the payment plan
+    let other_user = database.create_user().finish();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = order.id;
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let response: HttpResponse = orders::payment_plan((database.connection.clone(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+pub fn refund_split_payment() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let mut cart = database
+        .create_cart()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .finish();
+    let total = cart.calculate_total(connection).unwrap();
+    let first_payment = cart
+        .add_external_payment(None, ExternalPaymentType::Cash, user.id, total - 10, connection)
+        .unwrap();
+    let second_payment = cart
+        .add_external_payment(None, ExternalPaymentType::CreditCard, user.id, 10, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    diesel::update(schema::payments::table.filter(schema::payments::id.eq(first_payment.id)))
+        .set(schema::payments::created_at.eq(dates::now().add_minutes(-10).finish()))
+        .execute(connection)
+        .unwrap();
+
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let ticket = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .remove(0);
+    let json = Json(RefundAttributes {
+        items: vec![RefundItemRequest {
+            order_item_id: order_item.id,
+            ticket_instance_id: Some(ticket.id),
+        }],
+        reason: None,
+        manual_override: false,
+        to_store_credit: false,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = cart.id;
+    let response: HttpResponse = orders::refund((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_response: RefundResponse = serde_json::from_str(&body).unwrap();
+    assert!(refund_response.amount_refunded > 10);
+
+    // The most recent payment is refunded first, the rest comes from the earlier payment
+    let refund_payments: Vec<Payment> = cart
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.status == PaymentStatus::Refunded)
+        .collect();
+    assert_eq!(refund_payments.len(), 2);
+    let second_refund = refund_payments
+        .iter()
+        .find(|p| p.refunded_payment_id == Some(second_payment.id))
+        .unwrap();
+    assert_eq!(second_refund.amount, -10);
+    let first_refund = refund_payments
+        .iter()
+        .find(|p| p.refunded_payment_id == Some(first_payment.id))
+        .unwrap();
+    assert_eq!(first_refund.amount, -(refund_response.amount_refunded - 10));
+}
@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::packages::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::packages::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::packages::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::packages::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::packages::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::packages::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::packages::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::packages::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::packages::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod add_ticket_type_tests {
+    use super::*;
+    #[test]
+    fn add_ticket_type_org_member() {
+        base::packages::add_ticket_type(Roles::OrgMember, true);
+    }
+    #[test]
+    fn add_ticket_type_admin() {
+        base::packages::add_ticket_type(Roles::Admin, true);
+    }
+    #[test]
+    fn add_ticket_type_user() {
+        base::packages::add_ticket_type(Roles::User, false);
+    }
+    #[test]
+    fn add_ticket_type_org_owner() {
+        base::packages::add_ticket_type(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn add_ticket_type_door_person() {
+        base::packages::add_ticket_type(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn add_ticket_type_promoter() {
+        base::packages::add_ticket_type(Roles::Promoter, true);
+    }
+    #[test]
+    fn add_ticket_type_promoter_read_only() {
+        base::packages::add_ticket_type(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn add_ticket_type_org_admin() {
+        base::packages::add_ticket_type(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn add_ticket_type_box_office() {
+        base::packages::add_ticket_type(Roles::OrgBoxOffice, false);
+    }
+}
@@ -0,0 +1,43 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::presale_lotteries::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::presale_lotteries::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::presale_lotteries::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::presale_lotteries::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::presale_lotteries::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::presale_lotteries::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::presale_lotteries::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::presale_lotteries::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::presale_lotteries::create(Roles::OrgBoxOffice, false);
+    }
+}
@@ -0,0 +1,43 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::pricing_rules::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::pricing_rules::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::pricing_rules::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::pricing_rules::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::pricing_rules::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::pricing_rules::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::pricing_rules::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::pricing_rules::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::pricing_rules::create(Roles::OrgBoxOffice, false);
+    }
+}
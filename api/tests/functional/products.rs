@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::products::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::products::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::products::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::products::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::products::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::products::create(Roles::Promoter, true);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::products::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::products::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::products::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod redeem_tests {
+    use super::*;
+    #[test]
+    fn redeem_org_member() {
+        base::products::redeem(Roles::OrgMember, true);
+    }
+    #[test]
+    fn redeem_admin() {
+        base::products::redeem(Roles::Admin, true);
+    }
+    #[test]
+    fn redeem_user() {
+        base::products::redeem(Roles::User, false);
+    }
+    #[test]
+    fn redeem_org_owner() {
+        base::products::redeem(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn redeem_door_person() {
+        base::products::redeem(Roles::DoorPerson, true);
+    }
+    #[test]
+    fn redeem_promoter() {
+        base::products::redeem(Roles::Promoter, false);
+    }
+    #[test]
+    fn redeem_promoter_read_only() {
+        base::products::redeem(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn redeem_org_admin() {
+        base::products::redeem(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn redeem_box_office() {
+        base::products::redeem(Roles::OrgBoxOffice, true);
+    }
+}
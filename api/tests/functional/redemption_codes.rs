@@ -150,6 +150,11 @@ fn show_code() {
             end_date,
             max_per_user: max_tickets_per_user,
             available,
+            min_quantity,
+            buy_quantity,
+            get_quantity,
+            order_discount_in_cents,
+            waives_fees,
         } => {
             let user_display_ticket_type = UserDisplayTicketType::from_ticket_type(
                 &ticket_type,
@@ -169,6 +174,11 @@ fn show_code() {
             assert_eq!(code_type, CodeTypes::Discount);
             assert_eq!(available, 30);
             assert_eq!(discount_as_percentage, None);
+            assert_eq!(min_quantity, None);
+            assert_eq!(buy_quantity, None);
+            assert_eq!(get_quantity, None);
+            assert_eq!(order_discount_in_cents, None);
+            assert!(!waives_fees);
         }
         _ => panic!("Expected RedemptionCodeResponse::Code response"),
     }
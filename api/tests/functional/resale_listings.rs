@@ -0,0 +1,100 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::resale_listings;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let user = database.create_user().finish();
+    database
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(resale_listings::NewResaleListingRequest { price_in_cents: 1000 });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse =
+        resale_listings::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let listing: ResaleListing = serde_json::from_str(&body).unwrap();
+    assert_eq!(listing.ticket_instance_id, ticket.id);
+    assert_eq!(listing.event_id, event.id);
+    assert_eq!(listing.seller_user_id, user.id);
+    assert_eq!(listing.price_in_cents, 1000);
+    assert_eq!(listing.status, ResaleListingStatus::Active);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    database
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, event.id, user.id, 1000)
+        .commit(connection)
+        .unwrap();
+
+    // Only the seller can withdraw the listing
+    let auth_user = support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = listing.id;
+    let response: HttpResponse = resale_listings::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = listing.id;
+    let response: HttpResponse = resale_listings::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Cancelled);
+}
@@ -0,0 +1,84 @@
+use bigneon_db::models::*;
+use functional::base;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::seating_sections::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::seating_sections::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::seating_sections::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::seating_sections::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::seating_sections::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::seating_sections::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::seating_sections::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::seating_sections::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::seating_sections::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_seats_tests {
+    use super::*;
+    #[test]
+    fn create_seats_org_member() {
+        base::seating_sections::create_seats(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_seats_admin() {
+        base::seating_sections::create_seats(Roles::Admin, true);
+    }
+    #[test]
+    fn create_seats_user() {
+        base::seating_sections::create_seats(Roles::User, false);
+    }
+    #[test]
+    fn create_seats_org_owner() {
+        base::seating_sections::create_seats(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_seats_door_person() {
+        base::seating_sections::create_seats(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_seats_promoter() {
+        base::seating_sections::create_seats(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_seats_promoter_read_only() {
+        base::seating_sections::create_seats(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_seats_org_admin() {
+        base::seating_sections::create_seats(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_seats_box_office() {
+        base::seating_sections::create_seats(Roles::OrgBoxOffice, false);
+    }
+}
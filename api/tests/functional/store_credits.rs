@@ -0,0 +1,152 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::cart::{self, PaymentRequest};
+use bigneon_api::controllers::store_credits::{self, RedeemGiftCardRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::*;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn buy_gift_card(database: &TestDatabase, event: &Event) -> ProductInstance {
+    let connection = database.connection.get();
+    let product = Product::create(event.organization_id, None, "Gift card".to_string(), None, 5000)
+        .as_gift_card()
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Digital".to_string(), "GIFT-50".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+    let purchaser = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&purchaser, connection).unwrap();
+    cart.update_product_quantities(
+        purchaser.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: Some(event.id),
+            quantity: 1,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        purchaser.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    ProductInstance::find_for_user_for_display(purchaser.id, connection)
+        .unwrap()
+        .into_iter()
+        .map(|p| ProductInstance::find(p.id, connection).unwrap())
+        .next()
+        .unwrap()
+}
+
+#[test]
+fn redeem_gift_card() {
+    let database = TestDatabase::new();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let gift_card = buy_gift_card(&database, &event);
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse = store_credits::redeem_gift_card((
+        database.connection.clone().into(),
+        Json(RedeemGiftCardRequest {
+            redeem_key: gift_card.redeem_key.clone(),
+        }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let store_credit: DisplayStoreCredit = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        store_credit.balances,
+        vec![StoreCreditBalance {
+            currency: "USD".to_string(),
+            balance_in_cents: 5000,
+        }]
+    );
+    assert_eq!(store_credit.transactions.len(), 1);
+
+    let response: HttpResponse = store_credits::show((database.connection.clone().into(), auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let shown: DisplayStoreCredit = serde_json::from_str(&body).unwrap();
+    assert_eq!(shown, store_credit);
+
+    // Gift cards can only be redeemed once
+    let response: HttpResponse = store_credits::redeem_gift_card((
+        database.connection.clone().into(),
+        Json(RedeemGiftCardRequest {
+            redeem_key: gift_card.redeem_key.clone(),
+        }),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn checkout_with_store_credit() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let gift_card = buy_gift_card(&database, &event);
+    let user = database.create_user().finish();
+    ProductInstance::redeem_gift_card(&gift_card.redeem_key, user.id, connection).unwrap();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Store credit can't be combined with free or external checkouts
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            answers: Vec::new(),
+            store_credit_in_cents: Some(100),
+            installment_count: None,
+            amount_in_cents: None,
+            method: PaymentRequest::Free,
+        }),
+        auth_user.clone(),
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            tracking_data: None,
+            answers: Vec::new(),
+            store_credit_in_cents: None,
+            installment_count: None,
+            amount_in_cents: None,
+            method: PaymentRequest::StoreCredit,
+        }),
+        auth_user,
+        TestRequest::create().extract_state(),
+        RequestInfo { user_agent: None },
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].payment_method, PaymentMethods::StoreCredit);
+    assert_eq!(payments[0].amount, total);
+    assert_eq!(
+        StoreCreditTransaction::balance_for_user(user.id, "USD", connection).unwrap(),
+        5000 - total
+    );
+}
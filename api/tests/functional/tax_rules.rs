@@ -0,0 +1,112 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rules::{self, CreateTaxRuleRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn create_request() -> CreateTaxRuleRequest {
+    CreateTaxRuleRequest {
+        name: "GST".to_string(),
+        rate_percent: 5.0,
+        inclusive: false,
+        taxes_fees: true,
+    }
+}
+
+#[test]
+fn create_for_region() {
+    let database = TestDatabase::new();
+    let region = database.create_region().finish();
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = region.id;
+
+    let user = support::create_auth_user(Roles::User, None, &database);
+    let response: HttpResponse =
+        tax_rules::create_for_region((database.connection.clone().into(), path, Json(create_request()), user)).into();
+    support::expects_unauthorized(&response);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = region.id;
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let response: HttpResponse =
+        tax_rules::create_for_region((database.connection.clone().into(), path, Json(create_request()), user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rule.region_id, Some(region.id));
+    assert_eq!(tax_rule.rate_percent, 5.0);
+    assert!(tax_rule.taxes_fees);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = region.id;
+    let response: HttpResponse = tax_rules::index_for_region((database.connection.clone().into(), path)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found: Vec<TaxRule> = serde_json::from_str(&body).unwrap();
+    assert_eq!(found, vec![tax_rule]);
+}
+
+#[test]
+fn create_for_venue() {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = venue.id;
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+
+    let mut json = create_request();
+    json.rate_percent = 120.0;
+    let response: HttpResponse =
+        tax_rules::create_for_venue((database.connection.clone().into(), path, Json(json), user.clone())).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = venue.id;
+    let response: HttpResponse =
+        tax_rules::create_for_venue((database.connection.clone().into(), path, Json(create_request()), user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rule.venue_id, Some(venue.id));
+}
+
+#[test]
+fn update_and_destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let region = database.create_region().finish();
+    let tax_rule = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let test_request = TestRequest::create();
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+    let response: HttpResponse = tax_rules::update((
+        database.connection.clone().into(),
+        path,
+        Json(TaxRuleEditableAttributes {
+            inclusive: Some(true),
+            ..Default::default()
+        }),
+        user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated: TaxRule = serde_json::from_str(&body).unwrap();
+    assert!(updated.inclusive);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+    let response: HttpResponse = tax_rules::destroy((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(TaxRule::find_for_region(region.id, connection).unwrap().is_empty());
+}
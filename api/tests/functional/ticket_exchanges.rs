@@ -0,0 +1,125 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::ticket_exchanges::{self, NewTicketExchangeRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use serde_json::Value;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::ticket_exchanges::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::ticket_exchanges::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_super() {
+        base::ticket_exchanges::create(Roles::Super, true);
+    }
+    #[test]
+    fn create_user() {
+        base::ticket_exchanges::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::ticket_exchanges::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::ticket_exchanges::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_promoter() {
+        base::ticket_exchanges::create(Roles::Promoter, false);
+    }
+    #[test]
+    fn create_promoter_read_only() {
+        base::ticket_exchanges::create(Roles::PromoterReadOnly, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::ticket_exchanges::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::ticket_exchanges::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_as_ticket_holder() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_types[0].id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let ticket = &cart.tickets(Some(ticket_types[0].id), connection).unwrap()[0];
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(NewTicketExchangeRequest {
+        ticket_type_id: ticket_types[1].id,
+        token: None,
+        provider: None,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket.id;
+    let response: HttpResponse = ticket_exchanges::create((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state(),
+    ))
+    .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(result["price_difference_in_cents"], json!(0));
+    let new_order = TicketExchange::find_for_order(cart.id, connection).unwrap()[0]
+        .new_order(connection)
+        .unwrap();
+    assert_eq!(result["order"]["id"], json!(new_order.id));
+    assert_eq!(new_order.status, OrderStatus::Paid);
+    assert_eq!(
+        new_order.tickets(Some(ticket_types[1].id), connection).unwrap().len(),
+        1
+    );
+}
@@ -240,6 +240,7 @@ pub fn create_with_validation_errors() {
         start_date: start_date2,
         end_date: end_date2,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -294,6 +295,7 @@ pub fn create_with_validation_errors_on_ticket_pricing() {
         start_date: start_date2,
         end_date: end_date2,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -347,6 +349,7 @@ pub fn create_with_overlapping_periods() {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -354,6 +357,7 @@ pub fn create_with_overlapping_periods() {
         start_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -413,6 +417,7 @@ pub fn create_with_out_of_bounds_ticket_capacity() {
         start_date,
         end_date: middle_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     ticket_pricing.push(CreateTicketPricingRequest {
         name: String::from("Base"),
@@ -420,6 +425,7 @@ pub fn create_with_out_of_bounds_ticket_capacity() {
         start_date: middle_date,
         end_date,
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = CreateTicketTypeRequest {
         name: "VIP".into(),
@@ -479,6 +485,7 @@ pub fn update_with_invalid_id() {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -547,6 +554,7 @@ pub fn update_with_validation_errors() {
         end_date: end_date2,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -623,6 +631,7 @@ pub fn update_with_validation_errors_on_ticket_pricing() {
         end_date: end_date2,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
@@ -700,6 +709,7 @@ pub fn update_with_overlapping_periods() {
         end_date,
         price_in_cents: Some(20000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     request_ticket_pricing.push(UpdateTicketPricingRequest {
         id: None,
@@ -708,6 +718,7 @@ pub fn update_with_overlapping_periods() {
         end_date: middle_date,
         price_in_cents: Some(15000),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     });
     let request_data = UpdateTicketTypeRequest {
         name: Some("Updated VIP".into()),
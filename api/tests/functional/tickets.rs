@@ -131,6 +131,11 @@ pub fn index() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
+        entry_start: None,
+        entry_end: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -157,6 +162,11 @@ pub fn index() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
+        entry_start: None,
+        entry_end: None,
     };
     assert_eq!(
         vec![
@@ -269,6 +279,11 @@ pub fn show() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
+        entry_start: None,
+        entry_end: None,
     };
 
     let expected_result = ShowTicketResponse {
@@ -0,0 +1,66 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::waitlist_entries;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database.create_order().for_event(&event).quantity(1).is_paid().finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let json = Json(waitlist_entries::JoinWaitlistRequest { quantity: Some(1) });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket_type.id;
+    let response: HttpResponse =
+        waitlist_entries::create((database.connection.clone().into(), path, json, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let waitlist_entry: WaitlistEntry = serde_json::from_str(&body).unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database.create_order().for_event(&event).quantity(1).is_paid().finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = ticket_type.id;
+    let response: HttpResponse =
+        waitlist_entries::destroy((database.connection.clone().into(), path, auth_user)).into();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+}
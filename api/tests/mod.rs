@@ -19,6 +19,7 @@ extern crate serde_derive;
 extern crate globee;
 extern crate jsonwebtoken as jwt;
 extern crate serde;
+extern crate stripe;
 extern crate uuid;
 extern crate validator;
 
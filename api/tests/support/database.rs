@@ -130,6 +130,10 @@ impl TestDatabase {
         StageBuilder::new(self.connection.get())
     }
 
+    pub fn create_seating_section(&self) -> SeatingSectionBuilder {
+        SeatingSectionBuilder::new(self.connection.get())
+    }
+
     pub fn create_settlement_entry(&self) -> SettlementEntryBuilder {
         SettlementEntryBuilder::new(self.connection.get())
     }
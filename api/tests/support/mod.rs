@@ -1,4 +1,5 @@
 pub mod database;
+pub mod stripe_mock;
 pub mod test_request;
 
 use actix_web::{http::StatusCode, Body::Binary, HttpResponse};
@@ -21,11 +21,41 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
+        TestRequest::create_with_headers(path, params, Vec::new())
+    }
+
+    pub fn create_with_headers(
+        path: &str,
+        params: Vec<&'static str>,
+        headers: Vec<(&'static str, String)>,
+    ) -> TestRequest {
+        TestRequest::create_with_config(path, params, headers, TestRequest::config())
+    }
+
+    /// Takes Stripe payments with payment intents against a mock of the Stripe API
+    pub fn create_with_stripe(stripe_base_url: String) -> TestRequest {
+        let mut config = TestRequest::config();
+        config.stripe_base_url = stripe_base_url;
+        config.stripe_payment_intents = true;
+        TestRequest::create_with_config("/", vec!["id"], Vec::new(), config)
+    }
+
+    fn config() -> Config {
         let mut config = Config::new(Environment::Test);
         config.token_secret = "test_secret".into();
         config.token_issuer = "bn-api-test".into();
         config.api_keys_encryption_key = "test_encryption_key".to_string();
         config.google_recaptcha_secret_key = None;
+        config.stripe_webhook_secret = Some("whsec_test_secret".to_string());
+        config
+    }
+
+    fn create_with_config(
+        path: &str,
+        params: Vec<&'static str>,
+        headers: Vec<(&'static str, String)>,
+        config: Config,
+    ) -> TestRequest {
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
@@ -50,6 +80,10 @@ impl TestRequest {
             request = request.param(param, "0f85443e-9e70-45ba-bf28-0f59c183856f");
         }
 
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
         TestRequest {
             request: request.finish(),
             config,
@@ -1,3 +1,4 @@
 pub mod helpers;
 pub mod mailers;
 pub mod models;
+pub mod stripe_client;
@@ -0,0 +1,132 @@
+use stripe::{PaymentIntentStatus, StripeClient};
+use support::stripe_mock::mock_stripe;
+
+const REQUIRES_ACTION: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "eur",
+    "status": "requires_action",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": {"type": "use_stripe_sdk", "use_stripe_sdk": {"type": "three_d_secure_redirect"}}
+}"#;
+const REQUIRES_CAPTURE: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "eur",
+    "status": "requires_capture",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": null
+}"#;
+const SUCCEEDED: &str = r#"{
+    "id": "pi_1GAbc",
+    "object": "payment_intent",
+    "amount": 1500,
+    "currency": "eur",
+    "status": "succeeded",
+    "client_secret": "pi_1GAbc_secret_xyz",
+    "next_action": null
+}"#;
+
+#[test]
+fn create_payment_intent_requiring_action() {
+    let (base_url, server) = mock_stripe(vec![(200, REQUIRES_ACTION)]);
+    let client = StripeClient::new("sk_test".to_string(), base_url);
+
+    let payment_intent = client
+        .create_payment_intent(
+            "tok_visa",
+            1500,
+            "eur",
+            "Big Neon Tickets",
+            false,
+            vec![("order_id".to_string(), "1234".to_string())],
+        )
+        .unwrap();
+    assert_eq!(payment_intent.id, "pi_1GAbc");
+    assert_eq!(payment_intent.status, PaymentIntentStatus::RequiresAction);
+    assert_eq!(payment_intent.client_secret, Some("pi_1GAbc_secret_xyz".to_string()));
+    assert_eq!(
+        payment_intent.next_action.unwrap()["type"].as_str(),
+        Some("use_stripe_sdk")
+    );
+    assert_eq!(payment_intent.to_json(), REQUIRES_ACTION);
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("POST /v1/payment_intents "));
+    assert!(requests[0].contains("amount=1500"));
+    assert!(requests[0].contains("capture_method=manual"));
+    assert!(requests[0].contains("confirmation_method=manual"));
+    assert!(requests[0].contains("confirm=true"));
+    assert!(requests[0].contains("payment_method_data%5Bcard%5D%5Btoken%5D=tok_visa"));
+    assert!(requests[0].contains("metadata%5Border_id%5D=1234"));
+    assert!(!requests[0].contains("off_session"));
+}
+
+#[test]
+fn confirm_and_capture_payment_intent() {
+    let (base_url, server) = mock_stripe(vec![(200, REQUIRES_CAPTURE), (200, SUCCEEDED)]);
+    let client = StripeClient::new("sk_test".to_string(), base_url);
+
+    let payment_intent = client.confirm_payment_intent("pi_1GAbc").unwrap();
+    assert_eq!(payment_intent.status, PaymentIntentStatus::RequiresCapture);
+    assert_eq!(payment_intent.next_action, None);
+    let payment_intent = client.capture_payment_intent("pi_1GAbc").unwrap();
+    assert_eq!(payment_intent.status, PaymentIntentStatus::Succeeded);
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests,
+        vec![
+            "POST /v1/payment_intents/pi_1GAbc/confirm ".to_string(),
+            "POST /v1/payment_intents/pi_1GAbc/capture ".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn create_off_session_payment_intent_requiring_authentication() {
+    let (base_url, server) = mock_stripe(vec![(
+        402,
+        r#"{
+            "error": {
+                "code": "authentication_required",
+                "message": "This payment requires authentication.",
+                "type": "card_error",
+                "payment_intent": {"id": "pi_1GAbc", "status": "requires_payment_method"}
+            }
+        }"#,
+    )]);
+    let client = StripeClient::new("sk_test".to_string(), base_url);
+
+    let error = client
+        .create_payment_intent("cus_1GAbc", 1500, "eur", "Big Neon Tickets", true, Vec::new())
+        .unwrap_err();
+    assert_eq!(error.error_code, Some("authentication_required".to_string()));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].contains("customer=cus_1GAbc"));
+    assert!(requests[0].contains("off_session=true"));
+    assert!(!requests[0].contains("confirmation_method"));
+}
+
+#[test]
+fn refund_payment_intent() {
+    let refund = r#"{"id": "re_1GAbc", "object": "refund", "amount": 500}"#;
+    let (base_url, server) = mock_stripe(vec![(200, refund), (200, refund)]);
+    let client = StripeClient::new("sk_test".to_string(), base_url);
+
+    assert_eq!(client.partial_refund("pi_1GAbc", 500).unwrap().id, "re_1GAbc");
+    assert_eq!(client.refund("ch_1GAbc").unwrap().id, "re_1GAbc");
+
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests,
+        vec![
+            "POST /v1/refunds payment_intent=pi_1GAbc&amount=500".to_string(),
+            "POST /v1/refunds charge=ch_1GAbc".to_string(),
+        ]
+    );
+}
@@ -6,8 +6,15 @@ CREATE OR REPLACE FUNCTION process_settlement_for_event(settlement_id UUID, even
 DECLARE
   -- Override to handle swapping from rolling to post event in scenarios where settlements were manually processed prior (initial launch)
   start_override timestamp;
+  -- Only orders in the settlement's currency can be paid out in it
+  settlement_currency text;
 BEGIN
 
+SELECT s.currency
+FROM settlements s
+WHERE s.id = $1
+INTO settlement_currency;
+
 SELECT CASE WHEN s.only_finished_events = true THEN NULL ELSE s.start_time END
 FROM settlements s
 JOIN organizations o ON o.id = s.organization_id
@@ -36,32 +43,42 @@ AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
 AND o.settlement_id IS NULL
 AND o.status = 'Paid'
+AND o.currency = settlement_currency
 AND oi.parent_id IS NULL
-AND o.box_office_pricing IS FALSE;
+AND o.box_office_pricing IS FALSE
+-- Orders on a payment plan are paid once the deposit clears, they are settled when the plan is paid in full
+AND NOT EXISTS (SELECT 1 FROM payment_plans pp WHERE pp.order_id = o.id AND pp.status = 'Active');
 
 -- Add refund items to the order items temp table
+-- Tax on fees is a child of the fee so refunded items are grouped under their top level item
 INSERT INTO order_item_ids(id, refund_id)
-SELECT DISTINCT COALESCE(oi.parent_id, oi.id), r.id
+SELECT DISTINCT oi_root.id, r.id
 FROM refunds r
 INNER JOIN refund_items ri ON ri.refund_id = r.id
 INNER JOIN order_items oi ON oi.id = ri.order_item_id
+LEFT JOIN order_items oi_parent ON oi_parent.id = oi.parent_id
+INNER JOIN order_items oi_root ON oi_root.id = COALESCE(oi_parent.parent_id, oi.parent_id, oi.id)
 INNER JOIN orders o on oi.order_id = o.id
 WHERE oi.event_id = $2
-AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
-AND oi.item_type <> 'CreditCardFees'
+AND (oi_root.item_type <> 'EventFees' OR oi_root.client_fee_in_cents > 0)
+AND oi_root.item_type <> 'CreditCardFees'
 AND (start_override IS NULL OR r.created_at >= start_override)
 AND ($3 IS NULL OR r.created_at >= $3)
 AND ($4 IS NULL OR r.created_at <= $4)
 AND o.settlement_id is distinct from $1
+AND o.currency = settlement_currency
 AND ri.amount > 0
+-- Discount and tax adjusted on the tickets left on an order are added separately below
+AND ri.quantity > 0
 AND r.settlement_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
-INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, product_variant_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
 SELECT -- Group result set by face price to prevent multiple records for holds that match code discounts
   entries.settlement_id,
   entries.event_id,
   entries.ticket_type_id,
+  entries.product_variant_id,
   entries.face_value_in_cents,
   entries.revenue_share_value_in_cents,
   SUM(online_sold_quantity),
@@ -73,9 +90,15 @@ FROM (
     $1 as settlement_id,
     oi.event_id,
     oi.ticket_type_id,
-    CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(oi.unit_price_in_cents + COALESCE(oi_promo_code.unit_price_in_cents, 0) AS BIGINT) END as face_value_in_cents,
+    oi.product_variant_id,
+    -- Resale face value is owed to the seller and paid out through resale_listings rather than to the organization
+    -- Inclusive tax is part of the price paid, it is taken out of the face value and listed on its own Tax line
+    CASE oi.item_type WHEN 'EventFees' THEN 0 WHEN 'ResaleTickets' THEN 0 ELSE CAST(oi.unit_price_in_cents + COALESCE(oi_promo_code.unit_price_in_cents, 0) - COALESCE(oi_inclusive_tax.tax_in_cents, 0) AS BIGINT) END as face_value_in_cents,
     -- Event fees record list the fee as part of the revenue share for that item with 0 face value
-    CASE oi.item_type WHEN 'EventFees' THEN CAST(oi.client_fee_in_cents AS BIGINT) ELSE CAST(COALESCE(oi_t_fees.client_fee_in_cents, 0) AS BIGINT) END as revenue_share_value_in_cents,
+    CASE oi.item_type
+      WHEN 'EventFees' THEN CAST(oi.client_fee_in_cents - COALESCE(oi_inclusive_tax.tax_in_cents, 0) AS BIGINT)
+      ELSE CAST(COALESCE(oi_t_fees.client_fee_in_cents, 0) - COALESCE(oi_t_fees_inclusive_tax.tax_in_cents, 0) AS BIGINT)
+    END as revenue_share_value_in_cents,
     -- Event fees list their quantity in the fee_sold_quantity field
     CASE oi.item_type
       WHEN 'EventFees' THEN 0
@@ -100,7 +123,7 @@ FROM (
           CAST(SUM(COALESCE(oi_t_fees.quantity, 0)) AS BIGINT)
         END
     END as fee_sold_quantity,
-    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type
+    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' WHEN 'ResaleTickets' THEN 'Resale' WHEN 'Products' THEN 'Product' ELSE 'TicketType' END as settlement_entry_type
   FROM order_items oi
   INNER JOIN order_item_ids oi_ids ON oi.id = oi_ids.id
   INNER JOIN orders o ON oi.order_id = o.id
@@ -108,21 +131,89 @@ FROM (
   LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
   LEFT JOIN order_items oi_t_fees ON oi_t_fees.parent_id = oi.id AND oi_t_fees.item_type = 'PerUnitFees'
   LEFT JOIN refund_items oi_t_fees_r ON oi_t_fees_r.order_item_id = oi_t_fees.id AND oi_t_fees_r.refund_id = oi_ids.refund_id
+  -- Inclusive tax items have no unit price of their own, only the tax included per unit
+  LEFT JOIN (
+    SELECT parent_id, SUM(tax_in_cents) AS tax_in_cents FROM order_items WHERE item_type = 'Tax' AND unit_price_in_cents = 0 GROUP BY parent_id
+  ) oi_inclusive_tax ON oi_inclusive_tax.parent_id = oi.id
+  LEFT JOIN (
+    SELECT parent_id, SUM(tax_in_cents) AS tax_in_cents FROM order_items WHERE item_type = 'Tax' AND unit_price_in_cents = 0 GROUP BY parent_id
+  ) oi_t_fees_inclusive_tax ON oi_t_fees_inclusive_tax.parent_id = oi_t_fees.id
   GROUP BY
     oi.item_type,
     oi.event_id,
     oi.ticket_type_id,
+    oi.product_variant_id,
     oi.unit_price_in_cents,
     oi.client_fee_in_cents,
     oi_t_fees.client_fee_in_cents,
     oi_promo_code.unit_price_in_cents,
+    oi_inclusive_tax.tax_in_cents,
+    oi_t_fees_inclusive_tax.tax_in_cents,
     oi_t_fees_r.quantity,
     oi_r.quantity
+  UNION ALL
+  -- Tax collected on the included items is passed on to the organization to remit, inclusive tax
+  -- has no unit price of its own so the tax included per unit is used for both
+  SELECT
+    $1 as settlement_id,
+    oi_tax.event_id,
+    NULL as ticket_type_id,
+    NULL as product_variant_id,
+    CAST(oi_tax.tax_in_cents AS BIGINT) as face_value_in_cents,
+    CAST(0 AS BIGINT) as revenue_share_value_in_cents,
+    CASE WHEN oi_ids.refund_id IS NOT NULL THEN
+      CAST(-COALESCE(oi_tax_r.quantity, 0) AS BIGINT)
+    ELSE
+      CAST(oi_tax.quantity AS BIGINT)
+    END as online_sold_quantity,
+    CAST(0 AS BIGINT) as fee_sold_quantity,
+    'Tax' as settlement_entry_type
+  FROM order_item_ids oi_ids
+  INNER JOIN order_items oi_tax ON oi_tax.item_type = 'Tax'
+    AND (oi_tax.parent_id = oi_ids.id OR oi_tax.parent_id IN (SELECT id FROM order_items WHERE parent_id = oi_ids.id))
+  LEFT JOIN refund_items oi_tax_r ON oi_tax_r.order_item_id = oi_tax.id AND oi_tax_r.refund_id = oi_ids.refund_id
+  UNION ALL
+  -- Cents of a discount that do not divide evenly over an item's tickets are taken off by a remainder under the discount
+  SELECT
+    $1 as settlement_id,
+    oi.event_id,
+    oi.ticket_type_id,
+    NULL as product_variant_id,
+    CAST(-oi_remainder.unit_price_in_cents AS BIGINT) as face_value_in_cents,
+    CAST(0 AS BIGINT) as revenue_share_value_in_cents,
+    CAST(-oi_remainder.quantity AS BIGINT) as online_sold_quantity,
+    CAST(0 AS BIGINT) as fee_sold_quantity,
+    'TicketType' as settlement_entry_type
+  FROM order_item_ids oi_ids
+  INNER JOIN order_items oi ON oi.id = oi_ids.id
+  INNER JOIN order_items oi_promo_code ON oi_promo_code.item_type = 'Discount' AND oi_promo_code.parent_id = oi.id
+  INNER JOIN order_items oi_remainder ON oi_remainder.item_type = 'Discount' AND oi_remainder.parent_id = oi_promo_code.id
+  WHERE oi_ids.refund_id IS NULL
+  UNION ALL
+  -- Discount taken back from the tickets left on an order by a refund is kept by the organization along with the tax
+  -- on it, discount returned to them is paid back. These are refund items without a quantity.
+  SELECT
+    $1 as settlement_id,
+    oi_adjusted.event_id,
+    CASE oi_adjusted.item_type WHEN 'Tax' THEN NULL ELSE oi.ticket_type_id END as ticket_type_id,
+    NULL as product_variant_id,
+    CAST(ABS(ri.amount) AS BIGINT) as face_value_in_cents,
+    CAST(0 AS BIGINT) as revenue_share_value_in_cents,
+    CAST(CASE WHEN ri.amount < 0 THEN 1 ELSE -1 END AS BIGINT) as online_sold_quantity,
+    CAST(0 AS BIGINT) as fee_sold_quantity,
+    CASE oi_adjusted.item_type WHEN 'Tax' THEN 'Tax' ELSE 'TicketType' END as settlement_entry_type
+  FROM refund_items ri
+  INNER JOIN order_items oi_adjusted ON oi_adjusted.id = ri.order_item_id AND oi_adjusted.item_type IN ('Discount', 'Tax')
+  INNER JOIN order_items oi ON oi.id = oi_adjusted.parent_id
+  WHERE ri.quantity = 0
+  AND oi_adjusted.event_id = $2
+  AND ri.refund_id IN (SELECT refund_id FROM order_item_ids WHERE refund_id IS NOT NULL)
 ) entries
   GROUP BY
     entries.settlement_id,
     entries.event_id,
     entries.ticket_type_id,
+    entries.product_variant_id,
     entries.face_value_in_cents,
     entries.revenue_share_value_in_cents,
     entries.settlement_entry_type
@@ -150,6 +241,14 @@ WHERE refunds.id = oi_ids.refund_id
 AND refunds.settlement_id IS NULL
 AND oi_ids.refund_id IS NOT NULL;
 
+-- Pay out sellers for resale listings sold in orders included in this settlement
+UPDATE resale_listings SET settlement_id = $1
+FROM order_item_ids oi_ids
+WHERE resale_listings.order_item_id = oi_ids.id
+AND resale_listings.status = 'Sold'
+AND resale_listings.settlement_id IS NULL
+AND oi_ids.refund_id IS NULL;
+
 DROP TABLE order_item_ids;
 
 END $$ LANGUAGE 'plpgsql';
@@ -45,8 +45,8 @@ CREATE OR REPLACE FUNCTION ticket_sales_per_ticket_pricing(start TIMESTAMP, "end
                 per_order_company_online_fees      BIGINT,
                 per_order_client_online_fees       BIGINT,
                 per_order_total_fees_in_cents      BIGINT,
-                user_count                         BIGINT
-
+                user_count                         BIGINT,
+                currency                           TEXT
             )
 AS
 $body$
@@ -64,7 +64,7 @@ SELECT e.organization_id
        tp.price_in_cents                                                                                        AS ticket_pricing_price_in_cents,
        CAST(CASE
                 WHEN gh.hold_type = 'Comp' THEN -tp.price_in_cents
-                WHEN gh.hold_type = 'Discount' THEN -LEAST(gh.discount_in_cents, tp.price_in_cents)
+                WHEN gh.hold_type = 'Discount' AND c.id IS NULL THEN -LEAST(gh.discount_in_cents, tp.price_in_cents)
                 ELSE oi_promo_code_price.unit_price_in_cents END
            AS BIGINT)                                                                                           AS promo_code_discounted_ticket_price,
        -- Order count
@@ -75,6 +75,9 @@ SELECT e.organization_id
                      (oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity))
                          + (COALESCE(oi_promo_code.unit_price_in_cents, 0) *
                             (COALESCE(oi_promo_code.quantity, 0) - COALESCE(oi_promo_code.refunded_quantity, 0)))
+                         + (COALESCE(oi_promo_code_remainder.unit_price_in_cents, 0) *
+                            COALESCE(oi_promo_code_remainder.quantity, 0))
+                         - COALESCE(oi_promo_code_adjustments.amount, 0)
                          )
                      FILTER (WHERE o.box_office_pricing IS TRUE),
                      0) AS BIGINT)                                                                              AS box_office_sales_in_cents,
@@ -82,6 +85,9 @@ SELECT e.organization_id
                      (oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity))
                          + (COALESCE(oi_promo_code.unit_price_in_cents, 0) *
                             (COALESCE(oi_promo_code.quantity, 0) - COALESCE(oi_promo_code.refunded_quantity, 0)))
+                         + (COALESCE(oi_promo_code_remainder.unit_price_in_cents, 0) *
+                            COALESCE(oi_promo_code_remainder.quantity, 0))
+                         - COALESCE(oi_promo_code_adjustments.amount, 0)
                          )
                      FILTER (WHERE o.box_office_pricing IS FALSE),
                      0) AS BIGINT)                                                                              AS online_sales_in_cents,
@@ -149,11 +155,22 @@ SELECT e.organization_id
        CAST(0 AS BIGINT)                                                                                        AS per_order_client_online_fees,
        CAST(0 AS BIGINT)                                                                                        AS per_order_total_fees_in_cents,
        --End of injected values
-       CAST(COUNT(DISTINCT (COALESCE(o.on_behalf_of_user_id, o.user_id))) AS BIGINT)                            AS user_count
+       CAST(COUNT(DISTINCT (COALESCE(o.on_behalf_of_user_id, o.user_id))) AS BIGINT)                            AS user_count,
+       -- Amounts in different currencies can't be added together
+       o.currency                                                                                               AS currency
 
 FROM order_items oi
          LEFT JOIN order_items oi_promo_code
                    ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+         -- Cents of the discount that do not divide evenly over the tickets
+         LEFT JOIN order_items oi_promo_code_remainder
+                   ON (oi_promo_code_remainder.item_type = 'Discount' AND oi_promo_code.id = oi_promo_code_remainder.parent_id)
+         -- Discount taken back from, or returned to, the tickets left on the order by refunds
+         LEFT JOIN (SELECT ri.order_item_id, SUM(ri.amount) AS amount
+                    FROM refund_items ri
+                    WHERE ri.quantity = 0
+                    GROUP BY ri.order_item_id) AS oi_promo_code_adjustments
+                   ON oi_promo_code_adjustments.order_item_id = oi_promo_code.id
          LEFT JOIN (SELECT oi_promo_code_price.unit_price_in_cents,
                            oi_promo_code_price.item_type,
                            oi_promo_code_price.parent_id
@@ -184,7 +201,7 @@ WHERE oi.ticket_type_id IS NOT NULL
   AND ($1 IS NULL OR o.paid_at >= $1)
   AND ($2 IS NULL OR o.paid_at <= $2)
 GROUP BY e.id, e.event_start, tt.id, tt.name, tt.status, tt.rank, tp.name, tp.price_in_cents, gh.id, gh.name, gh.hold_type, oi_t_fees.client_fee_in_cents,
-         gh.discount_in_cents, c.id, c.name, c.redemption_code, oi_promo_code_price.unit_price_in_cents
-ORDER BY e.id, tt.rank, c.redemption_code, (tp.price_in_cents - gh.discount_in_cents) DESC, oi_t_fees.client_fee_in_cents;
+         gh.discount_in_cents, c.id, c.name, c.redemption_code, oi_promo_code_price.unit_price_in_cents, o.currency
+ORDER BY e.id, o.currency, tt.rank, c.redemption_code, (tp.price_in_cents - gh.discount_in_cents) DESC, oi_t_fees.client_fee_in_cents;
 $body$
     LANGUAGE SQL;
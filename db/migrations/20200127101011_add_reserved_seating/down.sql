@@ -0,0 +1,15 @@
+DROP INDEX IF EXISTS index_ticket_instances_seat_id;
+DROP INDEX IF EXISTS index_ticket_types_seating_section_id;
+
+ALTER TABLE ticket_instances
+    DROP seat_id;
+
+ALTER TABLE ticket_types
+    DROP seating_section_id;
+
+DROP INDEX IF EXISTS index_seats_seating_section_id_row_rank_seat_rank;
+DROP INDEX IF EXISTS index_seats_seating_section_id_row_name_seat_number;
+DROP TABLE IF EXISTS seats;
+
+DROP INDEX IF EXISTS index_seating_sections_stage_id_name;
+DROP TABLE IF EXISTS seating_sections;
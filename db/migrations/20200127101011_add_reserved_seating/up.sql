@@ -0,0 +1,36 @@
+CREATE TABLE seating_sections
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    stage_id   UUID                                       NOT NULL REFERENCES stages (id),
+    name       TEXT                                       NOT NULL,
+    rank       INT                                        NOT NULL DEFAULT 0,
+    created_at TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_seating_sections_stage_id_name ON seating_sections (stage_id, name);
+
+CREATE TABLE seats
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    seating_section_id UUID                                       NOT NULL REFERENCES seating_sections (id),
+    row_name           TEXT                                       NOT NULL,
+    seat_number        TEXT                                       NOT NULL,
+    row_rank           INT                                        NOT NULL DEFAULT 0,
+    seat_rank          INT                                        NOT NULL DEFAULT 0,
+    accessible         BOOLEAN                                    NOT NULL DEFAULT FALSE,
+    created_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_seats_seating_section_id_row_name_seat_number ON seats (seating_section_id, row_name, seat_number);
+CREATE INDEX index_seats_seating_section_id_row_rank_seat_rank ON seats (seating_section_id, row_rank, seat_rank);
+
+ALTER TABLE ticket_types
+    ADD seating_section_id UUID NULL REFERENCES seating_sections (id);
+
+ALTER TABLE ticket_instances
+    ADD seat_id UUID NULL REFERENCES seats (id);
+
+CREATE INDEX index_ticket_types_seating_section_id ON ticket_types (seating_section_id);
+CREATE INDEX index_ticket_instances_seat_id ON ticket_instances (seat_id);
@@ -0,0 +1,11 @@
+DROP TABLE IF EXISTS resale_listings;
+
+ALTER TABLE fee_schedules
+    DROP resale_client_fee_percent;
+ALTER TABLE fee_schedules
+    DROP resale_company_fee_percent;
+
+ALTER TABLE events
+    DROP resale_max_markup_percentage;
+ALTER TABLE events
+    DROP resale_enabled;
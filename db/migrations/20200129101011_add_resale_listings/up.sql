@@ -0,0 +1,32 @@
+ALTER TABLE events
+    ADD resale_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE events
+    ADD resale_max_markup_percentage INT NULL;
+
+ALTER TABLE fee_schedules
+    ADD resale_company_fee_percent REAL NOT NULL DEFAULT 0;
+ALTER TABLE fee_schedules
+    ADD resale_client_fee_percent REAL NOT NULL DEFAULT 0;
+
+CREATE TABLE resale_listings
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_instance_id UUID                                       NOT NULL REFERENCES ticket_instances (id),
+    event_id           UUID                                       NOT NULL REFERENCES events (id),
+    seller_user_id     UUID                                       NOT NULL REFERENCES users (id),
+    price_in_cents     BIGINT                                     NOT NULL,
+    status             TEXT                                       NOT NULL DEFAULT 'Active',
+    order_item_id      UUID                                       NULL REFERENCES order_items (id) ON DELETE SET NULL,
+    buyer_user_id      UUID                                       NULL REFERENCES users (id),
+    sold_at            TIMESTAMP                                  NULL,
+    settlement_id      UUID                                       NULL REFERENCES settlements (id) ON DELETE SET NULL,
+    created_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_resale_listings_ticket_instance_id ON resale_listings (ticket_instance_id);
+CREATE INDEX index_resale_listings_event_id_status ON resale_listings (event_id, status);
+CREATE INDEX index_resale_listings_seller_user_id ON resale_listings (seller_user_id);
+CREATE INDEX index_resale_listings_order_item_id ON resale_listings (order_item_id);
+CREATE INDEX index_resale_listings_settlement_id ON resale_listings (settlement_id);
+CREATE UNIQUE INDEX index_resale_listings_ticket_instance_id_active ON resale_listings (ticket_instance_id) WHERE status = 'Active';
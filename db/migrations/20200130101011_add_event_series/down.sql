@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_events_event_series_id;
+ALTER TABLE events
+    DROP event_series_id;
+
+DROP TABLE IF EXISTS event_series;
@@ -0,0 +1,18 @@
+CREATE TABLE event_series
+(
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id   UUID                                       NOT NULL REFERENCES organizations (id),
+    template_event_id UUID                                       NOT NULL REFERENCES events (id),
+    name              TEXT                                       NOT NULL,
+    recurrence_rule   TEXT                                       NOT NULL,
+    created_at        TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_series_organization_id ON event_series (organization_id);
+CREATE UNIQUE INDEX index_event_series_template_event_id ON event_series (template_event_id);
+
+ALTER TABLE events
+    ADD event_series_id UUID NULL REFERENCES event_series (id);
+
+CREATE INDEX index_events_event_series_id ON events (event_series_id);
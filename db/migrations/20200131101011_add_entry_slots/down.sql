@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_ticket_instances_entry_slot_id;
+
+ALTER TABLE ticket_instances
+    DROP entry_slot_id;
+
+ALTER TABLE ticket_types
+    DROP entry_window_policy;
+
+DROP INDEX IF EXISTS index_entry_slots_ticket_type_id_entry_start;
+DROP TABLE IF EXISTS entry_slots;
@@ -0,0 +1,22 @@
+CREATE TABLE entry_slots
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id UUID                                       NOT NULL REFERENCES ticket_types (id),
+    entry_start    TIMESTAMP                                  NOT NULL,
+    entry_end      TIMESTAMP                                  NOT NULL,
+    capacity       INT                                        NOT NULL,
+    created_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (entry_end > entry_start),
+    CHECK (capacity >= 0)
+);
+
+CREATE UNIQUE INDEX index_entry_slots_ticket_type_id_entry_start ON entry_slots (ticket_type_id, entry_start);
+
+ALTER TABLE ticket_types
+    ADD entry_window_policy TEXT NOT NULL DEFAULT 'Reject';
+
+ALTER TABLE ticket_instances
+    ADD entry_slot_id UUID NULL REFERENCES entry_slots (id);
+
+CREATE INDEX index_ticket_instances_entry_slot_id ON ticket_instances (entry_slot_id);
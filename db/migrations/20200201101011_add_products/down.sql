@@ -0,0 +1,19 @@
+ALTER TABLE settlement_entries
+    DROP product_variant_id;
+
+DROP INDEX IF EXISTS index_product_instances_redeem_key;
+DROP INDEX IF EXISTS index_product_instances_user_id;
+DROP INDEX IF EXISTS index_product_instances_order_item_id;
+DROP TABLE IF EXISTS product_instances;
+
+DROP INDEX IF EXISTS index_order_items_product_variant_id;
+ALTER TABLE order_items
+    DROP product_variant_id;
+
+DROP INDEX IF EXISTS index_product_variants_sku;
+DROP INDEX IF EXISTS index_product_variants_product_id;
+DROP TABLE IF EXISTS product_variants;
+
+DROP INDEX IF EXISTS index_products_event_id;
+DROP INDEX IF EXISTS index_products_organization_id;
+DROP TABLE IF EXISTS products;
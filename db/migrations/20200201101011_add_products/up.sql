@@ -0,0 +1,60 @@
+CREATE TABLE products
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID                                       NOT NULL REFERENCES organizations (id),
+    event_id        UUID                                       NULL REFERENCES events (id),
+    name            TEXT                                       NOT NULL,
+    description     TEXT                                       NULL,
+    price_in_cents  BIGINT                                     NOT NULL,
+    active          BOOLEAN                                    NOT NULL DEFAULT TRUE,
+    created_at      TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (price_in_cents >= 0)
+);
+
+CREATE INDEX index_products_organization_id ON products (organization_id);
+CREATE INDEX index_products_event_id ON products (event_id);
+
+CREATE TABLE product_variants
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    product_id     UUID                                       NOT NULL REFERENCES products (id),
+    name           TEXT                                       NOT NULL,
+    sku            TEXT                                       NOT NULL,
+    price_in_cents BIGINT                                     NULL,
+    stock          INT                                        NOT NULL,
+    rank           INT                                        NOT NULL DEFAULT 0,
+    created_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (price_in_cents IS NULL OR price_in_cents >= 0),
+    CHECK (stock >= 0)
+);
+
+CREATE INDEX index_product_variants_product_id ON product_variants (product_id);
+CREATE INDEX index_product_variants_sku ON product_variants (sku);
+
+ALTER TABLE order_items
+    ADD product_variant_id UUID NULL REFERENCES product_variants (id);
+
+CREATE INDEX index_order_items_product_variant_id ON order_items (product_variant_id);
+
+CREATE TABLE product_instances
+(
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    product_variant_id  UUID                                       NOT NULL REFERENCES product_variants (id),
+    order_item_id       UUID                                       NOT NULL REFERENCES order_items (id),
+    user_id             UUID                                       NOT NULL REFERENCES users (id),
+    status              TEXT                                       NOT NULL DEFAULT 'Purchased',
+    redeem_key          TEXT                                       NOT NULL,
+    redeemed_at         TIMESTAMP                                  NULL,
+    redeemed_by_user_id UUID                                       NULL REFERENCES users (id),
+    created_at          TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_product_instances_order_item_id ON product_instances (order_item_id);
+CREATE INDEX index_product_instances_user_id ON product_instances (user_id);
+CREATE INDEX index_product_instances_redeem_key ON product_instances (redeem_key);
+
+ALTER TABLE settlement_entries
+    ADD product_variant_id UUID NULL REFERENCES product_variants (id);
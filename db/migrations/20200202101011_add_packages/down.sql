@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_order_items_package_id;
+ALTER TABLE order_items
+    DROP package_id;
+
+DROP INDEX IF EXISTS index_package_ticket_types_ticket_type_id;
+DROP INDEX IF EXISTS index_package_ticket_types_package_id_ticket_type_id;
+DROP TABLE IF EXISTS package_ticket_types;
+
+DROP INDEX IF EXISTS index_packages_organization_id;
+DROP TABLE IF EXISTS packages;
@@ -0,0 +1,31 @@
+CREATE TABLE packages
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID                                       NOT NULL REFERENCES organizations (id),
+    name            TEXT                                       NOT NULL,
+    description     TEXT                                       NULL,
+    active          BOOLEAN                                    NOT NULL DEFAULT TRUE,
+    created_at      TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_packages_organization_id ON packages (organization_id);
+
+CREATE TABLE package_ticket_types
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    package_id     UUID                                       NOT NULL REFERENCES packages (id),
+    ticket_type_id UUID                                       NOT NULL REFERENCES ticket_types (id),
+    price_in_cents BIGINT                                     NOT NULL,
+    created_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (price_in_cents >= 0)
+);
+
+CREATE UNIQUE INDEX index_package_ticket_types_package_id_ticket_type_id ON package_ticket_types (package_id, ticket_type_id);
+CREATE INDEX index_package_ticket_types_ticket_type_id ON package_ticket_types (ticket_type_id);
+
+ALTER TABLE order_items
+    ADD package_id UUID NULL REFERENCES packages (id);
+
+CREATE INDEX index_order_items_package_id ON order_items (package_id);
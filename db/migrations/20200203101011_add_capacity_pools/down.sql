@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_ticket_types_capacity_pool_id;
+ALTER TABLE ticket_types
+    DROP capacity_pool_id;
+
+DROP INDEX IF EXISTS index_capacity_pools_stage_id;
+DROP INDEX IF EXISTS index_capacity_pools_event_id;
+DROP TABLE IF EXISTS capacity_pools;
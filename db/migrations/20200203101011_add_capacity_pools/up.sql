@@ -0,0 +1,19 @@
+CREATE TABLE capacity_pools
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id   UUID                                       NOT NULL REFERENCES events (id),
+    stage_id   UUID                                       NULL REFERENCES stages (id),
+    name       TEXT                                       NOT NULL,
+    capacity   BIGINT                                     NOT NULL,
+    created_at TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (capacity >= 0)
+);
+
+CREATE INDEX index_capacity_pools_event_id ON capacity_pools (event_id);
+CREATE INDEX index_capacity_pools_stage_id ON capacity_pools (stage_id);
+
+ALTER TABLE ticket_types
+    ADD capacity_pool_id UUID NULL REFERENCES capacity_pools (id);
+
+CREATE INDEX index_ticket_types_capacity_pool_id ON ticket_types (capacity_pool_id);
@@ -0,0 +1,2 @@
+ALTER TABLE ticket_pricing
+    ADD quantity_limit BIGINT NULL CHECK (quantity_limit > 0);
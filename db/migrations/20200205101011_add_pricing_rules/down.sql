@@ -0,0 +1,5 @@
+ALTER TABLE ticket_pricing
+    DROP base_price_in_cents;
+
+DROP INDEX IF EXISTS index_pricing_rules_ticket_type_id;
+DROP TABLE IF EXISTS pricing_rules;
@@ -0,0 +1,22 @@
+CREATE TABLE pricing_rules
+(
+    id                     UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id         UUID                                       NOT NULL REFERENCES ticket_types (id),
+    name                   TEXT                                       NOT NULL,
+    rule_type              TEXT                                       NOT NULL,
+    threshold              BIGINT                                     NOT NULL,
+    adjustment_in_cents    BIGINT                                     NOT NULL,
+    floor_price_in_cents   BIGINT                                     NOT NULL,
+    ceiling_price_in_cents BIGINT                                     NOT NULL,
+    active                 BOOLEAN                                    NOT NULL DEFAULT TRUE,
+    created_at             TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at             TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (threshold >= 0),
+    CHECK (floor_price_in_cents >= 0),
+    CHECK (ceiling_price_in_cents >= floor_price_in_cents)
+);
+
+CREATE INDEX index_pricing_rules_ticket_type_id ON pricing_rules (ticket_type_id);
+
+ALTER TABLE ticket_pricing
+    ADD base_price_in_cents BIGINT NULL;
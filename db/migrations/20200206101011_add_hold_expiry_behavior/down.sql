@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_holds_expiry_behavior_end_at;
+ALTER TABLE holds
+    DROP expiry_behavior,
+    DROP released_at;
@@ -0,0 +1,5 @@
+ALTER TABLE holds
+    ADD expiry_behavior TEXT NOT NULL DEFAULT 'Keep',
+    ADD released_at TIMESTAMP NULL;
+
+CREATE INDEX index_holds_expiry_behavior_end_at ON holds (expiry_behavior, end_at) WHERE released_at IS NULL AND deleted_at IS NULL;
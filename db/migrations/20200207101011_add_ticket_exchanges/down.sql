@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_ticket_exchanges_user_id;
+DROP INDEX IF EXISTS index_ticket_exchanges_new_order_id;
+DROP INDEX IF EXISTS index_ticket_exchanges_original_order_id;
+DROP INDEX IF EXISTS index_ticket_exchanges_ticket_instance_id;
+DROP TABLE IF EXISTS ticket_exchanges;
@@ -0,0 +1,20 @@
+CREATE TABLE ticket_exchanges
+(
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_instance_id       UUID                                       NOT NULL REFERENCES ticket_instances (id),
+    original_order_id        UUID                                       NOT NULL REFERENCES orders (id),
+    refund_id                UUID                                       NOT NULL REFERENCES refunds (id),
+    new_ticket_type_id       UUID                                       NOT NULL REFERENCES ticket_types (id),
+    new_order_id             UUID                                       NOT NULL REFERENCES orders (id),
+    user_id                  UUID                                       NOT NULL REFERENCES users (id),
+    refunded_amount_in_cents BIGINT                                     NOT NULL,
+    new_order_total_in_cents BIGINT                                     NOT NULL,
+    created_by               UUID                                       NOT NULL REFERENCES users (id),
+    created_at               TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at               TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_ticket_exchanges_ticket_instance_id ON ticket_exchanges (ticket_instance_id);
+CREATE INDEX index_ticket_exchanges_original_order_id ON ticket_exchanges (original_order_id);
+CREATE INDEX index_ticket_exchanges_new_order_id ON ticket_exchanges (new_order_id);
+CREATE INDEX index_ticket_exchanges_user_id ON ticket_exchanges (user_id);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS index_event_reschedules_event_id;
+DROP TABLE IF EXISTS event_reschedules;
@@ -0,0 +1,18 @@
+CREATE TABLE event_reschedules
+(
+    id                      UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id                UUID                                       NOT NULL REFERENCES events (id),
+    previous_event_start    TIMESTAMP                                  NULL,
+    previous_event_end      TIMESTAMP                                  NULL,
+    previous_door_time      TIMESTAMP                                  NULL,
+    event_start             TIMESTAMP                                  NOT NULL,
+    event_end               TIMESTAMP                                  NOT NULL,
+    door_time               TIMESTAMP                                  NULL,
+    refund_window_ends_at   TIMESTAMP                                  NOT NULL,
+    refund_window_closed_at TIMESTAMP                                  NULL,
+    created_by              UUID                                       NOT NULL REFERENCES users (id),
+    created_at              TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at              TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_reschedules_event_id ON event_reschedules (event_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_event_answers_ticket_answer;
+DROP INDEX IF EXISTS index_event_answers_order_answer;
+DROP INDEX IF EXISTS index_event_answers_ticket_instance_id;
+DROP INDEX IF EXISTS index_event_answers_order_id;
+DROP TABLE IF EXISTS event_answers;
+
+DROP INDEX IF EXISTS index_event_questions_event_id;
+DROP TABLE IF EXISTS event_questions;
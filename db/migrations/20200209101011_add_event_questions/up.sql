@@ -0,0 +1,33 @@
+CREATE TABLE event_questions
+(
+    id                     UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id               UUID                                       NOT NULL REFERENCES events (id),
+    question               TEXT                                       NOT NULL,
+    question_type          TEXT                                       NOT NULL,
+    choices                TEXT[]                                     NOT NULL DEFAULT '{}',
+    required               BOOLEAN                                    NOT NULL DEFAULT FALSE,
+    per_ticket             BOOLEAN                                    NOT NULL DEFAULT FALSE,
+    rank                   INT                                        NOT NULL DEFAULT 0,
+    answers_editable_until TIMESTAMP                                  NULL,
+    deleted_at             TIMESTAMP                                  NULL,
+    created_at             TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at             TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_questions_event_id ON event_questions (event_id);
+
+CREATE TABLE event_answers
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_question_id  UUID                                       NOT NULL REFERENCES event_questions (id),
+    order_id           UUID                                       NOT NULL REFERENCES orders (id),
+    ticket_instance_id UUID                                       NULL REFERENCES ticket_instances (id),
+    answer             TEXT                                       NOT NULL,
+    created_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_answers_order_id ON event_answers (order_id);
+CREATE INDEX index_event_answers_ticket_instance_id ON event_answers (ticket_instance_id);
+CREATE UNIQUE INDEX index_event_answers_order_answer ON event_answers (event_question_id, order_id) WHERE ticket_instance_id IS NULL;
+CREATE UNIQUE INDEX index_event_answers_ticket_answer ON event_answers (event_question_id, order_id, ticket_instance_id) WHERE ticket_instance_id IS NOT NULL;
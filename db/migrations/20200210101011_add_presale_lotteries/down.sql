@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_presale_lottery_entries_code_id;
+DROP INDEX IF EXISTS index_presale_lottery_entries_user_id;
+DROP INDEX IF EXISTS index_presale_lottery_entries_presale_lottery_id_user_id;
+DROP TABLE IF EXISTS presale_lottery_entries;
+
+DROP INDEX IF EXISTS index_presale_lotteries_event_id;
+DROP TABLE IF EXISTS presale_lotteries;
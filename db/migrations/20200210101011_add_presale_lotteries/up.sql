@@ -0,0 +1,42 @@
+CREATE TABLE presale_lotteries
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id           UUID                                       NOT NULL REFERENCES events (id),
+    name               TEXT                                       NOT NULL,
+    ticket_type_ids    UUID[]                                     NOT NULL,
+    registration_start TIMESTAMP                                  NOT NULL,
+    registration_end   TIMESTAMP                                  NOT NULL,
+    draw_at            TIMESTAMP                                  NOT NULL,
+    code_expires_at    TIMESTAMP                                  NOT NULL,
+    winner_count       INT                                        NOT NULL,
+    tickets_per_winner INT                                        NOT NULL,
+    interaction_weight INT                                        NOT NULL DEFAULT 0,
+    max_weight         INT                                        NOT NULL DEFAULT 1,
+    status             TEXT                                       NOT NULL DEFAULT 'Open',
+    drawn_at           TIMESTAMP                                  NULL,
+    created_by         UUID                                       NOT NULL REFERENCES users (id),
+    created_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK (winner_count > 0),
+    CHECK (tickets_per_winner > 0),
+    CHECK (interaction_weight >= 0),
+    CHECK (max_weight >= 1)
+);
+
+CREATE INDEX index_presale_lotteries_event_id ON presale_lotteries (event_id);
+
+CREATE TABLE presale_lottery_entries
+(
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    presale_lottery_id UUID                                       NOT NULL REFERENCES presale_lotteries (id),
+    user_id            UUID                                       NOT NULL REFERENCES users (id),
+    status             TEXT                                       NOT NULL DEFAULT 'Registered',
+    weight             INT                                        NULL,
+    code_id            UUID                                       NULL REFERENCES codes (id),
+    created_at         TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_presale_lottery_entries_presale_lottery_id_user_id ON presale_lottery_entries (presale_lottery_id, user_id);
+CREATE INDEX index_presale_lottery_entries_user_id ON presale_lottery_entries (user_id);
+CREATE INDEX index_presale_lottery_entries_code_id ON presale_lottery_entries (code_id);
@@ -0,0 +1,7 @@
+ALTER TABLE codes
+    DROP COLUMN min_quantity,
+    DROP COLUMN buy_quantity,
+    DROP COLUMN get_quantity,
+    DROP COLUMN order_discount_in_cents,
+    DROP COLUMN waives_fees,
+    DROP COLUMN hold_stacking;
@@ -0,0 +1,7 @@
+ALTER TABLE codes
+    ADD min_quantity BIGINT NULL CHECK (min_quantity > 0),
+    ADD buy_quantity BIGINT NULL CHECK (buy_quantity > 0),
+    ADD get_quantity BIGINT NULL CHECK (get_quantity > 0),
+    ADD order_discount_in_cents BIGINT NULL CHECK (order_discount_in_cents > 0),
+    ADD waives_fees BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD hold_stacking TEXT NOT NULL DEFAULT 'Exclusive';
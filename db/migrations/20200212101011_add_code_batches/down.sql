@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_codes_code_batch_id;
+
+ALTER TABLE codes
+    DROP COLUMN code_batch_id;
+
+DROP INDEX IF EXISTS index_code_batches_event_id;
+DROP TABLE IF EXISTS code_batches;
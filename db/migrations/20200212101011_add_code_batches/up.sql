@@ -0,0 +1,19 @@
+CREATE TABLE code_batches
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id   UUID                                       NOT NULL REFERENCES events (id),
+    name       TEXT                                       NOT NULL,
+    quantity   INT                                        NOT NULL CHECK (quantity > 0),
+    prefix     TEXT                                       NULL,
+    created_by UUID                                       NOT NULL REFERENCES users (id),
+    revoked_at TIMESTAMP                                  NULL,
+    created_at TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_code_batches_event_id ON code_batches (event_id);
+
+ALTER TABLE codes
+    ADD code_batch_id UUID NULL REFERENCES code_batches (id);
+
+CREATE INDEX index_codes_code_batch_id ON codes (code_batch_id);
@@ -0,0 +1,7 @@
+ALTER TABLE products
+    DROP COLUMN gift_card;
+
+DROP INDEX IF EXISTS index_store_credit_transactions_product_instance_id;
+DROP INDEX IF EXISTS index_store_credit_transactions_order_id;
+DROP INDEX IF EXISTS index_store_credit_transactions_user_id;
+DROP TABLE IF EXISTS store_credit_transactions;
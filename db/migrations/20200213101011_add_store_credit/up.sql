@@ -0,0 +1,21 @@
+CREATE TABLE store_credit_transactions
+(
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id             UUID                                       NOT NULL REFERENCES users (id),
+    amount_in_cents     BIGINT                                     NOT NULL CHECK (amount_in_cents <> 0),
+    transaction_type    TEXT                                       NOT NULL,
+    order_id            UUID                                       NULL REFERENCES orders (id),
+    payment_id          UUID                                       NULL REFERENCES payments (id),
+    refund_id           UUID                                       NULL REFERENCES refunds (id),
+    product_instance_id UUID                                       NULL REFERENCES product_instances (id),
+    created_by          UUID                                       NULL REFERENCES users (id),
+    created_at          TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_store_credit_transactions_user_id ON store_credit_transactions (user_id);
+CREATE INDEX index_store_credit_transactions_order_id ON store_credit_transactions (order_id);
+CREATE UNIQUE INDEX index_store_credit_transactions_product_instance_id ON store_credit_transactions (product_instance_id);
+
+ALTER TABLE products
+    ADD gift_card BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,24 @@
+CREATE TABLE tax_rules
+(
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    region_id    UUID                                       NULL REFERENCES regions (id),
+    venue_id     UUID                                       NULL REFERENCES venues (id),
+    name         TEXT                                       NOT NULL,
+    rate_percent DOUBLE PRECISION                           NOT NULL,
+    inclusive    BOOLEAN                                    NOT NULL DEFAULT FALSE,
+    taxes_fees   BOOLEAN                                    NOT NULL DEFAULT FALSE,
+    deleted_at   TIMESTAMP                                  NULL,
+    created_at   TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at   TIMESTAMP                                  NOT NULL DEFAULT now(),
+    CHECK ((region_id IS NULL) <> (venue_id IS NULL)),
+    CHECK (rate_percent > 0 AND rate_percent <= 100)
+);
+
+CREATE INDEX index_tax_rules_region_id ON tax_rules (region_id);
+CREATE INDEX index_tax_rules_venue_id ON tax_rules (venue_id);
+
+ALTER TABLE order_items
+    ADD tax_rule_id UUID NULL REFERENCES tax_rules (id),
+    ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX index_order_items_tax_rule_id ON order_items (tax_rule_id);
@@ -0,0 +1,16 @@
+DROP INDEX IF EXISTS index_store_credit_transactions_user_id_currency;
+
+ALTER TABLE store_credit_transactions
+    DROP COLUMN currency;
+
+ALTER TABLE settlements
+    DROP COLUMN currency;
+
+ALTER TABLE payments
+    DROP COLUMN currency;
+
+ALTER TABLE orders
+    DROP COLUMN currency;
+
+ALTER TABLE organizations
+    DROP COLUMN currency;
@@ -0,0 +1,16 @@
+-- Existing amounts were all made in the deployment's primary currency, which the migrate command
+-- sets from PRIMARY_CURRENCY. Orders, payments, settlements and store credit keep the currency they
+-- were made in so changing an organization's currency doesn't change the meaning of historic amounts
+DO $$
+DECLARE
+    primary_currency TEXT := UPPER(COALESCE(NULLIF(current_setting('bigneon.primary_currency', true), ''), 'USD'));
+BEGIN
+    -- Prices, fees and settlements of an organization are in its currency (ISO 4217 code)
+    EXECUTE format('ALTER TABLE organizations ADD currency TEXT NOT NULL DEFAULT %L CHECK (currency ~ ''^[A-Z]{3}$'')', primary_currency);
+    EXECUTE format('ALTER TABLE orders ADD currency TEXT NOT NULL DEFAULT %L', primary_currency);
+    EXECUTE format('ALTER TABLE payments ADD currency TEXT NOT NULL DEFAULT %L', primary_currency);
+    EXECUTE format('ALTER TABLE settlements ADD currency TEXT NOT NULL DEFAULT %L', primary_currency);
+    EXECUTE format('ALTER TABLE store_credit_transactions ADD currency TEXT NOT NULL DEFAULT %L', primary_currency);
+END $$;
+
+CREATE INDEX index_store_credit_transactions_user_id_currency ON store_credit_transactions (user_id, currency);
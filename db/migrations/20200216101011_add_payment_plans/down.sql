@@ -0,0 +1,18 @@
+ALTER TABLE refunds
+    DROP COLUMN retained_in_cents;
+
+DROP INDEX IF EXISTS index_payment_plan_installments_payment_plan_id_installment_number;
+DROP TABLE IF EXISTS payment_plan_installments;
+
+DROP INDEX IF EXISTS index_payment_plans_user_id;
+DROP INDEX IF EXISTS index_payment_plans_order_id;
+DROP TABLE IF EXISTS payment_plans;
+
+ALTER TABLE organizations
+    DROP COLUMN payment_plan_refund_policy;
+ALTER TABLE organizations
+    DROP COLUMN payment_plan_grace_period_days;
+ALTER TABLE organizations
+    DROP COLUMN payment_plan_max_installments;
+ALTER TABLE organizations
+    DROP COLUMN payment_plan_min_total_in_cents;
@@ -0,0 +1,53 @@
+-- Organizations opt in to payment plans by setting the minimum order total they are offered for
+ALTER TABLE organizations
+    ADD payment_plan_min_total_in_cents BIGINT NULL;
+ALTER TABLE organizations
+    ADD payment_plan_max_installments INT NOT NULL DEFAULT 3;
+ALTER TABLE organizations
+    ADD payment_plan_grace_period_days INT NOT NULL DEFAULT 7;
+ALTER TABLE organizations
+    ADD payment_plan_refund_policy TEXT NOT NULL DEFAULT 'RetainDeposit';
+
+CREATE TABLE payment_plans
+(
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    order_id          UUID                                       NOT NULL REFERENCES orders (id),
+    user_id           UUID                                       NOT NULL REFERENCES users (id),
+    payment_method_id UUID                                       NOT NULL REFERENCES payment_methods (id),
+    total_in_cents    BIGINT                                     NOT NULL,
+    deposit_in_cents  BIGINT                                     NOT NULL,
+    grace_period_days INT                                        NOT NULL,
+    refund_policy     TEXT                                       NOT NULL,
+    status            TEXT                                       NOT NULL DEFAULT 'Active',
+    completed_at      TIMESTAMP                                  NULL,
+    cancelled_at      TIMESTAMP                                  NULL,
+    created_at        TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_plans_order_id ON payment_plans (order_id);
+CREATE INDEX index_payment_plans_user_id ON payment_plans (user_id);
+
+CREATE TABLE payment_plan_installments
+(
+    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    payment_plan_id      UUID                                       NOT NULL REFERENCES payment_plans (id),
+    installment_number   INT                                        NOT NULL,
+    amount_in_cents      BIGINT                                     NOT NULL CHECK (amount_in_cents > 0),
+    due_at               TIMESTAMP                                  NOT NULL,
+    status               TEXT                                       NOT NULL DEFAULT 'Pending',
+    payment_id           UUID                                       NULL REFERENCES payments (id),
+    failed_attempts      INT                                        NOT NULL DEFAULT 0,
+    last_failure_reason  TEXT                                       NULL,
+    grace_period_ends_at TIMESTAMP                                  NULL,
+    paid_at              TIMESTAMP                                  NULL,
+    created_at           TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at           TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_plan_installments_payment_plan_id_installment_number ON payment_plan_installments (payment_plan_id, installment_number);
+
+-- Part of a refund's value kept rather than returned to the purchaser, such as the deposit of a
+-- cancelled payment plan
+ALTER TABLE refunds
+    ADD retained_in_cents BIGINT NOT NULL DEFAULT 0;
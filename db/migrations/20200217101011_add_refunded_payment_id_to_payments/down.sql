@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_payments_refunded_payment_id;
+
+ALTER TABLE payments
+  DROP COLUMN refunded_payment_id;
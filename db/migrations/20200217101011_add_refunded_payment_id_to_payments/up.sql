@@ -0,0 +1,51 @@
+ALTER TABLE payments
+  ADD refunded_payment_id UUID NULL REFERENCES payments (id);
+
+CREATE INDEX index_payments_refunded_payment_id ON payments (refunded_payment_id);
+
+-- Link existing refunds to the payment they were taken from
+UPDATE payments r
+SET refunded_payment_id = (
+  SELECT p.id
+  FROM payments p
+  WHERE p.order_id = r.order_id
+    AND p.status = 'Completed'
+    AND p.amount > 0
+    AND p.external_reference IS NOT DISTINCT FROM r.external_reference
+  ORDER BY p.created_at
+  LIMIT 1
+)
+WHERE r.refund_id IS NOT NULL
+  AND r.amount < 0;
+
+-- Refunds whose reference didn't match are linked to a payment made with the same method and
+-- provider, and failing that to the order's first payment, so no refund is left unlinked
+UPDATE payments r
+SET refunded_payment_id = (
+  SELECT p.id
+  FROM payments p
+  WHERE p.order_id = r.order_id
+    AND p.status = 'Completed'
+    AND p.amount > 0
+    AND p.payment_method = r.payment_method
+    AND p.provider = r.provider
+  ORDER BY p.created_at
+  LIMIT 1
+)
+WHERE r.refund_id IS NOT NULL
+  AND r.amount < 0
+  AND r.refunded_payment_id IS NULL;
+
+UPDATE payments r
+SET refunded_payment_id = (
+  SELECT p.id
+  FROM payments p
+  WHERE p.order_id = r.order_id
+    AND p.status = 'Completed'
+    AND p.amount > 0
+  ORDER BY p.created_at
+  LIMIT 1
+)
+WHERE r.refund_id IS NOT NULL
+  AND r.amount < 0
+  AND r.refunded_payment_id IS NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE payments
+  DROP dispute_status;
+
+DROP INDEX IF EXISTS index_payment_provider_events_provider_external_id;
+DROP TABLE IF EXISTS payment_provider_events;
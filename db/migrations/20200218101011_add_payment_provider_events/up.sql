@@ -0,0 +1,18 @@
+-- Raw notifications received from payment providers, stored once per provider event id
+CREATE TABLE payment_provider_events
+(
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    provider     TEXT                                       NOT NULL,
+    external_id  TEXT                                       NOT NULL,
+    event_type   TEXT                                       NOT NULL,
+    payload      JSONB                                      NOT NULL,
+    processed_at TIMESTAMP                                  NULL,
+    created_at   TIMESTAMP                                  NOT NULL DEFAULT now(),
+    updated_at   TIMESTAMP                                  NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_provider_events_provider_external_id ON payment_provider_events (provider, external_id);
+
+-- Disputes are tracked apart from the payment status so disputed payments still count as paid
+ALTER TABLE payments
+  ADD dispute_status TEXT NULL CHECK (dispute_status IN ('Open', 'Won', 'Lost'));
@@ -26,6 +26,7 @@ use clap::{App, Arg, SubCommand};
 use diesel::connection::SimpleConnection;
 use diesel::pg::PgConnection;
 use diesel::Connection;
+use std::env;
 use std::error::Error;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
@@ -229,12 +230,28 @@ fn migrate_db(matches: &ArgMatches) {
     println!("Migrating database");
 
     let connection = PgConnection::establish(conn_string).unwrap();
+    set_primary_currency(&connection);
 
     embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).expect("Migration failed");
 
     run_function_migrations(matches);
 }
 
+/// Migrations that add currencies label existing amounts with the deployment's primary currency,
+/// taken from the same `PRIMARY_CURRENCY` setting the API uses for new organizations.
+fn set_primary_currency(connection: &PgConnection) {
+    let currency = env::var("PRIMARY_CURRENCY")
+        .unwrap_or_else(|_| "usd".to_string())
+        .trim()
+        .to_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        panic!("PRIMARY_CURRENCY '{}' is not a valid currency code", currency);
+    }
+    connection
+        .batch_execute(&format!("SET bigneon.primary_currency = '{}'", currency))
+        .expect("Could not set primary currency");
+}
+
 fn run_function_migrations(matches: &ArgMatches) {
     let conn_string = matches
         .value_of("connection")
@@ -278,6 +295,7 @@ fn create_db_and_user(matches: &ArgMatches) {
 
     {
         let connection = get_connection(conn_string);
+        set_primary_currency(&connection);
 
         embedded_migrations::run_with_output(&connection, &mut std::io::stdout()).expect("Migration failed");
 
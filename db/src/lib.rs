@@ -19,6 +19,7 @@ extern crate backtrace;
 extern crate bigneon_http;
 extern crate chrono;
 extern crate chrono_tz;
+extern crate csv;
 extern crate hex;
 extern crate itertools;
 //#[macro_use]
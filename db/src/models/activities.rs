@@ -104,6 +104,21 @@ pub enum ActivityItem {
         note: String,
         occurred_at: NaiveDateTime,
     },
+    Exchange {
+        ticket_exchange_id: Uuid,
+        ticket_instance_id: Uuid,
+        ticket_number: String,
+        original_order_id: Uuid,
+        original_order_number: String,
+        new_order_id: Uuid,
+        new_order_number: String,
+        refund_id: Uuid,
+        from_ticket_type_name: String,
+        to_ticket_type_name: String,
+        price_difference_in_cents: i64,
+        exchanged_by: UserActivityItem,
+        occurred_at: NaiveDateTime,
+    },
 }
 
 impl ActivityItem {
@@ -157,6 +172,14 @@ impl ActivityItem {
                 conn,
             )?);
         }
+        if activity_type.is_none() || activity_type == Some(ActivityType::Exchange) {
+            activity_items.append(&mut ActivityItem::load_exchanges(
+                None,
+                Some(event_id),
+                Some(user_id),
+                conn,
+            )?);
+        }
         activity_items.sort_by_key(|activity| Reverse(activity.occurred_at()));
         Ok(activity_items)
     }
@@ -174,6 +197,7 @@ impl ActivityItem {
         activity_items.append(&mut ActivityItem::load_check_ins(Some(order.id), None, None, conn)?);
         activity_items.append(&mut ActivityItem::load_refunds(Some(order.id), None, None, conn)?);
         activity_items.append(&mut ActivityItem::load_notes(Some(order.id), None, None, conn)?);
+        activity_items.append(&mut ActivityItem::load_exchanges(Some(order.id), None, None, conn)?);
         activity_items.sort_by_key(|activity| Reverse(activity.occurred_at()));
         Ok(activity_items)
     }
@@ -963,6 +987,118 @@ impl ActivityItem {
         Ok(activity_items)
     }
 
+    fn load_exchanges(
+        order_id: Option<Uuid>,
+        event_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<ActivityItem>, DatabaseError> {
+        if order_id.is_none() && event_id.is_none() || (event_id.is_some() && user_id.is_none()) {
+            return Err(DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Activity loading requires either order_id or event_id to be present".to_string()),
+            ));
+        }
+
+        #[derive(Debug, Queryable, QueryableByName)]
+        struct R {
+            #[sql_type = "dUuid"]
+            ticket_exchange_id: Uuid,
+            #[sql_type = "dUuid"]
+            ticket_instance_id: Uuid,
+            #[sql_type = "dUuid"]
+            original_order_id: Uuid,
+            #[sql_type = "dUuid"]
+            new_order_id: Uuid,
+            #[sql_type = "dUuid"]
+            refund_id: Uuid,
+            #[sql_type = "Text"]
+            from_ticket_type_name: String,
+            #[sql_type = "Text"]
+            to_ticket_type_name: String,
+            #[sql_type = "BigInt"]
+            price_difference_in_cents: i64,
+            #[sql_type = "dUuid"]
+            exchanged_by: Uuid,
+            #[sql_type = "Timestamp"]
+            occurred_at: NaiveDateTime,
+        }
+        let mut query = sql_query(
+            r#"
+        SELECT
+            te.id as ticket_exchange_id,
+            te.ticket_instance_id,
+            te.original_order_id,
+            te.new_order_id,
+            te.refund_id,
+            ott.name as from_ticket_type_name,
+            ntt.name as to_ticket_type_name,
+            te.new_order_total_in_cents - te.refunded_amount_in_cents as price_difference_in_cents,
+            te.created_by as exchanged_by,
+            te.created_at as occurred_at
+        FROM ticket_exchanges te
+        JOIN ticket_instances ti ON ti.id = te.ticket_instance_id
+        JOIN assets a ON a.id = ti.asset_id
+        JOIN ticket_types ott ON ott.id = a.ticket_type_id
+        JOIN ticket_types ntt ON ntt.id = te.new_ticket_type_id
+        WHERE 1 = 1
+        "#,
+        )
+        .into_boxed();
+
+        if let (Some(event_id), Some(user_id)) = (event_id, user_id) {
+            query = query
+                .sql(" AND (ott.event_id = $1 OR ntt.event_id = $1) ")
+                .bind::<dUuid, _>(event_id);
+            query = query.sql(" AND te.user_id = $2 ").bind::<dUuid, _>(user_id);
+        } else if let Some(order_id) = order_id {
+            query = query
+                .sql(" AND (te.original_order_id = $1 OR te.new_order_id = $1) ")
+                .bind::<dUuid, _>(order_id);
+        }
+
+        let exchange_data: Vec<R> = query
+            .sql(" ORDER BY te.created_at DESC")
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load exchanges for organization fan")?;
+
+        let mut user_ids: Vec<Uuid> = exchange_data.iter().map(|e| e.exchanged_by).collect();
+        user_ids.sort();
+        user_ids.dedup();
+        let users = User::find_by_ids(&user_ids, conn)?;
+        let mut user_map: HashMap<Uuid, UserActivityItem> = HashMap::new();
+        for user in users {
+            user_map.insert(user.id, user.into());
+        }
+
+        let mut activity_items: Vec<ActivityItem> = Vec::new();
+        for exchange_datum in exchange_data {
+            let exchanged_by = user_map.get(&exchange_datum.exchanged_by).map(|u| u.clone()).ok_or_else(|| {
+                DatabaseError::new(
+                    ErrorCode::BusinessProcessError,
+                    Some("Unable to load exchanged by user".to_string()),
+                )
+            })?;
+
+            activity_items.push(ActivityItem::Exchange {
+                ticket_exchange_id: exchange_datum.ticket_exchange_id,
+                ticket_instance_id: exchange_datum.ticket_instance_id,
+                ticket_number: TicketInstance::parse_ticket_number(exchange_datum.ticket_instance_id),
+                original_order_id: exchange_datum.original_order_id,
+                original_order_number: Order::parse_order_number(exchange_datum.original_order_id),
+                new_order_id: exchange_datum.new_order_id,
+                new_order_number: Order::parse_order_number(exchange_datum.new_order_id),
+                refund_id: exchange_datum.refund_id,
+                from_ticket_type_name: exchange_datum.from_ticket_type_name,
+                to_ticket_type_name: exchange_datum.to_ticket_type_name,
+                price_difference_in_cents: exchange_datum.price_difference_in_cents,
+                exchanged_by,
+                occurred_at: exchange_datum.occurred_at,
+            });
+        }
+        Ok(activity_items)
+    }
+
     pub fn occurred_at(&self) -> NaiveDateTime {
         match *self {
             ActivityItem::Purchase { occurred_at, .. } => occurred_at,
@@ -970,6 +1106,7 @@ impl ActivityItem {
             ActivityItem::CheckIn { occurred_at, .. } => occurred_at,
             ActivityItem::Refund { occurred_at, .. } => occurred_at,
             ActivityItem::Note { occurred_at, .. } => occurred_at,
+            ActivityItem::Exchange { occurred_at, .. } => occurred_at,
         }
     }
 }
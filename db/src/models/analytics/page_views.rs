@@ -1,6 +1,7 @@
 use chrono::prelude::*;
 use diesel::pg::upsert::on_constraint;
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Nullable, Timestamp};
 use diesel::{dsl, PgConnection};
 use schema::analytics_page_views;
 use utils::errors::*;
@@ -31,6 +32,26 @@ pub struct PageView {
 }
 
 impl PageView {
+    /// Total page views recorded for the event in hourly buckets starting at or after `since`.
+    pub fn event_page_view_count(
+        event_id: Uuid,
+        since: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let count: Option<i64> = analytics_page_views::table
+            .filter(analytics_page_views::event_id.eq(event_id))
+            .filter(
+                dsl::sql::<Bool>("(analytics_page_views.date + analytics_page_views.hour) >= ")
+                    .bind::<Timestamp, _>(since),
+            )
+            .select(dsl::sql::<Nullable<BigInt>>(
+                "CAST(SUM(analytics_page_views.count) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load page view count for event")?;
+        Ok(count.unwrap_or(0))
+    }
+
     pub fn create(
         date: NaiveDateTime,
         event_id: Uuid,
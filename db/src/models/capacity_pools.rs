@@ -0,0 +1,331 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::expression::sql_literal::sql;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use models::*;
+use schema::{assets, capacity_pools, ticket_instances, ticket_types};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A limit on the number of tickets that can be sold across several ticket types of an event,
+/// for example the capacity of a stage shared by general admission, VIP and comp tickets.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "capacity_pools"]
+pub struct CapacityPool {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+    pub capacity: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayCapacityPool {
+    #[serde(flatten)]
+    pub capacity_pool: CapacityPool,
+    pub available: u32,
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "capacity_pools"]
+pub struct CapacityPoolEditableAttributes {
+    pub name: Option<String>,
+    pub capacity: Option<i64>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "capacity_pools"]
+pub struct NewCapacityPool {
+    pub event_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+    pub capacity: i64,
+}
+
+impl NewCapacityPool {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<CapacityPool, DatabaseError> {
+        CapacityPool::validate_attributes(None, self.event_id, self.stage_id, &self.name, self.capacity, conn)?;
+        let result: CapacityPool = diesel::insert_into(capacity_pools::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create capacity pool")?;
+
+        DomainEvent::create(
+            DomainEventTypes::CapacityPoolCreated,
+            "Capacity pool created".to_string(),
+            Tables::CapacityPools,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl CapacityPool {
+    pub fn create(event_id: Uuid, stage_id: Option<Uuid>, name: String, capacity: i64) -> NewCapacityPool {
+        NewCapacityPool {
+            event_id,
+            stage_id,
+            name,
+            capacity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<CapacityPool, DatabaseError> {
+        capacity_pools::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading capacity pool")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<CapacityPool>, DatabaseError> {
+        capacity_pools::table
+            .filter(capacity_pools::event_id.eq(event_id))
+            .order_by(capacity_pools::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading capacity pools")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<TicketType>, DatabaseError> {
+        ticket_types::table
+            .filter(ticket_types::capacity_pool_id.eq(self.id))
+            .filter(ticket_types::deleted_at.is_null())
+            .order_by(ticket_types::rank)
+            .then_order_by(ticket_types::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading ticket types for capacity pool")
+    }
+
+    /// Number of tickets drawing from the pool. Tickets that are sold, held or in an unexpired
+    /// cart reservation all count against the capacity.
+    pub fn used_capacity(&self, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let ticket_type_ids: Vec<Uuid> = ticket_types::table
+            .filter(ticket_types::capacity_pool_id.eq(self.id))
+            .select(ticket_types::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading ticket types for capacity pool")?;
+        CapacityPool::used_capacity_for_ticket_types(&ticket_type_ids, conn)
+    }
+
+    fn used_capacity_for_ticket_types(ticket_type_ids: &[Uuid], conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let used: i64 = ticket_instances::table
+            .inner_join(assets::table)
+            .filter(assets::ticket_type_id.eq_any(ticket_type_ids.to_vec()))
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .filter(
+                ticket_instances::status
+                    .eq_any(vec![TicketInstanceStatus::Purchased, TicketInstanceStatus::Redeemed])
+                    .or(ticket_instances::hold_id.is_not_null())
+                    .or(sql("(ticket_instances.status=")
+                        .bind::<Text, _>(TicketInstanceStatus::Reserved)
+                        .sql(" AND ticket_instances.reserved_until >= CURRENT_TIMESTAMP)")),
+            )
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load used capacity for capacity pool")?;
+        Ok(used as u32)
+    }
+
+    pub fn available(&self, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let used = self.used_capacity(conn)? as i64;
+        Ok(cmp::max(self.capacity - used, 0) as u32)
+    }
+
+    /// Locks the pool for the remainder of the transaction and confirms that `quantity` more
+    /// tickets can be taken from it. Reservations and holds for any of the pool's ticket types
+    /// lock the same row, so they cannot oversell the pool concurrently.
+    pub(crate) fn confirm_capacity_available(
+        id: Uuid,
+        quantity: u32,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let capacity_pool = CapacityPool::lock(id, conn)?;
+        if capacity_pool.available(conn)? < quantity {
+            return DatabaseError::validation_error(
+                "quantity",
+                "Could not reserve tickets, not enough tickets are available",
+            );
+        }
+        Ok(())
+    }
+
+    fn lock(id: Uuid, conn: &PgConnection) -> Result<CapacityPool, DatabaseError> {
+        capacity_pools::table
+            .find(id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading capacity pool")
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayCapacityPool, DatabaseError> {
+        Ok(DisplayCapacityPool {
+            capacity_pool: self.clone(),
+            available: self.available(conn)?,
+            ticket_type_ids: self.ticket_types(conn)?.iter().map(|t| t.id).collect(),
+        })
+    }
+
+    /// Replaces the ticket types drawing from this pool. Ticket types can only belong to one pool, and the
+    /// tickets already sold, held or reserved for them must fit within the capacity.
+    pub fn set_ticket_types(
+        &self,
+        ticket_type_ids: &[Uuid],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketType>, DatabaseError> {
+        let capacity_pool = CapacityPool::lock(self.id, conn)?;
+        if CapacityPool::used_capacity_for_ticket_types(ticket_type_ids, conn)? as i64 > capacity_pool.capacity {
+            return DatabaseError::validation_error(
+                "ticket_type_ids",
+                "Ticket types have more tickets sold or held than the capacity pool allows",
+            );
+        }
+        for ticket_type in TicketType::find_by_ids(&ticket_type_ids.to_vec(), conn)? {
+            if ticket_type.event_id != self.event_id {
+                return DatabaseError::validation_error("ticket_type_ids", "Ticket type does not belong to this event");
+            }
+            if ticket_type.capacity_pool_id.is_some() && ticket_type.capacity_pool_id != Some(self.id) {
+                return DatabaseError::validation_error(
+                    "ticket_type_ids",
+                    "Ticket type already belongs to another capacity pool",
+                );
+            }
+        }
+
+        diesel::update(
+            ticket_types::table
+                .filter(ticket_types::capacity_pool_id.eq(self.id))
+                .filter(ticket_types::id.ne_all(ticket_type_ids.to_vec())),
+        )
+        .set((
+            ticket_types::capacity_pool_id.eq(None::<Uuid>),
+            ticket_types::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not remove ticket types from capacity pool",
+        )?;
+
+        diesel::update(ticket_types::table.filter(ticket_types::id.eq_any(ticket_type_ids.to_vec())))
+            .set((
+                ticket_types::capacity_pool_id.eq(self.id),
+                ticket_types::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add ticket types to capacity pool")?;
+
+        DomainEvent::create(
+            DomainEventTypes::CapacityPoolUpdated,
+            "Capacity pool ticket types updated".to_string(),
+            Tables::CapacityPools,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "ticket_type_ids": ticket_type_ids })),
+        )
+        .commit(conn)?;
+
+        self.ticket_types(conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: CapacityPoolEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<CapacityPool, DatabaseError> {
+        CapacityPool::validate_attributes(
+            Some(self.id),
+            self.event_id,
+            self.stage_id,
+            attributes.name.as_ref().unwrap_or(&self.name),
+            attributes.capacity.unwrap_or(self.capacity),
+            conn,
+        )?;
+        if let Some(capacity) = attributes.capacity {
+            let capacity_pool = CapacityPool::lock(self.id, conn)?;
+            if capacity < capacity_pool.used_capacity(conn)? as i64 {
+                return DatabaseError::validation_error(
+                    "capacity",
+                    "Capacity cannot be lower than the number of tickets sold or held",
+                );
+            }
+        }
+
+        let result: CapacityPool = diesel::update(self)
+            .set((&attributes, capacity_pools::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update capacity pool")?;
+
+        DomainEvent::create(
+            DomainEventTypes::CapacityPoolUpdated,
+            "Capacity pool updated".to_string(),
+            Tables::CapacityPools,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Removes the pool. Its ticket types go back to being limited only by their own inventory.
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        self.set_ticket_types(&[], current_user_id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::CapacityPoolDeleted,
+            "Capacity pool deleted".to_string(),
+            Tables::CapacityPools,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete capacity pool")
+    }
+
+    fn validate_attributes(
+        capacity_pool_id: Option<Uuid>,
+        event_id: Uuid,
+        stage_id: Option<Uuid>,
+        name: &str,
+        capacity: i64,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        if capacity < 0 {
+            return DatabaseError::validation_error("capacity", "Capacity cannot be negative");
+        }
+        if let Some(stage_id) = stage_id {
+            let stage = Stage::find(stage_id, conn)?;
+            if Event::find(event_id, conn)?.venue_id != Some(stage.venue_id) {
+                return DatabaseError::validation_error("stage_id", "Stage does not belong to the event's venue");
+            }
+            if !stage.has_capacity_for(event_id, capacity, capacity_pool_id, conn)? {
+                return DatabaseError::validation_error("capacity", "Capacity cannot exceed the stage capacity");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::{code_batches, codes, holds, ticket_type_codes};
+use std::collections::HashSet;
+use utils::csv::write_csv;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Length of the random part of each code in a batch.
+pub const CODE_BATCH_REDEMPTION_CODE_LENGTH: usize = 10;
+pub const CODE_BATCH_MAX_QUANTITY: i32 = 10_000;
+const CODE_BATCH_MAX_PREFIX_LENGTH: usize = 10;
+// Keeps each insert well below Postgres' limit on bind parameters
+const CODE_BATCH_INSERT_SIZE: usize = 1_000;
+
+/// A set of single use codes generated together for a campaign. Every code in the batch shares the
+/// batch's configuration and has its own random redemption code.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "code_batches"]
+pub struct CodeBatch {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub quantity: i32,
+    pub prefix: Option<String>,
+    pub created_by: Uuid,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "code_batches"]
+pub struct NewCodeBatch {
+    pub event_id: Uuid,
+    pub name: String,
+    pub quantity: i32,
+    pub prefix: Option<String>,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct CodeBatchCode {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "Text"]
+    pub redemption_code: String,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub deleted_at: Option<NaiveDateTime>,
+    #[sql_type = "BigInt"]
+    pub redeemed_quantity: i64,
+}
+
+/// Totals for the paid orders that used codes from a batch.
+#[derive(Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct CodeBatchSummary {
+    #[sql_type = "BigInt"]
+    pub code_count: i64,
+    #[sql_type = "BigInt"]
+    pub redeemed_code_count: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_count: i64,
+    #[sql_type = "BigInt"]
+    pub face_value_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub discount_in_cents: i64,
+}
+
+impl NewCodeBatch {
+    /// Creates the batch and its codes. Each code is a copy of `code` that can be used once, the
+    /// template's name, redemption code and maximum uses are replaced.
+    pub fn commit(
+        &self,
+        code: NewCode,
+        ticket_type_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<CodeBatch, DatabaseError> {
+        self.validate_record(&code, ticket_type_ids, conn)?;
+
+        let result: CodeBatch = diesel::insert_into(code_batches::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create code batch")?;
+
+        let new_codes: Vec<NewCode> = self
+            .generate_redemption_codes(conn)?
+            .into_iter()
+            .map(|redemption_code| NewCode {
+                name: self.name.clone(),
+                event_id: self.event_id,
+                redemption_code,
+                max_uses: 1,
+                code_batch_id: Some(result.id),
+                ..code.clone()
+            })
+            .collect();
+        for new_codes in new_codes.chunks(CODE_BATCH_INSERT_SIZE) {
+            let code_ids: Vec<Uuid> = diesel::insert_into(codes::table)
+                .values(new_codes)
+                .returning(codes::id)
+                .get_results(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create codes for code batch")?;
+            let new_ticket_type_codes: Vec<NewTicketTypeCode> = code_ids
+                .iter()
+                .flat_map(|code_id| {
+                    ticket_type_ids
+                        .iter()
+                        .map(move |ticket_type_id| TicketTypeCode::create(*ticket_type_id, *code_id))
+                })
+                .collect();
+            diesel::insert_into(ticket_type_codes::table)
+                .values(&new_ticket_type_codes)
+                .execute(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not add ticket types to code batch")?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::CodeBatchCreated,
+            format!("Code batch '{}' created", &result.name),
+            Tables::CodeBatches,
+            Some(result.id),
+            Some(self.created_by),
+            Some(json!({ "quantity": self.quantity, "ticket_type_ids": ticket_type_ids, "code": &code })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(
+        &self,
+        code: &NewCode,
+        ticket_type_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+        if self.quantity <= 0 || self.quantity > CODE_BATCH_MAX_QUANTITY {
+            return DatabaseError::validation_error("quantity", "Quantity must be between 1 and 10000");
+        }
+        if let Some(ref prefix) = self.prefix {
+            if prefix.len() > CODE_BATCH_MAX_PREFIX_LENGTH || !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return DatabaseError::validation_error("prefix", "Prefix must be at most 10 letters or numbers");
+            }
+        }
+        if ticket_type_ids.is_empty() {
+            return DatabaseError::validation_error("ticket_type_ids", "At least one ticket type is required");
+        }
+        for ticket_type_id in ticket_type_ids {
+            if TicketType::find(*ticket_type_id, conn)?.event_id != self.event_id {
+                return DatabaseError::validation_error(
+                    "ticket_type_ids",
+                    "Ticket types must belong to the code batch's event",
+                );
+            }
+        }
+
+        // Validate the shared configuration once with a sample code
+        NewCode {
+            event_id: self.event_id,
+            redemption_code: self.random_redemption_code(),
+            max_uses: 1,
+            ..code.clone()
+        }
+        .validate_record(conn)
+    }
+
+    fn random_redemption_code(&self) -> String {
+        format!(
+            "{}{}",
+            self.prefix.clone().unwrap_or_default(),
+            random_alpha_string(CODE_BATCH_REDEMPTION_CODE_LENGTH)
+        )
+        .to_uppercase()
+    }
+
+    /// Generates the batch's redemption codes, skipping any already used by the event's codes or holds.
+    fn generate_redemption_codes(&self, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        let quantity = self.quantity as usize;
+        let mut redemption_codes = HashSet::new();
+        while redemption_codes.len() < quantity {
+            let mut candidates = HashSet::new();
+            while redemption_codes.len() + candidates.len() < quantity {
+                let candidate = self.random_redemption_code();
+                if !redemption_codes.contains(&candidate) {
+                    candidates.insert(candidate);
+                }
+            }
+            let candidates: Vec<String> = candidates.into_iter().collect();
+
+            let mut taken: Vec<String> = codes::table
+                .filter(codes::event_id.eq(self.event_id))
+                .filter(codes::redemption_code.eq_any(&candidates))
+                .filter(codes::deleted_at.is_null())
+                .select(codes::redemption_code)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not check redemption codes")?;
+            let taken_by_holds: Vec<Option<String>> = holds::table
+                .filter(holds::event_id.eq(self.event_id))
+                .filter(holds::redemption_code.eq_any(&candidates))
+                .filter(holds::deleted_at.is_null())
+                .select(holds::redemption_code)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not check redemption codes")?;
+            taken.extend(taken_by_holds.into_iter().filter_map(|r| r));
+
+            redemption_codes.extend(candidates.into_iter().filter(|c| !taken.contains(c)));
+        }
+
+        let mut redemption_codes: Vec<String> = redemption_codes.into_iter().collect();
+        redemption_codes.sort();
+        Ok(redemption_codes)
+    }
+}
+
+impl CodeBatch {
+    pub fn create(
+        event_id: Uuid,
+        name: String,
+        quantity: i32,
+        prefix: Option<String>,
+        created_by: Uuid,
+    ) -> NewCodeBatch {
+        NewCodeBatch {
+            event_id,
+            name,
+            quantity,
+            prefix: prefix.map(|p| p.to_uppercase()),
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<CodeBatch, DatabaseError> {
+        code_batches::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading code batch")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<CodeBatch>, DatabaseError> {
+        code_batches::table
+            .filter(code_batches::event_id.eq(event_id))
+            .order_by(code_batches::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading code batches")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    /// The batch's codes with the number of tickets purchased with each.
+    pub fn codes(&self, conn: &PgConnection) -> Result<Vec<CodeBatchCode>, DatabaseError> {
+        let query = r#"
+            SELECT c.id,
+                   c.redemption_code,
+                   c.deleted_at,
+                   CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity), 0) AS BIGINT) AS redeemed_quantity
+            FROM codes c
+                     LEFT JOIN (order_items oi JOIN orders o ON o.id = oi.order_id AND o.status = 'Paid')
+                               ON oi.code_id = c.id AND oi.item_type = 'Tickets'
+            WHERE c.code_batch_id = $1
+            GROUP BY c.id, c.redemption_code, c.deleted_at
+            ORDER BY c.redemption_code;"#;
+
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load codes for code batch")
+    }
+
+    pub fn summary(&self, conn: &PgConnection) -> Result<CodeBatchSummary, DatabaseError> {
+        let query = r#"
+            SELECT CAST(COUNT(DISTINCT c.id) AS BIGINT)                                                   AS code_count,
+                   CAST(COUNT(DISTINCT oi.code_id) AS BIGINT)                                             AS redeemed_code_count,
+                   CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity), 0) AS BIGINT)                   AS ticket_count,
+                   CAST(COALESCE(SUM(oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity)), 0) AS BIGINT)
+                                                                                                          AS face_value_in_cents,
+                   CAST(COALESCE(SUM(-d.unit_price_in_cents * (d.quantity - d.refunded_quantity)), 0) AS BIGINT)
+                                                                                                          AS discount_in_cents
+            FROM codes c
+                     LEFT JOIN (order_items oi JOIN orders o ON o.id = oi.order_id AND o.status = 'Paid')
+                               ON oi.code_id = c.id AND oi.item_type = 'Tickets'
+                     LEFT JOIN order_items d ON d.parent_id = oi.id AND d.item_type = 'Discount'
+            WHERE c.code_batch_id = $1;"#;
+
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load code batch summary")
+    }
+
+    /// Exports the batch's codes as CSV with the columns `redemption_code, status, redeemed_quantity`.
+    pub fn to_csv(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
+        let mut records = vec![vec![
+            "redemption_code".to_string(),
+            "status".to_string(),
+            "redeemed_quantity".to_string(),
+        ]];
+        for code in self.codes(conn)? {
+            let status = if code.deleted_at.is_some() {
+                "Revoked"
+            } else if code.redeemed_quantity > 0 {
+                "Redeemed"
+            } else {
+                "Available"
+            };
+            records.push(vec![
+                code.redemption_code,
+                status.to_string(),
+                code.redeemed_quantity.to_string(),
+            ]);
+        }
+
+        write_csv(&records)
+    }
+
+    /// Revokes every code in the batch. Tickets already purchased with the codes are not affected.
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<CodeBatch, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return DatabaseError::business_process_error("Code batch has already been revoked");
+        }
+
+        let revoked_count = diesel::update(
+            codes::table
+                .filter(codes::code_batch_id.eq(self.id))
+                .filter(codes::deleted_at.is_null()),
+        )
+        .set((codes::deleted_at.eq(dsl::now), codes::updated_at.eq(dsl::now)))
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not revoke codes for code batch")?;
+
+        let result: CodeBatch = diesel::update(self)
+            .set((
+                code_batches::revoked_at.eq(dsl::now.nullable()),
+                code_batches::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke code batch")?;
+
+        DomainEvent::create(
+            DomainEventTypes::CodeBatchRevoked,
+            format!("Code batch '{}' revoked", &self.name),
+            Tables::CodeBatches,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "revoked_count": revoked_count })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
@@ -2,10 +2,12 @@ use chrono::prelude::*;
 use diesel;
 use diesel::dsl;
 use diesel::prelude::*;
-use diesel::sql_types::{Array, BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{Array, BigInt, Bool, Nullable, Text, Timestamp, Uuid as dUuid};
 use models::*;
 use schema::{codes, order_items, orders};
 use std::borrow::Cow;
+use std::cmp;
+use std::collections::HashSet;
 use test::times;
 use utils::errors::*;
 use uuid::Uuid;
@@ -28,6 +30,29 @@ pub struct Code {
     pub updated_at: NaiveDateTime,
     pub discount_as_percentage: Option<i64>,
     pub deleted_at: Option<NaiveDateTime>,
+    pub min_quantity: Option<i64>,
+    pub buy_quantity: Option<i64>,
+    pub get_quantity: Option<i64>,
+    pub order_discount_in_cents: Option<i64>,
+    pub waives_fees: bool,
+    pub hold_stacking: CodeHoldStackingRules,
+    pub code_batch_id: Option<Uuid>,
+}
+
+/// Optional rules for how a discount code's discount is applied. Unset rules keep the plain
+/// per ticket discount.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CodeDiscountRules {
+    /// Tickets the order must contain with this code before the discount applies.
+    pub min_quantity: Option<u32>,
+    /// Buy X get Y, of every `buy_quantity + get_quantity` tickets the cheapest `get_quantity` are discounted.
+    pub buy_quantity: Option<u32>,
+    pub get_quantity: Option<u32>,
+    /// A flat discount for the order shared across the tickets using the code.
+    pub order_discount_in_cents: Option<u32>,
+    #[serde(default)]
+    pub waives_fees: bool,
+    pub hold_stacking: Option<CodeHoldStackingRules>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -69,6 +94,18 @@ pub struct DisplayCode {
     pub ticket_type_ids: Vec<Uuid>,
     #[sql_type = "Nullable<Timestamp>"]
     pub deleted_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<BigInt>"]
+    pub min_quantity: Option<i64>,
+    #[sql_type = "Nullable<BigInt>"]
+    pub buy_quantity: Option<i64>,
+    #[sql_type = "Nullable<BigInt>"]
+    pub get_quantity: Option<i64>,
+    #[sql_type = "Nullable<BigInt>"]
+    pub order_discount_in_cents: Option<i64>,
+    #[sql_type = "Bool"]
+    pub waives_fees: bool,
+    #[sql_type = "Text"]
+    pub hold_stacking: CodeHoldStackingRules,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -89,6 +126,12 @@ pub struct UpdateCodeAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub max_tickets_per_user: Option<Option<i64>>,
+    pub min_quantity: Option<Option<i64>>,
+    pub buy_quantity: Option<Option<i64>>,
+    pub get_quantity: Option<Option<i64>>,
+    pub order_discount_in_cents: Option<Option<i64>>,
+    pub waives_fees: Option<bool>,
+    pub hold_stacking: Option<CodeHoldStackingRules>,
 }
 
 impl Code {
@@ -119,12 +162,20 @@ impl Code {
         Ok(self.max_uses - Code::find_number_of_uses(self.id, None, conn)?)
     }
 
+    /// Uses of the code by orders other than `order_id_to_exclude`. Codes from a batch are single
+    /// use codes for a whole order so their uses are counted in orders rather than tickets, as
+    /// buy X get Y and minimum quantity rules need more than one ticket.
     pub fn find_number_of_uses(
         code_id: Uuid,
         order_id_to_exclude: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<i64, DatabaseError> {
-        let used: Vec<i64> = order_items::table
+        let code_batch_id: Option<Uuid> = codes::table
+            .find(code_id)
+            .select(codes::code_batch_id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load code")?;
+        let used: Vec<(Uuid, i64)> = order_items::table
             .inner_join(orders::table.on(order_items::order_id.eq(orders::id)))
             .filter(order_items::order_id.ne(order_id_to_exclude.unwrap_or(Uuid::nil())))
             .filter(order_items::code_id.eq(code_id))
@@ -134,11 +185,14 @@ impl Code {
                     .gt(dsl::now.nullable())
                     .or(orders::status.eq(OrderStatus::Paid)),
             )
-            .select(order_items::quantity)
+            .select((order_items::order_id, order_items::quantity))
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Error loading redemption code uses")?;
 
-        Ok(used.iter().fold(0, |acc, x| acc + x))
+        if code_batch_id.is_some() {
+            return Ok(used.iter().map(|(order_id, _)| order_id).collect::<HashSet<_>>().len() as i64);
+        }
+        Ok(used.iter().fold(0, |acc, (_, x)| acc + x))
     }
 
     pub fn update_ticket_types(&self, ticket_type_ids: Vec<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
@@ -199,6 +253,12 @@ impl Code {
             updated_at: self.updated_at,
             ticket_type_ids,
             deleted_at: None,
+            min_quantity: self.min_quantity,
+            buy_quantity: self.buy_quantity,
+            get_quantity: self.get_quantity,
+            order_discount_in_cents: self.order_discount_in_cents,
+            waives_fees: self.waives_fees,
+            hold_stacking: self.hold_stacking,
         };
 
         let available = display_code.max_uses - Code::find_number_of_uses(display_code.id, None, conn)?;
@@ -232,6 +292,13 @@ impl Code {
             start_date,
             end_date,
             max_tickets_per_user: max_tickets_per_user.map(|max| max as i64),
+            min_quantity: None,
+            buy_quantity: None,
+            get_quantity: None,
+            order_discount_in_cents: None,
+            waives_fees: false,
+            hold_stacking: CodeHoldStackingRules::Exclusive,
+            code_batch_id: None,
         }
     }
 
@@ -288,12 +355,19 @@ impl Code {
                     codes.created_at,
                     codes.updated_at,
                     ARRAY(select ticket_type_id FROM ticket_type_codes WHERE ticket_type_codes.code_id = codes.id) as ticket_type_ids,
-                    codes.deleted_at
+                    codes.deleted_at,
+                    codes.min_quantity,
+                    codes.buy_quantity,
+                    codes.get_quantity,
+                    codes.order_discount_in_cents,
+                    codes.waives_fees,
+                    codes.hold_stacking
                 FROM codes
                 WHERE
                     codes.event_id = $1
                     AND ($2 IS NULL OR codes.code_type = $2)
                     AND codes.deleted_at IS NULL
+                    AND codes.code_batch_id IS NULL
                 ORDER BY codes.name;"#;
 
         let display_codes: Vec<DisplayCode> = diesel::sql_query(query)
@@ -320,16 +394,22 @@ impl Code {
         code_type: CodeTypes,
         discount_in_cents: Option<i64>,
         discount_as_percentage: Option<i64>,
+        order_discount_in_cents: Option<i64>,
     ) -> Result<(), ValidationError> {
-        if code_type == CodeTypes::Discount && discount_in_cents.is_none() && discount_as_percentage.is_none() {
+        let discount_count = [discount_in_cents, discount_as_percentage, order_discount_in_cents]
+            .iter()
+            .filter(|d| d.is_some())
+            .count();
+        if code_type == CodeTypes::Discount && discount_count == 0 {
             let mut validation_error = create_validation_error("required", "Discount required for Discount code type");
             validation_error.add_param(Cow::from("code_type"), &code_type);
             validation_error.add_param(Cow::from("discount_in_cents"), &discount_in_cents);
             validation_error.add_param(Cow::from("discount_as_percentage"), &discount_as_percentage);
+            validation_error.add_param(Cow::from("order_discount_in_cents"), &order_discount_in_cents);
             return Err(validation_error);
         }
 
-        if code_type == CodeTypes::Discount && discount_in_cents.is_some() && discount_as_percentage.is_some() {
+        if code_type == CodeTypes::Discount && discount_count > 1 {
             let mut validation_error = create_validation_error(
                 "only_single_discount_type_allowed",
                 "Cannot apply more than one type of discount",
@@ -337,12 +417,180 @@ impl Code {
             validation_error.add_param(Cow::from("code_type"), &code_type);
             validation_error.add_param(Cow::from("discount_in_cents"), &discount_in_cents);
             validation_error.add_param(Cow::from("discount_as_percentage"), &discount_as_percentage);
+            validation_error.add_param(Cow::from("order_discount_in_cents"), &order_discount_in_cents);
             return Err(validation_error);
         }
 
         Ok(())
     }
 
+    // Validate that discount rules are only used by Discount codes and that buy X get Y codes have
+    // both quantities and a per ticket discount.
+    pub fn discount_rules_valid(
+        code_type: CodeTypes,
+        min_quantity: Option<i64>,
+        buy_quantity: Option<i64>,
+        get_quantity: Option<i64>,
+        order_discount_in_cents: Option<i64>,
+        waives_fees: bool,
+        hold_stacking: CodeHoldStackingRules,
+    ) -> Result<(), ValidationError> {
+        if code_type != CodeTypes::Discount
+            && (min_quantity.is_some()
+                || buy_quantity.is_some()
+                || get_quantity.is_some()
+                || order_discount_in_cents.is_some()
+                || waives_fees
+                || hold_stacking != CodeHoldStackingRules::Exclusive)
+        {
+            let mut validation_error = create_validation_error(
+                "discount_rules_require_discount_type",
+                "Discount rules can only be used with Discount codes",
+            );
+            validation_error.add_param(Cow::from("code_type"), &code_type);
+            return Err(validation_error);
+        }
+
+        if buy_quantity.is_some() != get_quantity.is_some() {
+            let mut validation_error = create_validation_error(
+                "buy_and_get_quantity_required",
+                "Buy and get quantities must be set together",
+            );
+            validation_error.add_param(Cow::from("buy_quantity"), &buy_quantity);
+            validation_error.add_param(Cow::from("get_quantity"), &get_quantity);
+            return Err(validation_error);
+        }
+
+        if buy_quantity.is_some() && order_discount_in_cents.is_some() {
+            let mut validation_error = create_validation_error(
+                "buy_quantity_with_order_discount",
+                "Buy X get Y codes must use a per ticket discount",
+            );
+            validation_error.add_param(Cow::from("buy_quantity"), &buy_quantity);
+            validation_error.add_param(Cow::from("order_discount_in_cents"), &order_discount_in_cents);
+            return Err(validation_error);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the code's discount depends on the order's other tickets using the code, so that
+    /// it changes as tickets are refunded.
+    pub fn discount_depends_on_order(&self) -> bool {
+        self.min_quantity.is_some() || self.buy_quantity.is_some() || self.order_discount_in_cents.is_some()
+    }
+
+    /// The total discount for the order item's tickets that have not been refunded, along with
+    /// the number of those tickets it discounts. Minimum quantities, buy X get Y and order
+    /// discounts depend on all of the order's tickets using the code that have not been refunded.
+    /// Order discounts are shared out by price rounding down, with the last item taking the
+    /// remainder so that exactly the full discount is applied.
+    pub fn discount_for_order_item(
+        &self,
+        order_item: &OrderItem,
+        conn: &PgConnection,
+    ) -> Result<(i64, i64), DatabaseError> {
+        let item_quantity = order_item.quantity - order_item.refunded_quantity;
+        if item_quantity <= 0 {
+            return Ok((0, 0));
+        }
+
+        let code_items: Vec<(OrderItem, i64)> = order_items::table
+            .filter(order_items::order_id.eq(order_item.order_id))
+            .filter(order_items::code_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .filter(order_items::quantity.gt(order_items::refunded_quantity))
+            .order_by(order_items::unit_price_in_cents)
+            .then_order_by(order_items::id)
+            .load::<OrderItem>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order items for code")?
+            .into_iter()
+            .map(|i| {
+                let quantity = i.quantity - i.refunded_quantity;
+                (i, quantity)
+            })
+            .collect();
+        let total_quantity: i64 = code_items.iter().map(|(_, quantity)| quantity).sum();
+        if total_quantity < self.min_quantity.unwrap_or(0) {
+            return Ok((0, 0));
+        }
+
+        if let Some(order_discount_in_cents) = self.order_discount_in_cents {
+            let subtotal: i64 = code_items
+                .iter()
+                .map(|(i, quantity)| i.unit_price_in_cents * quantity)
+                .sum();
+            if subtotal <= 0 {
+                return Ok((0, 0));
+            }
+            let order_discount = cmp::min(order_discount_in_cents, subtotal);
+            let mut remaining_discount = order_discount;
+            for (index, (code_item, quantity)) in code_items.iter().enumerate() {
+                let item_price = code_item.unit_price_in_cents * quantity;
+                let discount = if index == code_items.len() - 1 {
+                    remaining_discount
+                } else {
+                    order_discount * item_price / subtotal
+                };
+                let discount = cmp::min(discount, item_price);
+                if code_item.id == order_item.id {
+                    return Ok((discount, *quantity));
+                }
+                remaining_discount -= discount;
+            }
+            return Ok((0, 0));
+        }
+
+        let unit_discount = self.unit_discount(order_item.unit_price_in_cents);
+        if let (Some(buy_quantity), Some(get_quantity)) = (self.buy_quantity, self.get_quantity) {
+            let group_size = buy_quantity + get_quantity;
+            let mut discounted_quantity =
+                (total_quantity / group_size) * get_quantity + cmp::max(0, total_quantity % group_size - buy_quantity);
+            // The cheapest tickets are the ones discounted
+            for (code_item, quantity) in code_items {
+                let quantity = cmp::min(discounted_quantity, quantity);
+                if code_item.id == order_item.id {
+                    return Ok((quantity * unit_discount, quantity));
+                }
+                discounted_quantity -= quantity;
+            }
+            return Ok((0, 0));
+        }
+
+        Ok((unit_discount * item_quantity, item_quantity))
+    }
+
+    /// The discount for the stacking rules when the code is used together with a hold. Discounts
+    /// are the total for the item along with the number of tickets discounted.
+    pub fn stacked_discount(
+        &self,
+        hold_discount: (i64, i64),
+        code_discount: (i64, i64),
+        price_in_cents: i64,
+    ) -> (i64, i64) {
+        match self.hold_stacking {
+            CodeHoldStackingRules::Exclusive => hold_discount,
+            CodeHoldStackingRules::BestDiscount => cmp::max(hold_discount, code_discount),
+            CodeHoldStackingRules::Combine => (
+                cmp::min(hold_discount.0 + code_discount.0, price_in_cents),
+                cmp::max(hold_discount.1, code_discount.1),
+            ),
+        }
+    }
+
+    fn unit_discount(&self, unit_price_in_cents: i64) -> i64 {
+        if let Some(discount_percent) = self.discount_as_percentage {
+            cmp::min(
+                ((unit_price_in_cents as f32) * (discount_percent as f32) / 100.0f32) as i64,
+                unit_price_in_cents,
+            )
+        } else if let Some(discount_in_cents) = self.discount_in_cents {
+            cmp::min(discount_in_cents, unit_price_in_cents)
+        } else {
+            0
+        }
+    }
+
     fn validate_record(&self, update_attrs: &UpdateCodeAttributes, conn: &PgConnection) -> Result<(), DatabaseError> {
         let mut validation_errors = update_attrs.validate();
 
@@ -364,6 +612,25 @@ impl Code {
                 update_attrs
                     .discount_as_percentage
                     .unwrap_or(self.discount_as_percentage),
+                update_attrs
+                    .order_discount_in_cents
+                    .unwrap_or(self.order_discount_in_cents),
+            ),
+        );
+
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "discount_rules",
+            Code::discount_rules_valid(
+                self.code_type.clone(),
+                update_attrs.min_quantity.unwrap_or(self.min_quantity),
+                update_attrs.buy_quantity.unwrap_or(self.buy_quantity),
+                update_attrs.get_quantity.unwrap_or(self.get_quantity),
+                update_attrs
+                    .order_discount_in_cents
+                    .unwrap_or(self.order_discount_in_cents),
+                update_attrs.waives_fees.unwrap_or(self.waives_fees),
+                update_attrs.hold_stacking.unwrap_or(self.hold_stacking),
             ),
         );
 
@@ -393,9 +660,13 @@ impl Code {
     ) -> Result<Code, DatabaseError> {
         let mut update_attrs = update_attrs;
 
-        if update_attrs.discount_in_cents.is_some() || update_attrs.discount_as_percentage.is_some() {
+        if update_attrs.discount_in_cents.is_some()
+            || update_attrs.discount_as_percentage.is_some()
+            || update_attrs.order_discount_in_cents.is_some()
+        {
             update_attrs.discount_in_cents = Some(update_attrs.discount_in_cents.unwrap_or(None));
             update_attrs.discount_as_percentage = Some(update_attrs.discount_as_percentage.unwrap_or(None));
+            update_attrs.order_discount_in_cents = Some(update_attrs.order_discount_in_cents.unwrap_or(None));
         }
 
         self.validate_record(&update_attrs, conn)?;
@@ -450,7 +721,7 @@ impl Code {
     }
 }
 
-#[derive(Deserialize, Insertable, Serialize, Validate)]
+#[derive(Clone, Deserialize, Insertable, Serialize, Validate)]
 #[table_name = "codes"]
 pub struct NewCode {
     pub name: String,
@@ -463,9 +734,26 @@ pub struct NewCode {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub max_tickets_per_user: Option<i64>,
+    pub min_quantity: Option<i64>,
+    pub buy_quantity: Option<i64>,
+    pub get_quantity: Option<i64>,
+    pub order_discount_in_cents: Option<i64>,
+    pub waives_fees: bool,
+    pub hold_stacking: CodeHoldStackingRules,
+    pub code_batch_id: Option<Uuid>,
 }
 
 impl NewCode {
+    pub fn with_discount_rules(mut self, discount_rules: CodeDiscountRules) -> NewCode {
+        self.min_quantity = discount_rules.min_quantity.map(|q| q as i64);
+        self.buy_quantity = discount_rules.buy_quantity.map(|q| q as i64);
+        self.get_quantity = discount_rules.get_quantity.map(|q| q as i64);
+        self.order_discount_in_cents = discount_rules.order_discount_in_cents.map(|d| d as i64);
+        self.waives_fees = discount_rules.waives_fees;
+        self.hold_stacking = discount_rules.hold_stacking.unwrap_or(CodeHoldStackingRules::Exclusive);
+        self
+    }
+
     pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Code, DatabaseError> {
         self.validate_record(conn)?;
 
@@ -487,7 +775,7 @@ impl NewCode {
         Ok(result)
     }
 
-    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+    pub(crate) fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let mut validation_errors = self.validate();
 
         validation_errors = validators::append_validation_error(
@@ -497,6 +785,20 @@ impl NewCode {
                 self.code_type.clone(),
                 self.discount_in_cents,
                 self.discount_as_percentage,
+                self.order_discount_in_cents,
+            ),
+        );
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "discount_rules",
+            Code::discount_rules_valid(
+                self.code_type.clone(),
+                self.min_quantity,
+                self.buy_quantity,
+                self.get_quantity,
+                self.order_discount_in_cents,
+                self.waives_fees,
+                self.hold_stacking,
             ),
         );
         validation_errors = validators::append_validation_error(
@@ -0,0 +1,169 @@
+use chrono::NaiveDateTime;
+use diesel::PgConnection;
+use models::*;
+use utils::csv::parse_csv;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validator::validate_email;
+
+const COMP_IMPORT_COLUMNS: [&str; 4] = ["name", "email", "phone", "quantity"];
+const COMP_IMPORT_REDEMPTION_CODE_LENGTH: usize = 10;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CompImportRow {
+    pub row: usize,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub quantity: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CompImportRowError {
+    pub row: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CompImportResult {
+    pub comps: Vec<Hold>,
+    pub errors: Vec<CompImportRowError>,
+}
+
+/// A guest list to be issued as comps from a single hold. Rows are numbered as they appear in the
+/// uploaded file, including the optional header row, so errors can be matched to the spreadsheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompImport {
+    pub hold_id: Uuid,
+    pub rows: Vec<CompImportRow>,
+    pub errors: Vec<CompImportRowError>,
+    pub end_at: Option<NaiveDateTime>,
+    pub max_per_user: Option<u32>,
+}
+
+impl CompImport {
+    /// Reads a guest list with the columns `name, email, phone, quantity`. Rows that cannot be read
+    /// are recorded in `errors` rather than failing the whole import.
+    pub fn from_csv(
+        hold_id: Uuid,
+        content: &str,
+        end_at: Option<NaiveDateTime>,
+        max_per_user: Option<u32>,
+    ) -> Result<CompImport, DatabaseError> {
+        let records = parse_csv(content)?;
+
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
+        for (index, (row, record)) in records.into_iter().enumerate() {
+            if record.iter().all(|field| field.is_empty()) {
+                continue;
+            }
+            if index == 0 && record[0].to_lowercase() == COMP_IMPORT_COLUMNS[0] {
+                continue;
+            }
+            if record.len() != COMP_IMPORT_COLUMNS.len() {
+                errors.push(CompImportRowError {
+                    row,
+                    errors: vec![format!(
+                        "Expected {} columns ({}) but found {}",
+                        COMP_IMPORT_COLUMNS.len(),
+                        COMP_IMPORT_COLUMNS.join(", "),
+                        record.len()
+                    )],
+                });
+                continue;
+            }
+            let quantity = match record[3].parse::<u32>() {
+                Ok(quantity) => quantity,
+                Err(_) => {
+                    errors.push(CompImportRowError {
+                        row,
+                        errors: vec![format!("Quantity '{}' is not a valid number", record[3])],
+                    });
+                    continue;
+                }
+            };
+            let optional = |value: &String| if value.is_empty() { None } else { Some(value.clone()) };
+            rows.push(CompImportRow {
+                row,
+                name: record[0].clone(),
+                email: optional(&record[1]),
+                phone: optional(&record[2]),
+                quantity,
+            });
+        }
+
+        Ok(CompImport {
+            hold_id,
+            rows,
+            errors,
+            end_at,
+            max_per_user,
+        })
+    }
+
+    /// Validates every row against the hold's remaining quantity and creates a comp for each guest.
+    /// If any row is invalid no comps are created and the errors for every row are returned instead.
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<CompImportResult, DatabaseError> {
+        let hold = Hold::find(self.hold_id, conn)?;
+        if self.rows.is_empty() && self.errors.is_empty() {
+            return DatabaseError::business_process_error("Guest list does not contain any guests");
+        }
+
+        let (_, available) = hold.quantity(conn)?;
+        let mut errors = self.errors;
+        let mut total_quantity = 0;
+        for row in &self.rows {
+            let mut row_errors = Vec::new();
+            if row.name.is_empty() {
+                row_errors.push("Name is required".to_string());
+            }
+            if let Some(ref email) = row.email {
+                if !validate_email(email.as_str()) {
+                    row_errors.push("Email is invalid".to_string());
+                }
+            }
+            if row.quantity == 0 {
+                row_errors.push("Quantity must be greater than 0".to_string());
+            }
+            total_quantity += row.quantity;
+            if total_quantity > available {
+                row_errors.push(format!(
+                    "Quantity exceeds the {} tickets remaining in the hold",
+                    available
+                ));
+            }
+
+            if !row_errors.is_empty() {
+                errors.push(CompImportRowError {
+                    row: row.row,
+                    errors: row_errors,
+                });
+            }
+        }
+
+        if !errors.is_empty() {
+            errors.sort_by_key(|e| e.row);
+            return Ok(CompImportResult { comps: vec![], errors });
+        }
+
+        let mut comps = Vec::new();
+        for row in self.rows {
+            comps.push(Hold::create_comp_for_person(
+                row.name,
+                current_user_id,
+                hold.id,
+                row.email,
+                row.phone,
+                random_alpha_string(COMP_IMPORT_REDEMPTION_CODE_LENGTH),
+                self.end_at,
+                self.max_per_user,
+                row.quantity,
+                conn,
+            )?);
+        }
+
+        Ok(CompImportResult { comps, errors })
+    }
+}
@@ -230,6 +230,7 @@ impl DomainEvent {
         data.insert("webhook_event_type".to_string(), json!("purchase_ticket"));
         data.insert("order_id".to_string(), json!(order.id));
         data.insert("order_number".to_string(), json!(order.order_number()));
+        data.insert("currency".to_string(), json!(order.currency));
         let user = order.user(conn)?;
         data.insert("customer_email".to_string(), json!(user.email));
         data.insert("customer_first_name".to_string(), json!(user.first_name));
@@ -252,6 +253,8 @@ impl DomainEvent {
         let mut refunded_fees_total = 0;
         let mut discount_total = 0;
         let mut refunded_discount_total = 0;
+        let mut tax_total = 0;
+        let mut refunded_tax_total = 0;
         let mut j_items = Vec::<R>::new();
         for item in order.items(conn)? {
             let item_total = item.unit_price_in_cents * item.quantity;
@@ -266,11 +269,15 @@ impl DomainEvent {
             });
 
             match item.item_type {
-                OrderItemTypes::Tickets => {
+                OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets => {
                     count = count + item.quantity - item.refunded_quantity;
                     sub_total = sub_total + item_total;
                     refunded_sub_total = refunded_sub_total + refunded_total;
                 }
+                OrderItemTypes::Products => {
+                    sub_total = sub_total + item_total;
+                    refunded_sub_total = refunded_sub_total + refunded_total;
+                }
                 OrderItemTypes::Discount => {
                     discount_total = discount_total + item_total;
                     refunded_discount_total = refunded_discount_total + refunded_total;
@@ -279,6 +286,10 @@ impl DomainEvent {
                     fees_total = fees_total + item_total;
                     refunded_fees_total = refunded_fees_total + refunded_total;
                 }
+                OrderItemTypes::Tax => {
+                    tax_total = tax_total + item.tax_in_cents * item.quantity;
+                    refunded_tax_total = refunded_tax_total + item.tax_in_cents * item.refunded_quantity;
+                }
             }
         }
 
@@ -290,6 +301,8 @@ impl DomainEvent {
         data.insert("refunded_fees_total".to_string(), json!(refunded_fees_total));
         data.insert("discount_total".to_string(), json!(discount_total));
         data.insert("refunded_discount_total".to_string(), json!(refunded_discount_total));
+        data.insert("tax_total".to_string(), json!(tax_total));
+        data.insert("refunded_tax_total".to_string(), json!(refunded_tax_total));
 
         data.insert(
             "user_id".to_string(),
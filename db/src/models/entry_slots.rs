@@ -0,0 +1,294 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::{entry_slots, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+// Guards against schedules that would create an unreasonable number of slots in one request
+pub const MAXIMUM_ENTRY_SLOTS_PER_SCHEDULE: usize = 5000;
+
+#[derive(Clone, Associations, Identifiable, Queryable, QueryableByName, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[table_name = "entry_slots"]
+pub struct EntrySlot {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub entry_start: NaiveDateTime,
+    pub entry_end: NaiveDateTime,
+    pub capacity: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "entry_slots"]
+pub struct NewEntrySlot {
+    pub ticket_type_id: Uuid,
+    pub entry_start: NaiveDateTime,
+    pub entry_end: NaiveDateTime,
+    pub capacity: i32,
+}
+
+impl NewEntrySlot {
+    pub fn commit(&self, conn: &PgConnection) -> Result<EntrySlot, DatabaseError> {
+        self.validate_record()?;
+        diesel::insert_into(entry_slots::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create entry slot")
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        if self.entry_end <= self.entry_start {
+            return DatabaseError::validation_error("entry_end", "Entry end must be after entry start");
+        }
+        if self.capacity < 0 {
+            return DatabaseError::validation_error("capacity", "Capacity cannot be negative");
+        }
+        Ok(())
+    }
+}
+
+/// Daily schedule used to generate entry slots. Slots start every `interval_minutes` from
+/// `first_entry` up to and including `last_entry` on each day from `start_date` to `end_date`.
+/// Each slot stays open for entry for `entry_window_minutes`, defaulting to the interval.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EntrySlotSchedule {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub first_entry: NaiveTime,
+    pub last_entry: NaiveTime,
+    pub interval_minutes: u32,
+    pub entry_window_minutes: Option<u32>,
+    pub capacity: u32,
+}
+
+impl EntrySlotSchedule {
+    pub fn slots(&self, ticket_type_id: Uuid) -> Result<Vec<NewEntrySlot>, DatabaseError> {
+        if self.end_date < self.start_date {
+            return DatabaseError::validation_error("end_date", "End date must not be before start date");
+        }
+        if self.last_entry < self.first_entry {
+            return DatabaseError::validation_error("last_entry", "Last entry must not be before first entry");
+        }
+        if self.interval_minutes == 0 {
+            return DatabaseError::validation_error("interval_minutes", "Interval must be a positive number");
+        }
+        if self.entry_window_minutes == Some(0) {
+            return DatabaseError::validation_error("entry_window_minutes", "Entry window must be a positive number");
+        }
+
+        let interval = Duration::minutes(self.interval_minutes as i64);
+        let entry_window = Duration::minutes(self.entry_window_minutes.unwrap_or(self.interval_minutes) as i64);
+        let mut slots = Vec::new();
+        let mut date = self.start_date;
+        while date <= self.end_date {
+            let last_entry = date.and_time(self.last_entry);
+            let mut entry_start = date.and_time(self.first_entry);
+            while entry_start <= last_entry {
+                slots.push(NewEntrySlot {
+                    ticket_type_id,
+                    entry_start,
+                    entry_end: entry_start + entry_window,
+                    capacity: self.capacity as i32,
+                });
+                if slots.len() > MAXIMUM_ENTRY_SLOTS_PER_SCHEDULE {
+                    return DatabaseError::validation_error(
+                        "interval_minutes",
+                        "Schedule creates too many entry slots",
+                    );
+                }
+                entry_start = entry_start + interval;
+            }
+            date = date.succ();
+        }
+
+        Ok(slots)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DisplayEntrySlot {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Timestamp"]
+    pub entry_start: NaiveDateTime,
+    #[sql_type = "Timestamp"]
+    pub entry_end: NaiveDateTime,
+    #[sql_type = "Integer"]
+    pub capacity: i32,
+    #[sql_type = "BigInt"]
+    pub available: i64,
+}
+
+impl EntrySlot {
+    pub fn create(
+        ticket_type_id: Uuid,
+        entry_start: NaiveDateTime,
+        entry_end: NaiveDateTime,
+        capacity: i32,
+    ) -> NewEntrySlot {
+        NewEntrySlot {
+            ticket_type_id,
+            entry_start,
+            entry_end,
+            capacity,
+        }
+    }
+
+    /// Creates the slots described by the schedule for the ticket type. Slots that already exist
+    /// for an entry start are left untouched so a schedule can be extended by re-submitting it.
+    pub fn create_from_schedule(
+        ticket_type: &TicketType,
+        schedule: &EntrySlotSchedule,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<EntrySlot>, DatabaseError> {
+        let existing_starts: Vec<NaiveDateTime> = EntrySlot::find_for_ticket_type(ticket_type.id, conn)?
+            .into_iter()
+            .map(|s| s.entry_start)
+            .collect();
+
+        let mut entry_slots = Vec::new();
+        for slot in schedule
+            .slots(ticket_type.id)?
+            .into_iter()
+            .filter(|s| !existing_starts.contains(&s.entry_start))
+        {
+            entry_slots.push(slot.commit(conn)?);
+        }
+
+        let entry_slot_ids: Vec<Uuid> = entry_slots.iter().map(|s| s.id).collect();
+        DomainEvent::create(
+            DomainEventTypes::TicketTypeEntrySlotsCreated,
+            format!("{} entry slots created", entry_slots.len()),
+            Tables::TicketTypes,
+            Some(ticket_type.id),
+            current_user_id,
+            Some(json!({ "schedule": schedule, "entry_slot_ids": entry_slot_ids })),
+        )
+        .commit(conn)?;
+
+        Ok(entry_slots)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EntrySlot, DatabaseError> {
+        entry_slots::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading entry slot")
+    }
+
+    pub fn find_for_ticket_type(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<EntrySlot>, DatabaseError> {
+        entry_slots::table
+            .filter(entry_slots::ticket_type_id.eq(ticket_type_id))
+            .order_by(entry_slots::entry_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load entry slots")
+    }
+
+    pub fn find_for_ticket_type_with_availability(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayEntrySlot>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/find_entry_slots_for_ticket_type.sql"))
+            .bind::<dUuid, _>(ticket_type_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load entry slots for ticket type")
+    }
+
+    /// Earliest slot of the ticket type that is still open for entry and can take `quantity` more
+    /// tickets. Tickets in `excluded_ticket_instance_ids` do not count against capacity.
+    pub fn find_first_available(
+        ticket_type_id: Uuid,
+        quantity: i64,
+        excluded_ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Option<EntrySlot>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        for entry_slot in EntrySlot::find_for_ticket_type(ticket_type_id, conn)?
+            .into_iter()
+            .filter(|s| s.entry_end > now)
+        {
+            if entry_slot.remaining_capacity(excluded_ticket_instance_ids, conn)? >= quantity {
+                return Ok(Some(entry_slot));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Capacity left in the slot after purchased, redeemed and currently reserved tickets. The slot
+    /// row is locked until the end of the transaction so concurrent carts cannot oversell it.
+    pub fn remaining_capacity(
+        &self,
+        excluded_ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        entry_slots::table
+            .find(self.id)
+            .select(entry_slots::id)
+            .for_update()
+            .first::<Uuid>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock entry slot")?;
+
+        let taken: i64 = ticket_instances::table
+            .filter(ticket_instances::entry_slot_id.eq(self.id))
+            .filter(ticket_instances::id.ne_all(excluded_ticket_instance_ids))
+            .filter(
+                ticket_instances::status
+                    .eq_any(vec![TicketInstanceStatus::Purchased, TicketInstanceStatus::Redeemed])
+                    .or(ticket_instances::status
+                        .eq(TicketInstanceStatus::Reserved)
+                        .and(ticket_instances::reserved_until.ge(dsl::now.nullable()))),
+            )
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count tickets for entry slot")?;
+
+        Ok(self.capacity as i64 - taken)
+    }
+
+    pub fn is_within_entry_window(&self, time: NaiveDateTime) -> bool {
+        self.entry_start <= time && time <= self.entry_end
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let ticket_count: i64 = ticket_instances::table
+            .filter(ticket_instances::entry_slot_id.eq(self.id))
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count tickets for entry slot")?;
+        if ticket_count > 0 {
+            return DatabaseError::business_process_error(
+                "Entry slot cannot be deleted once tickets have been assigned to it",
+            );
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::TicketTypeEntrySlotDeleted,
+            "Entry slot deleted".to_string(),
+            Tables::TicketTypes,
+            Some(self.ticket_type_id),
+            current_user_id,
+            Some(json!({ "entry_slot_id": self.id, "entry_start": self.entry_start, "entry_end": self.entry_end })),
+        )
+        .commit(conn)?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete entry slot")
+    }
+}
@@ -81,15 +81,21 @@ macro_rules! string_enum {
     }
 }
 
-string_enum! { ActivityType [Purchase, Transfer, CheckIn,Refund, Note]}
+string_enum! { ActivityType [Purchase, Transfer, CheckIn,Refund, Note, Exchange]}
 string_enum! { AssetStatus [Unsynced] }
 string_enum! { BroadcastAudience [ PeopleAtTheEvent, TicketHolders  ]}
 string_enum! { CartItemStatus [CodeExpired, HoldExpired, TicketNullified, TicketNotReserved, Valid] }
 string_enum! { CheckInSource [GuestList, Scanned] }
+string_enum! { CodeHoldStackingRules [Exclusive, BestDiscount, Combine] }
 string_enum! { CodeTypes [Access, Discount] }
 string_enum! { CommunicationChannelType [Email, Sms, Push, Webhook]}
 string_enum! { CommunicationType [EmailTemplate, Sms, Push, Webhook]}
 string_enum! { DomainEventTypes [
+    CapacityPoolCreated,
+    CapacityPoolDeleted,
+    CapacityPoolUpdated,
+    CodeBatchCreated,
+    CodeBatchRevoked,
     CodeCreated,
     CodeDeleted,
     CodeUpdated,
@@ -101,8 +107,16 @@ string_enum! { DomainEventTypes [
     EventDeleted,
     EventInterestCreated,
     EventPublished,
+    EventQuestionCreated,
+    EventQuestionDeleted,
+    EventQuestionUpdated,
     EventReportSubscriberCreated,
     EventReportSubscriberDeleted,
+    EventRescheduled,
+    EventRescheduleRefundWindowClosed,
+    EventSeriesCreated,
+    EventSeriesOccurrencesGenerated,
+    EventSeriesUpdated,
     EventUpdated,
     EventUnpublished,
     ExternalLoginCreated,
@@ -112,6 +126,7 @@ string_enum! { DomainEventTypes [
     HoldCreated,
     HoldDeleted,
     HoldQuantityChanged,
+    OrderAttendeeAnswersUpdated,
     OrderBehalfOfUserChanged,
     OrderCompleted,
     OrderCreated,
@@ -123,14 +138,36 @@ string_enum! { DomainEventTypes [
     OrganizationCreated,
     NoteCreated,
     NoteDeleted,
+    PackageCreated,
+    PackageUpdated,
     PaymentCancelled,
     PaymentCreated,
     PaymentCompleted,
+    PaymentDisputeClosed,
+    PaymentDisputed,
     PaymentRefund,
     PaymentProviderIPN,
     PaymentMethodCreated,
     PaymentMethodUpdated,
+    PaymentPlanCancelled,
+    PaymentPlanCompleted,
+    PaymentPlanCreated,
+    PaymentPlanInstallmentFailed,
+    PaymentPlanInstallmentPaid,
     PaymentUpdated,
+    PresaleLotteryCreated,
+    PresaleLotteryDrawn,
+    PresaleLotteryEntryCreated,
+    PresaleLotteryEntryLost,
+    PresaleLotteryEntryWon,
+    PricingRuleCreated,
+    PricingRuleDeleted,
+    PricingRuleUpdated,
+    ProductCreated,
+    ProductInstanceRedeemed,
+    ProductUpdated,
+    ProductVariantCreated,
+    ProductVariantUpdated,
     UserCreated,
     UserDisabled,
     UserLogin,
@@ -140,6 +177,10 @@ string_enum! { DomainEventTypes [
     PurchaseCompleted,
     PushNotificationTokenCreated,
     SettlementReportProcessed,
+    StoreCreditTransactionCreated,
+    TaxRuleCreated,
+    TaxRuleDeleted,
+    TaxRuleUpdated,
     TransferTicketDripSourceSent,
     TransferTicketDripDestinationSent,
     TransferTicketCancelled,
@@ -148,31 +189,57 @@ string_enum! { DomainEventTypes [
     TrackingDataUpdated,
     TemporaryUserCreated,
     TicketInstanceAddedToHold,
+    TicketInstanceExchanged,
     TicketInstanceNullified,
     TicketInstancePurchased,
     TicketInstanceRedeemed,
     TicketInstanceReleasedFromHold,
     TicketInstanceUpdated,
     TicketPricingAdded,
+    TicketPricingAdjusted,
     TicketPricingCreated,
     TicketPricingDeleted,
+    TicketPricingSalesEnded,
     TicketPricingSalesStarted,
     TicketPricingUpdated,
     TicketTypeCreated,
+    TicketTypeEntrySlotDeleted,
+    TicketTypeEntrySlotsCreated,
     TicketTypeSalesStarted,
     TicketTypeSoldOut,
-    TicketTypeUpdated
+    TicketTypeUpdated,
+    WaitlistEntryCancelled,
+    WaitlistEntryCreated,
+    WaitlistOfferExpired,
+    WaitlistOfferPurchased,
+    WaitlistOfferSent,
+    ResaleListingCancelled,
+    ResaleListingCreated,
+    ResaleListingSold
 ]}
 string_enum! { DomainActionTypes [
     BroadcastPushNotification,
+    CancelPaymentPlan,
+    ChargePaymentPlanInstallment,
+    CloseEventRescheduleRefundWindow,
     // Email/SMS/Push Communication
     Communication,
+    DrawPresaleLottery,
+    EvaluatePricingRules,
+    ExpireWaitlistOffer,
     PaymentProviderIPN,
+    ProcessEventReschedule,
+    ProcessPaymentProviderEvent,
     ProcessSettlementReport,
     ProcessTransferDrip,
+    ProcessWaitlist,
+    RefundExpiredOrderPayments,
     RegenerateDripActions,
+    ReleaseExpiredHolds,
     RetargetAbandonedOrders,
     SendAutomaticReportEmails,
+    SendPaymentPlanDunningCommunication,
+    SendPresaleLotteryResults,
     SendPurchaseCompletedCommunication,
     SubmitSitemapToSearchEngines,
     UpdateGenres
@@ -182,36 +249,51 @@ string_enum! { BroadcastChannel [PushNotification, Email]}
 string_enum! { BroadcastType [Custom, LastCall]}
 string_enum! { DomainActionStatus [Pending, RetriesExceeded, Errored, Success, Cancelled]}
 string_enum! { EmailProvider [Sendgrid, CustomerIo]}
+string_enum! { EntryWindowPolicy [Reject, Warn] }
 string_enum! { Environment [Development, Production, Staging, Test]}
 string_enum! { EventStatus [Draft,Closed,Published,Offline]}
+string_enum! { EventQuestionTypes [Checkbox, Choice, Text] }
 string_enum! { EventSearchSortField [ Name, EventStart]}
 string_enum! { EventOverrideStatus [PurchaseTickets,SoldOut,OnSaleSoon,TicketsAtTheDoor,Free,Rescheduled,Cancelled,OffSale,Ended]}
 string_enum! { EventTypes [ Music, Conference, Art, Culinary, Comedy, Sports, Tech, Other]}
 string_enum! { ExternalPaymentType [Cash, CreditCard, Voucher]}
 string_enum! { FanSortField [FirstName, LastName, Email, Phone, OrganizationId, UserCreated, Orders, FirstOrder, LastOrder, Revenue, FirstInteracted, LastInteracted] }
 string_enum! { HistoryType [Purchase]}
+string_enum! { HoldExpiryBehaviors [Keep, Release] }
 string_enum! { HoldTypes [Discount, Comp] }
 string_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees]}
+string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees, ResaleTickets, Products, Tax]}
 string_enum! { OrderTypes [Cart, BackOffice] }
-string_enum! { PaymentMethods [CreditCard, External, Free, Provider] }
-string_enum! { PaymentProviders [External, Globee, Free, Stripe] }
+string_enum! { PaymentDisputeStatus [Open, Won, Lost] }
+string_enum! { PaymentMethods [CreditCard, Exchange, External, Free, Provider, StoreCredit] }
+string_enum! { PaymentPlanInstallmentStatus [Pending, Paid, Failed, Cancelled] }
+string_enum! { PaymentPlanRefundPolicies [FullRefund, RetainDeposit, NoRefund] }
+string_enum! { PaymentPlanStatus [Active, Completed, Cancelled] }
+string_enum! { PaymentProviders [Exchange, External, Globee, Free, StoreCredit, Stripe] }
 string_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
 string_enum! { PastOrUpcoming [Past,Upcoming]}
 string_enum! { Platforms [Web, App, BoxOffice]}
+string_enum! { PresaleLotteryEntryStatus [Registered, Won, Lost] }
+string_enum! { PresaleLotteryStatus [Open, Drawn] }
+string_enum! { PricingRuleTypes [DaysUntilEvent, PageViews, SellThrough] }
+string_enum! { ProductInstanceStatus [Purchased, Redeemed, Refunded] }
 string_enum! { ReportTypes [TicketCounts]}
+string_enum! { ResaleListingStatus [Active, Sold, Cancelled] }
 string_enum! { Roles [Admin, DoorPerson, OrgAdmin, OrgBoxOffice, OrgMember, OrgOwner, PrismIntegration, Promoter, PromoterReadOnly, User, Super] }
 string_enum! { SettlementStatus[PendingSettlement, RequiresAudit, SettledInFull] }
 string_enum! { SettlementTypes [Rolling, PostEvent]}
 string_enum! { SettlementAdjustmentTypes [ManualCredit, ManualDeduction, Chargeback]}
-string_enum! { SettlementEntryTypes [EventFees, TicketType]}
+string_enum! { SettlementEntryTypes [EventFees, TicketType, Resale, Product, Tax]}
 string_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre ] }
 string_enum! { SortingDir[ Asc, Desc ] }
 string_enum! { SourceOrDestination [Destination,Source]}
+string_enum! { StoreCreditTransactionTypes [GiftCard, Payment, Refund] }
 string_enum! { Tables [
     Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, ExternalLogins, FeeSchedules,
     Holds, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
-    TicketPricing, Transfers, Users, Venues, Genres
+    TicketPricing, Transfers, Users, Venues, Genres, WaitlistEntries, ResaleListings, EventSeries, Products, Packages, CapacityPools,
+    PricingRules, EventReschedules, EventQuestions, PresaleLotteries, PresaleLotteryEntries, CodeBatches, StoreCreditTransactions,
+    TaxRules, PaymentPlans, PaymentPlanInstallments, PaymentProviderEvents
 ] }
 string_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 string_enum! { TicketPricingStatus [Published, Deleted, Default] }
@@ -220,6 +302,7 @@ string_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, OnSaleSoon
 string_enum! { TicketTypeVisibility [ Always, Hidden, WhenAvailable ]}
 string_enum! { TransferMessageType [Email, Phone] }
 string_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded] }
+string_enum! { WaitlistEntryStatus [Waiting, Offered, Purchased, Expired, Cancelled] }
 string_enum! { WebhookAdapters [CustomerIo]}
 
 impl Roles {
@@ -0,0 +1,307 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_answers, event_questions, events, orders};
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(EventQuestion)]
+#[belongs_to(Order)]
+#[table_name = "event_answers"]
+pub struct EventAnswer {
+    pub id: Uuid,
+    pub event_question_id: Uuid,
+    pub order_id: Uuid,
+    pub ticket_instance_id: Option<Uuid>,
+    pub answer: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "event_answers"]
+struct NewEventAnswer {
+    event_question_id: Uuid,
+    order_id: Uuid,
+    ticket_instance_id: Option<Uuid>,
+    answer: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventAnswerRequest {
+    pub event_question_id: Uuid,
+    #[serde(default)]
+    pub ticket_instance_id: Option<Uuid>,
+    pub answer: String,
+}
+
+/// A question the order needs answered. Per ticket questions appear once for every ticket.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AttendeeQuestion {
+    pub question: EventQuestion,
+    pub ticket_instance_id: Option<Uuid>,
+    pub ticket_type_id: Option<Uuid>,
+    pub answer: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplayEventAnswer {
+    pub event_id: Uuid,
+    pub event_question_id: Uuid,
+    pub question: String,
+    pub order_id: Uuid,
+    pub ticket_instance_id: Option<Uuid>,
+    pub answer: String,
+    pub updated_at: NaiveDateTime,
+}
+
+impl EventAnswer {
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<EventAnswer>, DatabaseError> {
+        event_answers::table
+            .filter(event_answers::order_id.eq(order_id))
+            .order_by(event_answers::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event answers")
+    }
+
+    /// Answers given on the orders for questions of the event.
+    pub fn find_for_event_orders(
+        event_id: Uuid,
+        order_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayEventAnswer>, DatabaseError> {
+        event_answers::table
+            .inner_join(event_questions::table)
+            .filter(event_questions::event_id.eq(event_id))
+            .filter(event_questions::deleted_at.is_null())
+            .filter(event_answers::order_id.eq_any(order_ids))
+            .select(EventAnswer::display_columns())
+            .order_by(event_questions::rank)
+            .then_order_by(event_questions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event answers")
+    }
+
+    /// Answers the user gave on their orders for events of the organization.
+    pub fn find_for_user_and_organization(
+        user_id: Uuid,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayEventAnswer>, DatabaseError> {
+        event_answers::table
+            .inner_join(event_questions::table.inner_join(events::table))
+            .inner_join(orders::table)
+            .filter(events::organization_id.eq(organization_id))
+            .filter(event_questions::deleted_at.is_null())
+            .filter(
+                orders::on_behalf_of_user_id
+                    .eq(user_id)
+                    .or(orders::on_behalf_of_user_id.is_null().and(orders::user_id.eq(user_id))),
+            )
+            .filter(orders::status.eq(OrderStatus::Paid))
+            .select(EventAnswer::display_columns())
+            .order_by(events::event_start.desc())
+            .then_order_by(event_questions::rank)
+            .then_order_by(event_questions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event answers")
+    }
+
+    /// The questions the order has to answer along with any answers already given.
+    pub fn questions_for_order(order: &Order, conn: &PgConnection) -> Result<Vec<AttendeeQuestion>, DatabaseError> {
+        let answers: HashMap<(Uuid, Option<Uuid>), String> = EventAnswer::find_for_order(order.id, conn)?
+            .into_iter()
+            .map(|a| ((a.event_question_id, a.ticket_instance_id), a.answer))
+            .collect();
+
+        let items: Vec<OrderItem> = order
+            .items(conn)?
+            .into_iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets)
+            .collect();
+        let mut event_ids: Vec<Uuid> = Vec::new();
+        for item in &items {
+            if let Some(event_id) = item.event_id {
+                if !event_ids.contains(&event_id) {
+                    event_ids.push(event_id);
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        for event_id in event_ids {
+            for question in EventQuestion::find_for_event(event_id, conn)? {
+                if !question.per_ticket {
+                    result.push(AttendeeQuestion {
+                        answer: answers.get(&(question.id, None)).cloned(),
+                        question,
+                        ticket_instance_id: None,
+                        ticket_type_id: None,
+                    });
+                    continue;
+                }
+                for item in items.iter().filter(|i| i.event_id == Some(event_id)) {
+                    for ticket in TicketInstance::find_for_order_item(item.id, conn)? {
+                        result.push(AttendeeQuestion {
+                            question: question.clone(),
+                            ticket_instance_id: Some(ticket.id),
+                            ticket_type_id: item.ticket_type_id,
+                            answer: answers.get(&(question.id, Some(ticket.id))).cloned(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Saves the answers for the order. Draft orders must answer every required question, answers
+    /// on paid orders can be changed until the question's cutoff.
+    pub fn save_for_order(
+        order: &Order,
+        answers: &[EventAnswerRequest],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<AttendeeQuestion>, DatabaseError> {
+        if order.status != OrderStatus::Draft && order.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Answers can only be given for draft or paid orders");
+        }
+
+        let mut questions = EventAnswer::questions_for_order(order, conn)?;
+        let mut events: HashMap<Uuid, Event> = HashMap::new();
+        for request in answers {
+            let attendee_question = match questions.iter_mut().find(|q| {
+                q.question.id == request.event_question_id && q.ticket_instance_id == request.ticket_instance_id
+            }) {
+                Some(attendee_question) => attendee_question,
+                None => {
+                    return DatabaseError::validation_error(
+                        "answers",
+                        "Answer does not match a question for this order",
+                    );
+                }
+            };
+            let question = &attendee_question.question;
+            question.validate_answer(&request.answer)?;
+            if order.status == OrderStatus::Paid {
+                if !events.contains_key(&question.event_id) {
+                    events.insert(question.event_id, question.event(conn)?);
+                }
+                if !question.answers_editable(&events[&question.event_id]) {
+                    return DatabaseError::business_process_error("Answers can no longer be changed for this event");
+                }
+            }
+            attendee_question.answer = Some(request.answer.trim().to_string());
+        }
+
+        if order.status == OrderStatus::Draft
+            && questions
+                .iter()
+                .any(|q| q.question.required && !q.question.is_answered(q.answer.as_ref().map(|a| a.as_str())))
+        {
+            return DatabaseError::validation_error("answers", "Required questions must be answered");
+        }
+
+        for request in answers {
+            EventAnswer::upsert(
+                order.id,
+                request.event_question_id,
+                request.ticket_instance_id,
+                request.answer.trim(),
+                conn,
+            )?;
+        }
+        if order.status == OrderStatus::Draft {
+            EventAnswer::clear_released_ticket_answers(order, conn)?;
+        }
+
+        if order.status == OrderStatus::Paid && !answers.is_empty() {
+            DomainEvent::create(
+                DomainEventTypes::OrderAttendeeAnswersUpdated,
+                "Attendee answers updated".to_string(),
+                Tables::Orders,
+                Some(order.id),
+                current_user_id,
+                Some(json!({ "answers": answers })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(questions)
+    }
+
+    fn upsert(
+        order_id: Uuid,
+        event_question_id: Uuid,
+        ticket_instance_id: Option<Uuid>,
+        answer: &str,
+        conn: &PgConnection,
+    ) -> Result<EventAnswer, DatabaseError> {
+        let mut query = event_answers::table
+            .filter(event_answers::order_id.eq(order_id))
+            .filter(event_answers::event_question_id.eq(event_question_id))
+            .into_boxed();
+        query = match ticket_instance_id {
+            Some(ticket_instance_id) => query.filter(event_answers::ticket_instance_id.eq(ticket_instance_id)),
+            None => query.filter(event_answers::ticket_instance_id.is_null()),
+        };
+        let existing: Option<EventAnswer> = query
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading event answer")?;
+
+        match existing {
+            Some(existing) => diesel::update(&existing)
+                .set((event_answers::answer.eq(answer), event_answers::updated_at.eq(dsl::now)))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update event answer"),
+            None => diesel::insert_into(event_answers::table)
+                .values(NewEventAnswer {
+                    event_question_id,
+                    order_id,
+                    ticket_instance_id,
+                    answer: answer.to_string(),
+                })
+                .get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create event answer"),
+        }
+    }
+
+    /// Removes answers for tickets that were released from the cart after they were answered.
+    fn clear_released_ticket_answers(order: &Order, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let ticket_ids = TicketInstance::find_ids_for_order(order.id, conn)?;
+        diesel::delete(
+            event_answers::table
+                .filter(event_answers::order_id.eq(order.id))
+                .filter(event_answers::ticket_instance_id.is_not_null())
+                .filter(dsl::not(event_answers::ticket_instance_id.eq_any(ticket_ids))),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove event answers")
+    }
+
+    fn display_columns() -> (
+        event_questions::event_id,
+        event_answers::event_question_id,
+        event_questions::question,
+        event_answers::order_id,
+        event_answers::ticket_instance_id,
+        event_answers::answer,
+        event_answers::updated_at,
+    ) {
+        (
+            event_questions::event_id,
+            event_answers::event_question_id,
+            event_questions::question,
+            event_answers::order_id,
+            event_answers::ticket_instance_id,
+            event_answers::answer,
+            event_answers::updated_at,
+        )
+    }
+}
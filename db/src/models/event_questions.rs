@@ -0,0 +1,225 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::event_questions;
+use serde_with::rust::double_option;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A question asked of attendees at checkout. Per ticket questions are answered once for every
+/// ticket in the order, other questions once per order.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "event_questions"]
+pub struct EventQuestion {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub question: String,
+    pub question_type: EventQuestionTypes,
+    pub choices: Vec<String>,
+    pub required: bool,
+    pub per_ticket: bool,
+    pub rank: i32,
+    pub answers_editable_until: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "event_questions"]
+pub struct EventQuestionEditableAttributes {
+    pub question: Option<String>,
+    pub question_type: Option<EventQuestionTypes>,
+    pub choices: Option<Vec<String>>,
+    pub required: Option<bool>,
+    pub per_ticket: Option<bool>,
+    pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub answers_editable_until: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_questions"]
+pub struct NewEventQuestion {
+    pub event_id: Uuid,
+    pub question: String,
+    pub question_type: EventQuestionTypes,
+    pub choices: Vec<String>,
+    pub required: bool,
+    pub per_ticket: bool,
+    pub rank: i32,
+    pub answers_editable_until: Option<NaiveDateTime>,
+}
+
+impl NewEventQuestion {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<EventQuestion, DatabaseError> {
+        EventQuestion::validate_attributes(&self.question, self.question_type, &self.choices)?;
+        let result: EventQuestion = diesel::insert_into(event_questions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event question")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventQuestionCreated,
+            "Event question created".to_string(),
+            Tables::EventQuestions,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl EventQuestion {
+    pub fn create(
+        event_id: Uuid,
+        question: String,
+        question_type: EventQuestionTypes,
+        choices: Vec<String>,
+        required: bool,
+        per_ticket: bool,
+        rank: i32,
+        answers_editable_until: Option<NaiveDateTime>,
+    ) -> NewEventQuestion {
+        NewEventQuestion {
+            event_id,
+            question,
+            question_type,
+            choices,
+            required,
+            per_ticket,
+            rank,
+            answers_editable_until,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventQuestion, DatabaseError> {
+        event_questions::table
+            .find(id)
+            .filter(event_questions::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event question")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventQuestion>, DatabaseError> {
+        event_questions::table
+            .filter(event_questions::event_id.eq(event_id))
+            .filter(event_questions::deleted_at.is_null())
+            .order_by(event_questions::rank)
+            .then_order_by(event_questions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event questions")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: EventQuestionEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<EventQuestion, DatabaseError> {
+        EventQuestion::validate_attributes(
+            attributes.question.as_ref().unwrap_or(&self.question),
+            attributes.question_type.unwrap_or(self.question_type),
+            attributes.choices.as_ref().unwrap_or(&self.choices),
+        )?;
+
+        let result: EventQuestion = diesel::update(self)
+            .set((&attributes, event_questions::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event question")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventQuestionUpdated,
+            "Event question updated".to_string(),
+            Tables::EventQuestions,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Hides the question from future checkouts, answers already given are kept.
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let result = diesel::update(event_questions::table.filter(event_questions::id.eq(self.id)))
+            .set((
+                event_questions::deleted_at.eq(dsl::now.nullable()),
+                event_questions::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete event question")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventQuestionDeleted,
+            "Event question deleted".to_string(),
+            Tables::EventQuestions,
+            Some(self.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Answers can be changed until the question's cutoff, defaulting to the start of the event.
+    pub fn answers_editable(&self, event: &Event) -> bool {
+        match self.answers_editable_until.or(event.event_start) {
+            Some(cutoff) => cutoff > Utc::now().naive_utc(),
+            None => true,
+        }
+    }
+
+    /// Whether the answer counts towards a required question, checkboxes must be ticked.
+    pub fn is_answered(&self, answer: Option<&str>) -> bool {
+        match answer.map(|a| a.trim()) {
+            None | Some("") => false,
+            Some(answer) => self.question_type != EventQuestionTypes::Checkbox || answer == "true",
+        }
+    }
+
+    pub fn validate_answer(&self, answer: &str) -> Result<(), DatabaseError> {
+        let answer = answer.trim();
+        if self.required && !self.is_answered(Some(answer)) {
+            return DatabaseError::validation_error("answers", "Required questions must be answered");
+        }
+        if answer.is_empty() {
+            return Ok(());
+        }
+        match self.question_type {
+            EventQuestionTypes::Checkbox if answer != "true" && answer != "false" => {
+                DatabaseError::validation_error("answers", "Checkbox answers must be true or false")
+            }
+            EventQuestionTypes::Choice if !self.choices.iter().any(|c| c == answer) => {
+                DatabaseError::validation_error("answers", "Answer must be one of the question's choices")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_attributes(
+        question: &str,
+        question_type: EventQuestionTypes,
+        choices: &[String],
+    ) -> Result<(), DatabaseError> {
+        if question.trim().is_empty() {
+            return DatabaseError::validation_error("question", "Question cannot be blank");
+        }
+        if question_type == EventQuestionTypes::Choice && choices.is_empty() {
+            return DatabaseError::validation_error("choices", "Choice questions must have at least one choice");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::event_reschedules;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "event_reschedules"]
+pub struct EventReschedule {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub event_end: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub refund_window_ends_at: NaiveDateTime,
+    pub refund_window_closed_at: Option<NaiveDateTime>,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_reschedules"]
+pub struct NewEventReschedule {
+    pub event_id: Uuid,
+    pub previous_event_start: Option<NaiveDateTime>,
+    pub previous_event_end: Option<NaiveDateTime>,
+    pub previous_door_time: Option<NaiveDateTime>,
+    pub event_start: NaiveDateTime,
+    pub event_end: NaiveDateTime,
+    pub door_time: Option<NaiveDateTime>,
+    pub refund_window_ends_at: NaiveDateTime,
+    pub created_by: Uuid,
+}
+
+impl NewEventReschedule {
+    /// Moves the event to the new dates and opens the refund window. Ticket holders are notified
+    /// by the `ProcessEventReschedule` domain action and the window is closed by the
+    /// `CloseEventRescheduleRefundWindow` domain action once `refund_window_ends_at` has passed.
+    pub fn commit(mut self, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        let event = Event::find(self.event_id, conn)?;
+        self.validate_record(&event, conn)?;
+
+        self.previous_event_start = event.event_start;
+        self.previous_event_end = event.event_end;
+        self.previous_door_time = event.door_time;
+        // Keep the doors opening the same amount of time before the show unless told otherwise
+        if self.door_time.is_none() {
+            if let (Some(door_time), Some(event_start)) = (event.door_time, event.event_start) {
+                self.door_time = Some(self.event_start - (event_start - door_time));
+            }
+        }
+
+        let result: EventReschedule = diesel::insert_into(event_reschedules::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not reschedule event")?;
+
+        event.update(
+            Some(self.created_by),
+            EventEditableAttributes {
+                event_start: Some(result.event_start),
+                event_end: Some(result.event_end),
+                door_time: result.door_time,
+                override_status: Some(Some(EventOverrideStatus::Rescheduled)),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduled,
+            format!("Event '{}' rescheduled", &event.name),
+            Tables::Events,
+            Some(event.id),
+            Some(self.created_by),
+            Some(json!({
+                "event_reschedule_id": result.id,
+                "previous_event_start": result.previous_event_start,
+                "event_start": result.event_start,
+                "refund_window_ends_at": result.refund_window_ends_at,
+            })),
+        )
+        .commit(conn)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::ProcessEventReschedule,
+            None,
+            json!({}),
+            Some(Tables::EventReschedules),
+            Some(result.id),
+        )
+        .commit(conn)?;
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::CloseEventRescheduleRefundWindow,
+            None,
+            json!({}),
+            Some(Tables::EventReschedules),
+            Some(result.id),
+        );
+        action.schedule_at(result.refund_window_ends_at);
+        action.commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, event: &Event, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if event.cancelled_at.is_some() || event.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Cancelled events cannot be rescheduled");
+        }
+        let now = Utc::now().naive_utc();
+        if self.event_start <= now {
+            return DatabaseError::validation_error("event_start", "Event can only be rescheduled to a future date");
+        }
+        if self.refund_window_ends_at <= now || self.refund_window_ends_at > self.event_start {
+            return DatabaseError::validation_error(
+                "refund_window_ends_at",
+                "Refund window must end in the future and before the event starts",
+            );
+        }
+        if EventReschedule::find_open_for_event(event.id, conn)?.is_some() {
+            return DatabaseError::business_process_error("Event already has an open refund window");
+        }
+
+        Ok(())
+    }
+}
+
+impl EventReschedule {
+    pub fn create(
+        event_id: Uuid,
+        event_start: NaiveDateTime,
+        event_end: NaiveDateTime,
+        door_time: Option<NaiveDateTime>,
+        refund_window_ends_at: NaiveDateTime,
+        created_by: Uuid,
+    ) -> NewEventReschedule {
+        NewEventReschedule {
+            event_id,
+            previous_event_start: None,
+            previous_event_end: None,
+            previous_door_time: None,
+            event_start,
+            event_end,
+            door_time,
+            refund_window_ends_at,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        event_reschedules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedule")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<EventReschedule>, DatabaseError> {
+        event_reschedules::table
+            .filter(event_reschedules::event_id.eq(event_id))
+            .order_by(event_reschedules::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedules")
+    }
+
+    pub fn find_open_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Option<EventReschedule>, DatabaseError> {
+        event_reschedules::table
+            .filter(event_reschedules::event_id.eq(event_id))
+            .filter(event_reschedules::refund_window_closed_at.is_null())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading event reschedule")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn is_refund_window_open(&self) -> bool {
+        self.refund_window_closed_at.is_none() && self.refund_window_ends_at > Utc::now().naive_utc()
+    }
+
+    /// Refunds the tickets for the event that the user holds and bought themselves. Tickets that
+    /// were redeemed, transferred or bought as part of a package are left alone. Only the order
+    /// records are refunded here, returning the tokens and the money is left to the caller.
+    pub fn refund_tickets_for_user(
+        &self,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<(Order, Refund, i64, Vec<TicketInstance>)>, DatabaseError> {
+        if !self.is_refund_window_open() {
+            return DatabaseError::business_process_error("The refund window for this event has closed");
+        }
+
+        let mut refunds = Vec::new();
+        for (holder, tickets, order_id) in
+            Event::find_all_ticket_holders(self.event_id, conn, TicketHoldersCountType::All)?
+        {
+            let order_id = match order_id {
+                Some(order_id) => order_id,
+                None => continue,
+            };
+            if holder.id != user_id {
+                continue;
+            }
+            let mut order = Order::find(order_id, conn)?;
+            if order.status != OrderStatus::Paid || order.on_behalf_of_user_id.unwrap_or(order.user_id) != user_id {
+                continue;
+            }
+
+            let mut refund_items = Vec::new();
+            let mut refunded_tickets = Vec::new();
+            for ticket in tickets {
+                if ticket.status != TicketInstanceStatus::Purchased {
+                    continue;
+                }
+                let order_item_id = match ticket.order_item_id {
+                    Some(order_item_id) => order_item_id,
+                    None => continue,
+                };
+                if OrderItem::find(order_item_id, conn)?.package_id.is_some() {
+                    continue;
+                }
+                refund_items.push(RefundItemRequest {
+                    order_item_id,
+                    ticket_instance_id: Some(ticket.id),
+                });
+                refunded_tickets.push(ticket);
+            }
+            if refund_items.is_empty() {
+                continue;
+            }
+
+            let (refund, amount) = order.refund(
+                &refund_items,
+                user_id,
+                Some("Event rescheduled".to_string()),
+                false,
+                conn,
+            )?;
+            refunds.push((order, refund, amount, refunded_tickets));
+        }
+
+        if refunds.is_empty() {
+            return DatabaseError::business_process_error("No tickets are eligible for a refund");
+        }
+
+        Ok(refunds)
+    }
+
+    /// Ends the refund window. Tickets that were not refunded stay valid for the new date.
+    pub fn close_refund_window(&self, conn: &PgConnection) -> Result<EventReschedule, DatabaseError> {
+        if self.refund_window_closed_at.is_some() {
+            return Ok(self.clone());
+        }
+
+        let result: EventReschedule = diesel::update(self)
+            .set((
+                event_reschedules::refund_window_closed_at.eq(dsl::now.nullable()),
+                event_reschedules::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not close event reschedule refund window")?;
+
+        let event = self.event(conn)?;
+        if event.override_status == Some(EventOverrideStatus::Rescheduled) {
+            event.update(
+                None,
+                EventEditableAttributes {
+                    override_status: Some(None),
+                    ..Default::default()
+                },
+                conn,
+            )?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::EventRescheduleRefundWindowClosed,
+            format!("Refund window closed for rescheduled event '{}'", &event.name),
+            Tables::Events,
+            Some(event.id),
+            None,
+            Some(json!({
+                "event_reschedule_id": result.id,
+                "ticket_holders": Event::find_all_ticket_holders_count(event.id, conn, TicketHoldersCountType::All)?,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
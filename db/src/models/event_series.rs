@@ -0,0 +1,419 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_series, events};
+use services::RecurrenceRule;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "event_series"]
+pub struct EventSeries {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_rule: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Serialize, Validate)]
+#[table_name = "event_series"]
+pub struct EventSeriesEditableAttributes {
+    #[validate(length(min = "1", message = "Name cannot be blank"))]
+    pub name: Option<String>,
+    pub recurrence_rule: Option<String>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "event_series"]
+pub struct NewEventSeries {
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_rule: String,
+}
+
+impl NewEventSeries {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        self.validate_record(conn)?;
+        let result: EventSeries = diesel::insert_into(event_series::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event series")?;
+
+        // The template is the first occurrence of the series
+        diesel::update(events::table.filter(events::id.eq(self.template_event_id)))
+            .set((events::event_series_id.eq(result.id), events::updated_at.eq(dsl::now)))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add template event to event series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesCreated,
+            "Event series created".to_string(),
+            Tables::EventSeries,
+            Some(result.id),
+            current_user_id,
+            Some(json!({ "template_event_id": self.template_event_id, "recurrence_rule": self.recurrence_rule })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+        if RecurrenceRule::parse(&self.recurrence_rule).is_err() {
+            return DatabaseError::validation_error("recurrence_rule", "Recurrence rule is invalid");
+        }
+
+        let template_event = Event::find(self.template_event_id, conn)?;
+        if template_event.organization_id != self.organization_id {
+            return DatabaseError::business_process_error("Template event does not belong to this organization");
+        }
+        if template_event.event_start.is_none() || template_event.event_end.is_none() {
+            return DatabaseError::validation_error(
+                "template_event_id",
+                "Template event must have a start and end date",
+            );
+        }
+        if template_event.event_series_id.is_some() {
+            return DatabaseError::business_process_error("Template event already belongs to an event series");
+        }
+
+        Ok(())
+    }
+}
+
+impl EventSeries {
+    pub fn create(
+        organization_id: Uuid,
+        template_event_id: Uuid,
+        name: String,
+        recurrence_rule: String,
+    ) -> NewEventSeries {
+        NewEventSeries {
+            organization_id,
+            template_event_id,
+            name,
+            recurrence_rule,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        event_series::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event series")
+    }
+
+    pub fn find_by_template_event_id(
+        template_event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<EventSeries>, DatabaseError> {
+        event_series::table
+            .filter(event_series::template_event_id.eq(template_event_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading event series")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventSeries>, DatabaseError> {
+        event_series::table
+            .filter(event_series::organization_id.eq(organization_id))
+            .order_by(event_series::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event series")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn template_event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.template_event_id, conn)
+    }
+
+    pub fn recurrence(&self) -> Result<RecurrenceRule, DatabaseError> {
+        RecurrenceRule::parse(&self.recurrence_rule)
+    }
+
+    /// All occurrences in the series, including the template, ordered by start date.
+    pub fn occurrences(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .order_by(events::event_start)
+            .then_order_by(events::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event series occurrences")
+    }
+
+    pub fn update(
+        &self,
+        attributes: EventSeriesEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<EventSeries, DatabaseError> {
+        attributes.validate()?;
+        if let Some(ref recurrence_rule) = attributes.recurrence_rule {
+            if RecurrenceRule::parse(recurrence_rule).is_err() {
+                return DatabaseError::validation_error("recurrence_rule", "Recurrence rule is invalid");
+            }
+        }
+
+        let result: EventSeries = diesel::update(self)
+            .set((&attributes, event_series::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesUpdated,
+            "Event series updated".to_string(),
+            Tables::EventSeries,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Creates the occurrences described by the recurrence rule up to `horizon`. Occurrences that
+    /// already exist or would start in the past are skipped, so this can be called repeatedly as
+    /// the horizon moves forward or the rule changes. Each occurrence is a clone of the template
+    /// with its own ticket inventory.
+    pub fn generate_occurrences(
+        &self,
+        horizon: NaiveDateTime,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let template_event = self.template_event(conn)?;
+        let (template_start, template_end) = match (template_event.event_start, template_event.event_end) {
+            (Some(event_start), Some(event_end)) => (event_start, event_end),
+            _ => {
+                return DatabaseError::business_process_error("Template event must have a start and end date");
+            }
+        };
+        let existing_starts: Vec<NaiveDateTime> = self
+            .occurrences(conn)?
+            .into_iter()
+            .filter_map(|occurrence| occurrence.event_start)
+            .collect();
+        let now = Utc::now().naive_utc();
+
+        let mut generated = Vec::new();
+        for event_start in self.recurrence()?.occurrences(template_start, horizon) {
+            if event_start < now || existing_starts.contains(&event_start) {
+                continue;
+            }
+
+            let occurrence = template_event.clone_record(
+                &CloneFields {
+                    name: template_event.name.clone(),
+                    event_start,
+                    event_end: event_start + template_end.signed_duration_since(template_start),
+                },
+                current_user_id,
+                conn,
+            )?;
+            let occurrence: Event = diesel::update(&occurrence)
+                .set((
+                    events::event_series_id.eq(self.id),
+                    events::client_fee_in_cents.eq(template_event.client_fee_in_cents),
+                    events::company_fee_in_cents.eq(template_event.company_fee_in_cents),
+                    events::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not add occurrence to event series")?;
+            self.sync_artists(&template_event, &occurrence, current_user_id, conn)?;
+            generated.push(occurrence);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesOccurrencesGenerated,
+            "Event series occurrences generated".to_string(),
+            Tables::EventSeries,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "event_ids": generated.iter().map(|e| e.id).collect::<Vec<Uuid>>() })),
+        )
+        .commit(conn)?;
+
+        Ok(generated)
+    }
+
+    /// Pushes template changes to upcoming occurrences. Past, cancelled and deleted occurrences
+    /// are never changed. Descriptive details propagate to every upcoming occurrence. Venue, door
+    /// time, fees and artists only propagate to occurrences without sales so fans who already
+    /// bought tickets keep what they paid for. Ticket types are handled by `sync_ticket_types`.
+    pub fn sync_occurrences(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let template_event = self.template_event(conn)?;
+
+        let mut synced = Vec::new();
+        for occurrence in self.upcoming_occurrences(&template_event, conn)? {
+            let mut attributes = EventEditableAttributes {
+                name: Some(template_event.name.clone()),
+                promo_image_url: Some(template_event.promo_image_url.clone()),
+                cover_image_url: Some(template_event.cover_image_url.clone()),
+                additional_info: Some(template_event.additional_info.clone()),
+                age_limit: template_event.age_limit.clone(),
+                top_line_info: Some(template_event.top_line_info.clone()),
+                video_url: Some(template_event.video_url.clone()),
+                is_external: Some(template_event.is_external),
+                external_url: Some(template_event.external_url.clone()),
+                event_type: Some(template_event.event_type.clone()),
+                resale_enabled: Some(template_event.resale_enabled),
+                resale_max_markup_percentage: Some(template_event.resale_max_markup_percentage),
+                ..Default::default()
+            };
+
+            let has_sales = occurrence.associated_with_active_orders(conn)?;
+            if !has_sales {
+                attributes.venue_id = template_event.venue_id;
+                attributes.door_time = match (template_event.door_time, template_event.event_start) {
+                    (Some(door_time), Some(template_start)) => occurrence
+                        .event_start
+                        .map(|event_start| event_start + door_time.signed_duration_since(template_start)),
+                    _ => None,
+                };
+            }
+
+            let occurrence = occurrence.update(current_user_id, attributes, conn)?;
+            if has_sales {
+                synced.push(occurrence);
+                continue;
+            }
+
+            let occurrence: Event = diesel::update(&occurrence)
+                .set((
+                    events::client_fee_in_cents.eq(template_event.client_fee_in_cents),
+                    events::company_fee_in_cents.eq(template_event.company_fee_in_cents),
+                    events::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update event series occurrence fees")?;
+            self.sync_artists(&template_event, &occurrence, current_user_id, conn)?;
+            synced.push(occurrence);
+        }
+
+        Ok(synced)
+    }
+
+    /// Adds ticket types that exist on the template but not on upcoming occurrences without sales.
+    /// Ticket types are matched by name. Existing ticket types, their pricing and inventory are
+    /// never changed as each occurrence manages its own. Returns the ticket types added to each
+    /// occurrence so their blockchain assets can be created.
+    pub fn sync_ticket_types(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<(Event, Vec<TicketType>)>, DatabaseError> {
+        let template_event = self.template_event(conn)?;
+        let template_ticket_types: Vec<TicketType> = template_event
+            .ticket_types(false, None, conn)?
+            .into_iter()
+            .filter(|ticket_type| {
+                ticket_type.status != TicketTypeStatus::Cancelled
+                    && ticket_type.deleted_at.is_none()
+                    && ticket_type.cancelled_at.is_none()
+                    && ticket_type.parent_id.is_none()
+            })
+            .collect();
+        let org_wallet = Wallet::find_default_for_organization(self.organization_id, conn)?;
+
+        let mut added = Vec::new();
+        for occurrence in self.upcoming_occurrences(&template_event, conn)? {
+            if occurrence.associated_with_active_orders(conn)? {
+                continue;
+            }
+
+            let existing_names: Vec<String> = occurrence
+                .ticket_types(false, None, conn)?
+                .into_iter()
+                .filter(|ticket_type| ticket_type.deleted_at.is_none())
+                .map(|ticket_type| ticket_type.name)
+                .collect();
+            let mut added_ticket_types = Vec::new();
+            for ticket_type in template_ticket_types.iter() {
+                if existing_names.contains(&ticket_type.name) {
+                    continue;
+                }
+                added_ticket_types.push(occurrence.clone_ticket_type(
+                    &org_wallet,
+                    None,
+                    ticket_type,
+                    current_user_id,
+                    conn,
+                )?);
+            }
+
+            if !added_ticket_types.is_empty() {
+                added.push((occurrence, added_ticket_types));
+            }
+        }
+
+        Ok(added)
+    }
+
+    fn upcoming_occurrences(&self, template_event: &Event, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        Ok(self
+            .occurrences(conn)?
+            .into_iter()
+            .filter(|occurrence| {
+                occurrence.id != template_event.id
+                    && occurrence.cancelled_at.is_none()
+                    && occurrence
+                        .event_start
+                        .map(|event_start| event_start >= now)
+                        .unwrap_or(false)
+            })
+            .collect())
+    }
+
+    fn sync_artists(
+        &self,
+        template_event: &Event,
+        occurrence: &Event,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let offset = match (template_event.event_start, occurrence.event_start) {
+            (Some(template_start), Some(event_start)) => event_start.signed_duration_since(template_start),
+            _ => Duration::zero(),
+        };
+
+        EventArtist::clear_all_from_event(occurrence.id, conn)?;
+        for event_artist in EventArtist::find_all_from_event(template_event.id, conn)? {
+            EventArtist::create(
+                occurrence.id,
+                event_artist.artist.id,
+                event_artist.rank,
+                event_artist.set_time.map(|set_time| set_time + offset),
+                event_artist.importance,
+                event_artist.stage_id,
+            )
+            .commit(current_user_id, conn)?;
+        }
+
+        Ok(())
+    }
+}
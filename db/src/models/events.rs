@@ -73,6 +73,9 @@ pub struct Event {
     pub facebook_event_id: Option<String>,
     pub settled_at: Option<NaiveDateTime>,
     pub cloned_from_event_id: Option<Uuid>,
+    pub resale_enabled: bool,
+    pub resale_max_markup_percentage: Option<i32>,
+    pub event_series_id: Option<Uuid>,
 }
 
 impl PartialOrd for Event {
@@ -127,6 +130,9 @@ pub struct NewEvent {
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_event_id: Option<String>,
     pub cloned_from_event_id: Option<Uuid>,
+    #[serde(default)]
+    pub resale_enabled: bool,
+    pub resale_max_markup_percentage: Option<i32>,
 }
 
 pub enum TicketHoldersCountType {
@@ -251,6 +257,9 @@ pub struct EventEditableAttributes {
     #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
     pub facebook_event_id: Option<Option<String>>,
     pub cloned_from_event_id: Option<Option<Uuid>>,
+    pub resale_enabled: Option<bool>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub resale_max_markup_percentage: Option<Option<i32>>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -313,6 +322,8 @@ impl Event {
         event.video_url = self.video_url.clone();
         event.is_external = self.is_external;
         event.external_url = self.external_url.clone();
+        event.resale_enabled = self.resale_enabled;
+        event.resale_max_markup_percentage = self.resale_max_markup_percentage;
         let event = event.commit(current_user_id, conn)?;
 
         for event_artist in EventArtist::find_all_from_event(self.id, conn)? {
@@ -354,7 +365,7 @@ impl Event {
         Ok(event)
     }
 
-    fn clone_ticket_type(
+    pub(crate) fn clone_ticket_type(
         &self,
         org_wallet: &Wallet,
         parent_ticket_type: Option<&TicketType>,
@@ -705,6 +716,19 @@ impl Event {
             }
         }
 
+        if let Some(Some(resale_max_markup_percentage)) = attributes.resale_max_markup_percentage {
+            if resale_max_markup_percentage < 0 {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "event.resale_max_markup_percentage",
+                    Err(create_validation_error(
+                        "resale_max_markup_percentage_negative",
+                        "Resale max markup percentage cannot be negative",
+                    )),
+                );
+            }
+        }
+
         Ok(validation_errors?)
     }
 
@@ -759,6 +783,12 @@ impl Event {
             result.regenerate_drip_actions(conn)?;
         }
 
+        if result.event_series_id.is_some() {
+            if let Some(event_series) = EventSeries::find_by_template_event_id(result.id, conn)? {
+                event_series.sync_occurrences(current_user_id, conn)?;
+            }
+        }
+
         DomainEvent::create(
             DomainEventTypes::EventUpdated,
             format!("Event '{}' was updated", &self.name),
@@ -1748,6 +1778,11 @@ impl Event {
                 , sql::<Timestamp>("ticket_instances.updated_at AS updated_at")
                 , sql::<Nullable<Text>>("CASE WHEN ticket_instances.redeemed_by_user_id IS NOT NULL THEN (SELECT CONCAT(u2.first_name, ' ', u2.last_name) FROM users u2 WHERE u2.id = ticket_instances.redeemed_by_user_id) ELSE NULL END  AS redeemed_by")
                 , sql::<Nullable<Timestamp>>("ticket_instances.redeemed_at AS redeemed_at")
+                , sql::<Nullable<Text>>("(SELECT ss.name FROM seats s JOIN seating_sections ss ON ss.id = s.seating_section_id WHERE s.id = ticket_instances.seat_id) AS section_name")
+                , sql::<Nullable<Text>>("(SELECT s.row_name FROM seats s WHERE s.id = ticket_instances.seat_id) AS row_name")
+                , sql::<Nullable<Text>>("(SELECT s.seat_number FROM seats s WHERE s.id = ticket_instances.seat_id) AS seat_number")
+                , sql::<Nullable<Timestamp>>("(SELECT es.entry_start FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id) AS entry_start")
+                , sql::<Nullable<Timestamp>>("(SELECT es.entry_end FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id) AS entry_end")
             ))
             .paginate(paging.page as i64)
             .per_page(paging.limit as i64)
@@ -1807,6 +1842,12 @@ impl Event {
             }
         }
 
+        let attendee_answers = EventAnswer::find_for_event_orders(
+            self.id,
+            &tickets.iter().map(|t| t.order_id).collect::<Vec<Uuid>>(),
+            conn,
+        )?;
+
         for t in &tickets {
             let mut providers: Vec<String> = Vec::new();
             for order_payment_provider in &order_payment_providers {
@@ -1821,6 +1862,11 @@ impl Event {
                 ticket: t.clone(),
                 providers,
                 pending_transfer,
+                attendee_answers: attendee_answers
+                    .iter()
+                    .filter(|a| a.order_id == t.order_id && a.ticket_instance_id.map(|id| id == t.id).unwrap_or(true))
+                    .cloned()
+                    .collect(),
             })
         }
 
@@ -2343,4 +2389,5 @@ pub struct GuestListItem {
     pub ticket: RedeemableTicket,
     pub providers: Vec<String>,
     pub pending_transfer: Option<PendingTransfer>,
+    pub attendee_answers: Vec<DisplayEventAnswer>,
 }
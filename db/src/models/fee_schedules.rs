@@ -16,6 +16,8 @@ pub struct FeeSchedule {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub organization_id: Uuid,
+    pub resale_company_fee_percent: f32,
+    pub resale_client_fee_percent: f32,
 }
 
 impl FeeSchedule {
@@ -24,6 +26,8 @@ impl FeeSchedule {
             organization_id,
             name,
             ranges,
+            resale_company_fee_percent: 0f32,
+            resale_client_fee_percent: 0f32,
         }
     }
 
@@ -74,6 +78,13 @@ impl FeeSchedule {
             None => DatabaseError::no_results("Could not find a valid fee for this price"),
         }
     }
+    /// Per ticket company and client fees charged to the buyer of a resale listing.
+    pub fn resale_fees(&self, price_in_cents: i64) -> (i64, i64) {
+        (
+            (price_in_cents as f32 * (self.resale_company_fee_percent / 100f32)).round() as i64,
+            (price_in_cents as f32 * (self.resale_client_fee_percent / 100f32)).round() as i64,
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -81,6 +92,10 @@ pub struct NewFeeSchedule {
     pub organization_id: Uuid,
     pub name: String,
     pub ranges: Vec<NewFeeScheduleRange>,
+    #[serde(default)]
+    pub resale_company_fee_percent: f32,
+    #[serde(default)]
+    pub resale_client_fee_percent: f32,
 }
 
 impl NewFeeSchedule {
@@ -102,6 +117,8 @@ impl NewFeeSchedule {
             .values((
                 fee_schedules::name.eq(&self.name),
                 fee_schedules::version.eq(next_version),
+                fee_schedules::resale_company_fee_percent.eq(self.resale_company_fee_percent),
+                fee_schedules::resale_client_fee_percent.eq(self.resale_client_fee_percent),
             ))
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not create fee schedule")?;
@@ -12,5 +12,13 @@ pub fn schedule_domain_actions(conn: &PgConnection) -> Result<(), DatabaseError>
         Order::create_next_retarget_abandoned_cart_domain_action(conn)?;
     }
 
+    if DomainAction::upcoming_domain_action(None, None, DomainActionTypes::EvaluatePricingRules, conn)?.is_none() {
+        PricingRule::create_next_evaluate_pricing_rules_domain_action(conn)?;
+    }
+
+    if DomainAction::upcoming_domain_action(None, None, DomainActionTypes::ReleaseExpiredHolds, conn)?.is_none() {
+        Hold::create_next_release_expired_holds_domain_action(conn)?;
+    }
+
     Ok(())
 }
@@ -1,4 +1,5 @@
 use chrono::prelude::*;
+use chrono::Duration;
 use diesel;
 use diesel::dsl;
 use diesel::prelude::*;
@@ -28,6 +29,8 @@ pub struct Hold {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub expiry_behavior: HoldExpiryBehaviors,
+    pub released_at: Option<NaiveDateTime>,
 }
 
 #[derive(AsChangeset, Default, Validate)]
@@ -42,6 +45,7 @@ pub struct UpdateHoldAttributes {
     pub phone: Option<Option<String>>,
     pub end_at: Option<Option<NaiveDateTime>>,
     pub max_per_user: Option<Option<i64>>,
+    pub expiry_behavior: Option<HoldExpiryBehaviors>,
 }
 
 impl Hold {
@@ -69,6 +73,7 @@ impl Hold {
             max_per_user: max_per_user.map(|m| m as i64),
             hold_type,
             ticket_type_id,
+            expiry_behavior: HoldExpiryBehaviors::Keep,
         }
     }
 
@@ -101,6 +106,7 @@ impl Hold {
             max_per_user: max_per_user.map(|m| m as i64),
             hold_type: HoldTypes::Comp,
             ticket_type_id: hold.ticket_type_id,
+            expiry_behavior: hold.expiry_behavior,
         };
 
         let new_hold = new_hold.commit(current_user_id, conn)?;
@@ -121,12 +127,23 @@ impl Hold {
 
         self.validate_record(&update_attrs, conn)?;
 
+        // A new end date or expiry behavior makes a released hold eligible for release again
+        let released_at = if update_attrs.end_at.is_some() || update_attrs.expiry_behavior.is_some() {
+            None
+        } else {
+            self.released_at
+        };
+
         diesel::update(
             holds::table
                 .filter(holds::id.eq(self.id))
                 .filter(holds::updated_at.eq(self.updated_at)),
         )
-        .set((update_attrs, holds::updated_at.eq(dsl::now)))
+        .set((
+            update_attrs,
+            holds::released_at.eq(released_at),
+            holds::updated_at.eq(dsl::now),
+        ))
         .get_result(conn)
         .to_db_error(ErrorCode::UpdateError, "Could not update hold")
     }
@@ -270,6 +287,7 @@ impl Hold {
             max_per_user: max_per_user.map(|m| m as i64),
             hold_type,
             ticket_type_id: self.ticket_type_id,
+            expiry_behavior: self.expiry_behavior,
         };
 
         let new_hold = new_hold.commit(current_user_id, conn)?;
@@ -403,6 +421,112 @@ impl Hold {
         Ok(())
     }
 
+    /// Returns the unclaimed tickets of holds set to release at `end_at` once it has passed.
+    /// Comps are released before their parent hold so that tickets they return to the parent are
+    /// released along with it. Returns each released hold with the number of tickets released.
+    pub fn release_expired_holds(conn: &PgConnection) -> Result<Vec<(Hold, u32)>, DatabaseError> {
+        let mut expired_holds: Vec<Hold> = holds::table
+            .filter(holds::expiry_behavior.eq(HoldExpiryBehaviors::Release))
+            .filter(holds::end_at.le(dsl::now.nullable()))
+            .filter(holds::released_at.is_null())
+            .filter(holds::deleted_at.is_null())
+            .order_by(holds::end_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load expired holds")?;
+        expired_holds.sort_by_key(|hold| hold.parent_hold_id.is_none());
+
+        let mut released_holds = Vec::new();
+        for hold in expired_holds {
+            let released_quantity = hold.release_expired(conn)?;
+            released_holds.push((hold, released_quantity));
+        }
+        Ok(released_holds)
+    }
+
+    /// Releases the tickets in this hold that have not been claimed, returning how many were
+    /// released. Comps return their tickets to the parent hold unless it has been released or
+    /// deleted already, in which case the tickets go back to general sale.
+    pub fn release_expired(&self, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let (quantity, available) = self.quantity(conn)?;
+        if available > 0 {
+            let parent_hold = match self.parent_hold_id {
+                Some(parent_hold_id) => Some(Hold::find(parent_hold_id, conn)?),
+                None => None,
+            };
+            match parent_hold {
+                Some(ref parent_hold) if parent_hold.released_at.is_some() || parent_hold.deleted_at.is_some() => {
+                    TicketInstance::release_from_hold(None, self.id, self.ticket_type_id, available, conn)?;
+                    DomainEvent::create(
+                        DomainEventTypes::HoldQuantityChanged,
+                        format!(
+                            "Hold quantity decreased from {} to {}, released to general sale",
+                            quantity,
+                            quantity - available
+                        ),
+                        Tables::Holds,
+                        Some(self.id),
+                        None,
+                        Some(json!({"old_quantity": quantity, "new_quantity": quantity - available})),
+                    )
+                    .commit(conn)?;
+                }
+                _ => self.set_quantity(None, quantity - available, conn)?,
+            }
+        }
+
+        diesel::update(holds::table.filter(holds::id.eq(self.id)))
+            .set((
+                holds::released_at.eq(dsl::now.nullable()),
+                holds::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark hold as released")?;
+
+        Ok(available)
+    }
+
+    /// The user who created the hold, taken from its `HoldCreated` domain event.
+    pub fn creator(&self, conn: &PgConnection) -> Result<Option<User>, DatabaseError> {
+        let user_id = DomainEvent::find(Tables::Holds, Some(self.id), Some(DomainEventTypes::HoldCreated), conn)?
+            .into_iter()
+            .filter_map(|domain_event| domain_event.user_id)
+            .next();
+
+        match user_id {
+            Some(user_id) => Ok(Some(User::find(user_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn create_next_release_expired_holds_domain_action(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let now = Utc::now().naive_utc();
+        if let Some(upcoming_domain_action) =
+            DomainAction::upcoming_domain_action(None, None, DomainActionTypes::ReleaseExpiredHolds, conn)?
+        {
+            if upcoming_domain_action.scheduled_at > now {
+                return DatabaseError::business_process_error("Release expired holds domain action is already pending");
+            }
+        }
+
+        // Runs every 15 minutes so holds return to general sale soon after they end
+        let beginning_of_current_quarter_hour =
+            NaiveDate::from_ymd(now.year(), now.month(), now.day()).and_hms(now.hour(), now.minute() / 15 * 15, 0);
+        let next_action_date = beginning_of_current_quarter_hour + Duration::minutes(15);
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ReleaseExpiredHolds,
+            None,
+            json!({}),
+            None,
+            None,
+        );
+        action.schedule_at(next_action_date);
+        action.commit(conn)?;
+
+        Ok(())
+    }
+
     pub fn quantity(&self, conn: &PgConnection) -> Result<(u32, u32), DatabaseError> {
         TicketInstance::count_for_hold(self.id, self.ticket_type_id, false, conn)
     }
@@ -472,6 +596,7 @@ impl Hold {
             email: self.email,
             phone: self.phone,
             hold_type: self.hold_type,
+            expiry_behavior: self.expiry_behavior,
             available,
             quantity,
         })
@@ -497,6 +622,7 @@ pub struct NewHold {
     pub max_per_user: Option<i64>,
     pub hold_type: HoldTypes,
     pub ticket_type_id: Uuid,
+    pub expiry_behavior: HoldExpiryBehaviors,
 }
 
 impl NewHold {
@@ -557,6 +683,7 @@ pub struct DisplayHold {
     pub max_per_user: Option<i64>,
     pub email: Option<String>,
     pub phone: Option<String>,
+    pub expiry_behavior: HoldExpiryBehaviors,
     pub available: u32,
     pub quantity: u32,
 }
@@ -2,15 +2,23 @@ pub use self::activities::*;
 pub use self::artists::*;
 pub use self::assets::*;
 pub use self::broadcasts::*;
+pub use self::capacity_pools::*;
+pub use self::code_batches::*;
 pub use self::codes::*;
 pub use self::communication::*;
+pub use self::comp_imports::*;
 pub use self::domain_actions::*;
 pub use self::domain_event_publishers::*;
 pub use self::domain_events::*;
+pub use self::entry_slots::*;
 pub use self::enums::*;
+pub use self::event_answers::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
+pub use self::event_questions::*;
 pub use self::event_report_subscribers::*;
+pub use self::event_reschedules::*;
+pub use self::event_series::*;
 pub use self::event_users::*;
 pub use self::events::*;
 pub use self::external_logins::FACEBOOK_SITE;
@@ -30,10 +38,21 @@ pub use self::organization_interactions::*;
 pub use self::organization_invites::*;
 pub use self::organization_users::*;
 pub use self::organizations::*;
+pub use self::package_ticket_types::*;
+pub use self::packages::*;
 pub use self::paging::*;
 pub use self::payment_methods::*;
+pub use self::payment_plan_installments::*;
+pub use self::payment_plans::*;
+pub use self::payment_provider_events::*;
 pub use self::payments::*;
 pub use self::platforms::*;
+pub use self::presale_lotteries::*;
+pub use self::presale_lottery_entries::*;
+pub use self::pricing_rules::*;
+pub use self::product_instances::*;
+pub use self::product_variants::*;
+pub use self::products::*;
 pub use self::push_notification_tokens::*;
 pub use self::redeemable_ticket::*;
 pub use self::refund_items::*;
@@ -41,13 +60,19 @@ pub use self::refunded_tickets::*;
 pub use self::refunds::*;
 pub use self::regions::*;
 pub use self::reports::*;
+pub use self::resale_listings::*;
 pub use self::scopes::*;
+pub use self::seating_sections::*;
+pub use self::seats::*;
 pub use self::settlement_adjustments::*;
 pub use self::settlement_entries::*;
 pub use self::settlements::*;
 pub use self::slugs::*;
 pub use self::stages::*;
+pub use self::store_credit_transactions::*;
+pub use self::tax_rules::*;
 pub use self::temporary_users::*;
+pub use self::ticket_exchanges::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
@@ -57,6 +82,7 @@ pub use self::transfer_tickets::*;
 pub use self::transfers::*;
 pub use self::users::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
 
 use serde::{Deserialize, Deserializer};
@@ -69,15 +95,23 @@ pub mod analytics;
 mod artists;
 mod assets;
 mod broadcasts;
+mod capacity_pools;
+mod code_batches;
 mod codes;
 mod communication;
+mod comp_imports;
 mod domain_actions;
 mod domain_event_publishers;
 mod domain_events;
+mod entry_slots;
 pub mod enums;
+mod event_answers;
 mod event_artists;
 mod event_interest;
+mod event_questions;
 mod event_report_subscribers;
+mod event_reschedules;
+mod event_series;
 mod event_users;
 mod events;
 mod external_logins;
@@ -96,10 +130,21 @@ mod organization_interactions;
 mod organization_invites;
 mod organization_users;
 mod organizations;
+mod package_ticket_types;
+mod packages;
 mod paging;
 mod payment_methods;
+mod payment_plan_installments;
+mod payment_plans;
+mod payment_provider_events;
 mod payments;
 mod platforms;
+mod presale_lotteries;
+mod presale_lottery_entries;
+mod pricing_rules;
+mod product_instances;
+mod product_variants;
+mod products;
 mod push_notification_tokens;
 mod redeemable_ticket;
 mod refund_items;
@@ -107,13 +152,19 @@ mod refunded_tickets;
 mod refunds;
 mod regions;
 mod reports;
+mod resale_listings;
 pub mod scopes;
+mod seating_sections;
+mod seats;
 mod settlement_adjustments;
 mod settlement_entries;
 mod settlements;
 mod slugs;
 mod stages;
+mod store_credit_transactions;
+mod tax_rules;
 mod temporary_users;
+mod ticket_exchanges;
 mod ticket_instances;
 mod ticket_pricing;
 mod ticket_type_codes;
@@ -122,6 +173,7 @@ mod transfer_tickets;
 mod transfers;
 mod users;
 mod venues;
+mod waitlist_entries;
 mod wallets;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
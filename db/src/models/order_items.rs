@@ -5,7 +5,7 @@ use diesel::prelude::*;
 use diesel::sql_types::{Array, BigInt, Nullable, Text, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
-use schema::{codes, events, order_items, ticket_instances, ticket_types};
+use schema::{codes, events, order_items, refund_items, tax_rules, ticket_instances, ticket_types};
 use std::borrow::Cow;
 use std::cmp;
 use std::collections::HashMap;
@@ -39,6 +39,10 @@ pub struct OrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub product_variant_id: Option<Uuid>,
+    pub package_id: Option<Uuid>,
+    pub tax_rule_id: Option<Uuid>,
+    pub tax_in_cents: i64,
 }
 
 impl OrderItem {
@@ -67,6 +71,15 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item discount")
     }
 
+    pub fn find_tax_items(&self, conn: &PgConnection) -> Result<Vec<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .order_by(order_items::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item taxes")
+    }
+
     pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
         use models::OrderItemTypes::*;
         let res = match self.item_type {
@@ -80,11 +93,36 @@ impl OrderItem {
             }
             Discount => "Discount".to_string(),
             CreditCardFees => "Credit Card Fees".to_string(),
-            _ => {
+            ResaleTickets => {
                 let ticket_type = self.ticket_type(conn)?;
                 match ticket_type {
-                    Some(t) => format!("{} - {}", t.event(conn)?.name, t.name),
-                    None => "Other".to_string(),
+                    Some(t) => format!("{} - {} (Resale)", t.event(conn)?.name, t.name),
+                    None => "Resale".to_string(),
+                }
+            }
+            Products => match self.product_variant(conn)? {
+                Some(v) => format!("{} - {}", v.product(conn)?.name, v.name),
+                None => "Product".to_string(),
+            },
+            Tax => match self.tax_rule_id {
+                Some(tax_rule_id) => tax_rules::table
+                    .find(tax_rule_id)
+                    .select(tax_rules::name)
+                    .first::<String>(conn)
+                    .to_db_error(ErrorCode::QueryError, "Could not load tax rule for order item")?,
+                None => "Tax".to_string(),
+            },
+            _ => {
+                let ticket_type = self.ticket_type(conn)?;
+                match (ticket_type, self.package_id) {
+                    (Some(t), Some(package_id)) => format!(
+                        "{} - {} ({})",
+                        t.event(conn)?.name,
+                        t.name,
+                        Package::find(package_id, conn)?.name
+                    ),
+                    (Some(t), None) => format!("{} - {}", t.event(conn)?.name, t.name),
+                    (None, _) => "Other".to_string(),
                 }
             }
         };
@@ -104,8 +142,12 @@ impl OrderItem {
 
         self.refunded_quantity += 1;
 
-        // Check if any discounts exist for this order_item
-        let discount = self.find_discount_item(conn)?;
+        // Check if any discounts exist for this order_item, the remainder under a discount is
+        // returned when the discount is adjusted after the refund
+        let discount = match self.item_type {
+            OrderItemTypes::Discount => None,
+            _ => self.find_discount_item(conn)?,
+        };
         let discount_amount;
         if let Some(mut oi) = discount {
             discount_amount = oi.refund_one_unit(true, conn)?
@@ -114,14 +156,21 @@ impl OrderItem {
         }
 
         let mut refund_amount_in_cents = self.unit_price_in_cents + discount_amount;
-        // Refund fees if ticket is being refunded
-        if refund_fees && self.item_type == OrderItemTypes::Tickets {
+        // Refund fees if ticket or product is being refunded
+        if refund_fees && (self.item_type == OrderItemTypes::Tickets || self.item_type == OrderItemTypes::Products) {
             let fee_item = self.find_fee_item(conn)?;
             if let Some(mut fee_item) = fee_item {
-                refund_amount_in_cents += fee_item.refund_one_unit(true, conn)?;
+                // Tickets whose fees were waived have no fee to refund
+                if fee_item.refunded_quantity < fee_item.quantity {
+                    refund_amount_in_cents += fee_item.refund_one_unit(true, conn)?;
+                }
             }
         }
 
+        for mut tax_item in self.find_tax_items(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(true, conn)?;
+        }
+
         diesel::update(order_items::table.filter(order_items::id.eq(self.id)))
             .set((
                 order_items::updated_at.eq(dsl::now),
@@ -156,70 +205,65 @@ impl OrderItem {
             || self.item_type == OrderItemTypes::EventFees
             || self.item_type == OrderItemTypes::Discount
             || self.item_type == OrderItemTypes::CreditCardFees
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
 
         let discount_item = self.find_discount_item(conn)?;
+        let (hold_discount, discount, _) = self.calculate_discount(conn)?;
 
-        if let Some(code_id) = self.code_id {
-            let code = Code::find(code_id, conn)?;
-            let mut discount = 0;
-            if let Some(discount_percent) = code.discount_as_percentage {
-                discount = cmp::min(
-                    ((self.unit_price_in_cents as f32) * (discount_percent as f32) / 100.0f32) as i64,
-                    self.unit_price_in_cents,
-                );
-            } else if let Some(discount_in_cents) = code.discount_in_cents {
-                discount = cmp::min(discount_in_cents, self.unit_price_in_cents);
-            }
-            if discount > 0 {
-                if let Some(mut di) = discount_item {
-                    di.quantity = self.quantity;
-                    di.unit_price_in_cents = -discount;
-                    di.update(conn)?;
-                } else {
-                    NewDiscountOrderItem {
-                        order_id: self.order_id,
-                        item_type: OrderItemTypes::Discount,
-                        event_id: self.event_id,
-                        quantity: self.quantity,
-                        unit_price_in_cents: -discount,
-                        company_fee_in_cents: 0,
-                        client_fee_in_cents: 0,
-                        parent_id: Some(self.id),
-                    }
-                    .commit(conn)?;
-                }
+        if discount > 0 || hold_discount.is_some() {
+            // The discount is spread evenly over the item's tickets, any cents left over are
+            // taken off some of them by a remainder item under the discount
+            let (unit_discount, remainder) = if self.quantity > 0 {
+                (discount / self.quantity, discount % self.quantity)
             } else {
-                if let Some(di) = discount_item {
-                    order.destroy_item(di.id, conn)?;
-                }
-            }
-        } else if let Some(hold_id) = self.hold_id {
-            let h = Hold::find(hold_id, conn)?;
-
-            let hold_type = h.hold_type;
-            let discount = match hold_type {
-                HoldTypes::Discount => cmp::min(h.discount_in_cents.unwrap_or(0), self.unit_price_in_cents),
-                HoldTypes::Comp => self.unit_price_in_cents,
+                (0, 0)
             };
-            if let Some(mut di) = discount_item {
+            let discount_item = if let Some(mut di) = discount_item {
                 di.quantity = self.quantity;
-                di.unit_price_in_cents = -discount;
+                di.unit_price_in_cents = -unit_discount;
                 di.update(conn)?;
+                di
             } else {
                 NewDiscountOrderItem {
                     order_id: self.order_id,
                     item_type: OrderItemTypes::Discount,
                     event_id: self.event_id,
                     quantity: self.quantity,
-                    unit_price_in_cents: -discount,
+                    unit_price_in_cents: -unit_discount,
                     company_fee_in_cents: 0,
                     client_fee_in_cents: 0,
                     parent_id: Some(self.id),
                 }
-                .commit(conn)?;
+                .commit(conn)?
+            };
+
+            match discount_item.find_discount_item(conn)? {
+                Some(mut remainder_item) => {
+                    if remainder > 0 {
+                        remainder_item.quantity = remainder;
+                        remainder_item.update(conn)?;
+                    } else {
+                        order.destroy_item(remainder_item.id, conn)?;
+                    }
+                }
+                None => {
+                    if remainder > 0 {
+                        NewDiscountOrderItem {
+                            order_id: self.order_id,
+                            item_type: OrderItemTypes::Discount,
+                            event_id: self.event_id,
+                            quantity: remainder,
+                            unit_price_in_cents: -1,
+                            company_fee_in_cents: 0,
+                            client_fee_in_cents: 0,
+                            parent_id: Some(discount_item.id),
+                        }
+                        .commit(conn)?;
+                    }
+                }
             }
         } else if let Some(di) = discount_item {
             order.destroy_item(di.id, conn)?;
@@ -228,7 +272,131 @@ impl OrderItem {
         Ok(())
     }
 
+    /// The hold discount per ticket, if the item is from a hold, along with the overall discount
+    /// for the item's tickets that have not been refunded and the number of those tickets it
+    /// applies to.
+    fn calculate_discount(&self, conn: &PgConnection) -> Result<(Option<i64>, i64, i64), DatabaseError> {
+        let quantity = self.quantity - self.refunded_quantity;
+        let hold_discount = match self.hold_id {
+            Some(hold_id) => {
+                let h = Hold::find(hold_id, conn)?;
+                Some(match h.hold_type {
+                    HoldTypes::Discount => cmp::min(h.discount_in_cents.unwrap_or(0), self.unit_price_in_cents),
+                    HoldTypes::Comp => self.unit_price_in_cents,
+                })
+            }
+            None => None,
+        };
+        let code = match self.code_id {
+            Some(code_id) => Some(Code::find(code_id, conn)?),
+            None => None,
+        };
+        let (discount, discounted_quantity) = match (hold_discount, code) {
+            (Some(hold_discount), Some(code)) => {
+                let code_discount = code.discount_for_order_item(self, conn)?;
+                code.stacked_discount(
+                    (hold_discount * quantity, quantity),
+                    code_discount,
+                    self.unit_price_in_cents * quantity,
+                )
+            }
+            (Some(hold_discount), None) => (hold_discount * quantity, quantity),
+            (None, Some(code)) => code.discount_for_order_item(self, conn)?,
+            (None, None) => (0, 0),
+        };
+
+        Ok((hold_discount, discount, discounted_quantity))
+    }
+
+    /// The discount the purchaser still holds on the tickets of a discount item's parent: the
+    /// discount on the tickets that have not been refunded along with its remainder, adjusted by
+    /// the discount taken back or returned by earlier refunds.
+    fn active_discount(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let mut discount = -self.unit_price_in_cents * (self.quantity - self.refunded_quantity);
+        if let Some(remainder_item) = self.find_discount_item(conn)? {
+            discount -= remainder_item.unit_price_in_cents * remainder_item.quantity;
+        }
+        let adjustments: Vec<i64> = refund_items::table
+            .filter(refund_items::order_item_id.eq(self.id))
+            .filter(refund_items::quantity.eq(0))
+            .select(refund_items::amount)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load discount adjustments")?;
+        Ok(discount + adjustments.iter().sum::<i64>())
+    }
+
+    /// Adjusts the discount on the item's remaining tickets after a refund, as the order may no
+    /// longer qualify for all of its code's discount or may qualify for more of it again. The
+    /// change is recorded as refund items without a quantity against the discount item and the
+    /// tax charged on top of the tickets. No more is taken back than `limit_in_cents` so the
+    /// refund never goes below nothing. Returns the amount taken off the refund, negative when
+    /// discount is returned.
+    pub(crate) fn adjust_discount(
+        &self,
+        refund_id: Uuid,
+        limit_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        if self.item_type != OrderItemTypes::Tickets {
+            return Ok(0);
+        }
+        match self.code(conn)? {
+            Some(ref code) if code.discount_depends_on_order() => (),
+            _ => return Ok(0),
+        }
+        let discount_item = match self.find_discount_item(conn)? {
+            Some(discount_item) => discount_item,
+            None => return Ok(0),
+        };
+
+        let (_, discount, _) = self.calculate_discount(conn)?;
+        let mut taken_back = discount_item.active_discount(conn)? - discount;
+
+        // Inclusive tax is part of the ticket price so it has nothing to adjust
+        let mut taxes = Vec::new();
+        for tax_item in self.find_tax_items(conn)? {
+            if tax_item.unit_price_in_cents <= 0 {
+                continue;
+            }
+            if let Some(tax_rule_id) = tax_item.tax_rule_id {
+                let tax_rule = TaxRule::find(tax_rule_id, conn)?;
+                taxes.push((tax_item, tax_rule));
+            }
+        }
+        let tax_on = |amount: i64, rule: &TaxRule| {
+            if amount < 0 {
+                -rule.tax_for(-amount)
+            } else {
+                rule.tax_for(amount)
+            }
+        };
+        let with_tax = |amount: i64| amount + taxes.iter().map(|(_, rule)| tax_on(amount, rule)).sum::<i64>();
+
+        taken_back = cmp::min(taken_back, limit_in_cents);
+        while taken_back > 0 && with_tax(taken_back) > limit_in_cents {
+            taken_back -= 1;
+        }
+        if taken_back == 0 {
+            return Ok(0);
+        }
+
+        RefundItem::create(refund_id, discount_item.id, 0, -taken_back).commit(conn)?;
+        for (tax_item, rule) in &taxes {
+            let tax = tax_on(taken_back, rule);
+            if tax != 0 {
+                RefundItem::create(refund_id, tax_item.id, 0, -tax).commit(conn)?;
+            }
+        }
+        Ok(with_tax(taken_back))
+    }
+
     pub(crate) fn update_fees(&self, order: &Order, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.item_type == OrderItemTypes::ResaleTickets {
+            return self.update_resale_fees(order, conn);
+        }
+        if self.item_type == OrderItemTypes::Products {
+            return self.update_product_fees(order, conn);
+        }
         if self.item_type != OrderItemTypes::Tickets {
             return Ok(());
         }
@@ -247,7 +415,7 @@ impl OrderItem {
         let discount_item = self.find_discount_item(conn)?;
 
         let unit_price_with_discount = match discount_item {
-            Some(di) => self.unit_price_in_cents + di.unit_price_in_cents,
+            Some(ref di) => self.unit_price_in_cents + di.unit_price_in_cents,
             None => self.unit_price_in_cents,
         };
 
@@ -267,9 +435,26 @@ impl OrderItem {
                 }
             }
 
+            // Codes that waive fees only do so for tickets they discount
+            let mut fee_quantity = self.quantity;
+            if let Some(code) = self.code(conn)? {
+                if code.waives_fees {
+                    let (_, discount, discounted_quantity) = self.calculate_discount(conn)?;
+                    if discount > 0 {
+                        fee_quantity -= discounted_quantity;
+                    }
+                }
+            }
+            if fee_quantity <= 0 {
+                if let Some(fee_item) = fee_item {
+                    order.destroy_item(fee_item.id, conn)?;
+                }
+                return Ok(());
+            }
+
             match fee_item {
                 Some(mut fee_item) => {
-                    fee_item.quantity = self.quantity;
+                    fee_item.quantity = fee_quantity;
                     fee_item.unit_price_in_cents =
                         fee_schedule_range.fee_in_cents + ticket_type.additional_fee_in_cents;
                     fee_item.company_fee_in_cents = fee_schedule_range.company_fee_in_cents;
@@ -287,7 +472,7 @@ impl OrderItem {
                         company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
                         client_fee_in_cents: fee_schedule_range.client_fee_in_cents
                             + ticket_type.additional_fee_in_cents,
-                        quantity: self.quantity,
+                        quantity: fee_quantity,
                         parent_id: Some(self.id),
                     }
                     .commit(conn)?;
@@ -303,6 +488,98 @@ impl OrderItem {
         }
     }
 
+    /// Resale fees are a percentage of the listing price taken from the organization's fee schedule.
+    fn update_resale_fees(&self, order: &Order, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let fee_item = self.find_fee_item(conn)?;
+        let ticket_type = match self.ticket_type(conn)? {
+            Some(ticket_type) => ticket_type,
+            None => {
+                return DatabaseError::no_results("Order item does not have a valid ticket type");
+            }
+        };
+        let (company_fee_in_cents, client_fee_in_cents) =
+            ticket_type.fee_schedule(conn)?.resale_fees(self.unit_price_in_cents);
+
+        if company_fee_in_cents + client_fee_in_cents <= 0 {
+            if let Some(fee_item) = fee_item {
+                order.destroy_item(fee_item.id, conn)?;
+            }
+            return Ok(());
+        }
+
+        match fee_item {
+            Some(mut fee_item) => {
+                fee_item.quantity = self.quantity;
+                fee_item.unit_price_in_cents = company_fee_in_cents + client_fee_in_cents;
+                fee_item.update(conn)
+            }
+            None => {
+                NewFeesOrderItem {
+                    order_id: self.order_id,
+                    item_type: OrderItemTypes::PerUnitFees,
+                    event_id: self.event_id,
+                    unit_price_in_cents: company_fee_in_cents + client_fee_in_cents,
+                    fee_schedule_range_id: None,
+                    company_fee_in_cents,
+                    client_fee_in_cents,
+                    quantity: self.quantity,
+                    parent_id: Some(self.id),
+                }
+                .commit(conn)?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Products are charged the per unit fee from the organization's fee schedule for their price.
+    fn update_product_fees(&self, order: &Order, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let fee_item = self.find_fee_item(conn)?;
+        let product = match self.product_variant(conn)? {
+            Some(product_variant) => product_variant.product(conn)?,
+            None => {
+                return DatabaseError::no_results("Order item does not have a valid product variant");
+            }
+        };
+        let fee_schedule = FeeSchedule::find(product.organization(conn)?.fee_schedule_id, conn)?;
+        let fee_schedule_ranges = fee_schedule.ranges(conn)?;
+
+        if self.unit_price_in_cents <= 0
+            || fee_schedule_ranges.len() == 0
+            || self.unit_price_in_cents < fee_schedule_ranges[0].min_price_in_cents
+        {
+            if let Some(fee_item) = fee_item {
+                order.destroy_item(fee_item.id, conn)?;
+            }
+            return Ok(());
+        }
+
+        let fee_schedule_range = fee_schedule.get_range(self.unit_price_in_cents, conn)?;
+        match fee_item {
+            Some(mut fee_item) => {
+                fee_item.quantity = self.quantity;
+                fee_item.unit_price_in_cents = fee_schedule_range.fee_in_cents;
+                fee_item.update(conn)
+            }
+            None => {
+                NewFeesOrderItem {
+                    order_id: self.order_id,
+                    item_type: OrderItemTypes::PerUnitFees,
+                    event_id: self.event_id,
+                    unit_price_in_cents: fee_schedule_range.fee_in_cents,
+                    fee_schedule_range_id: Some(fee_schedule_range.id),
+                    company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
+                    client_fee_in_cents: fee_schedule_range.client_fee_in_cents,
+                    quantity: self.quantity,
+                    parent_id: Some(self.id),
+                }
+                .commit(conn)?;
+
+                Ok(())
+            }
+        }
+    }
+
     pub(crate) fn update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         self.validate_record(conn)?;
         diesel::update(self)
@@ -360,6 +637,13 @@ impl OrderItem {
         })
     }
 
+    pub fn product_variant(&self, conn: &PgConnection) -> Result<Option<ProductVariant>, DatabaseError> {
+        match self.product_variant_id {
+            Some(product_variant_id) => Ok(Some(ProductVariant::find(product_variant_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
     fn ticket_type_id_valid_for_access_code(
         ticket_type_id: Uuid,
         code_id: Option<Uuid>,
@@ -398,6 +682,8 @@ impl OrderItem {
             Some(code_id) => {
                 let code = Code::find(code_id, conn)?;
                 let uses = Code::find_number_of_uses(code_id, Some(order_id), conn)?;
+                // Codes from a batch are used once by the whole order
+                let quantity = if code.code_batch_id.is_some() { 1 } else { quantity };
                 if code.max_uses > 0 && code.max_uses < uses + quantity {
                     let mut validation_error =
                         create_validation_error("max_uses_reached", "Redemption code maximum uses limit exceeded");
@@ -480,6 +766,12 @@ impl OrderItem {
             event_id: Uuid,
             #[sql_type = "dUuid"]
             order_id: Uuid,
+            #[sql_type = "Nullable<dUuid>"]
+            product_variant_id: Option<Uuid>,
+            #[sql_type = "Nullable<dUuid>"]
+            package_id: Option<Uuid>,
+            #[sql_type = "BigInt"]
+            tax_in_cents: i64,
         }
 
         let results: Vec<R> = diesel::sql_query(
@@ -498,6 +790,10 @@ impl OrderItem {
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
              WHEN item_type = 'Discount' THEN 'Discount'
              WHEN item_type = 'CreditCardFees' THEN 'Credit Card Fees'
+             WHEN item_type = 'ResaleTickets' THEN e.name || ' - ' || tt.name || ' (Resale)'
+             WHEN item_type = 'Products' THEN p.name || ' - ' || pv.name
+             WHEN item_type = 'Tax' THEN tr.name
+             WHEN oi.package_id IS NOT NULL THEN e.name || ' - ' || tt.name || ' (' || pk.name || ')'
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
@@ -512,14 +808,17 @@ impl OrderItem {
              ELSE 'Valid'
            END AS cart_item_status,
            e.id AS event_id,
-           oi.order_id
+           oi.order_id,
+           oi.product_variant_id,
+           oi.package_id,
+           oi.tax_in_cents
         FROM order_items oi
            JOIN orders o ON oi.order_id = o.id
            LEFT JOIN ticket_pricing tp ON tp.id = oi.ticket_pricing_id
            LEFT JOIN events e ON oi.event_id = e.id
            LEFT JOIN users u on u.id = $3
            LEFT JOIN organization_users ou ON ou.organization_id = e.organization_id and ou.user_id = $3
-           LEFT JOIN ticket_types tt ON tp.ticket_type_id = tt.id
+           LEFT JOIN ticket_types tt ON tp.ticket_type_id = tt.id OR ((oi.item_type = 'ResaleTickets' OR oi.package_id IS NOT NULL) AND oi.ticket_type_id = tt.id)
            LEFT JOIN holds h ON oi.hold_id = h.id
            LEFT JOIN event_users ep ON u.id = ep.user_id and ep.event_id = e.id
            LEFT JOIN ticket_instances ti ON ti.id = (
@@ -532,6 +831,10 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN product_variants pv ON pv.id = oi.product_variant_id
+           LEFT JOIN products p ON p.id = pv.product_id
+           LEFT JOIN packages pk ON pk.id = oi.package_id
+           LEFT JOIN tax_rules tr ON tr.id = oi.tax_rule_id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -580,6 +883,9 @@ impl OrderItem {
                     redemption_code: item.redemption_code,
                     cart_item_status: item.cart_item_status,
                     event_id: item.event_id,
+                    product_variant_id: item.product_variant_id,
+                    package_id: item.package_id,
+                    tax_in_cents: item.tax_in_cents,
                 });
             }
             order_items.insert(order_id, display_items);
@@ -667,6 +973,68 @@ impl NewTicketsOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewResaleTicketsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub ticket_type_id: Uuid,
+}
+
+impl NewResaleTicketsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewProductsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub product_variant_id: Uuid,
+}
+
+impl NewProductsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewPackageTicketsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub ticket_type_id: Uuid,
+    pub ticket_pricing_id: Option<Uuid>,
+    pub package_id: Uuid,
+}
+
+impl NewPackageTicketsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "order_items"]
 pub(crate) struct NewFeesOrderItem {
@@ -712,6 +1080,28 @@ impl NewDiscountOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub tax_rule_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub parent_id: Option<Uuid>,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Deserialize, Queryable, QueryableByName, Serialize)]
 pub struct DisplayOrderItem {
     #[sql_type = "dUuid"]
@@ -740,4 +1130,10 @@ pub struct DisplayOrderItem {
     pub cart_item_status: Option<CartItemStatus>,
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    #[sql_type = "Nullable<dUuid>"]
+    pub product_variant_id: Option<Uuid>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub package_id: Option<Uuid>,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
 }
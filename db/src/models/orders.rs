@@ -30,6 +30,7 @@ use validator::*;
 use validators::*;
 
 pub const CART_EXPIRY_TIME_MINUTES: i64 = 15;
+pub const PARTIALLY_PAID_CART_EXPIRY_TIME_MINUTES: i64 = 24 * 60;
 const ORDER_NUMBER_LENGTH: usize = 8;
 
 #[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
@@ -63,6 +64,7 @@ pub struct Order {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub referrer: Option<String>,
+    pub currency: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -326,16 +328,23 @@ impl Order {
 
                 // Default to available quantity for non hold orders
                 let mut available = available_quantity as i64;
+                if let Some(hold_id) = item.hold_id {
+                    let hold = Hold::find(hold_id, conn)?;
+                    available = hold.quantity(conn)?.1 as i64;
+                }
                 if let Some(code_id) = item.code_id {
                     let code = Code::find(code_id, conn)?;
                     let code_available = code.available(conn)? as i64;
 
-                    if code_available < available {
+                    // Codes from a batch are used once by the whole order
+                    if code.code_batch_id.is_some() {
+                        if code_available < 1 {
+                            valid = false;
+                            break;
+                        }
+                    } else if code_available < available {
                         available = code_available;
                     }
-                } else if let Some(hold_id) = item.hold_id {
-                    let hold = Hold::find(hold_id, conn)?;
-                    available = hold.quantity(conn)?.1 as i64;
                 }
 
                 if available < item.quantity {
@@ -360,7 +369,7 @@ impl Order {
             let mut update_data = Vec::new();
             let redemption_code = self.redemption_code(conn)?;
             for item in self.items(conn)? {
-                if item.item_type != OrderItemTypes::Tickets {
+                if item.item_type != OrderItemTypes::Tickets || item.package_id.is_some() {
                     continue;
                 }
 
@@ -416,23 +425,103 @@ impl Order {
 
     pub fn redemption_code(&self, conn: &PgConnection) -> Result<Option<String>, DatabaseError> {
         for item in self.items(conn)? {
-            if let Some(code_id) = item.code_id {
-                return Ok(Some(Code::find(code_id, conn)?.redemption_code));
+            if item.hold_id.is_none() && item.code_id.is_none() {
+                continue;
             }
+
+            let mut redemption_codes = Vec::new();
             if let Some(hold_id) = item.hold_id {
-                return Ok(Hold::find(hold_id, conn)?.redemption_code);
+                if let Some(redemption_code) = Hold::find(hold_id, conn)?.redemption_code {
+                    redemption_codes.push(redemption_code);
+                }
+            }
+            if let Some(code_id) = item.code_id {
+                redemption_codes.push(Code::find(code_id, conn)?.redemption_code);
+            }
+            if redemption_codes.is_empty() {
+                return Ok(None);
             }
+            return Ok(Some(redemption_codes.join(",")));
         }
 
         Ok(None)
     }
 
+    /// Finds the hold and code for a redemption code. A hold and a discount code can be used
+    /// together by separating their redemption codes with a comma if the code's stacking rules
+    /// allow it.
+    fn find_redemption_codes(
+        &self,
+        redemption_code: &str,
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(Option<Hold>, Option<Code>), DatabaseError> {
+        let mut hold: Option<Hold> = None;
+        let mut code: Option<Code> = None;
+        for r in redemption_code.split(',').map(|r| r.trim()).filter(|r| !r.is_empty()) {
+            if let Some(h) = Hold::find_by_redemption_code(r, Some(event_id), conn).optional()? {
+                if hold.is_some() {
+                    return DatabaseError::validation_error(
+                        "redemption_code",
+                        "Only one hold can be redeemed at a time",
+                    );
+                }
+                h.confirm_hold_valid()?;
+                // Waitlist offers can only be redeemed by the user they were offered to
+                if let Some(entry) = WaitlistEntry::find_by_hold_id(h.id, conn)? {
+                    if entry.user_id != self.on_behalf_of_user_id.unwrap_or(self.user_id) {
+                        return DatabaseError::validation_error("redemption_code", "Redemption code is not valid");
+                    }
+                }
+                hold = Some(h);
+            } else if let Some(code_availability) =
+                Code::find_by_redemption_code_with_availability(r, Some(event_id), conn).optional()?
+            {
+                if code.is_some() {
+                    return DatabaseError::validation_error(
+                        "redemption_code",
+                        "Only one code can be redeemed at a time",
+                    );
+                }
+                code_availability.code.confirm_code_valid()?;
+                // Presale lottery codes can only be redeemed by the winner they were issued to
+                if let Some(entry) = PresaleLotteryEntry::find_by_code_id(code_availability.code.id, conn)? {
+                    if entry.user_id != self.on_behalf_of_user_id.unwrap_or(self.user_id) {
+                        return DatabaseError::validation_error("redemption_code", "Redemption code is not valid");
+                    }
+                }
+                code = Some(code_availability.code);
+            } else {
+                return DatabaseError::validation_error("redemption_code", "Redemption code is not valid");
+            }
+        }
+
+        if hold.is_none() && code.is_none() {
+            return DatabaseError::validation_error("redemption_code", "Redemption code is not valid");
+        }
+        if let (&Some(_), &Some(ref code)) = (&hold, &code) {
+            if code.code_type != CodeTypes::Discount || code.hold_stacking == CodeHoldStackingRules::Exclusive {
+                return DatabaseError::validation_error(
+                    "redemption_code",
+                    "Redemption code cannot be combined with a hold",
+                );
+            }
+        }
+
+        Ok((hold, code))
+    }
+
     pub fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let validation_errors = append_validation_error(
             Ok(()),
             "event_id",
             Order::order_contains_items_from_only_one_event(self.id, conn)?,
         );
+        let validation_errors = append_validation_error(
+            validation_errors,
+            "currency",
+            Order::order_contains_items_in_only_one_currency(self.id, conn)?,
+        );
 
         Ok(validation_errors?)
     }
@@ -441,9 +530,16 @@ impl Order {
         id: Uuid,
         conn: &PgConnection,
     ) -> Result<Result<(), ValidationError>, DatabaseError> {
+        // Tickets bought as part of a package may span several events of the package
         let event_count = order_items::table
             .filter(order_items::order_id.eq(id))
             .filter(order_items::event_id.is_not_null())
+            .filter(order_items::package_id.is_null())
+            .filter(order_items::item_type.eq_any(vec![
+                OrderItemTypes::Tickets,
+                OrderItemTypes::ResaleTickets,
+                OrderItemTypes::Products,
+            ]))
             .select(sql::<BigInt>("count(distinct event_id) AS event_count"))
             .get_result::<i64>(conn)
             .to_db_error(ErrorCode::QueryError, "Could not get count of unique events in cart")?;
@@ -457,6 +553,64 @@ impl Order {
         Ok(Ok(()))
     }
 
+    pub fn order_contains_items_in_only_one_currency(
+        id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Result<(), ValidationError>, DatabaseError> {
+        if Order::item_currencies(id, conn)?.len() > 1 {
+            let mut validation_error = create_validation_error(
+                "cart_currency_mismatch",
+                "Your cart already contains items priced in another currency. Please clear your cart first to purchase these items.",
+            );
+            validation_error.add_param(Cow::from("order_id"), &id);
+            return Ok(Err(validation_error.into()));
+        }
+        Ok(Ok(()))
+    }
+
+    /// Currencies of the organizations selling the items in the order.
+    fn item_currencies(id: Uuid, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "Text"]
+            currency: String,
+        }
+
+        let results: Vec<R> = sql_query(
+            r#"
+            SELECT DISTINCT orgs.currency
+            FROM order_items oi
+            LEFT JOIN events e ON e.id = oi.event_id
+            LEFT JOIN product_variants pv ON pv.id = oi.product_variant_id
+            LEFT JOIN products pr ON pr.id = pv.product_id
+            LEFT JOIN packages pk ON pk.id = oi.package_id
+            JOIN organizations orgs ON orgs.id = COALESCE(pr.organization_id, pk.organization_id, e.organization_id)
+            WHERE oi.order_id = $1
+            ORDER BY orgs.currency
+        "#,
+        )
+        .bind::<dUuid, _>(id)
+        .get_results(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load currencies of items in cart")?;
+        Ok(results.into_iter().map(|r| r.currency).collect())
+    }
+
+    /// Orders are charged in the currency of the organization selling the items. Empty carts keep
+    /// the currency of the items they last held.
+    fn update_currency(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut currencies = Order::item_currencies(self.id, conn)?;
+        if currencies.len() != 1 || currencies[0] == self.currency {
+            return Ok(());
+        }
+        let currency = currencies.remove(0);
+        diesel::update(orders::table.filter(orders::id.eq(self.id)))
+            .set((orders::currency.eq(&currency), orders::updated_at.eq(dsl::now)))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update order currency")?;
+        self.currency = currency;
+        Ok(())
+    }
+
     pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         let cart_user: Option<User> = users::table
             .filter(users::last_cart_id.eq(self.id))
@@ -498,7 +652,17 @@ impl Order {
             );
         }
 
-        // delete children order items
+        // delete children order items, fees can have their own tax items
+        let child_ids: Vec<Option<Uuid>> = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id.nullable())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child order items")?;
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(child_ids)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
             .map(|_| ())
@@ -574,18 +738,40 @@ impl Order {
         self.lock_version(conn)?;
         let mut total_to_be_refunded: i64 = 0;
 
+        let refund_data = self.with_package_refund_items(refund_data, conn)?;
         let refund = Refund::create(self.id, user_id, reason, manual_override).commit(conn)?;
         let previous_item_refund_counts: HashMap<Uuid, i64> =
             self.items(conn)?.iter().map(|i| (i.id, i.refunded_quantity)).collect();
 
-        for refund_datum in refund_data {
+        for refund_datum in &refund_data {
             let mut order_item = OrderItem::find(refund_datum.order_item_id, conn)?;
+            let root_item_type = match order_item.parent_id {
+                Some(parent_id) => OrderItem::find(parent_id, conn)?.item_type,
+                None => order_item.item_type,
+            };
             if order_item.item_type == OrderItemTypes::Discount {
                 return DatabaseError::business_process_error("Discount order items can not be refunded");
+            } else if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error(
+                    "Tax is refunded together with the item it was charged on",
+                );
+            } else if root_item_type == OrderItemTypes::ResaleTickets {
+                return DatabaseError::business_process_error("Resale order items can not be refunded");
             } else if order_item.order_id != self.id {
                 return DatabaseError::business_process_error("Order item id does not belong to this order");
             }
 
+            if root_item_type == OrderItemTypes::Products {
+                if order_item.item_type != OrderItemTypes::Products {
+                    return DatabaseError::business_process_error(
+                        "Product fees are refunded together with the product",
+                    );
+                }
+                ProductInstance::refund_one_for_order_item(order_item.id, conn)?;
+                total_to_be_refunded += order_item.refund_one_unit(true, conn)?;
+                continue;
+            }
+
             // Find the ticket instance if it was specified
             let ticket_instance = match refund_datum.ticket_instance_id {
                 Some(id) => Some(TicketInstance::find(id, conn)?),
@@ -638,9 +824,138 @@ impl Order {
             ));
         }
 
+        // Tickets left on the order may no longer qualify for the discount they were bought with,
+        // what they no longer qualify for is taken off the refund
+        for order_item in self.items(conn)? {
+            total_to_be_refunded -= order_item.adjust_discount(refund.id, total_to_be_refunded, conn)?;
+        }
+
         Ok((refund, total_to_be_refunded))
     }
 
+    /// Refund requests for every ticket and product on the order that is still held by the
+    /// purchaser, along with the tickets being refunded.
+    pub fn outstanding_refund_items(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<(Vec<RefundItemRequest>, Vec<TicketInstance>), DatabaseError> {
+        let mut refund_items = Vec::new();
+        let mut tickets = Vec::new();
+        for item in self.items(conn)? {
+            match item.item_type {
+                OrderItemTypes::Tickets => {
+                    for ticket in TicketInstance::find_for_order_item(item.id, conn)? {
+                        if ticket.status != TicketInstanceStatus::Purchased {
+                            continue;
+                        }
+                        refund_items.push(RefundItemRequest {
+                            order_item_id: item.id,
+                            ticket_instance_id: Some(ticket.id),
+                        });
+                        tickets.push(ticket);
+                    }
+                }
+                OrderItemTypes::Products => {
+                    for _ in 0..(item.quantity - item.refunded_quantity) {
+                        refund_items.push(RefundItemRequest {
+                            order_item_id: item.id,
+                            ticket_instance_id: None,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok((refund_items, tickets))
+    }
+
+    /// Tickets bought as part of a package are refunded a whole package at a time. For every
+    /// package ticket being refunded, a ticket for each of the package's other events is added to
+    /// the refund unless the request already includes one.
+    fn with_package_refund_items(
+        &self,
+        refund_data: &[RefundItemRequest],
+        conn: &PgConnection,
+    ) -> Result<Vec<RefundItemRequest>, DatabaseError> {
+        let items = self.items(conn)?;
+        let mut refund_data = refund_data.to_vec();
+
+        let mut requested_per_item: HashMap<Uuid, usize> = HashMap::new();
+        for refund_datum in &refund_data {
+            let order_item = match items.iter().find(|i| i.id == refund_datum.order_item_id) {
+                Some(order_item) => order_item,
+                None => continue,
+            };
+            if order_item.item_type == OrderItemTypes::PerUnitFees
+                && items
+                    .iter()
+                    .any(|i| Some(i.id) == order_item.parent_id && i.package_id.is_some())
+            {
+                return DatabaseError::business_process_error(
+                    "Package ticket fees are refunded together with the package",
+                );
+            }
+            if order_item.package_id.is_some() {
+                *requested_per_item.entry(order_item.id).or_insert(0) += 1;
+            }
+        }
+
+        let mut package_ids: Vec<Uuid> = items
+            .iter()
+            .filter(|i| requested_per_item.contains_key(&i.id))
+            .filter_map(|i| i.package_id)
+            .collect();
+        package_ids.sort();
+        package_ids.dedup();
+
+        for package_id in package_ids {
+            let package_items: Vec<&OrderItem> = items
+                .iter()
+                .filter(|i| i.item_type == OrderItemTypes::Tickets && i.package_id == Some(package_id))
+                .collect();
+            let packages_refunded = package_items
+                .iter()
+                .map(|i| requested_per_item.get(&i.id).cloned().unwrap_or(0))
+                .max()
+                .unwrap_or(0);
+
+            for package_item in package_items {
+                let requested = requested_per_item.get(&package_item.id).cloned().unwrap_or(0);
+                if requested >= packages_refunded {
+                    continue;
+                }
+
+                let requested_ticket_ids: Vec<Uuid> = refund_data.iter().filter_map(|r| r.ticket_instance_id).collect();
+                let tickets = TicketInstance::find_for_order_item(package_item.id, conn)?;
+                let refunded_ticket_ids: Vec<Uuid> =
+                    RefundedTicket::find_by_ticket_instance_ids(tickets.iter().map(|t| t.id).collect(), conn)?
+                        .into_iter()
+                        .filter(|r| r.ticket_refunded_at.is_some())
+                        .map(|r| r.ticket_instance_id)
+                        .collect();
+                let mut refundable_tickets = tickets
+                    .into_iter()
+                    .filter(|t| !requested_ticket_ids.contains(&t.id) && !refunded_ticket_ids.contains(&t.id));
+
+                for _ in requested..packages_refunded {
+                    match refundable_tickets.next() {
+                        Some(ticket) => refund_data.push(RefundItemRequest {
+                            order_item_id: package_item.id,
+                            ticket_instance_id: Some(ticket.id),
+                        }),
+                        None => {
+                            return DatabaseError::business_process_error(
+                                "Could not find a ticket to refund for every event in the package",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(refund_data)
+    }
+
     fn refund_ticket_instance(
         ticket_instance: &TicketInstance,
         order_item: &mut OrderItem,
@@ -785,13 +1100,23 @@ impl Order {
         }
 
         for item in self.items(conn)? {
-            if item.item_type != OrderItemTypes::Tickets {
+            if item.item_type == OrderItemTypes::Products {
+                if let Some(product_variant) = item.product_variant(conn)? {
+                    self.confirm_product_stock(&product_variant, item.quantity, conn)?;
+                }
+                continue;
+            } else if item.item_type != OrderItemTypes::Tickets {
                 continue;
             } else if item.ticket_type_id.is_none() {
                 // Sanity check given unwrap below
                 return DatabaseError::business_process_error("Ticket type required for order refresh");
             }
 
+            // Tickets at quantity limited pricing were released for others to buy when the cart expired
+            if let (Some(ticket_pricing_id), None, None) = (item.ticket_pricing_id, item.hold_id, item.package_id) {
+                TicketPricing::find(ticket_pricing_id, conn)?.reclaim(item.quantity - item.refunded_quantity, conn)?;
+            }
+
             // Sanity check: clear unexpired tickets (should affect 0; it inherits expires_at from order)
             let quantity = item.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&item, quantity as u32, current_user_id, conn)?;
@@ -821,6 +1146,80 @@ impl Order {
             .to_db_error(ErrorCode::QueryError, "Error loading payments")
     }
 
+    /// Completed payments that still have money left to refund, paired with that balance. Refunds are
+    /// taken from the most recent payment first, so when an order was split across several payments
+    /// the last payment made is the first one returned. Payments with an open or lost dispute are
+    /// excluded as the card holder's bank has taken the money back.
+    pub fn refundable_payments(&self, conn: &PgConnection) -> Result<Vec<(Payment, i64)>, DatabaseError> {
+        let payments: Vec<Payment> = payments::table
+            .filter(payments::order_id.eq(self.id))
+            .order_by(payments::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payments")?;
+
+        let mut refunded_per_payment: HashMap<Uuid, i64> = HashMap::new();
+        for payment in &payments {
+            if let Some(refunded_payment_id) = payment.refunded_payment_id {
+                *refunded_per_payment.entry(refunded_payment_id).or_insert(0) -= payment.amount;
+            }
+        }
+
+        Ok(payments
+            .into_iter()
+            .filter(|p| p.status == PaymentStatus::Completed && p.amount > 0 && !p.is_disputed())
+            .filter_map(|p| {
+                let balance = p.amount - refunded_per_payment.get(&p.id).cloned().unwrap_or(0);
+                if balance > 0 {
+                    Some((p, balance))
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Schedules the partial payments of a pending payment order to be refunded when it expires. The
+    /// action does nothing if the rest of the order is paid first, and an already scheduled refund is
+    /// moved to the order's current expiry.
+    pub fn schedule_expired_payments_refund(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let expires_at = match self.expires_at {
+            Some(expires_at) => expires_at,
+            None => return Ok(()),
+        };
+
+        if let Some(upcoming_domain_action) = DomainAction::upcoming_domain_action(
+            Some(Tables::Orders),
+            Some(self.id),
+            DomainActionTypes::RefundExpiredOrderPayments,
+            conn,
+        )? {
+            upcoming_domain_action.set_scheduled_at(expires_at, conn)?;
+            return Ok(());
+        }
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::RefundExpiredOrderPayments,
+            None,
+            json!({}),
+            Some(Tables::Orders),
+            Some(self.id),
+        );
+        action.schedule_at(expires_at);
+        action.commit(conn)?;
+        Ok(())
+    }
+
+    /// Cancels a pending payment order that expired before it was paid in full. Its payments should
+    /// be refunded first.
+    pub fn cancel_expired(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.status != OrderStatus::PendingPayment || !self.is_expired() {
+            return DatabaseError::business_process_error("Only expired pending payment orders can be cancelled");
+        }
+
+        self.update_status(None, OrderStatus::Cancelled, conn)
+    }
+
     pub fn set_browser_data(
         &mut self,
         user_agent: Option<String>,
@@ -997,12 +1396,23 @@ impl Order {
             .inner_join(orders::table.on(users::last_cart_id.eq(orders::id.nullable())))
             .filter(users::id.eq(user_id))
             .filter(orders::user_id.eq(user_id))
-            .filter(orders::status.eq(OrderStatus::Draft))
+            // Carts partially paid with a split payment are kept until the rest is paid
+            .filter(
+                orders::status.eq(OrderStatus::Draft).or(orders::status
+                    .eq(OrderStatus::PendingPayment)
+                    .and(exists(
+                        payments::table
+                            .filter(payments::order_id.eq(orders::id))
+                            .filter(payments::status.eq(PaymentStatus::Completed)),
+                    ))),
+            )
             .filter(orders::order_type.eq(OrderTypes::Cart))
+            // Expired partially paid carts remain reachable so they can be refreshed and paid off
             .filter(
                 orders::expires_at
                     .is_null()
-                    .or(orders::expires_at.ge(dsl::now.nullable())),
+                    .or(orders::expires_at.ge(dsl::now.nullable()))
+                    .or(orders::status.eq(OrderStatus::PendingPayment)),
             )
             .select(orders::all_columns)
             .first(conn)
@@ -1320,6 +1730,12 @@ impl Order {
         self.lock_version(conn)?;
 
         for current_line in self.items(conn)? {
+            if current_line.item_type == OrderItemTypes::ResaleTickets
+                || current_line.item_type == OrderItemTypes::Products
+            {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
             if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
@@ -1355,39 +1771,18 @@ impl Order {
         for (index, item) in items.iter().enumerate() {
             let ticket_type = TicketType::find(item.ticket_type_id, conn)?;
             mapped.push(match &item.redemption_code {
-                Some(r) => match Hold::find_by_redemption_code(r, Some(ticket_type.event_id), conn).optional()? {
-                    Some(hold) => {
-                        hold.confirm_hold_valid()?;
-                        MatchData {
-                            index: Some(index),
-                            hold_id: Some(hold.id),
-                            hold: Some(hold),
-                            code_id: None,
-                            code: None,
-                            redemption_code: item.redemption_code.clone(),
-                            update_order_item: item,
-                        }
+                Some(r) => {
+                    let (hold, code) = self.find_redemption_codes(r, ticket_type.event_id, conn)?;
+                    MatchData {
+                        index: Some(index),
+                        hold_id: hold.as_ref().map(|h| h.id),
+                        hold,
+                        code_id: code.as_ref().map(|c| c.id),
+                        code,
+                        redemption_code: item.redemption_code.clone(),
+                        update_order_item: item,
                     }
-                    None => match Code::find_by_redemption_code_with_availability(r, Some(ticket_type.event_id), conn)
-                        .optional()?
-                    {
-                        Some(code_availability) => {
-                            code_availability.code.confirm_code_valid()?;
-                            MatchData {
-                                index: Some(index),
-                                hold_id: None,
-                                hold: None,
-                                code_id: Some(code_availability.code.id),
-                                code: Some(code_availability.code),
-                                redemption_code: item.redemption_code.clone(),
-                                update_order_item: item,
-                            }
-                        }
-                        None => {
-                            return DatabaseError::validation_error("redemption_code", "Redemption code is not valid");
-                        }
-                    },
-                },
+                }
                 None => MatchData {
                     index: Some(index),
                     hold_id: None,
@@ -1401,9 +1796,28 @@ impl Order {
         }
 
         for mut current_line in current_items {
+            if (current_line.item_type == OrderItemTypes::ResaleTickets
+                || current_line.item_type == OrderItemTypes::Products)
+                && remove_others
+            {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
             if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
+            if current_line.package_id.is_some() {
+                if remove_others {
+                    TicketInstance::release_tickets(
+                        &current_line,
+                        current_line.quantity as u32,
+                        Some(current_user_id),
+                        conn,
+                    )?;
+                    self.destroy_item(current_line.id, conn)?;
+                }
+                continue;
+            }
 
             let mut index_to_remove: Option<usize> = None;
             {
@@ -1438,8 +1852,15 @@ impl Order {
 
                         // TODO: Fetch the ticket type and pricing in one go.
                         let ticket_type_id = current_line.ticket_type_id.unwrap();
-                        let ticket_pricing =
-                            TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, false, conn)?;
+                        let additional_quantity = match_data.update_order_item.quantity - current_line.quantity as u32;
+                        let ticket_pricing = Order::current_ticket_pricing(
+                            ticket_type_id,
+                            box_office_pricing,
+                            match_data.hold_id,
+                            additional_quantity,
+                            current_user_id,
+                            conn,
+                        )?;
                         let ticket_type = TicketType::find(ticket_type_id, conn)?;
                         check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
 
@@ -1450,7 +1871,7 @@ impl Order {
                             let order_item = NewTicketsOrderItem {
                                 order_id: self.id,
                                 item_type: OrderItemTypes::Tickets,
-                                quantity: match_data.update_order_item.quantity as i64,
+                                quantity: additional_quantity as i64,
                                 ticket_type_id: ticket_type.id,
                                 ticket_pricing_id: ticket_pricing.id,
                                 event_id: Some(ticket_type.event_id),
@@ -1464,7 +1885,7 @@ impl Order {
                                 self.expires_at,
                                 ticket_type_id,
                                 match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
+                                additional_quantity,
                                 conn,
                             )?;
                         } else {
@@ -1473,7 +1894,7 @@ impl Order {
                                 self.expires_at,
                                 ticket_type_id,
                                 match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
+                                additional_quantity,
                                 conn,
                             )?;
                             current_line.quantity = match_data.update_order_item.quantity as i64;
@@ -1498,104 +1919,508 @@ impl Order {
         }
 
         // Set cart expiration time if not currently set (empty carts have no expiration)
-        if self.expires_at.is_none() {
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        for match_data in mapped {
+            if match_data.update_order_item.quantity == 0 || match_data.index.is_none() {
+                continue;
+            }
+
+            jlog!(Level::Debug, "Adding new cart items");
+            let ticket_pricing = Order::current_ticket_pricing(
+                match_data.update_order_item.ticket_type_id,
+                box_office_pricing,
+                match_data.hold_id,
+                match_data.update_order_item.quantity,
+                current_user_id,
+                conn,
+            )?;
+
+            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
+            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+
+            let price_in_cents = ticket_pricing.price_in_cents;
+
+            // TODO: Move this to an external processer
+            let order_item = NewTicketsOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::Tickets,
+                quantity: match_data.update_order_item.quantity as i64,
+                ticket_type_id: ticket_type.id,
+                ticket_pricing_id: ticket_pricing.id,
+                event_id: Some(ticket_type.event_id),
+                unit_price_in_cents: price_in_cents,
+                hold_id: match_data.hold_id,
+                code_id: match_data.code_id,
+            }
+            .commit(conn);
+
+            TicketInstance::reserve_tickets(
+                &order_item?,
+                self.expires_at,
+                match_data.update_order_item.ticket_type_id,
+                match_data.hold_id,
+                match_data.update_order_item.quantity,
+                conn,
+            )?;
+        }
+
+        // if the cart is empty at this point, it is effectively a new cart, remove expiration
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        for limit_check in check_ticket_limits {
+            let ordered_quantity = Order::quantity_for_user_for_ticket_type(
+                self.user_id,
+                limit_check.ticket_type_id,
+                limit_check.hold_id,
+                limit_check.code_id,
+                &conn,
+            )?;
+
+            if limit_check.limit_per_person > 0 && ordered_quantity > limit_check.limit_per_person.into() {
+                let mut error = ValidationError::new("limit_per_person_exceeded");
+                error.message = Some(Cow::from(
+                    if limit_check.hold_id.is_some() || limit_check.code_id.is_some() {
+                        format!(
+                            "Max of {} uses for code {} exceeded",
+                            &limit_check.limit_per_person,
+                            limit_check.redemption_code.unwrap_or("".into())
+                        )
+                    } else {
+                        "You have exceeded the max tickets per customer limit.".into()
+                    },
+                ));
+                error.add_param(Cow::from("limit_per_person"), &limit_check.limit_per_person);
+                error.add_param(Cow::from("ticket_type_id"), &limit_check.ticket_type_id);
+                if let Some(hold_id) = limit_check.hold_id {
+                    error.add_param(Cow::from("hold_id"), &hold_id);
+                }
+                if let Some(code_id) = limit_check.code_id {
+                    error.add_param(Cow::from("code_id"), &code_id);
+                }
+                error.add_param(Cow::from("attempted_quantity"), &ordered_quantity);
+                let mut errors = ValidationErrors::new();
+                errors.add("quantity", error);
+                return Err(errors.into());
+            }
+        }
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        self.update_currency(conn)?;
+        // Beware there could be multiple orders that meet this condition
+        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            if remaining == 0 {
+                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the tickets in the cart for a reserved seating ticket type to exactly the selected seats.
+    /// An empty list of seats removes the ticket type from the cart.
+    pub fn update_seats(
+        &mut self,
+        current_user_id: Uuid,
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        redemption_code: Option<String>,
+        box_office_pricing: bool,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let seating_section_id = match ticket_type.seating_section_id {
+            Some(seating_section_id) => seating_section_id,
+            None => {
+                return DatabaseError::validation_error("seat_ids", "Ticket type does not use reserved seating");
+            }
+        };
+
+        let mut seat_ids = seat_ids.to_vec();
+        seat_ids.sort();
+        seat_ids.dedup();
+
+        self.update_quantities(
+            current_user_id,
+            &[UpdateOrderItem {
+                ticket_type_id,
+                quantity: seat_ids.len() as u32,
+                redemption_code,
+            }],
+            box_office_pricing,
+            false,
+            conn,
+        )?;
+
+        let mut tickets: Vec<TicketInstance> = Vec::new();
+        for item in self.items(conn)?.iter().filter(|i| {
+            i.item_type == OrderItemTypes::Tickets && i.ticket_type_id == Some(ticket_type_id) && i.package_id.is_none()
+        }) {
+            tickets.append(&mut TicketInstance::find_for_order_item(item.id, conn)?);
+        }
+
+        TicketInstance::assign_seats(&tickets, &seat_ids, seating_section_id, ticket_type.event_id, conn)?;
+
+        Ok(())
+    }
+
+    /// Moves all tickets in the cart for the entry slot's ticket type into that slot.
+    pub fn update_entry_slot(&mut self, entry_slot_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error(
+                "Cannot change the entry slot unless the order is in draft status",
+            );
+        }
+        if self.is_expired() {
+            return DatabaseError::business_process_error("Cart has expired");
+        }
+
+        let entry_slot = EntrySlot::find(entry_slot_id, conn)?;
+        let mut tickets: Vec<TicketInstance> = Vec::new();
+        for item in self
+            .items(conn)?
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets && i.ticket_type_id == Some(entry_slot.ticket_type_id))
+        {
+            tickets.append(&mut TicketInstance::find_for_order_item(item.id, conn)?);
+        }
+        if tickets.is_empty() {
+            return DatabaseError::validation_error("entry_slot_id", "No tickets in the cart for this entry slot");
+        }
+
+        TicketInstance::assign_entry_slot(&tickets, &entry_slot, conn)?;
+
+        Ok(())
+    }
+
+    /// Adds a fan resale listing to the cart. The listing is held for this cart until the cart
+    /// expires, after which it can be claimed by another buyer.
+    pub fn add_resale_listing(
+        &mut self,
+        current_user_id: Uuid,
+        resale_listing_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        if self.box_office_pricing {
+            return DatabaseError::business_process_error("Resale tickets cannot be purchased with box office pricing");
+        }
+
+        let listing = ResaleListing::find_for_update(resale_listing_id, conn)?;
+        if listing.status != ResaleListingStatus::Active {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+        if listing.seller_user_id == self.on_behalf_of_user_id.unwrap_or(self.user_id) {
+            return DatabaseError::business_process_error("You cannot purchase your own resale listing");
+        }
+        if let Some(order_item_id) = listing.order_item_id {
+            if self.items(conn)?.iter().any(|i| i.id == order_item_id) {
+                return Ok(());
+            }
+        }
+        if listing.in_other_cart(self.id, conn)? {
+            return DatabaseError::business_process_error("Resale listing is reserved by another customer");
+        }
+
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        let ticket_type = listing.ticket_instance(conn)?.ticket_type(conn)?;
+        let order_item = NewResaleTicketsOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::ResaleTickets,
+            event_id: Some(listing.event_id),
+            quantity: 1,
+            unit_price_in_cents: listing.price_in_cents,
+            ticket_type_id: ticket_type.id,
+        }
+        .commit(conn)?;
+        listing.reserve_for_order_item(Some(order_item.id), conn)?;
+
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        self.update_currency(conn)?;
+
+        Ok(())
+    }
+
+    pub fn remove_resale_listing(&mut self, resale_listing_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        let listing = ResaleListing::find(resale_listing_id, conn)?;
+        match self
+            .items(conn)?
+            .into_iter()
+            .find(|i| i.item_type == OrderItemTypes::ResaleTickets && Some(i.id) == listing.order_item_id)
+        {
+            Some(item) => self.destroy_item(item.id, conn)?,
+            None => return DatabaseError::no_results("Resale listing is not in this cart"),
+        }
+
+        self.update_fees_and_discounts(conn)
+    }
+
+    /// Sets the quantity of each product variant in the cart. Products tied to an event are picked
+    /// up at that event, organization wide products are picked up at the event given with them.
+    pub fn update_product_quantities(
+        &mut self,
+        current_user_id: Uuid,
+        items: &[UpdateProductOrderItem],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Cannot change products unless the order is in draft status");
+        }
+
+        jlog!(Debug, "Update order product quantities", {"items": items, "user_id": current_user_id});
+
+        let current_items = self.items(conn)?;
+        for item in items {
+            let product_variant = ProductVariant::find(item.product_variant_id, conn)?;
+            let product = product_variant.product(conn)?;
+            let event_id = match (product.event_id, item.event_id) {
+                (Some(product_event_id), Some(event_id)) if product_event_id != event_id => {
+                    return DatabaseError::validation_error("event_id", "Product is not sold for this event");
+                }
+                (Some(product_event_id), _) => product_event_id,
+                (None, Some(event_id)) => {
+                    if Event::find(event_id, conn)?.organization_id != product.organization_id {
+                        return DatabaseError::validation_error("event_id", "Product is not sold for this event");
+                    }
+                    event_id
+                }
+                (None, None) => {
+                    return DatabaseError::validation_error("event_id", "Event is required to pick up this product");
+                }
+            };
+
+            let current_line = current_items.iter().find(|i| {
+                i.item_type == OrderItemTypes::Products
+                    && i.product_variant_id == Some(product_variant.id)
+                    && i.event_id == Some(event_id)
+            });
+
+            if item.quantity == 0 {
+                if let Some(current_line) = current_line {
+                    self.destroy_item(current_line.id, conn)?;
+                }
+                continue;
+            }
+
+            if !product.active {
+                return DatabaseError::business_process_error("Product is no longer available");
+            }
+            self.confirm_product_stock(&product_variant, item.quantity as i64, conn)?;
+
+            let unit_price_in_cents = product_variant.unit_price_in_cents(&product);
+            match current_line {
+                Some(current_line) => {
+                    let mut current_line = current_line.clone();
+                    current_line.quantity = item.quantity as i64;
+                    current_line.unit_price_in_cents = unit_price_in_cents;
+                    current_line.update(conn)?;
+                }
+                None => {
+                    NewProductsOrderItem {
+                        order_id: self.id,
+                        item_type: OrderItemTypes::Products,
+                        event_id: Some(event_id),
+                        quantity: item.quantity as i64,
+                        unit_price_in_cents,
+                        product_variant_id: product_variant.id,
+                    }
+                    .commit(conn)?;
+                }
+            }
+        }
+
+        if self.items(conn)?.len() == 0 {
+            if self.expires_at.is_some() {
+                self.remove_expiry(current_user_id, conn)?;
+            }
+            return Ok(());
+        }
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        self.update_currency(conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the number of each package in the cart. Every package reserves one ticket for each event
+    /// it includes, priced at the share of the package price allocated to that event.
+    pub fn update_package_quantities(
+        &mut self,
+        current_user_id: Uuid,
+        items: &[UpdatePackageOrderItem],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Cannot change packages unless the order is in draft status");
+        }
+
+        jlog!(Debug, "Update order package quantities", {"items": items, "user_id": current_user_id});
+
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() && items.iter().any(|i| i.quantity > 0) {
             self.set_expiry(Some(current_user_id), None, false, conn)?;
         }
 
-        for match_data in mapped {
-            if match_data.update_order_item.quantity == 0 || match_data.index.is_none() {
+        let current_items = self.items(conn)?;
+        for item in items {
+            let package = Package::find(item.package_id, conn)?;
+            let quantity = item.quantity as i64;
+            let current_lines: Vec<&OrderItem> = current_items
+                .iter()
+                .filter(|i| i.item_type == OrderItemTypes::Tickets && i.package_id == Some(package.id))
+                .collect();
+            let current_quantity = current_lines.iter().map(|i| i.quantity).max().unwrap_or(0);
+
+            if quantity < current_quantity {
+                for current_line in current_lines {
+                    TicketInstance::release_tickets(
+                        current_line,
+                        (current_line.quantity - quantity) as u32,
+                        Some(current_user_id),
+                        conn,
+                    )?;
+                    if quantity == 0 {
+                        self.destroy_item(current_line.id, conn)?;
+                    } else {
+                        let mut current_line = current_line.clone();
+                        current_line.quantity = quantity;
+                        current_line.update(conn)?;
+                    }
+                }
+                continue;
+            } else if quantity == current_quantity {
                 continue;
             }
 
-            jlog!(Level::Debug, "Adding new cart items");
-            let ticket_pricing = TicketPricing::get_current_ticket_pricing(
-                match_data.update_order_item.ticket_type_id,
-                box_office_pricing,
-                false,
-                conn,
-            )?;
-
-            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
-            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+            if !package.active {
+                return DatabaseError::business_process_error("Package is no longer available");
+            }
+            let package_ticket_types = package.ticket_types(conn)?;
+            if package_ticket_types.is_empty() {
+                return DatabaseError::validation_error("package_id", "Package does not include any events");
+            }
 
-            let price_in_cents = ticket_pricing.price_in_cents;
+            for package_ticket_type in package_ticket_types {
+                let ticket_type = package_ticket_type.ticket_type(conn)?;
+                if !TicketType::is_event_available_for_sale(&ticket_type.id, conn)? {
+                    return DatabaseError::business_process_error("An event in this package is no longer on sale");
+                }
 
-            // TODO: Move this to an external processer
-            let order_item = NewTicketsOrderItem {
-                order_id: self.id,
-                item_type: OrderItemTypes::Tickets,
-                quantity: match_data.update_order_item.quantity as i64,
-                ticket_type_id: ticket_type.id,
-                ticket_pricing_id: ticket_pricing.id,
-                event_id: Some(ticket_type.event_id),
-                unit_price_in_cents: price_in_cents,
-                hold_id: match_data.hold_id,
-                code_id: match_data.code_id,
+                match current_lines.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)) {
+                    Some(current_line) => {
+                        if current_line.quantity >= quantity {
+                            continue;
+                        }
+                        TicketInstance::reserve_tickets(
+                            current_line,
+                            self.expires_at,
+                            ticket_type.id,
+                            None,
+                            (quantity - current_line.quantity) as u32,
+                            conn,
+                        )?;
+                        let mut current_line = (*current_line).clone();
+                        current_line.quantity = quantity;
+                        current_line.update(conn)?;
+                    }
+                    None => {
+                        let ticket_pricing_id =
+                            TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, conn)
+                                .optional()?
+                                .map(|ticket_pricing| ticket_pricing.id);
+                        let order_item = NewPackageTicketsOrderItem {
+                            order_id: self.id,
+                            item_type: OrderItemTypes::Tickets,
+                            event_id: Some(ticket_type.event_id),
+                            quantity,
+                            unit_price_in_cents: package_ticket_type.price_in_cents,
+                            ticket_type_id: ticket_type.id,
+                            ticket_pricing_id,
+                            package_id: package.id,
+                        }
+                        .commit(conn)?;
+                        TicketInstance::reserve_tickets(
+                            &order_item,
+                            self.expires_at,
+                            ticket_type.id,
+                            None,
+                            quantity as u32,
+                            conn,
+                        )?;
+                    }
+                }
             }
-            .commit(conn);
-
-            TicketInstance::reserve_tickets(
-                &order_item?,
-                self.expires_at,
-                match_data.update_order_item.ticket_type_id,
-                match_data.hold_id,
-                match_data.update_order_item.quantity,
-                conn,
-            )?;
         }
 
-        // if the cart is empty at this point, it is effectively a new cart, remove expiration
         if self.items(conn)?.len() == 0 {
-            self.remove_expiry(current_user_id, conn)?;
-        }
-        for limit_check in check_ticket_limits {
-            let ordered_quantity = Order::quantity_for_user_for_ticket_type(
-                self.user_id,
-                limit_check.ticket_type_id,
-                limit_check.hold_id,
-                limit_check.code_id,
-                &conn,
-            )?;
-
-            if limit_check.limit_per_person > 0 && ordered_quantity > limit_check.limit_per_person.into() {
-                let mut error = ValidationError::new("limit_per_person_exceeded");
-                error.message = Some(Cow::from(
-                    if limit_check.hold_id.is_some() || limit_check.code_id.is_some() {
-                        format!(
-                            "Max of {} uses for code {} exceeded",
-                            &limit_check.limit_per_person,
-                            limit_check.redemption_code.unwrap_or("".into())
-                        )
-                    } else {
-                        "You have exceeded the max tickets per customer limit.".into()
-                    },
-                ));
-                error.add_param(Cow::from("limit_per_person"), &limit_check.limit_per_person);
-                error.add_param(Cow::from("ticket_type_id"), &limit_check.ticket_type_id);
-                if let Some(hold_id) = limit_check.hold_id {
-                    error.add_param(Cow::from("hold_id"), &hold_id);
-                }
-                if let Some(code_id) = limit_check.code_id {
-                    error.add_param(Cow::from("code_id"), &code_id);
-                }
-                error.add_param(Cow::from("attempted_quantity"), &ordered_quantity);
-                let mut errors = ValidationErrors::new();
-                errors.add("quantity", error);
-                return Err(errors.into());
+            if self.expires_at.is_some() {
+                self.remove_expiry(current_user_id, conn)?;
             }
+            return Ok(());
         }
+
         self.update_fees_and_discounts(conn)?;
         self.validate_record(conn)?;
-        // Beware there could be multiple orders that meet this condition
-        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
-            if remaining == 0 {
-                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
-            }
-        }
+        self.update_currency(conn)?;
+
+        Ok(())
+    }
 
+    /// Errors unless the variant has `quantity` units of stock free for this order. The variant is
+    /// locked for the rest of the transaction so the stock cannot be claimed by another cart.
+    fn confirm_product_stock(
+        &self,
+        product_variant: &ProductVariant,
+        quantity: i64,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        product_variant.lock_for_update(conn)?;
+        if product_variant.available(Some(self.id), conn)? < quantity {
+            return DatabaseError::validation_error("quantity", "Not enough stock is available for this product");
+        }
         Ok(())
     }
 
+    /// Tickets from holds are set aside separately so they do not use up quantity limited pricing.
+    fn current_ticket_pricing(
+        ticket_type_id: Uuid,
+        box_office_pricing: bool,
+        hold_id: Option<Uuid>,
+        quantity: u32,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<TicketPricing, DatabaseError> {
+        if hold_id.is_some() {
+            return TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, false, conn);
+        }
+        TicketPricing::claim_current_ticket_pricing(
+            ticket_type_id,
+            box_office_pricing,
+            quantity,
+            Some(current_user_id),
+            conn,
+        )
+    }
+
     fn check_ticket_limits(ticket_type: &TicketType, match_data: &MatchData) -> Vec<LimitCheck> {
         let mut check_ticket_limits: Vec<LimitCheck> = vec![];
         check_ticket_limits.push(LimitCheck {
@@ -1613,7 +2438,8 @@ impl Order {
                 limit_per_person: hold.max_per_user.unwrap_or(0) as u32,
                 redemption_code: match_data.redemption_code.clone(),
             });
-        } else if let Some(ref code) = match_data.code {
+        }
+        if let Some(ref code) = match_data.code {
             check_ticket_limits.push(LimitCheck {
                 ticket_type_id: ticket_type.id,
                 hold_id: None,
@@ -1639,13 +2465,14 @@ impl Order {
             match o.item_type {
                 OrderItemTypes::EventFees => self.destroy_item(o.id, conn)?,
                 OrderItemTypes::CreditCardFees => self.destroy_item(o.id, conn)?,
+                OrderItemTypes::Tax => self.destroy_item(o.id, conn)?,
                 _ => {}
             }
         }
 
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
-            return Ok(());
+            return self.update_taxes(conn);
         }
 
         let mut per_event_fees_included: HashMap<Uuid, bool> = HashMap::new();
@@ -1676,7 +2503,7 @@ impl Order {
 
             for o in items {
                 match o.item_type {
-                    OrderItemTypes::Tickets => {
+                    OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets | OrderItemTypes::Products => {
                         let discount_item = o.find_discount_item(conn)?;
 
                         let unit_price_with_discount = match discount_item {
@@ -1741,6 +2568,80 @@ impl Order {
             }
         }
 
+        self.update_taxes(conn)
+    }
+
+    /// Adds a tax item under every taxed item in the order for each tax rule of its event's venue.
+    /// Organization wide products are taxed at the venue of the event they are picked up at.
+    /// Exclusive tax is charged on top of the item, inclusive tax is already part of its price so
+    /// it is only recorded in `tax_in_cents`. Gift cards are stored value and are not taxed.
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let items = self.items(conn)?;
+        let mut rules_per_event: HashMap<Uuid, Vec<TaxRule>> = HashMap::new();
+
+        for item in &items {
+            let event_id = match item.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
+            let parent = items.iter().find(|i| Some(i.id) == item.parent_id);
+            let is_fee = match item.item_type {
+                OrderItemTypes::Tickets => false,
+                OrderItemTypes::Products => {
+                    if let Some(product_variant_id) = item.product_variant_id {
+                        if ProductVariant::find(product_variant_id, conn)?.product(conn)?.gift_card {
+                            continue;
+                        }
+                    }
+                    false
+                }
+                OrderItemTypes::EventFees | OrderItemTypes::CreditCardFees => true,
+                // Resold tickets were taxed when they were first sold
+                OrderItemTypes::PerUnitFees
+                    if parent
+                        .map(|p| p.item_type != OrderItemTypes::ResaleTickets)
+                        .unwrap_or(false) =>
+                {
+                    true
+                }
+                _ => continue,
+            };
+
+            if !rules_per_event.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                rules_per_event.insert(event_id, TaxRule::find_for_event(&event, conn)?);
+            }
+
+            let discount_in_cents: i64 = items
+                .iter()
+                .filter(|i| i.parent_id == Some(item.id) && i.item_type == OrderItemTypes::Discount)
+                .map(|i| i.unit_price_in_cents)
+                .sum();
+            let taxable_in_cents = item.unit_price_in_cents + discount_in_cents;
+
+            for rule in &rules_per_event[&event_id] {
+                if is_fee && !rule.taxes_fees {
+                    continue;
+                }
+                let tax_in_cents = rule.tax_for(taxable_in_cents);
+                if tax_in_cents == 0 {
+                    continue;
+                }
+
+                NewTaxOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tax,
+                    event_id: item.event_id,
+                    quantity: item.quantity,
+                    unit_price_in_cents: if rule.inclusive { 0 } else { tax_in_cents },
+                    tax_rule_id: Some(rule.id),
+                    tax_in_cents,
+                    parent_id: Some(item.id),
+                }
+                .commit(conn)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -2034,6 +2935,8 @@ impl Order {
             checkout_url: Option<String>,
             #[sql_type = "Nullable<Timestamp>"]
             checkout_url_expires: Option<NaiveDateTime>,
+            #[sql_type = "Text"]
+            currency: String,
             #[sql_type = "Nullable<Array<Text>>"]
             payment_methods: Option<Vec<PaymentMethods>>,
             #[sql_type = "Nullable<Array<Text>>"]
@@ -2042,6 +2945,8 @@ impl Order {
             total_in_cents: i64,
             #[sql_type = "BigInt"]
             total_refunded_in_cents: i64,
+            #[sql_type = "BigInt"]
+            amount_paid_in_cents: i64,
             #[sql_type = "Nullable<Array<Text>>"]
             allowed_payment_providers: Option<Vec<String>>,
             #[sql_type = "Nullable<Array<dUuid>>"]
@@ -2064,10 +2969,12 @@ impl Order {
                 o.platform,
                 o.checkout_url,
                 o.checkout_url_expires,
+                o.currency,
                 p.payment_methods,
                 p.providers,
                 CAST(COALESCE(SUM(oi.unit_price_in_cents * oi.quantity), 0) as BigInt) as total_in_cents,
                 CAST(COALESCE(SUM(oi.unit_price_in_cents * oi.refunded_quantity), 0) as BigInt) as total_refunded_in_cents,
+                CAST(COALESCE(p.amount_paid_in_cents, 0) as BigInt) as amount_paid_in_cents,
                 ARRAY_AGG(DISTINCT SUBSTRING(orgs.allowed_payment_providers::text from 2 for char_length(orgs.allowed_payment_providers::text) - 2)) FILTER (WHERE orgs.allowed_payment_providers IS NOT NULL) as allowed_payment_providers,
                 ARRAY_AGG(DISTINCT e.organization_id) FILTER (WHERE e.organization_id IS NOT NULL) as organization_ids,
                 ARRAY_AGG(DISTINCT e.id) FILTER (WHERE e.id IS NOT NULL) as event_ids
@@ -2079,10 +2986,11 @@ impl Order {
                 SELECT
                     p.order_id,
                     ARRAY_AGG(DISTINCT p.provider) FILTER (WHERE p.provider IS NOT NULL) as providers,
-                    ARRAY_AGG(DISTINCT p.payment_method) FILTER (WHERE p.payment_method IS NOT NULL) as payment_methods
+                    ARRAY_AGG(DISTINCT p.payment_method) FILTER (WHERE p.payment_method IS NOT NULL) as payment_methods,
+                    SUM(p.amount) FILTER (WHERE p.status = 'Completed') as amount_paid_in_cents
                 FROM payments p
                 WHERE p.status in ('Completed', 'Refunded')
-                GROUP BY p.payment_method, p.order_id
+                GROUP BY p.order_id
             ) AS p on o.id = p.order_id
         "#,
         )
@@ -2104,8 +3012,10 @@ impl Order {
                 o.expires_at,
                 o.checkout_url_expires,
                 o.checkout_url,
+                o.currency,
                 p.payment_methods,
-                p.providers
+                p.providers,
+                p.amount_paid_in_cents
             ORDER BY o.order_date desc
         ",
         );
@@ -2273,6 +3183,8 @@ impl Order {
                 limited_tickets_remaining,
                 total_in_cents: result.total_in_cents,
                 total_refunded_in_cents: result.total_refunded_in_cents,
+                amount_paid_in_cents: result.amount_paid_in_cents,
+                currency: result.currency,
                 seconds_until_expiry,
                 user_id: result.user_id,
                 user,
@@ -2348,7 +3260,7 @@ impl Order {
         current_user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
-        if self.status != OrderStatus::Draft {
+        if self.status != OrderStatus::Draft && self.status != OrderStatus::PendingPayment {
             return DatabaseError::validation_error(
                 "status",
                 "Cannot change the order user unless the order is in draft status",
@@ -2405,6 +3317,7 @@ impl Order {
             None,
             None,
             None,
+            self.currency.clone(),
         );
         self.add_payment(payment, Some(current_user_id), conn)
     }
@@ -2430,6 +3343,7 @@ impl Order {
             None,
             None,
             None,
+            self.currency.clone(),
         );
         self.add_payment(payment, Some(current_user_id), conn)
     }
@@ -2456,6 +3370,7 @@ impl Order {
             Some(data),
             url_nonce,
             None,
+            self.currency.clone(),
         );
 
         self.add_payment(payment, current_user_id, conn)
@@ -2482,11 +3397,90 @@ impl Order {
             Some(provider_data),
             None,
             None,
+            self.currency.clone(),
+        );
+
+        self.add_payment(payment, Some(current_user_id), conn)
+    }
+
+    /// Records credit carried over from a ticket refunded as part of a `TicketExchange`.
+    pub fn add_exchange_payment(
+        &mut self,
+        current_user_id: Uuid,
+        amount: i64,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::Exchange,
+            PaymentProviders::Exchange,
+            None,
+            amount,
+            None,
+            None,
+            None,
+            self.currency.clone(),
         );
 
         self.add_payment(payment, Some(current_user_id), conn)
     }
 
+    /// Pays `amount` of the order from the purchaser's store credit. The amount can be less than
+    /// the order total, in which case the rest is paid with another payment method. Credit is
+    /// taken from the user the order is for, the same user refunds are credited to.
+    pub fn add_store_credit_payment(
+        &mut self,
+        current_user_id: Uuid,
+        amount: i64,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != OrderStatus::Draft && self.status != OrderStatus::PendingPayment {
+            return DatabaseError::business_process_error("Store credit can only be applied to draft orders");
+        }
+        if amount <= 0 {
+            return DatabaseError::validation_error("amount", "Store credit amount must be more than zero");
+        }
+        if amount > self.calculate_total(conn)? - self.total_paid(conn)? {
+            return DatabaseError::validation_error("amount", "Store credit amount is more than the amount due");
+        }
+        let purchaser_id = self.on_behalf_of_user_id.unwrap_or(self.user_id);
+        if amount > StoreCreditTransaction::balance_for_user(purchaser_id, &self.currency, conn)? {
+            return DatabaseError::validation_error("amount", "Insufficient store credit");
+        }
+
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::StoreCredit,
+            PaymentProviders::StoreCredit,
+            None,
+            amount,
+            None,
+            None,
+            None,
+            self.currency.clone(),
+        );
+        let payment = self.add_payment(payment, Some(current_user_id), conn)?;
+
+        NewStoreCreditTransaction {
+            order_id: Some(self.id),
+            payment_id: Some(payment.id),
+            ..StoreCreditTransaction::create(
+                purchaser_id,
+                -amount,
+                self.currency.clone(),
+                StoreCreditTransactionTypes::Payment,
+                Some(current_user_id),
+            )
+        }
+        .commit(conn)?;
+
+        Ok(payment)
+    }
+
     pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
         users::table
             .filter(users::id.eq(self.on_behalf_of_user_id.unwrap_or(self.user_id)))
@@ -2559,17 +3553,21 @@ impl Order {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
-        // Confirm codes are still valid
+        // Confirm codes and resale listings are still valid
         for item in self.items(conn)? {
             item.confirm_code_valid(conn)?;
+            if item.item_type == OrderItemTypes::ResaleTickets && self.status != OrderStatus::Paid {
+                ResaleListing::find_by_order_item_id(item.id, conn)?.confirm_available(item.id, conn)?;
+            }
         }
 
         let p = payment.commit(current_user_id, conn)?;
-        if p.status != PaymentStatus::Requested {
+        self.complete_if_fully_paid(current_user_id, conn)?;
+
+        // Partially paid orders stay in the user's cart so the rest can be paid with another payment
+        if p.status != PaymentStatus::Requested && !self.is_partially_paid(conn)? {
             self.clear_user_cart(conn)?;
         }
-
-        self.complete_if_fully_paid(current_user_id, conn)?;
         Ok(p)
     }
 
@@ -2582,10 +3580,10 @@ impl Order {
             return Ok(());
         }
 
-        let total_paid = self.total_paid(conn)?;
-        let total_required = self.calculate_total(conn)?;
-        if total_paid >= total_required {
+        let amount_due = self.amount_due_now(conn)?;
+        if amount_due <= 0 {
             self.update_status(current_user_id, OrderStatus::Paid, conn)?;
+            self.clear_user_cart(conn)?;
             //Mark tickets as Purchased
             let order_items = OrderItem::find_for_order(self.id, conn)?;
             for item in order_items
@@ -2594,9 +3592,35 @@ impl Order {
                 .collect_vec()
             {
                 TicketInstance::mark_as_purchased(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
+                if let Some(hold_id) = item.hold_id {
+                    if let Some(entry) = WaitlistEntry::find_by_hold_id(hold_id, conn)? {
+                        entry.mark_purchased(current_user_id, conn)?;
+                    }
+                }
+            }
+
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::Products)
+                .collect_vec()
+            {
+                ProductInstance::create_for_order_item(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
+            }
+
+            let mut ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::ResaleTickets)
+                .collect_vec()
+            {
+                let listing = ResaleListing::find_by_order_item_id(item.id, conn)?.complete_purchase(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    conn,
+                )?;
+                ticket_ids.push(listing.ticket_instance_id);
             }
 
-            let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
             let domain_event = DomainEvent::create(
                 DomainEventTypes::OrderCompleted,
                 "Order completed".into(),
@@ -2621,7 +3645,21 @@ impl Order {
             self.user(&conn)?.update_genre_info(conn)?;
             Ok(())
         } else {
-            jlog!(Debug, "Order was checked for completion but was short", {"required_amount": total_required, "total_paid": total_paid, "order_id": self.id});
+            jlog!(Debug, "Order was checked for completion but was short", {"amount_due": amount_due, "order_id": self.id});
+            // Orders split across several payments wait in pending payment until the rest is paid
+            if self.total_paid(conn)? > 0 {
+                if self.status == OrderStatus::Draft {
+                    self.update_status(current_user_id, OrderStatus::PendingPayment, conn)?;
+                }
+                // Hold the tickets long enough for the customer to pay the rest of the order
+                self.set_expiry(
+                    current_user_id,
+                    Some(Utc::now().naive_utc() + Duration::minutes(PARTIALLY_PAID_CART_EXPIRY_TIME_MINUTES)),
+                    true,
+                    conn,
+                )?;
+                self.schedule_expired_payments_refund(conn)?;
+            }
             Ok(())
         }
     }
@@ -2638,6 +3676,21 @@ impl Order {
         Ok(())
     }
 
+    /// Whether completed payments cover part, but not all, of the amount due on the order.
+    pub fn is_partially_paid(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(self.total_paid(conn)? > 0 && self.amount_due_now(conn)? > 0)
+    }
+
+    /// The amount still to be paid before the order is complete. Only the deposit is due up front
+    /// when the order is paid with a payment plan.
+    pub fn amount_due_now(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let total_required = match PaymentPlan::find_for_order(self.id, conn)? {
+            Some(payment_plan) => payment_plan.deposit_in_cents,
+            None => self.calculate_total(conn)?,
+        };
+        Ok(total_required - self.total_paid(conn)?)
+    }
+
     pub fn total_paid(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
         #[derive(QueryableByName)]
         struct ResultForSum {
@@ -2667,6 +3720,10 @@ impl Order {
 
         let order_items = self.order_items_in_invalid_state(conn)?;
         for item in order_items {
+            if item.item_type == OrderItemTypes::ResaleTickets || item.item_type == OrderItemTypes::Products {
+                self.destroy_item(item.id, conn)?;
+                continue;
+            }
             // Use calculated quantity as reserved may have been taken in the meantime
             let quantity = item.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&item, quantity as u32, Some(user_id), conn)?;
@@ -2864,6 +3921,10 @@ pub struct DisplayOrder {
     pub limited_tickets_remaining: Vec<TicketsRemaining>,
     pub total_in_cents: i64,
     pub total_refunded_in_cents: i64,
+    /// Completed payments towards the order, orders paid with a split payment stay pending payment
+    /// until this covers the total
+    pub amount_paid_in_cents: i64,
+    pub currency: String,
     pub user_id: Uuid,
     pub user: DisplayUser,
     pub order_number: String,
@@ -2910,6 +3971,19 @@ pub struct UpdateOrderItem {
     pub redemption_code: Option<String>,
 }
 
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct UpdatePackageOrderItem {
+    pub package_id: Uuid,
+    pub quantity: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct UpdateProductOrderItem {
+    pub product_variant_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub quantity: u32,
+}
+
 #[test]
 fn parse_order_number() {
     let id = Uuid::parse_str("01234567-1234-1234-1234-1234567890ab").unwrap();
@@ -12,6 +12,7 @@ use schema::{
     assets, event_users, events, fee_schedules, order_items, orders, organization_users, organizations, ticket_types,
     users, venues,
 };
+use serde_with::rust::double_option;
 use std::cmp;
 use std::collections::HashMap;
 use utils::encryption::*;
@@ -19,6 +20,7 @@ use utils::errors::*;
 use utils::pagination::Paginate;
 use utils::text;
 use uuid::Uuid;
+use validators::*;
 
 const DEFAULT_SETTLEMENT_TIMEZONE: &str = "America/Los_Angeles";
 
@@ -54,6 +56,11 @@ pub struct Organization {
     pub slug_id: Option<Uuid>,
     pub google_ads_conversion_id: Option<String>,
     pub google_ads_conversion_labels: Vec<String>,
+    pub currency: String,
+    pub payment_plan_min_total_in_cents: Option<i64>,
+    pub payment_plan_max_installments: i32,
+    pub payment_plan_grace_period_days: i32,
+    pub payment_plan_refund_policy: PaymentPlanRefundPolicies,
 }
 
 #[derive(Serialize)]
@@ -90,6 +97,8 @@ pub struct NewOrganization {
     pub globee_api_key: Option<String>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Serialize, Clone, Deserialize, Debug, PartialEq)]
@@ -109,6 +118,11 @@ impl NewOrganization {
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
         let mut updated_organisation = self;
+        if let Some(currency) = updated_organisation.currency.take() {
+            let currency = currency.trim().to_uppercase();
+            append_validation_error(Ok(()), "currency", validate_currency(&currency))?;
+            updated_organisation.currency = Some(currency);
+        }
         if encryption_key.len() > 0 {
             if let Some(key) = updated_organisation.sendgrid_api_key.clone() {
                 updated_organisation.sendgrid_api_key = Some(encrypt(&key, encryption_key)?);
@@ -214,6 +228,13 @@ pub struct OrganizationEditableAttributes {
     pub google_ads_conversion_id: Option<Option<String>>,
     #[serde(default)]
     pub google_ads_conversion_labels: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub payment_plan_min_total_in_cents: Option<Option<i64>>,
+    pub payment_plan_max_installments: Option<i32>,
+    pub payment_plan_grace_period_days: Option<i32>,
+    pub payment_plan_refund_policy: Option<PaymentPlanRefundPolicies>,
 }
 
 impl Organization {
@@ -372,6 +393,18 @@ impl Organization {
             }
         }
 
+        if let Some(currency) = attributes.currency.take() {
+            let currency = currency.trim().to_uppercase();
+            append_validation_error(Ok(()), "currency", validate_currency(&currency))?;
+            if currency != self.currency && self.has_sales(conn)? {
+                return DatabaseError::business_process_error(
+                    "Currency cannot be changed once the organization has sales",
+                );
+            }
+            attributes.currency = Some(currency);
+        }
+        Organization::validate_payment_plan_settings(&attributes)?;
+
         if attributes.timezone.is_some() && attributes.timezone != self.timezone {
             if let Some(settlement_job) = DomainAction::upcoming_domain_action(
                 Some(Tables::Organizations),
@@ -547,6 +580,49 @@ impl Organization {
         Ok(!results.data.is_empty())
     }
 
+    fn validate_payment_plan_settings(attributes: &OrganizationEditableAttributes) -> Result<(), DatabaseError> {
+        if let Some(Some(min_total_in_cents)) = attributes.payment_plan_min_total_in_cents {
+            if min_total_in_cents < 0 {
+                return DatabaseError::validation_error(
+                    "payment_plan_min_total_in_cents",
+                    "Payment plan minimum total cannot be negative",
+                );
+            }
+        }
+        if let Some(max_installments) = attributes.payment_plan_max_installments {
+            if max_installments < 1 || max_installments > 12 {
+                return DatabaseError::validation_error(
+                    "payment_plan_max_installments",
+                    "Payment plans must have between 1 and 12 installments",
+                );
+            }
+        }
+        if let Some(grace_period_days) = attributes.payment_plan_grace_period_days {
+            if grace_period_days < 1 {
+                return DatabaseError::validation_error(
+                    "payment_plan_grace_period_days",
+                    "Payment plan grace period must be at least 1 day",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether any order for the organization's events has gone past the cart stage.
+    pub fn has_sales(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            order_items::table
+                .inner_join(orders::table)
+                .inner_join(events::table)
+                .filter(events::organization_id.eq(self.id))
+                .filter(orders::status.ne(OrderStatus::Draft))
+                .select(order_items::id),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check organization sales")
+    }
+
     pub fn get_scopes_for_user(&self, user: &User, conn: &PgConnection) -> Result<Vec<Scopes>, DatabaseError> {
         Ok(scopes::get_scopes(self.get_roles_for_user(user, conn)?))
     }
@@ -1164,6 +1240,7 @@ impl Organization {
             phone: self.phone.clone(),
             timezone: self.timezone.clone(),
             slug: Slug::primary_slug(self.id, Tables::Organizations, conn)?.slug,
+            currency: self.currency.clone(),
         })
     }
 }
@@ -1180,4 +1257,5 @@ pub struct DisplayOrganization {
     pub phone: Option<String>,
     pub timezone: Option<String>,
     pub slug: String,
+    pub currency: String,
 }
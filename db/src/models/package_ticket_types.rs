@@ -0,0 +1,161 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, package_ticket_types, ticket_types};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Package)]
+#[belongs_to(TicketType)]
+#[table_name = "package_ticket_types"]
+pub struct PackageTicketType {
+    pub id: Uuid,
+    pub package_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub price_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplayPackageTicketType {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub event_id: Uuid,
+    pub event_name: String,
+    pub event_start: Option<NaiveDateTime>,
+    pub price_in_cents: i64,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "package_ticket_types"]
+pub struct NewPackageTicketType {
+    pub package_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+impl NewPackageTicketType {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PackageTicketType, DatabaseError> {
+        PackageTicketType::validate_price(self.price_in_cents)?;
+        diesel::insert_into(package_ticket_types::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add ticket type to package")
+    }
+}
+
+impl PackageTicketType {
+    pub fn create(package_id: Uuid, ticket_type_id: Uuid, price_in_cents: i64) -> NewPackageTicketType {
+        NewPackageTicketType {
+            package_id,
+            ticket_type_id,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PackageTicketType, DatabaseError> {
+        package_ticket_types::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading package ticket type")
+    }
+
+    pub fn find_for_package(package_id: Uuid, conn: &PgConnection) -> Result<Vec<PackageTicketType>, DatabaseError> {
+        package_ticket_types::table
+            .inner_join(ticket_types::table.inner_join(events::table))
+            .filter(package_ticket_types::package_id.eq(package_id))
+            .order_by(events::event_start)
+            .then_order_by(events::name)
+            .select(package_ticket_types::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading package ticket types")
+    }
+
+    pub fn find_for_package_for_display(
+        package_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayPackageTicketType>, DatabaseError> {
+        package_ticket_types::table
+            .inner_join(ticket_types::table.inner_join(events::table))
+            .filter(package_ticket_types::package_id.eq(package_id))
+            .order_by(events::event_start)
+            .then_order_by(events::name)
+            .select((
+                package_ticket_types::id,
+                ticket_types::id,
+                ticket_types::name,
+                events::id,
+                events::name,
+                events::event_start,
+                package_ticket_types::price_in_cents,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading package ticket types")
+    }
+
+    pub fn package(&self, conn: &PgConnection) -> Result<Package, DatabaseError> {
+        Package::find(self.package_id, conn)
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    /// Changes the share of the package price allocated to this event. Packages already sold keep
+    /// the allocation they were bought with.
+    pub fn update_price(
+        &self,
+        price_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PackageTicketType, DatabaseError> {
+        PackageTicketType::validate_price(price_in_cents)?;
+        let result: PackageTicketType = diesel::update(self)
+            .set((
+                package_ticket_types::price_in_cents.eq(price_in_cents),
+                package_ticket_types::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update package ticket type")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PackageUpdated,
+            "Package price allocation updated".to_string(),
+            Tables::Packages,
+            Some(self.package_id),
+            current_user_id,
+            Some(json!({ "ticket_type_id": self.ticket_type_id, "price_in_cents": price_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::PackageUpdated,
+            "Ticket type removed from package".to_string(),
+            Tables::Packages,
+            Some(self.package_id),
+            current_user_id,
+            Some(json!({ "ticket_type_id": self.ticket_type_id })),
+        )
+        .commit(conn)?;
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to remove ticket type from package")
+    }
+
+    fn validate_price(price_in_cents: i64) -> Result<(), DatabaseError> {
+        if price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::packages;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "packages"]
+pub struct Package {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayPackage {
+    #[serde(flatten)]
+    pub package: Package,
+    pub price_in_cents: i64,
+    pub ticket_types: Vec<DisplayPackageTicketType>,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "packages"]
+pub struct PackageEditableAttributes {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "packages"]
+pub struct NewPackage {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl NewPackage {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Package, DatabaseError> {
+        Package::validate_attributes(&self.name)?;
+        let result: Package = diesel::insert_into(packages::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create package")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PackageCreated,
+            "Package created".to_string(),
+            Tables::Packages,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl Package {
+    pub fn create(organization_id: Uuid, name: String, description: Option<String>) -> NewPackage {
+        NewPackage {
+            organization_id,
+            name,
+            description,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Package, DatabaseError> {
+        packages::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading package")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        active_only: bool,
+        conn: &PgConnection,
+    ) -> Result<Vec<Package>, DatabaseError> {
+        let mut query = packages::table
+            .filter(packages::organization_id.eq(organization_id))
+            .into_boxed();
+        if active_only {
+            query = query.filter(packages::active.eq(true));
+        }
+
+        query
+            .order_by(packages::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading packages")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<PackageTicketType>, DatabaseError> {
+        PackageTicketType::find_for_package(self.id, conn)
+    }
+
+    /// The package is sold for the sum of the prices allocated to each included event.
+    pub fn price_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self.ticket_types(conn)?.iter().map(|t| t.price_in_cents).sum())
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayPackage, DatabaseError> {
+        let ticket_types = PackageTicketType::find_for_package_for_display(self.id, conn)?;
+        Ok(DisplayPackage {
+            package: self.clone(),
+            price_in_cents: ticket_types.iter().map(|t| t.price_in_cents).sum(),
+            ticket_types,
+        })
+    }
+
+    /// Includes a ticket for the ticket type's event in the package. The price is the share of the
+    /// package price that is allocated to that event.
+    pub fn add_ticket_type(
+        &self,
+        ticket_type_id: Uuid,
+        price_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PackageTicketType, DatabaseError> {
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let event = ticket_type.event(conn)?;
+        if event.organization_id != self.organization_id {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type does not belong to this organization",
+            );
+        }
+        for package_ticket_type in self.ticket_types(conn)? {
+            if package_ticket_type.ticket_type(conn)?.event_id == event.id {
+                return DatabaseError::validation_error(
+                    "ticket_type_id",
+                    "Package already includes a ticket for this event",
+                );
+            }
+        }
+
+        let package_ticket_type = PackageTicketType::create(self.id, ticket_type.id, price_in_cents).commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PackageUpdated,
+            format!("{} added to package", event.name),
+            Tables::Packages,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "ticket_type_id": ticket_type.id, "event_id": event.id, "price_in_cents": price_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(package_ticket_type)
+    }
+
+    pub fn update(
+        &self,
+        attributes: PackageEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Package, DatabaseError> {
+        Package::validate_attributes(attributes.name.as_ref().unwrap_or(&self.name))?;
+
+        let result: Package = diesel::update(self)
+            .set((&attributes, packages::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update package")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PackageUpdated,
+            "Package updated".to_string(),
+            Tables::Packages,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_attributes(name: &str) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        Ok(())
+    }
+}
@@ -46,6 +46,13 @@ impl PaymentMethod {
         }
     }
 
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        payment_methods::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment method")
+    }
+
     pub fn find_default_for_user(user_id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
         payment_methods::table
             .filter(payment_methods::user_id.eq(user_id))
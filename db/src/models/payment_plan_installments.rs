@@ -0,0 +1,212 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::payment_plan_installments;
+use serde_json;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(PaymentPlan)]
+#[table_name = "payment_plan_installments"]
+pub struct PaymentPlanInstallment {
+    pub id: Uuid,
+    pub payment_plan_id: Uuid,
+    pub installment_number: i32,
+    pub amount_in_cents: i64,
+    pub due_at: NaiveDateTime,
+    pub status: PaymentPlanInstallmentStatus,
+    pub payment_id: Option<Uuid>,
+    pub failed_attempts: i32,
+    pub last_failure_reason: Option<String>,
+    pub grace_period_ends_at: Option<NaiveDateTime>,
+    pub paid_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "payment_plan_installments"]
+pub struct NewPaymentPlanInstallment {
+    pub payment_plan_id: Uuid,
+    pub installment_number: i32,
+    pub amount_in_cents: i64,
+    pub due_at: NaiveDateTime,
+}
+
+impl NewPaymentPlanInstallment {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PaymentPlanInstallment, DatabaseError> {
+        diesel::insert_into(payment_plan_installments::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create payment plan installment")
+    }
+}
+
+impl PaymentPlanInstallment {
+    pub fn create(
+        payment_plan_id: Uuid,
+        installment_number: i32,
+        amount_in_cents: i64,
+        due_at: NaiveDateTime,
+    ) -> NewPaymentPlanInstallment {
+        NewPaymentPlanInstallment {
+            payment_plan_id,
+            installment_number,
+            amount_in_cents,
+            due_at,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentPlanInstallment, DatabaseError> {
+        payment_plan_installments::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payment plan installment")
+    }
+
+    pub fn payment_plan(&self, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        PaymentPlan::find(self.payment_plan_id, conn)
+    }
+
+    /// Records the charge of this installment against the plan's order, completing the plan once every
+    /// installment has been paid.
+    pub fn mark_paid(
+        &self,
+        provider: PaymentProviders,
+        external_reference: String,
+        provider_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status == PaymentPlanInstallmentStatus::Paid {
+            return DatabaseError::business_process_error("Installment has already been paid");
+        }
+        let payment_plan = self.payment_plan(conn)?;
+        if payment_plan.status != PaymentPlanStatus::Active {
+            return DatabaseError::business_process_error("Payment plan is no longer active");
+        }
+
+        let order = payment_plan.order(conn)?;
+        let payment = Payment::create(
+            order.id,
+            None,
+            PaymentStatus::Completed,
+            PaymentMethods::CreditCard,
+            provider,
+            Some(external_reference),
+            self.amount_in_cents,
+            Some(provider_data),
+            None,
+            None,
+            order.currency.clone(),
+        )
+        .commit(None, conn)?;
+
+        diesel::update(self)
+            .set((
+                payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Paid),
+                payment_plan_installments::payment_id.eq(payment.id),
+                payment_plan_installments::paid_at.eq(dsl::now.nullable()),
+                payment_plan_installments::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not mark payment plan installment as paid",
+            )?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanInstallmentPaid,
+            "Payment plan installment paid".to_string(),
+            Tables::PaymentPlanInstallments,
+            Some(self.id),
+            None,
+            Some(json!({ "payment_id": payment.id, "amount_in_cents": self.amount_in_cents })),
+        )
+        .commit(conn)?;
+
+        payment_plan.complete_if_fully_paid(conn)?;
+
+        Ok(payment)
+    }
+
+    /// Records a failed charge. The first failure starts the plan's grace period and schedules the
+    /// `CancelPaymentPlan` domain action for when it ends. Every failure sends a dunning email and the
+    /// charge is retried daily until the grace period is over.
+    pub fn mark_failed(&self, reason: &str, conn: &PgConnection) -> Result<PaymentPlanInstallment, DatabaseError> {
+        if self.status == PaymentPlanInstallmentStatus::Paid {
+            return DatabaseError::business_process_error("Installment has already been paid");
+        }
+        let payment_plan = self.payment_plan(conn)?;
+        let now = Utc::now().naive_utc();
+        let grace_period_ends_at = self
+            .grace_period_ends_at
+            .unwrap_or(now + Duration::days(payment_plan.grace_period_days as i64));
+
+        let installment: PaymentPlanInstallment = diesel::update(self)
+            .set((
+                payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Failed),
+                payment_plan_installments::failed_attempts.eq(self.failed_attempts + 1),
+                payment_plan_installments::last_failure_reason.eq(reason),
+                payment_plan_installments::grace_period_ends_at.eq(grace_period_ends_at),
+                payment_plan_installments::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not mark payment plan installment as failed",
+            )?;
+
+        let domain_event = DomainEvent::create(
+            DomainEventTypes::PaymentPlanInstallmentFailed,
+            "Payment plan installment failed".to_string(),
+            Tables::PaymentPlanInstallments,
+            Some(self.id),
+            None,
+            Some(json!({ "reason": reason, "failed_attempts": installment.failed_attempts })),
+        )
+        .commit(conn)?;
+
+        DomainAction::create(
+            Some(domain_event.id),
+            DomainActionTypes::SendPaymentPlanDunningCommunication,
+            None,
+            json!({ "failed_attempts": installment.failed_attempts }),
+            Some(Tables::PaymentPlanInstallments),
+            Some(self.id),
+        )
+        .commit(conn)?;
+
+        let retry_at = now + Duration::days(1);
+        if retry_at < grace_period_ends_at {
+            let mut action = DomainAction::create(
+                Some(domain_event.id),
+                DomainActionTypes::ChargePaymentPlanInstallment,
+                None,
+                json!({}),
+                Some(Tables::PaymentPlanInstallments),
+                Some(self.id),
+            );
+            action.schedule_at(retry_at);
+            action.commit(conn)?;
+        }
+
+        if self.grace_period_ends_at.is_none() {
+            let mut action = DomainAction::create(
+                Some(domain_event.id),
+                DomainActionTypes::CancelPaymentPlan,
+                None,
+                json!({}),
+                Some(Tables::PaymentPlans),
+                Some(payment_plan.id),
+            );
+            action.schedule_at(grace_period_ends_at);
+            action.commit(conn)?;
+        }
+
+        Ok(installment)
+    }
+}
@@ -0,0 +1,338 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{order_items, payment_plan_installments, payment_plans, ticket_instances};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Days between the scheduled installments of a payment plan, the first is due this long after the deposit.
+pub const PAYMENT_PLAN_INSTALLMENT_INTERVAL_DAYS: i64 = 30;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Order)]
+#[belongs_to(User)]
+#[table_name = "payment_plans"]
+pub struct PaymentPlan {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub payment_method_id: Uuid,
+    pub total_in_cents: i64,
+    pub deposit_in_cents: i64,
+    pub grace_period_days: i32,
+    pub refund_policy: PaymentPlanRefundPolicies,
+    pub status: PaymentPlanStatus,
+    pub completed_at: Option<NaiveDateTime>,
+    pub cancelled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "payment_plans"]
+pub struct NewPaymentPlan {
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub payment_method_id: Uuid,
+    pub total_in_cents: i64,
+    pub deposit_in_cents: i64,
+    pub grace_period_days: i32,
+    pub refund_policy: PaymentPlanRefundPolicies,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayPaymentPlan {
+    #[serde(flatten)]
+    pub payment_plan: PaymentPlan,
+    pub amount_paid_in_cents: i64,
+    pub installments: Vec<PaymentPlanInstallment>,
+}
+
+impl PaymentPlan {
+    /// Splits the order's total into a deposit due now and `installment_count` equal installments charged
+    /// to the saved payment method every `PAYMENT_PLAN_INSTALLMENT_INTERVAL_DAYS`. Any remainder from the
+    /// split is added to the deposit. The last installment and its grace period have to end before the
+    /// first event on the order starts.
+    pub fn create_for_order(
+        order: &Order,
+        payment_method_id: Uuid,
+        installment_count: i32,
+        conn: &PgConnection,
+    ) -> Result<PaymentPlan, DatabaseError> {
+        if order.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Payment plans can only be added to orders awaiting payment");
+        }
+        if PaymentPlan::find_for_order(order.id, conn)?.is_some() {
+            return DatabaseError::business_process_error("Order already has a payment plan");
+        }
+        let payment_method = PaymentMethod::find(payment_method_id, conn)?;
+        if payment_method.user_id != order.user_id {
+            return DatabaseError::business_process_error("Payment method does not belong to the purchaser");
+        }
+        if order
+            .items(conn)?
+            .iter()
+            .any(|i| i.item_type == OrderItemTypes::ResaleTickets)
+        {
+            return DatabaseError::business_process_error("Resale tickets cannot be paid for with a payment plan");
+        }
+
+        let mut organizations = order.organizations(conn)?;
+        if organizations.len() != 1 {
+            return DatabaseError::business_process_error(
+                "Payment plans are only available for orders from a single organization",
+            );
+        }
+        let organization = organizations.remove(0);
+        let total_in_cents = order.calculate_total(conn)?;
+        match organization.payment_plan_min_total_in_cents {
+            Some(min_total_in_cents) if total_in_cents > 0 && total_in_cents >= min_total_in_cents => (),
+            _ => return DatabaseError::business_process_error("Payment plans are not available for this order"),
+        }
+        if installment_count < 1 || installment_count > organization.payment_plan_max_installments {
+            return DatabaseError::validation_error(
+                "installment_count",
+                "Number of installments is not available for this order",
+            );
+        }
+
+        let now = Utc::now().naive_utc();
+        let final_due_at = now + Duration::days(PAYMENT_PLAN_INSTALLMENT_INTERVAL_DAYS * installment_count as i64);
+        let grace_period = Duration::days(organization.payment_plan_grace_period_days as i64);
+        for event in order.events(conn)? {
+            if event
+                .event_start
+                .map(|s| final_due_at + grace_period >= s)
+                .unwrap_or(false)
+            {
+                return DatabaseError::validation_error(
+                    "installment_count",
+                    "Payment plan would not be paid in full before the event starts",
+                );
+            }
+        }
+
+        let installment_in_cents = total_in_cents / (installment_count as i64 + 1);
+        if installment_in_cents <= 0 {
+            return DatabaseError::business_process_error("Order total is too low for a payment plan");
+        }
+        let new_payment_plan = NewPaymentPlan {
+            order_id: order.id,
+            user_id: order.user_id,
+            payment_method_id,
+            total_in_cents,
+            deposit_in_cents: total_in_cents - installment_in_cents * installment_count as i64,
+            grace_period_days: organization.payment_plan_grace_period_days,
+            refund_policy: organization.payment_plan_refund_policy,
+        };
+        let payment_plan: PaymentPlan = diesel::insert_into(payment_plans::table)
+            .values(&new_payment_plan)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create payment plan")?;
+
+        for installment_number in 1..=installment_count {
+            let due_at = now + Duration::days(PAYMENT_PLAN_INSTALLMENT_INTERVAL_DAYS * installment_number as i64);
+            let installment =
+                PaymentPlanInstallment::create(payment_plan.id, installment_number, installment_in_cents, due_at)
+                    .commit(conn)?;
+
+            let mut action = DomainAction::create(
+                None,
+                DomainActionTypes::ChargePaymentPlanInstallment,
+                None,
+                json!({}),
+                Some(Tables::PaymentPlanInstallments),
+                Some(installment.id),
+            );
+            action.schedule_at(due_at);
+            action.commit(conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanCreated,
+            "Payment plan created".to_string(),
+            Tables::PaymentPlans,
+            Some(payment_plan.id),
+            Some(order.user_id),
+            Some(json!({
+                "order_id": order.id,
+                "deposit_in_cents": payment_plan.deposit_in_cents,
+                "installment_count": installment_count,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(payment_plan)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        payment_plans::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payment plan")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Option<PaymentPlan>, DatabaseError> {
+        payment_plans::table
+            .filter(payment_plans::order_id.eq(order_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading payment plan")
+    }
+
+    /// Tickets on an order with an active payment plan are locked until the plan is paid in full.
+    pub fn any_unpaid_for_tickets(ticket_instance_ids: &[Uuid], conn: &PgConnection) -> Result<bool, DatabaseError> {
+        dsl::select(dsl::exists(
+            ticket_instances::table
+                .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
+                .inner_join(payment_plans::table.on(payment_plans::order_id.eq(order_items::order_id)))
+                .filter(ticket_instances::id.eq_any(ticket_instance_ids))
+                .filter(payment_plans::status.eq(PaymentPlanStatus::Active))
+                .select(ticket_instances::id),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check for unpaid payment plans")
+    }
+
+    pub fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.order_id, conn)
+    }
+
+    pub fn installments(&self, conn: &PgConnection) -> Result<Vec<PaymentPlanInstallment>, DatabaseError> {
+        payment_plan_installments::table
+            .filter(payment_plan_installments::payment_plan_id.eq(self.id))
+            .order_by(payment_plan_installments::installment_number)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payment plan installments")
+    }
+
+    pub fn for_display(self, conn: &PgConnection) -> Result<DisplayPaymentPlan, DatabaseError> {
+        let amount_paid_in_cents = self.order(conn)?.total_paid(conn)?;
+        let installments = self.installments(conn)?;
+        Ok(DisplayPaymentPlan {
+            payment_plan: self,
+            amount_paid_in_cents,
+            installments,
+        })
+    }
+
+    /// An active plan is overdue once an installment is still failing after its grace period.
+    pub fn is_overdue(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        Ok(self.status == PaymentPlanStatus::Active
+            && self.installments(conn)?.iter().any(|i| {
+                i.status == PaymentPlanInstallmentStatus::Failed
+                    && i.grace_period_ends_at.map(|g| g <= now).unwrap_or(false)
+            }))
+    }
+
+    /// The part of `amount_paid_in_cents` returned to the purchaser when the plan is cancelled.
+    pub fn refund_due(&self, amount_paid_in_cents: i64) -> i64 {
+        match self.refund_policy {
+            PaymentPlanRefundPolicies::FullRefund => amount_paid_in_cents,
+            PaymentPlanRefundPolicies::RetainDeposit => cmp::max(amount_paid_in_cents - self.deposit_in_cents, 0),
+            PaymentPlanRefundPolicies::NoRefund => 0,
+        }
+    }
+
+    pub(crate) fn complete_if_fully_paid(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.status != PaymentPlanStatus::Active
+            || self
+                .installments(conn)?
+                .iter()
+                .any(|i| i.status != PaymentPlanInstallmentStatus::Paid)
+        {
+            return Ok(());
+        }
+
+        diesel::update(self)
+            .set((
+                payment_plans::status.eq(PaymentPlanStatus::Completed),
+                payment_plans::completed_at.eq(dsl::now.nullable()),
+                payment_plans::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not complete payment plan")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanCompleted,
+            "Payment plan paid in full".to_string(),
+            Tables::PaymentPlans,
+            Some(self.id),
+            None,
+            Some(json!({ "order_id": self.order_id })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Cancels the plan and refunds the tickets and products on its order. The amount due back to the
+    /// purchaser follows the plan's refund policy, the part of the amount paid that is kept is recorded
+    /// as retained on the refund. Only the order records are refunded here, returning the tokens and
+    /// the money is left to the caller.
+    pub fn cancel(&self, conn: &PgConnection) -> Result<(Order, Refund, i64, Vec<TicketInstance>), DatabaseError> {
+        if self.status != PaymentPlanStatus::Active {
+            return DatabaseError::business_process_error("Only active payment plans can be cancelled");
+        }
+
+        let mut order = self.order(conn)?;
+        let amount_paid_in_cents = order.total_paid(conn)?;
+        let (refund_items, refunded_tickets) = order.outstanding_refund_items(conn)?;
+
+        let (mut refund, _) = order.refund(
+            &refund_items,
+            self.user_id,
+            Some("Payment plan cancelled".to_string()),
+            false,
+            conn,
+        )?;
+
+        diesel::update(
+            payment_plan_installments::table
+                .filter(payment_plan_installments::payment_plan_id.eq(self.id))
+                .filter(payment_plan_installments::status.ne(PaymentPlanInstallmentStatus::Paid)),
+        )
+        .set((
+            payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Cancelled),
+            payment_plan_installments::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not cancel payment plan installments")?;
+
+        diesel::update(self)
+            .set((
+                payment_plans::status.eq(PaymentPlanStatus::Cancelled),
+                payment_plans::cancelled_at.eq(dsl::now.nullable()),
+                payment_plans::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not cancel payment plan")?;
+
+        let refund_due = self.refund_due(amount_paid_in_cents);
+        let retained_in_cents = amount_paid_in_cents - refund_due;
+        if retained_in_cents > 0 {
+            refund = refund.retain(retained_in_cents, conn)?;
+        }
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanCancelled,
+            "Payment plan cancelled".to_string(),
+            Tables::PaymentPlans,
+            Some(self.id),
+            None,
+            Some(json!({
+                "order_id": self.order_id,
+                "refund_id": refund.id,
+                "refund_due_in_cents": refund_due,
+                "retained_in_cents": retained_in_cents,
+            })),
+        )
+        .commit(conn)?;
+
+        Ok((order, refund, refund_due, refunded_tickets))
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::payment_provider_events;
+use serde_json;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Raw notification received from a payment provider, e.g. a Stripe webhook event.
+#[derive(Clone, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "payment_provider_events"]
+pub struct PaymentProviderEvent {
+    pub id: Uuid,
+    pub provider: PaymentProviders,
+    pub external_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub processed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "payment_provider_events"]
+pub struct NewPaymentProviderEvent {
+    pub provider: PaymentProviders,
+    pub external_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
+
+impl NewPaymentProviderEvent {
+    /// Records the event, returning `None` if an event with the same external id has already been
+    /// received from this provider. Providers retry deliveries, so duplicates are expected.
+    pub fn commit(&self, conn: &PgConnection) -> Result<Option<PaymentProviderEvent>, DatabaseError> {
+        diesel::insert_into(payment_provider_events::table)
+            .values(self)
+            .on_conflict_do_nothing()
+            .get_result(conn)
+            .optional()
+            .to_db_error(ErrorCode::InsertError, "Could not create payment provider event")
+    }
+}
+
+impl PaymentProviderEvent {
+    pub fn create(
+        provider: PaymentProviders,
+        external_id: String,
+        event_type: String,
+        payload: serde_json::Value,
+    ) -> NewPaymentProviderEvent {
+        NewPaymentProviderEvent {
+            provider,
+            external_id,
+            event_type,
+            payload,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentProviderEvent, DatabaseError> {
+        payment_provider_events::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading payment provider event")
+    }
+
+    pub fn find_by_external_id(
+        provider: PaymentProviders,
+        external_id: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<PaymentProviderEvent>, DatabaseError> {
+        payment_provider_events::table
+            .filter(payment_provider_events::provider.eq(provider))
+            .filter(payment_provider_events::external_id.eq(external_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading payment provider event")
+    }
+
+    pub fn mark_processed(&self, conn: &PgConnection) -> Result<PaymentProviderEvent, DatabaseError> {
+        diesel::update(self)
+            .set((
+                payment_provider_events::processed_at.eq(dsl::now.nullable()),
+                payment_provider_events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not mark payment provider event as processed",
+            )
+    }
+}
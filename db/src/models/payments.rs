@@ -27,6 +27,11 @@ pub struct Payment {
     updated_at: NaiveDateTime,
     pub url_nonce: Option<String>,
     pub refund_id: Option<Uuid>,
+    pub currency: String,
+    /// For refunds, the original payment the money was returned from
+    pub refunded_payment_id: Option<Uuid>,
+    /// Set once the card holder disputes the payment, which stays completed while it's disputed
+    pub dispute_status: Option<PaymentDisputeStatus>,
 }
 
 impl Payment {
@@ -41,6 +46,7 @@ impl Payment {
         raw_data: Option<serde_json::Value>,
         url_nonce: Option<String>,
         refund_id: Option<Uuid>,
+        currency: String,
     ) -> NewPayment {
         NewPayment {
             order_id,
@@ -53,6 +59,8 @@ impl Payment {
             raw_data,
             url_nonce,
             refund_id,
+            currency,
+            refunded_payment_id: None,
         }
     }
 
@@ -96,6 +104,23 @@ impl Payment {
             .expect_single()
     }
 
+    /// Finds the payment taken by a provider under the given reference, ignoring any refunds that
+    /// were logged against it.
+    pub fn find_by_provider_reference(
+        provider: PaymentProviders,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Payment>, DatabaseError> {
+        payments::table
+            .filter(payments::provider.eq(provider))
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::amount.gt(0))
+            .order_by(payments::created_at)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not find payment")
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
@@ -104,18 +129,51 @@ impl Payment {
         refund_data: Option<serde_json::Value>,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
-        let refund_payment = Payment::create(
-            self.order_id,
-            self.created_by,
-            PaymentStatus::Refunded,
+        self.log_refund_as(
+            current_user_id,
+            refund,
+            refund_amount,
             self.payment_method,
-            self.provider.clone(),
-            self.external_reference.clone(),
-            -refund_amount,
-            refund_data.clone(),
-            None,
-            Some(refund.id),
+            self.provider,
+            refund_data,
+            conn,
         )
+    }
+
+    /// Logs part of a refund of this payment that was returned some other way than the payment
+    /// itself, e.g. as store credit. The refund still counts against this payment's refundable
+    /// balance but is reported under the method it was actually returned with.
+    pub fn log_refund_as(
+        &self,
+        current_user_id: Uuid,
+        refund: &Refund,
+        refund_amount: i64,
+        payment_method: PaymentMethods,
+        provider: PaymentProviders,
+        refund_data: Option<serde_json::Value>,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        let external_reference = if payment_method == self.payment_method && provider == self.provider {
+            self.external_reference.clone()
+        } else {
+            None
+        };
+        let refund_payment = NewPayment {
+            refunded_payment_id: Some(self.id),
+            ..Payment::create(
+                self.order_id,
+                self.created_by,
+                PaymentStatus::Refunded,
+                payment_method,
+                provider,
+                external_reference,
+                -refund_amount,
+                refund_data.clone(),
+                None,
+                Some(refund.id),
+                self.currency.clone(),
+            )
+        }
         .commit(Some(current_user_id), conn)?;
 
         DomainEvent::create(
@@ -130,6 +188,179 @@ impl Payment {
         Ok(refund_payment)
     }
 
+    /// Records refunds issued directly with the provider (e.g. from the Stripe dashboard), given the
+    /// total the provider reports as refunded for this payment. Amounts already logged against the
+    /// payment are skipped so repeated notifications have no effect. If nothing remains paid on the
+    /// order its outstanding tickets and products are refunded as well, otherwise only the amount
+    /// is recorded. Returns the refund along with any tickets it refunded.
+    pub fn reconcile_provider_refund(
+        &self,
+        amount_refunded: i64,
+        refund_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<(Refund, Vec<TicketInstance>)>, DatabaseError> {
+        let amount_logged: i64 = payments::table
+            .filter(payments::refunded_payment_id.eq(self.id))
+            .filter(payments::provider.eq(self.provider))
+            .select(payments::amount)
+            .load::<i64>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load refunds for payment")?
+            .iter()
+            .map(|amount| -amount)
+            .sum();
+        let amount_to_log = amount_refunded - amount_logged;
+        if amount_to_log <= 0 {
+            return Ok(None);
+        }
+
+        let mut order = self.order(conn)?;
+        // Refunds issued with the provider have no user attached, so they are recorded for the purchaser
+        let user_id = order.on_behalf_of_user_id.unwrap_or(order.user_id);
+        let reason = Some(format!("Refunded with {}", self.provider));
+        let amount_remaining: i64 = order
+            .refundable_payments(conn)?
+            .iter()
+            .map(|(_, balance)| balance)
+            .sum();
+        let (refund, tickets) = if amount_to_log >= amount_remaining {
+            let (refund_items, tickets) = order.outstanding_refund_items(conn)?;
+            let (refund, _) = order.refund(&refund_items, user_id, reason, true, conn)?;
+            (refund, tickets)
+        } else {
+            (
+                Refund::create(order.id, user_id, reason, true).commit(conn)?,
+                Vec::new(),
+            )
+        };
+        self.log_refund(user_id, &refund, amount_to_log, Some(refund_data), conn)?;
+
+        Ok(Some((refund, tickets)))
+    }
+
+    /// Marks the payment as disputed by the card holder and deducts the disputed amount from the
+    /// organization's open settlement. The payment stays completed so the order is still paid, but it
+    /// can't be refunded while disputed. Returns the chargeback adjustment, or `None` if there is no
+    /// open settlement it could be applied to.
+    pub fn mark_disputed(
+        &self,
+        amount: i64,
+        dispute_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        if self.status != PaymentStatus::Completed || self.dispute_status.is_some() {
+            return DatabaseError::business_process_error("Only completed payments can be disputed");
+        }
+        self.update_dispute_status(PaymentDisputeStatus::Open, conn)?;
+        DomainEvent::create(
+            DomainEventTypes::PaymentDisputed,
+            "Payment was disputed".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            None,
+            Some(dispute_data),
+        )
+        .commit(conn)?;
+
+        self.adjust_settlement(
+            SettlementAdjustmentTypes::Chargeback,
+            amount,
+            format!("Chargeback for order {}", self.order_id),
+            conn,
+        )
+    }
+
+    /// Records the outcome of a dispute. A won dispute credits the chargeback back to the
+    /// organization, a lost one leaves the payment charged back and no longer refundable.
+    pub fn close_dispute(
+        &self,
+        won: bool,
+        amount: i64,
+        dispute_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        if self.status != PaymentStatus::Completed
+            || (self.dispute_status.is_some() && self.dispute_status != Some(PaymentDisputeStatus::Open))
+        {
+            return DatabaseError::business_process_error("Payment dispute has already been closed");
+        }
+        self.update_dispute_status(
+            if won {
+                PaymentDisputeStatus::Won
+            } else {
+                PaymentDisputeStatus::Lost
+            },
+            conn,
+        )?;
+        DomainEvent::create(
+            DomainEventTypes::PaymentDisputeClosed,
+            "Payment dispute was closed".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            None,
+            Some(dispute_data),
+        )
+        .commit(conn)?;
+
+        match (won, self.dispute_status) {
+            (true, Some(PaymentDisputeStatus::Open)) => self.adjust_settlement(
+                SettlementAdjustmentTypes::ManualCredit,
+                amount,
+                format!("Chargeback reversed for order {}", self.order_id),
+                conn,
+            ),
+            // The dispute was not recorded when it was opened
+            (false, None) => self.adjust_settlement(
+                SettlementAdjustmentTypes::Chargeback,
+                amount,
+                format!("Chargeback for order {}", self.order_id),
+                conn,
+            ),
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether the card holder has an open or lost dispute against the payment, in which case the
+    /// money can't be refunded
+    pub fn is_disputed(&self) -> bool {
+        self.dispute_status == Some(PaymentDisputeStatus::Open)
+            || self.dispute_status == Some(PaymentDisputeStatus::Lost)
+    }
+
+    fn update_dispute_status(
+        &self,
+        dispute_status: PaymentDisputeStatus,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        diesel::update(payments::table.filter(payments::id.eq(self.id)))
+            .set((
+                payments::dispute_status.eq(dispute_status),
+                payments::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not change the dispute status of payment")?;
+        Ok(())
+    }
+
+    fn adjust_settlement(
+        &self,
+        settlement_adjustment_type: SettlementAdjustmentTypes,
+        amount: i64,
+        note: String,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        let mut organizations = self.order(conn)?.organizations(conn)?;
+        if organizations.len() != 1 {
+            return Ok(None);
+        }
+        match Settlement::find_open_for_organization(organizations.remove(0).id, conn)? {
+            Some(settlement) => Ok(Some(
+                SettlementAdjustment::create(settlement.id, settlement_adjustment_type, Some(note), amount)
+                    .commit(conn)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub fn add_ipn(
         &self,
         new_status: PaymentStatus,
@@ -267,6 +498,42 @@ impl Payment {
         }
     }
 
+    /// Returns a store credit payment to the purchaser's credit when the rest of the order could
+    /// not be charged, cancelling the payment.
+    pub fn reverse_store_credit(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.payment_method != PaymentMethods::StoreCredit || self.status != PaymentStatus::Completed {
+            return DatabaseError::business_process_error("Only completed store credit payments can be reversed");
+        }
+        let order = self.order(conn)?;
+        NewStoreCreditTransaction {
+            order_id: Some(self.order_id),
+            payment_id: Some(self.id),
+            ..StoreCreditTransaction::create(
+                order.on_behalf_of_user_id.unwrap_or(order.user_id),
+                self.amount,
+                self.currency.clone(),
+                StoreCreditTransactionTypes::Refund,
+                current_user_id,
+            )
+        }
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentCancelled,
+            "Store credit payment was reversed".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+        self.update_status(PaymentStatus::Cancelled, current_user_id, conn)
+    }
+
     fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
         use schema::*;
         orders::table
@@ -289,6 +556,8 @@ pub struct NewPayment {
     raw_data: Option<serde_json::Value>,
     url_nonce: Option<String>,
     refund_id: Option<Uuid>,
+    currency: String,
+    refunded_payment_id: Option<Uuid>,
 }
 
 impl NewPayment {
@@ -0,0 +1,301 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use rand::{thread_rng, Rng};
+use schema::presale_lotteries;
+use std::cmp;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Length of the access codes issued to lottery winners.
+pub const PRESALE_LOTTERY_CODE_LENGTH: usize = 10;
+
+/// A registration window for a presale. Once registration closes the `DrawPresaleLottery` domain
+/// action draws the winners, each of whom receives a single use access code for the lottery's
+/// ticket types.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[table_name = "presale_lotteries"]
+pub struct PresaleLottery {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub ticket_type_ids: Vec<Uuid>,
+    pub registration_start: NaiveDateTime,
+    pub registration_end: NaiveDateTime,
+    pub draw_at: NaiveDateTime,
+    pub code_expires_at: NaiveDateTime,
+    pub winner_count: i32,
+    pub tickets_per_winner: i32,
+    pub interaction_weight: i32,
+    pub max_weight: i32,
+    pub status: PresaleLotteryStatus,
+    pub drawn_at: Option<NaiveDateTime>,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "presale_lotteries"]
+pub struct NewPresaleLottery {
+    pub event_id: Uuid,
+    pub name: String,
+    pub ticket_type_ids: Vec<Uuid>,
+    pub registration_start: NaiveDateTime,
+    pub registration_end: NaiveDateTime,
+    pub draw_at: NaiveDateTime,
+    pub code_expires_at: NaiveDateTime,
+    pub winner_count: i32,
+    pub tickets_per_winner: i32,
+    pub interaction_weight: i32,
+    pub max_weight: i32,
+    pub created_by: Uuid,
+}
+
+impl NewPresaleLottery {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PresaleLottery, DatabaseError> {
+        self.validate_record(conn)?;
+        let result: PresaleLottery = diesel::insert_into(presale_lotteries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create presale lottery")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PresaleLotteryCreated,
+            format!("Presale lottery '{}' created", &result.name),
+            Tables::PresaleLotteries,
+            Some(result.id),
+            Some(self.created_by),
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::DrawPresaleLottery,
+            None,
+            json!({}),
+            Some(Tables::PresaleLotteries),
+            Some(result.id),
+        );
+        action.schedule_at(result.draw_at);
+        action.commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+        if self.winner_count <= 0 {
+            return DatabaseError::validation_error("winner_count", "Winner count must be at least 1");
+        }
+        if self.tickets_per_winner <= 0 {
+            return DatabaseError::validation_error("tickets_per_winner", "Tickets per winner must be at least 1");
+        }
+        if self.interaction_weight < 0 || self.max_weight < 1 {
+            return DatabaseError::validation_error("max_weight", "Weights must be positive");
+        }
+        if self.registration_end <= self.registration_start {
+            return DatabaseError::validation_error("registration_end", "Registration must end after it starts");
+        }
+        if self.draw_at < self.registration_end {
+            return DatabaseError::validation_error("draw_at", "Draw must happen after registration ends");
+        }
+        if self.code_expires_at <= self.draw_at {
+            return DatabaseError::validation_error("code_expires_at", "Codes must expire after the draw");
+        }
+        if self.ticket_type_ids.is_empty() {
+            return DatabaseError::validation_error("ticket_type_ids", "At least one ticket type is required");
+        }
+        for ticket_type_id in &self.ticket_type_ids {
+            if TicketType::find(*ticket_type_id, conn)?.event_id != self.event_id {
+                return DatabaseError::validation_error(
+                    "ticket_type_ids",
+                    "Ticket types must belong to the lottery's event",
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PresaleLottery {
+    pub fn create(
+        event_id: Uuid,
+        name: String,
+        ticket_type_ids: Vec<Uuid>,
+        registration_start: NaiveDateTime,
+        registration_end: NaiveDateTime,
+        draw_at: NaiveDateTime,
+        code_expires_at: NaiveDateTime,
+        winner_count: i32,
+        tickets_per_winner: i32,
+        interaction_weight: i32,
+        max_weight: i32,
+        created_by: Uuid,
+    ) -> NewPresaleLottery {
+        NewPresaleLottery {
+            event_id,
+            name,
+            ticket_type_ids,
+            registration_start,
+            registration_end,
+            draw_at,
+            code_expires_at,
+            winner_count,
+            tickets_per_winner,
+            interaction_weight,
+            max_weight,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PresaleLottery, DatabaseError> {
+        presale_lotteries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<PresaleLottery>, DatabaseError> {
+        presale_lotteries::table
+            .filter(presale_lotteries::event_id.eq(event_id))
+            .order_by(presale_lotteries::registration_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lotteries")
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.event_id, conn)
+    }
+
+    pub fn entries(&self, conn: &PgConnection) -> Result<Vec<PresaleLotteryEntry>, DatabaseError> {
+        PresaleLotteryEntry::find_for_presale_lottery(self.id, conn)
+    }
+
+    pub fn is_registration_open(&self) -> bool {
+        let now = Utc::now().naive_utc();
+        self.status == PresaleLotteryStatus::Open && self.registration_start <= now && now < self.registration_end
+    }
+
+    /// Registers the user for the draw. Registering also records the user's interest in the event.
+    pub fn register(&self, user_id: Uuid, conn: &PgConnection) -> Result<PresaleLotteryEntry, DatabaseError> {
+        if !self.is_registration_open() {
+            return DatabaseError::business_process_error("Registration for this presale is not open");
+        }
+        if PresaleLotteryEntry::find_for_user(self.id, user_id, conn)?.is_some() {
+            return DatabaseError::business_process_error("User is already registered for this presale");
+        }
+
+        let entry = PresaleLotteryEntry::create(self.id, user_id).commit(conn)?;
+        if !EventInterest::user_interest(self.event_id, user_id, conn)? {
+            EventInterest::create(self.event_id, user_id).commit(conn)?;
+        }
+
+        Ok(entry)
+    }
+
+    /// The number of chances an entry gets in the draw. Fans with more interactions with the
+    /// organization get more chances, up to the lottery's maximum weight.
+    pub fn weight_for(&self, interaction_count: i64) -> i32 {
+        let weight = 1 + interaction_count.saturating_mul(self.interaction_weight as i64);
+        cmp::min(weight, self.max_weight as i64).max(1) as i32
+    }
+
+    /// Draws the winners at random, weighted by each entrant's interactions with the organization.
+    /// Winners are issued their own access code, results are sent by the `SendPresaleLotteryResults`
+    /// domain action.
+    pub fn draw(&self, conn: &PgConnection) -> Result<Vec<PresaleLotteryEntry>, DatabaseError> {
+        // Locked so that a manual draw and the scheduled draw cannot both issue winner codes
+        let presale_lottery: PresaleLottery = presale_lotteries::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery")?;
+        if presale_lottery.status != PresaleLotteryStatus::Open {
+            return DatabaseError::business_process_error("Presale lottery has already been drawn");
+        }
+        if self.registration_end > Utc::now().naive_utc() {
+            return DatabaseError::business_process_error("Presale lottery cannot be drawn before registration ends");
+        }
+
+        let event = self.event(conn)?;
+        let mut rng = thread_rng();
+        let mut keyed_entries = Vec::new();
+        for entry in self.entries(conn)? {
+            let interaction_count =
+                OrganizationInteraction::find_by_organization_user(event.organization_id, entry.user_id, conn)
+                    .optional()?
+                    .map(|i| i.interaction_count)
+                    .unwrap_or(0);
+            let weight = self.weight_for(interaction_count);
+            // Weighted sampling without replacement, the entries with the highest keys win
+            let key = rng.gen::<f64>().powf(1.0 / weight as f64);
+            keyed_entries.push((key, weight, entry));
+        }
+        keyed_entries.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(cmp::Ordering::Equal));
+
+        let mut result = Vec::new();
+        let mut winner_ids = Vec::new();
+        for (index, (_, weight, entry)) in keyed_entries.into_iter().enumerate() {
+            if index < self.winner_count as usize {
+                let code = Code::create(
+                    format!("{} {}", &self.name, entry.id),
+                    self.event_id,
+                    CodeTypes::Access,
+                    random_alpha_string(PRESALE_LOTTERY_CODE_LENGTH).to_uppercase(),
+                    self.tickets_per_winner as u32,
+                    None,
+                    None,
+                    Utc::now().naive_utc(),
+                    self.code_expires_at,
+                    Some(self.tickets_per_winner as u32),
+                )
+                .commit(None, conn)?;
+                code.update_ticket_types(self.ticket_type_ids.clone(), conn)?;
+                winner_ids.push(entry.user_id);
+                result.push(entry.win(weight, &code, conn)?);
+            } else {
+                result.push(entry.lose(weight, conn)?);
+            }
+        }
+
+        diesel::update(self)
+            .set((
+                presale_lotteries::status.eq(PresaleLotteryStatus::Drawn),
+                presale_lotteries::drawn_at.eq(dsl::now.nullable()),
+                presale_lotteries::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update presale lottery")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PresaleLotteryDrawn,
+            format!("Presale lottery '{}' drawn", &self.name),
+            Tables::PresaleLotteries,
+            Some(self.id),
+            None,
+            Some(json!({ "entries": result.len(), "winner_ids": winner_ids })),
+        )
+        .commit(conn)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendPresaleLotteryResults,
+            None,
+            json!({}),
+            Some(Tables::PresaleLotteries),
+            Some(self.id),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
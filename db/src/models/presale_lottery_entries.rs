@@ -0,0 +1,167 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::presale_lottery_entries;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(PresaleLottery)]
+#[belongs_to(User)]
+#[table_name = "presale_lottery_entries"]
+pub struct PresaleLotteryEntry {
+    pub id: Uuid,
+    pub presale_lottery_id: Uuid,
+    pub user_id: Uuid,
+    pub status: PresaleLotteryEntryStatus,
+    pub weight: Option<i32>,
+    pub code_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "presale_lottery_entries"]
+pub struct NewPresaleLotteryEntry {
+    pub presale_lottery_id: Uuid,
+    pub user_id: Uuid,
+}
+
+impl NewPresaleLotteryEntry {
+    pub fn commit(&self, conn: &PgConnection) -> Result<PresaleLotteryEntry, DatabaseError> {
+        let result: PresaleLotteryEntry = diesel::insert_into(presale_lottery_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not register for presale lottery")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PresaleLotteryEntryCreated,
+            "User registered for presale lottery".to_string(),
+            Tables::PresaleLotteryEntries,
+            Some(result.id),
+            Some(self.user_id),
+            Some(json!({ "presale_lottery_id": self.presale_lottery_id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl PresaleLotteryEntry {
+    pub fn create(presale_lottery_id: Uuid, user_id: Uuid) -> NewPresaleLotteryEntry {
+        NewPresaleLotteryEntry {
+            presale_lottery_id,
+            user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PresaleLotteryEntry, DatabaseError> {
+        presale_lottery_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery entry")
+    }
+
+    pub fn find_for_presale_lottery(
+        presale_lottery_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<PresaleLotteryEntry>, DatabaseError> {
+        presale_lottery_entries::table
+            .filter(presale_lottery_entries::presale_lottery_id.eq(presale_lottery_id))
+            .order_by(presale_lottery_entries::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery entries")
+    }
+
+    pub fn find_for_user(
+        presale_lottery_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<PresaleLotteryEntry>, DatabaseError> {
+        presale_lottery_entries::table
+            .filter(presale_lottery_entries::presale_lottery_id.eq(presale_lottery_id))
+            .filter(presale_lottery_entries::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery entry")
+    }
+
+    pub fn find_by_code_id(code_id: Uuid, conn: &PgConnection) -> Result<Option<PresaleLotteryEntry>, DatabaseError> {
+        presale_lottery_entries::table
+            .filter(presale_lottery_entries::code_id.eq(code_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading presale lottery entry")
+    }
+
+    pub fn presale_lottery(&self, conn: &PgConnection) -> Result<PresaleLottery, DatabaseError> {
+        PresaleLottery::find(self.presale_lottery_id, conn)
+    }
+
+    pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        User::find(self.user_id, conn)
+    }
+
+    pub fn code(&self, conn: &PgConnection) -> Result<Option<Code>, DatabaseError> {
+        match self.code_id {
+            Some(code_id) => Ok(Some(Code::find(code_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn win(
+        &self,
+        weight: i32,
+        code: &Code,
+        conn: &PgConnection,
+    ) -> Result<PresaleLotteryEntry, DatabaseError> {
+        let result = self.set_result(PresaleLotteryEntryStatus::Won, weight, Some(code.id), conn)?;
+        DomainEvent::create(
+            DomainEventTypes::PresaleLotteryEntryWon,
+            "Presale lottery entry won".to_string(),
+            Tables::PresaleLotteryEntries,
+            Some(self.id),
+            None,
+            Some(json!({ "weight": weight, "code_id": code.id })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub(crate) fn lose(&self, weight: i32, conn: &PgConnection) -> Result<PresaleLotteryEntry, DatabaseError> {
+        let result = self.set_result(PresaleLotteryEntryStatus::Lost, weight, None, conn)?;
+        DomainEvent::create(
+            DomainEventTypes::PresaleLotteryEntryLost,
+            "Presale lottery entry lost".to_string(),
+            Tables::PresaleLotteryEntries,
+            Some(self.id),
+            None,
+            Some(json!({ "weight": weight })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn set_result(
+        &self,
+        status: PresaleLotteryEntryStatus,
+        weight: i32,
+        code_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PresaleLotteryEntry, DatabaseError> {
+        diesel::update(self)
+            .set((
+                presale_lottery_entries::status.eq(status),
+                presale_lottery_entries::weight.eq(Some(weight)),
+                presale_lottery_entries::code_id.eq(code_id),
+                presale_lottery_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update presale lottery entry")
+    }
+}
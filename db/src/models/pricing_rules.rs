@@ -0,0 +1,397 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::analytics::PageView;
+use models::*;
+use schema::{pricing_rules, ticket_types};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A rule adjusting the online price of a ticket type while its metric meets the threshold. The
+/// adjusted price never leaves the floor and ceiling set on the ticket type's rules.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[table_name = "pricing_rules"]
+pub struct PricingRule {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub name: String,
+    pub rule_type: PricingRuleTypes,
+    pub threshold: i64,
+    pub adjustment_in_cents: i64,
+    pub floor_price_in_cents: i64,
+    pub ceiling_price_in_cents: i64,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "pricing_rules"]
+pub struct PricingRuleEditableAttributes {
+    pub name: Option<String>,
+    pub rule_type: Option<PricingRuleTypes>,
+    pub threshold: Option<i64>,
+    pub adjustment_in_cents: Option<i64>,
+    pub floor_price_in_cents: Option<i64>,
+    pub ceiling_price_in_cents: Option<i64>,
+    pub active: Option<bool>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "pricing_rules"]
+pub struct NewPricingRule {
+    pub ticket_type_id: Uuid,
+    pub name: String,
+    pub rule_type: PricingRuleTypes,
+    pub threshold: i64,
+    pub adjustment_in_cents: i64,
+    pub floor_price_in_cents: i64,
+    pub ceiling_price_in_cents: i64,
+}
+
+/// How a rule was applied when a ticket type's price was evaluated, recorded on the
+/// `TicketPricingAdjusted` domain event to explain the price change.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PricingRuleEvaluation {
+    pub pricing_rule_id: Uuid,
+    pub name: String,
+    pub rule_type: PricingRuleTypes,
+    pub metric: Option<i64>,
+    pub threshold: i64,
+    pub adjustment_in_cents: i64,
+    pub triggered: bool,
+}
+
+impl NewPricingRule {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<PricingRule, DatabaseError> {
+        PricingRule::validate_attributes(
+            &self.name,
+            self.rule_type,
+            self.threshold,
+            self.floor_price_in_cents,
+            self.ceiling_price_in_cents,
+        )?;
+        let result: PricingRule = diesel::insert_into(pricing_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create pricing rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PricingRuleCreated,
+            "Pricing rule created".to_string(),
+            Tables::PricingRules,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        PricingRule::evaluate_ticket_type(&result.ticket_type(conn)?, current_user_id, conn)?;
+
+        Ok(result)
+    }
+}
+
+impl PricingRule {
+    pub fn create(
+        ticket_type_id: Uuid,
+        name: String,
+        rule_type: PricingRuleTypes,
+        threshold: i64,
+        adjustment_in_cents: i64,
+        floor_price_in_cents: i64,
+        ceiling_price_in_cents: i64,
+    ) -> NewPricingRule {
+        NewPricingRule {
+            ticket_type_id,
+            name,
+            rule_type,
+            threshold,
+            adjustment_in_cents,
+            floor_price_in_cents,
+            ceiling_price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PricingRule, DatabaseError> {
+        pricing_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading pricing rule")
+    }
+
+    pub fn find_for_ticket_type(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<PricingRule>, DatabaseError> {
+        pricing_rules::table
+            .filter(pricing_rules::ticket_type_id.eq(ticket_type_id))
+            .order_by(pricing_rules::created_at)
+            .then_order_by(pricing_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading pricing rules")
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: PricingRuleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PricingRule, DatabaseError> {
+        PricingRule::validate_attributes(
+            attributes.name.as_ref().unwrap_or(&self.name),
+            attributes.rule_type.unwrap_or(self.rule_type),
+            attributes.threshold.unwrap_or(self.threshold),
+            attributes.floor_price_in_cents.unwrap_or(self.floor_price_in_cents),
+            attributes.ceiling_price_in_cents.unwrap_or(self.ceiling_price_in_cents),
+        )?;
+
+        let result: PricingRule = diesel::update(self)
+            .set((&attributes, pricing_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update pricing rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PricingRuleUpdated,
+            "Pricing rule updated".to_string(),
+            Tables::PricingRules,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        PricingRule::evaluate_ticket_type(&result.ticket_type(conn)?, current_user_id, conn)?;
+
+        Ok(result)
+    }
+
+    /// Removes the rule. The ticket type's price is evaluated again without it.
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::PricingRuleDeleted,
+            "Pricing rule deleted".to_string(),
+            Tables::PricingRules,
+            Some(self.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        let result = diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete pricing rule")?;
+
+        PricingRule::evaluate_ticket_type(&self.ticket_type(conn)?, current_user_id, conn)?;
+
+        Ok(result)
+    }
+
+    /// Current value of the rule's metric for the ticket type, `None` when it cannot be measured.
+    pub fn metric(
+        &self,
+        ticket_type: &TicketType,
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        Ok(match self.rule_type {
+            PricingRuleTypes::DaysUntilEvent => event.event_start.map(|event_start| (event_start - now).num_days()),
+            PricingRuleTypes::PageViews => Some(PageView::event_page_view_count(
+                event.id,
+                now - Duration::hours(24),
+                conn,
+            )?),
+            PricingRuleTypes::SellThrough => {
+                let ticket_count = ticket_type.valid_ticket_count(conn)? as i64;
+                if ticket_count == 0 {
+                    None
+                } else {
+                    Some(ticket_type.valid_sold_and_reserved_ticket_count(conn)? as i64 * 100 / ticket_count)
+                }
+            }
+        })
+    }
+
+    /// Whether the rule applies for the metric. Days until the event trigger the rule once they
+    /// drop to the threshold, sell-through percentage and page views from the last 24 hours once
+    /// they reach it.
+    pub fn is_triggered(&self, metric: Option<i64>) -> bool {
+        match (self.rule_type, metric) {
+            (_, None) => false,
+            (PricingRuleTypes::DaysUntilEvent, Some(metric)) => metric <= self.threshold,
+            (PricingRuleTypes::PageViews, Some(metric)) | (PricingRuleTypes::SellThrough, Some(metric)) => {
+                metric >= self.threshold
+            }
+        }
+    }
+
+    /// Evaluates the rules of every ticket type that has them, publishing new prices where the
+    /// rules call for one. Run on a schedule by the `EvaluatePricingRules` domain action.
+    pub fn evaluate_all(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let ticket_types: Vec<TicketType> = ticket_types::table
+            .filter(ticket_types::deleted_at.is_null())
+            .filter(ticket_types::cancelled_at.is_null())
+            .filter(ticket_types::id.eq_any(pricing_rules::table.select(pricing_rules::ticket_type_id)))
+            .order_by(ticket_types::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types with pricing rules")?;
+
+        for ticket_type in ticket_types {
+            PricingRule::evaluate_ticket_type(&ticket_type, None, conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates the ticket type's active rules against its base price, the price set by the
+    /// organizer before any adjustment. Adjustments of triggered rules are added up and the result
+    /// is kept within the highest floor and lowest ceiling of the rules. When the price changes a
+    /// new ticket pricing is published and a `TicketPricingAdjusted` domain event records why.
+    pub fn evaluate_ticket_type(
+        ticket_type: &TicketType,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Option<TicketPricing>, DatabaseError> {
+        let current_ticket_pricing = match ticket_type.current_ticket_pricing(false, conn).optional()? {
+            Some(current_ticket_pricing) => current_ticket_pricing,
+            // Not on sale, nothing to adjust
+            None => return Ok(None),
+        };
+        let pricing_rules: Vec<PricingRule> = PricingRule::find_for_ticket_type(ticket_type.id, conn)?
+            .into_iter()
+            .filter(|pricing_rule| pricing_rule.active)
+            .collect();
+        if pricing_rules.is_empty() && current_ticket_pricing.base_price_in_cents.is_none() {
+            return Ok(None);
+        }
+
+        let event = ticket_type.event(conn)?;
+        let base_price_in_cents = current_ticket_pricing
+            .base_price_in_cents
+            .unwrap_or(current_ticket_pricing.price_in_cents);
+        let mut price_in_cents = base_price_in_cents;
+        let mut floor_price_in_cents = 0;
+        let mut ceiling_price_in_cents = i64::max_value();
+        let mut evaluations = Vec::new();
+        for pricing_rule in pricing_rules {
+            let metric = pricing_rule.metric(ticket_type, &event, conn)?;
+            let triggered = pricing_rule.is_triggered(metric);
+            if triggered {
+                price_in_cents += pricing_rule.adjustment_in_cents;
+            }
+            floor_price_in_cents = cmp::max(floor_price_in_cents, pricing_rule.floor_price_in_cents);
+            ceiling_price_in_cents = cmp::min(ceiling_price_in_cents, pricing_rule.ceiling_price_in_cents);
+            evaluations.push(PricingRuleEvaluation {
+                pricing_rule_id: pricing_rule.id,
+                name: pricing_rule.name,
+                rule_type: pricing_rule.rule_type,
+                metric,
+                threshold: pricing_rule.threshold,
+                adjustment_in_cents: pricing_rule.adjustment_in_cents,
+                triggered,
+            });
+        }
+        let price_in_cents = cmp::max(cmp::min(price_in_cents, ceiling_price_in_cents), floor_price_in_cents);
+
+        if price_in_cents == current_ticket_pricing.price_in_cents {
+            return Ok(None);
+        }
+
+        let ticket_pricing = TicketPricing::publish_adjusted_price(
+            ticket_type,
+            &current_ticket_pricing,
+            price_in_cents,
+            base_price_in_cents,
+            current_user_id,
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingAdjusted,
+            format!(
+                "Price of '{}' adjusted from {} to {} by pricing rules",
+                ticket_type.name, current_ticket_pricing.price_in_cents, price_in_cents
+            ),
+            Tables::TicketTypes,
+            Some(ticket_type.id),
+            current_user_id,
+            Some(json!({
+                "old_ticket_pricing_id": current_ticket_pricing.id,
+                "new_ticket_pricing_id": ticket_pricing.id,
+                "old_price_in_cents": current_ticket_pricing.price_in_cents,
+                "new_price_in_cents": price_in_cents,
+                "base_price_in_cents": base_price_in_cents,
+                "floor_price_in_cents": floor_price_in_cents,
+                "ceiling_price_in_cents": ceiling_price_in_cents,
+                "pricing_rules": evaluations
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(Some(ticket_pricing))
+    }
+
+    pub fn create_next_evaluate_pricing_rules_domain_action(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let now = Utc::now().naive_utc();
+        if let Some(upcoming_domain_action) =
+            DomainAction::upcoming_domain_action(None, None, DomainActionTypes::EvaluatePricingRules, conn)?
+        {
+            if upcoming_domain_action.scheduled_at > now {
+                return DatabaseError::business_process_error(
+                    "Evaluate pricing rules domain action is already pending",
+                );
+            }
+        }
+
+        let beginning_of_current_hour =
+            NaiveDate::from_ymd(now.year(), now.month(), now.day()).and_hms(now.hour(), 0, 0);
+        let next_action_date = beginning_of_current_hour + Duration::hours(1);
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::EvaluatePricingRules,
+            None,
+            json!({}),
+            None,
+            None,
+        );
+        action.schedule_at(next_action_date);
+        action.commit(conn)?;
+
+        Ok(())
+    }
+
+    fn validate_attributes(
+        name: &str,
+        rule_type: PricingRuleTypes,
+        threshold: i64,
+        floor_price_in_cents: i64,
+        ceiling_price_in_cents: i64,
+    ) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        if threshold < 0 {
+            return DatabaseError::validation_error("threshold", "Threshold cannot be negative");
+        }
+        if rule_type == PricingRuleTypes::SellThrough && threshold > 100 {
+            return DatabaseError::validation_error("threshold", "Sell-through threshold is a percentage up to 100");
+        }
+        if floor_price_in_cents < 0 {
+            return DatabaseError::validation_error("floor_price_in_cents", "Floor price cannot be negative");
+        }
+        if ceiling_price_in_cents < floor_price_in_cents {
+            return DatabaseError::validation_error(
+                "ceiling_price_in_cents",
+                "Ceiling price cannot be lower than the floor price",
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{order_items, product_instances, product_variants, products};
+use utils::errors::*;
+use uuid::Uuid;
+
+const GIFT_CARD_REDEEM_KEY_LENGTH: u32 = 16;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(OrderItem)]
+#[belongs_to(ProductVariant)]
+#[table_name = "product_instances"]
+pub struct ProductInstance {
+    pub id: Uuid,
+    pub product_variant_id: Uuid,
+    pub order_item_id: Uuid,
+    pub user_id: Uuid,
+    pub status: ProductInstanceStatus,
+    #[serde(skip_serializing)]
+    pub redeem_key: String,
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub redeemed_by_user_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplayProductInstance {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub product_id: Uuid,
+    pub product_name: String,
+    pub product_variant_id: Uuid,
+    pub variant_name: String,
+    pub sku: String,
+    pub status: ProductInstanceStatus,
+    pub redeem_key: String,
+    pub redeemed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = "product_instances"]
+struct NewProductInstance {
+    product_variant_id: Uuid,
+    order_item_id: Uuid,
+    user_id: Uuid,
+    redeem_key: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProductRedeemResults {
+    ProductRedeemSuccess,
+    ProductAlreadyRedeemed,
+    ProductInvalid,
+}
+
+impl ProductInstance {
+    /// Issues one redeemable unit per product bought on the order item to the purchaser.
+    pub(crate) fn create_for_order_item(
+        order_item: &OrderItem,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ProductInstance>, DatabaseError> {
+        let product_variant_id = match order_item.product_variant_id {
+            Some(product_variant_id) => product_variant_id,
+            None => return DatabaseError::no_results("Order item does not have a product variant"),
+        };
+
+        // Gift cards are looked up by their key alone when redeemed so they get a longer one
+        let redeem_key_length = if ProductVariant::find(product_variant_id, conn)?.product(conn)?.gift_card {
+            GIFT_CARD_REDEEM_KEY_LENGTH
+        } else {
+            9
+        };
+        let new_instances: Vec<NewProductInstance> = (0..order_item.quantity)
+            .map(|_| NewProductInstance {
+                product_variant_id,
+                order_item_id: order_item.id,
+                user_id,
+                redeem_key: generate_redeem_key(redeem_key_length),
+            })
+            .collect();
+
+        diesel::insert_into(product_instances::table)
+            .values(&new_instances)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product instances")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ProductInstance, DatabaseError> {
+        product_instances::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product")
+    }
+
+    pub fn find_for_order_item(
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ProductInstance>, DatabaseError> {
+        product_instances::table
+            .filter(product_instances::order_item_id.eq(order_item_id))
+            .order_by(product_instances::created_at)
+            .then_order_by(product_instances::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading products for order item")
+    }
+
+    pub fn find_for_user_for_display(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DisplayProductInstance>, DatabaseError> {
+        product_instances::table
+            .inner_join(order_items::table)
+            .inner_join(product_variants::table.inner_join(products::table))
+            .filter(product_instances::user_id.eq(user_id))
+            .filter(product_instances::status.ne(ProductInstanceStatus::Refunded))
+            .order_by(product_instances::created_at.desc())
+            .then_order_by(products::name)
+            .select((
+                product_instances::id,
+                order_items::order_id,
+                order_items::event_id,
+                products::id,
+                products::name,
+                product_variants::id,
+                product_variants::name,
+                product_variants::sku,
+                product_instances::status,
+                product_instances::redeem_key,
+                product_instances::redeemed_at,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading products for user")
+    }
+
+    pub fn order_item(&self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        OrderItem::find(self.order_item_id, conn)
+    }
+
+    /// Marks the product as picked up if the key matches the one issued with it.
+    pub fn redeem(
+        &self,
+        redeem_key: &str,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ProductRedeemResults, DatabaseError> {
+        if self.product_variant(conn)?.product(conn)?.gift_card {
+            return Ok(ProductRedeemResults::ProductInvalid);
+        }
+
+        match self.status {
+            ProductInstanceStatus::Redeemed => return Ok(ProductRedeemResults::ProductAlreadyRedeemed),
+            ProductInstanceStatus::Refunded => return Ok(ProductRedeemResults::ProductInvalid),
+            ProductInstanceStatus::Purchased => {
+                if self.redeem_key != redeem_key {
+                    return Ok(ProductRedeemResults::ProductInvalid);
+                }
+            }
+        }
+
+        let update_count = diesel::update(
+            product_instances::table
+                .filter(product_instances::id.eq(self.id))
+                .filter(product_instances::status.eq(ProductInstanceStatus::Purchased)),
+        )
+        .set((
+            product_instances::status.eq(ProductInstanceStatus::Redeemed),
+            product_instances::redeemed_at.eq(dsl::now),
+            product_instances::redeemed_by_user_id.eq(current_user_id),
+            product_instances::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem product")?;
+        if update_count != 1 {
+            return Ok(ProductRedeemResults::ProductAlreadyRedeemed);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::ProductInstanceRedeemed,
+            "Product redeemed".to_string(),
+            Tables::Products,
+            Some(self.product_variant(conn)?.product_id),
+            Some(current_user_id),
+            Some(json!({ "product_instance_id": self.id, "order_item_id": self.order_item_id })),
+        )
+        .commit(conn)?;
+
+        Ok(ProductRedeemResults::ProductRedeemSuccess)
+    }
+
+    /// Exchanges an unused gift card for store credit worth the price it was bought for.
+    pub fn redeem_gift_card(
+        redeem_key: &str,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<StoreCreditTransaction, DatabaseError> {
+        let gift_card: Option<ProductInstance> = product_instances::table
+            .inner_join(product_variants::table.inner_join(products::table))
+            .filter(products::gift_card.eq(true))
+            .filter(product_instances::redeem_key.eq(redeem_key.trim().to_uppercase()))
+            .filter(product_instances::status.eq(ProductInstanceStatus::Purchased))
+            .select(product_instances::all_columns)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load gift card")?;
+        let gift_card = match gift_card {
+            Some(gift_card) => gift_card,
+            None => {
+                return DatabaseError::business_process_error("Gift card is invalid or has already been redeemed");
+            }
+        };
+
+        let update_count = diesel::update(
+            product_instances::table
+                .filter(product_instances::id.eq(gift_card.id))
+                .filter(product_instances::status.eq(ProductInstanceStatus::Purchased)),
+        )
+        .set((
+            product_instances::status.eq(ProductInstanceStatus::Redeemed),
+            product_instances::redeemed_at.eq(dsl::now),
+            product_instances::redeemed_by_user_id.eq(user_id),
+            product_instances::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem gift card")?;
+        if update_count != 1 {
+            return DatabaseError::business_process_error("Gift card is invalid or has already been redeemed");
+        }
+
+        let order_item = gift_card.order_item(conn)?;
+        // Gift cards are worth their price in the currency they were bought in
+        let order = Order::find(order_item.order_id, conn)?;
+        let store_credit = NewStoreCreditTransaction {
+            order_id: Some(order_item.order_id),
+            product_instance_id: Some(gift_card.id),
+            ..StoreCreditTransaction::create(
+                user_id,
+                order_item.unit_price_in_cents,
+                order.currency,
+                StoreCreditTransactionTypes::GiftCard,
+                Some(user_id),
+            )
+        }
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductInstanceRedeemed,
+            "Gift card redeemed".to_string(),
+            Tables::Products,
+            Some(gift_card.product_variant(conn)?.product_id),
+            Some(user_id),
+            Some(json!({ "product_instance_id": gift_card.id, "order_item_id": gift_card.order_item_id })),
+        )
+        .commit(conn)?;
+
+        Ok(store_credit)
+    }
+
+    pub fn product_variant(&self, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        ProductVariant::find(self.product_variant_id, conn)
+    }
+
+    /// Refunds one unit of the order item that has not been picked up yet.
+    pub(crate) fn refund_one_for_order_item(order_item_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let product_instance = ProductInstance::find_for_order_item(order_item_id, conn)?
+            .into_iter()
+            .find(|p| p.status == ProductInstanceStatus::Purchased);
+        let product_instance = match product_instance {
+            Some(product_instance) => product_instance,
+            None => {
+                return DatabaseError::business_process_error(
+                    "Products that have already been picked up can not be refunded",
+                );
+            }
+        };
+
+        diesel::update(&product_instance)
+            .set((
+                product_instances::status.eq(ProductInstanceStatus::Refunded),
+                product_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not refund product")?;
+        Ok(())
+    }
+}
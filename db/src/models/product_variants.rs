@@ -0,0 +1,266 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::expression::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use models::*;
+use schema::{order_items, orders, product_variants, products};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Product)]
+#[table_name = "product_variants"]
+pub struct ProductVariant {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub price_in_cents: Option<i64>,
+    pub stock: i32,
+    pub rank: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayProductVariant {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub price_in_cents: i64,
+    pub stock: i32,
+    pub available: i64,
+    pub rank: i32,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "product_variants"]
+pub struct ProductVariantEditableAttributes {
+    pub name: Option<String>,
+    pub sku: Option<String>,
+    pub price_in_cents: Option<Option<i64>>,
+    pub stock: Option<i32>,
+    pub rank: Option<i32>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "product_variants"]
+pub struct NewProductVariant {
+    pub product_id: Uuid,
+    pub name: String,
+    pub sku: String,
+    pub price_in_cents: Option<i64>,
+    pub stock: i32,
+    pub rank: i32,
+}
+
+impl NewProductVariant {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        let product = Product::find(self.product_id, conn)?;
+        ProductVariant::validate_attributes(
+            &product,
+            None,
+            &self.name,
+            &self.sku,
+            self.price_in_cents,
+            self.stock,
+            conn,
+        )?;
+        let result: ProductVariant = diesel::insert_into(product_variants::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product variant")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductVariantCreated,
+            format!("Product variant {} created", result.sku),
+            Tables::Products,
+            Some(product.id),
+            current_user_id,
+            Some(json!({ "product_variant_id": result.id, "variant": self })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl ProductVariant {
+    pub fn create(
+        product_id: Uuid,
+        name: String,
+        sku: String,
+        price_in_cents: Option<i64>,
+        stock: i32,
+        rank: i32,
+    ) -> NewProductVariant {
+        NewProductVariant {
+            product_id,
+            name,
+            sku,
+            price_in_cents,
+            stock,
+            rank,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        product_variants::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product variant")
+    }
+
+    pub fn find_for_product(product_id: Uuid, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        product_variants::table
+            .filter(product_variants::product_id.eq(product_id))
+            .order_by(product_variants::rank)
+            .then_order_by(product_variants::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product variants")
+    }
+
+    pub fn product(&self, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        Product::find(self.product_id, conn)
+    }
+
+    /// The variant's own price if it overrides the product price.
+    pub fn unit_price_in_cents(&self, product: &Product) -> i64 {
+        self.price_in_cents.unwrap_or(product.price_in_cents)
+    }
+
+    /// Locks the variant row until the end of the transaction so concurrent carts cannot oversell it.
+    pub(crate) fn lock_for_update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        product_variants::table
+            .find(self.id)
+            .select(product_variants::id)
+            .for_update()
+            .first::<Uuid>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock product variant")?;
+        Ok(())
+    }
+
+    /// Units of stock not yet sold to paid orders or held in carts that have not expired. Units in
+    /// `excluded_order_id` are not counted so a cart can change its own quantity.
+    pub fn available(&self, excluded_order_id: Option<Uuid>, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let mut query = order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::product_variant_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Products))
+            .filter(
+                orders::status.eq(OrderStatus::Paid).or(orders::status
+                    .eq_any(vec![OrderStatus::Draft, OrderStatus::PendingPayment])
+                    .and(orders::expires_at.gt(dsl::now.nullable()))),
+            )
+            .into_boxed();
+        if let Some(excluded_order_id) = excluded_order_id {
+            query = query.filter(orders::id.ne(excluded_order_id));
+        }
+
+        let taken: i64 = query
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.quantity - order_items.refunded_quantity), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count sold product units")?;
+
+        Ok(self.stock as i64 - taken)
+    }
+
+    pub fn for_display(&self, product: &Product, conn: &PgConnection) -> Result<DisplayProductVariant, DatabaseError> {
+        Ok(DisplayProductVariant {
+            id: self.id,
+            product_id: self.product_id,
+            name: self.name.clone(),
+            sku: self.sku.clone(),
+            price_in_cents: self.unit_price_in_cents(product),
+            stock: self.stock,
+            available: self.available(None, conn)?.max(0),
+            rank: self.rank,
+        })
+    }
+
+    pub fn update(
+        &self,
+        attributes: ProductVariantEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<ProductVariant, DatabaseError> {
+        let product = self.product(conn)?;
+        ProductVariant::validate_attributes(
+            &product,
+            Some(self.id),
+            attributes.name.as_ref().unwrap_or(&self.name),
+            attributes.sku.as_ref().unwrap_or(&self.sku),
+            attributes.price_in_cents.unwrap_or(self.price_in_cents),
+            attributes.stock.unwrap_or(self.stock),
+            conn,
+        )?;
+
+        let result: ProductVariant = diesel::update(self)
+            .set((&attributes, product_variants::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product variant")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductVariantUpdated,
+            format!("Product variant {} updated", result.sku),
+            Tables::Products,
+            Some(product.id),
+            current_user_id,
+            Some(json!({ "product_variant_id": self.id, "attributes": attributes })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_attributes(
+        product: &Product,
+        id: Option<Uuid>,
+        name: &str,
+        sku: &str,
+        price_in_cents: Option<i64>,
+        stock: i32,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        if sku.trim().is_empty() {
+            return DatabaseError::validation_error("sku", "SKU is required");
+        }
+        if price_in_cents.map(|p| p < 0).unwrap_or(false) {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+        if stock < 0 {
+            return DatabaseError::validation_error("stock", "Stock cannot be negative");
+        }
+
+        let mut query = product_variants::table
+            .inner_join(products::table)
+            .filter(products::organization_id.eq(product.organization_id))
+            .filter(product_variants::sku.eq(sku))
+            .select(product_variants::id)
+            .into_boxed();
+        if let Some(id) = id {
+            query = query.filter(product_variants::id.ne(id));
+        }
+        let sku_taken = query
+            .first::<Uuid>(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not check if SKU is unique")?
+            .is_some();
+        if sku_taken {
+            return DatabaseError::validation_error(
+                "sku",
+                "SKU is already used by another product in this organization",
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::products;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Organization)]
+#[table_name = "products"]
+pub struct Product {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Units of gift card products are redeemed by fans for store credit instead of being picked up.
+    pub gift_card: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayProduct {
+    #[serde(flatten)]
+    pub product: Product,
+    pub variants: Vec<DisplayProductVariant>,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "products"]
+pub struct ProductEditableAttributes {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+    pub active: Option<bool>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "products"]
+pub struct NewProduct {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub gift_card: bool,
+}
+
+impl NewProduct {
+    pub fn as_gift_card(mut self) -> NewProduct {
+        self.gift_card = true;
+        self
+    }
+
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        self.validate_record(conn)?;
+        let result: Product = diesel::insert_into(products::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductCreated,
+            "Product created".to_string(),
+            Tables::Products,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        Product::validate_attributes(&self.name, self.price_in_cents)?;
+        if let Some(event_id) = self.event_id {
+            if Event::find(event_id, conn)?.organization_id != self.organization_id {
+                return DatabaseError::validation_error("event_id", "Event does not belong to this organization");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Product {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        name: String,
+        description: Option<String>,
+        price_in_cents: i64,
+    ) -> NewProduct {
+        NewProduct {
+            organization_id,
+            event_id,
+            name,
+            description,
+            price_in_cents,
+            gift_card: false,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        products::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading product")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<Product>, DatabaseError> {
+        products::table
+            .filter(products::organization_id.eq(organization_id))
+            .order_by(products::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading products")
+    }
+
+    /// Active products that can be bought alongside tickets for the event, which includes the
+    /// organization's products that are not tied to a specific event.
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Vec<Product>, DatabaseError> {
+        products::table
+            .filter(products::organization_id.eq(event.organization_id))
+            .filter(products::event_id.eq(event.id).or(products::event_id.is_null()))
+            .filter(products::active.eq(true))
+            .order_by(products::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading products")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn event(&self, conn: &PgConnection) -> Result<Option<Event>, DatabaseError> {
+        match self.event_id {
+            Some(event_id) => Ok(Some(Event::find(event_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn variants(&self, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        ProductVariant::find_for_product(self.id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayProduct, DatabaseError> {
+        let mut variants = Vec::new();
+        for variant in self.variants(conn)? {
+            variants.push(variant.for_display(self, conn)?);
+        }
+
+        Ok(DisplayProduct {
+            product: self.clone(),
+            variants,
+        })
+    }
+
+    pub fn update(
+        &self,
+        attributes: ProductEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Product, DatabaseError> {
+        Product::validate_attributes(
+            attributes.name.as_ref().unwrap_or(&self.name),
+            attributes.price_in_cents.unwrap_or(self.price_in_cents),
+        )?;
+
+        let result: Product = diesel::update(self)
+            .set((&attributes, products::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductUpdated,
+            "Product updated".to_string(),
+            Tables::Products,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_attributes(name: &str, price_in_cents: i64) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        if price_in_cents < 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price cannot be negative");
+        }
+        Ok(())
+    }
+}
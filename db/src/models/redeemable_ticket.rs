@@ -49,4 +49,14 @@ pub struct RedeemableTicket {
     pub redeemed_by: Option<String>,
     #[sql_type = "Nullable<Timestamp>"]
     pub redeemed_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Text>"]
+    pub section_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub row_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub entry_start: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub entry_end: Option<NaiveDateTime>,
 }
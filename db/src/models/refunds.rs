@@ -1,5 +1,6 @@
 use chrono::prelude::*;
 use diesel;
+use diesel::dsl;
 use diesel::prelude::*;
 use models::*;
 use schema::{refund_items, refunds};
@@ -17,6 +18,8 @@ pub struct Refund {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub manual_override: bool,
+    /// Part of the refunded items' value kept rather than returned to the purchaser.
+    pub retained_in_cents: i64,
 }
 
 impl Refund {
@@ -40,6 +43,16 @@ impl Refund {
         Order::find(self.order_id, conn)
     }
 
+    pub(crate) fn retain(&self, retained_in_cents: i64, conn: &PgConnection) -> Result<Refund, DatabaseError> {
+        diesel::update(self)
+            .set((
+                refunds::retained_in_cents.eq(retained_in_cents),
+                refunds::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update refund")
+    }
+
     pub fn items(&self, conn: &PgConnection) -> Result<Vec<RefundItem>, DatabaseError> {
         refund_items::table
             .filter(refund_items::refund_id.eq(self.id))
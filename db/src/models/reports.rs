@@ -2,10 +2,10 @@ use chrono::prelude::*;
 use chrono_tz::Tz;
 use diesel;
 use diesel::prelude::*;
-use diesel::sql_types::{BigInt, Bool, Nullable, Text, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text, Timestamp, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use time::Duration;
 use utils::errors::*;
 use uuid::Uuid;
@@ -87,6 +87,8 @@ pub struct TicketSalesRow {
     pub per_order_total_fees_in_cents: i64,
     #[sql_type = "BigInt"]
     pub user_count: i64,
+    #[sql_type = "Nullable<Text>"]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -103,6 +105,8 @@ pub struct TicketSalesPerEventFees {
     pub per_order_client_online_fees: i64,
     #[sql_type = "BigInt"]
     pub per_order_total_fees_in_cents: i64,
+    #[sql_type = "Nullable<Text>"]
+    pub currency: Option<String>,
 }
 
 impl From<TicketSalesPerEventFees> for TicketSalesRow {
@@ -114,6 +118,7 @@ impl From<TicketSalesPerEventFees> for TicketSalesRow {
             per_order_company_online_fees: ticket_sales_per_event_fees.per_order_company_online_fees,
             per_order_client_online_fees: ticket_sales_per_event_fees.per_order_client_online_fees,
             per_order_total_fees_in_cents: ticket_sales_per_event_fees.per_order_total_fees_in_cents,
+            currency: ticket_sales_per_event_fees.currency,
             ..Default::default()
         }
     }
@@ -231,6 +236,12 @@ pub struct TransactionReportRow {
     pub payment_method: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub payment_provider: Option<String>,
+    #[serde(skip_serializing)]
+    #[sql_type = "BigInt"]
+    pub store_credit_in_cents: i64,
+    #[serde(skip_serializing)]
+    #[sql_type = "BigInt"]
+    pub store_credit_refunded_in_cents: i64,
     #[sql_type = "Timestamp"]
     pub transaction_date: NaiveDateTime,
     #[sql_type = "Nullable<Text>"]
@@ -271,6 +282,10 @@ pub struct TransactionReportRow {
     pub platform: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub check_in_source: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub attendee_answers: Option<String>,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -377,6 +392,8 @@ pub struct EventSummarySalesRow {
     pub total_gross_income_in_cents: i64,
     #[sql_type = "dUuid"]
     pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -405,6 +422,8 @@ pub struct EventSummaryFeesRow {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -421,10 +440,13 @@ pub struct EventSummaryOtherFees {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ReconciliationSummaryResult {
+    pub currency: String,
     pub payment_method: String,
     pub payment_provider: String,
     pub quantity: i64,
@@ -437,6 +459,8 @@ pub struct ReconciliationSummaryResult {
     pub refund_client_fee_in_cents: i64,
     pub refund_event_fee_in_cents: i64,
     pub refund_total: i64,
+    pub store_credit_in_cents: i64,
+    pub store_credit_refunded_in_cents: i64,
     pub total: i64,
 }
 
@@ -452,6 +476,7 @@ pub struct ReconciliationFeeRangeResult {
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ReconciliationDetailResult {
+    pub currency: String,
     pub payment_method: String,
     pub payment_provider: String,
     pub quantity: i64,
@@ -464,6 +489,8 @@ pub struct ReconciliationDetailResult {
     pub refund_client_fee_in_cents: Vec<ReconciliationFeeRangeResult>,
     pub refund_event_fee_in_cents: i64,
     pub refund_total: i64,
+    pub store_credit_in_cents: i64,
+    pub store_credit_refunded_in_cents: i64,
     pub total: i64,
 }
 
@@ -475,6 +502,51 @@ pub struct ReconciliationDetailEventResult {
     pub entries: Vec<ReconciliationDetailResult>,
 }
 
+/// Store credit issued and spent per currency and transaction type, the liability is what is still
+/// owed to fans.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct StoreCreditReportRow {
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
+    pub transaction_type: StoreCreditTransactionTypes,
+    #[sql_type = "BigInt"]
+    pub transaction_count: i64,
+    #[sql_type = "BigInt"]
+    pub issued_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub spent_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub liability_in_cents: i64,
+}
+
+/// Tax charged per event and tax rule, taxable sales and net tax exclude refunded items.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct TaxReportRow {
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "dUuid"]
+    pub tax_rule_id: Uuid,
+    #[sql_type = "Text"]
+    pub tax_rule_name: String,
+    #[sql_type = "Double"]
+    pub rate_percent: f64,
+    #[sql_type = "Bool"]
+    pub inclusive: bool,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "BigInt"]
+    pub taxable_sales_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_collected_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_refunded_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub net_tax_in_cents: i64,
+}
+
 pub fn group_by_string(
     group_by_ticket_type: bool,
     group_by_ticket_pricing: bool,
@@ -966,15 +1038,24 @@ impl Report {
 
         //Produce Report
         let mut results: Vec<ReconciliationSummaryResult> = Vec::new();
+        // Store credit is recorded per order so it is only counted on the order's first row
+        let mut store_credit_orders: HashSet<Uuid> = HashSet::new();
         for row in transaction_rows {
             if row.payment_method.is_some() && row.payment_provider.is_some() {
+                let (store_credit, store_credit_refunded) = if store_credit_orders.insert(row.order_id) {
+                    (row.store_credit_in_cents, row.store_credit_refunded_in_cents)
+                } else {
+                    (0, 0)
+                };
                 let entry_exists = results.iter().any(|r| {
-                    r.payment_method == row.payment_method.clone().unwrap()
+                    r.currency == row.currency
+                        && r.payment_method == row.payment_method.clone().unwrap()
                         && r.payment_provider == row.payment_provider.clone().unwrap()
                 });
                 if entry_exists {
                     if let Some(entry) = results.iter_mut().find(|r| {
-                        r.payment_method == row.payment_method.clone().unwrap()
+                        r.currency == row.currency
+                            && r.payment_method == row.payment_method.clone().unwrap()
                             && r.payment_provider == row.payment_provider.clone().unwrap()
                     }) {
                         let ticket_face = row.unit_price_in_cents * row.actual_quantity;
@@ -995,6 +1076,8 @@ impl Report {
                         entry.refund_client_fee_in_cents += refund_client_fee;
                         entry.refund_event_fee_in_cents += refund_event_fee;
                         entry.refund_total += refund_total;
+                        entry.store_credit_in_cents += store_credit;
+                        entry.store_credit_refunded_in_cents += store_credit_refunded;
                         entry.total += sales_total - refund_total;
                     }
                 } else {
@@ -1007,6 +1090,7 @@ impl Report {
                     let sales_total = ticket_face + client_fee + event_fee;
                     let refund_total = refund_ticket_face + refund_client_fee + refund_event_fee;
                     results.push(ReconciliationSummaryResult {
+                        currency: row.currency,
                         payment_method: row.payment_method.unwrap(),
                         payment_provider: row.payment_provider.unwrap(),
                         quantity: row.actual_quantity,
@@ -1019,6 +1103,8 @@ impl Report {
                         refund_client_fee_in_cents: refund_client_fee,
                         refund_event_fee_in_cents: refund_event_fee,
                         refund_total,
+                        store_credit_in_cents: store_credit,
+                        store_credit_refunded_in_cents: store_credit_refunded,
                         total: sales_total - refund_total,
                     });
                 }
@@ -1102,6 +1188,8 @@ impl Report {
 
         //Produce Report
         let mut results: Vec<ReconciliationDetailEventResult> = Vec::new();
+        // Store credit is recorded per order so it is only counted on the order's first row per event
+        let mut store_credit_orders: HashSet<(Uuid, Uuid)> = HashSet::new();
 
         for row in transaction_rows {
             let event_exists = results.iter().any(|r| r.event_id == row.event_id);
@@ -1118,7 +1206,8 @@ impl Report {
             if let Some(event_entry) = results.iter_mut().find(|ref r| r.event_id == row.event_id) {
                 if row.payment_method.is_some() && row.payment_provider.is_some() {
                     let entry_exists = event_entry.entries.iter().any(|r| {
-                        r.payment_method == row.payment_method.clone().unwrap()
+                        r.currency == row.currency
+                            && r.payment_method == row.payment_method.clone().unwrap()
                             && r.payment_provider == row.payment_provider.clone().unwrap()
                     });
 
@@ -1134,9 +1223,16 @@ impl Report {
                     }
 
                     if let Some(column_idx) = column_idx {
+                        let (store_credit, store_credit_refunded) =
+                            if store_credit_orders.insert((row.event_id, row.order_id)) {
+                                (row.store_credit_in_cents, row.store_credit_refunded_in_cents)
+                            } else {
+                                (0, 0)
+                            };
                         if entry_exists {
                             if let Some(entry) = event_entry.entries.iter_mut().find(|r| {
-                                r.payment_method == row.payment_method.clone().unwrap()
+                                r.currency == row.currency
+                                    && r.payment_method == row.payment_method.clone().unwrap()
                                     && r.payment_provider == row.payment_provider.clone().unwrap()
                             }) {
                                 let ticket_face = row.unit_price_in_cents * row.actual_quantity;
@@ -1158,6 +1254,8 @@ impl Report {
                                 entry.refund_client_fee_in_cents[column_idx].client_fee_in_cents += refund_client_fee;
                                 entry.refund_event_fee_in_cents += refund_event_fee;
                                 entry.refund_total += refund_total;
+                                entry.store_credit_in_cents += store_credit;
+                                entry.store_credit_refunded_in_cents += store_credit_refunded;
                                 entry.total += sales_total - refund_total;
                             }
                         } else {
@@ -1177,6 +1275,7 @@ impl Report {
                             refund_client_fee_in_cents[column_idx].client_fee_in_cents = refund_client_fee;
 
                             event_entry.entries.push(ReconciliationDetailResult {
+                                currency: row.currency,
                                 payment_method: row.payment_method.unwrap(),
                                 payment_provider: row.payment_provider.unwrap(),
                                 quantity: row.actual_quantity,
@@ -1189,6 +1288,8 @@ impl Report {
                                 refund_client_fee_in_cents,
                                 refund_event_fee_in_cents: refund_event_fee,
                                 refund_total,
+                                store_credit_in_cents: store_credit,
+                                store_credit_refunded_in_cents: store_credit_refunded,
                                 total: sales_total - refund_total,
                             });
                         }
@@ -1199,4 +1300,36 @@ impl Report {
 
         Ok(results)
     }
+
+    pub fn store_credit_report(
+        organization_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<StoreCreditReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_store_credit.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(organization_id)
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    pub fn tax_report(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TaxReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_tax.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
 }
@@ -0,0 +1,404 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{order_items, orders, resale_listings, ticket_instances};
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Event)]
+#[belongs_to(TicketInstance)]
+#[table_name = "resale_listings"]
+pub struct ResaleListing {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub price_in_cents: i64,
+    pub status: ResaleListingStatus,
+    pub order_item_id: Option<Uuid>,
+    pub buyer_user_id: Option<Uuid>,
+    pub sold_at: Option<NaiveDateTime>,
+    pub settlement_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayResaleListing {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub price_in_cents: i64,
+    pub status: ResaleListingStatus,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "resale_listings"]
+pub struct ResaleListingEditableAttributes {
+    pub status: Option<ResaleListingStatus>,
+    pub order_item_id: Option<Option<Uuid>>,
+    pub buyer_user_id: Option<Option<Uuid>>,
+    pub sold_at: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "resale_listings"]
+pub struct NewResaleListing {
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub seller_user_id: Uuid,
+    pub price_in_cents: i64,
+}
+
+impl NewResaleListing {
+    pub fn commit(&self, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        self.validate_record(conn)?;
+        let result: ResaleListing = diesel::insert_into(resale_listings::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create resale listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCreated,
+            "Ticket listed for resale".to_string(),
+            Tables::ResaleListings,
+            Some(result.id),
+            Some(self.seller_user_id),
+            Some(json!({ "ticket_instance_id": self.ticket_instance_id, "price_in_cents": self.price_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.price_in_cents <= 0 {
+            return DatabaseError::validation_error("price_in_cents", "Price must be greater than 0");
+        }
+
+        let event = Event::find(self.event_id, conn)?;
+        if !event.resale_enabled {
+            return DatabaseError::business_process_error("Resale is not enabled for this event");
+        }
+        if event.cancelled_at.is_some() || event.event_end.map(|e| e < Utc::now().naive_utc()).unwrap_or(false) {
+            return DatabaseError::business_process_error("Tickets for this event can no longer be resold");
+        }
+
+        let ticket = TicketInstance::find(self.ticket_instance_id, conn)?;
+        if ticket.ticket_type(conn)?.event_id != self.event_id {
+            return DatabaseError::business_process_error("Ticket does not belong to this event");
+        }
+        let wallet = Wallet::find_default_for_user(self.seller_user_id, conn)?;
+        if ticket.status != TicketInstanceStatus::Purchased || ticket.wallet_id != wallet.id {
+            return DatabaseError::business_process_error("User does not own this ticket");
+        }
+        if PaymentPlan::any_unpaid_for_tickets(&[ticket.id], conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets cannot be resold until their payment plan is paid in full",
+            );
+        }
+        if ticket.has_pending_transfer(conn)? {
+            return DatabaseError::business_process_error("Ticket has a pending transfer");
+        }
+        if ResaleListing::find_active_for_ticket(self.ticket_instance_id, conn)?.is_some() {
+            return DatabaseError::business_process_error("Ticket is already listed for resale");
+        }
+
+        if let Some(max_markup_percentage) = event.resale_max_markup_percentage {
+            let face_value_in_cents = ResaleListing::face_value_in_cents(&ticket, conn)?;
+            let max_price_in_cents = face_value_in_cents * (100 + max_markup_percentage as i64) / 100;
+            if self.price_in_cents > max_price_in_cents {
+                return DatabaseError::validation_error(
+                    "price_in_cents",
+                    "Price exceeds the maximum resale price for this event",
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ResaleListing {
+    pub fn create(
+        ticket_instance_id: Uuid,
+        event_id: Uuid,
+        seller_user_id: Uuid,
+        price_in_cents: i64,
+    ) -> NewResaleListing {
+        NewResaleListing {
+            ticket_instance_id,
+            event_id,
+            seller_user_id,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        resale_listings::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing")
+    }
+
+    /// Loads the listing and locks its row until the end of the transaction so that concurrent
+    /// carts cannot both claim it.
+    pub(crate) fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        resale_listings::table
+            .find(id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing")
+    }
+
+    pub fn find_active_for_ticket(
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::ticket_instance_id.eq(ticket_instance_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing")
+    }
+
+    pub fn find_by_order_item_id(order_item_id: Uuid, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::order_item_id.eq(order_item_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listing")
+    }
+
+    pub fn find_for_seller(seller_user_id: Uuid, conn: &PgConnection) -> Result<Vec<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::seller_user_id.eq(seller_user_id))
+            .order_by(resale_listings::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listings")
+    }
+
+    /// Active listings for the event that are not currently sitting in another buyer's cart,
+    /// cheapest first.
+    pub fn find_available_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .left_join(order_items::table.on(resale_listings::order_item_id.eq(order_items::id.nullable())))
+            .left_join(orders::table.on(orders::id.eq(order_items::order_id)))
+            .filter(resale_listings::event_id.eq(event_id))
+            .filter(resale_listings::status.eq(ResaleListingStatus::Active))
+            .filter(
+                orders::id
+                    .is_null()
+                    .or(orders::status.ne(OrderStatus::Draft))
+                    .or(orders::expires_at.lt(dsl::now.nullable())),
+            )
+            .order_by(resale_listings::price_in_cents.asc())
+            .then_order_by(resale_listings::created_at.asc())
+            .select(resale_listings::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listings")
+    }
+
+    /// Sold listings paid out to their sellers as part of the settlement.
+    pub fn find_for_settlement(settlement_id: Uuid, conn: &PgConnection) -> Result<Vec<ResaleListing>, DatabaseError> {
+        resale_listings::table
+            .filter(resale_listings::settlement_id.eq(settlement_id))
+            .order_by(resale_listings::sold_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading resale listings")
+    }
+
+    pub fn any_active_for_tickets(ticket_instance_ids: &[Uuid], conn: &PgConnection) -> Result<bool, DatabaseError> {
+        dsl::select(dsl::exists(
+            resale_listings::table
+                .filter(resale_listings::ticket_instance_id.eq_any(ticket_instance_ids))
+                .filter(resale_listings::status.eq(ResaleListingStatus::Active)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check for active resale listings")
+    }
+
+    /// The price originally paid for the ticket, after any discount.
+    pub fn face_value_in_cents(ticket: &TicketInstance, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let order_item = match ticket.order_item_id {
+            Some(order_item_id) => OrderItem::find(order_item_id, conn)?,
+            None => return Ok(0),
+        };
+        let discount_in_cents = order_item
+            .find_discount_item(conn)?
+            .map(|d| d.unit_price_in_cents)
+            .unwrap_or(0);
+        Ok(order_item.unit_price_in_cents + discount_in_cents)
+    }
+
+    pub fn ticket_instance(&self, conn: &PgConnection) -> Result<TicketInstance, DatabaseError> {
+        TicketInstance::find(self.ticket_instance_id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayResaleListing, DatabaseError> {
+        let ticket_type = self.ticket_instance(conn)?.ticket_type(conn)?;
+        Ok(DisplayResaleListing {
+            id: self.id,
+            event_id: self.event_id,
+            ticket_type_id: ticket_type.id,
+            ticket_type_name: ticket_type.name,
+            price_in_cents: self.price_in_cents,
+            status: self.status,
+            created_at: self.created_at,
+        })
+    }
+
+    /// Whether the listing is held in a cart other than the given order's that has not yet expired.
+    /// Load the listing with `find_for_update` before claiming it based on this check.
+    pub fn in_other_cart(&self, order_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let order_item_id = match self.order_item_id {
+            Some(order_item_id) => order_item_id,
+            None => return Ok(false),
+        };
+        let order = match order_items::table
+            .inner_join(orders::table)
+            .filter(order_items::id.eq(order_item_id))
+            .select(orders::all_columns)
+            .first::<Order>(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load order for resale listing")?
+        {
+            Some(order) => order,
+            None => return Ok(false),
+        };
+
+        Ok(order.id != order_id && order.status == OrderStatus::Draft && !order.is_expired())
+    }
+
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ResaleListing, DatabaseError> {
+        if self.status != ResaleListingStatus::Active {
+            return DatabaseError::business_process_error("Resale listing is no longer active");
+        }
+
+        let listing = self.update(
+            ResaleListingEditableAttributes {
+                status: Some(ResaleListingStatus::Cancelled),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingCancelled,
+            "Resale listing cancelled".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    pub(crate) fn reserve_for_order_item(
+        &self,
+        order_item_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        self.update(
+            ResaleListingEditableAttributes {
+                order_item_id: Some(order_item_id),
+                ..Default::default()
+            },
+            conn,
+        )
+    }
+
+    /// Confirms the listing can still be sold through the given order item. This is checked before
+    /// payment is taken so that buyers are not charged for tickets the seller no longer holds.
+    pub(crate) fn confirm_available(
+        &self,
+        order_item_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(TicketInstance, Wallet), DatabaseError> {
+        if self.status != ResaleListingStatus::Active || self.order_item_id != Some(order_item_id) {
+            return DatabaseError::business_process_error("Resale listing is no longer available");
+        }
+
+        let ticket = self.ticket_instance(conn)?;
+        let seller_wallet = Wallet::find_default_for_user(self.seller_user_id, conn)?;
+        if ticket.status != TicketInstanceStatus::Purchased || ticket.wallet_id != seller_wallet.id {
+            return DatabaseError::business_process_error("Ticket is no longer owned by the seller");
+        }
+
+        Ok((ticket, seller_wallet))
+    }
+
+    /// Moves the ticket from the seller to the buyer once the buyer's order is paid. The ticket
+    /// is issued a new redeem key so that any copy held by the seller can no longer be used.
+    pub(crate) fn complete_purchase(
+        &self,
+        order_item: &OrderItem,
+        buyer_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        let (ticket, seller_wallet) = self.confirm_available(order_item.id, conn)?;
+        let buyer_wallet = Wallet::find_default_for_user(buyer_user_id, conn)?;
+        let name_override: Option<String> = None;
+        let update_count = diesel::update(
+            ticket_instances::table
+                .filter(ticket_instances::id.eq(ticket.id))
+                .filter(ticket_instances::updated_at.eq(ticket.updated_at)),
+        )
+        .set((
+            ticket_instances::wallet_id.eq(buyer_wallet.id),
+            ticket_instances::updated_at.eq(dsl::now),
+            ticket_instances::first_name_override.eq(&name_override),
+            ticket_instances::last_name_override.eq(&name_override),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update ticket instance")?;
+        if update_count != 1 {
+            return DatabaseError::concurrency_error("Could not transfer resale ticket");
+        }
+        ticket.associate_redeem_key(conn)?;
+
+        let listing = self.update(
+            ResaleListingEditableAttributes {
+                status: Some(ResaleListingStatus::Sold),
+                buyer_user_id: Some(Some(buyer_user_id)),
+                sold_at: Some(Some(Utc::now().naive_utc())),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::ResaleListingSold,
+            "Resale listing sold".to_string(),
+            Tables::ResaleListings,
+            Some(listing.id),
+            Some(buyer_user_id),
+            Some(json!({
+                "order_id": order_item.order_id,
+                "seller_wallet_id": seller_wallet.id,
+                "buyer_wallet_id": buyer_wallet.id
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    fn update(
+        &self,
+        attributes: ResaleListingEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<ResaleListing, DatabaseError> {
+        diesel::update(self)
+            .set((attributes, resale_listings::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update resale listing")
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{seating_sections, seats, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Stage)]
+#[table_name = "seating_sections"]
+pub struct SeatingSection {
+    pub id: Uuid,
+    pub stage_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "seating_sections"]
+pub struct SeatingSectionEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    pub rank: Option<i32>,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seating_sections"]
+pub struct NewSeatingSection {
+    pub stage_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+}
+
+impl NewSeatingSection {
+    pub fn commit(&self, conn: &PgConnection) -> Result<SeatingSection, DatabaseError> {
+        diesel::insert_into(seating_sections::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seating section")
+    }
+}
+
+impl SeatingSection {
+    pub fn create(stage_id: Uuid, name: String, rank: i32) -> NewSeatingSection {
+        NewSeatingSection { stage_id, name, rank }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SeatingSection, DatabaseError> {
+        seating_sections::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading seating section")
+    }
+
+    pub fn find_by_stage_id(stage_id: Uuid, conn: &PgConnection) -> Result<Vec<SeatingSection>, DatabaseError> {
+        seating_sections::table
+            .filter(seating_sections::stage_id.eq(stage_id))
+            .order_by(seating_sections::rank)
+            .then_order_by(seating_sections::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seating sections")
+    }
+
+    pub fn stage(&self, conn: &PgConnection) -> Result<Stage, DatabaseError> {
+        Stage::find(self.stage_id, conn)
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        Seat::find_by_seating_section_id(self.id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: SeatingSectionEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<SeatingSection, DatabaseError> {
+        diesel::update(self)
+            .set((attributes, seating_sections::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update seating section")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        if TicketType::find_by_seating_section_id(self.id, conn)?.len() > 0 {
+            return DatabaseError::business_process_error(
+                "Seating section cannot be deleted while it is in use by a ticket type",
+            );
+        }
+        let seats_assigned: bool = diesel::select(dsl::exists(
+            ticket_instances::table
+                .inner_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+                .filter(seats::seating_section_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if seats are assigned to tickets",
+        )?;
+        if seats_assigned {
+            return DatabaseError::business_process_error(
+                "Seating section cannot be deleted while its seats are assigned to tickets",
+            );
+        }
+
+        diesel::delete(seats::table.filter(seats::seating_section_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete seats for seating section")?;
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete seating section")
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Bool, Text, Uuid as dUuid};
+use itertools::Itertools;
+use models::*;
+use schema::seats;
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, QueryableByName, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(SeatingSection)]
+#[table_name = "seats"]
+pub struct Seat {
+    pub id: Uuid,
+    pub seating_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+    pub row_rank: i32,
+    pub seat_rank: i32,
+    pub accessible: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "seats"]
+pub struct SeatEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub row_name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub seat_number: Option<String>,
+    pub row_rank: Option<i32>,
+    pub seat_rank: Option<i32>,
+    pub accessible: Option<bool>,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seats"]
+pub struct NewSeat {
+    pub seating_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+    pub row_rank: i32,
+    pub seat_rank: i32,
+    pub accessible: bool,
+}
+
+impl NewSeat {
+    pub fn commit(&self, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        diesel::insert_into(seats::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seat")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DisplaySeat {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub seating_section_id: Uuid,
+    #[sql_type = "Text"]
+    pub section_name: String,
+    #[sql_type = "Text"]
+    pub row_name: String,
+    #[sql_type = "Text"]
+    pub seat_number: String,
+    #[sql_type = "Bool"]
+    pub accessible: bool,
+    #[sql_type = "Bool"]
+    pub available: bool,
+    #[sql_type = "Array<dUuid>"]
+    pub ticket_type_ids: Vec<Uuid>,
+}
+
+impl Seat {
+    pub fn create(
+        seating_section_id: Uuid,
+        row_name: String,
+        seat_number: String,
+        row_rank: i32,
+        seat_rank: i32,
+        accessible: bool,
+    ) -> NewSeat {
+        NewSeat {
+            seating_section_id,
+            row_name,
+            seat_number,
+            row_rank,
+            seat_rank,
+            accessible,
+        }
+    }
+
+    pub fn create_multiple(seats: Vec<NewSeat>, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        diesel::insert_into(seats::table)
+            .values(&seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seats")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        seats::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading seat")
+    }
+
+    pub fn find_by_seating_section_id(
+        seating_section_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Seat>, DatabaseError> {
+        seats::table
+            .filter(seats::seating_section_id.eq(seating_section_id))
+            .order_by(seats::row_rank)
+            .then_order_by(seats::seat_rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats")
+    }
+
+    /// Seats in the section that are not held by a purchased, redeemed or currently reserved ticket
+    /// for the event. Tickets in `excluded_ticket_instance_ids` are ignored so that seats already
+    /// assigned to them are treated as available. All seats in the section are locked until the
+    /// end of the transaction.
+    pub fn find_available(
+        seating_section_id: Uuid,
+        event_id: Uuid,
+        excluded_ticket_instance_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<Seat>, DatabaseError> {
+        // The seats are locked before availability is checked, in a separate statement, so that
+        // seats taken by a concurrent transaction holding the lock are seen once it commits
+        seats::table
+            .filter(seats::seating_section_id.eq(seating_section_id))
+            .order_by(seats::id)
+            .select(seats::id)
+            .for_update()
+            .load::<Uuid>(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to lock seats")?;
+
+        diesel::sql_query(include_str!("../queries/find_available_seats.sql"))
+            .bind::<dUuid, _>(seating_section_id)
+            .bind::<dUuid, _>(event_id)
+            .bind::<Array<dUuid>, _>(excluded_ticket_instance_ids.to_vec())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load available seats")
+    }
+
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<DisplaySeat>, DatabaseError> {
+        diesel::sql_query(include_str!("../queries/find_seats_for_event.sql"))
+            .bind::<dUuid, _>(event_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats for event")
+    }
+
+    /// Picks the best `quantity` seats from `available_seats`, which must already be sorted by
+    /// preference. A block of adjacent seats in a single row is preferred, falling back to the
+    /// first seats in preference order when no such block exists.
+    pub fn best_available(available_seats: &[Seat], quantity: usize) -> Option<Vec<Seat>> {
+        if quantity == 0 {
+            return Some(vec![]);
+        }
+        if available_seats.len() < quantity {
+            return None;
+        }
+
+        for (_, row) in &available_seats
+            .iter()
+            .filter(|s| !s.accessible)
+            .group_by(|s| (s.row_rank, s.row_name.clone()))
+        {
+            let row: Vec<&Seat> = row.collect();
+            if row.len() < quantity {
+                continue;
+            }
+            for block in row.windows(quantity) {
+                if block.windows(2).all(|pair| pair[1].seat_rank == pair[0].seat_rank + 1) {
+                    return Some(block.iter().map(|s| (*s).clone()).collect());
+                }
+            }
+        }
+
+        Some(available_seats.iter().take(quantity).cloned().collect())
+    }
+
+    pub fn update(&self, attributes: SeatEditableAttributes, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        diesel::update(self)
+            .set((attributes, seats::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update seat")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Failed to delete seat")
+    }
+}
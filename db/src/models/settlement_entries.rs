@@ -26,6 +26,7 @@ pub struct SettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub product_variant_id: Option<Uuid>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -43,6 +44,8 @@ pub struct DisplaySettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub product_variant_id: Option<Uuid>,
+    pub product_name: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -74,6 +77,13 @@ impl SettlementEntry {
                 settlement_entries::settlement_entry_type,
                 settlement_entries::created_at,
                 settlement_entries::updated_at,
+                settlement_entries::product_variant_id,
+                sql::<Nullable<Text>>(
+                    "(SELECT p.name || ' - ' || pv.name
+                    FROM product_variants pv
+                    JOIN products p ON p.id = pv.product_id
+                    WHERE pv.id = settlement_entries.product_variant_id) AS product_name",
+                ),
             ))
             .order_by(events::event_start)
             .then_order_by(settlement_entries::event_id)
@@ -29,6 +29,7 @@ pub struct Settlement {
     pub only_finished_events: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
 }
 
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
@@ -47,17 +48,20 @@ pub struct DisplaySettlement {
     pub settlement: Settlement,
     pub adjustments: Vec<SettlementAdjustment>,
     pub event_entries: Vec<EventGroupedSettlementEntry>,
+    pub resale_payouts: Vec<ResaleListing>,
 }
 
 impl NewSettlement {
     pub fn commit(&self, user: Option<User>, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
         self.validate_record()?;
+        // Settlements are paid out in the organization's currency
+        let organization = Organization::find(self.organization_id, conn)?;
 
         let settlement = DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement",
             diesel::insert_into(settlements::table)
-                .values(self)
+                .values((self, settlements::currency.eq(&organization.currency)))
                 .get_result::<Settlement>(conn),
         )?;
 
@@ -125,6 +129,21 @@ impl Settlement {
             .to_db_error(ErrorCode::QueryError, "Could not load settlement")
     }
 
+    /// Latest settlement for the organization that has not been settled yet, which is where
+    /// adjustments arriving from outside the settlement process (e.g. chargebacks) are applied.
+    pub fn find_open_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .filter(settlements::status.eq(SettlementStatus::PendingSettlement))
+            .order_by(settlements::end_time.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement")
+    }
+
     pub fn process_settlement_for_organization(
         organization: &Organization,
         settlement_period_in_days: Option<u32>,
@@ -181,6 +200,7 @@ impl Settlement {
             settlement: self.clone(),
             adjustments,
             event_entries: SettlementEntry::find_for_settlement_by_event(self, conn)?,
+            resale_payouts: ResaleListing::find_for_settlement(self.id, conn)?,
         })
     }
 
@@ -3,7 +3,7 @@ use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
 use models::*;
-use schema::stages;
+use schema::{assets, capacity_pools, seating_sections, stages, ticket_instances, ticket_types};
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -81,6 +81,17 @@ impl Stage {
     }
 
     pub fn update(&self, attributes: StageEditableAttributes, conn: &PgConnection) -> Result<Stage, DatabaseError> {
+        if let Some(Some(capacity)) = attributes.capacity {
+            for event_id in self.event_ids(conn)? {
+                if self.allocated_capacity(event_id, None, conn)? > capacity {
+                    return DatabaseError::validation_error(
+                        "capacity",
+                        "Capacity cannot be lower than the tickets allocated to the stage",
+                    );
+                }
+            }
+        }
+
         DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Could not update stage",
@@ -90,6 +101,81 @@ impl Stage {
         )
     }
 
+    /// Number of tickets an event has allocated to this stage. Capacity pools limited to the stage
+    /// count at their capacity and seated ticket types on the stage outside those pools count at
+    /// their inventory.
+    pub fn allocated_capacity(
+        &self,
+        event_id: Uuid,
+        excluded_capacity_pool_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let capacity_pools: Vec<CapacityPool> = capacity_pools::table
+            .filter(capacity_pools::event_id.eq(event_id))
+            .filter(capacity_pools::stage_id.eq(self.id))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading capacity pools for stage")?;
+        let capacity_pool_ids: Vec<Uuid> = capacity_pools.iter().map(|p| p.id).collect();
+
+        let seated_ticket_count: i64 = ticket_instances::table
+            .inner_join(assets::table.inner_join(ticket_types::table.inner_join(seating_sections::table)))
+            .filter(ticket_types::event_id.eq(event_id))
+            .filter(ticket_types::deleted_at.is_null())
+            .filter(seating_sections::stage_id.eq(self.id))
+            .filter(
+                ticket_types::capacity_pool_id
+                    .is_null()
+                    .or(ticket_types::capacity_pool_id.ne_all(capacity_pool_ids)),
+            )
+            .filter(ticket_instances::status.ne(TicketInstanceStatus::Nullified))
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket count for stage")?;
+
+        Ok(capacity_pools
+            .iter()
+            .filter(|p| Some(p.id) != excluded_capacity_pool_id)
+            .map(|p| p.capacity)
+            .sum::<i64>()
+            + seated_ticket_count)
+    }
+
+    /// Whether `quantity` more tickets can be allocated to the stage for the event without exceeding
+    /// its capacity.
+    pub(crate) fn has_capacity_for(
+        &self,
+        event_id: Uuid,
+        quantity: i64,
+        excluded_capacity_pool_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        match self.capacity {
+            Some(capacity) => {
+                Ok(self.allocated_capacity(event_id, excluded_capacity_pool_id, conn)? + quantity <= capacity)
+            }
+            None => Ok(true),
+        }
+    }
+
+    fn event_ids(&self, conn: &PgConnection) -> Result<Vec<Uuid>, DatabaseError> {
+        let mut event_ids: Vec<Uuid> = capacity_pools::table
+            .filter(capacity_pools::stage_id.eq(self.id))
+            .select(capacity_pools::event_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading events for stage")?;
+        event_ids.extend(
+            ticket_types::table
+                .inner_join(seating_sections::table)
+                .filter(seating_sections::stage_id.eq(self.id))
+                .select(ticket_types::event_id)
+                .load::<Uuid>(conn)
+                .to_db_error(ErrorCode::QueryError, "Error loading events for stage")?,
+        );
+        event_ids.sort();
+        event_ids.dedup();
+        Ok(event_ids)
+    }
+
     pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::DeleteError,
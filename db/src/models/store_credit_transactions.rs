@@ -0,0 +1,196 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text, Uuid as dUuid};
+use models::*;
+use schema::{store_credit_transactions, users};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// An entry in a user's store credit ledger. Gift cards and refunds issued as credit are positive
+/// entries and purchases paid with credit are negative, the balance is the sum of the entries.
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "store_credit_transactions"]
+pub struct StoreCreditTransaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub amount_in_cents: i64,
+    pub transaction_type: StoreCreditTransactionTypes,
+    pub order_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub product_instance_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub currency: String,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "store_credit_transactions"]
+pub struct NewStoreCreditTransaction {
+    pub user_id: Uuid,
+    pub amount_in_cents: i64,
+    pub transaction_type: StoreCreditTransactionTypes,
+    pub order_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub product_instance_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub currency: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct StoreCreditBalance {
+    pub currency: String,
+    pub balance_in_cents: i64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayStoreCredit {
+    pub balances: Vec<StoreCreditBalance>,
+    pub transactions: Vec<StoreCreditTransaction>,
+}
+
+impl NewStoreCreditTransaction {
+    /// Records the entry, rejecting debits that would leave the user with a negative balance.
+    pub(crate) fn commit(&self, conn: &PgConnection) -> Result<StoreCreditTransaction, DatabaseError> {
+        if self.amount_in_cents == 0 {
+            return DatabaseError::validation_error("amount_in_cents", "Amount must not be zero");
+        }
+
+        // Lock the user so concurrent debits can't both spend the same balance
+        users::table
+            .filter(users::id.eq(self.user_id))
+            .select(users::id)
+            .for_update()
+            .first::<Uuid>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock user for store credit")?;
+        if self.amount_in_cents < 0
+            && StoreCreditTransaction::balance_for_user(self.user_id, &self.currency, conn)? + self.amount_in_cents < 0
+        {
+            return DatabaseError::validation_error("amount_in_cents", "Insufficient store credit");
+        }
+
+        let result: StoreCreditTransaction = diesel::insert_into(store_credit_transactions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create store credit transaction")?;
+
+        DomainEvent::create(
+            DomainEventTypes::StoreCreditTransactionCreated,
+            "Store credit transaction created".to_string(),
+            Tables::StoreCreditTransactions,
+            Some(result.id),
+            self.created_by,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl StoreCreditTransaction {
+    pub(crate) fn create(
+        user_id: Uuid,
+        amount_in_cents: i64,
+        currency: String,
+        transaction_type: StoreCreditTransactionTypes,
+        created_by: Option<Uuid>,
+    ) -> NewStoreCreditTransaction {
+        NewStoreCreditTransaction {
+            user_id,
+            amount_in_cents,
+            transaction_type,
+            order_id: None,
+            payment_id: None,
+            refund_id: None,
+            product_instance_id: None,
+            created_by,
+            currency,
+        }
+    }
+
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<StoreCreditTransaction>, DatabaseError> {
+        store_credit_transactions::table
+            .filter(store_credit_transactions::user_id.eq(user_id))
+            .order_by(store_credit_transactions::created_at.desc())
+            .then_order_by(store_credit_transactions::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load store credit transactions")
+    }
+
+    /// Credit can only be spent in the currency it was issued in so balances are kept per currency.
+    pub fn balance_for_user(user_id: Uuid, currency: &str, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct ResultForSum {
+            #[sql_type = "Nullable<BigInt>"]
+            s: Option<i64>,
+        };
+        let sum: ResultForSum = diesel::sql_query(
+            "SELECT CAST(SUM(amount_in_cents) AS BigInt) AS s FROM store_credit_transactions WHERE user_id = $1 AND currency = $2;",
+        )
+        .bind::<dUuid, _>(user_id)
+        .bind::<Text, _>(currency)
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not get store credit balance")?;
+        Ok(sum.s.unwrap_or(0))
+    }
+
+    pub fn balances_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<StoreCreditBalance>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "Text"]
+            currency: String,
+            #[sql_type = "BigInt"]
+            balance_in_cents: i64,
+        };
+        let results: Vec<R> = diesel::sql_query(
+            "SELECT currency, CAST(SUM(amount_in_cents) AS BigInt) AS balance_in_cents FROM store_credit_transactions WHERE user_id = $1 GROUP BY currency ORDER BY currency;",
+        )
+        .bind::<dUuid, _>(user_id)
+        .get_results(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not get store credit balances")?;
+        Ok(results
+            .into_iter()
+            .map(|r| StoreCreditBalance {
+                currency: r.currency,
+                balance_in_cents: r.balance_in_cents,
+            })
+            .collect())
+    }
+
+    pub fn for_display(user_id: Uuid, conn: &PgConnection) -> Result<DisplayStoreCredit, DatabaseError> {
+        Ok(DisplayStoreCredit {
+            balances: StoreCreditTransaction::balances_for_user(user_id, conn)?,
+            transactions: StoreCreditTransaction::find_for_user(user_id, conn)?,
+        })
+    }
+
+    /// Issues the refunded amount of a payment to the user as store credit instead of returning it
+    /// to the original payment method.
+    pub fn credit_refund(
+        user_id: Uuid,
+        refund: &Refund,
+        payment: &Payment,
+        amount_in_cents: i64,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<StoreCreditTransaction, DatabaseError> {
+        NewStoreCreditTransaction {
+            order_id: Some(refund.order_id),
+            payment_id: Some(payment.id),
+            refund_id: Some(refund.id),
+            ..StoreCreditTransaction::create(
+                user_id,
+                amount_in_cents,
+                payment.currency.clone(),
+                StoreCreditTransactionTypes::Refund,
+                Some(current_user_id),
+            )
+        }
+        .commit(conn)
+    }
+}
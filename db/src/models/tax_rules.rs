@@ -0,0 +1,225 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::tax_rules;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A sales tax or VAT charged on orders for events at a venue. Rules are configured for a whole
+/// region or for a single venue, venue rules replace the region's rules for that venue.
+#[derive(Clone, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "tax_rules"]
+pub struct TaxRule {
+    pub id: Uuid,
+    pub region_id: Option<Uuid>,
+    pub venue_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f64,
+    pub inclusive: bool,
+    pub taxes_fees: bool,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, Serialize)]
+#[table_name = "tax_rules"]
+pub struct TaxRuleEditableAttributes {
+    pub name: Option<String>,
+    pub rate_percent: Option<f64>,
+    pub inclusive: Option<bool>,
+    pub taxes_fees: Option<bool>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "tax_rules"]
+pub struct NewTaxRule {
+    pub region_id: Option<Uuid>,
+    pub venue_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f64,
+    pub inclusive: bool,
+    pub taxes_fees: bool,
+}
+
+impl NewTaxRule {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        if self.region_id.is_some() == self.venue_id.is_some() {
+            return DatabaseError::business_process_error("Tax rules must belong to either a region or a venue");
+        }
+        TaxRule::validate_attributes(&self.name, self.rate_percent)?;
+        let result: TaxRule = diesel::insert_into(tax_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRuleCreated,
+            format!("Tax rule {} created", self.name),
+            Tables::TaxRules,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
+
+impl TaxRule {
+    pub fn create_for_region(
+        region_id: Uuid,
+        name: String,
+        rate_percent: f64,
+        inclusive: bool,
+        taxes_fees: bool,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            region_id: Some(region_id),
+            venue_id: None,
+            name,
+            rate_percent,
+            inclusive,
+            taxes_fees,
+        }
+    }
+
+    pub fn create_for_venue(
+        venue_id: Uuid,
+        name: String,
+        rate_percent: f64,
+        inclusive: bool,
+        taxes_fees: bool,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            region_id: None,
+            venue_id: Some(venue_id),
+            name,
+            rate_percent,
+            inclusive,
+            taxes_fees,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        tax_rules::table
+            .find(id)
+            .filter(tax_rules::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading tax rule")
+    }
+
+    pub fn find_for_region(region_id: Uuid, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::region_id.eq(region_id))
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading tax rules")
+    }
+
+    pub fn find_for_venue(venue_id: Uuid, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::venue_id.eq(venue_id))
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading tax rules")
+    }
+
+    /// The rules that apply to sales for an event, several rules can apply at once (e.g. GST and
+    /// PST). Events without a venue are not taxed.
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        let venue = match event.venue(conn)? {
+            Some(venue) => venue,
+            None => return Ok(Vec::new()),
+        };
+        let rules = TaxRule::find_for_venue(venue.id, conn)?;
+        if !rules.is_empty() {
+            return Ok(rules);
+        }
+        match venue.region_id {
+            Some(region_id) => TaxRule::find_for_region(region_id, conn),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Tax owed on an amount. Inclusive rates are already part of the amount so the tax is the
+    /// portion of it above the pre-tax price.
+    pub fn tax_for(&self, amount_in_cents: i64) -> i64 {
+        if amount_in_cents <= 0 {
+            return 0;
+        }
+        let rate = self.rate_percent / 100f64;
+        if self.inclusive {
+            amount_in_cents - (amount_in_cents as f64 / (1f64 + rate)).round() as i64
+        } else {
+            (amount_in_cents as f64 * rate).round() as i64
+        }
+    }
+
+    pub fn update(
+        &self,
+        attributes: TaxRuleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TaxRule, DatabaseError> {
+        TaxRule::validate_attributes(
+            attributes.name.as_ref().unwrap_or(&self.name),
+            attributes.rate_percent.unwrap_or(self.rate_percent),
+        )?;
+
+        let result: TaxRule = diesel::update(self)
+            .set((&attributes, tax_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update tax rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRuleUpdated,
+            format!("Tax rule {} updated", result.name),
+            Tables::TaxRules,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Stops the rule applying to new orders, tax already charged under it is kept.
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let result = diesel::update(tax_rules::table.filter(tax_rules::id.eq(self.id)))
+            .set((
+                tax_rules::deleted_at.eq(dsl::now.nullable()),
+                tax_rules::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete tax rule")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TaxRuleDeleted,
+            format!("Tax rule {} deleted", self.name),
+            Tables::TaxRules,
+            Some(self.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_attributes(name: &str, rate_percent: f64) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name is required");
+        }
+        if !(rate_percent > 0f64 && rate_percent <= 100f64) {
+            return DatabaseError::validation_error("rate_percent", "Rate must be more than 0 and at most 100 percent");
+        }
+        Ok(())
+    }
+}
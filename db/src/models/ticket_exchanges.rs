@@ -0,0 +1,222 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::{orders, ticket_exchanges};
+use std::cmp;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+pub struct TicketExchange {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub original_order_id: Uuid,
+    pub refund_id: Uuid,
+    pub new_ticket_type_id: Uuid,
+    pub new_order_id: Uuid,
+    pub user_id: Uuid,
+    pub refunded_amount_in_cents: i64,
+    pub new_order_total_in_cents: i64,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "ticket_exchanges"]
+struct NewTicketExchange {
+    ticket_instance_id: Uuid,
+    original_order_id: Uuid,
+    refund_id: Uuid,
+    new_ticket_type_id: Uuid,
+    new_order_id: Uuid,
+    user_id: Uuid,
+    refunded_amount_in_cents: i64,
+    new_order_total_in_cents: i64,
+    created_by: Uuid,
+}
+
+impl TicketExchange {
+    /// Swaps a purchased ticket for a ticket of another ticket type belonging to the same
+    /// organization. The original ticket is refunded on its order and the new ticket is placed in a
+    /// new order for the ticket holder. The refunded amount is applied to the new order as exchange
+    /// credit, so the new order is only left awaiting payment when the new ticket costs more.
+    pub fn exchange(
+        ticket_instance_id: Uuid,
+        new_ticket_type_id: Uuid,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<TicketExchange, DatabaseError> {
+        let ticket = TicketInstance::find(ticket_instance_id, conn)?;
+        if ticket.status != TicketInstanceStatus::Purchased {
+            return DatabaseError::business_process_error("Only purchased tickets can be exchanged");
+        }
+        if ResaleListing::any_active_for_tickets(&[ticket.id], conn)? {
+            return DatabaseError::business_process_error("Tickets listed for resale cannot be exchanged");
+        }
+        if PaymentPlan::any_unpaid_for_tickets(&[ticket.id], conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets cannot be exchanged until their payment plan is paid in full",
+            );
+        }
+        let order_item = match ticket.order_item_id {
+            Some(order_item_id) => OrderItem::find(order_item_id, conn)?,
+            None => return DatabaseError::business_process_error("Ticket has not been purchased"),
+        };
+        if order_item.package_id.is_some() {
+            return DatabaseError::business_process_error("Tickets purchased as part of a package cannot be exchanged");
+        }
+        let mut original_order = Order::find(order_item.order_id, conn)?;
+        if original_order.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Only tickets on paid orders can be exchanged");
+        }
+
+        let ticket_type = ticket.ticket_type(conn)?;
+        let new_ticket_type = TicketType::find(new_ticket_type_id, conn)?;
+        if new_ticket_type.id == ticket_type.id {
+            return DatabaseError::business_process_error("Ticket is already of this ticket type");
+        }
+        if new_ticket_type.event(conn)?.organization_id != ticket_type.event(conn)?.organization_id {
+            return DatabaseError::business_process_error(
+                "Tickets can only be exchanged for ticket types of the same organization",
+            );
+        }
+        let user = match Wallet::find(ticket.wallet_id, conn)?.user_id {
+            Some(user_id) => User::find(user_id, conn)?,
+            None => return DatabaseError::business_process_error("Ticket is not held by a user"),
+        };
+
+        let (refund, refunded_amount_in_cents) = original_order.refund(
+            &[RefundItemRequest {
+                order_item_id: order_item.id,
+                ticket_instance_id: Some(ticket.id),
+            }],
+            current_user_id,
+            Some(format!("Exchanged for {}", new_ticket_type.name)),
+            false,
+            conn,
+        )?;
+
+        let mut new_order = TicketExchange::create_order(&user, conn)?;
+        new_order.update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: new_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            true,
+            conn,
+        )?;
+        let new_order_total_in_cents = new_order.calculate_total(conn)?;
+        new_order.add_exchange_payment(
+            current_user_id,
+            cmp::min(refunded_amount_in_cents, new_order_total_in_cents),
+            conn,
+        )?;
+
+        let ticket_exchange: TicketExchange = diesel::insert_into(ticket_exchanges::table)
+            .values(NewTicketExchange {
+                ticket_instance_id: ticket.id,
+                original_order_id: original_order.id,
+                refund_id: refund.id,
+                new_ticket_type_id: new_ticket_type.id,
+                new_order_id: new_order.id,
+                user_id: user.id,
+                refunded_amount_in_cents,
+                new_order_total_in_cents,
+                created_by: current_user_id,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ticket exchange")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketInstanceExchanged,
+            format!("Ticket exchanged from {} to {}", ticket_type.name, new_ticket_type.name),
+            Tables::TicketInstances,
+            Some(ticket.id),
+            Some(current_user_id),
+            Some(json!({
+                "ticket_exchange_id": ticket_exchange.id,
+                "original_order_id": original_order.id,
+                "refund_id": refund.id,
+                "new_order_id": new_order.id,
+                "old_ticket_type_id": ticket_type.id,
+                "new_ticket_type_id": new_ticket_type.id,
+                "price_difference_in_cents": ticket_exchange.price_difference_in_cents(),
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(ticket_exchange)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TicketExchange, DatabaseError> {
+        ticket_exchanges::table
+            .filter(ticket_exchanges::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find ticket exchange")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<TicketExchange>, DatabaseError> {
+        ticket_exchanges::table
+            .filter(
+                ticket_exchanges::original_order_id
+                    .eq(order_id)
+                    .or(ticket_exchanges::new_order_id.eq(order_id)),
+            )
+            .order_by(ticket_exchanges::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket exchanges for order")
+    }
+
+    /// Positive when the ticket holder owes money for the exchange, negative when they are owed a refund.
+    pub fn price_difference_in_cents(&self) -> i64 {
+        self.new_order_total_in_cents - self.refunded_amount_in_cents
+    }
+
+    /// The part of the refunded amount that is kept to pay for the new order rather than being
+    /// returned to the original payment.
+    pub fn credit_applied_in_cents(&self) -> i64 {
+        cmp::min(self.refunded_amount_in_cents, self.new_order_total_in_cents)
+    }
+
+    pub fn original_order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.original_order_id, conn)
+    }
+
+    pub fn new_order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        Order::find(self.new_order_id, conn)
+    }
+
+    pub fn refund(&self, conn: &PgConnection) -> Result<Refund, DatabaseError> {
+        Refund::find(self.refund_id, conn)
+    }
+
+    /// Exchange orders are not linked to the user's cart so that tickets already in the cart are
+    /// left alone.
+    fn create_order(user: &User, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        let order: Order = diesel::insert_into(orders::table)
+            .values((
+                orders::user_id.eq(user.id),
+                orders::status.eq(OrderStatus::Draft),
+                orders::order_type.eq(OrderTypes::Cart),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create exchange order")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrderCreated,
+            "Order created".into(),
+            Tables::Orders,
+            Some(order.id),
+            Some(user.id),
+            Some(json!(order)),
+        )
+        .commit(conn)?;
+
+        Ok(order)
+    }
+}
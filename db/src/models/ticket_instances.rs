@@ -42,6 +42,8 @@ pub struct TicketInstance {
     pub first_name_override: Option<String>,
     pub last_name_override: Option<String>,
     pub check_in_source: Option<CheckInSource>,
+    pub seat_id: Option<Uuid>,
+    pub entry_slot_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Clone, Deserialize, Serialize)]
@@ -105,7 +107,8 @@ impl TicketInstance {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         let query = include_str!("../queries/release_tickets.sql");
-        let new_status = if self.ticket_type(conn)?.status == TicketTypeStatus::Cancelled {
+        let ticket_type = self.ticket_type(conn)?;
+        let new_status = if ticket_type.status == TicketTypeStatus::Cancelled {
             TicketInstanceStatus::Nullified
         } else {
             TicketInstanceStatus::Available
@@ -126,6 +129,8 @@ impl TicketInstance {
 
         if new_status == TicketInstanceStatus::Nullified {
             tickets[0].create_nullified_domain_event(Some(user_id), conn)?;
+        } else {
+            WaitlistEntry::queue_processing(ticket_type.id, conn)?;
         }
 
         Ok(())
@@ -197,6 +202,17 @@ impl TicketInstance {
                 transfers::transfer_key.nullable(),
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
+                sql::<Nullable<Text>>(
+                    "(SELECT ss.name FROM seats s JOIN seating_sections ss ON ss.id = s.seating_section_id WHERE s.id = ticket_instances.seat_id)",
+                ),
+                sql::<Nullable<Text>>("(SELECT s.row_name FROM seats s WHERE s.id = ticket_instances.seat_id)"),
+                sql::<Nullable<Text>>("(SELECT s.seat_number FROM seats s WHERE s.id = ticket_instances.seat_id)"),
+                sql::<Nullable<Timestamp>>(
+                    "(SELECT es.entry_start FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id)",
+                ),
+                sql::<Nullable<Timestamp>>(
+                    "(SELECT es.entry_end FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id)",
+                ),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -298,6 +314,17 @@ impl TicketInstance {
                 transfers::transfer_key.nullable(),
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
+                sql::<Nullable<Text>>(
+                    "(SELECT ss.name FROM seats s JOIN seating_sections ss ON ss.id = s.seating_section_id WHERE s.id = ticket_instances.seat_id)",
+                ),
+                sql::<Nullable<Text>>("(SELECT s.row_name FROM seats s WHERE s.id = ticket_instances.seat_id)"),
+                sql::<Nullable<Text>>("(SELECT s.seat_number FROM seats s WHERE s.id = ticket_instances.seat_id)"),
+                sql::<Nullable<Timestamp>>(
+                    "(SELECT es.entry_start FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id)",
+                ),
+                sql::<Nullable<Timestamp>>(
+                    "(SELECT es.entry_end FROM entry_slots es WHERE es.id = ticket_instances.entry_slot_id)",
+                ),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -381,6 +408,13 @@ impl TicketInstance {
             Some("Expiration date was not set on cart prior to reserving tickets".to_string()),
         ))?;
 
+        // Tickets taken from a hold were already counted against the capacity pool when held
+        if ticket_holding_id.is_none() {
+            if let Some(capacity_pool_id) = TicketType::find(ticket_type_id, conn)?.capacity_pool_id {
+                CapacityPool::confirm_capacity_available(capacity_pool_id, quantity, conn)?;
+            }
+        }
+
         let query = include_str!("../queries/reserve_tickets.sql");
         let q = diesel::sql_query(query)
             .bind::<sql_types::Uuid, _>(order_item.id)
@@ -388,7 +422,7 @@ impl TicketInstance {
             .bind::<sql_types::Uuid, _>(ticket_type_id)
             .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
             .bind::<BigInt, _>(quantity as i64);
-        let tickets: Vec<TicketInstance> = q
+        let mut tickets: Vec<TicketInstance> = q
             .get_results(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not reserve tickets")?;
 
@@ -418,9 +452,118 @@ impl TicketInstance {
             }
         }
 
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        if let Some(seating_section_id) = ticket_type.seating_section_id {
+            let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+            let available_seats = Seat::find_available(seating_section_id, ticket_type.event_id, &ticket_ids, conn)?;
+            let seats = match Seat::best_available(&available_seats, tickets.len()) {
+                Some(seats) => seats,
+                None => {
+                    return DatabaseError::validation_error(
+                        "quantity",
+                        "Could not reserve tickets, not enough seats are available",
+                    );
+                }
+            };
+            let mut seated_tickets = Vec::new();
+            for (ticket, seat) in tickets.iter().zip(seats.iter()) {
+                seated_tickets.push(ticket.set_seat(Some(seat.id), conn)?);
+            }
+            tickets = seated_tickets;
+        }
+
+        if !tickets.is_empty() && !EntrySlot::find_for_ticket_type(ticket_type.id, conn)?.is_empty() {
+            let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+            let entry_slot =
+                match EntrySlot::find_first_available(ticket_type.id, tickets.len() as i64, &ticket_ids, conn)? {
+                    Some(entry_slot) => entry_slot,
+                    None => {
+                        return DatabaseError::validation_error(
+                            "quantity",
+                            "Could not reserve tickets, no entry slot has enough capacity",
+                        );
+                    }
+                };
+            tickets = TicketInstance::assign_entry_slot(&tickets, &entry_slot, conn)?;
+        }
+
         Ok(tickets)
     }
 
+    /// Moves the given tickets into `entry_slot`, which must have room for all of them.
+    pub fn assign_entry_slot(
+        tickets: &[TicketInstance],
+        entry_slot: &EntrySlot,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        if entry_slot.entry_end <= Utc::now().naive_utc() {
+            return DatabaseError::validation_error("entry_slot_id", "Entry slot has already ended");
+        }
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+        if entry_slot.remaining_capacity(&ticket_ids, conn)? < tickets.len() as i64 {
+            return DatabaseError::validation_error("entry_slot_id", "Entry slot does not have enough capacity");
+        }
+
+        diesel::update(ticket_instances::table.filter(ticket_instances::id.eq_any(&ticket_ids)))
+            .set((
+                ticket_instances::entry_slot_id.eq(entry_slot.id),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign entry slot to tickets")
+    }
+
+    /// Assigns the given seats to the given tickets, which must all belong to ticket types bound to
+    /// `seating_section_id`. Tickets already sitting in one of the requested seats keep it.
+    pub fn assign_seats(
+        tickets: &[TicketInstance],
+        seat_ids: &[Uuid],
+        seating_section_id: Uuid,
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        if tickets.len() != seat_ids.len() {
+            return DatabaseError::validation_error("seat_ids", "Number of seats must match the number of tickets");
+        }
+
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+        let available_seat_ids: Vec<Uuid> = Seat::find_available(seating_section_id, event_id, &ticket_ids, conn)?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        if seat_ids.iter().any(|id| !available_seat_ids.contains(id)) {
+            return DatabaseError::validation_error("seat_ids", "One or more of the selected seats are not available");
+        }
+
+        let mut unassigned_seat_ids: Vec<Uuid> = seat_ids
+            .iter()
+            .filter(|id| !tickets.iter().any(|t| t.seat_id == Some(**id)))
+            .cloned()
+            .collect();
+        let mut results = Vec::new();
+        for ticket in tickets {
+            match ticket.seat_id {
+                Some(seat_id) if seat_ids.contains(&seat_id) => results.push(ticket.clone()),
+                _ => {
+                    let seat_id = unassigned_seat_ids.remove(0);
+                    results.push(ticket.set_seat(Some(seat_id), conn)?);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn set_seat(&self, seat_id: Option<Uuid>, conn: &PgConnection) -> Result<TicketInstance, DatabaseError> {
+        diesel::update(self)
+            .set((
+                ticket_instances::seat_id.eq(seat_id),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seat to ticket")
+    }
+
     fn validate_record(&self, update_attrs: &UpdateTicketInstanceAttributes) -> Result<(), DatabaseError> {
         let mut validation_errors = Ok(());
         let first_name = update_attrs
@@ -490,6 +633,8 @@ impl TicketInstance {
             for ticket in &tickets {
                 ticket.create_nullified_domain_event(user_id, conn)?;
             }
+        } else if let Some(ticket_type_id) = order_item.ticket_type_id {
+            WaitlistEntry::queue_processing(ticket_type_id, conn)?;
         }
 
         Ok(tickets)
@@ -503,6 +648,12 @@ impl TicketInstance {
         from_hold_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        if from_hold_id.is_none() {
+            if let Some(capacity_pool_id) = TicketType::find(ticket_type_id, conn)?.capacity_pool_id {
+                CapacityPool::confirm_capacity_available(capacity_pool_id, quantity, conn)?;
+            }
+        }
+
         let query = include_str!("../queries/add_tickets_to_hold.sql");
         let q = diesel::sql_query(query)
             .bind::<sql_types::Uuid, _>(hold_id)
@@ -587,6 +738,7 @@ impl TicketInstance {
             )
             .commit(conn)?;
         }
+        WaitlistEntry::queue_processing(ticket_type_id, conn)?;
 
         Ok(tickets)
     }
@@ -776,12 +928,25 @@ impl TicketInstance {
             .find(ticket_id)
             .first(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+        let mut result = RedeemResults::TicketRedeemSuccess;
         if ticket.has_pending_transfer(conn)? {
             return Ok(RedeemResults::TicketTransferInProcess);
+        } else if PaymentPlan::any_unpaid_for_tickets(&[ticket.id], conn)? {
+            return Ok(RedeemResults::TicketPaymentPlanUnpaid);
         } else if ticket.status == TicketInstanceStatus::Purchased
             && ticket.redeem_key.is_some()
             && ticket.redeem_key.clone().unwrap() == redeem_key
         {
+            if let Some(entry_slot_id) = ticket.entry_slot_id {
+                let entry_slot = EntrySlot::find(entry_slot_id, conn)?;
+                if !entry_slot.is_within_entry_window(Utc::now().naive_utc()) {
+                    match entry_slot.ticket_type(conn)?.entry_window_policy {
+                        EntryWindowPolicy::Reject => return Ok(RedeemResults::TicketOutsideEntryWindow),
+                        EntryWindowPolicy::Warn => result = RedeemResults::TicketRedeemedOutsideEntryWindow,
+                    }
+                }
+            }
+
             diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket_id)))
                 .set((
                     ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
@@ -793,6 +958,11 @@ impl TicketInstance {
                 .execute(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
 
+            // The seller has used the ticket so it can no longer be resold
+            if let Some(listing) = ResaleListing::find_active_for_ticket(ticket.id, conn)? {
+                listing.cancel(Some(user_id), conn)?;
+            }
+
             DomainEvent::create(
                 DomainEventTypes::TicketInstanceRedeemed,
                 "Ticket redeemed".to_string(),
@@ -807,7 +977,7 @@ impl TicketInstance {
         } else {
             return Ok(RedeemResults::TicketInvalid);
         }
-        Ok(RedeemResults::TicketRedeemSuccess)
+        Ok(result)
     }
 
     pub fn show_redeemable_ticket(ticket_id: Uuid, conn: &PgConnection) -> Result<RedeemableTicket, DatabaseError> {
@@ -908,6 +1078,14 @@ impl TicketInstance {
         //Confirm that tickets are purchased and owned by user
         let (wallet_id, ticket_ids_and_updated_at) =
             TicketInstance::verify_tickets_belong_to_user(user.id, ticket_ids, conn)?;
+        if ResaleListing::any_active_for_tickets(ticket_ids, conn)? {
+            return DatabaseError::business_process_error("Tickets listed for resale cannot be transferred");
+        }
+        if PaymentPlan::any_unpaid_for_tickets(ticket_ids, conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets cannot be transferred until their payment plan is paid in full",
+            );
+        }
 
         //Generate transfer_key and store keys and set transfer_expiry date
         let transfer_key = Uuid::new_v4();
@@ -1139,6 +1317,11 @@ pub struct DisplayTicket {
     pub transfer_key: Option<Uuid>,
     pub transfer_address: Option<String>,
     pub check_in_source: Option<CheckInSource>,
+    pub section_name: Option<String>,
+    pub row_name: Option<String>,
+    pub seat_number: Option<String>,
+    pub entry_start: Option<NaiveDateTime>,
+    pub entry_end: Option<NaiveDateTime>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1181,6 +1364,16 @@ pub struct DisplayTicketIntermediary {
     pub transfer_address: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub check_in_source: Option<CheckInSource>,
+    #[sql_type = "Nullable<Text>"]
+    pub section_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub row_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub entry_start: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub entry_end: Option<NaiveDateTime>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1223,6 +1416,11 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             transfer_key: ticket_intermediary.transfer_key,
             transfer_address: ticket_intermediary.transfer_address,
             check_in_source: ticket_intermediary.check_in_source,
+            section_name: ticket_intermediary.section_name,
+            row_name: ticket_intermediary.row_name,
+            seat_number: ticket_intermediary.seat_number,
+            entry_start: ticket_intermediary.entry_start,
+            entry_end: ticket_intermediary.entry_end,
         }
     }
 }
@@ -1282,9 +1480,15 @@ pub enum RedeemResults {
     TicketAlreadyRedeemed,
     TicketInvalid,
     TicketTransferInProcess,
+    /// The ticket's entry slot is not open and its ticket type rejects entry outside the window
+    TicketOutsideEntryWindow,
+    /// The ticket was redeemed but its entry slot is not open, door staff should be warned
+    TicketRedeemedOutsideEntryWindow,
+    /// The ticket's order is being paid with a payment plan that has not been paid in full
+    TicketPaymentPlanUnpaid,
 }
 
-fn generate_redeem_key(len: u32) -> String {
+pub(crate) fn generate_redeem_key(len: u32) -> String {
     let hash_char_list = vec![
         '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'M', 'N', 'P',
         'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
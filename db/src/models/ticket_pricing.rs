@@ -3,15 +3,34 @@ use dev::times;
 use diesel;
 use diesel::dsl::{self, exists, select, sql};
 use diesel::prelude::*;
-use diesel::sql_types::{Bool, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Nullable, Timestamp, Uuid as dUuid};
 use models::*;
 use schema::{order_items, orders, ticket_pricing};
+use serde_with::rust::double_option;
 use std::borrow::Cow;
+use std::cmp;
 use utils::errors::*;
 use uuid::Uuid;
 use validator::*;
 use validators::{self, *};
 
+// Matches order items sold at a ticket pricing or at any of the earlier versions it replaced
+const PRICING_VERSIONS_START: &str = "
+    order_items.ticket_pricing_id in
+    (WITH RECURSIVE ticket_pricing_r(id) AS (
+        SELECT tp.*
+        FROM ticket_pricing AS tp
+        WHERE tp.id =
+";
+const PRICING_VERSIONS_END: &str = "
+    UNION ALL
+    SELECT tp.*
+    FROM ticket_pricing_r AS p, ticket_pricing AS tp
+    WHERE p.previous_ticket_pricing_id = tp.id
+    )
+    SELECT id FROM ticket_pricing_r)
+";
+
 sql_function!(fn ticket_pricing_no_overlapping_periods(id: dUuid, ticket_type_id: dUuid, start_date: Timestamp, end_date: Timestamp, is_box_office_only: Bool, is_default_status: Bool) -> Bool);
 
 #[derive(Clone, Identifiable, Associations, Queryable, PartialEq, Debug, Serialize)]
@@ -29,6 +48,10 @@ pub struct TicketPricing {
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
     pub previous_ticket_pricing_id: Option<Uuid>,
+    pub quantity_limit: Option<i64>,
+    /// Price set by the organizer before pricing rules adjusted it, `None` unless this pricing
+    /// was published by the pricing rules.
+    pub base_price_in_cents: Option<i64>,
 }
 
 #[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
@@ -39,6 +62,8 @@ pub struct TicketPricingEditableAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub is_box_office_only: Option<bool>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub quantity_limit: Option<Option<i64>>,
 }
 
 impl TicketPricing {
@@ -48,24 +73,9 @@ impl TicketPricing {
                 .inner_join(orders::table.on(orders::id.eq(order_items::order_id)))
                 .filter(orders::status.eq(OrderStatus::Paid).or(orders::expires_at.ge(dsl::now)))
                 .filter(
-                    sql("
-                        order_items.ticket_pricing_id in
-                        (WITH RECURSIVE ticket_pricing_r(id) AS (
-                            SELECT tp.*
-                            FROM ticket_pricing AS tp
-                            WHERE tp.id =
-                    ")
-                    .bind::<dUuid, _>(self.id)
-                    .sql(
-                        "
-                        UNION ALL
-                        SELECT tp.*
-                        FROM ticket_pricing_r AS p, ticket_pricing AS tp
-                        WHERE p.previous_ticket_pricing_id = tp.id
-                        )
-                        SELECT id FROM ticket_pricing_r)
-                    ",
-                    ),
+                    sql(PRICING_VERSIONS_START)
+                        .bind::<dUuid, _>(self.id)
+                        .sql(PRICING_VERSIONS_END),
                 ),
         ))
         .get_result(conn)
@@ -94,6 +104,8 @@ impl TicketPricing {
             price_in_cents,
             is_box_office_only,
             previous_ticket_pricing_id,
+            quantity_limit: None,
+            base_price_in_cents: None,
         }
     }
 
@@ -106,6 +118,11 @@ impl TicketPricing {
                 attributes.end_date.unwrap_or(self.end_date),
             ),
         );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.quantity_limit",
+            TicketPricing::quantity_limit_valid(attributes.quantity_limit.unwrap_or(self.quantity_limit)),
+        );
         Ok(validation_errors?)
     }
 
@@ -115,7 +132,8 @@ impl TicketPricing {
             && (attributes.start_date.is_none() || Some(self.start_date) == attributes.start_date)
             && (attributes.end_date.is_none() || Some(self.end_date) == attributes.end_date)
             && (attributes.is_box_office_only.is_none()
-                || Some(self.is_box_office_only) == attributes.is_box_office_only))
+                || Some(self.is_box_office_only) == attributes.is_box_office_only)
+            && (attributes.quantity_limit.is_none() || Some(self.quantity_limit) == attributes.quantity_limit))
     }
 
     pub fn update(
@@ -130,9 +148,18 @@ impl TicketPricing {
                 || attributes.price_in_cents.is_none()
                 || attributes.price_in_cents == Some(self.price_in_cents)
             {
-                // No orders affected or price does not change, update existing record
+                // No orders affected or price does not change, update existing record. Setting the
+                // price by hand replaces any adjustment made by the pricing rules.
+                let base_price_in_cents = match attributes.price_in_cents {
+                    Some(price_in_cents) if price_in_cents != self.price_in_cents => None,
+                    _ => self.base_price_in_cents,
+                };
                 let result = diesel::update(self)
-                    .set((&attributes, ticket_pricing::updated_at.eq(dsl::now)))
+                    .set((
+                        &attributes,
+                        ticket_pricing::base_price_in_cents.eq(base_price_in_cents),
+                        ticket_pricing::updated_at.eq(dsl::now),
+                    ))
                     .get_result(conn)
                     .to_db_error(ErrorCode::UpdateError, "Could not update ticket_pricing");
 
@@ -148,7 +175,7 @@ impl TicketPricing {
                 result
             } else {
                 // Orders affected, create new ticket pricing and delete old
-                let new_ticket_pricing = TicketPricing::create(
+                let mut new_ticket_pricing = TicketPricing::create(
                     self.ticket_type_id,
                     attributes.name.unwrap_or(self.name.clone()),
                     attributes.start_date.unwrap_or(self.start_date),
@@ -158,6 +185,7 @@ impl TicketPricing {
                     Some(self.status),
                     Some(self.id),
                 );
+                new_ticket_pricing.quantity_limit = attributes.quantity_limit.unwrap_or(self.quantity_limit);
                 self.destroy(current_user_id, conn)?;
                 new_ticket_pricing.commit(current_user_id, conn)
             }
@@ -310,6 +338,253 @@ impl TicketPricing {
         Ok(())
     }
 
+    /// Number of tickets sold at this pricing, or any earlier version of it, by paid orders and
+    /// unexpired carts. Tickets bought through holds or packages are not counted. Carts release
+    /// their claim when they expire or their tickets are removed.
+    pub fn claimed_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        self.sold_quantity(false, conn)
+    }
+
+    /// Number of tickets sold at this pricing, or any earlier version of it, by paid orders.
+    pub fn paid_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        self.sold_quantity(true, conn)
+    }
+
+    fn sold_quantity(&self, paid_only: bool, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        let mut query = order_items::table
+            .inner_join(orders::table.on(orders::id.eq(order_items::order_id)))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .filter(order_items::hold_id.is_null())
+            .filter(order_items::package_id.is_null())
+            .filter(
+                sql(PRICING_VERSIONS_START)
+                    .bind::<dUuid, _>(self.id)
+                    .sql(PRICING_VERSIONS_END),
+            )
+            .select(sql::<Nullable<BigInt>>(
+                "CAST(SUM(order_items.quantity - order_items.refunded_quantity) AS BIGINT)",
+            ))
+            .into_boxed();
+        if paid_only {
+            query = query.filter(orders::status.eq(OrderStatus::Paid));
+        } else {
+            query = query.filter(orders::status.eq(OrderStatus::Paid).or(orders::expires_at.ge(dsl::now)));
+        }
+
+        let sold_quantity: Option<i64> = query.first(conn).to_db_error(
+            ErrorCode::QueryError,
+            "Could not load claimed quantity for ticket pricing",
+        )?;
+        Ok(sold_quantity.unwrap_or(0))
+    }
+
+    /// Tickets left at this price for pricing limited to a quantity, `None` when unlimited.
+    pub fn remaining_quantity(&self, conn: &PgConnection) -> Result<Option<i64>, DatabaseError> {
+        match self.quantity_limit {
+            Some(quantity_limit) => Ok(Some(cmp::max(quantity_limit - self.claimed_quantity(conn)?, 0))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the ticket pricing for buying `quantity` more tickets. Pricing limited to a quantity
+    /// is locked while its remaining quantity is checked so concurrent checkouts cannot oversell it.
+    /// Sales at that price only end, moving on to the next pricing period, once paid orders have
+    /// bought the full quantity. Until then requests for more tickets than remain overflow into the
+    /// next pricing period, as tickets held in carts may still be released.
+    pub fn claim_current_ticket_pricing(
+        ticket_type_id: Uuid,
+        box_office_pricing: bool,
+        quantity: u32,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketPricing, DatabaseError> {
+        loop {
+            let ticket_pricing =
+                TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, false, conn)?;
+            if ticket_pricing.quantity_limit.is_none() {
+                return Ok(ticket_pricing);
+            }
+
+            // Another checkout may have ended sales at this price while waiting for the lock
+            let locked_ticket_pricing: Option<TicketPricing> = ticket_pricing::table
+                .filter(ticket_pricing::id.eq(ticket_pricing.id))
+                .filter(ticket_pricing::status.eq(ticket_pricing.status))
+                .filter(ticket_pricing::end_date.gt(dsl::now))
+                .for_update()
+                .first(conn)
+                .optional()
+                .to_db_error(ErrorCode::QueryError, "Could not lock ticket pricing")?;
+
+            if let Some(ticket_pricing) = locked_ticket_pricing {
+                if ticket_pricing.paid_quantity(conn)? < ticket_pricing.quantity_limit.unwrap_or(0) {
+                    return ticket_pricing.claim_or_overflow(quantity as i64, conn);
+                }
+                ticket_pricing.end_sales(current_user_id, conn)?;
+            }
+        }
+    }
+
+    /// Returns the first pricing, starting with this one, with `quantity` tickets remaining.
+    fn claim_or_overflow(self, quantity: i64, conn: &PgConnection) -> Result<TicketPricing, DatabaseError> {
+        let mut ticket_pricing = self;
+        loop {
+            if ticket_pricing.remaining_quantity(conn)?.unwrap_or(quantity) >= quantity {
+                return Ok(ticket_pricing);
+            }
+            ticket_pricing = match ticket_pricing.next_ticket_pricing(conn)? {
+                Some(next_ticket_pricing) => next_ticket_pricing,
+                None => {
+                    return DatabaseError::business_process_error("Not enough tickets remain at the current price");
+                }
+            };
+        }
+    }
+
+    /// The published pricing period following this one, locked until the end of the transaction.
+    fn next_ticket_pricing(&self, conn: &PgConnection) -> Result<Option<TicketPricing>, DatabaseError> {
+        ticket_pricing::table
+            .filter(ticket_pricing::ticket_type_id.eq(self.ticket_type_id))
+            .filter(ticket_pricing::status.eq(TicketPricingStatus::Published))
+            .filter(ticket_pricing::is_box_office_only.eq(self.is_box_office_only))
+            .filter(ticket_pricing::start_date.ge(self.end_date))
+            .order_by(ticket_pricing::start_date)
+            .for_update()
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load next ticket pricing")
+    }
+
+    /// Claims `quantity` tickets at this price again for an expired cart being refreshed, the
+    /// cart's earlier claim having been released when it expired.
+    pub(crate) fn reclaim(&self, quantity: i64, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.quantity_limit.is_none() {
+            return Ok(());
+        }
+        let ticket_pricing: TicketPricing = ticket_pricing::table
+            .filter(ticket_pricing::id.eq(self.id))
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock ticket pricing")?;
+        if ticket_pricing.remaining_quantity(conn)?.unwrap_or(0) < quantity {
+            return DatabaseError::business_process_error("Not enough tickets remain at the current price");
+        }
+        Ok(())
+    }
+
+    /// Ends sales at this price now and moves the start of the following pricing period up to
+    /// take its place.
+    fn end_sales(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                ticket_pricing::end_date.eq(dsl::now),
+                ticket_pricing::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update end date on Ticket Pricing")?;
+
+        let next_ticket_pricing = self.next_ticket_pricing(conn)?;
+        if let Some(next_ticket_pricing) = next_ticket_pricing.as_ref() {
+            diesel::update(next_ticket_pricing)
+                .set((
+                    ticket_pricing::start_date.eq(dsl::now),
+                    ticket_pricing::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update start date on Ticket Pricing")?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::TicketPricingSalesEnded,
+            format!("Sales have ended on '{}'", self.name),
+            Tables::TicketPricing,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "old_end_date": self.end_date,
+                "quantity_limit": self.quantity_limit,
+                "next_ticket_pricing_id": next_ticket_pricing.map(|t| t.id)
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Publishes a price set by the pricing rules for online sales of the ticket type. The current
+    /// pricing period ends now and a new version of it continues at the adjusted price until the
+    /// period would have ended. When only the default pricing applies, a new period runs until the
+    /// next scheduled period or the end of the ticket type's sales.
+    pub(crate) fn publish_adjusted_price(
+        ticket_type: &TicketType,
+        current_ticket_pricing: &TicketPricing,
+        price_in_cents: i64,
+        base_price_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketPricing, DatabaseError> {
+        let mut new_ticket_pricing = if current_ticket_pricing.status == TicketPricingStatus::Published {
+            let ended_ticket_pricing: TicketPricing = diesel::update(current_ticket_pricing)
+                .set((
+                    ticket_pricing::end_date.eq(dsl::now),
+                    ticket_pricing::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update end date on Ticket Pricing")?;
+
+            let mut new_ticket_pricing = TicketPricing::create(
+                ticket_type.id,
+                current_ticket_pricing.name.clone(),
+                ended_ticket_pricing.end_date,
+                current_ticket_pricing.end_date,
+                price_in_cents,
+                false,
+                Some(TicketPricingStatus::Published),
+                Some(current_ticket_pricing.id),
+            );
+            new_ticket_pricing.quantity_limit = current_ticket_pricing.quantity_limit;
+            new_ticket_pricing
+        } else {
+            let now: NaiveDateTime = select(dsl::now)
+                .get_result(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load current time")?;
+            let next_start_date: Option<NaiveDateTime> = ticket_pricing::table
+                .filter(ticket_pricing::ticket_type_id.eq(ticket_type.id))
+                .filter(ticket_pricing::status.eq(TicketPricingStatus::Published))
+                .filter(ticket_pricing::is_box_office_only.eq(false))
+                .filter(ticket_pricing::start_date.gt(now))
+                .select(dsl::min(ticket_pricing::start_date))
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load next ticket pricing")?;
+            let end_date = match next_start_date {
+                Some(next_start_date) => cmp::min(next_start_date, ticket_type.end_date(conn)?),
+                None => ticket_type.end_date(conn)?,
+            };
+
+            TicketPricing::create(
+                ticket_type.id,
+                ticket_type.name.clone(),
+                now,
+                end_date,
+                price_in_cents,
+                false,
+                Some(TicketPricingStatus::Published),
+                None,
+            )
+        };
+        new_ticket_pricing.base_price_in_cents = Some(base_price_in_cents);
+        new_ticket_pricing.commit(current_user_id, conn)
+    }
+
+    fn quantity_limit_valid(quantity_limit: Option<i64>) -> Result<(), ValidationError> {
+        if quantity_limit.unwrap_or(1) < 1 {
+            return Err(create_validation_error(
+                "number_must_be_positive",
+                "Quantity limit must be at least 1",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn get_default(ticket_type_id: Uuid, conn: &PgConnection) -> Result<TicketPricing, DatabaseError> {
         ticket_pricing::table
             .filter(ticket_pricing::ticket_type_id.eq(ticket_type_id))
@@ -388,6 +663,8 @@ pub struct NewTicketPricing {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     previous_ticket_pricing_id: Option<Uuid>,
+    quantity_limit: Option<i64>,
+    base_price_in_cents: Option<i64>,
 }
 
 impl NewTicketPricing {
@@ -409,6 +686,12 @@ impl NewTicketPricing {
             ),
         );
 
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.quantity_limit",
+            TicketPricing::quantity_limit_valid(self.quantity_limit),
+        );
+
         Ok(validation_errors?)
     }
 
@@ -44,6 +44,9 @@ pub struct TicketType {
     pub web_sales_enabled: bool,
     pub box_office_sales_enabled: bool,
     pub app_sales_enabled: bool,
+    pub seating_section_id: Option<Uuid>,
+    pub entry_window_policy: EntryWindowPolicy,
+    pub capacity_pool_id: Option<Uuid>,
 }
 
 impl PartialOrd for TicketType {
@@ -75,6 +78,9 @@ pub struct TicketTypeEditableAttributes {
     pub box_office_sales_enabled: Option<bool>,
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub seating_section_id: Option<Option<Uuid>>,
+    pub entry_window_policy: Option<EntryWindowPolicy>,
 }
 
 impl TicketType {
@@ -352,11 +358,55 @@ impl TicketType {
             .to_db_error(ErrorCode::QueryError, "Could not find ticket type")
     }
 
+    pub fn find_by_seating_section_id(
+        seating_section_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketType>, DatabaseError> {
+        ticket_types::table
+            .filter(ticket_types::seating_section_id.eq(seating_section_id))
+            .filter(ticket_types::deleted_at.is_null())
+            .order_by(ticket_types::rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for seating section")
+    }
+
+    pub fn seating_section(&self, conn: &PgConnection) -> Result<Option<SeatingSection>, DatabaseError> {
+        match self.seating_section_id {
+            Some(seating_section_id) => Ok(Some(SeatingSection::find(seating_section_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
     pub fn validate_record(
         &self,
         attributes: &mut TicketTypeEditableAttributes,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
+        if let Some(Some(seating_section_id)) = attributes.seating_section_id {
+            if Some(seating_section_id) != self.seating_section_id {
+                let venue_id = self.event(conn)?.venue_id;
+                let stage = SeatingSection::find(seating_section_id, conn)?.stage(conn)?;
+                if venue_id != Some(stage.venue_id) {
+                    return DatabaseError::validation_error(
+                        "seating_section_id",
+                        "Seating section must belong to a stage at the event's venue",
+                    );
+                }
+                if self.valid_sold_and_reserved_ticket_count(conn)? > 0 {
+                    return DatabaseError::validation_error(
+                        "seating_section_id",
+                        "Seating section cannot be changed once tickets have been sold",
+                    );
+                }
+                if !self.stage_has_capacity_for(&stage, self.valid_ticket_count(conn)? as i64, conn)? {
+                    return DatabaseError::validation_error(
+                        "seating_section_id",
+                        "Seating section's stage does not have capacity for the ticket type's tickets",
+                    );
+                }
+            }
+        }
+
         if attributes.end_date_type.unwrap_or(self.end_date_type) == TicketTypeEndDateType::Manual
             && (attributes.end_date == Some(None) || (attributes.end_date.is_none() && self.end_date.is_none()))
         {
@@ -624,7 +674,41 @@ impl TicketType {
         let valid_available_ticket_count: i64 = query
             .first(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load ticket count for ticket type")?;
-        Ok(valid_available_ticket_count as u32)
+
+        // Ticket types sharing a capacity pool can never sell more than what remains in the pool
+        match self.capacity_pool(conn)? {
+            Some(capacity_pool) => Ok(cmp::min(
+                valid_available_ticket_count as u32,
+                capacity_pool.available(conn)?,
+            )),
+            None => Ok(valid_available_ticket_count as u32),
+        }
+    }
+
+    /// Whether `quantity` more tickets of this type fit on the stage of its seating section. Ticket
+    /// types without a seating section are not tied to a stage.
+    pub fn has_stage_capacity_for(&self, quantity: i64, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        match self.seating_section(conn)? {
+            Some(seating_section) => self.stage_has_capacity_for(&seating_section.stage(conn)?, quantity, conn),
+            None => Ok(true),
+        }
+    }
+
+    fn stage_has_capacity_for(&self, stage: &Stage, quantity: i64, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        // A capacity pool limited to the stage already counts against it at its full capacity
+        if let Some(capacity_pool) = self.capacity_pool(conn)? {
+            if capacity_pool.stage_id == Some(stage.id) {
+                return Ok(true);
+            }
+        }
+        stage.has_capacity_for(self.event_id, quantity, None, conn)
+    }
+
+    pub fn capacity_pool(&self, conn: &PgConnection) -> Result<Option<CapacityPool>, DatabaseError> {
+        match self.capacity_pool_id {
+            Some(capacity_pool_id) => Ok(Some(CapacityPool::find(capacity_pool_id, conn)?)),
+            None => Ok(None),
+        }
     }
 
     pub fn current_ticket_pricing(
@@ -7,7 +7,7 @@ use diesel::expression::sql_literal::sql;
 use diesel::pg::types::sql_types::{Array, Jsonb};
 use diesel::prelude::*;
 use diesel::sql_query;
-use diesel::sql_types::{BigInt, Bool, Nullable, Text, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Text, Timestamp, Uuid as dUuid};
 use models::*;
 use schema::{event_users, events, genres, organization_users, organizations, user_genres, users};
 use serde_json::Value;
@@ -106,6 +106,7 @@ pub struct FanProfile {
     pub cover_photo_url: Option<String>,
     pub created_at: NaiveDateTime,
     pub attendance_information: Vec<AttendanceInformation>,
+    pub attendee_answers: Vec<DisplayEventAnswer>,
     pub deleted_at: Option<NaiveDateTime>,
 }
 
@@ -530,6 +531,12 @@ impl User {
             facebook_event_id: Option<String>,
             #[sql_type = "Nullable<dUuid>"]
             cloned_from_event_id: Option<Uuid>,
+            #[sql_type = "Bool"]
+            resale_enabled: bool,
+            #[sql_type = "Nullable<Integer>"]
+            resale_max_markup_percentage: Option<i32>,
+            #[sql_type = "Nullable<dUuid>"]
+            event_series_id: Option<Uuid>,
         }
 
         let mut query = sql_query(
@@ -620,6 +627,9 @@ impl User {
             slug_id: Some(event.slug_id),
             facebook_event_id: event.facebook_event_id,
             cloned_from_event_id: event.cloned_from_event_id,
+            resale_enabled: event.resale_enabled,
+            resale_max_markup_percentage: event.resale_max_markup_percentage,
+            event_series_id: event.event_series_id,
         });
 
         let mut result: Vec<ActivitySummary> = Vec::new();
@@ -784,6 +794,7 @@ impl User {
             cover_photo_url: self.cover_photo_url.clone(),
             created_at: self.created_at,
             attendance_information: self.attendance_information(conn)?,
+            attendee_answers: EventAnswer::find_for_user_and_organization(self.id, organization.id, conn)?,
             deleted_at: self.deleted_at,
         })
     }
@@ -0,0 +1,380 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::waitlist_entries;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// How long a waitlist offer is reserved for before it is returned to the pool
+/// and offered to the next person in line.
+pub const WAITLIST_OFFER_EXPIRY_HOURS: i64 = 24;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[belongs_to(User)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+    pub status: WaitlistEntryStatus,
+    pub hold_id: Option<Uuid>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntryEditableAttributes {
+    pub status: Option<WaitlistEntryStatus>,
+    pub hold_id: Option<Option<Uuid>>,
+    pub offer_expires_at: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i32,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        self.validate_record(conn)?;
+        let result: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not join waitlist")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCreated,
+            "User joined waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(result.id),
+            Some(self.user_id),
+            Some(json!({ "ticket_type_id": self.ticket_type_id, "quantity": self.quantity })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.quantity <= 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be at least 1");
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.limit_per_person > 0 && self.quantity > ticket_type.limit_per_person {
+            return DatabaseError::validation_error("quantity", "Quantity exceeds the limit per person");
+        }
+        if ticket_type.status(false, conn)? != TicketTypeStatus::SoldOut {
+            return DatabaseError::business_process_error("Waitlist is only available for sold out ticket types");
+        }
+        if WaitlistEntry::find_active_for_user(self.ticket_type_id, self.user_id, conn)?.is_some() {
+            return DatabaseError::business_process_error("User is already on the waitlist for this ticket type");
+        }
+
+        Ok(())
+    }
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid, quantity: i32) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+            quantity,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    pub fn find_active_for_user(
+        ticket_type_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    pub fn find_by_hold_id(hold_id: Uuid, conn: &PgConnection) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::hold_id.eq(hold_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entry")
+    }
+
+    /// Entries still waiting for an offer, first come first served.
+    pub fn find_waiting(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .order_by(waitlist_entries::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading waitlist entries")
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn hold(&self, conn: &PgConnection) -> Result<Option<Hold>, DatabaseError> {
+        match self.hold_id {
+            Some(hold_id) => Ok(Some(Hold::find(hold_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Schedules a `ProcessWaitlist` action for the ticket type when someone is waiting
+    /// and one is not already pending. Called whenever inventory is returned to the pool.
+    pub fn queue_processing(ticket_type_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let waiting: i64 = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .select(dsl::count(waitlist_entries::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not count waitlist entries")?;
+        if waiting == 0 {
+            return Ok(());
+        }
+
+        if DomainAction::upcoming_domain_action(
+            Some(Tables::TicketTypes),
+            Some(ticket_type_id),
+            DomainActionTypes::ProcessWaitlist,
+            conn,
+        )?
+        .is_none()
+        {
+            DomainAction::create(
+                None,
+                DomainActionTypes::ProcessWaitlist,
+                None,
+                json!({}),
+                Some(Tables::TicketTypes),
+                Some(ticket_type_id),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Offers currently available inventory to waiting entries in order. Processing stops at
+    /// the first entry that cannot be satisfied so that nobody is skipped in line. Returns
+    /// the entries that received an offer.
+    pub fn process_waitlist(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        if ticket_type.status != TicketTypeStatus::Published {
+            return Ok(vec![]);
+        }
+
+        let mut available = ticket_type.valid_available_ticket_count(conn)? as i32;
+        let mut offered = Vec::new();
+        for entry in WaitlistEntry::find_waiting(ticket_type_id, conn)? {
+            if entry.quantity > available {
+                break;
+            }
+            available -= entry.quantity;
+            offered.push(entry.offer(&ticket_type, conn)?);
+        }
+
+        Ok(offered)
+    }
+
+    /// Reserves the entry's quantity in a single use hold that is redeemable via its
+    /// redemption code until the offer expires. Only the entry's user can redeem the code.
+    pub fn offer(&self, ticket_type: &TicketType, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting {
+            return DatabaseError::business_process_error("Only waiting entries can be offered tickets");
+        }
+
+        let offer_expires_at = Utc::now().naive_utc() + Duration::hours(WAITLIST_OFFER_EXPIRY_HOURS);
+        let hold = Hold::create_hold(
+            format!("Waitlist offer {}", self.id),
+            ticket_type.event_id,
+            Some(random_alpha_string(10)),
+            Some(0),
+            Some(offer_expires_at),
+            Some(self.quantity as u32),
+            HoldTypes::Discount,
+            ticket_type.id,
+        )
+        .commit(None, conn)?;
+        hold.set_quantity(None, self.quantity as u32, conn)?;
+
+        let entry = self.update(
+            WaitlistEntryEditableAttributes {
+                status: Some(WaitlistEntryStatus::Offered),
+                hold_id: Some(Some(hold.id)),
+                offer_expires_at: Some(Some(offer_expires_at)),
+            },
+            conn,
+        )?;
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ExpireWaitlistOffer,
+            None,
+            json!({}),
+            Some(Tables::WaitlistEntries),
+            Some(entry.id),
+        );
+        action.schedule_at(offer_expires_at);
+        action.commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferSent,
+            "Waitlist offer sent".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            None,
+            Some(json!({ "hold_id": hold.id, "offer_expires_at": offer_expires_at })),
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    /// Closes an offer once it has expired. Any tickets from the offer that were not purchased
+    /// are returned to the pool, which in turn offers them to the next person in line.
+    pub fn expire_offer(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Offered {
+            return Ok(self.clone());
+        }
+        if self
+            .offer_expires_at
+            .map(|e| e > Utc::now().naive_utc())
+            .unwrap_or(false)
+        {
+            return DatabaseError::business_process_error("Waitlist offer has not expired yet");
+        }
+
+        let mut status = WaitlistEntryStatus::Expired;
+        if let Some(hold) = self.hold(conn)? {
+            let (quantity, available) = hold.quantity(conn)?;
+            if quantity > available {
+                status = WaitlistEntryStatus::Purchased;
+            }
+            hold.remove_available_quantity(None, conn)?;
+        }
+
+        let entry = self.update(
+            WaitlistEntryEditableAttributes {
+                status: Some(status),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferExpired,
+            "Waitlist offer expired".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            None,
+            Some(json!({ "status": status })),
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    /// Closes an offer once the user has bought tickets from it. Any offered tickets they did not
+    /// buy are returned to the pool.
+    pub(crate) fn mark_purchased(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Offered {
+            return Ok(self.clone());
+        }
+
+        if let Some(hold) = self.hold(conn)? {
+            hold.remove_available_quantity(current_user_id, conn)?;
+        }
+
+        let entry = self.update(
+            WaitlistEntryEditableAttributes {
+                status: Some(WaitlistEntryStatus::Purchased),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferPurchased,
+            "Waitlist offer purchased".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    /// Removes the user from the waitlist, returning any unpurchased offered tickets to the pool.
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting && self.status != WaitlistEntryStatus::Offered {
+            return DatabaseError::business_process_error("Waitlist entry is no longer active");
+        }
+
+        if let Some(hold) = self.hold(conn)? {
+            hold.remove_available_quantity(current_user_id, conn)?;
+        }
+
+        let entry = self.update(
+            WaitlistEntryEditableAttributes {
+                status: Some(WaitlistEntryStatus::Cancelled),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCancelled,
+            "User left waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    fn update(
+        &self,
+        attributes: WaitlistEntryEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<WaitlistEntry, DatabaseError> {
+        diesel::update(self)
+            .set((attributes, waitlist_entries::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+}
@@ -0,0 +1,12 @@
+SELECT s.*
+FROM seats s
+WHERE s.seating_section_id = $1
+  AND NOT EXISTS(SELECT 1
+                 FROM ticket_instances ti
+                          INNER JOIN assets a ON a.id = ti.asset_id
+                          INNER JOIN ticket_types tt ON tt.id = a.ticket_type_id
+                 WHERE ti.seat_id = s.id
+                   AND tt.event_id = $2
+                   AND ti.id <> ALL ($3)
+                   AND (ti.status IN ('Purchased', 'Redeemed') OR (ti.status = 'Reserved' AND ti.reserved_until >= now())))
+ORDER BY s.accessible, s.row_rank, s.seat_rank;
@@ -0,0 +1,13 @@
+SELECT es.id,
+       es.ticket_type_id,
+       es.entry_start,
+       es.entry_end,
+       es.capacity,
+       GREATEST(es.capacity - (SELECT count(ti.id)
+                               FROM ticket_instances ti
+                               WHERE ti.entry_slot_id = es.id
+                                 AND (ti.status IN ('Purchased', 'Redeemed') OR
+                                      (ti.status = 'Reserved' AND ti.reserved_until >= now()))), 0) AS available
+FROM entry_slots es
+WHERE es.ticket_type_id = $1
+ORDER BY es.entry_start;
@@ -0,0 +1,23 @@
+SELECT s.id,
+       s.seating_section_id,
+       ss.name                                                              AS section_name,
+       s.row_name,
+       s.seat_number,
+       s.accessible,
+       NOT EXISTS(SELECT 1
+                  FROM ticket_instances ti
+                           INNER JOIN assets a ON a.id = ti.asset_id
+                           INNER JOIN ticket_types tt2 ON tt2.id = a.ticket_type_id
+                  WHERE ti.seat_id = s.id
+                    AND tt2.event_id = $1
+                    AND (ti.status IN ('Purchased', 'Redeemed') OR
+                         (ti.status = 'Reserved' AND ti.reserved_until >= now()))) AS available,
+       array_agg(tt.id)                                                     AS ticket_type_ids
+FROM seats s
+         INNER JOIN seating_sections ss ON ss.id = s.seating_section_id
+         INNER JOIN ticket_types tt ON tt.seating_section_id = ss.id
+WHERE tt.event_id = $1
+  AND tt.deleted_at IS NULL
+  AND tt.status <> 'Cancelled'
+GROUP BY s.id, ss.id
+ORDER BY ss.rank, ss.name, s.row_rank, s.seat_rank;
@@ -20,3 +20,31 @@ AND (
     OR h.end_at < now()
     OR oit.count <> oi.quantity
 )
+UNION
+SELECT oi.*
+FROM order_items oi
+INNER JOIN orders o ON oi.order_id = o.id
+LEFT JOIN resale_listings rl ON rl.order_item_id = oi.id
+LEFT JOIN ticket_instances ti ON ti.id = rl.ticket_instance_id
+LEFT JOIN wallets w ON w.id = ti.wallet_id
+WHERE oi.order_id = $1
+AND oi.item_type = 'ResaleTickets'
+AND (
+    rl.id IS NULL
+    OR rl.status <> 'Active'
+    OR o.expires_at < now()
+    OR ti.status <> 'Purchased'
+    OR w.user_id IS DISTINCT FROM rl.seller_user_id
+)
+UNION
+SELECT oi.*
+FROM order_items oi
+INNER JOIN orders o ON oi.order_id = o.id
+INNER JOIN product_variants pv ON pv.id = oi.product_variant_id
+INNER JOIN products p ON p.id = pv.product_id
+WHERE oi.order_id = $1
+AND oi.item_type = 'Products'
+AND (
+    o.expires_at < now()
+    OR p.active IS FALSE
+)
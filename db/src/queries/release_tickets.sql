@@ -9,6 +9,8 @@ UPDATE ticket_instances
 SET order_item_id  = NULL,
     reserved_until = NULL,
     redeem_key     = NULL,
+    seat_id        = NULL,
+    entry_slot_id  = NULL,
     status         = $5,
     updated_at     = now()
 FROM cte
@@ -12,7 +12,8 @@ SELECT oi.event_id,
        CAST(COALESCE(SUM(oi_fees.company_fee_in_cents * (oi_fees.quantity - oi_fees.refunded_quantity)), 0) AS BIGINT) AS total_company_fee_in_cents,
        CAST(COALESCE(oi_fees.company_fee_in_cents, 0) AS BIGINT) AS company_fee_in_cents,
        CAST(COALESCE(SUM(oi_fees.client_fee_in_cents * (oi_fees.quantity - oi_fees.refunded_quantity)), 0) AS BIGINT)  AS total_client_fee_in_cents,
-       CAST(COALESCE(oi_fees.client_fee_in_cents, 0) AS BIGINT) AS client_fee_in_cents
+       CAST(COALESCE(oi_fees.client_fee_in_cents, 0) AS BIGINT) AS client_fee_in_cents,
+       orders.currency
 FROM orders
        LEFT JOIN order_items oi on orders.id = oi.order_id
        LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
@@ -29,4 +30,4 @@ WHERE orders.status = 'Paid'
   AND oi.item_type = 'Tickets'
   AND ($3 IS NULL OR orders.paid_at >= $3)
   AND ($4 IS NULL OR orders.paid_at <= $4)
-GROUP BY oi.event_id, oi.ticket_type_id, tt.name, tt.status, tp.name, oi.unit_price_in_cents, oi_promo_code.unit_price_in_cents, oi_fees.company_fee_in_cents, oi_fees.client_fee_in_cents;
+GROUP BY oi.event_id, oi.ticket_type_id, tt.name, tt.status, tp.name, oi.unit_price_in_cents, oi_promo_code.unit_price_in_cents, oi_fees.company_fee_in_cents, oi_fees.client_fee_in_cents, orders.currency;
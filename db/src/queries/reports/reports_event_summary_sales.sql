@@ -12,6 +12,7 @@ SELECT event_id,
        total_client_fee_in_cents,
        pricing_name,
        ticket_name,
+       currency,
        CAST(total_net_income + total_company_fee_in_cents +
             total_client_fee_in_cents AS BIGINT) AS total_gross_income_in_cents
 FROM (
@@ -45,7 +46,8 @@ FROM (
                      COALESCE(oi_promo_code.unit_price_in_cents, 0))),
                               0) AS BIGINT)            AS total_net_income,
                 tp.name                                AS pricing_name,
-                CASE WHEN tt.status = 'Cancelled' THEN concat(tt.name, ' (Cancelled)') ELSE tt.name END AS ticket_name
+                CASE WHEN tt.status = 'Cancelled' THEN concat(tt.name, ' (Cancelled)') ELSE tt.name END AS ticket_name,
+                orders.currency
          FROM orders
                   LEFT JOIN order_items oi ON orders.id = oi.order_id
                   LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
@@ -63,5 +65,5 @@ FROM (
            AND ($3 IS NULL OR orders.paid_at >= $3)
            AND ($4 IS NULL OR orders.paid_at <= $4)
          GROUP BY oi.event_id, oi.ticket_type_id, tt.name, tt.status, tp.name, oi.unit_price_in_cents,
-                  oi_promo_code.unit_price_in_cents, h.id, h.name, c.id
+                  oi_promo_code.unit_price_in_cents, h.id, h.name, c.id, orders.currency
      ) AS report_data;
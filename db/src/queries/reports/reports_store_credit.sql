@@ -0,0 +1,21 @@
+SELECT sct.currency,
+       sct.transaction_type,
+       CAST(COUNT(*) AS BIGINT)                                                              AS transaction_count,
+       CAST(COALESCE(SUM(CASE WHEN sct.amount_in_cents > 0 THEN sct.amount_in_cents END), 0) AS BIGINT)  AS issued_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN sct.amount_in_cents < 0 THEN -sct.amount_in_cents END), 0) AS BIGINT) AS spent_in_cents,
+       CAST(SUM(sct.amount_in_cents) AS BIGINT)                                              AS liability_in_cents
+FROM store_credit_transactions sct
+         LEFT JOIN product_instances pi ON pi.id = sct.product_instance_id
+         LEFT JOIN product_variants pv ON pv.id = pi.product_variant_id
+         LEFT JOIN products pr ON pr.id = pv.product_id
+WHERE ($1 IS NULL
+    OR pr.organization_id = $1
+    OR (sct.product_instance_id IS NULL AND EXISTS(SELECT 1
+                                                  FROM order_items oi
+                                                           JOIN events e ON e.id = oi.event_id
+                                                  WHERE oi.order_id = sct.order_id
+                                                    AND e.organization_id = $1)))
+  AND ($2 IS NULL OR sct.created_at >= $2)
+  AND ($3 IS NULL OR sct.created_at <= $3)
+GROUP BY sct.currency, sct.transaction_type
+ORDER BY sct.currency, sct.transaction_type;
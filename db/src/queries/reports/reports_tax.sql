@@ -0,0 +1,26 @@
+SELECT e.id                                                                                         AS event_id,
+       e.name                                                                                       AS event_name,
+       tr.id                                                                                        AS tax_rule_id,
+       tr.name                                                                                      AS tax_rule_name,
+       tr.rate_percent,
+       tr.inclusive,
+       o.currency,
+       CAST(SUM((oi_taxed.unit_price_in_cents + COALESCE(oi_discount.unit_price_in_cents, 0)) *
+                (oi.quantity - oi.refunded_quantity)) AS BIGINT)                                    AS taxable_sales_in_cents,
+       CAST(SUM(oi.tax_in_cents * oi.quantity) AS BIGINT)                                           AS tax_collected_in_cents,
+       CAST(SUM(oi.tax_in_cents * oi.refunded_quantity) AS BIGINT)                                  AS tax_refunded_in_cents,
+       CAST(SUM(oi.tax_in_cents * (oi.quantity - oi.refunded_quantity)) AS BIGINT)                  AS net_tax_in_cents
+FROM order_items oi
+         INNER JOIN orders o ON o.id = oi.order_id
+         INNER JOIN order_items oi_taxed ON oi_taxed.id = oi.parent_id
+         LEFT JOIN order_items oi_discount ON oi_discount.parent_id = oi_taxed.id AND oi_discount.item_type = 'Discount'
+         INNER JOIN tax_rules tr ON tr.id = oi.tax_rule_id
+         INNER JOIN events e ON e.id = oi.event_id
+WHERE oi.item_type = 'Tax'
+  AND o.paid_at IS NOT NULL
+  AND e.organization_id = $1
+  AND ($2 IS NULL OR e.id = $2)
+  AND ($3 IS NULL OR o.paid_at >= $3)
+  AND ($4 IS NULL OR o.paid_at <= $4)
+GROUP BY e.id, e.name, e.event_start, tr.id, tr.name, tr.rate_percent, tr.inclusive, o.currency
+ORDER BY e.event_start, e.name, tr.name, o.currency;
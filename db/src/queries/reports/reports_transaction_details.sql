@@ -29,6 +29,8 @@ SELECT COUNT(*) OVER ()
        o.order_type,
        p.payment_method,
        p.payment_provider,
+       CAST(COALESCE(p.store_credit_in_cents, 0) AS BIGINT)                                               AS store_credit_in_cents,
+       CAST(COALESCE(p.store_credit_refunded_in_cents, 0) AS BIGINT)                                      AS store_credit_refunded_in_cents,
        h.redemption_code,
        o.id                                                                                               AS order_id,
        oi.event_id,
@@ -48,7 +50,16 @@ SELECT COUNT(*) OVER ()
        o.term,
        o.content,
        o.platform,
-       ti_agg.check_in_source
+       ti_agg.check_in_source,
+       (SELECT STRING_AGG(CONCAT(eq.question, ': ', ea.answer), '; ' ORDER BY eq.rank, eq.created_at, ea.ticket_instance_id)
+        FROM event_answers ea
+                 JOIN event_questions eq ON eq.id = ea.event_question_id
+        WHERE ea.order_id = o.id
+          AND eq.event_id = oi.event_id
+          AND eq.deleted_at IS NULL
+          AND (ea.ticket_instance_id IS NULL OR
+               ea.ticket_instance_id IN (SELECT ti.id FROM ticket_instances ti WHERE ti.order_item_id = oi.id))) AS attendee_answers,
+       o.currency
 FROM orders o
          LEFT JOIN order_items oi ON (o.id = oi.order_id AND oi.item_type = 'Tickets')
          LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
@@ -60,7 +71,9 @@ FROM orders o
          LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
          LEFT JOIN (SELECT order_id,
                            ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.payment_method), ', ') AS payment_method,
-                           ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.provider), ', ')       AS payment_provider
+                           ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.provider), ', ')       AS payment_provider,
+                           SUM(p.amount) FILTER (WHERE p.provider = 'StoreCredit' AND p.status = 'Completed') AS store_credit_in_cents,
+                           -SUM(p.amount) FILTER (WHERE p.provider = 'StoreCredit' AND p.status = 'Refunded') AS store_credit_refunded_in_cents
                     FROM payments p
                     WHERE p.status IN ('Completed','Refunded')
                     GROUP BY p.order_id) AS p on o.id = p.order_id
@@ -12,6 +12,8 @@ UPDATE ticket_instances
 SET order_item_id  = $1,
     reserved_until = $2,
     status         = 'Reserved',
+    seat_id        = NULL,
+    entry_slot_id  = NULL,
     updated_at     = now()
 FROM r
 WHERE ticket_instances.id = r.id RETURNING ticket_instances.*;
@@ -89,6 +89,32 @@ table! {
     }
 }
 
+table! {
+    capacity_pools (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        stage_id -> Nullable<Uuid>,
+        name -> Text,
+        capacity -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    code_batches (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        name -> Text,
+        quantity -> Int4,
+        prefix -> Nullable<Text>,
+        created_by -> Uuid,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     codes (id) {
         id -> Uuid,
@@ -105,6 +131,13 @@ table! {
         updated_at -> Timestamp,
         discount_as_percentage -> Nullable<Int8>,
         deleted_at -> Nullable<Timestamp>,
+        min_quantity -> Nullable<Int8>,
+        buy_quantity -> Nullable<Int8>,
+        get_quantity -> Nullable<Int8>,
+        order_discount_in_cents -> Nullable<Int8>,
+        waives_fees -> Bool,
+        hold_stacking -> Text,
+        code_batch_id -> Nullable<Uuid>,
     }
 }
 
@@ -171,6 +204,30 @@ table! {
     }
 }
 
+table! {
+    entry_slots (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        entry_start -> Timestamp,
+        entry_end -> Timestamp,
+        capacity -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_answers (id) {
+        id -> Uuid,
+        event_question_id -> Uuid,
+        order_id -> Uuid,
+        ticket_instance_id -> Nullable<Uuid>,
+        answer -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_artists (id) {
         id -> Uuid,
@@ -205,6 +262,23 @@ table! {
     }
 }
 
+table! {
+    event_questions (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        question -> Text,
+        question_type -> Text,
+        choices -> Array<Text>,
+        required -> Bool,
+        per_ticket -> Bool,
+        rank -> Int4,
+        answers_editable_until -> Nullable<Timestamp>,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_report_subscribers (id) {
         id -> Uuid,
@@ -216,6 +290,24 @@ table! {
     }
 }
 
+table! {
+    event_reschedules (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        previous_event_start -> Nullable<Timestamp>,
+        previous_event_end -> Nullable<Timestamp>,
+        previous_door_time -> Nullable<Timestamp>,
+        event_start -> Timestamp,
+        event_end -> Timestamp,
+        door_time -> Nullable<Timestamp>,
+        refund_window_ends_at -> Timestamp,
+        refund_window_closed_at -> Nullable<Timestamp>,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     events (id) {
         id -> Uuid,
@@ -253,6 +345,21 @@ table! {
         facebook_event_id -> Nullable<Text>,
         settled_at -> Nullable<Timestamp>,
         cloned_from_event_id -> Nullable<Uuid>,
+        resale_enabled -> Bool,
+        resale_max_markup_percentage -> Nullable<Int4>,
+        event_series_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    event_series (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_event_id -> Uuid,
+        name -> Text,
+        recurrence_rule -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -302,6 +409,8 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         organization_id -> Uuid,
+        resale_company_fee_percent -> Float4,
+        resale_client_fee_percent -> Float4,
     }
 }
 
@@ -331,6 +440,8 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        expiry_behavior -> Text,
+        released_at -> Nullable<Timestamp>,
     }
 }
 
@@ -367,6 +478,10 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        product_variant_id -> Nullable<Uuid>,
+        package_id -> Nullable<Uuid>,
+        tax_rule_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
     }
 }
 
@@ -398,6 +513,7 @@ table! {
         platform -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         referrer -> Nullable<Text>,
+        currency -> Text,
     }
 }
 
@@ -470,6 +586,11 @@ table! {
         slug_id -> Nullable<Uuid>,
         google_ads_conversion_id -> Nullable<Text>,
         google_ads_conversion_labels -> Array<Text>,
+        currency -> Text,
+        payment_plan_min_total_in_cents -> Nullable<Int8>,
+        payment_plan_max_installments -> Int4,
+        payment_plan_grace_period_days -> Int4,
+        payment_plan_refund_policy -> Text,
     }
 }
 
@@ -484,6 +605,29 @@ table! {
     }
 }
 
+table! {
+    package_ticket_types (id) {
+        id -> Uuid,
+        package_id -> Uuid,
+        ticket_type_id -> Uuid,
+        price_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    packages (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     payment_methods (id) {
         id -> Uuid,
@@ -497,6 +641,55 @@ table! {
     }
 }
 
+table! {
+    payment_plan_installments (id) {
+        id -> Uuid,
+        payment_plan_id -> Uuid,
+        installment_number -> Int4,
+        amount_in_cents -> Int8,
+        due_at -> Timestamp,
+        status -> Text,
+        payment_id -> Nullable<Uuid>,
+        failed_attempts -> Int4,
+        last_failure_reason -> Nullable<Text>,
+        grace_period_ends_at -> Nullable<Timestamp>,
+        paid_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    payment_plans (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        user_id -> Uuid,
+        payment_method_id -> Uuid,
+        total_in_cents -> Int8,
+        deposit_in_cents -> Int8,
+        grace_period_days -> Int4,
+        refund_policy -> Text,
+        status -> Text,
+        completed_at -> Nullable<Timestamp>,
+        cancelled_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    payment_provider_events (id) {
+        id -> Uuid,
+        provider -> Text,
+        external_id -> Text,
+        event_type -> Text,
+        payload -> Jsonb,
+        processed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     payments (id) {
         id -> Uuid,
@@ -512,6 +705,104 @@ table! {
         updated_at -> Timestamp,
         url_nonce -> Nullable<Text>,
         refund_id -> Nullable<Uuid>,
+        currency -> Text,
+        refunded_payment_id -> Nullable<Uuid>,
+        dispute_status -> Nullable<Text>,
+    }
+}
+
+table! {
+    presale_lotteries (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        name -> Text,
+        ticket_type_ids -> Array<Uuid>,
+        registration_start -> Timestamp,
+        registration_end -> Timestamp,
+        draw_at -> Timestamp,
+        code_expires_at -> Timestamp,
+        winner_count -> Int4,
+        tickets_per_winner -> Int4,
+        interaction_weight -> Int4,
+        max_weight -> Int4,
+        status -> Text,
+        drawn_at -> Nullable<Timestamp>,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    presale_lottery_entries (id) {
+        id -> Uuid,
+        presale_lottery_id -> Uuid,
+        user_id -> Uuid,
+        status -> Text,
+        weight -> Nullable<Int4>,
+        code_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    pricing_rules (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        name -> Text,
+        rule_type -> Text,
+        threshold -> Int8,
+        adjustment_in_cents -> Int8,
+        floor_price_in_cents -> Int8,
+        ceiling_price_in_cents -> Int8,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    product_instances (id) {
+        id -> Uuid,
+        product_variant_id -> Uuid,
+        order_item_id -> Uuid,
+        user_id -> Uuid,
+        status -> Text,
+        redeem_key -> Text,
+        redeemed_at -> Nullable<Timestamp>,
+        redeemed_by_user_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    products (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        gift_card -> Bool,
+    }
+}
+
+table! {
+    product_variants (id) {
+        id -> Uuid,
+        product_id -> Uuid,
+        name -> Text,
+        sku -> Text,
+        price_in_cents -> Nullable<Int8>,
+        stock -> Int4,
+        rank -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -560,6 +851,7 @@ table! {
         reason -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         manual_override -> Bool,
+        retained_in_cents -> Int8,
     }
 }
 
@@ -572,6 +864,48 @@ table! {
     }
 }
 
+table! {
+    resale_listings (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        event_id -> Uuid,
+        seller_user_id -> Uuid,
+        price_in_cents -> Int8,
+        status -> Text,
+        order_item_id -> Nullable<Uuid>,
+        buyer_user_id -> Nullable<Uuid>,
+        sold_at -> Nullable<Timestamp>,
+        settlement_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seating_sections (id) {
+        id -> Uuid,
+        stage_id -> Uuid,
+        name -> Text,
+        rank -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seats (id) {
+        id -> Uuid,
+        seating_section_id -> Uuid,
+        row_name -> Text,
+        seat_number -> Text,
+        row_rank -> Int4,
+        seat_rank -> Int4,
+        accessible -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     settlement_adjustments (id) {
         id -> Uuid,
@@ -598,6 +932,7 @@ table! {
         settlement_entry_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        product_variant_id -> Nullable<Uuid>,
     }
 }
 
@@ -612,6 +947,7 @@ table! {
         only_finished_events -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        currency -> Text,
     }
 }
 
@@ -651,6 +987,38 @@ table! {
     }
 }
 
+table! {
+    store_credit_transactions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        amount_in_cents -> Int8,
+        transaction_type -> Text,
+        order_id -> Nullable<Uuid>,
+        payment_id -> Nullable<Uuid>,
+        refund_id -> Nullable<Uuid>,
+        product_instance_id -> Nullable<Uuid>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        currency -> Text,
+    }
+}
+
+table! {
+    tax_rules (id) {
+        id -> Uuid,
+        region_id -> Nullable<Uuid>,
+        venue_id -> Nullable<Uuid>,
+        name -> Text,
+        rate_percent -> Float8,
+        inclusive -> Bool,
+        taxes_fees -> Bool,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     temporary_user_links (temporary_user_id, user_id) {
         temporary_user_id -> Uuid,
@@ -670,6 +1038,23 @@ table! {
     }
 }
 
+table! {
+    ticket_exchanges (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        original_order_id -> Uuid,
+        refund_id -> Uuid,
+        new_ticket_type_id -> Uuid,
+        new_order_id -> Uuid,
+        user_id -> Uuid,
+        refunded_amount_in_cents -> Int8,
+        new_order_total_in_cents -> Int8,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_instances (id) {
         id -> Uuid,
@@ -688,6 +1073,8 @@ table! {
         first_name_override -> Nullable<Text>,
         last_name_override -> Nullable<Text>,
         check_in_source -> Nullable<Text>,
+        seat_id -> Nullable<Uuid>,
+        entry_slot_id -> Nullable<Uuid>,
     }
 }
 
@@ -704,6 +1091,8 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         previous_ticket_pricing_id -> Nullable<Uuid>,
+        quantity_limit -> Nullable<Int8>,
+        base_price_in_cents -> Nullable<Int8>,
     }
 }
 
@@ -741,6 +1130,9 @@ table! {
         web_sales_enabled -> Bool,
         box_office_sales_enabled -> Bool,
         app_sales_enabled -> Bool,
+        seating_section_id -> Nullable<Uuid>,
+        entry_window_policy -> Text,
+        capacity_pool_id -> Nullable<Uuid>,
     }
 }
 
@@ -831,6 +1223,20 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        quantity -> Int4,
+        status -> Text,
+        hold_id -> Nullable<Uuid>,
+        offer_expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -851,6 +1257,11 @@ joinable!(artists -> genres (main_genre_id));
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
 joinable!(broadcasts -> events (event_id));
+joinable!(capacity_pools -> events (event_id));
+joinable!(capacity_pools -> stages (stage_id));
+joinable!(code_batches -> events (event_id));
+joinable!(code_batches -> users (created_by));
+joinable!(codes -> code_batches (code_batch_id));
 joinable!(codes -> events (event_id));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_event_published -> domain_event_publishers (domain_event_publisher_id));
@@ -858,6 +1269,10 @@ joinable!(domain_event_published -> domain_events (domain_event_id));
 joinable!(domain_event_publishers -> organizations (organization_id));
 joinable!(domain_events -> organizations (organization_id));
 joinable!(domain_events -> users (user_id));
+joinable!(entry_slots -> ticket_types (ticket_type_id));
+joinable!(event_answers -> event_questions (event_question_id));
+joinable!(event_answers -> orders (order_id));
+joinable!(event_answers -> ticket_instances (ticket_instance_id));
 joinable!(event_artists -> artists (artist_id));
 joinable!(event_artists -> events (event_id));
 joinable!(event_artists -> stages (stage_id));
@@ -865,9 +1280,14 @@ joinable!(event_genres -> events (event_id));
 joinable!(event_genres -> genres (genre_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_questions -> events (event_id));
 joinable!(event_report_subscribers -> events (event_id));
+joinable!(event_reschedules -> events (event_id));
+joinable!(event_reschedules -> users (created_by));
+joinable!(event_series -> organizations (organization_id));
 joinable!(event_users -> events (event_id));
 joinable!(event_users -> users (user_id));
+joinable!(events -> event_series (event_series_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
@@ -879,6 +1299,9 @@ joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> packages (package_id));
+joinable!(order_items -> product_variants (product_variant_id));
+joinable!(order_items -> tax_rules (tax_rule_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
 joinable!(order_transfers -> orders (order_id));
@@ -890,10 +1313,29 @@ joinable!(organization_invites -> organizations (organization_id));
 joinable!(organization_users -> organizations (organization_id));
 joinable!(organization_users -> users (user_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
+joinable!(package_ticket_types -> packages (package_id));
+joinable!(package_ticket_types -> ticket_types (ticket_type_id));
+joinable!(packages -> organizations (organization_id));
 joinable!(payment_methods -> users (user_id));
+joinable!(payment_plan_installments -> payment_plans (payment_plan_id));
+joinable!(payment_plan_installments -> payments (payment_id));
+joinable!(payment_plans -> orders (order_id));
+joinable!(payment_plans -> payment_methods (payment_method_id));
+joinable!(payment_plans -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> refunds (refund_id));
 joinable!(payments -> users (created_by));
+joinable!(presale_lotteries -> events (event_id));
+joinable!(presale_lotteries -> users (created_by));
+joinable!(presale_lottery_entries -> codes (code_id));
+joinable!(presale_lottery_entries -> presale_lotteries (presale_lottery_id));
+joinable!(presale_lottery_entries -> users (user_id));
+joinable!(pricing_rules -> ticket_types (ticket_type_id));
+joinable!(product_instances -> order_items (order_item_id));
+joinable!(product_instances -> product_variants (product_variant_id));
+joinable!(product_variants -> products (product_id));
+joinable!(products -> events (event_id));
+joinable!(products -> organizations (organization_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(refund_items -> order_items (order_item_id));
 joinable!(refund_items -> refunds (refund_id));
@@ -902,27 +1344,50 @@ joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
 joinable!(refunds -> orders (order_id));
 joinable!(refunds -> settlements (settlement_id));
 joinable!(refunds -> users (user_id));
+joinable!(resale_listings -> events (event_id));
+joinable!(resale_listings -> order_items (order_item_id));
+joinable!(resale_listings -> settlements (settlement_id));
+joinable!(resale_listings -> ticket_instances (ticket_instance_id));
+joinable!(seating_sections -> stages (stage_id));
+joinable!(seats -> seating_sections (seating_section_id));
 joinable!(settlement_adjustments -> settlements (settlement_id));
 joinable!(settlement_entries -> events (event_id));
 joinable!(settlement_entries -> settlements (settlement_id));
 joinable!(settlement_entries -> ticket_types (ticket_type_id));
 joinable!(settlements -> organizations (organization_id));
+joinable!(store_credit_transactions -> orders (order_id));
+joinable!(store_credit_transactions -> payments (payment_id));
+joinable!(store_credit_transactions -> product_instances (product_instance_id));
+joinable!(store_credit_transactions -> refunds (refund_id));
+joinable!(store_credit_transactions -> users (user_id));
+joinable!(tax_rules -> regions (region_id));
+joinable!(tax_rules -> venues (venue_id));
 joinable!(temporary_user_links -> temporary_users (temporary_user_id));
 joinable!(temporary_user_links -> users (user_id));
+joinable!(ticket_exchanges -> refunds (refund_id));
+joinable!(ticket_exchanges -> ticket_instances (ticket_instance_id));
+joinable!(ticket_exchanges -> ticket_types (new_ticket_type_id));
 joinable!(ticket_instances -> assets (asset_id));
+joinable!(ticket_instances -> entry_slots (entry_slot_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> seats (seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
+joinable!(ticket_types -> capacity_pools (capacity_pool_id));
 joinable!(ticket_types -> events (event_id));
+joinable!(ticket_types -> seating_sections (seating_section_id));
 joinable!(transfer_tickets -> ticket_instances (ticket_instance_id));
 joinable!(transfer_tickets -> transfers (transfer_id));
 joinable!(user_genres -> genres (genre_id));
 joinable!(user_genres -> users (user_id));
 joinable!(venues -> organizations (organization_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> holds (hold_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
 
@@ -932,16 +1397,23 @@ allow_tables_to_appear_in_same_query!(
     artists,
     assets,
     broadcasts,
+    capacity_pools,
+    code_batches,
     codes,
     domain_actions,
     domain_event_published,
     domain_event_publishers,
     domain_events,
+    entry_slots,
+    event_answers,
     event_artists,
     event_genres,
     event_interest,
+    event_questions,
     event_report_subscribers,
+    event_reschedules,
     events,
+    event_series,
     event_users,
     external_logins,
     fee_schedule_ranges,
@@ -956,21 +1428,38 @@ allow_tables_to_appear_in_same_query!(
     organization_invites,
     organizations,
     organization_users,
+    packages,
+    package_ticket_types,
     payment_methods,
+    payment_plan_installments,
+    payment_plans,
+    payment_provider_events,
     payments,
+    presale_lotteries,
+    presale_lottery_entries,
+    pricing_rules,
+    product_instances,
+    products,
+    product_variants,
     push_notification_tokens,
     refunded_tickets,
     refund_items,
     refunds,
     regions,
+    resale_listings,
+    seating_sections,
+    seats,
     settlement_adjustments,
     settlement_entries,
     settlements,
     slugs,
     source_aliases,
     stages,
+    store_credit_transactions,
+    tax_rules,
     temporary_user_links,
     temporary_users,
+    ticket_exchanges,
     ticket_instances,
     ticket_pricing,
     ticket_type_codes,
@@ -980,5 +1469,6 @@ allow_tables_to_appear_in_same_query!(
     user_genres,
     users,
     venues,
+    waitlist_entries,
     wallets,
 );
@@ -1,3 +1,5 @@
 pub use self::country_lookup::*;
+pub use self::recurrence_rule::*;
 
 mod country_lookup;
+mod recurrence_rule;
@@ -0,0 +1,217 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use utils::errors::DatabaseError;
+use utils::errors::ParseError;
+
+// Hard cap on generated occurrences so unbounded rules cannot create runaway series
+pub const MAXIMUM_OCCURRENCES: usize = 366;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Subset of RFC 5545 RRULE supported for event series:
+/// FREQ (DAILY, WEEKLY, MONTHLY), INTERVAL, COUNT, UNTIL and BYDAY.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDateTime>,
+    pub by_day: Vec<Weekday>,
+}
+
+impl RecurrenceRule {
+    pub fn parse(input: &str) -> Result<RecurrenceRule, DatabaseError> {
+        let parse_error = |message: &str| -> DatabaseError {
+            ParseError {
+                message: message.to_string(),
+                input: input.to_string(),
+            }
+            .into()
+        };
+
+        let rule = input.trim();
+        let rule = if rule.to_uppercase().starts_with("RRULE:") {
+            &rule[6..]
+        } else {
+            rule
+        };
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+        for part in rule.split(';').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let mut key_value = part.splitn(2, '=');
+            let key = key_value.next().unwrap_or("").to_uppercase();
+            let value = match key_value.next() {
+                Some(value) => value.trim().to_uppercase(),
+                None => return Err(parse_error("Recurrence rule parts must be in KEY=VALUE form")),
+            };
+
+            match key.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => RecurrenceFrequency::Daily,
+                        "WEEKLY" => RecurrenceFrequency::Weekly,
+                        "MONTHLY" => RecurrenceFrequency::Monthly,
+                        _ => return Err(parse_error("Unsupported recurrence frequency")),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .map_err(|_| parse_error("Recurrence interval must be a positive number"))?;
+                    if interval == 0 {
+                        return Err(parse_error("Recurrence interval must be a positive number"));
+                    }
+                }
+                "COUNT" => {
+                    let parsed_count = value
+                        .parse::<u32>()
+                        .map_err(|_| parse_error("Recurrence count must be a positive number"))?;
+                    if parsed_count == 0 {
+                        return Err(parse_error("Recurrence count must be a positive number"));
+                    }
+                    count = Some(parsed_count);
+                }
+                "UNTIL" => {
+                    until = Some(
+                        RecurrenceRule::parse_until(&value)
+                            .ok_or_else(|| parse_error("Recurrence until is invalid"))?,
+                    )
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        by_day.push(
+                            RecurrenceRule::parse_weekday(day.trim())
+                                .ok_or_else(|| parse_error("Recurrence day is invalid"))?,
+                        );
+                    }
+                }
+                _ => return Err(parse_error("Unsupported recurrence rule part")),
+            }
+        }
+
+        let frequency = match frequency {
+            Some(frequency) => frequency,
+            None => return Err(parse_error("Recurrence frequency is required")),
+        };
+        if count.is_some() && until.is_some() {
+            return Err(parse_error("Recurrence rule cannot contain both COUNT and UNTIL"));
+        }
+        if frequency == RecurrenceFrequency::Monthly && !by_day.is_empty() {
+            return Err(parse_error("BYDAY is not supported for monthly recurrence"));
+        }
+        by_day.sort_by_key(|d| d.num_days_from_monday());
+        by_day.dedup();
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+        })
+    }
+
+    /// Occurrence start times beginning at `dtstart` (the first occurrence) up to and including
+    /// `horizon`. Rules bounded by COUNT or UNTIL stop early.
+    pub fn occurrences(&self, dtstart: NaiveDateTime, horizon: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut occurrences = vec![dtstart];
+        let end = match self.until {
+            Some(until) if until < horizon => until,
+            _ => horizon,
+        };
+        let limit = match self.count {
+            Some(count) => (count as usize).min(MAXIMUM_OCCURRENCES),
+            None => MAXIMUM_OCCURRENCES,
+        };
+
+        let mut period = 0;
+        'periods: while occurrences.len() < limit {
+            for candidate in self.candidates(dtstart, period) {
+                if candidate > end {
+                    break 'periods;
+                }
+                if candidate <= dtstart {
+                    continue;
+                }
+                occurrences.push(candidate);
+                if occurrences.len() >= limit {
+                    break 'periods;
+                }
+            }
+            period += 1;
+
+            // Months with fewer days are skipped so guard against rules that never match
+            if period > (MAXIMUM_OCCURRENCES as u32) * 12 {
+                break;
+            }
+        }
+
+        occurrences
+    }
+
+    fn candidates(&self, dtstart: NaiveDateTime, period: u32) -> Vec<NaiveDateTime> {
+        let step = (period * self.interval) as i64;
+        match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let candidate = dtstart + Duration::days(step);
+                if self.by_day.is_empty() || self.by_day.contains(&candidate.weekday()) {
+                    vec![candidate]
+                } else {
+                    vec![]
+                }
+            }
+            RecurrenceFrequency::Weekly => {
+                if self.by_day.is_empty() {
+                    return vec![dtstart + Duration::weeks(step)];
+                }
+                let week_start =
+                    dtstart - Duration::days(dtstart.weekday().num_days_from_monday() as i64) + Duration::weeks(step);
+                self.by_day
+                    .iter()
+                    .map(|day| week_start + Duration::days(day.num_days_from_monday() as i64))
+                    .collect()
+            }
+            RecurrenceFrequency::Monthly => {
+                let months = dtstart.month0() as i64 + step;
+                let year = dtstart.year() + (months / 12) as i32;
+                let month = (months % 12) as u32 + 1;
+                match NaiveDate::from_ymd_opt(year, month, dtstart.day()) {
+                    Some(date) => vec![date.and_time(dtstart.time())],
+                    None => vec![],
+                }
+            }
+        }
+    }
+
+    fn parse_until(value: &str) -> Option<NaiveDateTime> {
+        let value = value.trim_end_matches('Z');
+        if let Ok(until) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+            return Some(until);
+        }
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(|date| date.and_hms(23, 59, 59))
+    }
+
+    fn parse_weekday(value: &str) -> Option<Weekday> {
+        match value {
+            "MO" => Some(Weekday::Mon),
+            "TU" => Some(Weekday::Tue),
+            "WE" => Some(Weekday::Wed),
+            "TH" => Some(Weekday::Thu),
+            "FR" => Some(Weekday::Fri),
+            "SA" => Some(Weekday::Sat),
+            "SU" => Some(Weekday::Sun),
+            _ => None,
+        }
+    }
+}
@@ -22,6 +22,7 @@ pub struct CodeBuilder<'a> {
     max_tickets_per_user: Option<u32>,
     start_date: NaiveDateTime,
     end_date: NaiveDateTime,
+    discount_rules: CodeDiscountRules,
 }
 
 impl<'a> CodeBuilder<'a> {
@@ -46,6 +47,7 @@ impl<'a> CodeBuilder<'a> {
             max_uses: 30,
             start_date: NaiveDateTime::from(Utc::now().naive_utc() - Duration::days(2)),
             end_date: NaiveDateTime::from(Utc::now().naive_utc() + Duration::days(2)),
+            discount_rules: CodeDiscountRules::default(),
         }
     }
 
@@ -79,6 +81,16 @@ impl<'a> CodeBuilder<'a> {
         self
     }
 
+    pub fn with_discount_as_percentage(mut self, discount_as_percentage: Option<u32>) -> Self {
+        self.discount_as_percentage = discount_as_percentage;
+        self
+    }
+
+    pub fn with_discount_rules(mut self, discount_rules: CodeDiscountRules) -> Self {
+        self.discount_rules = discount_rules;
+        self
+    }
+
     pub fn with_redemption_code(mut self, redemption_code: String) -> Self {
         self.redemption_code = redemption_code;
         self
@@ -130,6 +142,7 @@ impl<'a> CodeBuilder<'a> {
             self.end_date,
             self.max_tickets_per_user,
         )
+        .with_discount_rules(self.discount_rules)
         .commit(None, self.connection)
         .unwrap();
 
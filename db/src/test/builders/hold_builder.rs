@@ -16,6 +16,7 @@ pub struct HoldBuilder<'a> {
     max_per_user: Option<u32>,
     parent_hold_id: Option<Uuid>,
     discount_in_cents: Option<u32>,
+    expiry_behavior: HoldExpiryBehaviors,
     connection: &'a PgConnection,
 }
 
@@ -34,6 +35,7 @@ impl<'a> HoldBuilder<'a> {
             max_per_user: None,
             parent_hold_id: None,
             discount_in_cents: None,
+            expiry_behavior: HoldExpiryBehaviors::Keep,
         }
     }
 
@@ -62,6 +64,11 @@ impl<'a> HoldBuilder<'a> {
         self
     }
 
+    pub fn with_expiry_behavior(mut self, expiry_behavior: HoldExpiryBehaviors) -> Self {
+        self.expiry_behavior = expiry_behavior;
+        self
+    }
+
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
         self
@@ -107,7 +114,7 @@ impl<'a> HoldBuilder<'a> {
             }
         };
 
-        let hold = Hold::create_hold(
+        let mut new_hold = Hold::create_hold(
             self.name,
             self.event_id.unwrap(),
             Some(self.redemption_code),
@@ -120,9 +127,9 @@ impl<'a> HoldBuilder<'a> {
             self.max_per_user,
             self.hold_type,
             ticket_type_id,
-        )
-        .commit(None, self.connection)
-        .unwrap();
+        );
+        new_hold.expiry_behavior = self.expiry_behavior;
+        let hold = new_hold.commit(None, self.connection).unwrap();
 
         hold.set_quantity(None, self.quantity, self.connection).unwrap();
         hold
@@ -19,6 +19,7 @@ pub use self::payment_builder::*;
 pub use self::payment_method_builder::*;
 pub use self::refund_builder::*;
 pub use self::region_builder::*;
+pub use self::seating_section_builder::*;
 pub use self::settlement_adjustment_builder::*;
 pub use self::settlement_builder::*;
 pub use self::settlement_entry_builder::*;
@@ -49,6 +50,7 @@ mod payment_builder;
 mod payment_method_builder;
 mod refund_builder;
 mod region_builder;
+mod seating_section_builder;
 mod settlement_adjustment_builder;
 mod settlement_builder;
 mod settlement_entry_builder;
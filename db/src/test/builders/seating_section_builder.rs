@@ -0,0 +1,69 @@
+use diesel::prelude::*;
+use models::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct SeatingSectionBuilder<'a> {
+    name: String,
+    stage_id: Option<Uuid>,
+    rows: u32,
+    seats_per_row: u32,
+    connection: &'a PgConnection,
+}
+
+impl<'a> SeatingSectionBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> SeatingSectionBuilder {
+        let x: u32 = rand::random();
+
+        SeatingSectionBuilder {
+            connection,
+            name: format!("Section {}", x).into(),
+            stage_id: None,
+            rows: 1,
+            seats_per_row: 10,
+        }
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_stage_id(mut self, stage_id: Uuid) -> Self {
+        self.stage_id = Some(stage_id);
+        self
+    }
+
+    pub fn with_seats(mut self, rows: u32, seats_per_row: u32) -> Self {
+        self.rows = rows;
+        self.seats_per_row = seats_per_row;
+        self
+    }
+
+    pub fn finish(self) -> SeatingSection {
+        let stage_id = self.stage_id.unwrap_or_else(|| {
+            let venue = VenueBuilder::new(self.connection).finish();
+            StageBuilder::new(self.connection).with_venue_id(venue.id).finish().id
+        });
+        let seating_section = SeatingSection::create(stage_id, self.name, 0)
+            .commit(self.connection)
+            .unwrap();
+
+        let mut seats = Vec::new();
+        for row in 0..self.rows {
+            for seat in 0..self.seats_per_row {
+                seats.push(Seat::create(
+                    seating_section.id,
+                    format!("{}", (b'A' + row as u8) as char),
+                    format!("{}", seat + 1),
+                    row as i32,
+                    seat as i32,
+                    false,
+                ));
+            }
+        }
+        Seat::create_multiple(seats, self.connection).unwrap();
+
+        seating_section
+    }
+}
@@ -139,6 +139,10 @@ impl TestProject {
         StageBuilder::new(&self.connection)
     }
 
+    pub fn create_seating_section(&self) -> SeatingSectionBuilder {
+        SeatingSectionBuilder::new(&self.connection)
+    }
+
     pub fn create_event_artist(&self) -> EventArtistBuilder {
         EventArtistBuilder::new(&self.connection)
     }
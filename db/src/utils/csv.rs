@@ -0,0 +1,81 @@
+use csv::{ReaderBuilder, Terminator, Trim, WriterBuilder};
+use utils::errors::*;
+
+/// Parses CSV content into records of trimmed fields, each paired with the line of the content it
+/// starts on. Blank lines are skipped but still counted, as are line breaks within quoted fields,
+/// so the line numbers match what is shown in a spreadsheet.
+pub fn parse_csv(content: &str) -> Result<Vec<(usize, Vec<String>)>, DatabaseError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(Trim::All)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut records = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| DatabaseError::new(ErrorCode::ParseError, Some(e.to_string())))?;
+        let line = record
+            .position()
+            .map(|p| p.line() as usize)
+            .unwrap_or(records.len() + 1);
+        records.push((line, record.iter().map(|field| field.to_string()).collect()));
+    }
+    Ok(records)
+}
+
+/// Writes records as CSV content, quoting fields that contain commas, quotes or line breaks.
+pub fn write_csv(records: &[Vec<String>]) -> Result<String, DatabaseError> {
+    let mut writer = WriterBuilder::new()
+        .flexible(true)
+        .terminator(Terminator::CRLF)
+        .from_writer(Vec::new());
+    for record in records {
+        writer
+            .write_record(record)
+            .map_err(|e| DatabaseError::new(ErrorCode::InternalError, Some(e.to_string())))?;
+    }
+    let content = writer
+        .into_inner()
+        .map_err(|e| DatabaseError::new(ErrorCode::InternalError, Some(e.to_string())))?;
+    String::from_utf8(content).map_err(|e| DatabaseError::new(ErrorCode::InternalError, Some(e.to_string())))
+}
+
+#[test]
+fn test_parse_csv() {
+    assert_eq!(parse_csv("").unwrap(), Vec::<(usize, Vec<String>)>::new());
+    assert_eq!(
+        parse_csv("name,quantity\r\n\r\nBob , 2\n").unwrap(),
+        vec![
+            (1, vec!["name".to_string(), "quantity".to_string()]),
+            (3, vec!["Bob".to_string(), "2".to_string()])
+        ]
+    );
+    assert_eq!(
+        parse_csv("\"Smith, \"\"Bob\"\"\",,\"line\nbreak\"\nAlice,1").unwrap(),
+        vec![
+            (
+                1,
+                vec!["Smith, \"Bob\"".to_string(), "".to_string(), "line\nbreak".to_string()]
+            ),
+            (3, vec!["Alice".to_string(), "1".to_string()])
+        ]
+    );
+}
+
+#[test]
+fn test_write_csv() {
+    let records = vec![
+        vec!["name".to_string(), "quantity".to_string()],
+        vec!["Smith, \"Bob\"".to_string(), "2".to_string()],
+    ];
+    let content = write_csv(&records).unwrap();
+    assert_eq!(content, "name,quantity\r\n\"Smith, \"\"Bob\"\"\",2\r\n");
+    assert_eq!(
+        parse_csv(&content)
+            .unwrap()
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect::<Vec<Vec<String>>>(),
+        records
+    );
+}
@@ -0,0 +1,320 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let other_venue = project.create_venue().finish();
+    let stage = project
+        .create_stage()
+        .with_venue_id(venue.id)
+        .with_capacity(500)
+        .finish();
+    let other_stage = project.create_stage().with_venue_id(other_venue.id).finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    let user = project.create_user().finish();
+
+    assert!(CapacityPool::create(event.id, None, "".to_string(), 100)
+        .commit(Some(user.id), connection)
+        .is_err());
+    assert!(CapacityPool::create(event.id, None, "Venue".to_string(), -1)
+        .commit(Some(user.id), connection)
+        .is_err());
+    // Stage must be part of the event's venue
+    assert!(
+        CapacityPool::create(event.id, Some(other_stage.id), "Stage".to_string(), 100)
+            .commit(Some(user.id), connection)
+            .is_err()
+    );
+    // Pool cannot hold more than the stage
+    assert!(CapacityPool::create(event.id, Some(stage.id), "Stage".to_string(), 501)
+        .commit(Some(user.id), connection)
+        .is_err());
+
+    let capacity_pool = CapacityPool::create(event.id, Some(stage.id), "Stage".to_string(), 500)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(capacity_pool.event_id, event.id);
+    assert_eq!(capacity_pool.stage_id, Some(stage.id));
+    assert_eq!(capacity_pool.capacity, 500);
+    assert_eq!(
+        CapacityPool::find_for_event(event.id, connection).unwrap(),
+        vec![capacity_pool.clone()]
+    );
+
+    assert!(capacity_pool
+        .update(
+            CapacityPoolEditableAttributes {
+                capacity: Some(600),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .is_err());
+    let capacity_pool = capacity_pool
+        .update(
+            CapacityPoolEditableAttributes {
+                capacity: Some(400),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(capacity_pool.capacity, 400);
+}
+
+#[test]
+fn set_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let other_event = project.create_event().with_tickets().finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+
+    let capacity_pool = CapacityPool::create(event.id, None, "Venue".to_string(), 150)
+        .commit(None, connection)
+        .unwrap();
+    let other_capacity_pool = CapacityPool::create(event.id, None, "Balcony".to_string(), 50)
+        .commit(None, connection)
+        .unwrap();
+
+    // Ticket types must belong to the pool's event
+    assert!(capacity_pool
+        .set_ticket_types(&[other_ticket_type.id], None, connection)
+        .is_err());
+
+    let pooled = capacity_pool
+        .set_ticket_types(&[ticket_types[0].id, ticket_types[1].id], None, connection)
+        .unwrap();
+    assert_eq!(pooled.len(), 2);
+    assert!(pooled.iter().all(|t| t.capacity_pool_id == Some(capacity_pool.id)));
+
+    // Ticket types can only draw from one pool
+    assert!(other_capacity_pool
+        .set_ticket_types(&[ticket_types[0].id], None, connection)
+        .is_err());
+
+    capacity_pool
+        .set_ticket_types(&[ticket_types[1].id], None, connection)
+        .unwrap();
+    let display_capacity_pool = capacity_pool.for_display(connection).unwrap();
+    assert_eq!(display_capacity_pool.ticket_type_ids, vec![ticket_types[1].id]);
+    assert_eq!(display_capacity_pool.available, 150);
+    assert_eq!(
+        TicketType::find(ticket_types[0].id, connection)
+            .unwrap()
+            .capacity_pool_id,
+        None
+    );
+
+    capacity_pool.destroy(None, connection).unwrap();
+    assert!(CapacityPool::find(capacity_pool.id, connection).is_err());
+    assert_eq!(
+        TicketType::find(ticket_types[1].id, connection)
+            .unwrap()
+            .capacity_pool_id,
+        None
+    );
+}
+
+#[test]
+fn pooled_availability() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_a_specific_number_of_tickets(10)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let ticket_type = &ticket_types[0];
+    let ticket_type2 = &ticket_types[1];
+    let capacity_pool = CapacityPool::create(event.id, None, "Venue".to_string(), 15)
+        .commit(None, connection)
+        .unwrap();
+    capacity_pool
+        .set_ticket_types(&[ticket_type.id, ticket_type2.id], None, connection)
+        .unwrap();
+    let ticket_type = TicketType::find(ticket_type.id, connection).unwrap();
+    let ticket_type2 = TicketType::find(ticket_type2.id, connection).unwrap();
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 10);
+    assert_eq!(ticket_type2.valid_available_ticket_count(connection).unwrap(), 10);
+
+    // Reserving one ticket type shrinks what the other can sell
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 8,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(capacity_pool.used_capacity(connection).unwrap(), 8);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 2);
+    assert_eq!(ticket_type2.valid_available_ticket_count(connection).unwrap(), 7);
+
+    // Holds draw from the pool as well
+    let hold = project
+        .create_hold()
+        .with_event(&event)
+        .with_ticket_type_id(ticket_type2.id)
+        .with_quantity(5)
+        .finish();
+    assert_eq!(capacity_pool.available(connection).unwrap(), 2);
+    assert_eq!(ticket_type2.valid_available_ticket_count(connection).unwrap(), 2);
+    assert!(hold.set_quantity(None, 8, connection).is_err());
+
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 3,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+
+    // Tickets already held are not counted twice when purchased through the hold
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 5,
+                redemption_code: hold.redemption_code.clone(),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(capacity_pool.used_capacity(connection).unwrap(), 13);
+
+    // Releasing the reservation gives the capacity back to every pooled ticket type
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 0,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(capacity_pool.available(connection).unwrap(), 10);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 10);
+    assert_eq!(ticket_type2.valid_available_ticket_count(connection).unwrap(), 5);
+}
+
+#[test]
+fn capacity_cannot_drop_below_used_capacity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_a_specific_number_of_tickets(10)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let ticket_type = &ticket_types[0];
+    let ticket_type2 = &ticket_types[1];
+    let capacity_pool = CapacityPool::create(event.id, None, "Venue".to_string(), 10)
+        .commit(None, connection)
+        .unwrap();
+    capacity_pool
+        .set_ticket_types(&[ticket_type.id], None, connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 4,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    project
+        .create_hold()
+        .with_event(&event)
+        .with_ticket_type_id(ticket_type2.id)
+        .with_quantity(5)
+        .finish();
+
+    // Capacity cannot be lowered below the tickets already drawing from the pool
+    assert!(capacity_pool
+        .update(
+            CapacityPoolEditableAttributes {
+                capacity: Some(3),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+    let capacity_pool = capacity_pool
+        .update(
+            CapacityPoolEditableAttributes {
+                capacity: Some(4),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(capacity_pool.available(connection).unwrap(), 0);
+
+    // Adding a ticket type brings its held tickets into the pool
+    assert!(capacity_pool
+        .set_ticket_types(&[ticket_type.id, ticket_type2.id], None, connection)
+        .is_err());
+    assert_eq!(
+        TicketType::find(ticket_type2.id, connection).unwrap().capacity_pool_id,
+        None
+    );
+    let capacity_pool = capacity_pool
+        .update(
+            CapacityPoolEditableAttributes {
+                capacity: Some(9),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    capacity_pool
+        .set_ticket_types(&[ticket_type.id, ticket_type2.id], None, connection)
+        .unwrap();
+    assert_eq!(capacity_pool.used_capacity(connection).unwrap(), 9);
+}
@@ -0,0 +1,249 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use uuid::Uuid;
+
+fn create_code_batch(project: &TestProject, event: &Event, quantity: i32) -> CodeBatch {
+    let connection = project.get_connection();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let code = Code::create(
+        "Template".to_string(),
+        event.id,
+        CodeTypes::Discount,
+        String::new(),
+        1,
+        Some(100),
+        None,
+        dates::now().add_days(-1).finish(),
+        dates::now().add_days(1).finish(),
+        None,
+    );
+    CodeBatch::create(
+        event.id,
+        "Partner campaign".to_string(),
+        quantity,
+        Some("prt".to_string()),
+        user.id,
+    )
+    .commit(code, &[ticket_type.id], connection)
+    .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code_batch = create_code_batch(&project, &event, 50);
+    assert_eq!(code_batch.prefix, Some("PRT".to_string()));
+
+    let codes = code_batch.codes(connection).unwrap();
+    assert_eq!(codes.len(), 50);
+    let mut redemption_codes: Vec<String> = codes.iter().map(|c| c.redemption_code.clone()).collect();
+    redemption_codes.dedup();
+    assert_eq!(redemption_codes.len(), 50);
+    assert!(redemption_codes.iter().all(|r| r.starts_with("PRT")));
+
+    // Each code resolves on its own and shares the batch configuration
+    let code = Code::find_by_redemption_code_with_availability(&redemption_codes[0], Some(event.id), connection)
+        .unwrap()
+        .code;
+    assert_eq!(code.code_batch_id, Some(code_batch.id));
+    assert_eq!(code.name, code_batch.name);
+    assert_eq!(code.max_uses, 1);
+    assert_eq!(code.discount_in_cents, Some(100));
+    assert_eq!(
+        TicketType::find_for_code(code.id, connection)
+            .unwrap()
+            .into_iter()
+            .map(|tt| tt.id)
+            .collect::<Vec<Uuid>>(),
+        vec![ticket_type.id]
+    );
+
+    // Batch codes are not listed with the event's individual codes
+    assert!(Code::find_for_event(event.id, None, connection).unwrap().is_empty());
+    assert_eq!(
+        CodeBatch::find_for_event(event.id, connection).unwrap(),
+        vec![code_batch]
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let other_event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let code = Code::create(
+        "Template".to_string(),
+        event.id,
+        CodeTypes::Discount,
+        String::new(),
+        1,
+        Some(100),
+        None,
+        dates::now().add_days(-1).finish(),
+        dates::now().add_days(1).finish(),
+        None,
+    );
+
+    assert_eq!(
+        CodeBatch::create(event.id, "Campaign".to_string(), 0, None, user.id).commit(
+            code.clone(),
+            &[ticket_type.id],
+            connection
+        ),
+        DatabaseError::validation_error("quantity", "Quantity must be between 1 and 10000")
+    );
+    assert_eq!(
+        CodeBatch::create(
+            event.id,
+            "Campaign".to_string(),
+            5,
+            Some("NO-DASH".to_string()),
+            user.id
+        )
+        .commit(code.clone(), &[ticket_type.id], connection),
+        DatabaseError::validation_error("prefix", "Prefix must be at most 10 letters or numbers")
+    );
+    assert_eq!(
+        CodeBatch::create(event.id, "Campaign".to_string(), 5, None, user.id).commit(
+            code.clone(),
+            &[other_ticket_type.id],
+            connection
+        ),
+        DatabaseError::validation_error("ticket_type_ids", "Ticket types must belong to the code batch's event")
+    );
+
+    // The shared configuration is validated like a single code
+    let code = Code::create(
+        "Template".to_string(),
+        event.id,
+        CodeTypes::Discount,
+        String::new(),
+        1,
+        None,
+        None,
+        dates::now().add_days(-1).finish(),
+        dates::now().add_days(1).finish(),
+        None,
+    );
+    assert!(CodeBatch::create(event.id, "Campaign".to_string(), 5, None, user.id)
+        .commit(code, &[ticket_type.id], connection)
+        .is_err());
+    assert!(CodeBatch::find_for_event(event.id, connection).unwrap().is_empty());
+}
+
+#[test]
+fn summary_and_to_csv() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code_batch = create_code_batch(&project, &event, 3);
+    let codes = code_batch.codes(connection).unwrap();
+
+    project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .with_redemption_code(codes[0].redemption_code.clone())
+        .is_paid()
+        .finish();
+
+    let summary = code_batch.summary(connection).unwrap();
+    assert_eq!(summary.code_count, 3);
+    assert_eq!(summary.redeemed_code_count, 1);
+    assert_eq!(summary.ticket_count, 1);
+    assert_eq!(summary.discount_in_cents, 100);
+
+    let csv = code_batch.to_csv(connection).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "redemption_code,status,redeemed_quantity");
+    assert_eq!(lines[1], format!("{},Redeemed,1", codes[0].redemption_code));
+    assert_eq!(lines[2], format!("{},Available,0", codes[1].redemption_code));
+}
+
+#[test]
+fn codes_are_used_once_per_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code_batch = create_code_batch(&project, &event, 1);
+    let redemption_code = code_batch.codes(connection).unwrap()[0].redemption_code.clone();
+
+    // A single order can use the code for several tickets
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert!(cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: Some(redemption_code.clone()),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_ok());
+    assert_eq!(
+        Code::find_by_redemption_code_with_availability(&redemption_code, Some(event.id), connection)
+            .unwrap()
+            .available,
+        0
+    );
+
+    // Another order cannot
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert!(cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: Some(redemption_code),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let code_batch = create_code_batch(&project, &event, 3);
+    let codes = code_batch.codes(connection).unwrap();
+
+    let code_batch = code_batch.revoke(None, connection).unwrap();
+    assert!(code_batch.revoked_at.is_some());
+    assert!(
+        Code::find_by_redemption_code_with_availability(&codes[0].redemption_code, Some(event.id), connection).is_err()
+    );
+    assert!(code_batch
+        .to_csv(connection)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .all(|line| line.ends_with(",Revoked,0")));
+
+    assert_eq!(
+        code_batch.revoke(None, connection),
+        DatabaseError::business_process_error("Code batch has already been revoked")
+    );
+}
@@ -689,3 +689,453 @@ pub fn find_for_event_access_code() {
 
     assert_eq!(event.ticket_types(true, None, conn).unwrap().len(), 1);
 }
+
+#[test]
+pub fn create_with_discount_rule_validation_errors() {
+    let db = TestProject::new();
+    let event = db.create_event().with_tickets().finish();
+    let start_date = NaiveDateTime::from(Utc::now().naive_utc() - Duration::days(1));
+    let end_date = NaiveDateTime::from(Utc::now().naive_utc() + Duration::days(2));
+
+    // Discount rules on an access code
+    let result = Code::create(
+        "test".into(),
+        event.id,
+        CodeTypes::Access,
+        "ACCESSRULES".into(),
+        10,
+        None,
+        None,
+        start_date,
+        end_date,
+        None,
+    )
+    .with_discount_rules(CodeDiscountRules {
+        waives_fees: true,
+        ..Default::default()
+    })
+    .commit(None, db.get_connection());
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("discount_rules"));
+                assert_eq!(errors["discount_rules"][0].code, "discount_rules_require_discount_type");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Buy quantity without a get quantity
+    let result = Code::create(
+        "test".into(),
+        event.id,
+        CodeTypes::Discount,
+        "BUYONLY".into(),
+        10,
+        Some(100),
+        None,
+        start_date,
+        end_date,
+        None,
+    )
+    .with_discount_rules(CodeDiscountRules {
+        buy_quantity: Some(2),
+        ..Default::default()
+    })
+    .commit(None, db.get_connection());
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("discount_rules"));
+                assert_eq!(errors["discount_rules"][0].code, "buy_and_get_quantity_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Order discount together with a per ticket discount
+    let result = Code::create(
+        "test".into(),
+        event.id,
+        CodeTypes::Discount,
+        "TWODISCOUNTS".into(),
+        10,
+        Some(100),
+        None,
+        start_date,
+        end_date,
+        None,
+    )
+    .with_discount_rules(CodeDiscountRules {
+        order_discount_in_cents: Some(500),
+        ..Default::default()
+    })
+    .commit(None, db.get_connection());
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("discount_in_cents"));
+                assert_eq!(errors["discount_in_cents"][0].code, "only_single_discount_type_allowed");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn discount_for_order_item_buy_x_get_y() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(None)
+        .with_discount_as_percentage(Some(100))
+        .with_discount_rules(CodeDiscountRules {
+            buy_quantity: Some(2),
+            get_quantity: Some(1),
+            ..Default::default()
+        })
+        .finish();
+
+    // Two tickets do not earn a free ticket
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: Some(code.redemption_code.clone()),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert_eq!(code.discount_for_order_item(&order_item, connection).unwrap(), (0, 0));
+    assert!(order_item.find_discount_item(connection).unwrap().is_none());
+
+    // The third ticket is free, spread across the three tickets
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_tickets(ticket_type.id)
+        .quantity(3)
+        .with_redemption_code(code.redemption_code.clone())
+        .is_paid()
+        .finish();
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert_eq!(
+        code.discount_for_order_item(&order_item, connection).unwrap(),
+        (order_item.unit_price_in_cents, 1)
+    );
+    let discount_item = order_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(discount_item.quantity, 3);
+    assert_eq!(discount_item.unit_price_in_cents, -order_item.unit_price_in_cents / 3);
+    let remainder = order_item.unit_price_in_cents % 3;
+    match discount_item.find_discount_item(connection).unwrap() {
+        Some(remainder_item) => assert_eq!(remainder_item.quantity * remainder_item.unit_price_in_cents, -remainder),
+        None => assert_eq!(remainder, 0),
+    }
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+
+    // Refunds return the ticket less the discount the remaining two tickets no longer earn,
+    // leaving only its fee
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(amount, fee_item.unit_price_in_cents);
+    let refund_items = refund.items(connection).unwrap();
+    assert!(refund_items.iter().any(|i| i.order_item_id == discount_item.id
+        && i.quantity == 0
+        && i.amount == -(order_item.unit_price_in_cents + discount_item.unit_price_in_cents)));
+
+    // With the free ticket taken back the next refund returns the full price
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[1];
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (_, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(amount, order_item.unit_price_in_cents + fee_item.unit_price_in_cents);
+}
+
+#[test]
+fn discount_for_order_item_min_quantity_and_order_discount() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(None)
+        .with_discount_rules(CodeDiscountRules {
+            min_quantity: Some(3),
+            order_discount_in_cents: Some(90),
+            ..Default::default()
+        })
+        .finish();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let update_quantity = |cart: &mut Order, quantity: u32| {
+        cart.update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity,
+                redemption_code: Some(code.redemption_code.clone()),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+        cart.items(connection)
+            .unwrap()
+            .into_iter()
+            .find(|i| i.item_type == OrderItemTypes::Tickets)
+            .unwrap()
+    };
+
+    // Below the minimum quantity
+    let order_item = update_quantity(&mut cart, 2);
+    assert!(order_item.find_discount_item(connection).unwrap().is_none());
+
+    // The order discount is shared by the tickets
+    let order_item = update_quantity(&mut cart, 3);
+    let discount_item = order_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(discount_item.unit_price_in_cents, -30);
+    assert!(discount_item.find_discount_item(connection).unwrap().is_none());
+
+    // Cents that do not divide evenly are taken off some of the tickets
+    let order_item = update_quantity(&mut cart, 4);
+    let discount_item = order_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(discount_item.unit_price_in_cents, -22);
+    let remainder_item = discount_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(remainder_item.quantity, 2);
+    assert_eq!(remainder_item.unit_price_in_cents, -1);
+    let discount_total: i64 = cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Discount)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(discount_total, -90);
+}
+
+#[test]
+fn discount_waives_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(Some(10))
+        .with_discount_rules(CodeDiscountRules {
+            waives_fees: true,
+            ..Default::default()
+        })
+        .finish();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: Some(code.redemption_code.clone()),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert!(order_item.find_discount_item(connection).unwrap().is_some());
+    assert!(order_item.find_fee_item(connection).unwrap().is_none());
+}
+
+#[test]
+fn discount_waives_fees_for_discounted_tickets_only() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(None)
+        .with_discount_as_percentage(Some(100))
+        .with_discount_rules(CodeDiscountRules {
+            buy_quantity: Some(2),
+            get_quantity: Some(1),
+            waives_fees: true,
+            ..Default::default()
+        })
+        .finish();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: Some(code.redemption_code.clone()),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.quantity, 2);
+}
+
+#[test]
+fn discount_stacked_with_hold() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let hold = project
+        .create_hold()
+        .with_event(&event)
+        .with_ticket_type_id(ticket_type.id)
+        .with_discount_in_cents(10)
+        .finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let add_to_cart = |cart: &mut Order, code: &Code| {
+        cart.update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: Some(format!(
+                    "{},{}",
+                    hold.redemption_code.clone().unwrap(),
+                    code.redemption_code
+                )),
+            }],
+            false,
+            true,
+            connection,
+        )
+    };
+
+    // Exclusive codes cannot be combined with a hold
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(Some(20))
+        .finish();
+    let result = add_to_cart(&mut cart, &code);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("redemption_code"));
+                assert_eq!(
+                    errors["redemption_code"][0].message.clone().unwrap().into_owned(),
+                    "Redemption code cannot be combined with a hold"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Best discount uses the larger of the two discounts
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(Some(20))
+        .with_discount_rules(CodeDiscountRules {
+            hold_stacking: Some(CodeHoldStackingRules::BestDiscount),
+            ..Default::default()
+        })
+        .finish();
+    add_to_cart(&mut cart, &code).unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    assert_eq!(order_item.hold_id, Some(hold.id));
+    assert_eq!(order_item.code_id, Some(code.id));
+    let discount_item = order_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(discount_item.unit_price_in_cents, -20);
+    assert_eq!(
+        cart.redemption_code(connection).unwrap(),
+        Some(format!(
+            "{},{}",
+            hold.redemption_code.clone().unwrap(),
+            code.redemption_code
+        ))
+    );
+
+    // Combined discounts add up
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .with_discount_in_cents(Some(20))
+        .with_discount_rules(CodeDiscountRules {
+            hold_stacking: Some(CodeHoldStackingRules::Combine),
+            ..Default::default()
+        })
+        .finish();
+    add_to_cart(&mut cart, &code).unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let discount_item = order_item.find_discount_item(connection).unwrap().unwrap();
+    assert_eq!(discount_item.unit_price_in_cents, -30);
+}
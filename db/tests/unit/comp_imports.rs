@@ -0,0 +1,133 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn from_csv() {
+    let project = TestProject::new();
+    let hold = project.create_hold().with_hold_type(HoldTypes::Comp).finish();
+
+    let comp_import = CompImport::from_csv(
+        hold.id,
+        "Name,Email,Phone,Quantity\r\n\"Smith, Bob\",bob@address.com,,2\nAlice\nCarol,,5551234567,two\n\nDave,,,1\n",
+        None,
+        Some(1),
+    )
+    .unwrap();
+    assert_eq!(
+        comp_import.rows,
+        vec![
+            CompImportRow {
+                row: 2,
+                name: "Smith, Bob".to_string(),
+                email: Some("bob@address.com".to_string()),
+                phone: None,
+                quantity: 2,
+            },
+            CompImportRow {
+                row: 6,
+                name: "Dave".to_string(),
+                email: None,
+                phone: None,
+                quantity: 1,
+            },
+        ]
+    );
+    assert_eq!(
+        comp_import.errors,
+        vec![
+            CompImportRowError {
+                row: 3,
+                errors: vec!["Expected 4 columns (name, email, phone, quantity) but found 1".to_string()],
+            },
+            CompImportRowError {
+                row: 4,
+                errors: vec!["Quantity 'two' is not a valid number".to_string()],
+            },
+        ]
+    );
+    assert_eq!(comp_import.max_per_user, Some(1));
+
+    // Line breaks inside quoted fields still count towards the row numbers
+    let comp_import = CompImport::from_csv(hold.id, "\"Bob\nSmith\",,,1\nCarol,,,two", None, None).unwrap();
+    assert_eq!(comp_import.rows[0].name, "Bob\nSmith".to_string());
+    assert_eq!(
+        comp_import.errors,
+        vec![CompImportRowError {
+            row: 3,
+            errors: vec!["Quantity 'two' is not a valid number".to_string()],
+        }]
+    );
+
+    // An unterminated quote takes up the rest of the file as a single field
+    let comp_import = CompImport::from_csv(hold.id, "\"Bob,,,1", None, None).unwrap();
+    assert!(comp_import.rows.is_empty());
+    assert_eq!(comp_import.errors.len(), 1);
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let hold = project
+        .create_hold()
+        .with_hold_type(HoldTypes::Comp)
+        .with_quantity(5)
+        .finish();
+
+    // Every row is validated and nothing is created if any of them fail
+    let result = CompImport::from_csv(hold.id, ",bob@address.com,,2\nAlice,invalid,,0\nCarol,,,4", None, None)
+        .unwrap()
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert!(result.comps.is_empty());
+    assert_eq!(
+        result.errors,
+        vec![
+            CompImportRowError {
+                row: 1,
+                errors: vec!["Name is required".to_string()],
+            },
+            CompImportRowError {
+                row: 2,
+                errors: vec![
+                    "Email is invalid".to_string(),
+                    "Quantity must be greater than 0".to_string(),
+                ],
+            },
+            CompImportRowError {
+                row: 3,
+                errors: vec!["Quantity exceeds the 5 tickets remaining in the hold".to_string()],
+            },
+        ]
+    );
+    assert!(
+        Hold::find_by_parent_id(hold.id, Some(HoldTypes::Comp), 0, 100, connection)
+            .unwrap()
+            .data
+            .is_empty()
+    );
+
+    assert!(CompImport::from_csv(hold.id, "name,email,phone,quantity\n", None, None)
+        .unwrap()
+        .commit(Some(user.id), connection)
+        .is_err());
+
+    let result = CompImport::from_csv(hold.id, "Bob,bob@address.com,,2\nAlice,,5551234567,3", None, Some(2))
+        .unwrap()
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert!(result.errors.is_empty());
+    assert_eq!(result.comps.len(), 2);
+    let comp = &result.comps[0];
+    assert_eq!(comp.name, "Bob");
+    assert_eq!(comp.email, Some("bob@address.com".to_string()));
+    assert_eq!(comp.parent_hold_id, Some(hold.id));
+    assert_eq!(comp.hold_type, HoldTypes::Comp);
+    assert_eq!(comp.max_per_user, Some(2));
+    assert!(comp.redemption_code.is_some());
+    assert_eq!(comp.quantity(connection).unwrap(), (2, 2));
+    assert_eq!(result.comps[1].phone, Some("5551234567".to_string()));
+    assert_eq!(result.comps[1].quantity(connection).unwrap(), (3, 3));
+    assert_eq!(hold.quantity(connection).unwrap(), (0, 0));
+}
@@ -0,0 +1,445 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
+use uuid::Uuid;
+
+fn schedule_for(date: NaiveDate, capacity: u32) -> EntrySlotSchedule {
+    EntrySlotSchedule {
+        start_date: date,
+        end_date: date,
+        first_entry: NaiveTime::from_hms(0, 0, 0),
+        last_entry: NaiveTime::from_hms(23, 0, 0),
+        interval_minutes: 60,
+        entry_window_minutes: None,
+        capacity,
+    }
+}
+
+fn move_slot(entry_slot: &EntrySlot, entry_start: NaiveDateTime, connection: &PgConnection) -> EntrySlot {
+    use bigneon_db::schema::entry_slots;
+    diesel::update(entry_slot)
+        .set((
+            entry_slots::entry_start.eq(entry_start),
+            entry_slots::entry_end.eq(entry_start + Duration::hours(1)),
+        ))
+        .get_result(connection)
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let entry_start = NaiveDate::from_ymd(2050, 7, 8).and_hms(10, 0, 0);
+    let entry_slot = EntrySlot::create(ticket_type.id, entry_start, entry_start + Duration::minutes(30), 25)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(entry_slot.ticket_type_id, ticket_type.id);
+    assert_eq!(entry_slot.entry_start, entry_start);
+    assert_eq!(entry_slot.entry_end, entry_start + Duration::minutes(30));
+    assert_eq!(entry_slot.capacity, 25);
+
+    // Entry end must follow entry start
+    let result = EntrySlot::create(ticket_type.id, entry_start, entry_start, 25).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("entry_end"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn schedule_slots() {
+    let ticket_type_id = Uuid::new_v4();
+    let schedule = EntrySlotSchedule {
+        start_date: NaiveDate::from_ymd(2050, 7, 8),
+        end_date: NaiveDate::from_ymd(2050, 7, 9),
+        first_entry: NaiveTime::from_hms(9, 0, 0),
+        last_entry: NaiveTime::from_hms(10, 0, 0),
+        interval_minutes: 30,
+        entry_window_minutes: Some(15),
+        capacity: 50,
+    };
+    let slots = schedule.slots(ticket_type_id).unwrap();
+
+    let starts: Vec<NaiveDateTime> = slots.iter().map(|s| s.entry_start).collect();
+    assert_eq!(
+        starts,
+        vec![
+            NaiveDate::from_ymd(2050, 7, 8).and_hms(9, 0, 0),
+            NaiveDate::from_ymd(2050, 7, 8).and_hms(9, 30, 0),
+            NaiveDate::from_ymd(2050, 7, 8).and_hms(10, 0, 0),
+            NaiveDate::from_ymd(2050, 7, 9).and_hms(9, 0, 0),
+            NaiveDate::from_ymd(2050, 7, 9).and_hms(9, 30, 0),
+            NaiveDate::from_ymd(2050, 7, 9).and_hms(10, 0, 0),
+        ]
+    );
+    for slot in &slots {
+        assert_eq!(slot.ticket_type_id, ticket_type_id);
+        assert_eq!(slot.entry_end, slot.entry_start + Duration::minutes(15));
+        assert_eq!(slot.capacity, 50);
+    }
+
+    // Interval must be positive
+    let schedule = EntrySlotSchedule {
+        interval_minutes: 0,
+        ..schedule
+    };
+    assert!(schedule.slots(ticket_type_id).is_err());
+}
+
+#[test]
+fn create_from_schedule() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = NaiveDate::from_ymd(2050, 7, 8);
+    let schedule = EntrySlotSchedule {
+        last_entry: NaiveTime::from_hms(2, 0, 0),
+        ..schedule_for(date, 10)
+    };
+
+    let entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, None, connection).unwrap();
+    assert_eq!(entry_slots.len(), 3);
+    assert_eq!(
+        EntrySlot::find_for_ticket_type(ticket_type.id, connection).unwrap(),
+        entry_slots
+    );
+
+    // Re-submitting an extended schedule only adds the missing slots
+    let schedule = EntrySlotSchedule {
+        last_entry: NaiveTime::from_hms(3, 0, 0),
+        ..schedule
+    };
+    let new_entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, None, connection).unwrap();
+    assert_eq!(new_entry_slots.len(), 1);
+    assert_eq!(new_entry_slots[0].entry_start, date.and_hms(3, 0, 0));
+    assert_eq!(
+        EntrySlot::find_for_ticket_type(ticket_type.id, connection)
+            .unwrap()
+            .len(),
+        4
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketTypes,
+        Some(ticket_type.id),
+        Some(DomainEventTypes::TicketTypeEntrySlotsCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+}
+
+#[test]
+fn reserve_tickets_assigns_first_available_slot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = (Utc::now().naive_utc() + Duration::days(2)).date();
+    let schedule = EntrySlotSchedule {
+        last_entry: NaiveTime::from_hms(1, 0, 0),
+        ..schedule_for(date, 2)
+    };
+    let entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, None, connection).unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    for ticket in TicketInstance::find_for_order_item(order_item.id, connection).unwrap() {
+        assert_eq!(ticket.entry_slot_id, Some(entry_slots[0].id));
+    }
+
+    // First slot is full so the next cart lands in the second slot
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let order_item = cart2.items(connection).unwrap().remove(0);
+    let ticket = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(ticket.entry_slot_id, Some(entry_slots[1].id));
+
+    let availability = EntrySlot::find_for_ticket_type_with_availability(ticket_type.id, connection).unwrap();
+    assert_eq!(availability[0].available, 0);
+    assert_eq!(availability[1].available, 1);
+
+    // Not enough room left in any slot
+    let user3 = project.create_user().finish();
+    let mut cart3 = Order::find_or_create_cart(&user3, connection).unwrap();
+    let result = cart3.update_quantities(
+        user3.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("quantity"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Removing tickets from the cart frees the slot
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 0,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let availability = EntrySlot::find_for_ticket_type_with_availability(ticket_type.id, connection).unwrap();
+    assert_eq!(availability[0].available, 2);
+}
+
+#[test]
+fn update_entry_slot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = (Utc::now().naive_utc() + Duration::days(2)).date();
+    let schedule = EntrySlotSchedule {
+        last_entry: NaiveTime::from_hms(1, 0, 0),
+        ..schedule_for(date, 2)
+    };
+    let entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, None, connection).unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    cart.update_entry_slot(entry_slots[1].id, connection).unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    for ticket in TicketInstance::find_for_order_item(order_item.id, connection).unwrap() {
+        assert_eq!(ticket.entry_slot_id, Some(entry_slots[1].id));
+    }
+
+    // Slot without enough room left
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let result = cart2.update_entry_slot(entry_slots[1].id, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("entry_slot_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Slot for a ticket type not in the cart
+    let event2 = project.create_event().with_ticket_pricing().finish();
+    let ticket_type2 = event2.ticket_types(true, None, connection).unwrap().remove(0);
+    let other_slots = EntrySlot::create_from_schedule(&ticket_type2, &schedule, None, connection).unwrap();
+    assert!(cart2.update_entry_slot(other_slots[0].id, connection).is_err());
+}
+
+#[test]
+fn redeem_ticket_outside_entry_window() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = (Utc::now().naive_utc() + Duration::days(2)).date();
+    let entry_slot = EntrySlot::create_from_schedule(&ticket_type, &schedule_for(date, 10), None, connection)
+        .unwrap()
+        .remove(0);
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let mut tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = tickets.remove(0);
+    let ticket2 = tickets.remove(0);
+    assert_eq!(ticket.entry_slot_id, Some(entry_slot.id));
+
+    // Slot has not opened yet so entry is rejected
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketOutsideEntryWindow);
+    assert_eq!(
+        TicketInstance::find(ticket.id, connection).unwrap().status,
+        TicketInstanceStatus::Purchased
+    );
+
+    // Slot is open
+    let entry_slot = move_slot(&entry_slot, Utc::now().naive_utc() - Duration::minutes(5), connection);
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+
+    // Slot has closed but the ticket type only warns
+    move_slot(&entry_slot, Utc::now().naive_utc() - Duration::hours(3), connection);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                entry_window_policy: Some(EntryWindowPolicy::Warn),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let result = TicketInstance::redeem_ticket(
+        ticket2.id,
+        ticket2.redeem_key.clone().unwrap(),
+        admin.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemedOutsideEntryWindow);
+    assert_eq!(
+        TicketInstance::find(ticket2.id, connection).unwrap().status,
+        TicketInstanceStatus::Redeemed
+    );
+}
+
+#[test]
+fn guest_list_shows_entry_slot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = (Utc::now().naive_utc() + Duration::days(2)).date();
+    let entry_slot = EntrySlot::create_from_schedule(&ticket_type, &schedule_for(date, 10), None, connection)
+        .unwrap()
+        .remove(0);
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+
+    let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection).unwrap();
+    assert_eq!(redeemable.entry_start, Some(entry_slot.entry_start));
+    assert_eq!(redeemable.entry_end, Some(entry_slot.entry_end));
+
+    let (_, _, display_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
+    assert_eq!(display_ticket.entry_start, Some(entry_slot.entry_start));
+    assert_eq!(display_ticket.entry_end, Some(entry_slot.entry_end));
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let date = (Utc::now().naive_utc() + Duration::days(2)).date();
+    let schedule = EntrySlotSchedule {
+        last_entry: NaiveTime::from_hms(1, 0, 0),
+        ..schedule_for(date, 10)
+    };
+    let entry_slots = EntrySlot::create_from_schedule(&ticket_type, &schedule, None, connection).unwrap();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    // Slot with tickets cannot be removed
+    assert!(entry_slots[0].destroy(None, connection).is_err());
+
+    entry_slots[1].destroy(None, connection).unwrap();
+    assert!(EntrySlot::find(entry_slots[1].id, connection).is_err());
+}
@@ -0,0 +1,174 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use diesel::PgConnection;
+
+fn create_questions(event: &Event, connection: &PgConnection) -> (EventQuestion, EventQuestion) {
+    let company = EventQuestion::create(
+        event.id,
+        "Company".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        false,
+        false,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let name = EventQuestion::create(
+        event.id,
+        "Name on badge".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        true,
+        true,
+        1,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    (company, name)
+}
+
+#[test]
+fn questions_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let (company, name) = create_questions(&event, connection);
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .for_user(&user)
+        .finish();
+
+    let questions = EventAnswer::questions_for_order(&order, connection).unwrap();
+    assert_eq!(questions.len(), 3);
+    assert_eq!(questions[0].question, company);
+    assert_eq!(questions[0].ticket_instance_id, None);
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    for question in &questions[1..] {
+        assert_eq!(question.question, name);
+        assert!(ticket_ids.contains(&question.ticket_instance_id.unwrap()));
+        assert_eq!(question.answer, None);
+    }
+}
+
+#[test]
+fn save_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let (company, name) = create_questions(&event, connection);
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .for_user(&user)
+        .finish();
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+
+    // Per ticket question is required
+    assert_eq!(
+        EventAnswer::save_for_order(
+            &order,
+            &[EventAnswerRequest {
+                event_question_id: company.id,
+                ticket_instance_id: None,
+                answer: "Big Neon".to_string(),
+            }],
+            Some(user.id),
+            connection,
+        ),
+        DatabaseError::validation_error("answers", "Required questions must be answered")
+    );
+    // Per ticket question must be answered for a ticket
+    assert_eq!(
+        EventAnswer::save_for_order(
+            &order,
+            &[EventAnswerRequest {
+                event_question_id: name.id,
+                ticket_instance_id: None,
+                answer: "Jane".to_string(),
+            }],
+            Some(user.id),
+            connection,
+        ),
+        DatabaseError::validation_error("answers", "Answer does not match a question for this order")
+    );
+
+    let answers: Vec<EventAnswerRequest> = ticket_ids
+        .iter()
+        .map(|id| EventAnswerRequest {
+            event_question_id: name.id,
+            ticket_instance_id: Some(*id),
+            answer: "Jane".to_string(),
+        })
+        .collect();
+    let questions = EventAnswer::save_for_order(&order, &answers, Some(user.id), connection).unwrap();
+    assert_eq!(questions.iter().filter(|q| q.answer.is_some()).count(), 2);
+    assert_eq!(EventAnswer::find_for_order(order.id, connection).unwrap().len(), 2);
+
+    // Saving again replaces the answer
+    EventAnswer::save_for_order(&order, &answers[0..1], Some(user.id), connection).unwrap();
+    assert_eq!(EventAnswer::find_for_order(order.id, connection).unwrap().len(), 2);
+}
+
+#[test]
+fn save_for_order_after_cutoff() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let (company, _) = create_questions(&event, connection);
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let answers = vec![EventAnswerRequest {
+        event_question_id: company.id,
+        ticket_instance_id: None,
+        answer: "Big Neon".to_string(),
+    }];
+
+    // Paid orders can change their answers without answering every question
+    EventAnswer::save_for_order(&order, &answers, Some(user.id), connection).unwrap();
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Orders,
+            Some(order.id),
+            Some(DomainEventTypes::OrderAttendeeAnswersUpdated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+    let answers_for_profile =
+        EventAnswer::find_for_user_and_organization(user.id, event.organization_id, connection).unwrap();
+    assert_eq!(answers_for_profile.len(), 1);
+    assert_eq!(answers_for_profile[0].answer, "Big Neon".to_string());
+
+    company
+        .update(
+            EventQuestionEditableAttributes {
+                answers_editable_until: Some(Some(dates::now().add_hours(-1).finish())),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        EventAnswer::save_for_order(&order, &answers, Some(user.id), connection),
+        DatabaseError::business_process_error("Answers can no longer be changed for this event")
+    );
+}
@@ -0,0 +1,191 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    // Choice questions need choices to pick from
+    assert!(EventQuestion::create(
+        event.id,
+        "T-shirt size".to_string(),
+        EventQuestionTypes::Choice,
+        Vec::new(),
+        false,
+        true,
+        0,
+        None,
+    )
+    .commit(Some(user.id), connection)
+    .is_err());
+
+    let event_question = EventQuestion::create(
+        event.id,
+        "T-shirt size".to_string(),
+        EventQuestionTypes::Choice,
+        vec!["S".to_string(), "M".to_string()],
+        false,
+        true,
+        0,
+        None,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(
+        EventQuestion::find(event_question.id, connection).unwrap(),
+        event_question
+    );
+    assert_eq!(
+        DomainEvent::find(
+            Tables::EventQuestions,
+            Some(event_question.id),
+            Some(DomainEventTypes::EventQuestionCreated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event_question = EventQuestion::create(
+        event.id,
+        "Company".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        false,
+        false,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    assert!(event_question
+        .update(
+            EventQuestionEditableAttributes {
+                question_type: Some(EventQuestionTypes::Choice),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+
+    let event_question = event_question
+        .update(
+            EventQuestionEditableAttributes {
+                question: Some("Company name".to_string()),
+                required: Some(true),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(event_question.question, "Company name".to_string());
+    assert!(event_question.required);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event_question = EventQuestion::create(
+        event.id,
+        "Company".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        false,
+        false,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    event_question.destroy(None, connection).unwrap();
+    assert!(EventQuestion::find(event_question.id, connection).is_err());
+    assert!(EventQuestion::find_for_event(event.id, connection).unwrap().is_empty());
+}
+
+#[test]
+fn validate_answer() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let choice = EventQuestion::create(
+        event.id,
+        "Meal".to_string(),
+        EventQuestionTypes::Choice,
+        vec!["Vegan".to_string(), "Regular".to_string()],
+        false,
+        false,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert!(choice.validate_answer("Vegan").is_ok());
+    assert!(choice.validate_answer("").is_ok());
+    assert!(choice.validate_answer("Fish").is_err());
+
+    let waiver = EventQuestion::create(
+        event.id,
+        "I accept the waiver".to_string(),
+        EventQuestionTypes::Checkbox,
+        Vec::new(),
+        true,
+        false,
+        1,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert!(waiver.validate_answer("true").is_ok());
+    assert!(waiver.validate_answer("false").is_err());
+    assert!(waiver.validate_answer("yes").is_err());
+}
+
+#[test]
+fn answers_editable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event_question = EventQuestion::create(
+        event.id,
+        "Company".to_string(),
+        EventQuestionTypes::Text,
+        Vec::new(),
+        false,
+        false,
+        0,
+        None,
+    )
+    .commit(None, connection)
+    .unwrap();
+    // Defaults to the event start
+    assert!(event_question.answers_editable(&event));
+
+    let event_question = event_question
+        .update(
+            EventQuestionEditableAttributes {
+                answers_editable_until: Some(Some(dates::now().add_hours(-1).finish())),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(!event_question.answers_editable(&event));
+}
@@ -0,0 +1,183 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let now = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+    let event_start = now + Duration::days(30);
+    let event_end = now + Duration::days(31);
+    let refund_window_ends_at = now + Duration::days(7);
+
+    // Refund window must close before the new date
+    assert!(EventReschedule::create(
+        event.id,
+        event_start,
+        event_end,
+        None,
+        event_start + Duration::days(1),
+        user.id
+    )
+    .commit(connection)
+    .is_err());
+    assert!(EventReschedule::create(
+        event.id,
+        now - Duration::days(1),
+        event_end,
+        None,
+        refund_window_ends_at,
+        user.id
+    )
+    .commit(connection)
+    .is_err());
+
+    let event_reschedule =
+        EventReschedule::create(event.id, event_start, event_end, None, refund_window_ends_at, user.id)
+            .commit(connection)
+            .unwrap();
+    assert_eq!(event_reschedule.previous_event_start, event.event_start);
+    assert_eq!(event_reschedule.previous_event_end, event.event_end);
+    // Door time keeps the same offset from the event start
+    assert_eq!(event_reschedule.door_time, Some(event_start - Duration::hours(1)));
+    assert!(event_reschedule.is_refund_window_open());
+    assert_eq!(
+        EventReschedule::find_open_for_event(event.id, connection).unwrap(),
+        Some(event_reschedule.clone())
+    );
+
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.event_start, Some(event_start));
+    assert_eq!(event.event_end, Some(event_end));
+    assert_eq!(event.door_time, event_reschedule.door_time);
+    assert_eq!(event.override_status, Some(EventOverrideStatus::Rescheduled));
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::EventReschedules),
+        Some(event_reschedule.id),
+        DomainActionTypes::CloseEventRescheduleRefundWindow,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(domain_actions[0].scheduled_at, refund_window_ends_at);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Events,
+            Some(event.id),
+            Some(DomainEventTypes::EventRescheduled),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+
+    // Only one refund window can be open at a time
+    assert_eq!(
+        EventReschedule::create(event.id, event_start, event_end, None, refund_window_ends_at, user.id)
+            .commit(connection),
+        DatabaseError::business_process_error("Event already has an open refund window")
+    );
+}
+
+#[test]
+fn refund_tickets_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let event_reschedule = EventReschedule::create(
+        event.id,
+        dates::now().add_days(30).finish(),
+        dates::now().add_days(31).finish(),
+        None,
+        dates::now().add_days(7).finish(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        event_reschedule.refund_tickets_for_user(user2.id, connection),
+        DatabaseError::business_process_error("No tickets are eligible for a refund")
+    );
+
+    let refunds = event_reschedule.refund_tickets_for_user(user.id, connection).unwrap();
+    assert_eq!(refunds.len(), 1);
+    let (refunded_order, refund, amount, tickets) = &refunds[0];
+    assert_eq!(refunded_order.id, order.id);
+    assert_eq!(refund.reason, Some("Event rescheduled".to_string()));
+    assert_eq!(tickets.len(), 2);
+    assert_eq!(
+        *amount,
+        refund.items(connection).unwrap().iter().map(|i| i.amount).sum::<i64>()
+    );
+    for ticket in tickets {
+        let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+        assert!(ticket.order_item_id.is_none());
+    }
+
+    // Window closed so no more refunds
+    let event_reschedule = event_reschedule.close_refund_window(connection).unwrap();
+    assert_eq!(
+        event_reschedule.refund_tickets_for_user(user.id, connection),
+        DatabaseError::business_process_error("The refund window for this event has closed")
+    );
+}
+
+#[test]
+fn close_refund_window() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let event_reschedule = EventReschedule::create(
+        event.id,
+        dates::now().add_days(30).finish(),
+        dates::now().add_days(31).finish(),
+        None,
+        dates::now().add_days(7).finish(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let closed = event_reschedule.close_refund_window(connection).unwrap();
+    assert!(closed.refund_window_closed_at.is_some());
+    assert!(!closed.is_refund_window_open());
+    assert!(EventReschedule::find_open_for_event(event.id, connection)
+        .unwrap()
+        .is_none());
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.override_status, None);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Events,
+            Some(event.id),
+            Some(DomainEventTypes::EventRescheduleRefundWindowClosed),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+
+    // Closing again has no effect
+    assert_eq!(closed.close_refund_window(connection).unwrap(), closed);
+}
@@ -0,0 +1,250 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(7).finish())
+        .with_event_end(dates::now().add_days(7).add_hours(3).finish())
+        .finish();
+
+    // Invalid recurrence rule
+    assert!(EventSeries::create(
+        organization.id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=HOURLY".to_string()
+    )
+    .commit(None, connection)
+    .is_err());
+
+    // Template belongs to another organization
+    assert!(EventSeries::create(
+        other_organization.id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=WEEKLY".to_string()
+    )
+    .commit(None, connection)
+    .is_err());
+
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=WEEKLY".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(event_series.template_event_id, event.id);
+    assert_eq!(event_series.organization_id, organization.id);
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.event_series_id, Some(event_series.id));
+    assert_eq!(event_series.occurrences(connection).unwrap(), vec![event.clone()]);
+
+    // Template already belongs to a series
+    assert!(EventSeries::create(
+        organization.id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=DAILY".to_string()
+    )
+    .commit(None, connection)
+    .is_err());
+}
+
+#[test]
+fn generate_occurrences() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let artist = project.create_artist().finish();
+    let event_start = dates::now().add_days(7).finish();
+    let event = project
+        .create_event()
+        .with_event_start(event_start)
+        .with_event_end(event_start + Duration::hours(3))
+        .with_door_time(event_start - Duration::hours(1))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_event_artist()
+        .with_event(&event)
+        .with_artist(&artist)
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let occurrences = event_series
+        .generate_occurrences(dates::now().add_days(60).finish(), None, connection)
+        .unwrap();
+    assert_eq!(occurrences.len(), 2);
+    for (i, occurrence) in occurrences.iter().enumerate() {
+        let expected_start = event_start + Duration::weeks(i as i64 + 1);
+        assert_eq!(occurrence.event_series_id, Some(event_series.id));
+        assert_eq!(occurrence.name, event.name);
+        assert_eq!(occurrence.venue_id, event.venue_id);
+        assert_eq!(occurrence.event_start, Some(expected_start));
+        assert_eq!(occurrence.event_end, Some(expected_start + Duration::hours(3)));
+        assert_eq!(occurrence.door_time, Some(expected_start - Duration::hours(1)));
+
+        // Each occurrence has its own inventory
+        let ticket_types = occurrence.ticket_types(true, None, connection).unwrap();
+        assert_eq!(ticket_types.len(), 1);
+        assert_eq!(
+            ticket_types[0].valid_ticket_count(connection).unwrap(),
+            event.ticket_types(true, None, connection).unwrap()[0]
+                .valid_ticket_count(connection)
+                .unwrap()
+        );
+        let artists = occurrence.artists(connection).unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].artist.id, artist.id);
+    }
+
+    // Existing occurrences are not generated twice
+    assert!(event_series
+        .generate_occurrences(dates::now().add_days(60).finish(), None, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(event_series.occurrences(connection).unwrap().len(), 3);
+}
+
+#[test]
+fn sync_occurrences() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let event_start = dates::now().add_days(7).finish();
+    let event = project
+        .create_event()
+        .with_event_start(event_start)
+        .with_event_end(event_start + Duration::hours(3))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series
+        .generate_occurrences(dates::now().add_days(60).finish(), None, connection)
+        .unwrap();
+    let sold_occurrence = &occurrences[0];
+    let unsold_occurrence = &occurrences[1];
+    project
+        .create_order()
+        .for_event(sold_occurrence)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    // Updating the template propagates to the occurrences
+    let template_event = Event::find(event.id, connection).unwrap();
+    template_event
+        .update(
+            None,
+            EventEditableAttributes {
+                name: Some("New name".to_string()),
+                venue_id: Some(venue.id),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    let sold_occurrence = Event::find(sold_occurrence.id, connection).unwrap();
+    assert_eq!(sold_occurrence.name, "New name".to_string());
+    assert_eq!(sold_occurrence.venue_id, event.venue_id);
+    let unsold_occurrence = Event::find(unsold_occurrence.id, connection).unwrap();
+    assert_eq!(unsold_occurrence.name, "New name".to_string());
+    assert_eq!(unsold_occurrence.venue_id, Some(venue.id));
+}
+
+#[test]
+fn sync_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = dates::now().add_days(7).finish();
+    let event = project
+        .create_event()
+        .with_event_start(event_start)
+        .with_event_end(event_start + Duration::hours(3))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series
+        .generate_occurrences(dates::now().add_days(60).finish(), None, connection)
+        .unwrap();
+    let sold_occurrence = &occurrences[0];
+    let unsold_occurrence = &occurrences[1];
+    project
+        .create_order()
+        .for_event(sold_occurrence)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    assert!(event_series.sync_ticket_types(None, connection).unwrap().is_empty());
+
+    let wallet_id = event.issuer_wallet(connection).unwrap().id;
+    event
+        .add_ticket_type(
+            "VIP".to_string(),
+            None,
+            10,
+            Some(dates::now().add_hours(-1).finish()),
+            None,
+            TicketTypeEndDateType::EventEnd,
+            Some(wallet_id),
+            None,
+            0,
+            100,
+            TicketTypeVisibility::Always,
+            None,
+            0,
+            true,
+            true,
+            true,
+            None,
+            connection,
+        )
+        .unwrap();
+
+    // Only the occurrence without sales receives the new ticket type
+    let added = event_series.sync_ticket_types(None, connection).unwrap();
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].0.id, unsold_occurrence.id);
+    assert_eq!(added[0].1.len(), 1);
+    assert_eq!(added[0].1[0].name, "VIP".to_string());
+    assert_eq!(unsold_occurrence.ticket_types(true, None, connection).unwrap().len(), 2);
+    assert_eq!(sold_occurrence.ticket_types(true, None, connection).unwrap().len(), 1);
+
+    // Ticket types are matched by name so syncing again adds nothing
+    assert!(event_series.sync_ticket_types(None, connection).unwrap().is_empty());
+}
@@ -11,12 +11,19 @@ fn schedule_domain_actions() {
     let domain_actions = domain_actions_pending(DomainActionTypes::RetargetAbandonedOrders, connection);
     assert_eq!(0, domain_actions.len());
 
+    let domain_actions = domain_actions_pending(DomainActionTypes::EvaluatePricingRules, connection);
+    assert_eq!(0, domain_actions.len());
+
     // Schedule domain action
     global::schedule_domain_actions(connection).unwrap();
     let domain_actions = domain_actions_pending(DomainActionTypes::SendAutomaticReportEmails, connection);
     assert_eq!(1, domain_actions.len());
     let domain_actions = domain_actions_pending(DomainActionTypes::RetargetAbandonedOrders, connection);
     assert_eq!(1, domain_actions.len());
+    let domain_actions = domain_actions_pending(DomainActionTypes::EvaluatePricingRules, connection);
+    assert_eq!(1, domain_actions.len());
+    let domain_actions = domain_actions_pending(DomainActionTypes::ReleaseExpiredHolds, connection);
+    assert_eq!(1, domain_actions.len());
 
     // No change since action exists
     global::schedule_domain_actions(connection).unwrap();
@@ -447,6 +447,161 @@ fn remove_available_quantity() {
     assert_eq!(1, child_hold.quantity(connection).unwrap().0);
 }
 
+#[test]
+fn release_expired_holds() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(50)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let creator = project.create_user().finish();
+    let one_minute_ago = Utc::now().naive_utc() - Duration::minutes(1);
+
+    let mut new_hold = Hold::create_hold(
+        "Promoters".to_string(),
+        event.id,
+        Some("RELEASEME".to_string()),
+        Some(10),
+        None,
+        None,
+        HoldTypes::Discount,
+        ticket_type.id,
+    );
+    new_hold.expiry_behavior = HoldExpiryBehaviors::Release;
+    let hold = new_hold.commit(Some(creator.id), connection).unwrap();
+    hold.set_quantity(Some(creator.id), 10, connection).unwrap();
+    let comp = Hold::create_comp_for_person(
+        "Guest".into(),
+        Some(creator.id),
+        hold.id,
+        None,
+        None,
+        "GUESTCODE".into(),
+        None,
+        None,
+        2,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(comp.expiry_behavior, HoldExpiryBehaviors::Release);
+    let kept_hold = project
+        .create_hold()
+        .with_event(&event)
+        .with_ticket_type_id(ticket_type.id)
+        .with_end_at(one_minute_ago)
+        .with_quantity(5)
+        .finish();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: hold.redemption_code.clone(),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let hold = hold
+        .update(
+            UpdateHoldAttributes {
+                end_at: Some(Some(one_minute_ago)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    comp.update(
+        UpdateHoldAttributes {
+            end_at: Some(Some(one_minute_ago)),
+            ..Default::default()
+        },
+        connection,
+    )
+    .unwrap();
+    let available_ticket_count = ticket_type.valid_available_ticket_count(connection).unwrap();
+
+    // Comps return their tickets to the parent hold first, which then releases everything unclaimed
+    let released_holds = Hold::release_expired_holds(connection).unwrap();
+    assert_eq!(
+        released_holds
+            .iter()
+            .map(|(hold, quantity)| (hold.id, *quantity))
+            .collect::<Vec<(Uuid, u32)>>(),
+        vec![(comp.id, 2), (hold.id, 7)]
+    );
+    assert_eq!((3, 0), hold.quantity(connection).unwrap());
+    assert_eq!((0, 0), comp.quantity(connection).unwrap());
+    assert_eq!((5, 5), kept_hold.quantity(connection).unwrap());
+    assert_eq!(
+        ticket_type.valid_available_ticket_count(connection).unwrap(),
+        available_ticket_count + 7
+    );
+    assert!(!DomainEvent::find(
+        Tables::Holds,
+        Some(hold.id),
+        Some(DomainEventTypes::HoldQuantityChanged),
+        connection,
+    )
+    .unwrap()
+    .is_empty());
+    assert_eq!(hold.creator(connection).unwrap(), Some(creator));
+
+    // Already released holds are not processed again
+    assert!(Hold::release_expired_holds(connection).unwrap().is_empty());
+    let hold = Hold::find(hold.id, connection).unwrap();
+    assert!(hold.released_at.is_some());
+
+    // Moving the end date makes the hold eligible for release again
+    let hold = hold
+        .update(
+            UpdateHoldAttributes {
+                end_at: Some(Some(Utc::now().naive_utc() + Duration::days(1))),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(hold.released_at, None);
+}
+
+#[test]
+fn create_next_release_expired_holds_domain_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+
+    Hold::create_next_release_expired_holds_domain_action(connection).unwrap();
+    let domain_action =
+        DomainAction::upcoming_domain_action(None, None, DomainActionTypes::ReleaseExpiredHolds, connection)
+            .unwrap()
+            .unwrap();
+    let beginning_of_current_quarter_hour =
+        NaiveDate::from_ymd(now.year(), now.month(), now.day()).and_hms(now.hour(), now.minute() / 15 * 15, 0);
+    assert_eq!(
+        domain_action.scheduled_at,
+        beginning_of_current_quarter_hour + Duration::minutes(15)
+    );
+    assert!(Hold::create_next_release_expired_holds_domain_action(connection).is_err());
+}
+
 #[test]
 fn destroy() {
     let project = TestProject::new();
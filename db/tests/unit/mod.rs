@@ -2,16 +2,24 @@ pub mod activities;
 pub mod artists;
 pub mod assets;
 pub mod broadcasts;
+pub mod capacity_pools;
+pub mod code_batches;
 pub mod codes;
 pub mod communication;
+pub mod comp_imports;
 pub mod comps;
 pub mod concerns;
 pub mod domain_actions;
 pub mod domain_event_publishers;
 pub mod domain_events;
+pub mod entry_slots;
+pub mod event_answers;
 pub mod event_artists;
 pub mod event_interest;
+pub mod event_questions;
 pub mod event_report_subscribers;
+pub mod event_reschedules;
+pub mod event_series;
 pub mod event_users;
 pub mod events;
 pub mod external_logins;
@@ -27,22 +35,34 @@ pub mod organization_interactions;
 pub mod organization_invites;
 pub mod organization_users;
 pub mod organizations;
+pub mod packages;
 pub mod paging;
 pub mod payment_methods;
+pub mod payment_plans;
+pub mod payment_provider_events;
 pub mod payments;
+pub mod presale_lotteries;
+pub mod pricing_rules;
+pub mod products;
 pub mod push_notification_tokens;
 pub mod refund_items;
 pub mod refunded_tickets;
 pub mod refunds;
 pub mod regions;
 pub mod reports;
+pub mod resale_listings;
+pub mod seating_sections;
+pub mod seats;
 pub mod services;
 pub mod settlement_adjustments;
 pub mod settlement_entries;
 pub mod settlements;
 pub mod slugs;
 pub mod stages;
+pub mod store_credit_transactions;
+pub mod tax_rules;
 pub mod temporary_users;
+pub mod ticket_exchanges;
 pub mod ticket_instances;
 pub mod ticket_pricing;
 pub mod ticket_type_codes;
@@ -51,3 +71,4 @@ pub mod transfer_tickets;
 pub mod transfers;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
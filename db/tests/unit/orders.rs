@@ -1,7 +1,7 @@
 use bigneon_db::dev::times;
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
-use bigneon_db::schema::{fee_schedule_ranges, order_items, orders, ticket_instances};
+use bigneon_db::schema::{fee_schedule_ranges, order_items, orders, payments, ticket_instances};
 use bigneon_db::utils::dates;
 use bigneon_db::utils::errors::DatabaseError;
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
@@ -1984,7 +1984,7 @@ fn add_provider_payment() {
         connection,
     )
     .unwrap();
-    assert_eq!(OrderStatus::Draft, cart.status);
+    assert_eq!(OrderStatus::PendingPayment, cart.status);
     let payment = &cart.payments(connection).unwrap()[0];
     assert_eq!(Some(external_reference.clone()), payment.external_reference);
     assert_eq!(PaymentProviders::Stripe, payment.provider);
@@ -2057,7 +2057,7 @@ fn add_credit_card_payment() {
         connection,
     )
     .unwrap();
-    assert_eq!(OrderStatus::Draft, cart.status);
+    assert_eq!(OrderStatus::PendingPayment, cart.status);
     let payment = &cart.payments(connection).unwrap()[0];
     assert_eq!(Some(external_reference.clone()), payment.external_reference);
     assert_eq!(PaymentProviders::Stripe, payment.provider);
@@ -2395,7 +2395,7 @@ fn total_paid() {
         connection,
     )
     .unwrap();
-    assert_eq!(OrderStatus::Draft, cart.status);
+    assert_eq!(OrderStatus::PendingPayment, cart.status);
     assert_eq!(cart.total_paid(connection).unwrap(), 500);
 
     let remaining = cart.calculate_total(connection).unwrap() - 500;
@@ -2417,6 +2417,188 @@ fn total_paid() {
     );
 }
 
+#[test]
+fn split_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = cart.calculate_total(connection).unwrap();
+
+    let cash = cart
+        .add_external_payment(None, ExternalPaymentType::Cash, user.id, 500, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::PendingPayment);
+    assert!(cart.is_partially_paid(connection).unwrap());
+    // The partially paid order is still the user's cart
+    assert_eq!(
+        Order::find_cart_for_user(user.id, connection).unwrap().map(|o| o.id),
+        Some(cart.id)
+    );
+
+    let card = cart
+        .add_credit_card_payment(
+            user.id,
+            total - 500,
+            PaymentProviders::Stripe,
+            "ch_split".to_string(),
+            PaymentStatus::Completed,
+            serde_json::Value::Null,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert!(!cart.is_partially_paid(connection).unwrap());
+    assert!(Order::find_cart_for_user(user.id, connection).unwrap().is_none());
+
+    // Payments made in the same transaction share a timestamp
+    diesel::update(payments::table.filter(payments::id.eq(cash.id)))
+        .set(payments::created_at.eq(dates::now().add_minutes(-10).finish()))
+        .execute(connection)
+        .unwrap();
+
+    // Most recent payment is refunded first
+    let refundable_payments = cart.refundable_payments(connection).unwrap();
+    assert_eq!(
+        refundable_payments
+            .iter()
+            .map(|(p, balance)| (p.id, *balance))
+            .collect::<Vec<(Uuid, i64)>>(),
+        vec![(card.id, total - 500), (cash.id, 500)]
+    );
+
+    let refund = Refund::create(cart.id, user.id, None, false)
+        .commit(connection)
+        .unwrap();
+    let refund_payment = card
+        .log_refund(user.id, &refund, total - 600, None, connection)
+        .unwrap();
+    assert_eq!(refund_payment.refunded_payment_id, Some(card.id));
+    assert_eq!(refund_payment.amount, -(total - 600));
+    let refundable_payments = cart.refundable_payments(connection).unwrap();
+    assert_eq!(
+        refundable_payments
+            .iter()
+            .map(|(p, balance)| (p.id, *balance))
+            .collect::<Vec<(Uuid, i64)>>(),
+        vec![(card.id, 100), (cash.id, 500)]
+    );
+
+    card.log_refund(user.id, &refund, 100, None, connection).unwrap();
+    let refundable_payments = cart.refundable_payments(connection).unwrap();
+    assert_eq!(refundable_payments.len(), 1);
+    assert_eq!(refundable_payments[0].0.id, cash.id);
+}
+
+#[test]
+fn split_payment_on_expired_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = cart.calculate_total(connection).unwrap();
+
+    // Paying part of the order holds the tickets for the partially paid window
+    cart.add_external_payment(None, ExternalPaymentType::Cash, user.id, 500, connection)
+        .unwrap();
+    let partially_paid_expiry =
+        NaiveDateTime::from(Utc::now().naive_utc() + Duration::minutes(PARTIALLY_PAID_CART_EXPIRY_TIME_MINUTES));
+    let cart = Order::find(cart.id, connection).unwrap();
+    assert!((partially_paid_expiry.timestamp() - cart.expires_at.unwrap().timestamp()).abs() < 2);
+    for ticket in cart.tickets(None, connection).unwrap() {
+        assert!((partially_paid_expiry.timestamp() - ticket.reserved_until.unwrap().timestamp()).abs() < 2);
+    }
+    // The partial payment is refunded if the rest isn't paid before the order expires
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::Orders),
+        Some(cart.id),
+        DomainActionTypes::RefundExpiredOrderPayments,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(domain_action.scheduled_at, cart.expires_at.unwrap());
+
+    // Once expired the partially paid cart is still reachable and can be refreshed to pay the rest
+    let past_expiry = NaiveDateTime::from(Utc::now().naive_utc() - Duration::minutes(5));
+    diesel::update(orders::table.filter(orders::id.eq(cart.id)))
+        .set(orders::expires_at.eq(past_expiry))
+        .execute(connection)
+        .unwrap();
+    diesel::update(
+        ticket_instances::table.filter(
+            ticket_instances::order_item_id.eq_any(
+                cart.items(connection)
+                    .unwrap()
+                    .iter()
+                    .map(|i| i.id)
+                    .collect::<Vec<Uuid>>(),
+            ),
+        ),
+    )
+    .set(ticket_instances::reserved_until.eq(past_expiry))
+    .execute(connection)
+    .unwrap();
+    let mut cart = Order::find_cart_for_user(user.id, connection).unwrap().unwrap();
+    assert!(cart.is_expired());
+    assert_eq!(cart.status, OrderStatus::PendingPayment);
+    cart.try_refresh_expired_cart(Some(user.id), connection).unwrap();
+    assert!(!cart.is_expired());
+    assert_eq!(cart.tickets(None, connection).unwrap().len(), 2);
+
+    cart.add_external_payment(None, ExternalPaymentType::Cash, user.id, total - 500, connection)
+        .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert!(Order::find_cart_for_user(user.id, connection).unwrap().is_none());
+}
+
+#[test]
+fn cancel_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    cart.add_external_payment(None, ExternalPaymentType::Cash, user.id, 500, connection)
+        .unwrap();
+    let mut cart = Order::find(cart.id, connection).unwrap();
+    assert_eq!(cart.status, OrderStatus::PendingPayment);
+
+    // Orders are only cancelled once they have expired
+    let result = cart.cancel_expired(connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Only expired pending payment orders can be cancelled")
+    );
+
+    diesel::update(orders::table.filter(orders::id.eq(cart.id)))
+        .set(orders::expires_at.eq(NaiveDateTime::from(Utc::now().naive_utc() - Duration::minutes(5))))
+        .execute(connection)
+        .unwrap();
+    let mut cart = Order::find(cart.id, connection).unwrap();
+    assert!(cart.cancel_expired(connection).is_ok());
+    assert_eq!(cart.status, OrderStatus::Cancelled);
+    let cart = Order::find(cart.id, connection).unwrap();
+    assert_eq!(cart.status, OrderStatus::Cancelled);
+}
+
 #[test]
 fn clear_invalid_items() {
     let project = TestProject::new();
@@ -3048,6 +3230,91 @@ fn update_quantities_check_limits() {
     }
 }
 
+#[test]
+fn update_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = project
+        .create_seating_section()
+        .with_stage_id(stage.id)
+        .with_seats(2, 5)
+        .finish();
+    let seats = seating_section.seats(connection).unwrap();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Best available seats are assigned when adding tickets without picking seats
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    let mut seat_ids: Vec<Option<Uuid>> = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .iter()
+        .map(|t| t.seat_id)
+        .collect();
+    seat_ids.sort();
+    let mut expected: Vec<Option<Uuid>> = vec![Some(seats[0].id), Some(seats[1].id)];
+    expected.sort();
+    assert_eq!(seat_ids, expected);
+
+    // Picking seats replaces the assignment
+    let picked = vec![seats[1].id, seats[6].id, seats[7].id];
+    cart.update_seats(user.id, ticket_type.id, &picked, None, false, connection)
+        .unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    assert_eq!(order_item.quantity, 3);
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    assert_eq!(tickets.len(), 3);
+    for ticket in &tickets {
+        assert!(picked.contains(&ticket.seat_id.unwrap()));
+    }
+
+    // Seats held by another cart cannot be picked
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    let result = cart2.update_seats(user2.id, ticket_type.id, &[seats[6].id], None, false, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Ticket types without a seating section do not accept seats
+    let event2 = project.create_event().with_ticket_pricing().finish();
+    let ticket_type2 = event2.ticket_types(true, None, connection).unwrap().remove(0);
+    assert!(cart
+        .update_seats(user.id, ticket_type2.id, &[seats[2].id], None, false, connection)
+        .is_err());
+}
+
 #[test]
 fn add_tickets() {
     let project = TestProject::new();
@@ -5076,7 +5343,7 @@ fn add_external_payment() {
         conn,
     )
     .unwrap();
-    assert_eq!(cart.status, OrderStatus::Draft);
+    assert_eq!(cart.status, OrderStatus::PendingPayment);
     assert!(cart.paid_at.is_none());
     assert!(user.genres(conn).unwrap().is_empty());
 
@@ -5754,6 +6021,74 @@ fn validate_record() {
     }
 }
 
+#[test]
+fn validate_record_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_currency("CAD").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert_eq!(cart.currency, "USD");
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    // The cart takes on the currency of the organization selling the tickets
+    assert_eq!(cart.currency, "CAD");
+    assert_eq!(Order::find(cart.id, connection).unwrap().currency, "CAD");
+
+    let gbp_organization = project.create_organization().with_currency("GBP").finish();
+    let gbp_event = project
+        .create_event()
+        .with_organization(&gbp_organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let gbp_ticket_type = &gbp_event.ticket_types(true, None, connection).unwrap()[0];
+    let package = Package::create(gbp_organization.id, "Season pass".to_string(), None)
+        .commit(None, connection)
+        .unwrap();
+    package
+        .add_ticket_type(gbp_ticket_type.id, 3000, None, connection)
+        .unwrap();
+    let result = cart.update_package_quantities(
+        user.id,
+        &[UpdatePackageOrderItem {
+            package_id: package.id,
+            quantity: 1,
+        }],
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"].len(), 1);
+                assert_eq!(errors["currency"][0].code, "cart_currency_mismatch");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn for_display_with_organization_id_and_event_id_filters() {
     let project = TestProject::new();
@@ -621,6 +621,44 @@ fn update() {
     assert_eq!(settlement_report_job.status, DomainActionStatus::Cancelled);
 }
 
+#[test]
+fn update_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("cad").finish();
+    assert_eq!(organization.currency, "CAD");
+
+    let mut changed_attrs: OrganizationEditableAttributes = Default::default();
+    changed_attrs.currency = Some("CA".to_string());
+    assert!(organization
+        .update(changed_attrs, None, &"encryption_key".to_string(), connection)
+        .is_err());
+
+    let mut changed_attrs: OrganizationEditableAttributes = Default::default();
+    changed_attrs.currency = Some(" gbp ".to_string());
+    let organization = organization
+        .update(changed_attrs, None, &"encryption_key".to_string(), connection)
+        .unwrap();
+    assert_eq!(organization.currency, "GBP");
+
+    // Once tickets have been sold the currency is fixed
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    assert_eq!(order.currency, "GBP");
+    assert!(organization.has_sales(connection).unwrap());
+    let mut changed_attrs: OrganizationEditableAttributes = Default::default();
+    changed_attrs.currency = Some("USD".to_string());
+    assert_eq!(
+        organization.update(changed_attrs, None, &"encryption_key".to_string(), connection),
+        DatabaseError::business_process_error("Currency cannot be changed once the organization has sales")
+    );
+}
+
 #[test]
 fn find() {
     let project = TestProject::new();
@@ -2327,3 +2365,69 @@ fn credit_card_fees() {
         pre_cc_fee_total + (pre_cc_fee_total as f32 * (5f32 / 100f32)).round() as i64
     );
 }
+
+#[test]
+fn update_payment_plan_settings() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    assert_eq!(organization.payment_plan_min_total_in_cents, None);
+    assert_eq!(organization.payment_plan_max_installments, 3);
+    assert_eq!(
+        organization.payment_plan_refund_policy,
+        PaymentPlanRefundPolicies::RetainDeposit
+    );
+
+    let result = organization.update(
+        OrganizationEditableAttributes {
+            payment_plan_max_installments: Some(13),
+            ..Default::default()
+        },
+        None,
+        &"encryption_key".to_string(),
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::validation_error(
+            "payment_plan_max_installments",
+            "Payment plans must have between 1 and 12 installments"
+        )
+    );
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                payment_plan_min_total_in_cents: Some(Some(10000)),
+                payment_plan_max_installments: Some(6),
+                payment_plan_grace_period_days: Some(14),
+                payment_plan_refund_policy: Some(PaymentPlanRefundPolicies::FullRefund),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.payment_plan_min_total_in_cents, Some(10000));
+    assert_eq!(organization.payment_plan_max_installments, 6);
+    assert_eq!(organization.payment_plan_grace_period_days, 14);
+    assert_eq!(
+        organization.payment_plan_refund_policy,
+        PaymentPlanRefundPolicies::FullRefund
+    );
+
+    // Clearing the minimum total disables payment plans
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                payment_plan_min_total_in_cents: Some(None),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.payment_plan_min_total_in_cents, None);
+}
@@ -0,0 +1,334 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn add_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_type_count(2)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let other_event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    assert!(Package::create(organization.id, "".to_string(), None)
+        .commit(Some(user.id), connection)
+        .is_err());
+    let package = Package::create(organization.id, "Season pass".to_string(), None)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert!(package.active);
+
+    package
+        .add_ticket_type(ticket_types[0].id, 3000, Some(user.id), connection)
+        .unwrap();
+    // Only one ticket per event
+    assert!(package
+        .add_ticket_type(ticket_types[1].id, 3000, Some(user.id), connection)
+        .is_err());
+    // Events must belong to the package's organization
+    assert!(package
+        .add_ticket_type(other_ticket_type.id, 3000, Some(user.id), connection)
+        .is_err());
+    // Price allocations cannot be negative
+    assert!(package
+        .add_ticket_type(ticket_type2.id, -1, Some(user.id), connection)
+        .is_err());
+    package
+        .add_ticket_type(ticket_type2.id, 2000, Some(user.id), connection)
+        .unwrap();
+
+    assert_eq!(package.price_in_cents(connection).unwrap(), 5000);
+    let display_package = package.for_display(connection).unwrap();
+    assert_eq!(display_package.price_in_cents, 5000);
+    assert_eq!(display_package.ticket_types.len(), 2);
+    let event_ids: Vec<_> = display_package.ticket_types.iter().map(|t| t.event_id).collect();
+    assert!(event_ids.contains(&event.id));
+    assert!(event_ids.contains(&event2.id));
+}
+
+#[test]
+fn update_package_quantities() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let package = Package::create(organization.id, "Season pass".to_string(), None)
+        .commit(None, connection)
+        .unwrap();
+    package.add_ticket_type(ticket_type.id, 3000, None, connection).unwrap();
+    package
+        .add_ticket_type(ticket_type2.id, 2000, None, connection)
+        .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_package_quantities(
+        user.id,
+        &[UpdatePackageOrderItem {
+            package_id: package.id,
+            quantity: 2,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert!(cart.expires_at.is_some());
+
+    let items = cart.items(connection).unwrap();
+    let package_items: Vec<&OrderItem> = items.iter().filter(|i| i.package_id == Some(package.id)).collect();
+    assert_eq!(package_items.len(), 2);
+    let item = package_items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    assert_eq!(item.item_type, OrderItemTypes::Tickets);
+    assert_eq!(item.event_id, Some(event.id));
+    assert_eq!(item.quantity, 2);
+    assert_eq!(item.unit_price_in_cents, 3000);
+    assert_eq!(
+        TicketInstance::find_for_order_item(item.id, connection).unwrap().len(),
+        2
+    );
+    let item2 = package_items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id))
+        .unwrap();
+    assert_eq!(item2.event_id, Some(event2.id));
+    assert_eq!(item2.unit_price_in_cents, 2000);
+    assert_eq!(
+        TicketInstance::find_for_order_item(item2.id, connection).unwrap().len(),
+        2
+    );
+    assert!(item.find_fee_item(connection).unwrap().is_some());
+    assert!(cart.items_valid_for_purchase(connection).unwrap());
+
+    // Regular tickets can be bought alongside the package
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    assert_eq!(OrderItem::find(item.id, connection).unwrap().quantity, 2);
+    assert!(items
+        .iter()
+        .any(|i| i.ticket_type_id == Some(ticket_type.id) && i.package_id.is_none() && i.quantity == 1));
+
+    cart.update_package_quantities(
+        user.id,
+        &[UpdatePackageOrderItem {
+            package_id: package.id,
+            quantity: 1,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert_eq!(OrderItem::find(item.id, connection).unwrap().quantity, 1);
+    assert_eq!(
+        TicketInstance::find_for_order_item(item2.id, connection).unwrap().len(),
+        1
+    );
+
+    cart.update_package_quantities(
+        user.id,
+        &[UpdatePackageOrderItem {
+            package_id: package.id,
+            quantity: 0,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert!(cart.items(connection).unwrap().iter().all(|i| i.package_id.is_none()));
+
+    // Inactive packages can no longer be added
+    package
+        .update(
+            PackageEditableAttributes {
+                active: Some(false),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(cart
+        .update_package_quantities(
+            user.id,
+            &[UpdatePackageOrderItem {
+                package_id: package.id,
+                quantity: 1,
+            }],
+            connection,
+        )
+        .is_err());
+}
+
+#[test]
+fn refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let package = Package::create(organization.id, "Season pass".to_string(), None)
+        .commit(None, connection)
+        .unwrap();
+    package.add_ticket_type(ticket_type.id, 3000, None, connection).unwrap();
+    package
+        .add_ticket_type(ticket_type2.id, 2000, None, connection)
+        .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_package_quantities(
+        user.id,
+        &[UpdatePackageOrderItem {
+            package_id: package.id,
+            quantity: 2,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let items = cart.items(connection).unwrap();
+    let item = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id) && i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let item2 = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id) && i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = item.find_fee_item(connection).unwrap().unwrap();
+    let fee_item2 = item2.find_fee_item(connection).unwrap().unwrap();
+    let ticket = &TicketInstance::find_for_order_item(item.id, connection).unwrap()[0];
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+
+    // Fees of package tickets are only refunded with the package
+    assert!(cart
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: fee_item.id,
+                ticket_instance_id: Some(ticket.id),
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .is_err());
+
+    // Refunding one ticket of the package refunds the ticket for the other event as well
+    let (_refund, amount) = cart
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: item.id,
+                ticket_instance_id: Some(ticket.id),
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        amount,
+        3000 + 2000 + fee_item.unit_price_in_cents + fee_item2.unit_price_in_cents
+    );
+    assert_eq!(OrderItem::find(item.id, connection).unwrap().refunded_quantity, 1);
+    assert_eq!(OrderItem::find(item2.id, connection).unwrap().refunded_quantity, 1);
+    assert_eq!(
+        TicketInstance::find_for_order_item(item.id, connection).unwrap().len(),
+        1
+    );
+    assert_eq!(
+        TicketInstance::find_for_order_item(item2.id, connection).unwrap().len(),
+        1
+    );
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert!(ticket.order_item_id.is_none());
+
+    // Listing every ticket of the remaining package does not refund more than one package
+    let remaining_ticket = &TicketInstance::find_for_order_item(item.id, connection).unwrap()[0];
+    let remaining_ticket2 = &TicketInstance::find_for_order_item(item2.id, connection).unwrap()[0];
+    let (_refund, amount) = cart
+        .refund(
+            &[
+                RefundItemRequest {
+                    order_item_id: item.id,
+                    ticket_instance_id: Some(remaining_ticket.id),
+                },
+                RefundItemRequest {
+                    order_item_id: item2.id,
+                    ticket_instance_id: Some(remaining_ticket2.id),
+                },
+            ],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        amount,
+        3000 + 2000 + fee_item.unit_price_in_cents + fee_item2.unit_price_in_cents
+    );
+    assert_eq!(OrderItem::find(item.id, connection).unwrap().refunded_quantity, 2);
+    assert_eq!(OrderItem::find(item2.id, connection).unwrap().refunded_quantity, 2);
+}
@@ -0,0 +1,412 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use diesel::PgConnection;
+
+fn enable_payment_plans(organization: &Organization, connection: &PgConnection) -> Organization {
+    organization
+        .update(
+            OrganizationEditableAttributes {
+                payment_plan_min_total_in_cents: Some(Some(100)),
+                payment_plan_max_installments: Some(3),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap()
+}
+
+fn create_payment_plan(
+    project: &TestProject,
+    installment_count: i32,
+) -> (User, Order, PaymentPlan, Vec<PaymentPlanInstallment>) {
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    enable_payment_plans(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(180).finish())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+    let payment_plan = PaymentPlan::create_for_order(&order, payment_method.id, installment_count, connection).unwrap();
+    let installments = payment_plan.installments(connection).unwrap();
+    (user, order, payment_plan, installments)
+}
+
+fn pay_deposit(order: &mut Order, payment_plan: &PaymentPlan, user: &User, connection: &PgConnection) {
+    order
+        .add_credit_card_payment(
+            user.id,
+            payment_plan.deposit_in_cents,
+            PaymentProviders::Stripe,
+            "deposit".to_string(),
+            PaymentStatus::Completed,
+            json!({}),
+            connection,
+        )
+        .unwrap();
+}
+
+#[test]
+fn create_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(180).finish())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    let other_payment_method = project.create_payment_method().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+
+    // Payment plans are disabled until the organization sets a minimum total
+    assert_eq!(
+        PaymentPlan::create_for_order(&order, payment_method.id, 3, connection),
+        DatabaseError::business_process_error("Payment plans are not available for this order")
+    );
+    enable_payment_plans(&organization, connection);
+
+    assert_eq!(
+        PaymentPlan::create_for_order(&order, other_payment_method.id, 3, connection),
+        DatabaseError::business_process_error("Payment method does not belong to the purchaser")
+    );
+    assert_eq!(
+        PaymentPlan::create_for_order(&order, payment_method.id, 4, connection),
+        DatabaseError::validation_error(
+            "installment_count",
+            "Number of installments is not available for this order"
+        )
+    );
+
+    let payment_plan = PaymentPlan::create_for_order(&order, payment_method.id, 3, connection).unwrap();
+    let total = order.calculate_total(connection).unwrap();
+    assert_eq!(payment_plan.order_id, order.id);
+    assert_eq!(payment_plan.user_id, user.id);
+    assert_eq!(payment_plan.total_in_cents, total);
+    assert_eq!(payment_plan.status, PaymentPlanStatus::Active);
+    assert_eq!(payment_plan.refund_policy, PaymentPlanRefundPolicies::RetainDeposit);
+    assert_eq!(order.amount_due_now(connection).unwrap(), payment_plan.deposit_in_cents);
+
+    let installments = payment_plan.installments(connection).unwrap();
+    assert_eq!(installments.len(), 3);
+    assert_eq!(
+        installments.iter().map(|i| i.installment_number).collect::<Vec<i32>>(),
+        vec![1, 2, 3]
+    );
+    // Remainder of the split is added to the deposit
+    assert!(payment_plan.deposit_in_cents >= installments[0].amount_in_cents);
+    assert_eq!(
+        payment_plan.deposit_in_cents + installments.iter().map(|i| i.amount_in_cents).sum::<i64>(),
+        total
+    );
+    for installment in &installments {
+        assert_eq!(installment.status, PaymentPlanInstallmentStatus::Pending);
+        assert_eq!(
+            DomainAction::find_by_resource(
+                Some(Tables::PaymentPlanInstallments),
+                Some(installment.id),
+                DomainActionTypes::ChargePaymentPlanInstallment,
+                DomainActionStatus::Pending,
+                connection,
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+
+    assert_eq!(
+        PaymentPlan::create_for_order(&order, payment_method.id, 3, connection),
+        DatabaseError::business_process_error("Order already has a payment plan")
+    );
+}
+
+#[test]
+fn create_for_order_event_too_soon() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    enable_payment_plans(&organization, connection);
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(dates::now().add_days(40).finish())
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(10)
+        .finish();
+
+    assert_eq!(
+        PaymentPlan::create_for_order(&order, payment_method.id, 2, connection),
+        DatabaseError::validation_error(
+            "installment_count",
+            "Payment plan would not be paid in full before the event starts"
+        )
+    );
+    assert!(PaymentPlan::create_for_order(&order, payment_method.id, 1, connection).is_ok());
+}
+
+#[test]
+fn deposit_completes_order_and_locks_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_plan, installments) = create_payment_plan(&project, 2);
+
+    pay_deposit(&mut order, &payment_plan, &user, connection);
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.amount_due_now(connection).unwrap(), 0);
+
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    assert_eq!(
+        TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection),
+        DatabaseError::business_process_error("Tickets cannot be transferred until their payment plan is paid in full")
+    );
+
+    for installment in installments {
+        installment
+            .mark_paid(
+                PaymentProviders::Stripe,
+                format!("installment-{}", installment.installment_number),
+                json!({}),
+                connection,
+            )
+            .unwrap();
+    }
+    let payment_plan = PaymentPlan::find(payment_plan.id, connection).unwrap();
+    assert_eq!(payment_plan.status, PaymentPlanStatus::Completed);
+    assert!(payment_plan.completed_at.is_some());
+    assert_eq!(order.total_paid(connection).unwrap(), payment_plan.total_in_cents);
+    assert!(TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).is_ok());
+}
+
+#[test]
+fn mark_paid() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_plan, installments) = create_payment_plan(&project, 2);
+    pay_deposit(&mut order, &payment_plan, &user, connection);
+
+    let installment = &installments[0];
+    let payment = installment
+        .mark_paid(PaymentProviders::Stripe, "ch_1".to_string(), json!({}), connection)
+        .unwrap();
+    assert_eq!(payment.order_id, order.id);
+    assert_eq!(payment.amount, installment.amount_in_cents);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+
+    let installment = PaymentPlanInstallment::find(installment.id, connection).unwrap();
+    assert_eq!(installment.status, PaymentPlanInstallmentStatus::Paid);
+    assert_eq!(installment.payment_id, Some(payment.id));
+    assert!(installment.paid_at.is_some());
+    // One installment remains
+    assert_eq!(
+        PaymentPlan::find(payment_plan.id, connection).unwrap().status,
+        PaymentPlanStatus::Active
+    );
+
+    assert_eq!(
+        installment.mark_paid(PaymentProviders::Stripe, "ch_2".to_string(), json!({}), connection),
+        DatabaseError::business_process_error("Installment has already been paid")
+    );
+}
+
+#[test]
+fn mark_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_plan, installments) = create_payment_plan(&project, 2);
+    pay_deposit(&mut order, &payment_plan, &user, connection);
+
+    let installment = installments[0].mark_failed("Card declined", connection).unwrap();
+    assert_eq!(installment.status, PaymentPlanInstallmentStatus::Failed);
+    assert_eq!(installment.failed_attempts, 1);
+    assert_eq!(installment.last_failure_reason, Some("Card declined".to_string()));
+    let grace_period_ends_at = installment.grace_period_ends_at.unwrap();
+    assert!(grace_period_ends_at > dates::now().add_days(6).finish());
+
+    assert_eq!(
+        DomainAction::find_by_resource(
+            Some(Tables::PaymentPlanInstallments),
+            Some(installment.id),
+            DomainActionTypes::SendPaymentPlanDunningCommunication,
+            DomainActionStatus::Pending,
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+    assert_eq!(
+        DomainAction::find_by_resource(
+            Some(Tables::PaymentPlans),
+            Some(payment_plan.id),
+            DomainActionTypes::CancelPaymentPlan,
+            DomainActionStatus::Pending,
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+    // Not overdue until the grace period is over
+    assert!(!payment_plan.is_overdue(connection).unwrap());
+
+    // Later failures keep the original grace period and don't schedule another cancellation
+    let installment = installment.mark_failed("Card declined", connection).unwrap();
+    assert_eq!(installment.failed_attempts, 2);
+    assert_eq!(installment.grace_period_ends_at, Some(grace_period_ends_at));
+    assert_eq!(
+        DomainAction::find_by_resource(
+            Some(Tables::PaymentPlans),
+            Some(payment_plan.id),
+            DomainActionTypes::CancelPaymentPlan,
+            DomainActionStatus::Pending,
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn refund_due() {
+    let project = TestProject::new();
+    let (_, _, payment_plan, _) = create_payment_plan(&project, 2);
+    let deposit = payment_plan.deposit_in_cents;
+
+    assert_eq!(payment_plan.refund_due(deposit + 500), 500);
+    assert_eq!(payment_plan.refund_due(deposit), 0);
+
+    let payment_plan = PaymentPlan {
+        refund_policy: PaymentPlanRefundPolicies::FullRefund,
+        ..payment_plan
+    };
+    assert_eq!(payment_plan.refund_due(deposit + 500), deposit + 500);
+
+    let payment_plan = PaymentPlan {
+        refund_policy: PaymentPlanRefundPolicies::NoRefund,
+        ..payment_plan
+    };
+    assert_eq!(payment_plan.refund_due(deposit + 500), 0);
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_plan, installments) = create_payment_plan(&project, 2);
+    pay_deposit(&mut order, &payment_plan, &user, connection);
+    installments[0]
+        .mark_paid(PaymentProviders::Stripe, "ch_1".to_string(), json!({}), connection)
+        .unwrap();
+
+    let (order, refund, refund_due, tickets) = payment_plan.cancel(connection).unwrap();
+    assert_eq!(refund.order_id, order.id);
+    assert_eq!(refund.reason, Some("Payment plan cancelled".to_string()));
+    assert_eq!(refund_due, installments[0].amount_in_cents);
+    // The deposit is kept under the default policy
+    assert_eq!(refund.retained_in_cents, payment_plan.deposit_in_cents);
+    assert_eq!(tickets.len(), 10);
+    for ticket in tickets {
+        assert_eq!(
+            TicketInstance::find(ticket.id, connection).unwrap().status,
+            TicketInstanceStatus::Available
+        );
+    }
+
+    let payment_plan = PaymentPlan::find(payment_plan.id, connection).unwrap();
+    assert_eq!(payment_plan.status, PaymentPlanStatus::Cancelled);
+    assert!(payment_plan.cancelled_at.is_some());
+    let installments = payment_plan.installments(connection).unwrap();
+    assert_eq!(installments[0].status, PaymentPlanInstallmentStatus::Paid);
+    assert_eq!(installments[1].status, PaymentPlanInstallmentStatus::Cancelled);
+
+    assert_eq!(
+        payment_plan.cancel(connection),
+        DatabaseError::business_process_error("Only active payment plans can be cancelled")
+    );
+}
+
+#[test]
+fn orders_are_settled_once_paid_in_full() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (user, mut order, payment_plan, installments) = create_payment_plan(&project, 2);
+    let organization = order.organizations(connection).unwrap().remove(0);
+    pay_deposit(&mut order, &payment_plan, &user, connection);
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-5).finish(),
+        dates::now().add_days(2).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert!(settlement.for_display(connection).unwrap().event_entries.is_empty());
+    assert_eq!(Order::find(order.id, connection).unwrap().settlement_id, None);
+
+    for installment in installments {
+        installment
+            .mark_paid(
+                PaymentProviders::Stripe,
+                format!("installment-{}", installment.installment_number),
+                json!({}),
+                connection,
+            )
+            .unwrap();
+    }
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-5).finish(),
+        dates::now().add_days(2).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(
+        settlement.clone().for_display(connection).unwrap().event_entries.len(),
+        1
+    );
+    assert_eq!(
+        Order::find(order.id, connection).unwrap().settlement_id,
+        Some(settlement.id)
+    );
+}
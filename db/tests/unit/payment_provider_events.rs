@@ -0,0 +1,89 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let payload = json!({ "id": "evt_1", "type": "charge.refunded" });
+    let provider_event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload.clone(),
+    )
+    .commit(connection)
+    .unwrap()
+    .unwrap();
+    assert_eq!(provider_event.external_id, "evt_1");
+    assert_eq!(provider_event.event_type, "charge.refunded");
+    assert_eq!(provider_event.payload, payload);
+    assert!(provider_event.processed_at.is_none());
+
+    // Redelivered events are not recorded again
+    assert!(PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload.clone(),
+    )
+    .commit(connection)
+    .unwrap()
+    .is_none());
+
+    // Event ids are unique per provider
+    assert!(PaymentProviderEvent::create(
+        PaymentProviders::Globee,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload,
+    )
+    .commit(connection)
+    .unwrap()
+    .is_some());
+}
+
+#[test]
+fn find_by_external_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let provider_event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        PaymentProviderEvent::find_by_external_id(PaymentProviders::Stripe, "evt_1", connection).unwrap(),
+        Some(provider_event.clone())
+    );
+    assert_eq!(
+        PaymentProviderEvent::find(provider_event.id, connection).unwrap(),
+        provider_event
+    );
+    assert!(
+        PaymentProviderEvent::find_by_external_id(PaymentProviders::Globee, "evt_1", connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn mark_processed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let provider_event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap()
+    .unwrap();
+    let provider_event = provider_event.mark_processed(connection).unwrap();
+    assert!(provider_event.processed_at.is_some());
+}
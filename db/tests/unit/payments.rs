@@ -25,6 +25,43 @@ fn log_refund() {
     assert_eq!(refund_payment.amount, -100);
 }
 
+#[test]
+fn log_refund_as() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let refund = project.create_refund().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+    let refund_payment = payment
+        .log_refund_as(
+            user.id,
+            &refund,
+            100,
+            PaymentMethods::Exchange,
+            PaymentProviders::Exchange,
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(refund_payment.refunded_payment_id, Some(payment.id));
+    assert_eq!(refund_payment.payment_method, PaymentMethods::Exchange);
+    assert_eq!(refund_payment.provider, PaymentProviders::Exchange);
+    assert_eq!(refund_payment.external_reference, None);
+    assert_eq!(refund_payment.amount, -100);
+}
+
 #[test]
 fn find_by_order() {
     let project = TestProject::new();
@@ -208,3 +245,201 @@ fn mark_cancelled() {
         true
     )
 }
+
+#[test]
+fn find_by_provider_reference() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    assert_eq!(
+        Payment::find_by_provider_reference(PaymentProviders::Stripe, "blah", connection).unwrap(),
+        Some(Payment::find(payment.id, connection).unwrap())
+    );
+    assert!(
+        Payment::find_by_provider_reference(PaymentProviders::Globee, "blah", connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        Payment::find_by_provider_reference(PaymentProviders::Stripe, "other", connection)
+            .unwrap()
+            .is_none()
+    );
+
+    // Refunds logged against the payment share its reference but are not returned
+    let refund = project.create_refund().with_order(&order).finish();
+    payment
+        .log_refund(order.user_id, &refund, 100, None, connection)
+        .unwrap();
+    assert_eq!(
+        Payment::find_by_provider_reference(PaymentProviders::Stripe, "blah", connection)
+            .unwrap()
+            .map(|p| p.id),
+        Some(payment.id)
+    );
+}
+
+#[test]
+fn mark_disputed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+
+    // Without an open settlement there is nothing to adjust
+    assert!(payment.mark_disputed(1000, json!({}), connection).unwrap().is_none());
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.dispute_status, Some(PaymentDisputeStatus::Open));
+    // The order is still paid but the disputed payment can't be refunded
+    assert_eq!(order.total_paid(connection).unwrap(), payment.amount);
+    assert!(order.refundable_payments(connection).unwrap().is_empty());
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Payments,
+            Some(payment.id),
+            Some(DomainEventTypes::PaymentDisputed),
+            connection
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+    assert!(payment.mark_disputed(1000, json!({}), connection).is_err());
+
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let adjustment = payment.mark_disputed(1000, json!({}), connection).unwrap().unwrap();
+    assert_eq!(adjustment.settlement_id, settlement.id);
+    assert_eq!(
+        adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustment.amount_in_cents, 1000);
+    assert_eq!(settlement.adjustments(connection).unwrap(), vec![adjustment]);
+}
+
+#[test]
+fn close_dispute() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+
+    // Won disputes credit the chargeback back
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    payment.mark_disputed(1000, json!({}), connection).unwrap();
+    let payment = Payment::find(payment.id, connection).unwrap();
+    let adjustment = payment
+        .close_dispute(true, 1000, json!({}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::ManualCredit
+    );
+    assert_eq!(adjustment.amount_in_cents, 1000);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.dispute_status, Some(PaymentDisputeStatus::Won));
+    assert_eq!(order.refundable_payments(connection).unwrap().len(), 1);
+
+    // Lost disputes keep the chargeback
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    payment.mark_disputed(1000, json!({}), connection).unwrap();
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert!(payment
+        .close_dispute(false, 1000, json!({}), connection)
+        .unwrap()
+        .is_none());
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+    assert_eq!(payment.dispute_status, Some(PaymentDisputeStatus::Lost));
+    assert_eq!(order.total_paid(connection).unwrap(), payment.amount);
+    assert!(order.refundable_payments(connection).unwrap().is_empty());
+    assert!(payment.close_dispute(false, 1000, json!({}), connection).is_err());
+
+    // Lost disputes that were never marked as disputed are charged back when closed
+    let order = project.create_order().for_event(&event).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let adjustment = payment
+        .close_dispute(false, 500, json!({}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustment.amount_in_cents, 500);
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 3);
+}
+
+#[test]
+fn reconcile_provider_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let order = project.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+
+    // Partial refunds are recorded without refunding any items
+    let (refund, tickets) = payment
+        .reconcile_provider_refund(100, json!({ "id": "ch_1" }), connection)
+        .unwrap()
+        .unwrap();
+    assert!(refund.manual_override);
+    assert!(refund.items(connection).unwrap().is_empty());
+    assert!(tickets.is_empty());
+    let refunds: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refunded_payment_id == Some(payment.id))
+        .collect();
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].amount, -100);
+    assert_eq!(refunds[0].refund_id, Some(refund.id));
+
+    // Amounts already recorded are ignored
+    assert!(payment
+        .reconcile_provider_refund(100, json!({ "id": "ch_1" }), connection)
+        .unwrap()
+        .is_none());
+
+    // Refunding the rest of the payment refunds the remaining items
+    let (refund, tickets) = payment
+        .reconcile_provider_refund(payment.amount, json!({ "id": "ch_1" }), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(tickets.len(), 2);
+    assert!(!refund.items(connection).unwrap().is_empty());
+    for ticket in tickets {
+        assert_eq!(
+            TicketInstance::find(ticket.id, connection).unwrap().status,
+            TicketInstanceStatus::Available
+        );
+    }
+    assert!(Order::find(order.id, connection)
+        .unwrap()
+        .refundable_payments(connection)
+        .unwrap()
+        .is_empty());
+}
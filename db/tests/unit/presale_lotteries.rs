@@ -0,0 +1,192 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::presale_lotteries;
+use bigneon_db::utils::dates;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+fn create_presale_lottery(
+    project: &TestProject,
+    event: &Event,
+    winner_count: i32,
+    registration_end: NaiveDateTime,
+) -> PresaleLottery {
+    let connection = project.get_connection();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    PresaleLottery::create(
+        event.id,
+        "Fan presale".to_string(),
+        vec![ticket_type.id],
+        dates::now().add_hours(-1).finish(),
+        registration_end,
+        registration_end,
+        dates::now().add_days(2).finish(),
+        winner_count,
+        2,
+        1,
+        5,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap()
+}
+
+fn close_registration(presale_lottery: &PresaleLottery, connection: &PgConnection) -> PresaleLottery {
+    diesel::update(presale_lotteries::table.filter(presale_lotteries::id.eq(presale_lottery.id)))
+        .set(presale_lotteries::registration_end.eq(dates::now().add_minutes(-1).finish()))
+        .get_result(connection)
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    // Draw cannot happen while registration is still open
+    assert!(PresaleLottery::create(
+        event.id,
+        "Fan presale".to_string(),
+        vec![ticket_type.id],
+        dates::now().add_hours(-1).finish(),
+        dates::now().add_hours(2).finish(),
+        dates::now().add_hours(1).finish(),
+        dates::now().add_days(2).finish(),
+        10,
+        2,
+        0,
+        1,
+        user.id,
+    )
+    .commit(connection)
+    .is_err());
+
+    let presale_lottery = create_presale_lottery(&project, &event, 10, dates::now().add_hours(1).finish());
+    assert_eq!(
+        PresaleLottery::find(presale_lottery.id, connection).unwrap(),
+        presale_lottery
+    );
+    assert_eq!(presale_lottery.status, PresaleLotteryStatus::Open);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::PresaleLotteries),
+        Some(presale_lottery.id),
+        DomainActionTypes::DrawPresaleLottery,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+}
+
+#[test]
+fn register() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let presale_lottery = create_presale_lottery(&project, &event, 10, dates::now().add_hours(1).finish());
+    let user = project.create_user().finish();
+
+    let entry = presale_lottery.register(user.id, connection).unwrap();
+    assert_eq!(entry.status, PresaleLotteryEntryStatus::Registered);
+    assert_eq!(entry.user_id, user.id);
+    assert!(EventInterest::user_interest(event.id, user.id, connection).unwrap());
+
+    // Users can only register once
+    assert!(presale_lottery.register(user.id, connection).is_err());
+
+    // Registration is closed once the window ends
+    let presale_lottery = close_registration(&presale_lottery, connection);
+    let user2 = project.create_user().finish();
+    assert!(presale_lottery.register(user2.id, connection).is_err());
+}
+
+#[test]
+fn weight_for() {
+    let project = TestProject::new();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let presale_lottery = create_presale_lottery(&project, &event, 10, dates::now().add_hours(1).finish());
+
+    assert_eq!(presale_lottery.weight_for(0), 1);
+    assert_eq!(presale_lottery.weight_for(2), 3);
+    assert_eq!(presale_lottery.weight_for(100), 5);
+}
+
+#[test]
+fn draw() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let presale_lottery = create_presale_lottery(&project, &event, 2, dates::now().add_hours(1).finish());
+    let mut user_ids: Vec<Uuid> = Vec::new();
+    for _ in 0..3 {
+        let user = project.create_user().finish();
+        presale_lottery.register(user.id, connection).unwrap();
+        user_ids.push(user.id);
+    }
+
+    // Registration is still open
+    assert!(presale_lottery.draw(connection).is_err());
+
+    let presale_lottery = close_registration(&presale_lottery, connection);
+    let entries = presale_lottery.draw(connection).unwrap();
+    assert_eq!(entries.len(), 3);
+    let winners: Vec<&PresaleLotteryEntry> = entries
+        .iter()
+        .filter(|e| e.status == PresaleLotteryEntryStatus::Won)
+        .collect();
+    assert_eq!(winners.len(), 2);
+    for winner in &winners {
+        let code = winner.code(connection).unwrap().unwrap();
+        assert_eq!(code.code_type, CodeTypes::Access);
+        assert_eq!(code.max_uses, 2);
+        assert_eq!(code.max_tickets_per_user, Some(2));
+    }
+    let loser = entries
+        .iter()
+        .find(|e| e.status == PresaleLotteryEntryStatus::Lost)
+        .unwrap();
+    assert!(loser.code_id.is_none());
+    assert_eq!(loser.weight, Some(1));
+
+    let presale_lottery = PresaleLottery::find(presale_lottery.id, connection).unwrap();
+    assert_eq!(presale_lottery.status, PresaleLotteryStatus::Drawn);
+    assert!(presale_lottery.drawn_at.is_some());
+    assert!(presale_lottery.draw(connection).is_err());
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::PresaleLotteries),
+        Some(presale_lottery.id),
+        DomainActionTypes::SendPresaleLotteryResults,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+
+    // Codes can only be redeemed by the winner they were issued to
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = winners[0].code(connection).unwrap().unwrap();
+    let items = vec![UpdateOrderItem {
+        ticket_type_id: ticket_type.id,
+        quantity: 1,
+        redemption_code: Some(code.redemption_code.clone()),
+    }];
+    let loser_user = User::find(loser.user_id, connection).unwrap();
+    let mut cart = Order::find_or_create_cart(&loser_user, connection).unwrap();
+    assert!(cart
+        .update_quantities(loser_user.id, &items, false, false, connection)
+        .is_err());
+
+    let winner_user = User::find(winners[0].user_id, connection).unwrap();
+    let mut cart = Order::find_or_create_cart(&winner_user, connection).unwrap();
+    assert!(cart
+        .update_quantities(winner_user.id, &items, false, false, connection)
+        .is_ok());
+}
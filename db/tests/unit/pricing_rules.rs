@@ -0,0 +1,349 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::analytics::PageView;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    assert!(PricingRule::create(
+        ticket_type.id,
+        "".to_string(),
+        PricingRuleTypes::SellThrough,
+        50,
+        100,
+        0,
+        500
+    )
+    .commit(Some(user.id), connection)
+    .is_err());
+    assert!(PricingRule::create(
+        ticket_type.id,
+        "Demand".to_string(),
+        PricingRuleTypes::SellThrough,
+        101,
+        100,
+        0,
+        500
+    )
+    .commit(Some(user.id), connection)
+    .is_err());
+    assert!(PricingRule::create(
+        ticket_type.id,
+        "Demand".to_string(),
+        PricingRuleTypes::PageViews,
+        -1,
+        100,
+        0,
+        500
+    )
+    .commit(Some(user.id), connection)
+    .is_err());
+    // Ceiling cannot be below the floor
+    assert!(PricingRule::create(
+        ticket_type.id,
+        "Demand".to_string(),
+        PricingRuleTypes::SellThrough,
+        50,
+        100,
+        500,
+        400
+    )
+    .commit(Some(user.id), connection)
+    .is_err());
+
+    let pricing_rule = PricingRule::create(
+        ticket_type.id,
+        "Demand".to_string(),
+        PricingRuleTypes::SellThrough,
+        50,
+        100,
+        0,
+        500,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(pricing_rule.ticket_type_id, ticket_type.id);
+    assert!(pricing_rule.active);
+    assert_eq!(
+        PricingRule::find_for_ticket_type(ticket_type.id, connection).unwrap(),
+        vec![pricing_rule.clone()]
+    );
+    // Nothing sold yet so the price is unchanged
+    assert_eq!(
+        ticket_type
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        150
+    );
+
+    assert!(pricing_rule
+        .update(
+            PricingRuleEditableAttributes {
+                floor_price_in_cents: Some(600),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .is_err());
+    let pricing_rule = pricing_rule
+        .update(
+            PricingRuleEditableAttributes {
+                threshold: Some(75),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(pricing_rule.threshold, 75);
+
+    pricing_rule.destroy(Some(user.id), connection).unwrap();
+    assert!(PricingRule::find(pricing_rule.id, connection).is_err());
+}
+
+#[test]
+fn evaluate_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(10)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let standard_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(standard_pricing.price_in_cents, 150);
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 5,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // Half of the tickets are gone, the sell-through rule raises the price
+    let sell_through_rule = PricingRule::create(
+        ticket_type.id,
+        "Sell-through".to_string(),
+        PricingRuleTypes::SellThrough,
+        50,
+        100,
+        0,
+        1000,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.price_in_cents, 250);
+    assert_eq!(ticket_pricing.base_price_in_cents, Some(150));
+    assert_eq!(ticket_pricing.previous_ticket_pricing_id, Some(standard_pricing.id));
+    assert_eq!(ticket_pricing.end_date, standard_pricing.end_date);
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketTypes,
+        Some(ticket_type.id),
+        Some(DomainEventTypes::TicketPricingAdjusted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    let payload = domain_events[0].event_data.clone().unwrap();
+    assert_eq!(payload["old_price_in_cents"], json!(150));
+    assert_eq!(payload["new_price_in_cents"], json!(250));
+    assert_eq!(
+        payload["pricing_rules"][0]["pricing_rule_id"],
+        json!(sell_through_rule.id)
+    );
+    assert_eq!(payload["pricing_rules"][0]["metric"], json!(50));
+    assert_eq!(payload["pricing_rules"][0]["triggered"], json!(true));
+
+    // Adjustments add up but the price stays under the lowest ceiling
+    let days_until_event_rule = PricingRule::create(
+        ticket_type.id,
+        "Last days".to_string(),
+        PricingRuleTypes::DaysUntilEvent,
+        3,
+        100,
+        0,
+        300,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.price_in_cents, 300);
+    assert_eq!(ticket_pricing.base_price_in_cents, Some(150));
+
+    days_until_event_rule
+        .update(
+            PricingRuleEditableAttributes {
+                active: Some(false),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        ticket_type
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        250
+    );
+
+    // Without rules the price goes back to the organizer's price
+    days_until_event_rule.destroy(None, connection).unwrap();
+    sell_through_rule.destroy(None, connection).unwrap();
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.price_in_cents, 150);
+    assert!(PricingRule::evaluate_ticket_type(ticket_type, None, connection)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        DomainEvent::find(
+            Tables::TicketTypes,
+            Some(ticket_type.id),
+            Some(DomainEventTypes::TicketPricingAdjusted),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        4
+    );
+}
+
+#[test]
+fn evaluate_all() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_a_specific_number_of_tickets(10)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    PricingRule::create(
+        ticket_types[0].id,
+        "Demand".to_string(),
+        PricingRuleTypes::PageViews,
+        3,
+        -50,
+        75,
+        500,
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(
+        ticket_types[0]
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        150
+    );
+
+    let viewed_at = Utc::now().naive_utc() - Duration::hours(1);
+    for _ in 0..3 {
+        PageView::create(
+            viewed_at,
+            event.id,
+            "direct".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "web".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+        )
+        .commit(connection)
+        .unwrap();
+    }
+
+    PricingRule::evaluate_all(connection).unwrap();
+    assert_eq!(
+        ticket_types[0]
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        100
+    );
+    // Ticket types without rules keep their price
+    assert_eq!(
+        ticket_types[1]
+            .current_ticket_pricing(false, connection)
+            .unwrap()
+            .price_in_cents,
+        150
+    );
+}
+
+#[test]
+fn metric() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_event_start(Utc::now().naive_utc() + Duration::days(5) + Duration::hours(1))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let pricing_rule = PricingRule::create(
+        ticket_type.id,
+        "Last days".to_string(),
+        PricingRuleTypes::DaysUntilEvent,
+        3,
+        100,
+        0,
+        500,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let metric = pricing_rule.metric(ticket_type, &event, connection).unwrap();
+    assert_eq!(metric, Some(5));
+    assert!(!pricing_rule.is_triggered(metric));
+    assert!(pricing_rule.is_triggered(Some(3)));
+    assert!(!pricing_rule.is_triggered(None));
+}
+
+#[test]
+fn create_next_evaluate_pricing_rules_domain_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+
+    PricingRule::create_next_evaluate_pricing_rules_domain_action(connection).unwrap();
+    let domain_action =
+        DomainAction::upcoming_domain_action(None, None, DomainActionTypes::EvaluatePricingRules, connection)
+            .unwrap()
+            .unwrap();
+    let beginning_of_current_hour = NaiveDate::from_ymd(now.year(), now.month(), now.day()).and_hms(now.hour(), 0, 0);
+    assert_eq!(
+        domain_action.scheduled_at,
+        beginning_of_current_hour + Duration::hours(1)
+    );
+    assert!(PricingRule::create_next_evaluate_pricing_rules_domain_action(connection).is_err());
+}
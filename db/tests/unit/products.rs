@@ -0,0 +1,375 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    // Price cannot be negative
+    assert!(Product::create(organization.id, None, "T-Shirt".to_string(), None, -1)
+        .commit(Some(user.id), connection)
+        .is_err());
+
+    // Event must belong to the organization
+    assert!(
+        Product::create(organization.id, Some(other_event.id), "T-Shirt".to_string(), None, 2000)
+            .commit(Some(user.id), connection)
+            .is_err()
+    );
+
+    let product = Product::create(
+        organization.id,
+        None,
+        "T-Shirt".to_string(),
+        Some("Tour shirt".to_string()),
+        2000,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(product.organization_id, organization.id);
+    assert_eq!(product.event_id, None);
+    assert_eq!(product.price_in_cents, 2000);
+    assert!(product.active);
+
+    let variant = ProductVariant::create(product.id, "Small".to_string(), "TS-S".to_string(), None, 10, 0)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(variant.unit_price_in_cents(&product), 2000);
+
+    // SKUs are unique within the organization
+    assert!(
+        ProductVariant::create(product.id, "Small".to_string(), "TS-S".to_string(), None, 10, 0)
+            .commit(Some(user.id), connection)
+            .is_err()
+    );
+    // Stock cannot be negative
+    assert!(
+        ProductVariant::create(product.id, "Large".to_string(), "TS-L".to_string(), None, -1, 1)
+            .commit(Some(user.id), connection)
+            .is_err()
+    );
+
+    let variant = ProductVariant::create(product.id, "XXL".to_string(), "TS-XXL".to_string(), Some(2500), 5, 1)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(variant.unit_price_in_cents(&product), 2500);
+    assert_eq!(product.variants(connection).unwrap().len(), 2);
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization).finish();
+
+    let parking = Product::create(organization.id, Some(event.id), "Parking".to_string(), None, 1500)
+        .commit(None, connection)
+        .unwrap();
+    let shirt = Product::create(organization.id, None, "T-Shirt".to_string(), None, 2000)
+        .commit(None, connection)
+        .unwrap();
+    let voucher = Product::create(organization.id, Some(event2.id), "Drink voucher".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    let retired = Product::create(organization.id, None, "Poster".to_string(), None, 1000)
+        .commit(None, connection)
+        .unwrap()
+        .update(
+            ProductEditableAttributes {
+                active: Some(false),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(!retired.active);
+
+    assert_eq!(
+        Product::find_for_event(&event, connection).unwrap(),
+        vec![parking.clone(), shirt.clone()]
+    );
+    assert_eq!(
+        Product::find_for_event(&event2, connection).unwrap(),
+        vec![voucher.clone(), shirt.clone()]
+    );
+    assert_eq!(
+        Product::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        4
+    );
+}
+
+#[test]
+fn update_product_quantities() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let product = Product::create(organization.id, None, "T-Shirt".to_string(), None, 2000)
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Small".to_string(), "TS-S".to_string(), None, 3, 0)
+        .commit(None, connection)
+        .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Organization wide products need an event to be picked up at
+    assert!(cart
+        .update_product_quantities(
+            user.id,
+            &[UpdateProductOrderItem {
+                product_variant_id: variant.id,
+                event_id: None,
+                quantity: 1,
+            }],
+            connection,
+        )
+        .is_err());
+
+    // Not enough stock
+    assert!(cart
+        .update_product_quantities(
+            user.id,
+            &[UpdateProductOrderItem {
+                product_variant_id: variant.id,
+                event_id: Some(event.id),
+                quantity: 4,
+            }],
+            connection,
+        )
+        .is_err());
+
+    cart.update_product_quantities(
+        user.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: Some(event.id),
+            quantity: 2,
+        }],
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let product_item = items.iter().find(|i| i.item_type == OrderItemTypes::Products).unwrap();
+    assert_eq!(product_item.quantity, 2);
+    assert_eq!(product_item.unit_price_in_cents, 2000);
+    assert_eq!(product_item.event_id, Some(event.id));
+    assert_eq!(product_item.product_variant_id, Some(variant.id));
+    assert_eq!(product_item.description(connection).unwrap(), "T-Shirt - Small");
+    let fee_item = product_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.quantity, 2);
+    assert_eq!(fee_item.unit_price_in_cents, 20);
+    assert!(cart.expires_at.is_some());
+
+    // Units in the cart are held from other customers
+    assert_eq!(variant.available(None, connection).unwrap(), 1);
+    assert_eq!(variant.available(Some(cart.id), connection).unwrap(), 3);
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(cart2
+        .update_product_quantities(
+            user2.id,
+            &[UpdateProductOrderItem {
+                product_variant_id: variant.id,
+                event_id: Some(event.id),
+                quantity: 2,
+            }],
+            connection,
+        )
+        .is_err());
+
+    // Adding tickets keeps the products in the cart
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().any(|i| i.id == product_item.id));
+    assert!(items.iter().any(|i| i.item_type == OrderItemTypes::Tickets));
+
+    cart.update_product_quantities(
+        user.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: Some(event.id),
+            quantity: 0,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.item_type != OrderItemTypes::Products && i.parent_id != Some(product_item.id)));
+    assert_eq!(variant.available(None, connection).unwrap(), 3);
+}
+
+#[test]
+fn purchase_and_redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let user = project.create_user().finish();
+    let door_person = project.create_user().finish();
+    let product = Product::create(organization.id, Some(event.id), "Parking".to_string(), None, 1500)
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Lot A".to_string(), "PARK-A".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_product_quantities(
+        user.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: None,
+            quantity: 2,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert!(cart.items_valid_for_purchase(connection).unwrap());
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    assert_eq!(variant.available(None, connection).unwrap(), 8);
+
+    let product_instances = ProductInstance::find_for_user_for_display(user.id, connection).unwrap();
+    assert_eq!(product_instances.len(), 2);
+    assert_eq!(product_instances[0].product_name, "Parking");
+    assert_eq!(product_instances[0].variant_name, "Lot A");
+    assert_eq!(product_instances[0].event_id, Some(event.id));
+    assert_eq!(product_instances[0].status, ProductInstanceStatus::Purchased);
+
+    let product_instance = ProductInstance::find(product_instances[0].id, connection).unwrap();
+    assert_eq!(
+        product_instance.redeem("WRONG", door_person.id, connection).unwrap(),
+        ProductRedeemResults::ProductInvalid
+    );
+    assert_eq!(
+        product_instance
+            .redeem(&product_instance.redeem_key, door_person.id, connection)
+            .unwrap(),
+        ProductRedeemResults::ProductRedeemSuccess
+    );
+    let product_instance = ProductInstance::find(product_instance.id, connection).unwrap();
+    assert_eq!(product_instance.status, ProductInstanceStatus::Redeemed);
+    assert_eq!(product_instance.redeemed_by_user_id, Some(door_person.id));
+    assert!(product_instance.redeemed_at.is_some());
+    assert_eq!(
+        product_instance
+            .redeem(&product_instance.redeem_key, door_person.id, connection)
+            .unwrap(),
+        ProductRedeemResults::ProductAlreadyRedeemed
+    );
+}
+
+#[test]
+fn refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let user = project.create_user().finish();
+    let product = Product::create(organization.id, Some(event.id), "Drink voucher".to_string(), None, 500)
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Beer".to_string(), "DV-BEER".to_string(), None, 1, 0)
+        .commit(None, connection)
+        .unwrap();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_product_quantities(
+        user.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: Some(event.id),
+            quantity: 1,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(variant.available(None, connection).unwrap(), 0);
+
+    let items = cart.items(connection).unwrap();
+    let product_item = items.iter().find(|i| i.item_type == OrderItemTypes::Products).unwrap();
+    let fee_item = product_item.find_fee_item(connection).unwrap().unwrap();
+
+    // Product fees are only refunded along with the product
+    assert!(cart
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: fee_item.id,
+                ticket_instance_id: None,
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .is_err());
+
+    let (_refund, amount) = cart
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: product_item.id,
+                ticket_instance_id: None,
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(amount, 500 + fee_item.unit_price_in_cents);
+    assert_eq!(
+        ProductInstance::find_for_order_item(product_item.id, connection).unwrap()[0].status,
+        ProductInstanceStatus::Refunded
+    );
+
+    // Refunded units are returned to stock
+    assert_eq!(variant.available(None, connection).unwrap(), 1);
+}
@@ -464,6 +464,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -497,6 +498,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -520,6 +522,7 @@ fn ticket_count_report() {
             online_face_sales_in_cents: 200,
             comp_sale_count: 2,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -552,6 +555,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -586,6 +590,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 24,
             client_online_fees_in_cents: 36,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -618,6 +623,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -651,6 +657,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -667,6 +674,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 500,
             per_order_client_online_fees: 750,
             per_order_total_fees_in_cents: 1250,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -780,6 +788,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -813,6 +822,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -836,6 +846,7 @@ fn ticket_count_report() {
             online_face_sales_in_cents: 200,
             comp_sale_count: 2,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -868,6 +879,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -902,6 +914,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 24,
             client_online_fees_in_cents: 36,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -934,6 +947,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 0,
             client_online_fees_in_cents: 0,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -967,6 +981,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 8,
             client_online_fees_in_cents: 12,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -993,6 +1008,7 @@ fn ticket_count_report() {
             company_online_fees_in_cents: 16,
             client_online_fees_in_cents: 24,
             user_count: 1,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -1009,6 +1025,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 500,
             per_order_client_online_fees: 750,
             per_order_total_fees_in_cents: 1250,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -1025,6 +1042,7 @@ fn ticket_count_report() {
             per_order_company_online_fees: 100,
             per_order_client_online_fees: 150,
             per_order_total_fees_in_cents: 250,
+            currency: Some("USD".to_string()),
             ..Default::default()
         })
     );
@@ -1632,6 +1650,8 @@ fn build_transaction_report_row(
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
         payment_provider: Some(PaymentProviders::Stripe.to_string()),
+        store_credit_in_cents: 0,
+        store_credit_refunded_in_cents: 0,
         transaction_date: order.paid_at.clone().unwrap(),
         redemption_code: None,
         order_id: order.id,
@@ -1652,6 +1672,8 @@ fn build_transaction_report_row(
         content: None,
         platform: None,
         check_in_source: None,
+        attendee_answers: None,
+        currency: order.currency.clone(),
     }
 }
 
@@ -1844,3 +1866,55 @@ fn promo_code_report() {
 
     assert_eq!(test_pass_count, 5);
 }
+
+#[test]
+fn reports_are_split_by_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let usd_organization = project.create_organization().with_event_fee().with_fees().finish();
+    let usd_event = project
+        .create_event()
+        .with_organization(&usd_organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let cad_organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fees()
+        .with_currency("CAD")
+        .finish();
+    let cad_event = project
+        .create_event()
+        .with_organization(&cad_organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project.create_order().for_event(&usd_event).is_paid().finish();
+    project.create_order().for_event(&cad_event).is_paid().finish();
+
+    let summary = Report::summary_event_report(cad_event.id, None, None, connection).unwrap();
+    assert!(summary.sales.iter().all(|s| s.currency == "CAD"));
+    assert!(summary.ticket_fees.iter().all(|f| f.currency == "CAD"));
+    assert!(summary.other_fees.iter().all(|f| f.currency == "CAD"));
+
+    let promo_rows = Report::promo_code_report(Some(cad_event.id), Some(cad_organization.id), connection).unwrap();
+    assert!(!promo_rows.is_empty());
+    assert!(promo_rows.iter().all(|r| r.currency == Some("CAD".to_string())));
+
+    // Sales across organizations are never added up across currencies
+    let all_sales =
+        Report::ticket_sales_and_counts(None, None, None, None, false, false, false, false, connection).unwrap();
+    let usd_rows: Vec<&TicketSalesRow> = all_sales
+        .sales
+        .iter()
+        .filter(|r| r.event_id == Some(usd_event.id))
+        .collect();
+    let cad_rows: Vec<&TicketSalesRow> = all_sales
+        .sales
+        .iter()
+        .filter(|r| r.event_id == Some(cad_event.id))
+        .collect();
+    assert!(!usd_rows.is_empty() && usd_rows.iter().all(|r| r.currency == Some("USD".to_string())));
+    assert!(!cad_rows.is_empty() && cad_rows.iter().all(|r| r.currency == Some("CAD".to_string())));
+}
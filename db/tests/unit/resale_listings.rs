@@ -0,0 +1,313 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::fee_schedules;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    let face_value_in_cents = ResaleListing::face_value_in_cents(ticket, connection).unwrap();
+
+    // Resale is not enabled for the event
+    assert!(ResaleListing::create(ticket.id, event.id, user.id, face_value_in_cents)
+        .commit(connection)
+        .is_err());
+
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                resale_max_markup_percentage: Some(Some(10)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    // Price exceeds the markup cap
+    let max_price_in_cents = face_value_in_cents * 110 / 100;
+    assert!(
+        ResaleListing::create(ticket.id, event.id, user.id, max_price_in_cents + 1)
+            .commit(connection)
+            .is_err()
+    );
+
+    // User does not own the ticket
+    assert!(ResaleListing::create(ticket.id, event.id, user2.id, max_price_in_cents)
+        .commit(connection)
+        .is_err());
+
+    let listing = ResaleListing::create(ticket.id, event.id, user.id, max_price_in_cents)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(listing.ticket_instance_id, ticket.id);
+    assert_eq!(listing.seller_user_id, user.id);
+    assert_eq!(listing.price_in_cents, max_price_in_cents);
+    assert_eq!(listing.status, ResaleListingStatus::Active);
+
+    // Ticket is already listed
+    assert!(ResaleListing::create(ticket.id, event.id, user.id, max_price_in_cents)
+        .commit(connection)
+        .is_err());
+
+    // Listed tickets cannot be transferred
+    assert!(TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).is_err());
+}
+
+#[test]
+fn purchase() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    diesel::update(fee_schedules::table.filter(fee_schedules::id.eq(organization.fee_schedule_id)))
+        .set((
+            fee_schedules::resale_company_fee_percent.eq(5f32),
+            fee_schedules::resale_client_fee_percent.eq(10f32),
+        ))
+        .execute(connection)
+        .unwrap();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_user(&seller)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection).unwrap().remove(0);
+    let listing = ResaleListing::create(ticket.id, event.id, seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+
+    // Sellers cannot buy their own listing
+    let mut seller_cart = Order::find_or_create_cart(&seller, connection).unwrap();
+    assert!(seller_cart
+        .add_resale_listing(seller.id, listing.id, connection)
+        .is_err());
+
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, listing.id, connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let resale_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::ResaleTickets)
+        .unwrap();
+    assert_eq!(resale_item.unit_price_in_cents, 1000);
+    let fee_item = resale_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.unit_price_in_cents, 150);
+    assert_eq!(fee_item.company_fee_in_cents, 50);
+    assert_eq!(fee_item.client_fee_in_cents, 100);
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.order_item_id, Some(resale_item.id));
+    assert!(ResaleListing::find_available_for_event(event.id, connection)
+        .unwrap()
+        .is_empty());
+
+    // Listing is held by the buyer's cart
+    let other_user = project.create_user().finish();
+    let mut other_cart = Order::find_or_create_cart(&other_user, connection).unwrap();
+    assert!(other_cart
+        .add_resale_listing(other_user.id, listing.id, connection)
+        .is_err());
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Sold);
+    assert_eq!(listing.buyer_user_id, Some(buyer.id));
+    assert!(listing.sold_at.is_some());
+
+    let purchased_ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(
+        purchased_ticket.wallet_id,
+        Wallet::find_default_for_user(buyer.id, connection).unwrap().id
+    );
+    assert_ne!(purchased_ticket.redeem_key, ticket.redeem_key);
+    assert!(TicketInstance::find_for_user(seller.id, connection).unwrap().is_empty());
+}
+
+#[test]
+fn clear_invalid_items() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_user(&seller)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(seller.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, event.id, seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, listing.id, connection).unwrap();
+    assert!(cart.items_valid_for_purchase(connection).unwrap());
+
+    // Seller withdraws the listing while it is in the buyer's cart
+    listing.cancel(Some(seller.id), connection).unwrap();
+    assert!(!cart.items_valid_for_purchase(connection).unwrap());
+
+    cart.clear_invalid_items(buyer.id, connection).unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.item_type != OrderItemTypes::ResaleTickets));
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = &TicketInstance::find_for_user(user.id, connection).unwrap()[0];
+    let listing = ResaleListing::create(ticket.id, event.id, user.id, 1000)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        ResaleListing::find_available_for_event(event.id, connection).unwrap(),
+        vec![listing.clone()]
+    );
+
+    let listing = listing.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Cancelled);
+    assert!(ResaleListing::find_available_for_event(event.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(listing.cancel(Some(user.id), connection).is_err());
+
+    // Ticket can be transferred once the listing is cancelled
+    assert!(TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).is_ok());
+}
+
+#[test]
+fn redeeming_ticket_cancels_listing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                resale_enabled: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_user(&seller)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(seller.id, connection).unwrap().remove(0);
+    let listing = ResaleListing::create(ticket.id, event.id, seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_resale_listing(buyer.id, listing.id, connection).unwrap();
+
+    // Seller redeems the ticket while the listing is in the buyer's cart
+    let result = TicketInstance::redeem_ticket(
+        ticket.id,
+        TicketInstance::find(ticket.id, connection).unwrap().redeem_key.unwrap(),
+        seller.id,
+        CheckInSource::GuestList,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result, RedeemResults::TicketRedeemSuccess);
+    let listing = ResaleListing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ResaleListingStatus::Cancelled);
+    assert!(!cart.items_valid_for_purchase(connection).unwrap());
+
+    // The buyer's payment is rejected before it is recorded
+    let total = cart.calculate_total(connection).unwrap();
+    assert!(cart
+        .add_external_payment(
+            Some("test".to_string()),
+            ExternalPaymentType::CreditCard,
+            buyer.id,
+            total,
+            connection,
+        )
+        .is_err());
+    assert!(cart.payments(connection).unwrap().is_empty());
+}
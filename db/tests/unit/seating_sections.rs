@@ -0,0 +1,140 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = SeatingSection::create(stage.id, "Orchestra".to_string(), 1)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(seating_section.stage_id, stage.id);
+    assert_eq!(seating_section.name, "Orchestra".to_string());
+    assert_eq!(seating_section.rank, 1);
+}
+
+#[test]
+fn find_by_stage_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let stage2 = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = SeatingSection::create(stage.id, "Balcony".to_string(), 1)
+        .commit(connection)
+        .unwrap();
+    let seating_section2 = SeatingSection::create(stage.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    SeatingSection::create(stage2.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        SeatingSection::find_by_stage_id(stage.id, connection).unwrap(),
+        vec![seating_section2, seating_section]
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let seating_section = project.create_seating_section().finish();
+    let parameters = SeatingSectionEditableAttributes {
+        name: Some("Mezzanine".to_string()),
+        ..Default::default()
+    };
+
+    let seating_section = seating_section.update(parameters, connection).unwrap();
+    assert_eq!(seating_section.name, "Mezzanine".to_string());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let seating_section = project.create_seating_section().with_seats(2, 5).finish();
+    assert_eq!(seating_section.seats(connection).unwrap().len(), 10);
+
+    seating_section.destroy(connection).unwrap();
+    assert!(SeatingSection::find(seating_section.id, connection).is_err());
+    assert!(Seat::find_by_seating_section_id(seating_section.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn destroy_fails_when_used_by_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = project.create_seating_section().with_stage_id(stage.id).finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+
+    assert!(seating_section.destroy(connection).is_err());
+}
+
+#[test]
+fn destroy_fails_when_seats_assigned_to_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = project
+        .create_seating_section()
+        .with_stage_id(stage.id)
+        .with_seats(1, 2)
+        .finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .finish();
+
+    // The ticket keeps its seat after the ticket type stops using the section
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(None),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        seating_section.destroy(connection),
+        DatabaseError::business_process_error(
+            "Seating section cannot be deleted while its seats are assigned to tickets",
+        )
+    );
+}
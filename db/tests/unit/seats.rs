@@ -0,0 +1,109 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn create_multiple() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let seating_section = project.create_seating_section().with_seats(0, 0).finish();
+    let seats = Seat::create_multiple(
+        vec![
+            Seat::create(seating_section.id, "A".to_string(), "1".to_string(), 0, 0, false),
+            Seat::create(seating_section.id, "A".to_string(), "2".to_string(), 0, 1, true),
+        ],
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(seats.len(), 2);
+    assert_eq!(seats[1].seat_number, "2".to_string());
+    assert!(seats[1].accessible);
+    assert_eq!(seating_section.seats(connection).unwrap(), seats);
+}
+
+#[test]
+fn best_available() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let seating_section = project.create_seating_section().with_seats(2, 4).finish();
+    let seats = seating_section.seats(connection).unwrap();
+
+    // Prefers a contiguous block in the first row
+    let best = Seat::best_available(&seats, 3).unwrap();
+    assert_eq!(best, seats[0..3].to_vec());
+
+    // Skips rows without a large enough block
+    let mut fragmented = seats.clone();
+    fragmented.remove(1);
+    let best = Seat::best_available(&fragmented, 3).unwrap();
+    assert_eq!(best, seats[4..7].to_vec());
+
+    // Falls back to seats in preference order
+    let best = Seat::best_available(&fragmented, 5).unwrap();
+    assert_eq!(best, fragmented[0..5].to_vec());
+
+    assert!(Seat::best_available(&seats, 9).is_none());
+    assert_eq!(Seat::best_available(&seats, 0), Some(vec![]));
+}
+
+#[test]
+fn find_available() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let seating_section = project
+        .create_seating_section()
+        .with_stage_id(stage.id)
+        .with_seats(1, 4)
+        .finish();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        Seat::find_available(seating_section.id, event.id, &[], connection)
+            .unwrap()
+            .len(),
+        4
+    );
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let available = Seat::find_available(seating_section.id, event.id, &[], connection).unwrap();
+    assert_eq!(available.len(), 2);
+
+    let display_seats = Seat::find_for_event(event.id, connection).unwrap();
+    assert_eq!(display_seats.len(), 4);
+    assert_eq!(display_seats.iter().filter(|s| s.available).count(), 2);
+    assert_eq!(display_seats[0].ticket_type_ids, vec![ticket_type.id]);
+
+    // Seats from other events are not considered taken
+    assert_eq!(
+        Seat::find_available(seating_section.id, Uuid::new_v4(), &[], connection)
+            .unwrap()
+            .len(),
+        4
+    );
+}
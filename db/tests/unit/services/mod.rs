@@ -1 +1,2 @@
 pub mod country_lookup;
+pub mod recurrence_rule;
@@ -0,0 +1,78 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+
+#[test]
+fn parse() {
+    let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,TU;COUNT=6").unwrap();
+    assert_eq!(rule.frequency, RecurrenceFrequency::Weekly);
+    assert_eq!(rule.interval, 2);
+    assert_eq!(rule.count, Some(6));
+    assert_eq!(rule.until, None);
+    assert_eq!(rule.by_day, vec![Weekday::Tue, Weekday::Fri]);
+
+    let rule = RecurrenceRule::parse("freq=daily;until=20200301").unwrap();
+    assert_eq!(rule.frequency, RecurrenceFrequency::Daily);
+    assert_eq!(rule.until, Some(NaiveDate::from_ymd(2020, 3, 1).and_hms(23, 59, 59)));
+
+    assert!(RecurrenceRule::parse("").is_err());
+    assert!(RecurrenceRule::parse("FREQ=YEARLY").is_err());
+    assert!(RecurrenceRule::parse("FREQ=WEEKLY;INTERVAL=0").is_err());
+    assert!(RecurrenceRule::parse("FREQ=WEEKLY;COUNT=2;UNTIL=20200301").is_err());
+    assert!(RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=XX").is_err());
+    assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYDAY=MO").is_err());
+    assert!(RecurrenceRule::parse("FREQ=WEEKLY;BYMONTH=1").is_err());
+}
+
+#[test]
+fn occurrences() {
+    // Wednesday
+    let dtstart = NaiveDate::from_ymd(2020, 1, 1).and_hms(20, 0, 0);
+    let horizon = NaiveDate::from_ymd(2021, 1, 1).and_hms(0, 0, 0);
+
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;COUNT=3").unwrap();
+    assert_eq!(
+        rule.occurrences(dtstart, horizon),
+        vec![
+            dtstart,
+            NaiveDate::from_ymd(2020, 1, 8).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 1, 15).and_hms(20, 0, 0),
+        ]
+    );
+
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20200110").unwrap();
+    assert_eq!(
+        rule.occurrences(dtstart, horizon),
+        vec![
+            dtstart,
+            NaiveDate::from_ymd(2020, 1, 3).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 1, 6).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 1, 10).and_hms(20, 0, 0),
+        ]
+    );
+
+    let rule = RecurrenceRule::parse("FREQ=DAILY;INTERVAL=2;COUNT=3").unwrap();
+    assert_eq!(
+        rule.occurrences(dtstart, horizon),
+        vec![
+            dtstart,
+            NaiveDate::from_ymd(2020, 1, 3).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 1, 5).and_hms(20, 0, 0),
+        ]
+    );
+
+    // Months without the 31st are skipped
+    let dtstart = NaiveDate::from_ymd(2020, 1, 31).and_hms(20, 0, 0);
+    let rule = RecurrenceRule::parse("FREQ=MONTHLY;COUNT=3").unwrap();
+    assert_eq!(
+        rule.occurrences(dtstart, horizon),
+        vec![
+            dtstart,
+            NaiveDate::from_ymd(2020, 3, 31).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 5, 31).and_hms(20, 0, 0),
+        ]
+    );
+
+    // Open ended rules stop at the horizon
+    let rule = RecurrenceRule::parse("FREQ=MONTHLY").unwrap();
+    assert_eq!(rule.occurrences(dtstart, horizon).len(), 7);
+}
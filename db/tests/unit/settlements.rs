@@ -1,6 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
-use bigneon_db::schema::{orders, refunds};
+use bigneon_db::schema::{orders, refunds, settlements};
 use bigneon_db::utils::dates;
 use bigneon_db::utils::errors::ErrorCode::ValidationError;
 use chrono::prelude::*;
@@ -39,6 +39,39 @@ fn find_last_settlement_for_organization() {
     );
 }
 
+#[test]
+fn find_open_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    assert!(Settlement::find_open_for_organization(organization.id, connection)
+        .unwrap()
+        .is_none());
+
+    let settlement = project
+        .create_settlement()
+        .with_organization(&organization)
+        .with_start_time(dates::now().add_days(-14).finish())
+        .finish();
+    let settlement2 = project.create_settlement().with_organization(&organization).finish();
+    project.create_settlement().with_organization(&organization2).finish();
+    assert_eq!(
+        Settlement::find_open_for_organization(organization.id, connection).unwrap(),
+        Some(settlement2.clone())
+    );
+
+    // Settled settlements can no longer be adjusted
+    diesel::update(settlements::table.filter(settlements::id.eq(settlement2.id)))
+        .set(settlements::status.eq(SettlementStatus::SettledInFull))
+        .execute(connection)
+        .unwrap();
+    assert_eq!(
+        Settlement::find_open_for_organization(organization.id, connection).unwrap(),
+        Some(settlement)
+    );
+}
+
 #[test]
 fn process_settlement_for_organization() {
     let project = TestProject::new();
@@ -115,3 +115,78 @@ fn destroy() {
     let all_stages = vec![stage_1, stage_2];
     assert_eq!(venue_1_stages, all_stages);
 }
+
+#[test]
+fn capacity_limits_seated_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project
+        .create_stage()
+        .with_venue_id(venue.id)
+        .with_capacity(15)
+        .finish();
+    let seating_section = project.create_seating_section().with_stage_id(stage.id).finish();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_type_count(2)
+        .with_a_specific_number_of_tickets(10)
+        .with_ticket_pricing()
+        .finish();
+    let mut ticket_types = event.ticket_types(true, None, connection).unwrap();
+
+    ticket_types
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(stage.allocated_capacity(event.id, None, connection).unwrap(), 10);
+
+    // Ticket types outside a capacity pool are still limited by their stage
+    assert!(ticket_types
+        .remove(0)
+        .update(
+            TicketTypeEditableAttributes {
+                seating_section_id: Some(Some(seating_section.id)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+    assert!(CapacityPool::create(event.id, Some(stage.id), "Stage".to_string(), 6)
+        .commit(None, connection)
+        .is_err());
+    CapacityPool::create(event.id, Some(stage.id), "Stage".to_string(), 5)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(stage.allocated_capacity(event.id, None, connection).unwrap(), 15);
+
+    // Capacity cannot be lowered below what events have allocated to the stage
+    assert!(stage
+        .update(
+            StageEditableAttributes {
+                capacity: Some(Some(14)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .is_err());
+    let stage = stage
+        .update(
+            StageEditableAttributes {
+                capacity: Some(Some(15)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(stage.capacity, Some(15));
+}
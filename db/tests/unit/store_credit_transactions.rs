@@ -0,0 +1,206 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::DatabaseError;
+
+fn buy_gift_card(project: &TestProject, event: &Event, purchaser: &User) -> ProductInstance {
+    let connection = project.get_connection();
+    let product = Product::create(event.organization_id, None, "Gift card".to_string(), None, 5000)
+        .as_gift_card()
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Digital".to_string(), "GIFT-50".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+    let mut cart = Order::find_or_create_cart(purchaser, connection).unwrap();
+    cart.update_product_quantities(
+        purchaser.id,
+        &[UpdateProductOrderItem {
+            product_variant_id: variant.id,
+            event_id: Some(event.id),
+            quantity: 1,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        ExternalPaymentType::CreditCard,
+        purchaser.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let product_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+    ProductInstance::find_for_order_item(product_item.id, connection)
+        .unwrap()
+        .remove(0)
+}
+
+#[test]
+fn redeem_gift_card() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let purchaser = project.create_user().finish();
+    let fan = project.create_user().finish();
+    let gift_card = buy_gift_card(&project, &event, &purchaser);
+    assert_eq!(gift_card.redeem_key.len(), 16);
+
+    // Gift cards can't be picked up like other products
+    assert_eq!(
+        gift_card
+            .redeem(&gift_card.redeem_key, purchaser.id, connection)
+            .unwrap(),
+        ProductRedeemResults::ProductInvalid
+    );
+
+    let store_credit =
+        ProductInstance::redeem_gift_card(&gift_card.redeem_key.to_lowercase(), fan.id, connection).unwrap();
+    assert_eq!(store_credit.user_id, fan.id);
+    assert_eq!(store_credit.amount_in_cents, 5000);
+    assert_eq!(store_credit.transaction_type, StoreCreditTransactionTypes::GiftCard);
+    assert_eq!(store_credit.product_instance_id, Some(gift_card.id));
+    assert_eq!(
+        StoreCreditTransaction::balance_for_user(fan.id, "USD", connection).unwrap(),
+        5000
+    );
+    assert_eq!(
+        StoreCreditTransaction::balance_for_user(purchaser.id, "USD", connection).unwrap(),
+        0
+    );
+
+    let gift_card = ProductInstance::find(gift_card.id, connection).unwrap();
+    assert_eq!(gift_card.status, ProductInstanceStatus::Redeemed);
+    assert_eq!(gift_card.redeemed_by_user_id, Some(fan.id));
+
+    assert_eq!(
+        ProductInstance::redeem_gift_card(&gift_card.redeem_key, purchaser.id, connection),
+        DatabaseError::business_process_error("Gift card is invalid or has already been redeemed")
+    );
+    assert_eq!(
+        ProductInstance::redeem_gift_card("NOTAGIFTCARD", purchaser.id, connection),
+        DatabaseError::business_process_error("Gift card is invalid or has already been redeemed")
+    );
+}
+
+#[test]
+fn add_store_credit_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let gift_card = buy_gift_card(&project, &event, &project.create_user().finish());
+    ProductInstance::redeem_gift_card(&gift_card.redeem_key, user.id, connection).unwrap();
+
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+
+    assert_eq!(
+        order.add_store_credit_payment(user.id, 0, connection),
+        DatabaseError::validation_error("amount", "Store credit amount must be more than zero")
+    );
+    assert_eq!(
+        order.add_store_credit_payment(user.id, total + 1, connection),
+        DatabaseError::validation_error("amount", "Store credit amount is more than the amount due")
+    );
+
+    // Part of the order is paid with credit, leaving the rest for another payment method
+    let payment = order.add_store_credit_payment(user.id, 100, connection).unwrap();
+    assert_eq!(payment.payment_method, PaymentMethods::StoreCredit);
+    assert_eq!(payment.provider, PaymentProviders::StoreCredit);
+    assert_eq!(payment.amount, 100);
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+    assert_eq!(
+        StoreCreditTransaction::balance_for_user(user.id, "USD", connection).unwrap(),
+        4900
+    );
+    let transactions = StoreCreditTransaction::find_for_user(user.id, connection).unwrap();
+    let debit = transactions
+        .iter()
+        .find(|t| t.transaction_type == StoreCreditTransactionTypes::Payment)
+        .unwrap();
+    assert_eq!(debit.amount_in_cents, -100);
+    assert_eq!(debit.order_id, Some(order.id));
+    assert_eq!(debit.payment_id, Some(payment.id));
+
+    order
+        .add_store_credit_payment(user.id, total - 100, connection)
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(
+        StoreCreditTransaction::balance_for_user(user.id, "USD", connection).unwrap(),
+        5000 - total
+    );
+
+    // Users can't spend more credit than they have
+    let user2 = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user2)
+        .for_tickets(ticket_type.id)
+        .finish();
+    assert_eq!(
+        order.add_store_credit_payment(user2.id, 100, connection),
+        DatabaseError::validation_error("amount", "Insufficient store credit")
+    );
+}
+
+#[test]
+fn credit_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_user(&user)
+        .for_tickets(ticket_type.id)
+        .is_paid()
+        .finish();
+    let payment = order.payments(connection).unwrap().remove(0);
+    let refund = Refund::create(order.id, user.id, None, false)
+        .commit(connection)
+        .unwrap();
+
+    let store_credit =
+        StoreCreditTransaction::credit_refund(user.id, &refund, &payment, 150, user.id, connection).unwrap();
+    assert_eq!(store_credit.transaction_type, StoreCreditTransactionTypes::Refund);
+    assert_eq!(store_credit.refund_id, Some(refund.id));
+    assert_eq!(store_credit.payment_id, Some(payment.id));
+
+    let display = StoreCreditTransaction::for_display(user.id, connection).unwrap();
+    assert_eq!(
+        display.balances,
+        vec![StoreCreditBalance {
+            currency: "USD".to_string(),
+            balance_in_cents: 150,
+        }]
+    );
+    assert_eq!(display.transactions, vec![store_credit]);
+
+    let report = Report::store_credit_report(Some(event.organization_id), None, None, connection).unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].transaction_type, StoreCreditTransactionTypes::Refund);
+    assert_eq!(report[0].issued_in_cents, 150);
+    assert_eq!(report[0].spent_in_cents, 0);
+    assert_eq!(report[0].liability_in_cents, 150);
+    let other_organization = project.create_organization().finish();
+    assert!(
+        Report::store_credit_report(Some(other_organization.id), None, None, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
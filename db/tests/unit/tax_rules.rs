@@ -0,0 +1,417 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use diesel::PgConnection;
+
+fn tax_items(order: &Order, connection: &PgConnection) -> Vec<OrderItem> {
+    order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tax)
+        .collect()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let user = project.create_user().finish();
+
+    let tax_rule = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, true)
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(tax_rule.region_id, Some(region.id));
+    assert_eq!(tax_rule.venue_id, None);
+    assert_eq!(TaxRule::find_for_region(region.id, connection).unwrap(), vec![tax_rule]);
+
+    assert_eq!(
+        TaxRule::create_for_region(region.id, "GST".to_string(), 0.0, false, true).commit(None, connection),
+        DatabaseError::validation_error("rate_percent", "Rate must be more than 0 and at most 100 percent")
+    );
+    assert_eq!(
+        TaxRule::create_for_region(region.id, " ".to_string(), 5.0, false, true).commit(None, connection),
+        DatabaseError::validation_error("name", "Name is required")
+    );
+    let mut new_tax_rule = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, true);
+    new_tax_rule.venue_id = Some(project.create_venue().finish().id);
+    assert_eq!(
+        new_tax_rule.commit(None, connection),
+        DatabaseError::business_process_error("Tax rules must belong to either a region or a venue")
+    );
+}
+
+#[test]
+fn update_and_destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let tax_rule = TaxRule::create_for_venue(venue.id, "VAT".to_string(), 20.0, true, false)
+        .commit(None, connection)
+        .unwrap();
+
+    let tax_rule = tax_rule
+        .update(
+            TaxRuleEditableAttributes {
+                rate_percent: Some(21.0),
+                taxes_fees: Some(true),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(tax_rule.rate_percent, 21.0);
+    assert!(tax_rule.taxes_fees);
+    assert!(tax_rule.inclusive);
+
+    tax_rule.destroy(None, connection).unwrap();
+    assert!(TaxRule::find(tax_rule.id, connection).is_err());
+    assert!(TaxRule::find_for_venue(venue.id, connection).unwrap().is_empty());
+}
+
+#[test]
+fn tax_for() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let exclusive = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    let inclusive = TaxRule::create_for_region(region.id, "VAT".to_string(), 20.0, true, false)
+        .commit(None, connection)
+        .unwrap();
+
+    assert_eq!(exclusive.tax_for(1000), 50);
+    assert_eq!(exclusive.tax_for(1010), 51);
+    assert_eq!(exclusive.tax_for(0), 0);
+    assert_eq!(inclusive.tax_for(1200), 200);
+    assert_eq!(inclusive.tax_for(1000), 167);
+    assert_eq!(inclusive.tax_for(-100), 0);
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    assert!(TaxRule::find_for_event(&event, connection).unwrap().is_empty());
+
+    let gst = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    let pst = TaxRule::create_for_region(region.id, "PST".to_string(), 7.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(TaxRule::find_for_event(&event, connection).unwrap(), vec![gst, pst]);
+
+    // Venue rules replace the region's rules
+    let hst = TaxRule::create_for_venue(venue.id, "HST".to_string(), 13.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    assert_eq!(TaxRule::find_for_event(&event, connection).unwrap(), vec![hst]);
+
+    let event = project.create_event().finish();
+    assert!(TaxRule::find_for_event(&event, connection).unwrap().is_empty());
+}
+
+#[test]
+fn exclusive_tax_is_added_to_orders_and_refunded() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let gst = TaxRule::create_for_region(region.id, "GST".to_string(), 5.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let items = order.items(connection).unwrap();
+    let ticket_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+
+    // Fees are not taxed by this rule so the only tax is on the tickets
+    let taxes = tax_items(&order, connection);
+    assert_eq!(taxes.len(), 1);
+    let tax_item = &taxes[0];
+    let expected_tax = gst.tax_for(ticket_item.unit_price_in_cents);
+    assert_eq!(tax_item.parent_id, Some(ticket_item.id));
+    assert_eq!(tax_item.tax_rule_id, Some(gst.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(tax_item.unit_price_in_cents, expected_tax);
+    assert_eq!(tax_item.tax_in_cents, expected_tax);
+    assert_eq!(tax_item.description(connection).unwrap(), "GST");
+    let untaxed_total: i64 = items
+        .iter()
+        .filter(|i| i.item_type != OrderItemTypes::Tax)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+    assert_eq!(
+        order.calculate_total(connection).unwrap(),
+        untaxed_total + 2 * expected_tax
+    );
+
+    // Tax can't be refunded on its own
+    let ticket = &TicketInstance::find_for_order_item(ticket_item.id, connection).unwrap()[0];
+    assert_eq!(
+        order.refund(
+            &[RefundItemRequest {
+                order_item_id: tax_item.id,
+                ticket_instance_id: Some(ticket.id),
+            }],
+            user.id,
+            None,
+            false,
+            connection
+        ),
+        DatabaseError::business_process_error("Tax is refunded together with the item it was charged on")
+    );
+
+    let fee_item = ticket_item.find_fee_item(connection).unwrap().unwrap();
+    let (_refund, amount) = order
+        .refund(
+            &[RefundItemRequest {
+                order_item_id: ticket_item.id,
+                ticket_instance_id: Some(ticket.id),
+            }],
+            user.id,
+            None,
+            false,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        amount,
+        ticket_item.unit_price_in_cents + fee_item.unit_price_in_cents + expected_tax
+    );
+    let tax_item = OrderItem::find(tax_item.id, connection).unwrap();
+    assert_eq!(tax_item.refunded_quantity, 1);
+
+    let report = Report::tax_report(organization.id, None, None, None, connection).unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].tax_rule_id, gst.id);
+    assert_eq!(report[0].event_id, event.id);
+    assert_eq!(report[0].taxable_sales_in_cents, ticket_item.unit_price_in_cents);
+    assert_eq!(report[0].tax_collected_in_cents, 2 * expected_tax);
+    assert_eq!(report[0].tax_refunded_in_cents, expected_tax);
+    assert_eq!(report[0].net_tax_in_cents, expected_tax);
+}
+
+#[test]
+fn inclusive_tax_on_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let vat = TaxRule::create_for_venue(venue.id, "VAT".to_string(), 20.0, true, true)
+        .commit(None, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let ticket_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = ticket_item.find_fee_item(connection).unwrap().unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+
+    // Inclusive tax is recorded without changing what the fan pays
+    let ticket_tax = &ticket_item.find_tax_items(connection).unwrap()[0];
+    assert_eq!(ticket_tax.unit_price_in_cents, 0);
+    assert_eq!(ticket_tax.tax_in_cents, vat.tax_for(ticket_item.unit_price_in_cents));
+    let fee_tax = &fee_item.find_tax_items(connection).unwrap()[0];
+    assert_eq!(fee_tax.tax_in_cents, vat.tax_for(fee_item.unit_price_in_cents));
+    assert_eq!(total, ticket_item.unit_price_in_cents + fee_item.unit_price_in_cents);
+
+    // Removing the ticket removes the tax on it and its fees
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 0,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert!(tax_items(&cart, connection).is_empty());
+}
+
+#[test]
+fn inclusive_tax_is_settled_on_its_own_line() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let vat = TaxRule::create_for_venue(venue.id, "VAT".to_string(), 20.0, true, false)
+        .commit(None, connection)
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let ticket_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let tax_in_cents = vat.tax_for(ticket_item.unit_price_in_cents);
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-5).finish(),
+        dates::now().add_days(2).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let display_settlement = settlement.for_display(connection).unwrap();
+    let entries = &display_settlement.event_entries[0].entries;
+
+    // The tax included in the ticket price is taken out of the face value and settled as tax
+    let ticket_type_entry = entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::TicketType)
+        .unwrap();
+    assert_eq!(
+        ticket_type_entry.face_value_in_cents,
+        ticket_item.unit_price_in_cents - tax_in_cents
+    );
+    assert_eq!(ticket_type_entry.online_sold_quantity, 2);
+    let tax_entry = entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::Tax)
+        .unwrap();
+    assert_eq!(tax_entry.face_value_in_cents, tax_in_cents);
+    assert_eq!(tax_entry.online_sold_quantity, 2);
+    assert_eq!(
+        ticket_type_entry.total_sales_in_cents
+            - ticket_type_entry.fee_sold_quantity * ticket_type_entry.revenue_share_value_in_cents
+            + tax_entry.total_sales_in_cents,
+        2 * ticket_item.unit_price_in_cents
+    );
+}
+
+#[test]
+fn organization_products_are_taxed_at_their_pickup_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let gst = TaxRule::create_for_venue(venue.id, "GST".to_string(), 5.0, false, false)
+        .commit(None, connection)
+        .unwrap();
+    let product = Product::create(organization.id, None, "T-Shirt".to_string(), None, 2000)
+        .commit(None, connection)
+        .unwrap();
+    let variant = ProductVariant::create(product.id, "Small".to_string(), "TS-S".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+    let gift_card = Product::create(organization.id, None, "Gift card".to_string(), None, 5000)
+        .as_gift_card()
+        .commit(None, connection)
+        .unwrap();
+    let gift_card_variant = ProductVariant::create(gift_card.id, "$50".to_string(), "GC-50".to_string(), None, 10, 0)
+        .commit(None, connection)
+        .unwrap();
+    let user = project.create_user().finish();
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_product_quantities(
+        user.id,
+        &[
+            UpdateProductOrderItem {
+                product_variant_id: variant.id,
+                event_id: Some(event.id),
+                quantity: 2,
+            },
+            UpdateProductOrderItem {
+                product_variant_id: gift_card_variant.id,
+                event_id: Some(event.id),
+                quantity: 1,
+            },
+        ],
+        connection,
+    )
+    .unwrap();
+    let product_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.product_variant_id == Some(variant.id))
+        .unwrap();
+
+    // Gift cards are stored value so only the t-shirts are taxed
+    let taxes = tax_items(&cart, connection);
+    assert_eq!(taxes.len(), 1);
+    assert_eq!(taxes[0].parent_id, Some(product_item.id));
+    assert_eq!(taxes[0].event_id, Some(event.id));
+    assert_eq!(taxes[0].tax_rule_id, Some(gst.id));
+    assert_eq!(taxes[0].quantity, 2);
+    assert_eq!(taxes[0].unit_price_in_cents, gst.tax_for(2000));
+}
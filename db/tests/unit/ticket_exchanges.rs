@@ -0,0 +1,177 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::dates;
+use bigneon_db::utils::errors::DatabaseError;
+use diesel::PgConnection;
+
+fn add_ticket_type(event: &Event, name: &str, price_in_cents: i64, connection: &PgConnection) -> TicketType {
+    let wallet_id = event.issuer_wallet(connection).unwrap().id;
+    event
+        .add_ticket_type(
+            name.to_string(),
+            None,
+            10,
+            Some(dates::now().add_hours(-1).finish()),
+            None,
+            TicketTypeEndDateType::EventEnd,
+            Some(wallet_id),
+            None,
+            0,
+            price_in_cents,
+            TicketTypeVisibility::Always,
+            None,
+            0,
+            true,
+            true,
+            true,
+            None,
+            connection,
+        )
+        .unwrap()
+}
+
+#[test]
+fn exchange_for_more_expensive_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let vip_ticket_type = add_ticket_type(&event, "VIP", 500, connection);
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let ticket = &order.tickets(Some(ticket_type.id), connection).unwrap()[0];
+
+    let ticket_exchange = TicketExchange::exchange(ticket.id, vip_ticket_type.id, user.id, connection).unwrap();
+    assert_eq!(ticket_exchange.original_order_id, order.id);
+    assert_eq!(ticket_exchange.user_id, user.id);
+    assert_eq!(ticket_exchange.refunded_amount_in_cents, 150);
+    assert_eq!(ticket_exchange.new_order_total_in_cents, 500);
+    assert_eq!(ticket_exchange.price_difference_in_cents(), 350);
+    assert_eq!(ticket_exchange.credit_applied_in_cents(), 150);
+    assert_eq!(
+        TicketExchange::find(ticket_exchange.id, connection).unwrap(),
+        ticket_exchange
+    );
+
+    // Original ticket is refunded on the original order
+    let refund = ticket_exchange.refund(connection).unwrap();
+    assert_eq!(refund.order_id, order.id);
+    assert_eq!(refund.items(connection).unwrap().len(), 1);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_ne!(ticket.status, TicketInstanceStatus::Purchased);
+
+    // New order is waiting for the difference to be paid
+    let new_order = ticket_exchange.new_order(connection).unwrap();
+    assert_eq!(new_order.user_id, user.id);
+    assert_eq!(new_order.status, OrderStatus::Draft);
+    let new_items = new_order.items(connection).unwrap();
+    assert!(new_items
+        .iter()
+        .any(|i| i.ticket_type_id == Some(vip_ticket_type.id) && i.quantity == 1));
+    let payments = new_order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].payment_method, PaymentMethods::Exchange);
+    assert_eq!(payments[0].amount, 150);
+    assert_eq!(
+        TicketExchange::find_for_order(new_order.id, connection).unwrap(),
+        vec![ticket_exchange.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketInstances,
+        Some(ticket.id),
+        Some(DomainEventTypes::TicketInstanceExchanged),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn exchange_for_cheaper_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let other_event = project
+        .create_event()
+        .with_organization(&event.organization(connection).unwrap())
+        .finish();
+    let cheap_ticket_type = add_ticket_type(&other_event, "Balcony", 100, connection);
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let ticket = &order.tickets(Some(ticket_type.id), connection).unwrap()[0];
+
+    let ticket_exchange = TicketExchange::exchange(ticket.id, cheap_ticket_type.id, user.id, connection).unwrap();
+    assert_eq!(ticket_exchange.price_difference_in_cents(), -50);
+    assert_eq!(ticket_exchange.credit_applied_in_cents(), 100);
+
+    let new_order = ticket_exchange.new_order(connection).unwrap();
+    assert_eq!(new_order.status, OrderStatus::Paid);
+    assert_eq!(new_order.payments(connection).unwrap()[0].amount, 100);
+    assert_eq!(
+        new_order.tickets(Some(cheap_ticket_type.id), connection).unwrap().len(),
+        1
+    );
+
+    let activity_items = ActivityItem::load_for_order(&order, connection).unwrap();
+    assert!(activity_items.iter().any(|item| match item {
+        ActivityItem::Exchange {
+            ticket_exchange_id,
+            price_difference_in_cents,
+            ..
+        } => *ticket_exchange_id == ticket_exchange.id && *price_difference_in_cents == -50,
+        _ => false,
+    }));
+}
+
+#[test]
+fn exchange_validations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let other_ticket_type = add_ticket_type(&event, "VIP", 500, connection);
+    let other_organization_event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let other_organization_ticket_type = &other_organization_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let ticket = &order.tickets(Some(ticket_type.id), connection).unwrap()[0];
+
+    assert_eq!(
+        TicketExchange::exchange(ticket.id, ticket_type.id, user.id, connection),
+        DatabaseError::business_process_error("Ticket is already of this ticket type")
+    );
+    assert_eq!(
+        TicketExchange::exchange(ticket.id, other_organization_ticket_type.id, user.id, connection),
+        DatabaseError::business_process_error(
+            "Tickets can only be exchanged for ticket types of the same organization"
+        )
+    );
+
+    TicketExchange::exchange(ticket.id, other_ticket_type.id, user.id, connection).unwrap();
+
+    // Ticket was refunded by the exchange
+    assert_eq!(
+        TicketExchange::exchange(ticket.id, other_ticket_type.id, user.id, connection),
+        DatabaseError::business_process_error("Only purchased tickets can be exchanged")
+    );
+}
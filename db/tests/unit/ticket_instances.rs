@@ -772,6 +772,11 @@ fn find() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
+        entry_start: None,
+        entry_end: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -842,6 +847,11 @@ fn find_show_no_token() {
         transfer_key: None,
         transfer_address: None,
         check_in_source: None,
+        section_name: None,
+        row_name: None,
+        seat_number: None,
+        entry_start: None,
+        entry_end: None,
     };
     let (found_event, found_user, found_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
     assert_eq!(
@@ -1,11 +1,13 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
+use bigneon_db::schema::orders;
 use bigneon_db::utils::dates;
+use bigneon_db::utils::dates::IntoDateBuilder;
 use bigneon_db::utils::errors::{ErrorCode::ValidationError, *};
 use chrono::NaiveDate;
 use diesel;
+use diesel::prelude::*;
 use diesel::sql_types;
-use diesel::RunQueryDsl;
 
 #[test]
 fn has_changes() {
@@ -21,6 +23,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        quantity_limit: None,
     }));
 
     assert!(!ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -29,6 +32,7 @@ fn has_changes() {
         start_date: Some(ticket_pricing.start_date),
         end_date: Some(ticket_pricing.end_date),
         is_box_office_only: Some(ticket_pricing.is_box_office_only),
+        quantity_limit: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -37,6 +41,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        quantity_limit: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -45,6 +50,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        quantity_limit: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -53,6 +59,7 @@ fn has_changes() {
         start_date: Some(dates::now().add_days(-1).finish()),
         end_date: None,
         is_box_office_only: None,
+        quantity_limit: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -61,6 +68,7 @@ fn has_changes() {
         start_date: None,
         end_date: Some(dates::now().add_days(11).finish()),
         is_box_office_only: None,
+        quantity_limit: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -69,6 +77,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: Some(true),
+        quantity_limit: None,
     }));
 }
 
@@ -462,6 +471,7 @@ fn update() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
     assert_eq!(updated_ticket_pricing.id, ticket_pricing.id);
@@ -519,6 +529,7 @@ fn update_with_affected_orders() {
         start_date: Some(ticket_pricing.start_date),
         end_date: Some(ticket_pricing.end_date),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
     assert_eq!(updated_ticket_pricing.id, ticket_pricing.id);
@@ -541,6 +552,7 @@ fn update_with_affected_orders() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        quantity_limit: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
 
@@ -702,3 +714,209 @@ fn get_current_ticket_capacity() {
     let ticket_capacity = ticket_types[0].valid_ticket_count(project.get_connection()).unwrap();
     assert_eq!(ticket_capacity, 100);
 }
+
+#[test]
+fn claim_current_ticket_pricing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let standard = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    let standard = standard
+        .update(
+            TicketPricingEditableAttributes {
+                quantity_limit: Some(Some(3)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let late = ticket_type
+        .add_ticket_pricing(
+            "Late".to_string(),
+            standard.end_date,
+            standard.end_date.into_builder().add_days(1).finish(),
+            standard.price_in_cents + 1000,
+            false,
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    assert!(standard
+        .update(
+            TicketPricingEditableAttributes {
+                quantity_limit: Some(Some(0)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .is_err());
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(3));
+    assert_eq!(late.remaining_quantity(connection).unwrap(), None);
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(item.ticket_pricing_id, Some(standard.id));
+    assert_eq!(standard.claimed_quantity(connection).unwrap(), 2);
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(1));
+
+    // New versions of the pricing created after a price change keep counting earlier sales
+    let standard = standard
+        .update(
+            TicketPricingEditableAttributes {
+                price_in_cents: Some(standard.price_in_cents + 1),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(standard.quantity_limit, Some(3));
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(1));
+
+    // Requests for more tickets than remain overflow into the next pricing period without ending
+    // sales at this price
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let items = cart2.items(connection).unwrap();
+    let item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(item.ticket_pricing_id, Some(late.id));
+    assert_eq!(item.unit_price_in_cents, late.price_in_cents);
+    let current = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(current.id, standard.id);
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(1));
+
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 0,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let items = cart2.items(connection).unwrap();
+    let item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(item.ticket_pricing_id, Some(standard.id));
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(0));
+
+    // Expired carts release their claim
+    diesel::update(orders::table.filter(orders::id.eq(cart2.id)))
+        .set(orders::expires_at.eq(dates::now().add_minutes(-1).finish()))
+        .execute(connection)
+        .unwrap();
+    assert_eq!(standard.remaining_quantity(connection).unwrap(), Some(1));
+
+    // Sales at this price only end once paid orders have bought the full quantity
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(None, ExternalPaymentType::Cash, user.id, total, connection)
+        .unwrap();
+    assert_eq!(standard.paid_quantity(connection).unwrap(), 2);
+    let user3 = project.create_user().finish();
+    let mut cart3 = Order::find_or_create_cart(&user3, connection).unwrap();
+    cart3
+        .update_quantities(
+            user3.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let total = cart3.calculate_total(connection).unwrap();
+    cart3
+        .add_external_payment(None, ExternalPaymentType::Cash, user3.id, total, connection)
+        .unwrap();
+    assert_eq!(standard.paid_quantity(connection).unwrap(), 3);
+
+    // The expired cart can't claim tickets at this price again
+    let mut cart2 = Order::find(cart2.id, connection).unwrap();
+    assert_eq!(
+        cart2.try_refresh_expired_cart(Some(user2.id), connection),
+        DatabaseError::business_process_error("Not enough tickets remain at the current price")
+    );
+
+    let user4 = project.create_user().finish();
+    let mut cart4 = Order::find_or_create_cart(&user4, connection).unwrap();
+    cart4
+        .update_quantities(
+            user4.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+            }],
+            false,
+            false,
+            connection,
+        )
+        .unwrap();
+    let items = cart4.items(connection).unwrap();
+    let item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(item.ticket_pricing_id, Some(late.id));
+    assert_eq!(item.unit_price_in_cents, late.price_in_cents);
+
+    let current = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(current.id, late.id);
+    let standard = TicketPricing::find(standard.id, connection).unwrap();
+    assert!(standard.end_date <= TicketPricing::find(late.id, connection).unwrap().start_date);
+    let domain_events = DomainEvent::find(
+        Tables::TicketPricing,
+        Some(standard.id),
+        Some(DomainEventTypes::TicketPricingSalesEnded),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
@@ -987,6 +987,7 @@ fn get_profile_for_organization() {
             cover_photo_url: user.cover_photo_url.clone(),
             created_at: user.created_at,
             attendance_information: Vec::new(),
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1032,6 +1033,7 @@ fn get_profile_for_organization() {
                 event_id: event.id,
                 event_start: event.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1078,6 +1080,7 @@ fn get_profile_for_organization() {
                 event_id: event.id,
                 event_start: event.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1109,6 +1112,7 @@ fn get_profile_for_organization() {
                 event_id: event.id,
                 event_start: event.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1161,6 +1165,7 @@ fn get_profile_for_organization() {
                 event_id: event.id,
                 event_start: event.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1183,6 +1188,7 @@ fn get_profile_for_organization() {
                 event_id: event2.id,
                 event_start: event2.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1237,6 +1243,7 @@ fn get_profile_for_organization() {
                     event_start: event2.event_start
                 }
             ],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1282,6 +1289,7 @@ fn get_profile_for_organization() {
             cover_photo_url: user4.cover_photo_url.clone(),
             created_at: user4.created_at,
             attendance_information: vec![],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1334,6 +1342,7 @@ fn get_profile_for_organization() {
                 event_id: event3.id,
                 event_start: event3.event_start
             }],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1373,6 +1382,7 @@ fn get_profile_for_organization() {
                     event_start: event2.event_start
                 }
             ],
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -1391,6 +1401,7 @@ fn get_profile_for_organization() {
             cover_photo_url: user3.cover_photo_url.clone(),
             created_at: user3.created_at,
             attendance_information: Vec::new(),
+            attendee_answers: Vec::new(),
             deleted_at: None
         }
     );
@@ -0,0 +1,318 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::waitlist_entries;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    // Tickets are still available
+    assert!(WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .is_err());
+
+    project.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.quantity, 2);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+
+    // Already on the waitlist
+    assert!(WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .is_err());
+}
+
+#[test]
+fn release_queues_processing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let user = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(2)
+        .finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap();
+    assert!(domain_action.is_some());
+}
+
+#[test]
+fn process_waitlist() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(3)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let user3 = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(3)
+        .finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let waitlist_entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 2)
+        .commit(connection)
+        .unwrap();
+    let waitlist_entry3 = WaitlistEntry::create(ticket_type.id, user3.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    cart.update_quantities(
+        buyer.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+
+    // Second entry wants more than the remaining ticket so the third entry must also wait
+    let offered = WaitlistEntry::process_waitlist(ticket_type.id, connection).unwrap();
+    assert_eq!(offered.len(), 1);
+    assert_eq!(offered[0].id, waitlist_entry.id);
+    assert_eq!(offered[0].status, WaitlistEntryStatus::Offered);
+    assert!(offered[0].offer_expires_at.is_some());
+
+    let hold = offered[0].hold(connection).unwrap().unwrap();
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 1));
+    assert_eq!(hold.max_per_user, Some(1));
+    assert!(hold.redemption_code.is_some());
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::WaitlistEntries),
+        Some(waitlist_entry.id),
+        DomainActionTypes::ExpireWaitlistOffer,
+        connection,
+    )
+    .unwrap()
+    .is_some());
+
+    let waitlist_entry2 = WaitlistEntry::find(waitlist_entry2.id, connection).unwrap();
+    assert_eq!(waitlist_entry2.status, WaitlistEntryStatus::Waiting);
+    let waitlist_entry3 = WaitlistEntry::find(waitlist_entry3.id, connection).unwrap();
+    assert_eq!(waitlist_entry3.status, WaitlistEntryStatus::Waiting);
+}
+
+#[test]
+fn expire_offer() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(1)
+        .finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+    cart.update_quantities(buyer.id, &[], false, true, connection).unwrap();
+    let waitlist_entry = WaitlistEntry::process_waitlist(ticket_type.id, connection)
+        .unwrap()
+        .remove(0);
+
+    // Offer has not expired yet
+    assert!(waitlist_entry.expire_offer(connection).is_err());
+
+    let one_minute_ago = Utc::now().naive_utc() - Duration::minutes(1);
+    let waitlist_entry: WaitlistEntry =
+        diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(waitlist_entry.id)))
+            .set(waitlist_entries::offer_expires_at.eq(one_minute_ago))
+            .get_result(connection)
+            .unwrap();
+    let waitlist_entry = waitlist_entry.expire_offer(connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Expired);
+
+    let hold = waitlist_entry.hold(connection).unwrap().unwrap();
+    assert_eq!(hold.quantity(connection).unwrap(), (0, 0));
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+
+    // Released ticket is queued for the next person in line
+    assert!(DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .is_some());
+    let offered = WaitlistEntry::process_waitlist(ticket_type.id, connection).unwrap();
+    assert_eq!(offered.len(), 1);
+    assert_eq!(offered[0].user_id, user2.id);
+}
+
+#[test]
+fn purchase_offer() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(2)
+        .finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    cart.update_quantities(buyer.id, &[], false, true, connection).unwrap();
+    let waitlist_entry = WaitlistEntry::process_waitlist(ticket_type.id, connection)
+        .unwrap()
+        .remove(0);
+    let hold = waitlist_entry.hold(connection).unwrap().unwrap();
+
+    // Only the waitlisted user can redeem the offer
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(cart2
+        .update_quantities(
+            user2.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: hold.redemption_code.clone(),
+            }],
+            false,
+            false,
+            connection,
+        )
+        .is_err());
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: hold.redemption_code.clone(),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(None, ExternalPaymentType::Cash, user.id, total, connection)
+        .unwrap();
+
+    // The offer is closed and the ticket that was not bought returns to the pool
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Purchased);
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 0));
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let buyer = project.create_user().finish();
+    let user = project.create_user().finish();
+    let mut cart = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(1)
+        .finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    cart.update_quantities(buyer.id, &[], false, true, connection).unwrap();
+    let waitlist_entry = WaitlistEntry::process_waitlist(ticket_type.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 0);
+
+    let waitlist_entry = waitlist_entry.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+    assert!(WaitlistEntry::find_active_for_user(ticket_type.id, user.id, connection)
+        .unwrap()
+        .is_none());
+    assert!(waitlist_entry.cancel(Some(user.id), connection).is_err());
+}
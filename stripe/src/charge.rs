@@ -0,0 +1,12 @@
+/// Charge object as sent with `charge.*` webhook events
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Charge {
+    pub id: String,
+    pub amount: i64,
+    pub amount_refunded: i64,
+    pub currency: String,
+    pub refunded: bool,
+    /// Set when the charge was made for a payment intent
+    #[serde(default)]
+    pub payment_intent: Option<String>,
+}
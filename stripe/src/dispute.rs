@@ -0,0 +1,20 @@
+/// Dispute object as sent with `charge.dispute.*` webhook events
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Dispute {
+    pub id: String,
+    pub charge: String,
+    #[serde(default)]
+    pub payment_intent: Option<String>,
+    pub amount: i64,
+    pub currency: String,
+    pub reason: String,
+    /// One of `warning_needs_response`, `warning_under_review`, `warning_closed`, `needs_response`,
+    /// `under_review`, `charge_refunded`, `won` or `lost`
+    pub status: String,
+}
+
+impl Dispute {
+    pub fn is_won(&self) -> bool {
+        self.status == "won" || self.status == "warning_closed"
+    }
+}
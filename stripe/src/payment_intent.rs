@@ -0,0 +1,41 @@
+use reqwest;
+use serde_json;
+use StripeError;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentIntentStatus {
+    RequiresPaymentMethod,
+    RequiresConfirmation,
+    RequiresAction,
+    Processing,
+    RequiresCapture,
+    Canceled,
+    Succeeded,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PaymentIntent {
+    pub id: String,
+    pub status: PaymentIntentStatus,
+    pub amount: i64,
+    pub currency: String,
+    /// Passed to Stripe.js by the frontend to let the customer complete the `next_action`
+    pub client_secret: Option<String>,
+    pub next_action: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub raw_data: String,
+}
+
+impl PaymentIntent {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    pub fn from_response(mut resp: reqwest::Response) -> Result<PaymentIntent, StripeError> {
+        let raw: String = resp.text()?;
+        let mut result: PaymentIntent = serde_json::from_str(&raw)?;
+        result.raw_data = raw;
+        Ok(result)
+    }
+}
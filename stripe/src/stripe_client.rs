@@ -1,17 +1,27 @@
 use reqwest;
 use ChargeResult;
 use Customer;
+use PaymentIntent;
 use RefundResult;
 use StripeError;
 
 #[derive(Clone)]
 pub struct StripeClient {
     api_key: String,
+    base_url: String,
 }
 
 impl StripeClient {
-    pub fn new(api_key: String) -> StripeClient {
-        StripeClient { api_key }
+    /// base_url: https://api.stripe.com/v1/ unless calls are made to a mock of the Stripe API
+    pub fn new(api_key: String, base_url: String) -> StripeClient {
+        StripeClient {
+            api_key,
+            base_url: if base_url.ends_with("/") {
+                base_url
+            } else {
+                format!("{}/", base_url)
+            },
+        }
     }
 
     pub fn charge(
@@ -46,9 +56,15 @@ impl StripeClient {
             params.push((format!("metadata[{}]", key_value.0), key_value.1));
         }
 
+        // Payments taken with a payment intent are referenced by the intent rather than its charge
+        let url = if charge_id.starts_with("pi_") {
+            format!("{}payment_intents/{}", self.base_url, charge_id)
+        } else {
+            format!("{}charges/{}", self.base_url, charge_id)
+        };
         let client = reqwest::Client::new();
         let mut resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}", charge_id))
+            .post(&url)
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -89,7 +105,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let mut resp = client
-            .post("https://api.stripe.com/v1/charges")
+            .post(&format!("{}charges", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -102,11 +118,11 @@ impl StripeClient {
     }
 
     pub fn refund(&self, charge_id: &str) -> Result<RefundResult, StripeError> {
-        let params = vec![("charge".to_string(), charge_id.to_string())];
+        let params = vec![(refund_target(charge_id), charge_id.to_string())];
 
         let client = reqwest::Client::new();
         let mut resp = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(&format!("{}refunds", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -120,13 +136,13 @@ impl StripeClient {
 
     pub fn partial_refund(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
         let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
+            (refund_target(charge_id), charge_id.to_string()),
             ("amount".to_string(), amount.to_string()),
         ];
 
         let client = reqwest::Client::new();
         let mut resp = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(&format!("{}refunds", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -142,7 +158,7 @@ impl StripeClient {
         let client = reqwest::Client::new();
 
         let mut resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}/capture", charge_id))
+            .post(&format!("{}charges/{}/capture", self.base_url, charge_id))
             .basic_auth(&self.api_key, Some(""))
             .send()?;
         match resp.status() {
@@ -153,6 +169,89 @@ impl StripeClient {
         }
     }
 
+    /// Creates and confirms a payment intent that only authorizes the amount. The card issuer may
+    /// require the customer to authenticate the payment (e.g. 3D Secure), in which case the intent
+    /// is returned with a `requires_action` status and must be confirmed again once the customer has
+    /// completed the `next_action`. Payments made while the customer is not present are declined
+    /// instead.
+    pub fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        off_session: bool,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntent, StripeError> {
+        let mut params = vec![
+            ("currency".to_string(), currency.to_string()),
+            ("amount".to_string(), amount.to_string()),
+            ("description".to_string(), description.to_string()),
+            ("capture_method".to_string(), "manual".to_string()),
+            ("confirm".to_string(), "true".to_string()),
+        ];
+        if token.starts_with("cus_") {
+            // Stripe charges the customer's default source when no payment method is given
+            params.push(("customer".to_string(), token.to_string()));
+        } else if token.starts_with("tok_") {
+            params.push(("payment_method_data[type]".to_string(), "card".to_string()));
+            params.push(("payment_method_data[card][token]".to_string(), token.to_string()));
+        } else {
+            params.push(("payment_method".to_string(), token.to_string()));
+        }
+        if off_session {
+            params.push(("off_session".to_string(), "true".to_string()));
+        } else {
+            params.push(("confirmation_method".to_string(), "manual".to_string()));
+        }
+
+        for key_value in metadata {
+            params.push((format!("metadata[{}]", key_value.0), key_value.1));
+        }
+        let client = reqwest::Client::new();
+        let mut resp = client
+            .post(&format!("{}payment_intents", self.base_url))
+            .basic_auth(&self.api_key, Some(""))
+            .form(&params)
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
+    pub fn confirm_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        self.payment_intent_action(payment_intent_id, "confirm")
+    }
+
+    pub fn capture_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        self.payment_intent_action(payment_intent_id, "capture")
+    }
+
+    pub fn cancel_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        self.payment_intent_action(payment_intent_id, "cancel")
+    }
+
+    fn payment_intent_action(&self, payment_intent_id: &str, action: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+
+        let mut resp = client
+            .post(&format!(
+                "{}payment_intents/{}/{}",
+                self.base_url, payment_intent_id, action
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp);
+            }
+            _ => return Err(StripeError::from_response(&mut resp)),
+        }
+    }
+
     pub fn update_customer(
         &self,
         client_id: &str,
@@ -170,7 +269,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let mut resp = client
-            .post(&format!("https://api.stripe.com/v1/customers/{}", client_id,))
+            .post(&format!("{}customers/{}", self.base_url, client_id))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -198,7 +297,7 @@ impl StripeClient {
         }
         let client = reqwest::Client::new();
         let mut resp = client
-            .post("https://api.stripe.com/v1/customers")
+            .post(&format!("{}customers", self.base_url))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()?;
@@ -210,3 +309,12 @@ impl StripeClient {
         }
     }
 }
+
+/// Payments taken with a payment intent are refunded through the intent
+fn refund_target(charge_id: &str) -> String {
+    if charge_id.starts_with("pi_") {
+        "payment_intent".to_string()
+    } else {
+        "charge".to_string()
+    }
+}
@@ -35,13 +35,18 @@ impl StripeError {
     pub fn from_response(response: &mut reqwest::Response) -> StripeError {
         let response_text = response.text().unwrap_or("<Error reading response body>".to_string());
 
+        // Errors for payment intents include the intent object alongside the string fields
         #[derive(Deserialize)]
         struct R {
-            error: HashMap<String, String>,
+            error: HashMap<String, serde_json::Value>,
         }
         let deserialized_response: Result<R, _> = serde_json::from_str(&response_text);
         let error_code = if let Ok(deserialized_response) = deserialized_response {
-            deserialized_response.error.get("code".into()).map(|e| e.to_string())
+            deserialized_response
+                .error
+                .get("code".into())
+                .and_then(|e| e.as_str())
+                .map(|e| e.to_string())
         } else {
             None
         };
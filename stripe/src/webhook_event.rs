@@ -0,0 +1,162 @@
+use hex;
+use ring::{digest, hmac};
+use serde_json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use Charge;
+use Dispute;
+use StripeError;
+
+/// Maximum age in seconds of a signed payload, the same default as Stripe's own libraries
+pub const WEBHOOK_TOLERANCE_SECONDS: i64 = 300;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created: i64,
+    pub data: WebhookEventData,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WebhookEventData {
+    pub object: serde_json::Value,
+}
+
+impl WebhookEvent {
+    /// Verifies the `Stripe-Signature` header against the raw request body before parsing the event.
+    pub fn construct(payload: &str, signature_header: &str, secret: &str) -> Result<WebhookEvent, StripeError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        verify_signature(payload, signature_header, secret, WEBHOOK_TOLERANCE_SECONDS, now)?;
+        Ok(serde_json::from_str(payload)?)
+    }
+
+    pub fn dispute(&self) -> Result<Dispute, StripeError> {
+        Ok(serde_json::from_value(self.data.object.clone())?)
+    }
+
+    pub fn charge(&self) -> Result<Charge, StripeError> {
+        Ok(serde_json::from_value(self.data.object.clone())?)
+    }
+}
+
+/// Builds the `Stripe-Signature` header Stripe would send for the payload, e.g. for testing
+/// webhook endpoints.
+pub fn signature_header(payload: &str, secret: &str, timestamp: i64) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    format!("t={},v1={}", timestamp, hex::encode(signature.as_ref()))
+}
+
+/// Checks that one of the `v1` signatures in the header is the HMAC-SHA256 of the timestamped
+/// payload and that the timestamp is within `tolerance_seconds` of `now`.
+pub fn verify_signature(
+    payload: &str,
+    signature_header: &str,
+    secret: &str,
+    tolerance_seconds: i64,
+    now: i64,
+) -> Result<(), StripeError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in signature_header.split(',') {
+        let mut key_value = part.trim().splitn(2, '=');
+        match (key_value.next(), key_value.next()) {
+            (Some("t"), Some(t)) => timestamp = t.parse::<i64>().ok(),
+            (Some("v1"), Some(signature)) => signatures.push(signature),
+            _ => (),
+        }
+    }
+
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => return Err(signature_error("Unable to extract timestamp from signature header")),
+    };
+    if signatures.is_empty() {
+        return Err(signature_error("No v1 signatures found in signature header"));
+    }
+
+    let key = hmac::VerificationKey::new(&digest::SHA256, secret.as_bytes());
+    let signed_payload = format!("{}.{}", timestamp, payload);
+    let matched = signatures.iter().any(|signature| match hex::decode(signature) {
+        Ok(signature) => hmac::verify(&key, signed_payload.as_bytes(), &signature).is_ok(),
+        Err(_) => false,
+    });
+    if !matched {
+        return Err(signature_error(
+            "No signatures found matching the expected signature for payload",
+        ));
+    }
+    if timestamp < now - tolerance_seconds {
+        return Err(signature_error("Timestamp outside the tolerance zone"));
+    }
+
+    Ok(())
+}
+
+fn signature_error(description: &str) -> StripeError {
+    StripeError {
+        description: format!("Invalid webhook signature: {}", description),
+        cause: None,
+        error_code: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = r#"{
+        "id": "evt_1GAbc",
+        "type": "charge.dispute.created",
+        "created": 1581944400,
+        "data": {
+            "object": {
+                "id": "dp_1GAbc",
+                "charge": "ch_1GAbc",
+                "amount": 1500,
+                "currency": "usd",
+                "reason": "fraudulent",
+                "status": "needs_response"
+            }
+        }
+    }"#;
+
+    #[test]
+    fn verify_signature_accepts_valid_signature() {
+        let header = signature_header(PAYLOAD, "whsec_test", 1581944400);
+        assert!(verify_signature(PAYLOAD, &header, "whsec_test", 300, 1581944460).is_ok());
+
+        // Any of several signatures may match, e.g. while the secret is being rolled
+        let other = signature_header(PAYLOAD, "whsec_old", 1581944400);
+        let header = format!("{},v1={}", header, other.split("v1=").nth(1).unwrap());
+        assert!(verify_signature(PAYLOAD, &header, "whsec_old", 300, 1581944460).is_ok());
+    }
+
+    #[test]
+    fn verify_signature_rejects_invalid_signatures() {
+        let header = signature_header(PAYLOAD, "whsec_test", 1581944400);
+        assert!(verify_signature(PAYLOAD, &header, "whsec_other", 300, 1581944460).is_err());
+        assert!(verify_signature(&PAYLOAD.replace("1500", "15"), &header, "whsec_test", 300, 1581944460).is_err());
+        assert!(verify_signature(PAYLOAD, "t=1581944400,v1=not_hex", "whsec_test", 300, 1581944460).is_err());
+        assert!(verify_signature(PAYLOAD, "t=1581944400", "whsec_test", 300, 1581944460).is_err());
+        assert!(verify_signature(PAYLOAD, "v1=abcd", "whsec_test", 300, 1581944460).is_err());
+
+        // Replayed outside the tolerance
+        assert!(verify_signature(PAYLOAD, &header, "whsec_test", 300, 1581944701).is_err());
+    }
+
+    #[test]
+    fn deserialize_event() {
+        let event: WebhookEvent = serde_json::from_str(PAYLOAD).unwrap();
+        assert_eq!(event.id, "evt_1GAbc");
+        assert_eq!(event.event_type, "charge.dispute.created");
+        let dispute = event.dispute().unwrap();
+        assert_eq!(dispute.charge, "ch_1GAbc");
+        assert_eq!(dispute.amount, 1500);
+        assert!(!dispute.is_won());
+    }
+}